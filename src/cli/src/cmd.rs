@@ -6,9 +6,27 @@ use async_trait::async_trait;
 pub mod add;
 pub use add::AddCmd;
 
+pub mod append;
+pub use append::AppendCmd;
+
+pub mod archive;
+pub use archive::ArchiveCmd;
+
+pub mod bisect;
+pub use bisect::BisectCmd;
+
+pub mod blame;
+pub use blame::BlameCmd;
+
 pub mod branch;
 pub use branch::BranchCmd;
 
+pub mod bundle;
+pub use bundle::BundleCmd;
+
+pub mod cache;
+pub use cache::CacheCmd;
+
 pub mod checkout;
 pub use checkout::CheckoutCmd;
 
@@ -21,12 +39,18 @@ pub use commit::CommitCmd;
 pub mod config;
 pub use config::ConfigCmd;
 
+pub mod conflicts;
+pub use conflicts::ConflictsCmd;
+
 pub mod create_remote;
 pub use create_remote::CreateRemoteCmd;
 
 pub mod db;
 pub use db::DbCmd;
 
+pub mod dedup;
+pub use dedup::DedupCmd;
+
 pub mod delete_remote;
 pub use delete_remote::DeleteRemoteCmd;
 
@@ -45,6 +69,18 @@ pub use embeddings::EmbeddingsCmd;
 pub mod fetch;
 pub use fetch::FetchCmd;
 
+pub mod fork_cancel;
+pub use fork_cancel::ForkCancelCmd;
+
+pub mod fsck;
+pub use fsck::FsckCmd;
+
+pub mod gc;
+pub use gc::GcCmd;
+
+pub mod import;
+pub use import::ImportCmd;
+
 pub mod info;
 pub use info::InfoCmd;
 
@@ -57,12 +93,18 @@ pub use load::LoadCmd;
 pub mod log;
 pub use log::LogCmd;
 
+pub mod ls;
+pub use ls::LsCmd;
+
 pub mod migrate;
 pub use migrate::MigrateCmd;
 
 pub mod moo;
 pub use moo::MooCmd;
 
+pub mod mv;
+pub use mv::MvCmd;
+
 pub mod merge;
 pub use merge::MergeCmd;
 
@@ -75,15 +117,27 @@ pub use notebook::NotebookCmd;
 pub mod pack;
 pub use pack::PackCmd;
 
+pub mod pr;
+pub use pr::PrCmd;
+
 pub mod pull;
 pub use pull::PullCmd;
 
 pub mod push;
 pub use push::PushCmd;
 
+pub mod query;
+pub use query::QueryCmd;
+
+pub mod reflog;
+pub use reflog::ReflogCmd;
+
 pub mod remote;
 pub use remote::RemoteCmd;
 
+pub mod resolve;
+pub use resolve::ResolveCmd;
+
 pub mod restore;
 pub use restore::RestoreCmd;
 
@@ -99,24 +153,60 @@ pub use save::SaveCmd;
 pub mod schemas;
 pub use schemas::SchemasCmd;
 
+pub mod show;
+pub use show::ShowCmd;
+
+pub mod sparse_checkout;
+pub use sparse_checkout::SparseCheckoutCmd;
+
+pub mod squash;
+pub use squash::SquashCmd;
+
+pub mod stash;
+pub use stash::StashCmd;
+
+pub mod tag;
+pub use tag::TagCmd;
+
+pub mod telemetry;
+pub use telemetry::TelemetryCmd;
+
 pub mod tree;
 pub use tree::TreeCmd;
 
 pub mod unpack;
 pub use unpack::UnpackCmd;
 
+pub mod stats;
+pub use stats::StatsCmd;
+
 pub mod status;
 pub use status::StatusCmd;
 
 pub mod upload;
 pub use upload::UploadCmd;
 
+pub mod verify_remote;
+pub use verify_remote::VerifyRemoteCmd;
+
+pub mod whoami;
+pub use whoami::WhoamiCmd;
+
 pub mod workspace;
 pub use workspace::WorkspaceCmd;
 
 #[async_trait]
-pub trait RunCmd {
+pub trait RunCmd: Sync {
     fn name(&self) -> &str;
     fn args(&self) -> clap::Command;
     async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError>;
+
+    /// Run with `oxen --output json`, printing a JSON response instead of text. Commands
+    /// that support it override this.
+    async fn run_json(&self, _args: &clap::ArgMatches) -> Result<(), OxenError> {
+        Err(OxenError::basic_str(format!(
+            "`oxen {}` does not support --output json",
+            self.name()
+        )))
+    }
 }
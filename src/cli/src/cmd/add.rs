@@ -35,7 +35,12 @@ impl RunCmd for AddCmd {
 
     fn args(&self) -> Command {
         // Setups the CLI args for the command
-        add_args()
+        add_args().arg(
+            Arg::new("no-verify")
+                .long("no-verify")
+                .help("Skip the data validation rules in .oxen/checks.toml")
+                .action(clap::ArgAction::SetTrue),
+        )
     }
 
     async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
@@ -63,6 +68,10 @@ impl RunCmd for AddCmd {
         let repo = LocalRepository::from_current_dir()?;
         check_repo_migration_needed(&repo)?;
 
+        if !args.get_flag("no-verify") {
+            repositories::checks::run_on_paths(&repo, &opts.paths)?.into_result()?;
+        }
+
         for path in &opts.paths {
             repositories::add(&repo, path).await?;
         }
@@ -0,0 +1,259 @@
+use std::path::Path;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use clap::{arg, Arg, ArgMatches, Command};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, BufReader};
+
+use liboxen::api;
+use liboxen::config::UserConfig;
+use liboxen::constants::{DEFAULT_BRANCH_NAME, DEFAULT_HOST, DEFAULT_SCHEME};
+use liboxen::error::OxenError;
+use liboxen::model::RemoteRepository;
+use liboxen::opts::AppendOpts;
+
+use crate::cmd::RunCmd;
+
+pub const NAME: &str = "append";
+
+/// Rows are sent once there are this many, unless --batch-size is set
+const DEFAULT_BATCH_SIZE: usize = 100;
+/// A partial batch is sent once the input has been quiet for this long
+const BATCH_WAIT: Duration = Duration::from_secs(1);
+
+pub struct AppendCmd;
+
+#[async_trait]
+impl RunCmd for AppendCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        // Setups the CLI args for the command
+        Command::new(NAME)
+            .about("Stream rows into a data frame in a remote repository. The server buffers them and commits once enough rows arrive or the oldest has waited long enough. Ex: kafka-console-consumer --topic events | oxen append ox/events events.jsonl")
+            .arg(arg!(<ID> "The remote repository to append to. Format: namespace/repo-name"))
+            .arg(arg!(<PATH> "Path of the data frame within the repository"))
+            .arg(arg!([FILE] "File with the rows to append, one JSON object per line, CSV with a header or a JSON array. Defaults to reading JSON lines from stdin"))
+            .arg(
+                Arg::new("csv")
+                    .long("csv")
+                    .help("The rows are CSV with a header line. Implied by a FILE ending in .csv")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("branch")
+                    .long("branch")
+                    .short('b')
+                    .help("The branch to commit to. Defaults to main")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("commit-rows")
+                    .long("commit-rows")
+                    .help("Commit once this many rows are buffered on the server. Defaults to 1000")
+                    .value_parser(clap::value_parser!(usize))
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("commit-after")
+                    .long("commit-after")
+                    .help("Commit once the oldest buffered row is this many seconds old. Defaults to 60")
+                    .value_parser(clap::value_parser!(u64))
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("commit")
+                    .long("commit")
+                    .help("Commit the buffered rows once the input ends")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("message")
+                    .long("message")
+                    .short('m')
+                    .help("The commit message. Defaults to 'Appended N rows to PATH'")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("batch-size")
+                    .long("batch-size")
+                    .help("Number of rows to send per request. Defaults to 100")
+                    .value_parser(clap::value_parser!(usize))
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("host")
+                    .long("host")
+                    .help("The host of the remote repository. Defaults to hub.oxen.ai")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("scheme")
+                    .long("scheme")
+                    .help("The scheme of the remote repository. Defaults to https")
+                    .action(clap::ArgAction::Set),
+            )
+    }
+
+    async fn run(&self, args: &ArgMatches) -> Result<(), OxenError> {
+        let id = args.get_one::<String>("ID").expect("required");
+        let path = args.get_one::<String>("PATH").expect("required");
+        let file = args.get_one::<String>("FILE");
+        let branch = args
+            .get_one::<String>("branch")
+            .map(String::from)
+            .unwrap_or(DEFAULT_BRANCH_NAME.to_string());
+        let host = args
+            .get_one::<String>("host")
+            .map(String::from)
+            .unwrap_or(DEFAULT_HOST.to_string());
+        let scheme = args
+            .get_one::<String>("scheme")
+            .map(String::from)
+            .unwrap_or(DEFAULT_SCHEME.to_string());
+        let batch_size = args
+            .get_one::<usize>("batch-size")
+            .copied()
+            .unwrap_or(DEFAULT_BATCH_SIZE)
+            .max(1);
+
+        let Some(remote_repo) =
+            api::client::repositories::get_by_name_host_and_scheme(id, &host, &scheme).await?
+        else {
+            return Err(OxenError::basic_str(format!(
+                "Remote repository not found: {id}"
+            )));
+        };
+
+        let opts = AppendOpts {
+            commit_rows: args.get_one::<usize>("commit-rows").copied(),
+            commit_after_secs: args.get_one::<u64>("commit-after").copied(),
+            author: Some(UserConfig::get()?.to_user()),
+            message: args.get_one::<String>("message").cloned(),
+            commit: false,
+        };
+        let appender = Appender {
+            remote_repo,
+            branch,
+            path: path.to_string(),
+            opts,
+        };
+        let commit = args.get_flag("commit");
+
+        let extension = file
+            .and_then(|file| Path::new(file).extension())
+            .and_then(|ext| ext.to_str())
+            .unwrap_or_default();
+        // A JSON array can't be split into lines, send it as it is
+        if extension == "json" {
+            let data = tokio::fs::read_to_string(file.expect("has an extension")).await?;
+            appender.send(data, "application/json", commit).await?;
+            return Ok(());
+        }
+
+        let csv = args.get_flag("csv") || extension == "csv";
+        let reader: Box<dyn AsyncBufRead + Unpin + Send> = match file {
+            Some(file) => Box::new(BufReader::new(tokio::fs::File::open(file).await?)),
+            None => Box::new(BufReader::new(tokio::io::stdin())),
+        };
+        appender.stream(reader, csv, batch_size, commit).await
+    }
+}
+
+struct Appender {
+    remote_repo: RemoteRepository,
+    branch: String,
+    path: String,
+    opts: AppendOpts,
+}
+
+impl Appender {
+    /// Send the lines in batches as they arrive, so rows from a long running stream reach
+    /// the server while it is still going
+    async fn stream(
+        &self,
+        reader: Box<dyn AsyncBufRead + Unpin + Send>,
+        csv: bool,
+        batch_size: usize,
+        commit: bool,
+    ) -> Result<(), OxenError> {
+        let mut lines = reader.lines();
+        let header = if csv {
+            match lines.next_line().await? {
+                Some(header) => Some(header),
+                None => return Err(OxenError::basic_str("The CSV is missing its header")),
+            }
+        } else {
+            None
+        };
+        let content_type = if csv {
+            "text/csv"
+        } else {
+            "application/x-ndjson"
+        };
+
+        let mut batch: Vec<String> = vec![];
+        let mut num_rows = 0;
+        loop {
+            // next_line is cancel safe, a line cut off by the wait is read on the next call
+            let done = match tokio::time::timeout(BATCH_WAIT, lines.next_line()).await {
+                Ok(Ok(Some(line))) => {
+                    if !line.trim().is_empty() {
+                        batch.push(line);
+                    }
+                    if batch.len() < batch_size {
+                        continue;
+                    }
+                    false
+                }
+                Ok(Ok(None)) => true,
+                Ok(Err(err)) => return Err(err.into()),
+                Err(_) => false,
+            };
+            if !batch.is_empty() || (done && commit) {
+                num_rows += batch.len();
+                let mut data = match &header {
+                    Some(header) => format!("{header}\n"),
+                    None => String::new(),
+                };
+                data.push_str(&batch.join("\n"));
+                batch.clear();
+                self.send(data, content_type, done && commit).await?;
+            }
+            if done {
+                break;
+            }
+        }
+        println!("Appended {num_rows} rows to {}", self.path);
+        Ok(())
+    }
+
+    async fn send(&self, data: String, content_type: &str, commit: bool) -> Result<(), OxenError> {
+        let opts = AppendOpts {
+            commit,
+            ..self.opts.clone()
+        };
+        let response = api::client::data_frames::append(
+            &self.remote_repo,
+            &self.branch,
+            &self.path,
+            data,
+            content_type,
+            &opts,
+        )
+        .await?;
+        if let Some(commit) = response.commit {
+            println!("Committed {} in {}", self.path, commit.id);
+        }
+        if let Some(buffer) = response.buffer {
+            log::debug!(
+                "{} rows waiting to be committed to {}",
+                buffer.num_rows,
+                self.path
+            );
+        }
+        Ok(())
+    }
+}
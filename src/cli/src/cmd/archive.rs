@@ -0,0 +1,86 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use clap::{Arg, Command};
+
+use liboxen::error::OxenError;
+use liboxen::model::LocalRepository;
+use liboxen::repositories;
+use liboxen::repositories::archive::ArchiveFormat;
+
+use crate::cmd::RunCmd;
+use crate::helpers::{check_repo_migration_needed, get_output_path};
+
+pub const NAME: &str = "archive";
+
+pub struct ArchiveCmd;
+
+#[async_trait]
+impl RunCmd for ArchiveCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        // Setups the CLI args for the command
+        Command::new(NAME)
+            .about("Export the files of a revision to a tar, tar.gz or zip file without checking it out")
+            .arg(
+                Arg::new("paths")
+                    .help("Files or directories to export. Defaults to the whole revision.")
+                    .num_args(0..),
+            )
+            .arg(
+                Arg::new("revision")
+                    .long("revision")
+                    .short('r')
+                    .help("Branch, tag or commit to export. Defaults to HEAD.")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("output")
+                    .long("output")
+                    .short('o')
+                    .help("Where to write the archive")
+                    .required(true)
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("format")
+                    .long("format")
+                    .help("tar, tar.gz or zip. Defaults to the extension of the output, or tar.gz.")
+                    .action(clap::ArgAction::Set),
+            )
+    }
+
+    async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        let output = PathBuf::from(
+            get_output_path(args).ok_or(OxenError::basic_str("Must supply an --output path"))?,
+        );
+        let paths: Vec<PathBuf> = args
+            .get_many::<String>("paths")
+            .unwrap_or_default()
+            .map(PathBuf::from)
+            .collect();
+        let format = match args.get_one::<String>("format") {
+            Some(format) => ArchiveFormat::from_str(format)?,
+            None => ArchiveFormat::from_path(&output).unwrap_or_default(),
+        };
+
+        let repo = LocalRepository::from_current_dir()?;
+        check_repo_migration_needed(&repo)?;
+
+        let revision = match args.get_one::<String>("revision") {
+            Some(revision) => revision.clone(),
+            None => repositories::commits::head_commit(&repo)?.id,
+        };
+
+        let num_files = repositories::archive::export(&repo, &revision, &paths, format, &output)?;
+        println!(
+            "Exported {num_files} files from {revision} to {}",
+            output.display()
+        );
+        Ok(())
+    }
+}
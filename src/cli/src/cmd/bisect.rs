@@ -0,0 +1,190 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use clap::{Arg, ArgMatches, Command};
+use colored::Colorize;
+
+use liboxen::error::OxenError;
+use liboxen::model::LocalRepository;
+use liboxen::repositories;
+use liboxen::repositories::bisect::BisectStep;
+use liboxen::util;
+
+use crate::cmd::RunCmd;
+use crate::helpers::check_repo_migration_needed;
+
+pub const NAME: &str = "bisect";
+
+pub struct BisectCmd;
+
+fn revision_arg() -> Arg {
+    Arg::new("revision").help("The commit or branch to mark. Defaults to the commit being tested")
+}
+
+#[async_trait]
+impl RunCmd for BisectCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        Command::new(NAME)
+            .about("Binary search the commit history for the commit that introduced a regression")
+            .subcommand_required(true)
+            .arg_required_else_help(true)
+            .subcommand(
+                Command::new("start")
+                    .about("Start a bisect. Ex: oxen bisect start --bad main --good v1.0")
+                    .arg(
+                        Arg::new("bad")
+                            .long("bad")
+                            .help("A commit or branch with the regression")
+                            .action(clap::ArgAction::Set),
+                    )
+                    .arg(
+                        Arg::new("good")
+                            .long("good")
+                            .help("A commit or branch without the regression. Can be passed multiple times.")
+                            .action(clap::ArgAction::Append),
+                    )
+                    .arg(
+                        Arg::new("path")
+                            .long("path")
+                            .short('p')
+                            .help("Only check out this subtree of each commit. Can be passed multiple times.")
+                            .action(clap::ArgAction::Append),
+                    ),
+            )
+            .subcommand(
+                Command::new("good")
+                    .about("Mark a commit as not having the regression")
+                    .arg(revision_arg()),
+            )
+            .subcommand(
+                Command::new("bad")
+                    .about("Mark a commit as having the regression")
+                    .arg(revision_arg()),
+            )
+            .subcommand(
+                Command::new("skip")
+                    .about("Skip a commit that cannot be tested")
+                    .arg(revision_arg()),
+            )
+            .subcommand(
+                Command::new("run")
+                    .about("Run a script on each commit until the first bad one is found. Exit code 0 means good, 125 means skip and 1-127 means bad. Ex: oxen bisect run python eval.py")
+                    .arg(
+                        Arg::new("command")
+                            .required(true)
+                            .num_args(1..)
+                            .trailing_var_arg(true)
+                            .allow_hyphen_values(true)
+                            .help("The script and its arguments"),
+                    ),
+            )
+            .subcommand(
+                Command::new("reset")
+                    .about("End the bisect and check out the branch or commit it started from"),
+            )
+    }
+
+    async fn run(&self, args: &ArgMatches) -> Result<(), OxenError> {
+        let repo = LocalRepository::from_current_dir()?;
+        check_repo_migration_needed(&repo)?;
+
+        let step = match args.subcommand() {
+            Some(("start", sub_matches)) => {
+                let bad = sub_matches.get_one::<String>("bad").map(String::as_str);
+                let good: Vec<String> = sub_matches
+                    .get_many::<String>("good")
+                    .map(|values| values.cloned().collect())
+                    .unwrap_or_default();
+                let current_dir = std::env::current_dir()?;
+                let paths: Vec<PathBuf> = sub_matches
+                    .get_many::<String>("path")
+                    .map(|values| {
+                        values
+                            .map(|path| {
+                                util::fs::path_relative_to_dir(current_dir.join(path), &repo.path)
+                            })
+                            .collect::<Result<_, OxenError>>()
+                    })
+                    .transpose()?
+                    .unwrap_or_default();
+                repositories::bisect::start(&repo, bad, &good, &paths).await?
+            }
+            Some(("good", sub_matches)) => {
+                let revision = sub_matches.get_one::<String>("revision");
+                repositories::bisect::good(&repo, revision.map(String::as_str)).await?
+            }
+            Some(("bad", sub_matches)) => {
+                let revision = sub_matches.get_one::<String>("revision");
+                repositories::bisect::bad(&repo, revision.map(String::as_str)).await?
+            }
+            Some(("skip", sub_matches)) => {
+                let revision = sub_matches.get_one::<String>("revision");
+                repositories::bisect::skip(&repo, revision.map(String::as_str)).await?
+            }
+            Some(("run", sub_matches)) => {
+                let command: Vec<String> = sub_matches
+                    .get_many::<String>("command")
+                    .expect("required")
+                    .cloned()
+                    .collect();
+                repositories::bisect::run(&repo, &command).await?
+            }
+            Some(("reset", _)) => {
+                let state = repositories::bisect::reset(&repo).await?;
+                println!("Bisect ended, back on {}", state.original_head);
+                return Ok(());
+            }
+            Some((cmd, _)) => {
+                return Err(OxenError::basic_str(format!(
+                    "Unknown bisect subcommand {cmd}"
+                )))
+            }
+            None => unreachable!("subcommand is required"),
+        };
+
+        print_step(&step);
+        Ok(())
+    }
+}
+
+fn print_step(step: &BisectStep) {
+    match step {
+        BisectStep::Waiting { has_good, has_bad } => {
+            let mut missing = vec![];
+            if !has_bad {
+                missing.push("`oxen bisect bad`");
+            }
+            if !has_good {
+                missing.push("`oxen bisect good`");
+            }
+            println!(
+                "Waiting for {} to start narrowing down",
+                missing.join(" and ")
+            );
+        }
+        BisectStep::Testing {
+            commit,
+            remaining,
+            steps,
+        } => {
+            println!("Bisecting: {remaining} commits left to test (roughly {steps} steps)");
+            println!("{} {}", commit.id.yellow(), commit.message);
+        }
+        BisectStep::Found(commit) => {
+            println!("{} is the first bad commit", commit.id.red());
+            println!("Author: {}", commit.author);
+            println!("Date:   {}", commit.timestamp);
+            println!("\n    {}", commit.message);
+        }
+        BisectStep::Inconclusive(commits) => {
+            println!("Some commits were skipped, the first bad commit is one of:");
+            for commit in commits {
+                println!("{} {}", commit.id.yellow(), commit.message);
+            }
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use async_trait::async_trait;
+use clap::{Arg, Command};
+
+use liboxen::error::OxenError;
+use liboxen::model::LocalRepository;
+use liboxen::repositories;
+use liboxen::util;
+
+use crate::cmd::RunCmd;
+
+pub const NAME: &str = "blame";
+
+pub struct BlameCmd;
+
+#[async_trait]
+impl RunCmd for BlameCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        // Setups the CLI args for the command
+        Command::new(NAME)
+            .about("Show the last commit that modified each line of a text file, or each row of a tabular file")
+            .arg(
+                Arg::new("path")
+                    .help("Path of the file to blame")
+                    .required(true),
+            )
+            .arg(
+                Arg::new("revision")
+                    .long("revision")
+                    .help("The commit or branch to blame the file at. Defaults to HEAD.")
+                    .default_value("HEAD"),
+            )
+            .arg(
+                Arg::new("key")
+                    .long("key")
+                    .short('k')
+                    .help("Column that identifies the rows of a tabular file")
+                    .action(clap::ArgAction::Set),
+            )
+    }
+
+    async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        let path = args.get_one::<String>("path").expect("required");
+        let revision = args.get_one::<String>("revision").expect("has a default");
+
+        let repo = LocalRepository::from_current_dir()?;
+        // Paths are relative to where the command runs
+        let current_dir = std::env::current_dir()?;
+        let path = util::fs::path_relative_to_dir(current_dir.join(path), &repo.path)?;
+
+        if util::fs::is_tabular(&path) {
+            let Some(key) = args.get_one::<String>("key") else {
+                return Err(OxenError::basic_str(
+                    "Pass the column that identifies the rows with --key to blame a tabular file",
+                ));
+            };
+            for row in repositories::blame::blame_tabular(&repo, revision, &path, key)? {
+                println!(
+                    "{} ({} {}) {}",
+                    short_id(&row.commit_id),
+                    row.author,
+                    row.timestamp.date(),
+                    row.key
+                );
+            }
+        } else {
+            for line in repositories::blame::blame_text(&repo, revision, &path)? {
+                println!(
+                    "{} ({} {} {:>4}) {}",
+                    short_id(&line.commit_id),
+                    line.author,
+                    line.timestamp.date(),
+                    line.line_number,
+                    line.text
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+fn short_id(commit_id: &str) -> &str {
+    &commit_id[..commit_id.len().min(8)]
+}
@@ -4,12 +4,14 @@ use colored::Colorize;
 
 use liboxen::api;
 use liboxen::error::OxenError;
-use liboxen::model::LocalRepository;
+use liboxen::model::{Branch, LocalRepository};
+use liboxen::opts::PaginateOpts;
 use liboxen::repositories;
+use liboxen::view::{BranchResponse, ListBranchesResponse, StatusMessage};
 
 use crate::cmd::RunCmd;
 use crate::helpers::{
-    check_remote_version, check_remote_version_blocking, get_scheme_and_host_from_repo,
+    check_remote_version, check_remote_version_blocking, get_scheme_and_host_from_repo, print_json,
 };
 
 pub mod unlock;
@@ -66,6 +68,22 @@ impl RunCmd for BranchCmd {
                     .help("Rename the current local branch.")
                     .action(clap::ArgAction::Set),
             )
+            .arg(
+                Arg::new("compare")
+                    .long("compare")
+                    .help("Show how far HEAD is ahead of and behind BASE, the files that changed, and whether it can be merged. Compares the remote branches when used with --remote.")
+                    .num_args(2)
+                    .value_names(["BASE", "HEAD"])
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("recover")
+                    .long("recover")
+                    .help("Create a branch at a commit no branch points to any more, as long as it is still in the object store. List them with `oxen reflog --orphaned`.")
+                    .num_args(1..=2)
+                    .value_names(["COMMIT", "NAME"])
+                    .action(clap::ArgAction::Set),
+            )
             .arg(
                 Arg::new("show-current")
                     .long("show-current")
@@ -85,6 +103,11 @@ impl RunCmd for BranchCmd {
                 (unlock::NAME, args) => unlock::BranchUnlockCmd.run(args).await,
                 (cmd, _) => Err(OxenError::basic_str(format!("Unknown subcommand {cmd}"))),
             }
+        } else if let Some(names) = args.get_many::<String>("compare") {
+            let names: Vec<&String> = names.collect();
+            let remote_name = args.get_one::<String>("remote");
+            self.compare_branches(&repo, remote_name, names[0], names[1])
+                .await
         } else if args.get_flag("all") {
             self.list_all_branches(&repo).await
         } else if let Some(remote_name) = args.get_one::<String>("remote") {
@@ -102,6 +125,9 @@ impl RunCmd for BranchCmd {
             self.force_delete_branch(&repo, name)
         } else if let Some(name) = args.get_one::<String>("move") {
             self.rename_current_branch(&repo, name)
+        } else if let Some(values) = args.get_many::<String>("recover") {
+            let values: Vec<&String> = values.collect();
+            self.recover_branch(&repo, values[0], values.get(1).map(|name| name.as_str()))
         } else if args.get_flag("show-current") {
             self.show_current_branch(&repo)
         } else {
@@ -113,6 +139,48 @@ impl RunCmd for BranchCmd {
             }
         }
     }
+
+    async fn run_json(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        let repo = LocalRepository::from_current_dir()?;
+
+        let is_listing = args.subcommand().is_none()
+            && !args.get_flag("all")
+            && [
+                "name",
+                "compare",
+                "delete",
+                "force-delete",
+                "move",
+                "recover",
+            ]
+            .iter()
+            .all(|id| !args.contains_id(id));
+        if !is_listing {
+            return Err(OxenError::basic_str(
+                "`oxen branch` only supports --output json when listing branches, with --remote or --show-current",
+            ));
+        }
+
+        if args.get_flag("show-current") {
+            let branch = repositories::branches::current_branch(&repo)?.ok_or(
+                OxenError::basic_str("There is no current branch, HEAD is detached"),
+            )?;
+            return print_json(&BranchResponse {
+                status: StatusMessage::resource_found(),
+                branch,
+                signature: None,
+            });
+        }
+
+        let branches = match args.get_one::<String>("remote") {
+            Some(remote_name) => self.get_remote_branches(&repo, remote_name).await?,
+            None => repositories::branches::list(&repo)?,
+        };
+        print_json(&ListBranchesResponse {
+            status: StatusMessage::resource_found(),
+            branches,
+        })
+    }
 }
 
 impl BranchCmd {
@@ -180,6 +248,17 @@ impl BranchCmd {
         Ok(())
     }
 
+    pub fn recover_branch(
+        &self,
+        repo: &LocalRepository,
+        commit_id: &str,
+        name: Option<&str>,
+    ) -> Result<(), OxenError> {
+        let branch = repositories::reflog::recover(repo, commit_id, name)?;
+        println!("Recovered {} on branch {}", branch.commit_id, branch.name);
+        Ok(())
+    }
+
     pub fn rename_current_branch(
         &self,
         repo: &LocalRepository,
@@ -194,6 +273,18 @@ impl BranchCmd {
         repo: &LocalRepository,
         remote_name: &str,
     ) -> Result<(), OxenError> {
+        let branches = self.get_remote_branches(repo, remote_name).await?;
+        for branch in branches.iter() {
+            println!("{}\t{}", remote_name, branch.name);
+        }
+        Ok(())
+    }
+
+    async fn get_remote_branches(
+        &self,
+        repo: &LocalRepository,
+        remote_name: &str,
+    ) -> Result<Vec<Branch>, OxenError> {
         let (scheme, host) = get_scheme_and_host_from_repo(repo)?;
 
         check_remote_version_blocking(scheme.clone(), host.clone()).await?;
@@ -206,9 +297,68 @@ impl BranchCmd {
             .await?
             .ok_or(OxenError::remote_not_found(remote.clone()))?;
 
-        let branches = api::client::branches::list(&remote_repo).await?;
-        for branch in branches.iter() {
-            println!("{}\t{}", &remote.name, branch.name);
+        api::client::branches::list(&remote_repo).await
+    }
+
+    pub async fn compare_branches(
+        &self,
+        repo: &LocalRepository,
+        remote_name: Option<&String>,
+        base: &str,
+        head: &str,
+    ) -> Result<(), OxenError> {
+        let page_opts = PaginateOpts::default();
+        let compare = if let Some(remote_name) = remote_name {
+            let (scheme, host) = get_scheme_and_host_from_repo(repo)?;
+            check_remote_version(scheme, host).await?;
+
+            let remote = repo
+                .get_remote(remote_name)
+                .ok_or(OxenError::remote_not_set(remote_name))?;
+            let remote_repo = api::client::repositories::get_by_remote(&remote)
+                .await?
+                .ok_or(OxenError::remote_not_found(remote.clone()))?;
+            api::client::branches::compare(&remote_repo, base, head, &page_opts).await?
+        } else {
+            let base = repositories::branches::get_by_name(repo, base)?
+                .ok_or(OxenError::local_branch_not_found(base))?;
+            let head = repositories::branches::get_by_name(repo, head)?
+                .ok_or(OxenError::local_branch_not_found(head))?;
+            repositories::branches::compare(
+                repo,
+                &base,
+                &head,
+                page_opts.page_num,
+                page_opts.page_size,
+            )
+            .await?
+        };
+
+        println!(
+            "{} is {} commits ahead and {} commits behind {}",
+            compare.head.name, compare.ahead, compare.behind, compare.base.name
+        );
+        println!(
+            "{} added, {} modified, {} removed",
+            compare.counts.added, compare.counts.modified, compare.counts.removed
+        );
+        for entry in compare.entries.iter() {
+            println!("  {}\t{}", entry.status, entry.filename);
+        }
+        if compare.pagination.total_entries > compare.entries.len() {
+            println!(
+                "  ... and {} more",
+                compare.pagination.total_entries - compare.entries.len()
+            );
+        }
+
+        if compare.is_mergeable {
+            println!("{}", "Can be merged automatically.".green());
+        } else {
+            println!("{}", "Cannot be merged automatically, conflicts in:".red());
+            for path in compare.conflicts.iter() {
+                println!("  {path}");
+            }
         }
         Ok(())
     }
@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use clap::Command;
+use liboxen::error::OxenError;
+
+use crate::cmd::RunCmd;
+
+pub const NAME: &str = "bundle";
+
+pub mod create;
+pub use create::BundleCreateCmd;
+
+pub mod fetch;
+pub use fetch::BundleFetchCmd;
+
+pub mod unbundle;
+pub use unbundle::BundleUnbundleCmd;
+
+pub struct BundleCmd;
+
+#[async_trait]
+impl RunCmd for BundleCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        // Setups the CLI args for the command
+        let mut command = Command::new(NAME)
+            .about("Move commits and their files between repositories in a single file, without a network")
+            .subcommand_required(true)
+            .arg_required_else_help(true);
+
+        // These are all the subcommands the command
+        let sub_commands = self.get_subcommands();
+        for cmd in sub_commands.values() {
+            command = command.subcommand(cmd.args());
+        }
+        command
+    }
+
+    async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        // Parse Args
+        let sub_commands = self.get_subcommands();
+        let Some((name, sub_matches)) = args.subcommand() else {
+            return Err(OxenError::basic_str(
+                "Usage: `oxen bundle <create|unbundle|fetch> <file>`",
+            ));
+        };
+        let Some(cmd) = sub_commands.get(name) else {
+            eprintln!("Unknown bundle subcommand {name}");
+            return Err(OxenError::basic_str(format!(
+                "Unknown bundle subcommand {name}"
+            )));
+        };
+
+        // Calling await within an await is making it complain?
+        tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(cmd.run(sub_matches))
+        })?;
+
+        Ok(())
+    }
+}
+
+impl BundleCmd {
+    fn get_subcommands(&self) -> HashMap<String, Box<dyn RunCmd>> {
+        let commands: Vec<Box<dyn RunCmd>> = vec![
+            Box::new(BundleCreateCmd),
+            Box::new(BundleFetchCmd),
+            Box::new(BundleUnbundleCmd),
+        ];
+        let mut runners: HashMap<String, Box<dyn RunCmd>> = HashMap::new();
+        for cmd in commands {
+            runners.insert(cmd.name().to_string(), cmd);
+        }
+        runners
+    }
+}
@@ -0,0 +1,64 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use clap::{Arg, Command};
+
+use liboxen::error::OxenError;
+use liboxen::model::LocalRepository;
+use liboxen::repositories;
+
+use crate::cmd::RunCmd;
+use crate::helpers::check_repo_migration_needed;
+
+pub const NAME: &str = "create";
+
+pub struct BundleCreateCmd;
+
+#[async_trait]
+impl RunCmd for BundleCreateCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        // Setups the CLI args for the command
+        Command::new(NAME)
+            .about("Pack commits, their trees and their files into a bundle file")
+            .arg(
+                Arg::new("file")
+                    .help("Where to write the bundle")
+                    .required(true),
+            )
+            .arg(
+                Arg::new("revision")
+                    .long("revision")
+                    .short('r')
+                    .help("Branch, commit or base..head range to bundle. Defaults to every branch and its full history.")
+                    .action(clap::ArgAction::Set),
+            )
+    }
+
+    async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        let file = PathBuf::from(args.get_one::<String>("file").expect("required"));
+        let revision = args.get_one::<String>("revision");
+
+        let repo = LocalRepository::from_current_dir()?;
+        check_repo_migration_needed(&repo)?;
+
+        let manifest =
+            repositories::bundle::create(&repo, &file, revision.map(String::as_str)).await?;
+        println!(
+            "Bundled {} commits and {} branches into {}",
+            manifest.commit_ids.len(),
+            manifest.branches.len(),
+            file.display()
+        );
+        if !manifest.prerequisites.is_empty() {
+            println!("The bundle needs these commits to be unbundled:");
+            for commit_id in &manifest.prerequisites {
+                println!("  {commit_id}");
+            }
+        }
+        Ok(())
+    }
+}
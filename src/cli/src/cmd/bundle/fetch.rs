@@ -0,0 +1,49 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use clap::{Arg, Command};
+
+use liboxen::error::OxenError;
+use liboxen::model::LocalRepository;
+use liboxen::repositories;
+
+use crate::cmd::RunCmd;
+use crate::helpers::check_repo_migration_needed;
+
+pub const NAME: &str = "fetch";
+
+pub struct BundleFetchCmd;
+
+#[async_trait]
+impl RunCmd for BundleFetchCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        // Setups the CLI args for the command
+        Command::new(NAME)
+            .about("Import a bundle and move its branches forward to the bundled commits")
+            .arg(
+                Arg::new("file")
+                    .help("The bundle to fetch from")
+                    .required(true),
+            )
+    }
+
+    async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        let file = PathBuf::from(args.get_one::<String>("file").expect("required"));
+
+        let repo = LocalRepository::from_current_dir()?;
+        check_repo_migration_needed(&repo)?;
+
+        let branches = repositories::bundle::fetch(&repo, &file).await?;
+        if branches.is_empty() {
+            println!("Branches are up to date.");
+        }
+        for branch in &branches {
+            println!("Updated {} to {}", branch.name, branch.commit_id);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,43 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use clap::{Arg, Command};
+
+use liboxen::error::OxenError;
+use liboxen::model::LocalRepository;
+use liboxen::repositories;
+
+use crate::cmd::RunCmd;
+use crate::helpers::check_repo_migration_needed;
+
+pub const NAME: &str = "unbundle";
+
+pub struct BundleUnbundleCmd;
+
+#[async_trait]
+impl RunCmd for BundleUnbundleCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        // Setups the CLI args for the command
+        Command::new(NAME)
+            .about("Import the commits and files of a bundle and print its branches, without updating any branch")
+            .arg(Arg::new("file").help("The bundle to import").required(true))
+    }
+
+    async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        let file = PathBuf::from(args.get_one::<String>("file").expect("required"));
+
+        let repo = LocalRepository::from_current_dir()?;
+        check_repo_migration_needed(&repo)?;
+
+        let manifest = repositories::bundle::unbundle(&repo, &file).await?;
+        println!("Imported {} commits", manifest.commit_ids.len());
+        for branch in &manifest.branches {
+            println!("{}\t{}", branch.commit_id, branch.name);
+        }
+        Ok(())
+    }
+}
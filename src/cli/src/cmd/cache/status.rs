@@ -0,0 +1,114 @@
+use async_trait::async_trait;
+use clap::{Arg, Command};
+
+use liboxen::api;
+use liboxen::constants::DEFAULT_REMOTE_NAME;
+use liboxen::core::cache::{CacheJobRecord, CacheJobStatus};
+use liboxen::error::OxenError;
+use liboxen::model::LocalRepository;
+use liboxen::repositories;
+
+use crate::cmd::RunCmd;
+pub const NAME: &str = "status";
+
+pub struct CacheStatusCmd;
+
+#[async_trait]
+impl RunCmd for CacheStatusCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        // Setups the CLI args for the command
+        Command::new(NAME)
+            .about("Show which cachers have run on the remote for a commit, how long they took, and whether they succeeded")
+            .arg(
+                Arg::new("commit")
+                    .help("Commit id or branch name. Defaults to the HEAD commit.")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("remote")
+                    .long("remote")
+                    .short('r')
+                    .help("The remote the cachers ran on")
+                    .default_value(DEFAULT_REMOTE_NAME)
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("recompute")
+                    .long("recompute")
+                    .help("Queue this cacher to run on the commit again, such as --recompute content_validator")
+                    .action(clap::ArgAction::Set),
+            )
+    }
+
+    async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        // Parse Args
+        let remote_name = args.get_one::<String>("remote").expect("has default");
+        let repository = LocalRepository::from_current_dir()?;
+
+        // Resolve branch names locally, falling back to the raw id for commits only on the remote
+        let commit_id = match args.get_one::<String>("commit") {
+            Some(revision) => match repositories::revisions::get(&repository, revision)? {
+                Some(commit) => commit.id,
+                None => revision.clone(),
+            },
+            None => repositories::commits::head_commit(&repository)?.id,
+        };
+
+        // Get the remote repo
+        let remote = repository
+            .get_remote(remote_name)
+            .ok_or(OxenError::remote_not_set(remote_name))?;
+        let remote_repo = api::client::repositories::get_by_remote(&remote)
+            .await?
+            .ok_or(OxenError::remote_not_found(remote.clone()))?;
+
+        if let Some(cacher) = args.get_one::<String>("recompute") {
+            api::client::commits::recompute_cache_job(&remote_repo, &commit_id, cacher).await?;
+            println!("Queued {cacher} to run again on commit {commit_id}\n");
+        }
+
+        let jobs = api::client::commits::list_cache_jobs(&remote_repo, &commit_id).await?;
+        if jobs.is_empty() {
+            println!("No cachers have run on commit {commit_id}");
+            return Ok(());
+        }
+
+        println!("Cachers for commit {commit_id}\n");
+        for job in jobs.iter() {
+            print_job(job);
+        }
+
+        Ok(())
+    }
+}
+
+fn print_job(job: &CacheJobRecord) {
+    let status = match job.status {
+        CacheJobStatus::Queued => "queued",
+        CacheJobStatus::Running => "running",
+        CacheJobStatus::Succeeded => "succeeded",
+        CacheJobStatus::Failed => "failed",
+    };
+    let duration = job
+        .duration_ms
+        .map(|ms| format!("{ms}ms"))
+        .unwrap_or_else(|| "-".to_string());
+    let attempts = if job.attempts == 1 {
+        "1 attempt".to_string()
+    } else {
+        format!("{} attempts", job.attempts)
+    };
+    println!(
+        "  {:<20} {:<10} {:<12} {}",
+        job.cacher, status, attempts, duration
+    );
+    if let Some(error) = &job.error {
+        for line in error.lines() {
+            println!("      {line}");
+        }
+    }
+}
@@ -1,5 +1,8 @@
 use async_trait::async_trait;
 use clap::{Arg, Command};
+use std::path::PathBuf;
+
+use liboxen::config::MergeStrategy;
 use liboxen::error::OxenError;
 use liboxen::model::LocalRepository;
 use liboxen::repositories;
@@ -27,6 +30,13 @@ impl RunCmd for CheckoutCmd {
                     .exclusive(true)
                     .action(clap::ArgAction::Set),
             )
+            .arg(
+                Arg::new("orphan")
+                    .long("orphan")
+                    .help("Create a new branch with no history and check it out. The next commit starts from an empty tree.")
+                    .exclusive(true)
+                    .action(clap::ArgAction::Set),
+            )
             .arg(
                 Arg::new("ours")
                     .long("ours")
@@ -39,6 +49,12 @@ impl RunCmd for CheckoutCmd {
                     .help("Checkout the content of the merge branch and take it as the working directories version. Will overwrite your working file.")
                     .action(clap::ArgAction::SetTrue),
             )
+            .arg(
+                Arg::new("conflicts")
+                    .long("conflicts")
+                    .help("With --ours or --theirs, take that side of every conflicting file, or of the ones under the path given, and stage them")
+                    .action(clap::ArgAction::SetTrue),
+            )
     }
 
     async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
@@ -48,6 +64,20 @@ impl RunCmd for CheckoutCmd {
         // Parse Args
         if let Some(name) = args.get_one::<String>("create") {
             self.create_checkout_branch(&repo, name)?
+        } else if let Some(name) = args.get_one::<String>("orphan") {
+            self.create_orphan_branch(&repo, name)?
+        } else if args.get_flag("conflicts") {
+            let strategy = if args.get_flag("ours") {
+                MergeStrategy::Ours
+            } else if args.get_flag("theirs") {
+                MergeStrategy::Theirs
+            } else {
+                return Err(OxenError::basic_str(
+                    "Err: Usage `oxen checkout --conflicts [path] --ours` or `oxen checkout --conflicts [path] --theirs`",
+                ));
+            };
+            let path = args.get_one::<String>("name").map(PathBuf::from);
+            self.checkout_conflicts(&repo, path, strategy).await?
         } else if args.get_flag("ours") {
             let Some(name) = args.get_one::<String>("name") else {
                 return Err(OxenError::basic_str(
@@ -98,6 +128,29 @@ impl CheckoutCmd {
         Ok(())
     }
 
+    pub async fn checkout_conflicts(
+        &self,
+        repo: &LocalRepository,
+        path: Option<PathBuf>,
+        strategy: MergeStrategy,
+    ) -> Result<(), OxenError> {
+        let conflicts = repositories::merge::list_conflicts(repo)?;
+        for conflict in conflicts.iter() {
+            let conflict_path = &conflict.base_entry.path;
+            if let Some(path) = &path {
+                if !conflict_path.starts_with(path) {
+                    continue;
+                }
+            }
+            repositories::merge::resolve_conflict(repo, conflict_path, strategy).await?;
+            println!(
+                "Resolved and staged {} ({strategy})",
+                conflict_path.display()
+            );
+        }
+        Ok(())
+    }
+
     pub async fn checkout_ours(&self, repo: &LocalRepository, path: &str) -> Result<(), OxenError> {
         repositories::checkout::checkout_ours(repo, path).await?;
         Ok(())
@@ -111,4 +164,14 @@ impl CheckoutCmd {
         repositories::branches::create_checkout(repo, name)?;
         Ok(())
     }
+
+    pub fn create_orphan_branch(
+        &self,
+        repo: &LocalRepository,
+        name: &str,
+    ) -> Result<(), OxenError> {
+        repositories::branches::create_orphan_checkout(repo, name)?;
+        println!("Stage the files for the new branch with `oxen add` and commit to create it");
+        Ok(())
+    }
 }
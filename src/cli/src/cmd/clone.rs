@@ -34,10 +34,24 @@ impl RunCmd for CloneCmd {
                     .help("Filter down the set of directories you want to clone. Useful if you have a large repository and only want to make changes to a specific subset of files.")
                     .action(clap::ArgAction::Append),
             )
+            .arg(
+                Arg::new("paths")
+                    .long("paths")
+                    .help("Comma separated directories to clone, only their merkle nodes and files are downloaded. Pulls and pushes stay within them. Ex: --paths images/train,annotations")
+                    .value_delimiter(',')
+                    .action(clap::ArgAction::Append),
+            )
             .arg(
                 Arg::new("depth")
                     .long("depth")
-                    .help("Used in combination with --subtree. The depth at which to clone the subtree. If not provided, the entire subtree will be cloned.")
+                    .help("Used in combination with --filter or --paths. The depth at which to clone the subtree. If not provided, the entire subtree will be cloned.")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("history-depth")
+                    .long("history-depth")
+                    .help("Only fetch the merkle trees and data of the last N commits, like a git shallow clone. Run `oxen pull --unshallow` to fetch the rest of the history later.")
+                    .value_parser(clap::value_parser!(usize))
                     .action(clap::ArgAction::Set),
             )
             .arg(
@@ -61,9 +75,41 @@ impl RunCmd for CloneCmd {
                     .help("Clone the repo in 'remote mode', pulling the metadata but not the file contents")
                     .action(clap::ArgAction::SetTrue),
             )
+            .arg(
+                Arg::new("json")
+                    .long("json")
+                    .help("Print progress as json lines with files, bytes, throughput and ETA instead of a progress bar")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("jobs")
+                    .long("jobs")
+                    .short('j')
+                    .help("Number of connections to download files over at once. Defaults to remote.download_concurrency in the repo config, or the number of CPUs.")
+                    .value_parser(clap::value_parser!(usize))
+                    .action(clap::ArgAction::Set),
+            )
     }
 
     async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        let current_dir = std::env::current_dir().expect("Could not get current working directory");
+        let opts = CloneCmd::parse_opts(args, &current_dir)?;
+
+        let (scheme, host) = api::client::get_scheme_and_host_from_url(&opts.url)?;
+
+        // TODO: Do I need to worry about this for remote repo?
+        check_remote_version_blocking(scheme.clone(), host.clone()).await?;
+        check_remote_version(scheme, host).await?;
+
+        repositories::clone(&opts).await?;
+
+        Ok(())
+    }
+}
+
+impl CloneCmd {
+    /// Clone options for the args, with the destination relative to `current_dir`
+    fn parse_opts(args: &clap::ArgMatches, current_dir: &Path) -> Result<CloneOpts, OxenError> {
         // Parse Args
         let url = args.get_one::<String>("URL").expect("required");
         let all = args.get_flag("all");
@@ -73,14 +119,23 @@ impl RunCmd for CloneCmd {
         let filters: Vec<PathBuf> = args
             .get_many::<String>("filter")
             .unwrap_or_default()
+            .chain(args.get_many::<String>("paths").unwrap_or_default())
+            .filter(|path| !path.is_empty())
             .map(PathBuf::from)
             .collect();
         let depth: Option<i32> = args
             .get_one::<String>("depth")
             .map(|s| s.parse().expect("Invalid depth, must be an integer"));
+        let history_depth = args.get_one::<usize>("history-depth").copied();
+        if history_depth == Some(0) {
+            return Err(OxenError::basic_str("--history-depth must be at least 1"));
+        }
+        let jobs = args.get_one::<usize>("jobs").copied();
+        if jobs == Some(0) {
+            return Err(OxenError::basic_str("--jobs must be at least 1"));
+        }
         let is_remote = args.get_flag("remote");
 
-        let current_dir = std::env::current_dir().expect("Could not get current working directory");
         let dst: PathBuf = match args.get_one::<String>("DESTINATION") {
             Some(dir_name) => {
                 let path = Path::new(dir_name);
@@ -94,7 +149,7 @@ impl RunCmd for CloneCmd {
                 }
 
                 let joined = current_dir.join(path);
-                if !joined.starts_with(&current_dir) {
+                if !joined.starts_with(current_dir) {
                     return Err(OxenError::basic_str(
                         "Invalid destination: path escapes base directory",
                     ));
@@ -108,28 +163,21 @@ impl RunCmd for CloneCmd {
             }
         };
 
-        let opts = CloneOpts {
+        Ok(CloneOpts {
             url: url.to_string(),
             dst,
             fetch_opts: FetchOpts {
                 branch: branch.to_string(),
                 subtree_paths: filters_to_subtree_paths(&filters, depth),
                 depth,
+                history_depth,
                 all,
+                json_progress: args.get_flag("json"),
+                jobs,
                 ..FetchOpts::new()
             },
             is_remote,
-        };
-
-        let (scheme, host) = api::client::get_scheme_and_host_from_url(&opts.url)?;
-
-        // TODO: Do I need to worry about this for remote repo?
-        check_remote_version_blocking(scheme.clone(), host.clone()).await?;
-        check_remote_version(scheme, host).await?;
-
-        repositories::clone(&opts).await?;
-
-        Ok(())
+        })
     }
 }
 
@@ -145,3 +193,56 @@ fn filters_to_subtree_paths(filters: &[PathBuf], depth: Option<i32>) -> Option<V
         Some(filters.to_vec())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use liboxen::error::OxenError;
+    use liboxen::repositories;
+    use liboxen::test;
+
+    use super::CloneCmd;
+    use crate::cmd::RunCmd;
+
+    #[tokio::test]
+    async fn test_clone_paths_clones_only_those_subtrees() -> Result<(), OxenError> {
+        test::run_training_data_fully_sync_remote(|_local_repo, remote_repo| async move {
+            let cloned_remote = remote_repo.clone();
+            test::run_empty_dir_test_async(|dir| async move {
+                let args = CloneCmd.args().try_get_matches_from([
+                    "clone",
+                    remote_repo.remote.url.as_str(),
+                    "new_repo",
+                    "--paths",
+                    "annotations/test,nlp",
+                ]);
+                let args = args.map_err(|err| OxenError::basic_str(err.to_string()))?;
+                let opts = CloneCmd::parse_opts(&args, &dir)?;
+                let local_repo = repositories::clone(&opts).await?;
+
+                let expected = vec![PathBuf::from("annotations/test"), PathBuf::from("nlp")];
+                assert_eq!(local_repo.subtree_paths(), Some(expected));
+
+                let mut top_level: Vec<String> = std::fs::read_dir(&local_repo.path)?
+                    .map(|entry| Ok(entry?.file_name().to_string_lossy().to_string()))
+                    .collect::<Result<_, std::io::Error>>()?;
+                top_level.retain(|name| name != ".oxen");
+                top_level.sort();
+                assert_eq!(top_level, vec!["annotations", "nlp"]);
+                assert!(local_repo
+                    .path
+                    .join("annotations")
+                    .join("test")
+                    .join("annotations.csv")
+                    .exists());
+                assert!(!local_repo.path.join("annotations").join("train").exists());
+
+                Ok(())
+            })
+            .await?;
+            Ok(cloned_remote)
+        })
+        .await
+    }
+}
@@ -1,8 +1,13 @@
 use async_trait::async_trait;
 use clap::{Arg, Command};
+use std::collections::BTreeMap;
+use std::str::FromStr;
 
+use dialoguer::Editor;
+use liboxen::config::CommitMessageConfig;
 use liboxen::error::OxenError;
-use liboxen::model::LocalRepository;
+use liboxen::model::{LocalRepository, User};
+use liboxen::opts::CommitOpts;
 use liboxen::repositories;
 
 use crate::cmd::RunCmd;
@@ -23,28 +28,94 @@ impl RunCmd for CommitCmd {
             .about("Commit the staged files to the repository.")
             .arg(
                 Arg::new("message")
-                    .help("The message for the commit. Should be descriptive about what changed.")
+                    .help("The message for the commit. Should be descriptive about what changed. If omitted, an editor is opened with the repository's commit message template.")
                     .long("message")
                     .short('m')
-                    .required(true)
                     .action(clap::ArgAction::Set),
             )
+            .arg(
+                Arg::new("meta")
+                    .long("meta")
+                    .help("Attach a key=value to the commit, such as --meta run_id=abc. Can be passed multiple times.")
+                    .action(clap::ArgAction::Append),
+            )
+            .arg(
+                Arg::new("co-author")
+                    .long("co-author")
+                    .help("Credit another author on the commit, such as --co-author \"Name <email>\". Can be passed multiple times.")
+                    .action(clap::ArgAction::Append),
+            )
+            .arg(
+                Arg::new("no-verify")
+                    .long("no-verify")
+                    .help("Skip the data validation rules in .oxen/checks.toml")
+                    .action(clap::ArgAction::SetTrue),
+            )
     }
 
     async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        let repo = LocalRepository::from_current_dir()?;
+        check_repo_migration_needed(&repo)?;
+
         // Parse Args
-        let Some(message) = args.get_one::<String>("message") else {
-            return Err(OxenError::basic_str(
-                "Err: Usage `oxen commit -m <message>`",
-            ));
+        let message = match args.get_one::<String>("message") {
+            Some(message) => message.clone(),
+            None => edit_message(&repo)?,
         };
 
-        let repo = LocalRepository::from_current_dir()?;
-        check_repo_migration_needed(&repo)?;
+        let metadata = parse_metadata(args)?;
+        let co_authors = args
+            .get_many::<String>("co-author")
+            .unwrap_or_default()
+            .map(|co_author| User::from_str(co_author))
+            .collect::<Result<Vec<User>, OxenError>>()?;
+        let no_verify = args.get_flag("no-verify");
 
         println!("Committing with message: {message}");
-        repositories::commit(&repo, message)?;
+        if metadata.is_empty() && co_authors.is_empty() && !no_verify {
+            repositories::commit(&repo, &message)?;
+        } else {
+            let commit_opts = CommitOpts {
+                metadata,
+                co_authors,
+                no_verify,
+                ..CommitOpts::default()
+            };
+            repositories::commits::commit_with_opts(&repo, &message, &commit_opts)?;
+        }
 
         Ok(())
     }
 }
+
+fn edit_message(repo: &LocalRepository) -> Result<String, OxenError> {
+    let template = repo.commit_message_config().template.unwrap_or_default();
+    let edited = Editor::new()
+        .edit(&template)
+        .map_err(|e| OxenError::basic_str(format!("Error opening editor: {e}")))?;
+
+    let message = CommitMessageConfig::clean(edited.unwrap_or_default());
+    if message.is_empty() {
+        return Err(OxenError::basic_str(
+            "Err: Aborting commit due to empty commit message. Usage `oxen commit -m <message>`",
+        ));
+    }
+    Ok(message)
+}
+
+/// Parse the repeated `--meta key=value` args, shared with `oxen log`
+pub fn parse_metadata(args: &clap::ArgMatches) -> Result<BTreeMap<String, String>, OxenError> {
+    let mut metadata = BTreeMap::new();
+    let Some(values) = args.get_many::<String>("meta") else {
+        return Ok(metadata);
+    };
+    for value in values {
+        let Some((key, val)) = value.split_once('=') else {
+            return Err(OxenError::basic_str(format!(
+                "Err: Invalid metadata {value:?}, must be of the form key=value"
+            )));
+        };
+        metadata.insert(key.trim().to_string(), val.trim().to_string());
+    }
+    Ok(metadata)
+}
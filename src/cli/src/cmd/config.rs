@@ -1,8 +1,9 @@
 use async_trait::async_trait;
 use clap::{Arg, Command};
+use std::str::FromStr;
 
 use liboxen::command;
-use liboxen::config::{AuthConfig, UserConfig};
+use liboxen::config::{AuthConfig, CaseConflictMode, UserConfig};
 use liboxen::error::OxenError;
 use liboxen::model::LocalRepository;
 
@@ -51,6 +52,13 @@ impl RunCmd for ConfigCmd {
                     .help("Delete a remote from the current working repository.")
                     .action(clap::ArgAction::Set),
             )
+            .arg(
+                Arg::new("case-conflicts")
+                    .long("case-conflicts")
+                    .value_parser(["allow", "warn", "error"])
+                    .help("What to do with paths that only differ by case in the current working repository, which overwrite each other on case-insensitive file systems.")
+                    .action(clap::ArgAction::Set),
+            )
             .arg(
                 Arg::new("auth-token")
                     .long("auth")
@@ -60,6 +68,14 @@ impl RunCmd for ConfigCmd {
                     .help("Set the authentication token for a specific oxen-server host.")
                     .action(clap::ArgAction::Set),
             )
+            .arg(
+                Arg::new("signing-key")
+                    .long("signing-key")
+                    .number_of_values(2)
+                    .value_names(["HOST", "PUBLIC_KEY"])
+                    .help("Pin the public key an oxen-server host signs branches with. Fetches from that host fail if the signature does not match.")
+                    .action(clap::ArgAction::Set),
+            )
             .arg(
                 Arg::new("default-host")
                     .long("default-host")
@@ -102,6 +118,19 @@ impl RunCmd for ConfigCmd {
             }
         }
 
+        if let Some(signing_key) = args.get_many::<String>("signing-key") {
+            if let [host, public_key] = signing_key.collect::<Vec<_>>()[..] {
+                match self.set_signing_key(host, public_key) {
+                    Ok(_) => {}
+                    Err(err) => {
+                        eprintln!("{err}")
+                    }
+                }
+            } else {
+                eprintln!("invalid arguments for --signing-key");
+            }
+        }
+
         if let Some(default_host) = args.get_one::<String>("default-host") {
             match self.set_default_host(default_host) {
                 Ok(_) => {}
@@ -136,6 +165,13 @@ impl RunCmd for ConfigCmd {
             }
         }
 
+        if let Some(mode) = args.get_one::<String>("case-conflicts") {
+            let mut repo = LocalRepository::from_current_dir()?;
+            repo.set_case_conflict_mode(CaseConflictMode::from_str(mode)?);
+            repo.save()?;
+            println!("Set case_conflicts to {mode}");
+        }
+
         Ok(())
     }
 }
@@ -178,6 +214,15 @@ impl ConfigCmd {
         Ok(())
     }
 
+    pub fn set_signing_key(&self, host: &str, public_key: &str) -> Result<(), OxenError> {
+        let host = Self::strip_host(host)?;
+        let mut config = AuthConfig::get_or_create()?;
+        config.add_host_signing_key(host.as_ref(), public_key);
+        config.save_default()?;
+        println!("Signing key pinned for host: {host}");
+        Ok(())
+    }
+
     pub fn set_default_host(&self, host: &str) -> Result<(), OxenError> {
         let host = Self::strip_host(host)?;
         let mut config = AuthConfig::get_or_create()?;
@@ -0,0 +1,157 @@
+use async_trait::async_trait;
+use clap::{Arg, Command};
+use std::path::PathBuf;
+
+use dialoguer::{Input, Select};
+use liboxen::error::OxenError;
+use liboxen::model::merge_conflict::{MergeConflict, RowMergeConflict, RowResolution};
+use liboxen::model::LocalRepository;
+use liboxen::repositories;
+
+use crate::cmd::RunCmd;
+pub const NAME: &str = "conflicts";
+pub struct ConflictsCmd;
+
+#[async_trait]
+impl RunCmd for ConflictsCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        Command::new(NAME)
+            .about("Lists the files in conflict after a merge, and walks through the conflicting rows of tabular files to resolve them.")
+            .arg(Arg::new("path").help("Only resolve the conflicts in this file"))
+            .arg(
+                Arg::new("list")
+                    .long("list")
+                    .short('l')
+                    .help("Only list the conflicting files, without resolving them.")
+                    .action(clap::ArgAction::SetTrue),
+            )
+    }
+
+    async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        let repo = LocalRepository::from_current_dir()?;
+        let path = args.get_one::<String>("path").map(PathBuf::from);
+
+        let conflicts = repositories::merge::list_conflicts(&repo)?;
+        if conflicts.is_empty() {
+            println!("No merge conflicts.");
+            return Ok(());
+        }
+
+        println!("Merge conflicts:\n");
+        for conflict in conflicts.iter() {
+            if conflict.row_conflicts.is_empty() {
+                println!("  {}", conflict.base_entry.path.display());
+            } else {
+                println!(
+                    "  {} ({} conflicting rows)",
+                    conflict.base_entry.path.display(),
+                    conflict.row_conflicts.len()
+                );
+            }
+        }
+        println!();
+
+        if args.get_flag("list") {
+            return Ok(());
+        }
+
+        for conflict in conflicts.iter() {
+            if let Some(path) = &path {
+                if conflict.base_entry.path != *path {
+                    continue;
+                }
+            }
+
+            if conflict.row_conflicts.is_empty() {
+                println!(
+                    "Resolve {} with `oxen resolve --ours` or `oxen resolve --theirs`.",
+                    conflict.base_entry.path.display()
+                );
+                continue;
+            }
+
+            self.resolve_rows(&repo, conflict).await?;
+        }
+
+        Ok(())
+    }
+}
+
+impl ConflictsCmd {
+    pub async fn resolve_rows(
+        &self,
+        repo: &LocalRepository,
+        conflict: &MergeConflict,
+    ) -> Result<(), OxenError> {
+        let path = &conflict.base_entry.path;
+        let total = conflict.row_conflicts.len();
+
+        let mut resolutions: Vec<RowResolution> = vec![];
+        for (i, row_conflict) in conflict.row_conflicts.iter().enumerate() {
+            println!(
+                "{} row {}/{} key {}",
+                path.display(),
+                i + 1,
+                total,
+                row_conflict.key
+            );
+            if !row_conflict.columns.is_empty() {
+                println!("  conflicting columns: {}", row_conflict.columns.join(", "));
+            }
+            println!("  ours:   {}", row_display(&row_conflict.base_row));
+            println!("  theirs: {}", row_display(&row_conflict.merge_row));
+
+            let choice = Select::new()
+                .with_prompt("Keep which version?")
+                .items(&["ours", "theirs", "edit"])
+                .default(0)
+                .interact()
+                .map_err(|e| OxenError::basic_str(format!("Error reading choice: {e}")))?;
+
+            let resolution = match choice {
+                0 => RowResolution::Ours,
+                1 => RowResolution::Theirs,
+                _ => RowResolution::Edited(edit_row(row_conflict)?),
+            };
+            resolutions.push(resolution);
+        }
+
+        repositories::merge::resolve_row_conflicts(repo, path, &resolutions).await?;
+        println!("Resolved and staged {}\n", path.display());
+        Ok(())
+    }
+}
+
+fn row_display(row: &Option<serde_json::Value>) -> String {
+    match row {
+        Some(row) => row.to_string(),
+        None => "(deleted)".to_string(),
+    }
+}
+
+fn edit_row(row_conflict: &RowMergeConflict) -> Result<serde_json::Value, OxenError> {
+    let initial = row_conflict
+        .base_row
+        .as_ref()
+        .or(row_conflict.merge_row.as_ref())
+        .map(|row| row.to_string())
+        .unwrap_or_default();
+
+    let edited: String = Input::new()
+        .with_prompt("Row as json")
+        .with_initial_text(initial)
+        .interact_text()
+        .map_err(|e| OxenError::basic_str(format!("Error reading row: {e}")))?;
+
+    let row: serde_json::Value = serde_json::from_str(&edited)?;
+    if !row.is_object() {
+        return Err(OxenError::basic_str(
+            "Edited row must be a json object of column name to value",
+        ));
+    }
+    Ok(row)
+}
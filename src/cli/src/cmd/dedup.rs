@@ -0,0 +1,109 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use bytesize::ByteSize;
+use clap::{Arg, ArgMatches, Command};
+
+use liboxen::error::OxenError;
+use liboxen::model::LocalRepository;
+use liboxen::repositories;
+use liboxen::util;
+
+use crate::cmd::RunCmd;
+use crate::helpers::check_repo_migration_needed;
+
+pub const NAME: &str = "dedup";
+
+pub struct DedupCmd;
+
+#[async_trait]
+impl RunCmd for DedupCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        Command::new(NAME)
+            .about("Find files with the same contents")
+            .subcommand_required(true)
+            .arg_required_else_help(true)
+            .subcommand(
+                Command::new("report")
+                    .about("List groups of files in a revision that have the same contents and the bytes they waste. Ex: oxen dedup report main --path images")
+                    .arg(
+                        Arg::new("revision")
+                            .help("The branch or commit to look at. Defaults to HEAD")
+                            .default_value("HEAD"),
+                    )
+                    .arg(
+                        Arg::new("path")
+                            .long("path")
+                            .short('p')
+                            .help("Only look at the files under this directory")
+                            .action(clap::ArgAction::Set),
+                    )
+                    .arg(
+                        Arg::new("json")
+                            .long("json")
+                            .help("Print the report as JSON")
+                            .action(clap::ArgAction::SetTrue),
+                    ),
+            )
+    }
+
+    async fn run(&self, args: &ArgMatches) -> Result<(), OxenError> {
+        let repo = LocalRepository::from_current_dir()?;
+        check_repo_migration_needed(&repo)?;
+
+        match args.subcommand() {
+            Some(("report", sub_matches)) => report(&repo, sub_matches),
+            Some((cmd, _)) => Err(OxenError::basic_str(format!(
+                "Unknown dedup subcommand {cmd}"
+            ))),
+            None => unreachable!("subcommand is required"),
+        }
+    }
+}
+
+fn report(repo: &LocalRepository, args: &ArgMatches) -> Result<(), OxenError> {
+    let revision = args.get_one::<String>("revision").expect("has default");
+    let path: Option<PathBuf> = args
+        .get_one::<String>("path")
+        .map(|path| {
+            let current_dir = std::env::current_dir()?;
+            util::fs::path_relative_to_dir(current_dir.join(path), &repo.path)
+        })
+        .transpose()?;
+
+    let report = repositories::dedup::report(repo, revision, path.as_deref())?;
+    if args.get_flag("json") {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    for group in report.groups.iter() {
+        println!(
+            "{} {} copies of {}, {} wasted",
+            group.hash,
+            group.paths.len(),
+            ByteSize::b(group.num_bytes),
+            ByteSize::b(group.wasted_bytes())
+        );
+        for path in group.paths.iter() {
+            println!("  {}", path.display());
+        }
+    }
+    let num_duplicates: usize = report
+        .groups
+        .iter()
+        .map(|group| group.paths.len() - 1)
+        .sum();
+    println!(
+        "Checked {} files, found {} duplicates in {} groups wasting {}",
+        report.files_checked,
+        num_duplicates,
+        report.groups.len(),
+        ByteSize::b(report.wasted_bytes)
+    );
+    Ok(())
+}
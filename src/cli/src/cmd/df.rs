@@ -3,13 +3,23 @@ use std::path::PathBuf;
 use async_trait::async_trait;
 use clap::{arg, Arg, ArgMatches, Command};
 
+use liboxen::api;
 use liboxen::command;
+use liboxen::config::UserConfig;
+use liboxen::constants::{DEFAULT_BRANCH_NAME, DEFAULT_HOST, DEFAULT_SCHEME};
+use liboxen::core::df::tabular;
 use liboxen::error::OxenError;
-use liboxen::model::LocalRepository;
+use liboxen::model::{LocalRepository, NewCommitBody, Schema};
+use liboxen::opts::{DFOpts, DFSplitOpts};
+use liboxen::repositories;
 use liboxen::util::fs;
+use liboxen::view::schema::SchemaResponse;
+use liboxen::view::{JsonDataFrameViewResponse, JsonDataFrameViews, StatusMessage};
 
 use crate::cmd::RunCmd;
+use crate::helpers::{get_output_path, print_json};
 pub const NAME: &str = "df";
+const APPEND: &str = "append";
 pub struct DFCmd;
 
 #[async_trait]
@@ -131,7 +141,7 @@ impl RunCmd for DFCmd {
             Arg::new("sort")
                 .long("sort")
                 .short('s')
-                .help("Sort the output by a column name. Is run at the end of all the other transforms.")
+                .help("Sort the output by comma separated column names, prefix a name with '-' to sort it descending. Is run at the end of all the other transforms.")
                 .action(clap::ArgAction::Set),
         )
         .arg(
@@ -149,7 +159,7 @@ impl RunCmd for DFCmd {
         .arg(
             Arg::new("sql")
                 .long("sql")
-                .help("Run a sql query on the data frame.")
+                .help("Run a sql query on the data frame, which is the table `df`. Ie: SELECT label, count(*) FROM df GROUP BY label")
                 .action(clap::ArgAction::Set),
         )
         .arg(
@@ -176,6 +186,39 @@ impl RunCmd for DFCmd {
                 .help("Randomize the order of the table")
                 .action(clap::ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new("sample")
+                .long("sample")
+                .help("Randomly keep a fraction of the rows, between 0 and 1. Ex) 0.1")
+                .action(clap::ArgAction::Set),
+        )
+        .arg(
+            Arg::new("seed")
+                .long("seed")
+                .help("Seed for --sample, --randomize and --splits so the same rows are picked every time")
+                .action(clap::ArgAction::Set),
+        )
+        .arg(
+            Arg::new("splits")
+                .long("splits")
+                .help("Split the rows into random parts written to --output-dir and committed. Format: 'name=fraction,...' ie: 'train=0.8,val=0.1,test=0.1'")
+                .requires("output-dir")
+                .action(clap::ArgAction::Set),
+        )
+        .arg(
+            Arg::new("stratify")
+                .long("stratify")
+                .help("Keep the proportion of each value of this column the same in every split")
+                .requires("splits")
+                .action(clap::ArgAction::Set),
+        )
+        .arg(
+            Arg::new("output-dir")
+                .long("output-dir")
+                .help("Directory to write the splits to")
+                .requires("splits")
+                .action(clap::ArgAction::Set),
+        )
         .arg(
             Arg::new("reverse")
                 .long("reverse")
@@ -237,9 +280,22 @@ impl RunCmd for DFCmd {
                 .help("The quote character to use when reading the file. Default is '\"'")
                 .action(clap::ArgAction::Set),
         )
+        .arg(
+            Arg::new("sheet")
+                .long("sheet")
+                .help("The sheet to read from an Excel workbook. Default is the first sheet")
+                .action(clap::ArgAction::Set),
+        )
+        .args_conflicts_with_subcommands(true)
+        .subcommand_negates_reqs(true)
+        .subcommand(DFCmd::append_args())
     }
 
     async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        if let Some((APPEND, sub_matches)) = args.subcommand() {
+            return DFCmd::append(sub_matches).await;
+        }
+
         // Parse Args
         let mut opts = DFCmd::parse_df_args(args);
         let Some(path) = args.get_one::<String>("PATH") else {
@@ -247,7 +303,15 @@ impl RunCmd for DFCmd {
         };
         opts.path = Some(PathBuf::from(path));
 
-        if let Some(revision) = args.get_one::<String>("revision") {
+        if let Some(splits) = args.get_one::<String>("splits") {
+            let split_opts = DFSplitOpts {
+                splits: DFSplitOpts::parse_splits(splits)?,
+                stratify: args.get_one::<String>("stratify").map(String::from),
+                seed: opts.seed,
+                output_dir: PathBuf::from(args.get_one::<String>("output-dir").expect("required")),
+            };
+            DFCmd::split(path, opts, &split_opts).await?;
+        } else if let Some(revision) = args.get_one::<String>("revision") {
             let repo = LocalRepository::from_current_dir()?;
             command::df::df_revision(&repo, path, revision, opts)?;
         } else if args.get_flag("schema") || args.get_flag("schema-flat") {
@@ -260,9 +324,180 @@ impl RunCmd for DFCmd {
 
         Ok(())
     }
+
+    async fn run_json(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        if args.subcommand().is_some() || args.get_one::<String>("revision").is_some() {
+            return Err(OxenError::basic_str(
+                "`oxen df` does not support --output json with append or --revision",
+            ));
+        }
+
+        let opts = DFCmd::parse_df_args(args);
+        let Some(path) = args.get_one::<String>("PATH") else {
+            return Err(OxenError::basic_str("Must supply a DataFrame to process."));
+        };
+        if args.get_flag("schema") || args.get_flag("schema-flat") {
+            return print_json(&SchemaResponse {
+                status: StatusMessage::resource_found(),
+                schema: tabular::get_schema(path)?,
+            });
+        }
+
+        let mut df = tabular::read_df(path, opts.clone())?;
+        if let Some(write) = &opts.write {
+            tabular::write_df(&mut df, write)?;
+        }
+        if let Some(output) = &opts.output {
+            tabular::write_df(&mut df, output)?;
+        }
+
+        // The transforms are already applied, the view only paginates
+        let schema = Schema::from_polars(&df.schema());
+        let page_opts = DFOpts {
+            page: opts.page,
+            page_size: opts.page_size,
+            ..DFOpts::empty()
+        };
+        print_json(&JsonDataFrameViewResponse {
+            status: StatusMessage::resource_found(),
+            data_frame: JsonDataFrameViews::from_df_and_opts(df, schema, &page_opts),
+            commit: None,
+            resource: None,
+            derived_resource: None,
+        })
+    }
 }
 
 impl DFCmd {
+    fn append_args() -> Command {
+        Command::new(APPEND)
+            .about("Append rows to a data frame in a remote repository and commit them, without cloning. Ex: oxen df append ox/repo data/train.csv --data rows.jsonl")
+            .arg(arg!(<ID> "The remote repository to append to. Format: namespace/repo-name"))
+            .arg(arg!(<PATH> "Path of the data frame within the repository"))
+            .arg(
+                Arg::new("data")
+                    .long("data")
+                    .required(true)
+                    .help("The rows to append. Either a JSON object or array, or a path to a .json or .jsonl file")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("branch")
+                    .long("branch")
+                    .short('b')
+                    .help("The branch to commit to. Defaults to main")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("message")
+                    .long("message")
+                    .short('m')
+                    .help("The commit message. Defaults to 'Append N rows to PATH'")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("host")
+                    .long("host")
+                    .help("The host of the remote repository. Defaults to hub.oxen.ai")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("scheme")
+                    .long("scheme")
+                    .help("The scheme of the remote repository. Defaults to https")
+                    .action(clap::ArgAction::Set),
+            )
+    }
+
+    async fn append(args: &ArgMatches) -> Result<(), OxenError> {
+        let id = args.get_one::<String>("ID").expect("required");
+        let path = args.get_one::<String>("PATH").expect("required");
+        let data = args.get_one::<String>("data").expect("required");
+        let branch = args
+            .get_one::<String>("branch")
+            .map(String::from)
+            .unwrap_or(DEFAULT_BRANCH_NAME.to_string());
+        let host = args
+            .get_one::<String>("host")
+            .map(String::from)
+            .unwrap_or(DEFAULT_HOST.to_string());
+        let scheme = args
+            .get_one::<String>("scheme")
+            .map(String::from)
+            .unwrap_or(DEFAULT_SCHEME.to_string());
+
+        let rows = DFCmd::parse_rows(data)?;
+        let message = args
+            .get_one::<String>("message")
+            .map(String::from)
+            .unwrap_or(format!("Append {} rows to {}", rows.len(), path));
+
+        let Some(remote_repo) =
+            api::client::repositories::get_by_name_host_and_scheme(id, &host, &scheme).await?
+        else {
+            return Err(OxenError::basic_str(format!(
+                "Remote repository not found: {id}"
+            )));
+        };
+
+        let user = UserConfig::get()?.to_user();
+        let commit = NewCommitBody {
+            message,
+            author: user.name,
+            email: user.email,
+        };
+        api::client::data_frames::append_rows(&remote_repo, &branch, path, &rows, &commit).await?;
+        Ok(())
+    }
+
+    /// Write the splits, and commit them when run inside a repository so the exact rows of
+    /// each split are versioned
+    async fn split(path: &str, opts: DFOpts, split_opts: &DFSplitOpts) -> Result<(), OxenError> {
+        let paths = command::df::split(path, opts, split_opts)?;
+
+        let Ok(repo) = LocalRepository::from_current_dir() else {
+            return Ok(());
+        };
+        for path in paths.iter() {
+            repositories::add(&repo, path).await?;
+        }
+        let names: Vec<&str> = split_opts.splits.iter().map(|s| s.name.as_str()).collect();
+        let message = match &split_opts.seed {
+            Some(seed) => format!("Split {path} into {} with seed {seed}", names.join(", ")),
+            None => format!("Split {path} into {}", names.join(", ")),
+        };
+        let commit = repositories::commit(&repo, &message)?;
+        println!("Committed splits in {}", commit.id);
+        Ok(())
+    }
+
+    /// Rows can be passed inline as JSON or read from a .json or .jsonl file
+    fn parse_rows(data: &str) -> Result<Vec<serde_json::Value>, OxenError> {
+        let path = PathBuf::from(data);
+        let contents = if path.is_file() {
+            fs::read_from_path(&path)?
+        } else {
+            data.to_string()
+        };
+
+        if let Ok(value) = serde_json::from_str::<serde_json::Value>(&contents) {
+            return match value {
+                serde_json::Value::Array(rows) => Ok(rows),
+                serde_json::Value::Object(_) => Ok(vec![value]),
+                _ => Err(OxenError::basic_str(
+                    "Rows must be JSON objects, or an array of objects",
+                )),
+            };
+        }
+
+        // Otherwise treat it as JSON lines, one row per line
+        let mut rows = vec![];
+        for line in contents.lines().filter(|line| !line.trim().is_empty()) {
+            rows.push(serde_json::from_str(line)?);
+        }
+        Ok(rows)
+    }
+
     pub fn parse_df_args(args: &ArgMatches) -> liboxen::opts::DFOpts {
         let vstack: Option<Vec<PathBuf>> = if let Some(vstack) = args.get_many::<String>("vstack") {
             let values: Vec<PathBuf> = vstack.map(std::path::PathBuf::from).collect();
@@ -278,9 +513,8 @@ impl DFCmd {
             None
         };
 
-        let repo_dir: Option<PathBuf> = if args.get_one::<String>("sql").is_some()
-            || args.get_one::<String>("text2sql").is_some()
-        {
+        // --sql is run with polars on the file itself, only text2sql needs the repository
+        let repo_dir: Option<PathBuf> = if args.get_one::<String>("text2sql").is_some() {
             fs::get_repo_root_from_current_dir()
         } else {
             None
@@ -309,9 +543,7 @@ impl DFCmd {
                 .map(|x| x.parse::<usize>().expect("head must be valid int")),
             host: args.get_one::<String>("host").map(String::from),
             item: args.get_one::<String>("item").map(String::from),
-            output: args
-                .get_one::<String>("output")
-                .map(std::path::PathBuf::from),
+            output: get_output_path(args).map(std::path::PathBuf::from),
             output_column: args.get_one::<String>("output-column").map(String::from),
             page: args
                 .get_one::<String>("page")
@@ -322,6 +554,13 @@ impl DFCmd {
             path: None,
             quote_char: args.get_one::<String>("quote").map(String::from),
             repo_dir,
+            sample: args
+                .get_one::<String>("sample")
+                .map(|x| x.parse::<f64>().expect("sample must be a valid fraction")),
+            seed: args
+                .get_one::<String>("seed")
+                .map(|x| x.parse::<u64>().expect("seed must be valid int")),
+            sheet: args.get_one::<String>("sheet").map(String::from),
             row: args
                 .get_one::<String>("row")
                 .map(|x| x.parse::<usize>().expect("row must be valid int")),
@@ -1,4 +1,5 @@
 use async_trait::async_trait;
+use bytesize::ByteSize;
 use clap::{Arg, Command};
 use colored::ColoredString;
 use colored::Colorize;
@@ -9,12 +10,21 @@ use std::path::PathBuf;
 use liboxen::core::df::pretty_print;
 use liboxen::core::df::tabular;
 use liboxen::error::OxenError;
+use liboxen::model::diff::structured_diff::KeyDiff;
 use liboxen::model::diff::tabular_diff::TabularDiffMods;
-use liboxen::model::diff::{ChangeType, DiffResult, TextDiff};
+use liboxen::model::diff::{
+    BinaryDiff, ChangeType, DiffResult, DiffStat, DirChangesSummary, StructuredDiff, TextDiff,
+};
+use liboxen::model::LocalRepository;
 use liboxen::opts::DiffOpts;
 use liboxen::repositories;
+use liboxen::view::diff::{
+    DiffResultView, DiffResultsResponse, DiffStatResponse, DirChangesResponse,
+};
+use liboxen::view::StatusMessage;
 
 use crate::cmd::RunCmd;
+use crate::helpers::{get_output_path, print_json};
 pub const NAME: &str = "diff";
 pub const DIFFSEP: &str = "..";
 pub struct DiffCmd;
@@ -75,11 +85,41 @@ impl RunCmd for DiffCmd {
                     .help("Output directory path to write the results")
                     .action(clap::ArgAction::Set),
             )
+            .arg(
+                Arg::new("stat")
+                    .long("stat")
+                    .help("Only show the number of lines, rows or bytes inserted and deleted in each file")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("files")
+                    .long("files")
+                    .help("When comparing a directory between two revisions, show the diff of every changed file instead of a summary per directory")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("format")
+                    .long("format")
+                    .value_parser(["text", "json"])
+                    .default_value("text")
+                    .help("Print the diff as text in a pager, or as json for scripts. JSON and YAML files list their changed keys.")
+                    .action(clap::ArgAction::Set),
+            )
     }
 
     async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
         // Parse Args
+        if args.get_one::<String>("format").map(String::as_str) == Some("json") {
+            return self.run_json(args).await;
+        }
         let opts = DiffCmd::parse_args(args);
+        if args.get_flag("stat") {
+            return DiffCmd::print_diff_stat(&opts);
+        }
+        if let Some(summary) = DiffCmd::dir_changes(&opts, args)? {
+            DiffCmd::print_dir_changes(&summary);
+            return Ok(());
+        }
         let output = opts.output.clone();
 
         let mut diff_result = repositories::diffs::diff(opts)?;
@@ -89,6 +129,31 @@ impl RunCmd for DiffCmd {
 
         Ok(())
     }
+
+    async fn run_json(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        let opts = DiffCmd::parse_args(args);
+        if args.get_flag("stat") {
+            return print_json(&DiffStatResponse {
+                status: StatusMessage::resource_found(),
+                stat: DiffCmd::diff_stat(&opts)?,
+            });
+        }
+        if let Some(summary) = DiffCmd::dir_changes(&opts, args)? {
+            return print_json(&DirChangesResponse {
+                status: StatusMessage::resource_found(),
+                summary,
+            });
+        }
+        let output = opts.output.clone();
+
+        let mut diff_result = repositories::diffs::diff(opts)?;
+        DiffCmd::maybe_save_diff_output(&mut diff_result, output)?;
+
+        print_json(&DiffResultsResponse {
+            status: StatusMessage::resource_found(),
+            diffs: diff_result.into_iter().map(DiffResultView::from).collect(),
+        })
+    }
 }
 
 impl DiffCmd {
@@ -199,7 +264,7 @@ impl DiffCmd {
             .map(|values| values.cloned().collect())
             .unwrap_or_default();
 
-        let output = args.get_one::<String>("output").map(PathBuf::from);
+        let output = get_output_path(args).map(PathBuf::from);
 
         DiffOpts {
             repo_dir: None,
@@ -232,10 +297,23 @@ impl DiffCmd {
                     DiffCmd::print_column_changes(&mut p, &diff.summary.modifications)?;
                     DiffCmd::print_row_changes(&mut p, &diff.summary.modifications)?;
                     write_to_pager(&mut p, pretty_print::df_to_str(&diff.contents).as_str())?;
+                    if diff.modified_cells.height() > 0 {
+                        write_to_pager(&mut p, "\nModified cells:")?;
+                        write_to_pager(
+                            &mut p,
+                            pretty_print::df_to_str(&diff.modified_cells).as_str(),
+                        )?;
+                    }
                 }
                 DiffResult::Text(diff) => {
                     DiffCmd::print_text_diff(&mut p, diff)?;
                 }
+                DiffResult::Binary(diff) => {
+                    DiffCmd::print_binary_diff(&mut p, diff)?;
+                }
+                DiffResult::Structured(diff) => {
+                    DiffCmd::print_structured_diff(&mut p, diff)?;
+                }
             }
             write_to_pager(&mut p, "\n\n".to_string().as_str())?;
         }
@@ -250,6 +328,122 @@ impl DiffCmd {
         Ok(())
     }
 
+    fn print_diff_stat(opts: &DiffOpts) -> Result<(), OxenError> {
+        DiffCmd::print_stat(&DiffCmd::diff_stat(opts)?);
+        Ok(())
+    }
+
+    fn diff_stat(opts: &DiffOpts) -> Result<DiffStat, OxenError> {
+        let (Some(rev_1), Some(rev_2)) = (&opts.revision_1, &opts.revision_2) else {
+            return Err(OxenError::basic_str(
+                "--stat compares two revisions, such as `oxen diff --stat main..my-branch`",
+            ));
+        };
+        let repo = LocalRepository::from_current_dir()?;
+        let base = repositories::revisions::get(&repo, rev_1)?
+            .ok_or_else(|| OxenError::revision_not_found(rev_1.to_string().into()))?;
+        let head = repositories::revisions::get(&repo, rev_2)?
+            .ok_or_else(|| OxenError::revision_not_found(rev_2.to_string().into()))?;
+        repositories::diffs::diff_stat(&repo, &base, &head, &opts.path_1)
+    }
+
+    /// A summary per directory when a directory is compared between two revisions, `None`
+    /// for files or when `--files` asks for every file's diff
+    fn dir_changes(
+        opts: &DiffOpts,
+        args: &clap::ArgMatches,
+    ) -> Result<Option<DirChangesSummary>, OxenError> {
+        if args.get_flag("files") {
+            return Ok(None);
+        }
+        let (Some(rev_1), Some(rev_2)) = (&opts.revision_1, &opts.revision_2) else {
+            return Ok(None);
+        };
+        if opts
+            .path_2
+            .as_ref()
+            .is_some_and(|path_2| *path_2 != opts.path_1)
+        {
+            return Ok(None);
+        }
+        let repo = LocalRepository::from_current_dir()?;
+        let base = repositories::revisions::get(&repo, rev_1)?
+            .ok_or_else(|| OxenError::revision_not_found(rev_1.to_string().into()))?;
+        let head = repositories::revisions::get(&repo, rev_2)?
+            .ok_or_else(|| OxenError::revision_not_found(rev_2.to_string().into()))?;
+        let path = &opts.path_1;
+        let is_dir = path.as_os_str().is_empty()
+            || repositories::tree::has_dir(&repo, &head, path)?
+            || repositories::tree::has_dir(&repo, &base, path)?;
+        if !is_dir {
+            return Ok(None);
+        }
+        Ok(Some(repositories::diffs::dir_changes(
+            &repo, &base, &head, path,
+        )?))
+    }
+
+    /// Print one line per directory with changed files followed by the totals
+    pub fn print_dir_changes(summary: &DirChangesSummary) {
+        let byte_delta = |delta: i64| {
+            let sign = if delta < 0 { "-" } else { "+" };
+            format!("{sign}{}", ByteSize::b(delta.unsigned_abs()))
+        };
+        let width = summary
+            .dirs
+            .iter()
+            .map(|dir| dir.path.to_string_lossy().len().max(1))
+            .max()
+            .unwrap_or(0);
+        for dir in &summary.dirs {
+            let path = dir.path.to_string_lossy();
+            let path = if path.is_empty() { "/".into() } else { path };
+            println!(
+                " {:<width$} | {} {} {} | {}",
+                path,
+                format!("+{}", dir.file_counts.added).green(),
+                format!("-{}", dir.file_counts.removed).red(),
+                format!("~{}", dir.file_counts.modified).yellow(),
+                byte_delta(dir.byte_delta)
+            );
+        }
+
+        let total = summary.total();
+        println!(
+            " {} dirs changed, {} added, {} removed, {} modified files, {}",
+            summary.dirs.len(),
+            total.file_counts.added,
+            total.file_counts.removed,
+            total.file_counts.modified,
+            byte_delta(total.byte_delta)
+        );
+    }
+
+    /// Print one line per changed file followed by the totals
+    pub fn print_stat(stat: &DiffStat) {
+        let width = stat
+            .files
+            .iter()
+            .map(|file| file.display_path().len())
+            .max()
+            .unwrap_or(0);
+        for file in &stat.files {
+            println!(
+                " {:<width$} | {} {} {}",
+                file.display_path(),
+                format!("+{}", file.insertions).green(),
+                format!("-{}", file.deletions).red(),
+                file.unit
+            );
+        }
+
+        let mut totals: Vec<String> = vec![format!("{} files changed", stat.files.len())];
+        for (unit, (insertions, deletions)) in stat.totals() {
+            totals.push(format!("+{insertions} -{deletions} {unit}"));
+        }
+        println!(" {}", totals.join(", "));
+    }
+
     fn print_row_changes(p: &mut Pager, mods: &TabularDiffMods) -> Result<(), OxenError> {
         let mut outputs: Vec<ColoredString> = vec![];
 
@@ -324,6 +518,93 @@ impl DiffCmd {
         Ok(())
     }
 
+    fn print_binary_diff(p: &mut Pager, diff: &BinaryDiff) -> Result<(), OxenError> {
+        write_to_pager(
+            p,
+            &format!(
+                "--- from file: {}\n+++ to file: {}\n",
+                diff.filename1.as_deref().unwrap_or("<no file1>"),
+                diff.filename2.as_deref().unwrap_or("<no file2>")
+            ),
+        )?;
+        write_to_pager(
+            p,
+            &format!(
+                "Binary files: {} -> {}",
+                ByteSize::b(diff.size1),
+                ByteSize::b(diff.size2)
+            ),
+        )?;
+        write_to_pager(
+            p,
+            &format!(
+                "{:.1}% unchanged ({} of {} chunks), {} changed",
+                diff.unchanged_fraction() * 100.0,
+                diff.unchanged_chunks,
+                diff.num_chunks2,
+                ByteSize::b(diff.changed_bytes())
+            ),
+        )?;
+
+        if !diff.changed_ranges.is_empty() {
+            write_to_pager(p, "\nChanged regions:")?;
+        }
+        for range in &diff.changed_ranges {
+            let line = format!(
+                "   @ {:#012x} {} ({} bytes)",
+                range.offset,
+                ByteSize::b(range.len),
+                range.len
+            );
+            write_to_pager(p, &line.yellow().to_string())?;
+        }
+        Ok(())
+    }
+
+    fn print_structured_diff(p: &mut Pager, diff: &StructuredDiff) -> Result<(), OxenError> {
+        write_to_pager(
+            p,
+            &format!(
+                "--- from file: {}\n+++ to file: {}\n",
+                diff.filename1.as_deref().unwrap_or("<no file1>"),
+                diff.filename2.as_deref().unwrap_or("<no file2>")
+            ),
+        )?;
+        for key in &diff.keys {
+            DiffCmd::print_key_diff(p, key, 0)?;
+        }
+        write_to_pager(p, &format!("\n{} keys changed", diff.num_changes()))
+    }
+
+    fn print_key_diff(p: &mut Pager, key: &KeyDiff, depth: usize) -> Result<(), OxenError> {
+        let indent = "  ".repeat(depth);
+        let value = |value: &Option<serde_json::Value>| {
+            value.as_ref().map(|v| v.to_string()).unwrap_or_default()
+        };
+        let line = match key.modification {
+            ChangeType::Added => format!("{indent}+ {}: {}", key.key, value(&key.after))
+                .green()
+                .to_string(),
+            ChangeType::Removed => format!("{indent}- {}: {}", key.key, value(&key.before))
+                .red()
+                .to_string(),
+            _ if !key.children.is_empty() => format!("{indent}~ {}:", key.key),
+            _ => format!(
+                "{indent}~ {}: {} -> {}",
+                key.key,
+                value(&key.before),
+                value(&key.after)
+            )
+            .yellow()
+            .to_string(),
+        };
+        write_to_pager(p, &line)?;
+        for child in &key.children {
+            DiffCmd::print_key_diff(p, child, depth + 1)?;
+        }
+        Ok(())
+    }
+
     pub fn maybe_save_diff_output(
         result: &mut Vec<DiffResult>,
         output: Option<PathBuf>,
@@ -338,6 +619,12 @@ impl DiffCmd {
                     DiffResult::Text(_) => {
                         println!("Saving to disk not supported for text output");
                     }
+                    DiffResult::Binary(_) => {
+                        println!("Saving to disk not supported for binary output");
+                    }
+                    DiffResult::Structured(_) => {
+                        println!("Saving to disk not supported for json or yaml output");
+                    }
                 }
             }
         }
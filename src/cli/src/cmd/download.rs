@@ -11,7 +11,7 @@ use std::path::PathBuf;
 
 use liboxen::repositories;
 
-use crate::helpers::check_remote_version_blocking;
+use crate::helpers::{check_remote_version_blocking, get_output_path};
 
 use crate::cmd::RunCmd;
 pub const NAME: &str = "download";
@@ -86,8 +86,7 @@ impl RunCmd for DownloadCmd {
         if paths.is_empty() {
             return Err(OxenError::basic_str("Must supply a path to download."));
         }
-        let dst = args
-            .get_one::<String>("output")
+        let dst = get_output_path(args)
             .map(PathBuf::from)
             .unwrap_or(PathBuf::from("."));
         let revision = args
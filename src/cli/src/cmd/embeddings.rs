@@ -10,6 +10,9 @@ pub const NAME: &str = "embeddings";
 pub mod index;
 pub use index::EmbeddingsIndexCmd;
 
+pub mod neighbors;
+pub use neighbors::EmbeddingsNeighborsCmd;
+
 pub mod query;
 pub use query::EmbeddingsQueryCmd;
 
@@ -23,7 +26,7 @@ impl RunCmd for EmbeddingsCmd {
 
     fn args(&self) -> Command {
         // Setups the CLI args for the command
-        let mut command = Command::new(NAME).about("Index and query embeddings from a data frame.");
+        let mut command = Command::new(NAME).about("Index and query embeddings from a data frame, or find similar files by their embeddings.");
 
         // These are all the subcommands for the schemas command
         // including `index` and `query`
@@ -55,8 +58,11 @@ impl RunCmd for EmbeddingsCmd {
 
 impl EmbeddingsCmd {
     fn get_subcommands(&self) -> HashMap<String, Box<dyn RunCmd>> {
-        let commands: Vec<Box<dyn RunCmd>> =
-            vec![Box::new(EmbeddingsIndexCmd), Box::new(EmbeddingsQueryCmd)];
+        let commands: Vec<Box<dyn RunCmd>> = vec![
+            Box::new(EmbeddingsIndexCmd),
+            Box::new(EmbeddingsNeighborsCmd),
+            Box::new(EmbeddingsQueryCmd),
+        ];
         let mut runners: HashMap<String, Box<dyn RunCmd>> = HashMap::new();
         for cmd in commands {
             runners.insert(cmd.name().to_string(), cmd);
@@ -0,0 +1,97 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use clap::{arg, Arg, Command};
+use liboxen::error::OxenError;
+use liboxen::model::LocalRepository;
+use liboxen::opts::EmbeddingNeighborsOpts;
+use liboxen::repositories;
+
+use crate::cmd::RunCmd;
+pub const NAME: &str = "neighbors";
+
+pub struct EmbeddingsNeighborsCmd;
+
+#[async_trait]
+impl RunCmd for EmbeddingsNeighborsCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        // Setups the CLI args for the command
+        Command::new(NAME)
+            .about("Find the files most similar to a file by their embeddings at a revision. Ex: oxen embeddings neighbors embeddings.parquet -c embedding --file images/cat.jpg")
+            .arg(arg!(<PATH> "Path to the data frame with the embeddings."))
+            .arg(
+                Arg::new("column")
+                    .long("column")
+                    .short('c')
+                    .required(true)
+                    .help("The column of embedding vectors."),
+            )
+            .arg(
+                Arg::new("file")
+                    .long("file")
+                    .short('f')
+                    .conflicts_with("embedding")
+                    .help("Find the neighbors of the embedding of this file."),
+            )
+            .arg(
+                Arg::new("embedding")
+                    .long("embedding")
+                    .short('e')
+                    .help("Find the neighbors of this embedding, formatted as a JSON array ie: [0.1,0.2,0.3]"),
+            )
+            .arg(
+                Arg::new("file_column")
+                    .long("file-column")
+                    .help("The column naming the file each row describes. Defaults to 'file'."),
+            )
+            .arg(
+                Arg::new("revision")
+                    .long("revision")
+                    .short('r')
+                    .help("The branch or commit to search. Defaults to HEAD."),
+            )
+            .arg(
+                Arg::new("k")
+                    .long("k")
+                    .short('k')
+                    .value_parser(clap::value_parser!(usize))
+                    .help("The number of neighbors to return. Defaults to 10."),
+            )
+    }
+
+    async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        // Parse Args
+        let path = args.get_one::<String>("PATH").expect("required");
+        let column = args.get_one::<String>("column").expect("required");
+        let embedding = match args.get_one::<String>("embedding") {
+            Some(embedding) => Some(serde_json::from_str::<Vec<f32>>(embedding)?),
+            None => None,
+        };
+        let opts = EmbeddingNeighborsOpts {
+            path: PathBuf::from(path),
+            column: column.to_string(),
+            file_column: args.get_one::<String>("file_column").cloned(),
+            file: args.get_one::<String>("file").cloned(),
+            embedding,
+            k: args.get_one::<usize>("k").copied(),
+            nprobe: None,
+        };
+        opts.validate()?;
+
+        let repository = LocalRepository::from_current_dir()?;
+        let default_revision = String::from("HEAD");
+        let revision = args
+            .get_one::<String>("revision")
+            .unwrap_or(&default_revision);
+        let result = repositories::embeddings::neighbors(&repository, revision, &opts)?;
+        for neighbor in result.neighbors.iter() {
+            let file = neighbor.file.as_deref().unwrap_or("");
+            println!("{:.4}\t{}\t{}", neighbor.similarity, neighbor.row, file);
+        }
+        Ok(())
+    }
+}
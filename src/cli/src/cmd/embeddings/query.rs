@@ -10,6 +10,7 @@ use liboxen::opts::{EmbeddingQueryOpts, PaginateOpts};
 use liboxen::repositories;
 
 use crate::cmd::RunCmd;
+use crate::helpers::get_output_path;
 pub const NAME: &str = "query";
 
 pub struct EmbeddingsQueryCmd;
@@ -122,7 +123,7 @@ impl RunCmd for EmbeddingsQueryCmd {
         println!("{}", df);
         println!("Query took: {:?}", start.elapsed());
 
-        let Some(output) = args.get_one::<String>("output") else {
+        let Some(output) = get_output_path(args) else {
             return Ok(());
         };
 
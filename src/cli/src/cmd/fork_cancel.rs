@@ -0,0 +1,43 @@
+use async_trait::async_trait;
+use clap::{Arg, Command};
+use std::path::PathBuf;
+
+use liboxen::error::OxenError;
+use liboxen::repositories;
+
+use crate::cmd::RunCmd;
+
+pub const NAME: &str = "fork-cancel";
+pub struct ForkCancelCmd;
+
+#[async_trait]
+impl RunCmd for ForkCancelCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        // Setups the CLI args for the command
+        Command::new(NAME)
+            .about("Stop a running fork, it can be resumed later from where it stopped")
+            .arg(
+                Arg::new("DESTINATION")
+                    .help("Path of the repository the fork is writing to")
+                    .required(true),
+            )
+    }
+
+    async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        let dst = args
+            .get_one::<String>("DESTINATION")
+            .map(PathBuf::from)
+            .expect("Must supply a destination");
+
+        let status = repositories::fork::cancel_fork(&dst)?;
+        println!(
+            "Asked the fork into {} to stop, it was {}",
+            status.repository, status.status
+        );
+        Ok(())
+    }
+}
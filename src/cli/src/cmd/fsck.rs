@@ -0,0 +1,64 @@
+use async_trait::async_trait;
+use clap::{Arg, Command};
+
+use liboxen::error::OxenError;
+use liboxen::model::LocalRepository;
+use liboxen::repositories;
+
+use crate::cmd::RunCmd;
+
+pub const NAME: &str = "fsck";
+pub struct FsckCmd;
+
+#[async_trait]
+impl RunCmd for FsckCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        // Setups the CLI args for the command
+        Command::new(NAME)
+            .about("Verify the merkle tree and version files of every commit, exits non-zero if anything is missing or corrupt")
+            .arg(
+                Arg::new("revision")
+                    .long("revision")
+                    .help("Only check the commit at this branch or commit id")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("json")
+                    .long("json")
+                    .help("Print the report as JSON")
+                    .action(clap::ArgAction::SetTrue),
+            )
+    }
+
+    async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        let repo = LocalRepository::from_current_dir()?;
+        let revision = args.get_one::<String>("revision");
+
+        let report = repositories::fsck::run(&repo, revision.map(String::as_str))?;
+        if args.get_flag("json") {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        } else {
+            for problem in report.problems.iter() {
+                println!("{problem}");
+            }
+            println!(
+                "Checked {} commits and {} version files, found {} problems",
+                report.commits_checked,
+                report.versions_checked,
+                report.problems.len()
+            );
+        }
+
+        if !report.is_ok() {
+            return Err(OxenError::basic_str(format!(
+                "fsck found {} problems",
+                report.problems.len()
+            )));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,52 @@
+use async_trait::async_trait;
+use clap::{Arg, Command};
+
+use liboxen::error::OxenError;
+use liboxen::model::LocalRepository;
+use liboxen::repositories;
+
+use crate::cmd::RunCmd;
+
+pub const NAME: &str = "gc";
+pub struct GcCmd;
+
+#[async_trait]
+impl RunCmd for GcCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        // Setups the CLI args for the command
+        Command::new(NAME)
+            .about(
+                "Delete version files that no branch, workspace or reflog entry can reach anymore",
+            )
+            .arg(
+                Arg::new("dry-run")
+                    .long("dry-run")
+                    .help("Report how much space would be reclaimed without deleting anything")
+                    .action(clap::ArgAction::SetTrue),
+            )
+    }
+
+    async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        let dry_run = args.get_flag("dry-run");
+        let repo = LocalRepository::from_current_dir()?;
+
+        let report = repositories::gc::run(&repo, dry_run).await?;
+        let size = bytesize::ByteSize::b(report.removed_bytes);
+        if dry_run {
+            println!(
+                "Would remove {} unreachable version files ({size}), {} are reachable",
+                report.removed_versions, report.reachable_versions
+            );
+        } else {
+            println!(
+                "Removed {} unreachable version files ({size}), {} are reachable",
+                report.removed_versions, report.reachable_versions
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,136 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use clap::{Arg, ArgMatches, Command};
+
+use liboxen::error::OxenError;
+use liboxen::opts::S3ImportOpts;
+use liboxen::repositories;
+
+use crate::cmd::RunCmd;
+
+pub const NAME: &str = "import";
+pub struct ImportCmd;
+
+#[async_trait]
+impl RunCmd for ImportCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        // Setups the CLI args for the command
+        Command::new(NAME)
+            .about("Create an Oxen repository from a git repository or an S3 bucket")
+            .subcommand_required(true)
+            .subcommand(
+                Command::new("git")
+                    .about("Convert a git repository commit by commit, with its branches and tags")
+                    .arg(
+                        Arg::new("REPO")
+                            .help("Path or URL of the git repository")
+                            .required(true),
+                    )
+                    .arg(Arg::new("DEST").help(
+                        "Directory to create the Oxen repository in. Defaults to the name of the git repository.",
+                    ))
+                    .arg(
+                        Arg::new("lfs")
+                            .long("lfs")
+                            .help("Fetch the git LFS objects and import their content instead of the pointer files")
+                            .action(clap::ArgAction::SetTrue),
+                    ),
+            )
+            .subcommand(
+                Command::new("s3")
+                    .about("Snapshot the objects under an S3 prefix, keeping their key structure")
+                    .arg(
+                        Arg::new("URL")
+                            .help("Bucket and prefix to import, s3://bucket/prefix")
+                            .required(true),
+                    )
+                    .arg(Arg::new("DEST").help(
+                        "Directory of the Oxen repository. Defaults to the last part of the prefix.",
+                    ))
+                    .arg(
+                        Arg::new("sync")
+                            .long("sync")
+                            .help("Update a repository imported before, only downloading the objects that were added or changed, and commit the difference")
+                            .action(clap::ArgAction::SetTrue),
+                    )
+                    .arg(
+                        Arg::new("message")
+                            .long("message")
+                            .short('m')
+                            .help("Commit message. Defaults to the url that was imported"),
+                    )
+                    .arg(
+                        Arg::new("region")
+                            .long("region")
+                            .help("Region of the bucket. Defaults to AWS_REGION or the aws profile"),
+                    )
+                    .arg(
+                        Arg::new("endpoint")
+                            .long("endpoint")
+                            .help("Endpoint of an S3 compatible store such as MinIO or R2"),
+                    ),
+            )
+    }
+
+    async fn run(&self, args: &ArgMatches) -> Result<(), OxenError> {
+        match args.subcommand() {
+            Some(("git", sub_matches)) => {
+                let src = sub_matches.get_one::<String>("REPO").expect("required");
+                let dst = sub_matches.get_one::<String>("DEST").map(PathBuf::from);
+                let lfs = sub_matches.get_flag("lfs");
+
+                let import = repositories::import::git(src, dst.as_deref(), lfs).await?;
+                println!(
+                    "Imported {} commits, {} branches and {} tags into {}",
+                    import.num_commits,
+                    import.branches.len(),
+                    import.tags.len(),
+                    import.path.display()
+                );
+                if import.num_lfs_files > 0 {
+                    println!("Imported {} files from git LFS", import.num_lfs_files);
+                }
+                if import.num_lfs_pointers > 0 {
+                    println!(
+                        "Kept {} git LFS pointer files, run again with --lfs to import their content",
+                        import.num_lfs_pointers
+                    );
+                }
+                if import.num_skipped > 0 {
+                    println!("Skipped {} symlinks and submodules", import.num_skipped);
+                }
+            }
+            Some(("s3", sub_matches)) => {
+                let url = sub_matches.get_one::<String>("URL").expect("required");
+                let dst = sub_matches.get_one::<String>("DEST").map(PathBuf::from);
+                let opts = S3ImportOpts {
+                    sync: sub_matches.get_flag("sync"),
+                    region: sub_matches.get_one::<String>("region").cloned(),
+                    endpoint: sub_matches.get_one::<String>("endpoint").cloned(),
+                    message: sub_matches.get_one::<String>("message").cloned(),
+                };
+
+                let import = repositories::import::s3(url, dst.as_deref(), &opts).await?;
+                match import.commit {
+                    Some(commit) => println!(
+                        "Added {}, updated {} and removed {} files ({}) in commit {} in {}",
+                        import.num_added,
+                        import.num_updated,
+                        import.num_removed,
+                        bytesize::ByteSize::b(import.num_bytes),
+                        commit.id,
+                        import.path.display()
+                    ),
+                    None => println!("{} is up to date with {url}", import.path.display()),
+                }
+            }
+            _ => unreachable!("import subcommand is required"),
+        }
+        Ok(())
+    }
+}
@@ -6,10 +6,17 @@ use std::fmt::Write;
 use time::format_description;
 
 use liboxen::error::OxenError;
-use liboxen::model::LocalRepository;
+use liboxen::model::{Commit, LocalRepository};
+use liboxen::opts::LogOpts;
 use liboxen::repositories;
+use liboxen::util;
+use liboxen::view::{ListCommitResponse, StatusMessage};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 
+use crate::cmd::commit::parse_metadata;
 use crate::cmd::RunCmd;
+use crate::helpers::print_json;
 pub const NAME: &str = "log";
 pub struct LogCmd;
 
@@ -42,9 +49,82 @@ impl RunCmd for LogCmd {
                     .help("Number of commits to show")
                     .default_value("20"),
             )
+            .arg(
+                Arg::new("meta")
+                    .long("meta")
+                    .help("Only show commits with this key=value metadata, such as --meta run_id=abc. Can be passed multiple times.")
+                    .action(clap::ArgAction::Append),
+            )
+            .arg(
+                Arg::new("grep")
+                    .long("grep")
+                    .help("Only show commits whose message or key=value metadata matches this case insensitive regex.")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("path")
+                    .long("path")
+                    .help("Only show commits that changed this file or directory.")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("author")
+                    .long("author")
+                    .help("Only show commits whose author name or email contains this, ignoring case.")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("since")
+                    .long("since")
+                    .help("Only show commits made at or after this date, such as 2024-05-01, 2024-05-01T12:00:00Z or \"2 weeks ago\".")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("until")
+                    .long("until")
+                    .help("Only show commits made at or before this date, in the same formats as --since.")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("format")
+                    .long("format")
+                    .value_parser(["text", "json"])
+                    .default_value("text")
+                    .help("Print the commits as text in a pager, or as a json array for scripts.")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("follow")
+                    .long("follow")
+                    .value_name("PATH")
+                    .help("Only show commits that changed this file, following it back through renames.")
+                    .action(clap::ArgAction::Set),
+            )
     }
 
     async fn run(&self, args: &ArgMatches) -> Result<(), OxenError> {
+        let commits = self.list_commits(args)?;
+        if args.get_one::<String>("format").map(String::as_str) == Some("json") {
+            let commits: Vec<&Commit> = commits.iter().map(|(commit, _)| commit).collect();
+            println!("{}", serde_json::to_string_pretty(&commits)?);
+            return Ok(());
+        }
+        self.print_commits(&commits)
+    }
+
+    async fn run_json(&self, args: &ArgMatches) -> Result<(), OxenError> {
+        let commits = self.list_commits(args)?;
+        print_json(&ListCommitResponse {
+            status: StatusMessage::resource_found(),
+            commits: commits.into_iter().map(|(commit, _)| commit).collect(),
+        })
+    }
+}
+
+impl LogCmd {
+    /// The commits matching the args, with the old path of the followed file in the
+    /// commits from before it was renamed
+    fn list_commits(&self, args: &ArgMatches) -> Result<Vec<(Commit, Option<PathBuf>)>, OxenError> {
         // Look up from the current dir for .oxen directory
         let repo = LocalRepository::from_current_dir()?;
 
@@ -53,27 +133,69 @@ impl RunCmd for LogCmd {
             .expect("Must supply number")
             .parse::<usize>()
             .expect("number must be a valid integer.");
-        let revision = args.get_one::<String>("revision").map(String::from);
-        self.log_commits(&repo, revision, num_commits).await?;
+        let current_dir = std::env::current_dir()?;
+        let repo_path =
+            |path: &String| util::fs::path_relative_to_dir(current_dir.join(path), &repo.path);
+        let parse_date = |name: &str| {
+            args.get_one::<String>(name)
+                .map(|date| LogOpts::parse_date(date))
+                .transpose()
+        };
+        let opts = LogOpts {
+            revision: args.get_one::<String>("revision").map(String::from),
+            path: args.get_one::<String>("path").map(repo_path).transpose()?,
+            author: args.get_one::<String>("author").map(String::from),
+            since: parse_date("since")?,
+            until: parse_date("until")?,
+            grep: args.get_one::<String>("grep").map(String::from),
+            metadata: parse_metadata(args)?,
+            limit: Some(num_commits),
+        };
 
-        Ok(())
+        match args.get_one::<String>("follow") {
+            Some(path) => self.list_follow(&repo, &opts, &repo_path(path)?),
+            None => Ok(repositories::commits::list_with_opts(&repo, &opts)?
+                .into_iter()
+                .map(|commit| (commit, None))
+                .collect()),
+        }
     }
-}
 
-impl LogCmd {
-    pub async fn log_commits(
+    /// The commits that changed a file, with the old path of the file in the commits from
+    /// before it was renamed. The other filters in the opts apply on top.
+    fn list_follow(
         &self,
         repo: &LocalRepository,
-        revision: Option<String>,
-        num_commits: usize,
-    ) -> Result<(), OxenError> {
-        let revision = match revision {
-            Some(revision) => revision,
+        opts: &LogOpts,
+        path: &Path,
+    ) -> Result<Vec<(Commit, Option<PathBuf>)>, OxenError> {
+        let revision = match &opts.revision {
+            Some(revision) => revision.clone(),
             None => repositories::commits::head_commit(repo)?.id,
         };
-        let commits = repositories::commits::list_from(repo, &revision)?;
-        let commits = commits.iter().take(num_commits);
+        let filter_opts = LogOpts {
+            path: None,
+            limit: None,
+            ..opts.clone()
+        };
+        let matching: HashSet<String> = repositories::commits::list_with_opts(repo, &filter_opts)?
+            .into_iter()
+            .map(|commit| commit.id)
+            .collect();
+        Ok(
+            repositories::commits::list_by_path_follow(repo, &revision, path)?
+                .into_iter()
+                .filter(|(commit, _)| matching.contains(&commit.id))
+                .take(opts.limit.unwrap_or(usize::MAX))
+                .map(|(commit, commit_path)| {
+                    let renamed_from = (commit_path != path).then_some(commit_path);
+                    (commit, renamed_from)
+                })
+                .collect(),
+        )
+    }
 
+    fn print_commits(&self, commits: &[(Commit, Option<PathBuf>)]) -> Result<(), OxenError> {
         // Fri, 21 Oct 2022 16:08:39 -0700
         let format = format_description::parse(
             "[weekday], [day] [month repr:long] [year] [hour]:[minute]:[second] [offset_hour sign:mandatory]",
@@ -81,10 +203,19 @@ impl LogCmd {
 
         let mut output = Pager::new();
 
-        for commit in commits {
+        for (commit, path) in commits {
             let commit_id_str = format!("commit {}", commit.id).yellow();
             write_to_pager(&mut output, &format!("{}\n", commit_id_str))?;
             write_to_pager(&mut output, &format!("Author: {}", commit.author))?;
+            for co_author in commit.co_authors.iter() {
+                write_to_pager(&mut output, &format!("Co-author: {}", co_author))?;
+            }
+            for (key, value) in commit.metadata.iter() {
+                write_to_pager(&mut output, &format!("Meta:   {}={}", key, value))?;
+            }
+            if let Some(path) = path {
+                write_to_pager(&mut output, &format!("Path:   {}", path.display()))?;
+            }
             write_to_pager(
                 &mut output,
                 &format!("Date:   {}\n", commit.timestamp.format(&format).unwrap()),
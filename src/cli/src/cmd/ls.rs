@@ -0,0 +1,149 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use bytesize::ByteSize;
+use clap::{Arg, ArgMatches, Command};
+
+use liboxen::api;
+use liboxen::constants::{DEFAULT_BRANCH_NAME, DEFAULT_PAGE_NUM, DEFAULT_PAGE_SIZE};
+use liboxen::error::OxenError;
+use liboxen::model::{LocalRepository, MetadataFilter};
+use liboxen::opts::PaginateOpts;
+use liboxen::repositories;
+use liboxen::util;
+use liboxen::view::PaginatedDirEntries;
+
+use crate::cmd::RunCmd;
+use crate::helpers::check_repo_migration_needed;
+
+pub const NAME: &str = "ls";
+
+pub struct LsCmd;
+
+#[async_trait]
+impl RunCmd for LsCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        Command::new(NAME)
+            .about("List the files in a directory at a revision, optionally filtered by their metadata. Ex: oxen ls images --filter \"image.width>1024\"")
+            .arg(Arg::new("path").help("The directory to list. Defaults to the root of the repository"))
+            .arg(
+                Arg::new("revision")
+                    .long("revision")
+                    .short('r')
+                    .help("The branch or commit to list. Defaults to HEAD, or the current branch with --remote"),
+            )
+            .arg(
+                Arg::new("filter")
+                    .long("filter")
+                    .short('f')
+                    .help("Only list entries whose metadata matches, ie: data_type=video or image.width>1024. Can be repeated or comma separated, every condition must match")
+                    .action(clap::ArgAction::Append),
+            )
+            .arg(
+                Arg::new("remote")
+                    .long("remote")
+                    .help("List the directory on the remote instead of the local repository")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("page")
+                    .long("page")
+                    .short('p')
+                    .value_parser(clap::value_parser!(usize))
+                    .help("The page of entries to list"),
+            )
+            .arg(
+                Arg::new("page_size")
+                    .long("page-size")
+                    .value_parser(clap::value_parser!(usize))
+                    .help("The number of entries per page"),
+            )
+    }
+
+    async fn run(&self, args: &ArgMatches) -> Result<(), OxenError> {
+        let repo = LocalRepository::from_current_dir()?;
+        check_repo_migration_needed(&repo)?;
+
+        let path = match args.get_one::<String>("path") {
+            Some(path) => {
+                let current_dir = std::env::current_dir()?;
+                util::fs::path_relative_to_dir(current_dir.join(path), &repo.path)?
+            }
+            None => PathBuf::from(""),
+        };
+        let filters: Vec<&str> = args
+            .get_many::<String>("filter")
+            .into_iter()
+            .flatten()
+            .flat_map(|filter| filter.split(','))
+            .collect();
+        let filter = MetadataFilter::from_conditions(filters)?;
+        let page = *args.get_one::<usize>("page").unwrap_or(&DEFAULT_PAGE_NUM);
+        let page_size = *args
+            .get_one::<usize>("page_size")
+            .unwrap_or(&DEFAULT_PAGE_SIZE);
+
+        let entries = if args.get_flag("remote") {
+            let remote_repo = api::client::repositories::get_default_remote(&repo).await?;
+            let revision = match args.get_one::<String>("revision") {
+                Some(revision) => revision.to_string(),
+                None => repositories::branches::current_branch(&repo)?
+                    .map(|branch| branch.name)
+                    .unwrap_or(DEFAULT_BRANCH_NAME.to_string()),
+            };
+            api::client::dir::list_with_filter(
+                &remote_repo,
+                &revision,
+                &path,
+                &filter,
+                page,
+                page_size,
+            )
+            .await?
+        } else {
+            let default_revision = String::from("HEAD");
+            let revision = args
+                .get_one::<String>("revision")
+                .unwrap_or(&default_revision);
+            repositories::entries::list_directory_w_filter(
+                &repo,
+                &path,
+                revision,
+                &filter,
+                &PaginateOpts {
+                    page_num: page,
+                    page_size,
+                },
+            )?
+        };
+
+        print_entries(&entries);
+        Ok(())
+    }
+}
+
+fn print_entries(entries: &PaginatedDirEntries) {
+    for entry in entries.entries.iter() {
+        let name = if entry.is_dir() {
+            format!("{}/", entry.filename())
+        } else {
+            entry.filename().to_string()
+        };
+        println!(
+            "{}\t{}\t{}",
+            entry.data_type(),
+            ByteSize::b(entry.size()),
+            name
+        );
+    }
+    if entries.total_pages > 1 {
+        println!(
+            "Page {} of {}, {} entries",
+            entries.page_number, entries.total_pages, entries.total_entries
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use async_trait::async_trait;
-use clap::{arg, Command};
+use clap::{arg, Arg, Command};
+use liboxen::config::MergeStrategy;
 use liboxen::error::OxenError;
 use liboxen::model::LocalRepository;
 
@@ -22,6 +23,13 @@ impl RunCmd for MergeCmd {
             .about("Merges a branch into the current checked out branch.")
             .arg_required_else_help(true)
             .arg(arg!(<BRANCH> "The name of the branch you want to merge in."))
+            .arg(
+                Arg::new("strategy")
+                    .long("strategy")
+                    .help("Resolve conflicting files by keeping ours, taking theirs, or keeping the rows or lines from both with union.")
+                    .value_parser(["ours", "theirs", "union"])
+                    .action(clap::ArgAction::Set),
+            )
     }
 
     async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
@@ -29,6 +37,10 @@ impl RunCmd for MergeCmd {
         let branch = args
             .get_one::<String>("BRANCH")
             .expect("Must supply a branch");
+        let strategy = args
+            .get_one::<String>("strategy")
+            .map(|s| s.parse::<MergeStrategy>())
+            .transpose()?;
 
         let repository = LocalRepository::from_current_dir()?;
 
@@ -56,7 +68,7 @@ impl RunCmd for MergeCmd {
 
         check_repo_migration_needed(&repository)?;
 
-        repositories::merge::merge(&repository, branch).await?;
+        repositories::merge::merge_with_strategy(&repository, branch, strategy).await?;
         Ok(())
     }
 }
@@ -1,7 +1,10 @@
 use std::path::Path;
+use std::str::FromStr;
 
 use async_trait::async_trait;
 use clap::{Arg, Command};
+use liboxen::command::migrate::{hash, repo, vnode_size};
+use liboxen::util::hasher::HashAlgorithm;
 use liboxen::{error::OxenError, model::LocalRepository};
 
 use crate::cmd::RunCmd;
@@ -53,12 +56,81 @@ impl RunCmd for MigrateCmd {
             .subcommand_required(true)
             .subcommand(subcommands("up", "Apply a named migration forward."))
             .subcommand(subcommands("down", "Apply a named migration backward."))
+            .subcommand(
+                migrate_args(
+                    "vnode-size",
+                    "Change the vnode size used when future commits rewrite the merkle tree.",
+                )
+                .arg(
+                    Arg::new("size")
+                        .long("size")
+                        .short('s')
+                        .help("Maximum number of entries per vnode")
+                        .value_parser(clap::value_parser!(u64))
+                        .required(true),
+                ),
+            )
+            .subcommand(
+                migrate_args(
+                    "hash",
+                    "Rehash all content and rebuild the merkle trees with a new hash algorithm.",
+                )
+                .arg(
+                    Arg::new("algorithm")
+                        .long("algorithm")
+                        .help("Hash algorithm to migrate to")
+                        .value_parser(["xxh3", "sha256"])
+                        .required(true),
+                ),
+            )
+            .subcommand(
+                migrate_args(
+                    "repo",
+                    "Upgrade the on-disk format of a repository to the latest version.",
+                )
+                .arg(
+                    Arg::new("dry-run")
+                        .long("dry-run")
+                        .help("Print the upgrade steps without modifying the repository")
+                        .action(clap::ArgAction::SetTrue),
+                ),
+            )
     }
 
     async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
         // Parse Args
         let migrations = migrations();
 
+        if let Some(("vnode-size", sub_matches)) = args.subcommand() {
+            let path_str = sub_matches.get_one::<String>("PATH").expect("required");
+            let size = *sub_matches.get_one::<u64>("size").expect("required");
+            let all = sub_matches.get_flag("all");
+            vnode_size::set_vnode_size(Path::new(path_str), size, all)?;
+            println!(
+                "Set vnode size to {size}, directories will use it the next time they are committed"
+            );
+            return Ok(());
+        }
+
+        if let Some(("hash", sub_matches)) = args.subcommand() {
+            let path_str = sub_matches.get_one::<String>("PATH").expect("required");
+            let algorithm = sub_matches
+                .get_one::<String>("algorithm")
+                .expect("required");
+            let algorithm = HashAlgorithm::from_str(algorithm)?;
+            let all = sub_matches.get_flag("all");
+            hash::rehash(Path::new(path_str), algorithm, all).await?;
+            return Ok(());
+        }
+
+        if let Some(("repo", sub_matches)) = args.subcommand() {
+            let path_str = sub_matches.get_one::<String>("PATH").expect("required");
+            let all = sub_matches.get_flag("all");
+            let dry_run = sub_matches.get_flag("dry-run");
+            repo::upgrade(Path::new(path_str), all, dry_run)?;
+            return Ok(());
+        }
+
         if let Some((direction, sub_matches)) = args.subcommand() {
             if let Some((migration, sub_matches)) = sub_matches.subcommand() {
                 let migration = migrations
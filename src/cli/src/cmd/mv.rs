@@ -0,0 +1,54 @@
+use async_trait::async_trait;
+use clap::{Arg, ArgMatches, Command};
+
+use crate::helpers::check_repo_migration_needed;
+
+use liboxen::error::OxenError;
+use liboxen::model::LocalRepository;
+use liboxen::repositories;
+
+use crate::cmd::RunCmd;
+pub const NAME: &str = "mv";
+pub struct MvCmd;
+
+#[async_trait]
+impl RunCmd for MvCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        // Setups the CLI args for the command
+        Command::new(NAME)
+            .about("Move or rename a file or directory and stage the move")
+            .arg(
+                Arg::new("source")
+                    .required(true)
+                    .help("The tracked file or directory to move"),
+            )
+            .arg(
+                Arg::new("destination")
+                    .required(true)
+                    .help("The new path, or an existing directory to move the source into"),
+            )
+    }
+
+    async fn run(&self, args: &ArgMatches) -> Result<(), OxenError> {
+        let src = args
+            .get_one::<String>("source")
+            .expect("Must supply source");
+        let dst = args
+            .get_one::<String>("destination")
+            .expect("Must supply destination");
+        let current_dir = std::env::current_dir()?;
+
+        let repository = LocalRepository::from_current_dir()?;
+        check_repo_migration_needed(&repository)?;
+
+        let moved_to =
+            repositories::mv(&repository, current_dir.join(src), current_dir.join(dst)).await?;
+        println!("Moved {src} to {}", moved_to.display());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,218 @@
+use async_trait::async_trait;
+use clap::{Arg, ArgMatches, Command};
+
+use liboxen::api;
+use liboxen::config::UserConfig;
+use liboxen::constants::{DEFAULT_BRANCH_NAME, DEFAULT_REMOTE_NAME};
+use liboxen::error::OxenError;
+use liboxen::model::{LocalRepository, MergeProposalStatus, RemoteRepository};
+use liboxen::repositories;
+use liboxen::view::{MergeProposalApprovalNew, MergeProposalNew};
+
+use crate::cmd::RunCmd;
+
+pub const NAME: &str = "pr";
+pub struct PrCmd;
+
+#[async_trait]
+impl RunCmd for PrCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        // Setups the CLI args for the command
+        let remote_arg = Arg::new("remote")
+            .long("remote")
+            .help("Name of the remote. Defaults to the current remote");
+        Command::new(NAME)
+            .about("Propose merging a branch on the remote, and merge it on the server once it is reviewed")
+            .subcommand_required(true)
+            .subcommand(
+                Command::new("create")
+                    .about("Propose merging a branch into another")
+                    .arg(
+                        Arg::new("title")
+                            .long("title")
+                            .short('t')
+                            .help("Title of the proposal")
+                            .required(true),
+                    )
+                    .arg(
+                        Arg::new("description")
+                            .long("description")
+                            .short('d')
+                            .help("Longer description of the changes"),
+                    )
+                    .arg(
+                        Arg::new("base")
+                            .long("base")
+                            .short('b')
+                            .help("Branch to merge into")
+                            .default_value(DEFAULT_BRANCH_NAME),
+                    )
+                    .arg(
+                        Arg::new("head")
+                            .long("head")
+                            .help("Branch with the changes. Defaults to the current branch"),
+                    )
+                    .arg(remote_arg.clone()),
+            )
+            .subcommand(
+                Command::new("list")
+                    .about("List the proposals, newest first")
+                    .arg(
+                        Arg::new("status")
+                            .long("status")
+                            .short('s')
+                            .help("Only list the proposals that are open, merged or closed")
+                            .value_parser(["open", "merged", "closed"]),
+                    )
+                    .arg(remote_arg.clone()),
+            )
+            .subcommand(
+                Command::new("approve")
+                    .about("Approve a proposal as its head branch is now. Owners of the paths in the server's .oxen/owners file have to approve changes to them.")
+                    .arg(Arg::new("ID").help("Id of the proposal").required(true))
+                    .arg(remote_arg.clone()),
+            )
+            .subcommand(
+                Command::new("merge")
+                    .about("Merge a proposal on the server")
+                    .arg(Arg::new("ID").help("Id of the proposal").required(true))
+                    .arg(remote_arg),
+            )
+    }
+
+    async fn run(&self, args: &ArgMatches) -> Result<(), OxenError> {
+        match args.subcommand() {
+            Some(("create", sub_matches)) => {
+                let repo = LocalRepository::from_current_dir()?;
+                let remote_repo = get_remote_repo(&repo, sub_matches).await?;
+                let head = match sub_matches.get_one::<String>("head") {
+                    Some(head) => head.to_string(),
+                    None => {
+                        repositories::branches::current_branch(&repo)?
+                            .ok_or(OxenError::must_be_on_valid_branch())?
+                            .name
+                    }
+                };
+                let proposal = api::client::proposals::create(
+                    &remote_repo,
+                    &MergeProposalNew {
+                        title: sub_matches
+                            .get_one::<String>("title")
+                            .expect("required")
+                            .to_string(),
+                        description: sub_matches
+                            .get_one::<String>("description")
+                            .cloned()
+                            .unwrap_or_default(),
+                        base: sub_matches
+                            .get_one::<String>("base")
+                            .expect("has default")
+                            .to_string(),
+                        head,
+                        author: UserConfig::get()?.to_user(),
+                    },
+                )
+                .await?;
+                println!(
+                    "Created proposal #{} to merge {} into {}",
+                    proposal.id, proposal.head, proposal.base
+                );
+            }
+            Some(("list", sub_matches)) => {
+                let repo = LocalRepository::from_current_dir()?;
+                let remote_repo = get_remote_repo(&repo, sub_matches).await?;
+                let status = match sub_matches.get_one::<String>("status") {
+                    Some(status) => Some(status.parse::<MergeProposalStatus>()?),
+                    None => None,
+                };
+                for proposal in api::client::proposals::list(&remote_repo, status).await? {
+                    println!(
+                        "#{}\t{}\t{} -> {}\t{}",
+                        proposal.id, proposal.status, proposal.head, proposal.base, proposal.title
+                    );
+                }
+            }
+            Some(("approve", sub_matches)) => {
+                let repo = LocalRepository::from_current_dir()?;
+                let remote_repo = get_remote_repo(&repo, sub_matches).await?;
+                let id = parse_id(sub_matches)?;
+                let approval = MergeProposalApprovalNew {
+                    author: UserConfig::get()?.to_user(),
+                };
+                api::client::proposals::approve(&remote_repo, id, &approval).await?;
+                println!("Approved proposal #{id}");
+            }
+            Some(("merge", sub_matches)) => {
+                let repo = LocalRepository::from_current_dir()?;
+                let remote_repo = get_remote_repo(&repo, sub_matches).await?;
+                let id = parse_id(sub_matches)?;
+
+                let checks = api::client::proposals::mergeable(&remote_repo, id).await?;
+                if !checks.conflicts.is_empty() {
+                    for conflict in &checks.conflicts {
+                        eprintln!("conflict: {}", conflict.path.display());
+                    }
+                    return Err(OxenError::basic_str(format!(
+                        "Proposal #{id} has conflicts, merge the base branch into the head branch and resolve them first"
+                    )));
+                }
+                let waiting: Vec<_> = checks
+                    .required_approvals
+                    .iter()
+                    .filter(|approval| !approval.is_approved())
+                    .collect();
+                if !waiting.is_empty() {
+                    for approval in waiting {
+                        eprintln!(
+                            "needs approval: {} ({} files) from {}",
+                            approval.pattern,
+                            approval.paths.len(),
+                            approval.owners.join(", ")
+                        );
+                    }
+                    return Err(OxenError::basic_str(format!(
+                        "Proposal #{id} changes owned paths, an owner has to run `oxen pr approve {id}` first"
+                    )));
+                }
+
+                let proposal = api::client::proposals::merge(&remote_repo, id).await?;
+                println!(
+                    "Merged proposal #{} into {} at commit {}",
+                    proposal.id,
+                    proposal.base,
+                    proposal.merge_commit_id.unwrap_or_default()
+                );
+            }
+            _ => unreachable!("pr subcommand is required"),
+        }
+        Ok(())
+    }
+}
+
+fn parse_id(args: &ArgMatches) -> Result<u64, OxenError> {
+    let id = args.get_one::<String>("ID").expect("required");
+    id.trim_start_matches('#')
+        .parse::<u64>()
+        .map_err(|_| OxenError::basic_str(format!("Invalid proposal id {id:?}")))
+}
+
+async fn get_remote_repo(
+    repo: &LocalRepository,
+    args: &ArgMatches,
+) -> Result<RemoteRepository, OxenError> {
+    let name = args.get_one::<String>("remote");
+    let remote = match name {
+        Some(name) => repo.get_remote(name),
+        None => repo.remote(),
+    }
+    .ok_or(OxenError::remote_not_set(
+        name.map(String::as_str).unwrap_or(DEFAULT_REMOTE_NAME),
+    ))?;
+    api::client::repositories::get_by_remote(&remote)
+        .await?
+        .ok_or(OxenError::remote_repo_not_found(&remote.url))
+}
@@ -40,6 +40,26 @@ impl RunCmd for PullCmd {
                     .help("This pulls the full commit history, all the data files, and all the commit databases. Useful if you want to have the entire history locally or push to a new remote.")
                     .action(clap::ArgAction::SetTrue),
             )
+            .arg(
+                Arg::new("unshallow")
+                    .long("unshallow")
+                    .help("Fetch the commit history that a clone with --history-depth left out.")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("json")
+                    .long("json")
+                    .help("Print progress as json lines with files, bytes, throughput and ETA instead of a progress bar")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("jobs")
+                    .long("jobs")
+                    .short('j')
+                    .help("Number of connections to download files over at once. Defaults to remote.download_concurrency in the repo config, or the number of CPUs.")
+                    .value_parser(clap::value_parser!(usize))
+                    .action(clap::ArgAction::Set),
+            )
     }
 
     async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
@@ -60,6 +80,10 @@ impl RunCmd for PullCmd {
         };
 
         let all = args.get_flag("all");
+        let jobs = args.get_one::<usize>("jobs").copied();
+        if jobs == Some(0) {
+            return Err(OxenError::basic_str("--jobs must be at least 1"));
+        }
         let (scheme, host) = get_scheme_and_host_from_repo(&repo)?;
 
         check_repo_migration_needed(&repo)?;
@@ -72,6 +96,9 @@ impl RunCmd for PullCmd {
         fetch_opts.depth = repo.depth();
         fetch_opts.subtree_paths = repo.subtree_paths();
         fetch_opts.all = all;
+        fetch_opts.unshallow = args.get_flag("unshallow");
+        fetch_opts.json_progress = args.get_flag("json");
+        fetch_opts.jobs = jobs;
         repositories::pull_remote_branch(&repo, &fetch_opts).await?;
         Ok(())
     }
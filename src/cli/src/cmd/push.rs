@@ -39,6 +39,13 @@ impl RunCmd for PushCmd {
                     .help("Remove the remote branch")
                     .action(clap::ArgAction::SetTrue),
             )
+            .arg(
+                Arg::new("tags")
+                    .long("tags")
+                    .help("Push the tags instead of a branch, the tagged commits must already be on the remote")
+                    .conflicts_with("delete")
+                    .action(clap::ArgAction::SetTrue),
+            )
     }
 
     async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
@@ -48,6 +55,21 @@ impl RunCmd for PushCmd {
             .expect("Must supply a remote");
 
         let repo = LocalRepository::from_current_dir()?;
+
+        if args.get_flag("tags") {
+            let (scheme, host) = get_scheme_and_host_from_repo(&repo)?;
+            check_remote_version(scheme, host).await?;
+
+            let tags = repositories::tags::push(&repo, remote).await?;
+            for tag in &tags {
+                println!("Pushed tag {} ({})", tag.name, tag.commit_id);
+            }
+            if tags.is_empty() {
+                println!("Everything up-to-date");
+            }
+            return Ok(());
+        }
+
         let current_branch = repositories::branches::current_branch(&repo)?;
 
         // Default to CURRENT branch
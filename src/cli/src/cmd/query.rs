@@ -0,0 +1,107 @@
+use async_trait::async_trait;
+use clap::{arg, Arg, Command};
+use std::path::Path;
+
+use liboxen::api;
+use liboxen::constants::{DEFAULT_BRANCH_NAME, DEFAULT_HOST, DEFAULT_SCHEME};
+use liboxen::core::df::{pretty_print, tabular};
+use liboxen::error::OxenError;
+
+use crate::cmd::RunCmd;
+use crate::helpers::{check_remote_version_blocking, get_output_path};
+
+pub const NAME: &str = "query";
+pub struct QueryCmd;
+
+#[async_trait]
+impl RunCmd for QueryCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        // Setups the CLI args for the command
+        Command::new(NAME)
+            .about("Run a SQL query against a data frame in a remote repository without downloading it")
+            .arg(arg!(<ID> "ID of the repository you want to query ie. ox/my-repo"))
+            .arg(
+                Arg::new("sql")
+                    .long("sql")
+                    .help("The query, with the data frame named by its path ie. \"SELECT label, count(*) FROM 'train.csv' GROUP BY label\"")
+                    .required(true)
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("revision")
+                    .long("revision")
+                    .help("The branch or commit id to query. Defaults to main branch.")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("output")
+                    .long("output")
+                    .short('o')
+                    .help("Save the result to a file instead of printing it, the format is taken from the extension")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("host")
+                    .long("host")
+                    .help("The host of the repository you want to query. Defaults to hub.oxen.ai")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("scheme")
+                    .long("scheme")
+                    .help("The scheme of the repository you want to query. Defaults to https")
+                    .value_parser(["http", "https"])
+                    .action(clap::ArgAction::Set),
+            )
+    }
+
+    async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        // Parse Args
+        let id = args
+            .get_one::<String>("ID")
+            .expect("Must supply a repository id");
+        // Check that the id format is namespace/repo-name
+        if id.chars().filter(|&c| c == '/').count() != 1 {
+            return Err(OxenError::basic_str(
+                "Invalid repository ID format. Must be namespace/repo-name",
+            ));
+        }
+        let sql = args.get_one::<String>("sql").expect("required");
+        let revision = args
+            .get_one::<String>("revision")
+            .map(String::from)
+            .unwrap_or(DEFAULT_BRANCH_NAME.to_string());
+        let host = args
+            .get_one::<String>("host")
+            .map(String::from)
+            .unwrap_or(DEFAULT_HOST.to_string());
+        let scheme = args
+            .get_one::<String>("scheme")
+            .map(String::from)
+            .unwrap_or(DEFAULT_SCHEME.to_string());
+
+        check_remote_version_blocking(scheme.clone(), host.clone()).await?;
+
+        let Some(remote_repo) =
+            api::client::repositories::get_by_name_host_and_scheme(id, &host, &scheme).await?
+        else {
+            return Err(OxenError::basic_str(format!(
+                "Repository does not exist {id}"
+            )));
+        };
+
+        let mut df = api::client::data_frames::query(&remote_repo, &revision, sql).await?;
+        match get_output_path(args) {
+            Some(output) => {
+                tabular::write_df(&mut df, Path::new(output))?;
+                println!("Saved {} rows to {output}", df.height());
+            }
+            None => println!("{}", pretty_print::df_to_str(&df)),
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,94 @@
+use async_trait::async_trait;
+use clap::{Arg, Command};
+use colored::Colorize;
+use std::collections::HashMap;
+use time::format_description;
+
+use liboxen::error::OxenError;
+use liboxen::model::LocalRepository;
+use liboxen::repositories;
+
+use crate::cmd::RunCmd;
+
+pub const NAME: &str = "reflog";
+pub struct ReflogCmd;
+
+#[async_trait]
+impl RunCmd for ReflogCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        // Setups the CLI args for the command
+        Command::new(NAME)
+            .about("Show every move of HEAD and the branches, and restore a branch to a previous entry")
+            .arg(Arg::new("ref").help("Only show the entries for this branch, or HEAD"))
+            .arg(
+                Arg::new("orphaned")
+                    .long("orphaned")
+                    .help("List the commits still in the object store that no branch or tag leads to. Recover them with `oxen branch --recover`.")
+                    .conflicts_with("ref")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .args_conflicts_with_subcommands(true)
+            .subcommand(
+                Command::new("restore")
+                    .about("Point a branch back at the commit from one of its reflog entries, recreating it if it was deleted")
+                    .arg(Arg::new("branch").help("Name of the branch to restore").required(true))
+                    .arg(
+                        Arg::new("entry")
+                            .help("Reflog entry to restore, N in branch@{N}")
+                            .value_parser(clap::value_parser!(usize))
+                            .required(true),
+                    ),
+            )
+    }
+
+    async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        let repo = LocalRepository::from_current_dir()?;
+
+        if let Some(("restore", sub_matches)) = args.subcommand() {
+            let branch = sub_matches.get_one::<String>("branch").expect("required");
+            let entry = *sub_matches.get_one::<usize>("entry").expect("required");
+            let commit = repositories::reflog::restore_branch(&repo, branch, entry).await?;
+            println!("Restored {branch} to {} {}", commit.id, commit.message);
+            return Ok(());
+        }
+
+        let format =
+            format_description::parse("[year]-[month]-[day] [hour]:[minute]:[second]").unwrap();
+        if args.get_flag("orphaned") {
+            for commit in repositories::reflog::list_orphaned(&repo)? {
+                println!(
+                    "{} {} ({})",
+                    commit.id.yellow(),
+                    commit.message,
+                    commit.timestamp.format(&format).unwrap()
+                );
+            }
+            return Ok(());
+        }
+
+        let entries = match args.get_one::<String>("ref") {
+            Some(ref_name) => repositories::reflog::list_for_ref(&repo, ref_name)?,
+            None => repositories::reflog::list(&repo)?,
+        };
+
+        let mut positions: HashMap<&str, usize> = HashMap::new();
+        for entry in entries.iter() {
+            let position = positions.entry(entry.ref_name.as_str()).or_default();
+            let commit_id = entry.new_id.as_deref().unwrap_or("(deleted)");
+            println!(
+                "{} {}@{{{}}}: {} ({})",
+                commit_id.yellow(),
+                entry.ref_name,
+                position,
+                entry.action,
+                entry.timestamp.format(&format).unwrap()
+            );
+            *position += 1;
+        }
+        Ok(())
+    }
+}
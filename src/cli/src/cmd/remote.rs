@@ -1,11 +1,21 @@
 use async_trait::async_trait;
+use bytesize::ByteSize;
 use clap::{Arg, ArgMatches, Command};
 
+use liboxen::api;
+use liboxen::constants::DEFAULT_REMOTE_NAME;
 use liboxen::error::OxenError;
 use liboxen::model::LocalRepository;
+use liboxen::repositories;
+use liboxen::view::{ListRemotesResponse, RemoteBranchStatus, RemoteShowResponse, StatusMessage};
 
 use crate::cmd::RunCmd;
+use crate::helpers::print_json;
 pub const NAME: &str = "remote";
+
+pub mod webhook;
+pub use webhook::RemoteWebhookCmd;
+
 pub struct RemoteCmd;
 
 #[async_trait]
@@ -15,16 +25,36 @@ impl RunCmd for RemoteCmd {
     }
 
     fn args(&self) -> Command {
-        Command::new(NAME).about("List oxen remotes.").arg(
-            Arg::new("verbose")
-                .long("verbose")
-                .short('v')
-                .help("Verbose output")
-                .action(clap::ArgAction::SetTrue),
-        )
+        Command::new(NAME)
+            .about("List oxen remotes.")
+            .arg(
+                Arg::new("verbose")
+                    .long("verbose")
+                    .short('v')
+                    .help("Verbose output")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .subcommand(
+                Command::new("show")
+                    .about(
+                        "Query a remote for its branches, HEAD, server version, storage and size",
+                    )
+                    .arg(
+                        Arg::new("name").help("Name of the remote. Defaults to the current remote"),
+                    ),
+            )
+            .subcommand(RemoteWebhookCmd.args())
     }
 
     async fn run(&self, args: &ArgMatches) -> Result<(), OxenError> {
+        if let Some(("show", sub_matches)) = args.subcommand() {
+            let name = sub_matches.get_one::<String>("name");
+            return self.show_remote(name).await;
+        }
+        if let Some((webhook::NAME, sub_matches)) = args.subcommand() {
+            return RemoteWebhookCmd.run(sub_matches).await;
+        }
+
         let verbose = args.get_flag("verbose");
         if verbose {
             self.list_remotes_verbose()?;
@@ -34,6 +64,23 @@ impl RunCmd for RemoteCmd {
 
         Ok(())
     }
+
+    async fn run_json(&self, args: &ArgMatches) -> Result<(), OxenError> {
+        match args.subcommand() {
+            Some(("show", sub_matches)) => {
+                let name = sub_matches.get_one::<String>("name");
+                print_json(&self.get_remote_show(name).await?)
+            }
+            Some((webhook::NAME, sub_matches)) => RemoteWebhookCmd.run_json(sub_matches).await,
+            _ => {
+                let repo = LocalRepository::from_current_dir()?;
+                print_json(&ListRemotesResponse {
+                    status: StatusMessage::resource_found(),
+                    remotes: repo.remotes().clone(),
+                })
+            }
+        }
+    }
 }
 
 impl RemoteCmd {
@@ -56,4 +103,118 @@ impl RemoteCmd {
 
         Ok(())
     }
+
+    pub async fn get_remote_show(
+        &self,
+        name: Option<&String>,
+    ) -> Result<RemoteShowResponse, OxenError> {
+        let repo = LocalRepository::from_current_dir()?;
+        let remote = match name {
+            Some(name) => repo.get_remote(name),
+            None => repo.remote(),
+        }
+        .ok_or(OxenError::remote_not_set(
+            name.map(String::as_str).unwrap_or(DEFAULT_REMOTE_NAME),
+        ))?;
+
+        let (scheme, host) = api::client::get_scheme_and_host_from_url(&remote.url)?;
+        let server_version =
+            match api::client::oxen_version::get_remote_version(&scheme, &host).await {
+                Ok(version) => Some(version),
+                Err(err) => {
+                    log::debug!("Could not get the version of {}: {}", remote.url, err);
+                    None
+                }
+            };
+
+        let mut response = RemoteShowResponse {
+            status: StatusMessage::resource_found(),
+            remote: remote.clone(),
+            server_version,
+            repository: None,
+            head_commit: None,
+            branches: vec![],
+        };
+        let Some(remote_repo) = api::client::repositories::get_by_remote(&remote).await? else {
+            return Ok(response);
+        };
+
+        response.repository = api::client::repositories::get_repo_data_by_remote(&remote).await?;
+        let branches = api::client::branches::list(&remote_repo).await?;
+        // Servers older than `remote show` don't report their HEAD
+        let head = response
+            .repository
+            .as_ref()
+            .and_then(|data| data.head.as_ref());
+        if let Some(branch) = head.and_then(|head| branches.iter().find(|b| &b.name == head)) {
+            response.head_commit =
+                api::client::commits::get_by_id(&remote_repo, &branch.commit_id).await?;
+        }
+
+        for branch in branches {
+            let local_status = match repositories::branches::get_by_name(&repo, &branch.name)? {
+                Some(local) if local.commit_id == branch.commit_id => "up to date",
+                Some(_) => "differs from local",
+                None => "not tracked locally",
+            };
+            response.branches.push(RemoteBranchStatus {
+                name: branch.name,
+                commit_id: branch.commit_id,
+                local_status: local_status.to_string(),
+            });
+        }
+
+        Ok(response)
+    }
+
+    pub async fn show_remote(&self, name: Option<&String>) -> Result<(), OxenError> {
+        let response = self.get_remote_show(name).await?;
+
+        println!("remote: {}", response.remote.name);
+        println!("url:    {}", response.remote.url);
+        println!(
+            "server version: {}",
+            response.server_version.as_deref().unwrap_or("unknown")
+        );
+
+        let Some(data) = &response.repository else {
+            println!("\nrepository does not exist on the remote");
+            return Ok(());
+        };
+        println!(
+            "storage backend: {}",
+            data.storage_backend.as_deref().unwrap_or("unknown")
+        );
+        println!(
+            "size: {} in {} files",
+            ByteSize::b(data.size),
+            data.total_files()
+        );
+        if let Some(min_version) = &data.min_version {
+            println!("repository version: {min_version}");
+        }
+
+        match &data.head {
+            Some(head) => match response.branches.iter().find(|b| &b.name == head) {
+                Some(branch) => match &response.head_commit {
+                    Some(commit) => {
+                        println!("HEAD: {} -> {} {}", branch.name, commit.id, commit.message)
+                    }
+                    None => println!("HEAD: {} -> {}", branch.name, branch.commit_id),
+                },
+                None => println!("HEAD: {head} (no commits yet)"),
+            },
+            None => println!("HEAD: unknown"),
+        }
+
+        println!("\nremote branches:");
+        for branch in response.branches.iter() {
+            println!(
+                "  {}\t{}\t({})",
+                branch.name, branch.commit_id, branch.local_status
+            );
+        }
+
+        Ok(())
+    }
 }
@@ -0,0 +1,145 @@
+use async_trait::async_trait;
+use clap::{Arg, ArgMatches, Command};
+
+use liboxen::api;
+use liboxen::constants::DEFAULT_REMOTE_NAME;
+use liboxen::error::OxenError;
+use liboxen::model::{LocalRepository, RemoteRepository, Webhook, WebhookEvent};
+use liboxen::view::{ListWebhooksResponse, StatusMessage, WebhookNew, WebhookResponse};
+
+use crate::cmd::RunCmd;
+use crate::helpers::print_json;
+
+pub const NAME: &str = "webhook";
+pub struct RemoteWebhookCmd;
+
+#[async_trait]
+impl RunCmd for RemoteWebhookCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        // Setups the CLI args for the command
+        let remote_arg = Arg::new("remote")
+            .long("remote")
+            .help("Name of the remote. Defaults to the current remote");
+        Command::new(NAME)
+            .about("Manage the HTTP callbacks the remote calls on push, new branches, new tags and workspace commits")
+            .subcommand_required(true)
+            .subcommand(
+                Command::new("add")
+                    .about("Register a webhook")
+                    .arg(Arg::new("URL").help("URL to POST the events to").required(true))
+                    .arg(
+                        Arg::new("event")
+                            .long("event")
+                            .short('e')
+                            .help("Event to send: push, branch_created, tag_created or workspace_commit. Can be repeated, defaults to all of them.")
+                            .action(clap::ArgAction::Append),
+                    )
+                    .arg(
+                        Arg::new("secret")
+                            .long("secret")
+                            .help("Sign the payloads with this secret, the HMAC-SHA256 is sent in the X-Oxen-Signature header"),
+                    )
+                    .arg(remote_arg.clone()),
+            )
+            .subcommand(
+                Command::new("list")
+                    .about("List the webhooks")
+                    .arg(remote_arg.clone()),
+            )
+            .subcommand(
+                Command::new("remove")
+                    .about("Remove a webhook")
+                    .arg(Arg::new("ID").help("Id of the webhook").required(true))
+                    .arg(remote_arg),
+            )
+    }
+
+    async fn run(&self, args: &ArgMatches) -> Result<(), OxenError> {
+        match args.subcommand() {
+            Some(("add", sub_matches)) => {
+                let webhook = add_webhook(sub_matches).await?;
+                println!("Added webhook {}", webhook.id);
+            }
+            Some(("list", sub_matches)) => {
+                let remote_repo = get_remote_repo(sub_matches).await?;
+                for webhook in api::client::webhooks::list(&remote_repo).await? {
+                    let events: Vec<String> = webhook
+                        .events
+                        .iter()
+                        .map(|event| event.to_string())
+                        .collect();
+                    println!("{}\t{}\t{}", webhook.id, webhook.url, events.join(","));
+                }
+            }
+            Some(("remove", sub_matches)) => {
+                let remote_repo = get_remote_repo(sub_matches).await?;
+                let id = sub_matches.get_one::<String>("ID").expect("required");
+                api::client::webhooks::delete(&remote_repo, id).await?;
+                println!("Removed webhook {id}");
+            }
+            _ => unreachable!("webhook subcommand is required"),
+        }
+        Ok(())
+    }
+
+    async fn run_json(&self, args: &ArgMatches) -> Result<(), OxenError> {
+        match args.subcommand() {
+            Some(("add", sub_matches)) => print_json(&WebhookResponse {
+                status: StatusMessage::resource_created(),
+                webhook: add_webhook(sub_matches).await?,
+            }),
+            Some(("list", sub_matches)) => {
+                let remote_repo = get_remote_repo(sub_matches).await?;
+                print_json(&ListWebhooksResponse {
+                    status: StatusMessage::resource_found(),
+                    webhooks: api::client::webhooks::list(&remote_repo).await?,
+                })
+            }
+            Some(("remove", sub_matches)) => {
+                let remote_repo = get_remote_repo(sub_matches).await?;
+                let id = sub_matches.get_one::<String>("ID").expect("required");
+                api::client::webhooks::delete(&remote_repo, id).await?;
+                print_json(&StatusMessage::resource_deleted())
+            }
+            _ => unreachable!("webhook subcommand is required"),
+        }
+    }
+}
+
+async fn add_webhook(args: &ArgMatches) -> Result<Webhook, OxenError> {
+    let remote_repo = get_remote_repo(args).await?;
+    let url = args.get_one::<String>("URL").expect("required");
+    let events = args
+        .get_many::<String>("event")
+        .unwrap_or_default()
+        .map(|event| event.parse())
+        .collect::<Result<Vec<WebhookEvent>, OxenError>>()?;
+    api::client::webhooks::create(
+        &remote_repo,
+        &WebhookNew {
+            url: url.to_string(),
+            events,
+            secret: args.get_one::<String>("secret").cloned(),
+        },
+    )
+    .await
+}
+
+async fn get_remote_repo(args: &ArgMatches) -> Result<RemoteRepository, OxenError> {
+    let repo = LocalRepository::from_current_dir()?;
+    let name = args.get_one::<String>("remote");
+    let remote = match name {
+        Some(name) => repo.get_remote(name),
+        None => repo.remote(),
+    }
+    .ok_or(OxenError::remote_not_set(
+        name.map(String::as_str).unwrap_or(DEFAULT_REMOTE_NAME),
+    ))?;
+    api::client::repositories::get_by_remote(&remote)
+        .await?
+        .ok_or(OxenError::remote_repo_not_found(&remote.url))
+}
@@ -0,0 +1,127 @@
+use async_trait::async_trait;
+use clap::{Arg, ArgGroup, Command};
+use std::path::PathBuf;
+
+use dialoguer::Select;
+use liboxen::config::MergeStrategy;
+use liboxen::error::OxenError;
+use liboxen::model::LocalRepository;
+use liboxen::repositories;
+
+use crate::cmd::conflicts::ConflictsCmd;
+use crate::cmd::RunCmd;
+
+pub const NAME: &str = "resolve";
+pub struct ResolveCmd;
+
+#[async_trait]
+impl RunCmd for ResolveCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        Command::new(NAME)
+            .about("Resolve the conflicts left by a merge by picking a side for each file, or for each row of tabular files, and stage the result.")
+            .arg(Arg::new("path").help("Only resolve the conflicts in this file or directory"))
+            .arg(
+                Arg::new("ours")
+                    .long("ours")
+                    .help("Take our version of every conflicting file without asking")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("theirs")
+                    .long("theirs")
+                    .help("Take their version of every conflicting file without asking")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("union")
+                    .long("union")
+                    .help("Keep the lines or rows of both versions of every conflicting file without asking")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .group(ArgGroup::new("side").args(["ours", "theirs", "union"]))
+    }
+
+    async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        let repo = LocalRepository::from_current_dir()?;
+        let path = args.get_one::<String>("path").map(PathBuf::from);
+        let strategy = if args.get_flag("ours") {
+            Some(MergeStrategy::Ours)
+        } else if args.get_flag("theirs") {
+            Some(MergeStrategy::Theirs)
+        } else if args.get_flag("union") {
+            Some(MergeStrategy::Union)
+        } else {
+            None
+        };
+
+        let conflicts: Vec<_> = repositories::merge::list_conflicts(&repo)?
+            .into_iter()
+            .filter(|conflict| match &path {
+                Some(path) => conflict.base_entry.path.starts_with(path),
+                None => true,
+            })
+            .collect();
+        if conflicts.is_empty() {
+            println!("No merge conflicts.");
+            return Ok(());
+        }
+
+        let mut num_resolved = 0;
+        for (i, conflict) in conflicts.iter().enumerate() {
+            let conflict_path = &conflict.base_entry.path;
+            let strategy = match strategy {
+                Some(strategy) => strategy,
+                None => {
+                    println!(
+                        "{} ({}/{})",
+                        conflict_path.display(),
+                        i + 1,
+                        conflicts.len()
+                    );
+                    let mut items = vec!["ours", "theirs", "union"];
+                    if !conflict.row_conflicts.is_empty() {
+                        items.push("pick per row");
+                    }
+                    items.push("skip");
+
+                    let choice = Select::new()
+                        .with_prompt("Keep which version?")
+                        .items(&items)
+                        .default(0)
+                        .interact()
+                        .map_err(|e| OxenError::basic_str(format!("Error reading choice: {e}")))?;
+                    match items[choice] {
+                        "ours" => MergeStrategy::Ours,
+                        "theirs" => MergeStrategy::Theirs,
+                        "union" => MergeStrategy::Union,
+                        "pick per row" => {
+                            ConflictsCmd.resolve_rows(&repo, conflict).await?;
+                            num_resolved += 1;
+                            continue;
+                        }
+                        _ => continue,
+                    }
+                }
+            };
+
+            repositories::merge::resolve_conflict(&repo, conflict_path, strategy).await?;
+            println!(
+                "Resolved and staged {} ({strategy})",
+                conflict_path.display()
+            );
+            num_resolved += 1;
+        }
+
+        let num_left = conflicts.len() - num_resolved;
+        if num_left > 0 {
+            println!("\n{num_left} conflicts left to resolve.");
+        } else {
+            println!("\nAll conflicts resolved, run `oxen commit` to finish the merge.");
+        }
+        Ok(())
+    }
+}
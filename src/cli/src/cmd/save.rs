@@ -9,6 +9,7 @@ use liboxen::util;
 use std::path::Path;
 
 use crate::cmd::RunCmd;
+use crate::helpers::get_output_path;
 pub const NAME: &str = "save";
 pub struct SaveCmd;
 
@@ -37,7 +38,8 @@ impl RunCmd for SaveCmd {
 
     async fn run(&self, args: &ArgMatches) -> Result<(), OxenError> {
         let repo_str = args.get_one::<String>("PATH").expect("Required");
-        let output_str = args.get_one::<String>("output").expect("Required");
+        let output_str =
+            get_output_path(args).ok_or(OxenError::basic_str("Must supply an --output path"))?;
 
         let output_path = Path::new(output_str);
         let repo_path = Path::new(repo_str);
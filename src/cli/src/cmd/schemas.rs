@@ -12,6 +12,9 @@ pub const NAME: &str = "schemas";
 pub mod add;
 pub use add::SchemasAddCmd;
 
+pub mod diff;
+pub use diff::SchemasDiffCmd;
+
 pub mod list;
 pub use list::SchemasListCmd;
 
@@ -96,6 +99,7 @@ impl SchemasCmd {
     fn get_subcommands(&self) -> HashMap<String, Box<dyn RunCmd>> {
         let commands: Vec<Box<dyn RunCmd>> = vec![
             Box::new(SchemasAddCmd),
+            Box::new(SchemasDiffCmd),
             Box::new(SchemasListCmd),
             Box::new(SchemasRmCmd),
             Box::new(SchemasShowCmd),
@@ -0,0 +1,74 @@
+use async_trait::async_trait;
+use clap::{arg, Arg, Command};
+
+use liboxen::error::OxenError;
+use liboxen::model::LocalRepository;
+use liboxen::repositories;
+
+use crate::cmd::RunCmd;
+pub const NAME: &str = "diff";
+
+pub struct SchemasDiffCmd;
+
+#[async_trait]
+impl RunCmd for SchemasDiffCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        // Setups the CLI args for the command
+        Command::new(NAME)
+            .about("Compare the schema of a tabular file between two revisions. Exits with an error if columns were removed, renamed or changed dtype.")
+            .arg(arg!(<PATH> "Path of the tabular file to compare."))
+            .arg(arg!(<REV1> "The base branch or commit."))
+            .arg(arg!(<REV2> "The branch or commit to compare to the base."))
+            .arg(
+                Arg::new("json")
+                    .long("json")
+                    .help("Print the changes as JSON")
+                    .action(clap::ArgAction::SetTrue),
+            )
+    }
+
+    async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        // Parse Args
+        let repository = LocalRepository::from_current_dir()?;
+        let path = args.get_one::<String>("PATH").expect("required");
+        let base = args.get_one::<String>("REV1").expect("required");
+        let head = args.get_one::<String>("REV2").expect("required");
+
+        let changes = repositories::data_frames::schemas::diff(&repository, path, base, head)?;
+        if args.get_flag("json") {
+            println!("{}", serde_json::to_string_pretty(&changes)?);
+        } else if changes.is_empty() {
+            println!("Schema of {path} is unchanged between {base} and {head}");
+        } else {
+            for field in changes.added.iter() {
+                println!("added column\t{}\t{}", field.name, field.dtype);
+            }
+            for field in changes.removed.iter() {
+                println!("removed column\t{}\t{}", field.name, field.dtype);
+            }
+            for rename in changes.renamed.iter() {
+                println!(
+                    "renamed column\t{} -> {}\t{}",
+                    rename.from, rename.to, rename.dtype
+                );
+            }
+            for change in changes.dtype_changed.iter() {
+                println!(
+                    "changed dtype\t{}\t{} -> {}",
+                    change.name, change.from, change.to
+                );
+            }
+        }
+
+        if changes.is_breaking() {
+            return Err(OxenError::basic_str(format!(
+                "Breaking schema changes to {path} between {base} and {head}"
+            )));
+        }
+        Ok(())
+    }
+}
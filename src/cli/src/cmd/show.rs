@@ -0,0 +1,127 @@
+use async_trait::async_trait;
+use bytesize::ByteSize;
+use clap::{Arg, Command};
+use colored::Colorize;
+use std::path::Path;
+use time::format_description;
+
+use liboxen::core::df::{pretty_print, tabular};
+use liboxen::error::OxenError;
+use liboxen::model::{Commit, EntryDataType, LocalRepository};
+use liboxen::opts::DFOpts;
+use liboxen::repositories;
+use liboxen::util;
+
+use crate::cmd::{DiffCmd, RunCmd};
+
+pub const NAME: &str = "show";
+pub struct ShowCmd;
+
+#[async_trait]
+impl RunCmd for ShowCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        // Setups the CLI args for the command
+        Command::new(NAME)
+            .about("Show a commit and the files it changed, or a file at a revision")
+            .arg(
+                Arg::new("object")
+                    .help(
+                        "A commit or branch, or <revision>:<path> to print a file at that revision",
+                    )
+                    .default_value("HEAD")
+                    .value_name("revision | revision:path"),
+            )
+    }
+
+    async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        let repo = LocalRepository::from_current_dir()?;
+
+        // Parse Args
+        let object = args.get_one::<String>("object").expect("has default");
+        match object.split_once(':') {
+            Some((revision, path)) => ShowCmd::show_file(&repo, revision, Path::new(path)),
+            None => ShowCmd::show_commit(&repo, object),
+        }
+    }
+}
+
+impl ShowCmd {
+    fn get_commit(repo: &LocalRepository, revision: &str) -> Result<Commit, OxenError> {
+        let revision = if revision.is_empty() {
+            "HEAD"
+        } else {
+            revision
+        };
+        repositories::revisions::get(repo, revision)?
+            .ok_or_else(|| OxenError::revision_not_found(revision.to_string().into()))
+    }
+
+    fn show_commit(repo: &LocalRepository, revision: &str) -> Result<(), OxenError> {
+        let commit = ShowCmd::get_commit(repo, revision)?;
+
+        // Fri, 21 Oct 2022 16:08:39 -0700
+        let format = format_description::parse(
+            "[weekday], [day] [month repr:long] [year] [hour]:[minute]:[second] [offset_hour sign:mandatory]",
+        ).unwrap();
+
+        println!("{}", format!("commit {}", commit.id).yellow());
+        if commit.parent_ids.len() > 1 {
+            println!("Merge:  {}", commit.parent_ids.join(" "));
+        }
+        println!("Author: {}", commit.author);
+        for co_author in commit.co_authors.iter() {
+            println!("Co-author: {}", co_author);
+        }
+        for (key, value) in commit.metadata.iter() {
+            println!("Meta:   {}={}", key, value);
+        }
+        println!("Date:   {}\n", commit.timestamp.format(&format).unwrap());
+        println!("    {}\n", commit.message);
+
+        let stat = repositories::diffs::commit_stat(repo, &commit)?;
+        DiffCmd::print_stat(&stat);
+        Ok(())
+    }
+
+    fn show_file(repo: &LocalRepository, revision: &str, path: &Path) -> Result<(), OxenError> {
+        let commit = ShowCmd::get_commit(repo, revision)?;
+        let file_node = repositories::tree::get_file_by_path(repo, &commit, path)?
+            .ok_or(OxenError::entry_does_not_exist_in_commit(path, &commit.id))?;
+
+        let version_path = util::fs::version_path_from_hash(repo, file_node.hash().to_string());
+        if !version_path.exists() {
+            return Err(OxenError::basic_str(format!(
+                "{} is not downloaded at {}, run `oxen pull` or `oxen fetch` first",
+                path.display(),
+                commit.id
+            )));
+        }
+
+        match file_node.data_type() {
+            EntryDataType::Tabular => {
+                let df = tabular::read_df_with_extension(
+                    &version_path,
+                    file_node.extension(),
+                    &DFOpts::empty(),
+                )?;
+                println!("{}", pretty_print::df_to_str(&df));
+            }
+            EntryDataType::Text => {
+                print!("{}", util::fs::read_from_path(&version_path)?);
+            }
+            data_type => {
+                println!(
+                    "{} is a {} file of {}",
+                    path.display(),
+                    data_type,
+                    ByteSize::b(file_node.num_bytes())
+                );
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,75 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use clap::Command;
+use liboxen::error::OxenError;
+
+use crate::cmd::RunCmd;
+
+pub const NAME: &str = "sparse-checkout";
+
+pub mod list;
+pub use list::SparseCheckoutListCmd;
+
+pub mod set;
+pub use set::SparseCheckoutSetCmd;
+
+pub struct SparseCheckoutCmd;
+
+#[async_trait]
+impl RunCmd for SparseCheckoutCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        // Setups the CLI args for the command
+        let mut command = Command::new(NAME)
+            .about("Only check out some directories of the repository")
+            .subcommand_required(true)
+            .arg_required_else_help(true);
+
+        // These are all the subcommands the command
+        let sub_commands = self.get_subcommands();
+        for cmd in sub_commands.values() {
+            command = command.subcommand(cmd.args());
+        }
+        command
+    }
+
+    async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        // Parse Args
+        let sub_commands = self.get_subcommands();
+        if let Some((name, sub_matches)) = args.subcommand() {
+            let Some(cmd) = sub_commands.get(name) else {
+                eprintln!("Unknown sparse-checkout subcommand {name}");
+                return Err(OxenError::basic_str(format!(
+                    "Unknown sparse-checkout subcommand {name}"
+                )));
+            };
+
+            // Calling await within an await is making it complain?
+            tokio::task::block_in_place(|| {
+                tokio::runtime::Handle::current().block_on(cmd.run(sub_matches))
+            })?;
+        } else {
+            return Err(OxenError::basic_str("No subcommand provided"));
+        }
+
+        Ok(())
+    }
+}
+
+impl SparseCheckoutCmd {
+    fn get_subcommands(&self) -> HashMap<String, Box<dyn RunCmd>> {
+        let commands: Vec<Box<dyn RunCmd>> = vec![
+            Box::new(SparseCheckoutListCmd),
+            Box::new(SparseCheckoutSetCmd),
+        ];
+        let mut runners: HashMap<String, Box<dyn RunCmd>> = HashMap::new();
+        for cmd in commands {
+            runners.insert(cmd.name().to_string(), cmd);
+        }
+        runners
+    }
+}
@@ -0,0 +1,41 @@
+use async_trait::async_trait;
+use clap::Command;
+
+use liboxen::error::OxenError;
+use liboxen::model::LocalRepository;
+use liboxen::repositories;
+
+use crate::cmd::RunCmd;
+
+pub const NAME: &str = "list";
+
+pub struct SparseCheckoutListCmd;
+
+#[async_trait]
+impl RunCmd for SparseCheckoutListCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        // Setups the CLI args for the command
+        Command::new(NAME).about("List the directories that are checked out")
+    }
+
+    async fn run(&self, _args: &clap::ArgMatches) -> Result<(), OxenError> {
+        let repo = LocalRepository::from_current_dir()?;
+        match repositories::sparse_checkout::list(&repo) {
+            Some(paths) => {
+                for path in paths {
+                    if path.as_os_str().is_empty() {
+                        println!(".");
+                    } else {
+                        println!("{}", path.display());
+                    }
+                }
+            }
+            None => println!("The whole repository is checked out"),
+        }
+        Ok(())
+    }
+}
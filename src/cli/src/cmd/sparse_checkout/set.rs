@@ -0,0 +1,51 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use clap::{Arg, Command};
+
+use liboxen::error::OxenError;
+use liboxen::model::LocalRepository;
+use liboxen::repositories;
+
+use crate::cmd::RunCmd;
+use crate::helpers::check_repo_migration_needed;
+
+pub const NAME: &str = "set";
+
+pub struct SparseCheckoutSetCmd;
+
+#[async_trait]
+impl RunCmd for SparseCheckoutSetCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        // Setups the CLI args for the command
+        Command::new(NAME)
+            .about("Check out only these directories, removing the files of all others from the working tree. Use `.` to check out everything again.")
+            .arg(
+                Arg::new("paths")
+                    .required(true)
+                    .num_args(1..)
+                    .action(clap::ArgAction::Append),
+            )
+    }
+
+    async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        let paths: Vec<PathBuf> = args
+            .get_many::<String>("paths")
+            .expect("Must supply paths")
+            .map(PathBuf::from)
+            .collect();
+
+        let repo = LocalRepository::from_current_dir()?;
+        check_repo_migration_needed(&repo)?;
+
+        repositories::sparse_checkout::set(&repo, &paths).await?;
+        for path in &paths {
+            println!("{}", path.display());
+        }
+        Ok(())
+    }
+}
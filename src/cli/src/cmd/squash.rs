@@ -0,0 +1,51 @@
+use async_trait::async_trait;
+use clap::{Arg, Command};
+use liboxen::error::OxenError;
+use liboxen::model::LocalRepository;
+use liboxen::repositories;
+
+use crate::cmd::RunCmd;
+use crate::helpers::check_repo_migration_needed;
+
+pub const NAME: &str = "squash";
+pub struct SquashCmd;
+
+#[async_trait]
+impl RunCmd for SquashCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        // Setups the CLI args for the command
+        Command::new(NAME)
+            .about("Collapse the last N commits on the current branch into a single commit.")
+            .arg(
+                Arg::new("num")
+                    .help("Number of commits to squash, counting back from HEAD")
+                    .value_parser(clap::value_parser!(usize))
+                    .required(true),
+            )
+            .arg(
+                Arg::new("message")
+                    .help(
+                        "The message for the new commit. Defaults to the squashed commit messages.",
+                    )
+                    .long("message")
+                    .short('m')
+                    .action(clap::ArgAction::Set),
+            )
+    }
+
+    async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        let repo = LocalRepository::from_current_dir()?;
+        check_repo_migration_needed(&repo)?;
+
+        // Parse Args
+        let num = *args.get_one::<usize>("num").expect("required");
+        let message = args.get_one::<String>("message").map(|m| m.as_str());
+
+        repositories::commits::squash(&repo, num, message)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use clap::Command;
+use liboxen::error::OxenError;
+
+use crate::cmd::RunCmd;
+
+pub const NAME: &str = "stash";
+
+pub mod list;
+pub use list::StashListCmd;
+
+pub mod pop;
+pub use pop::StashPopCmd;
+
+pub mod push;
+pub use push::StashPushCmd;
+
+pub struct StashCmd;
+
+#[async_trait]
+impl RunCmd for StashCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        // Setups the CLI args for the command
+        let mut command = Command::new(NAME)
+            .about("Save your local changes away and reset the working directory to HEAD. Without a subcommand this runs `oxen stash push`.");
+
+        // These are all the subcommands the command
+        let sub_commands = self.get_subcommands();
+        for cmd in sub_commands.values() {
+            command = command.subcommand(cmd.args());
+        }
+        command
+    }
+
+    async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        // Parse Args
+        let sub_commands = self.get_subcommands();
+        let Some((name, sub_matches)) = args.subcommand() else {
+            // `oxen stash` on its own saves the changes
+            let push_args = StashPushCmd.args().get_matches_from([push::NAME]);
+            return StashPushCmd.run(&push_args).await;
+        };
+        let Some(cmd) = sub_commands.get(name) else {
+            eprintln!("Unknown stash subcommand {name}");
+            return Err(OxenError::basic_str(format!(
+                "Unknown stash subcommand {name}"
+            )));
+        };
+
+        // Calling await within an await is making it complain?
+        tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(cmd.run(sub_matches))
+        })?;
+
+        Ok(())
+    }
+}
+
+impl StashCmd {
+    fn get_subcommands(&self) -> HashMap<String, Box<dyn RunCmd>> {
+        let commands: Vec<Box<dyn RunCmd>> = vec![
+            Box::new(StashListCmd),
+            Box::new(StashPopCmd),
+            Box::new(StashPushCmd),
+        ];
+        let mut runners: HashMap<String, Box<dyn RunCmd>> = HashMap::new();
+        for cmd in commands {
+            runners.insert(cmd.name().to_string(), cmd);
+        }
+        runners
+    }
+}
@@ -0,0 +1,36 @@
+use async_trait::async_trait;
+use clap::Command;
+
+use liboxen::error::OxenError;
+use liboxen::model::LocalRepository;
+use liboxen::repositories;
+
+use crate::cmd::RunCmd;
+
+pub const NAME: &str = "list";
+
+pub struct StashListCmd;
+
+#[async_trait]
+impl RunCmd for StashListCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        // Setups the CLI args for the command
+        Command::new(NAME).about("List the stash entries, newest first")
+    }
+
+    async fn run(&self, _args: &clap::ArgMatches) -> Result<(), OxenError> {
+        let repo = LocalRepository::from_current_dir()?;
+        for (index, entry) in repositories::stash::list(&repo)?.iter().enumerate() {
+            println!(
+                "stash@{{{index}}}: {} ({} files)",
+                entry.message,
+                entry.files.len()
+            );
+        }
+        Ok(())
+    }
+}
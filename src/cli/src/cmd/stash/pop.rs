@@ -0,0 +1,47 @@
+use async_trait::async_trait;
+use clap::{Arg, Command};
+
+use liboxen::error::OxenError;
+use liboxen::model::LocalRepository;
+use liboxen::repositories;
+
+use crate::cmd::RunCmd;
+use crate::helpers::check_repo_migration_needed;
+
+pub const NAME: &str = "pop";
+
+pub struct StashPopCmd;
+
+#[async_trait]
+impl RunCmd for StashPopCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        // Setups the CLI args for the command
+        Command::new(NAME)
+            .about("Put the changes of a stash entry back and remove it from the stash")
+            .arg(
+                Arg::new("index")
+                    .help("Which entry to pop, as listed by `oxen stash list`. Defaults to the newest, 0.")
+                    .default_value("0")
+                    .value_parser(clap::value_parser!(usize)),
+            )
+    }
+
+    async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        let index = *args.get_one::<usize>("index").expect("has a default");
+
+        let repo = LocalRepository::from_current_dir()?;
+        check_repo_migration_needed(&repo)?;
+
+        let entry = repositories::stash::pop(&repo, index).await?;
+        println!(
+            "Restored {} changed files from stash@{{{index}}}: {}",
+            entry.files.len(),
+            entry.message
+        );
+        Ok(())
+    }
+}
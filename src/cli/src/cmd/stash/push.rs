@@ -0,0 +1,50 @@
+use async_trait::async_trait;
+use clap::{Arg, Command};
+
+use liboxen::error::OxenError;
+use liboxen::model::LocalRepository;
+use liboxen::repositories;
+
+use crate::cmd::RunCmd;
+use crate::helpers::check_repo_migration_needed;
+
+pub const NAME: &str = "push";
+
+pub struct StashPushCmd;
+
+#[async_trait]
+impl RunCmd for StashPushCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        // Setups the CLI args for the command
+        Command::new(NAME)
+            .about("Save the staged and unstaged changes to tracked files and reset them to HEAD")
+            .arg(
+                Arg::new("message")
+                    .long("message")
+                    .short('m')
+                    .help("Describe the stashed changes")
+                    .action(clap::ArgAction::Set),
+            )
+    }
+
+    async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        let message = args.get_one::<String>("message");
+
+        let repo = LocalRepository::from_current_dir()?;
+        check_repo_migration_needed(&repo)?;
+
+        match repositories::stash::push(&repo, message.map(String::as_str)).await? {
+            Some(entry) => println!(
+                "Saved {} changed files: {}",
+                entry.files.len(),
+                entry.message
+            ),
+            None => println!("No local changes to save"),
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,116 @@
+use async_trait::async_trait;
+use bytesize::ByteSize;
+use clap::{Arg, ArgMatches, Command};
+
+use liboxen::error::OxenError;
+use liboxen::model::LocalRepository;
+use liboxen::repositories;
+use liboxen::util;
+
+use crate::cmd::RunCmd;
+use crate::helpers::check_repo_migration_needed;
+
+pub const NAME: &str = "stats";
+
+pub struct StatsCmd;
+
+#[async_trait]
+impl RunCmd for StatsCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        Command::new(NAME)
+            .about("Show file counts, sizes, file types and the largest files of a directory, read from the merkle tree. Ex: oxen stats images --revision main")
+            .arg(Arg::new("path").help("The directory to show stats for. Defaults to the root of the repository"))
+            .arg(
+                Arg::new("revision")
+                    .long("revision")
+                    .short('r')
+                    .help("The branch or commit to show stats for. Defaults to HEAD")
+                    .default_value("HEAD")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("largest")
+                    .long("largest")
+                    .short('n')
+                    .help("How many of the largest files to list")
+                    .default_value("10")
+                    .value_parser(clap::value_parser!(usize))
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("json")
+                    .long("json")
+                    .help("Print the stats as JSON")
+                    .action(clap::ArgAction::SetTrue),
+            )
+    }
+
+    async fn run(&self, args: &ArgMatches) -> Result<(), OxenError> {
+        let repo = LocalRepository::from_current_dir()?;
+        check_repo_migration_needed(&repo)?;
+
+        let revision = args.get_one::<String>("revision").expect("has default");
+        let num_largest = *args.get_one::<usize>("largest").expect("has default");
+        let path = match args.get_one::<String>("path") {
+            Some(path) => {
+                let current_dir = std::env::current_dir()?;
+                util::fs::path_relative_to_dir(current_dir.join(path), &repo.path)?
+            }
+            None => std::path::PathBuf::new(),
+        };
+
+        let stats = repositories::stats::dataset_stats(&repo, revision, &path, num_largest)?;
+        if args.get_flag("json") {
+            println!("{}", serde_json::to_string_pretty(&stats)?);
+            return Ok(());
+        }
+
+        println!(
+            "{} files, {} at commit {}",
+            stats.num_files,
+            ByteSize::b(stats.num_bytes),
+            stats.commit_id
+        );
+
+        println!("\nDirectories");
+        for dir in stats.dirs.iter() {
+            let path = if dir.path.as_os_str().is_empty() {
+                "/".to_string()
+            } else {
+                dir.path.to_string_lossy().to_string()
+            };
+            println!(
+                "  {path}  {} files  {}  {} entries",
+                dir.num_files,
+                ByteSize::b(dir.num_bytes),
+                dir.num_entries
+            );
+        }
+
+        println!("\nFile types");
+        for extension in stats.extensions.iter() {
+            let name = if extension.extension.is_empty() {
+                "(none)"
+            } else {
+                extension.extension.as_str()
+            };
+            println!(
+                "  {name}  {} files  {}",
+                extension.num_files,
+                ByteSize::b(extension.num_bytes)
+            );
+        }
+
+        if !stats.largest_files.is_empty() {
+            println!("\nLargest files");
+            for file in stats.largest_files.iter() {
+                println!("  {}  {}", ByteSize::b(file.num_bytes), file.path.display());
+            }
+        }
+        Ok(())
+    }
+}
@@ -6,10 +6,11 @@ use liboxen::error::OxenError;
 use liboxen::model::staged_data::StagedDataOpts;
 use liboxen::model::LocalRepository;
 use liboxen::repositories;
+use liboxen::view::{RepoStatus, RepoStatusResponse, StatusMessage};
 use std::collections::HashSet;
 use std::path::PathBuf;
 
-use crate::helpers::check_repo_migration_needed;
+use crate::helpers::{check_repo_migration_needed, print_json};
 
 use crate::cmd::RunCmd;
 pub const NAME: &str = "status";
@@ -63,6 +64,55 @@ impl RunCmd for StatusCmd {
     }
 
     async fn run(&self, args: &ArgMatches) -> Result<(), OxenError> {
+        let repository = LocalRepository::from_current_dir()?;
+        check_repo_migration_needed(&repository)?;
+
+        let opts = StatusCmd::parse_opts(args, &repository);
+        log::debug!("status opts: {:?}", opts);
+
+        let repo_status = repositories::status::status_from_opts(&repository, &opts)?;
+
+        if let Some(current_branch) = repositories::branches::current_branch(&repository)? {
+            println!(
+                "On branch {} -> {}\n",
+                current_branch.name, current_branch.commit_id
+            );
+        } else if let Some(head) = repositories::commits::head_commit_maybe(&repository)? {
+            println!(
+                "You are in 'detached HEAD' state.\nHEAD is now at {} {}\n",
+                head.id, head.message
+            );
+        }
+
+        repo_status.print_with_params(&opts);
+
+        Ok(())
+    }
+
+    async fn run_json(&self, args: &ArgMatches) -> Result<(), OxenError> {
+        let repository = LocalRepository::from_current_dir()?;
+        check_repo_migration_needed(&repository)?;
+
+        let opts = StatusCmd::parse_opts(args, &repository);
+        print_json(&StatusCmd::json_status(&repository, &opts)?)
+    }
+}
+
+impl StatusCmd {
+    fn json_status(
+        repository: &LocalRepository,
+        opts: &StagedDataOpts,
+    ) -> Result<RepoStatusResponse, OxenError> {
+        let repo_status = repositories::status::status_from_opts(repository, opts)?;
+        let branch = repositories::branches::current_branch(repository)?;
+        let head_commit = repositories::commits::head_commit_maybe(repository)?;
+        Ok(RepoStatusResponse {
+            status: StatusMessage::resource_found(),
+            repo_status: RepoStatus::from_staged(&repo_status, branch, head_commit),
+        })
+    }
+
+    fn parse_opts(args: &ArgMatches, repository: &LocalRepository) -> StagedDataOpts {
         let skip = args
             .get_one::<String>("skip")
             .expect("Must supply skip")
@@ -75,41 +125,19 @@ impl RunCmd for StatusCmd {
             .expect("limit must be a valid integer.");
         let print_all = args.get_flag("print_all");
 
-        let repository = LocalRepository::from_current_dir()?;
-        check_repo_migration_needed(&repository)?;
-
         let paths = args
             .get_many::<String>("paths")
             .map(|vals| vals.map(|v| repository.path.join(v)).collect())
             .unwrap_or_else(|| vec![repository.path.clone()]);
         let is_remote = false;
-        let opts = StagedDataOpts {
+        StagedDataOpts {
             paths,
             skip,
             limit,
             print_all,
             is_remote,
             ignore: parse_ignore_files(args.get_one::<String>("ignore")),
-        };
-        log::debug!("status opts: {:?}", opts);
-
-        let repo_status = repositories::status::status_from_opts(&repository, &opts)?;
-
-        if let Some(current_branch) = repositories::branches::current_branch(&repository)? {
-            println!(
-                "On branch {} -> {}\n",
-                current_branch.name, current_branch.commit_id
-            );
-        } else if let Some(head) = repositories::commits::head_commit_maybe(&repository)? {
-            println!(
-                "You are in 'detached HEAD' state.\nHEAD is now at {} {}\n",
-                head.id, head.message
-            );
         }
-
-        repo_status.print_with_params(&opts);
-
-        Ok(())
     }
 }
 
@@ -130,3 +158,37 @@ fn parse_ignore_files(paths: Option<&String>) -> Option<HashSet<PathBuf>> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use liboxen::error::OxenError;
+    use liboxen::model::staged_data::StagedDataOpts;
+    use liboxen::repositories;
+    use liboxen::test;
+    use liboxen::util;
+
+    use super::StatusCmd;
+
+    #[tokio::test]
+    async fn test_status_json() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            let hello = repo.path.join("hello.txt");
+            util::fs::write_to_path(&hello, "hello")?;
+            repositories::add(&repo, &hello).await?;
+            let commit = repositories::commit(&repo, "Add hello")?;
+            util::fs::write_to_path(&hello, "hello again")?;
+            util::fs::write_to_path(repo.path.join("new.txt"), "new")?;
+
+            let response =
+                StatusCmd::json_status(&repo, &StagedDataOpts::from_paths(&[repo.path.clone()]))?;
+            let json = serde_json::to_value(&response)?;
+            assert_eq!(json["status"], "success");
+            assert_eq!(json["repo_status"]["branch"]["name"], "main");
+            assert_eq!(json["repo_status"]["head_commit"]["id"], commit.id);
+            assert_eq!(json["repo_status"]["modified_files"][0], "hello.txt");
+            assert_eq!(json["repo_status"]["untracked_files"][0], "new.txt");
+            Ok(())
+        })
+        .await
+    }
+}
@@ -0,0 +1,81 @@
+use async_trait::async_trait;
+use clap::{Arg, Command};
+
+use liboxen::error::OxenError;
+use liboxen::model::LocalRepository;
+use liboxen::repositories;
+
+use crate::cmd::RunCmd;
+
+pub const NAME: &str = "tag";
+
+pub struct TagCmd;
+
+#[async_trait]
+impl RunCmd for TagCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        // Setups the CLI args for the command
+        Command::new(NAME)
+            .about("Create, list or delete annotated tags that mark commits such as releases")
+            .arg(Arg::new("name").help("Name of the tag to create"))
+            .arg(
+                Arg::new("revision")
+                    .help("Commit id or branch to tag")
+                    .default_value("HEAD"),
+            )
+            .arg(
+                Arg::new("message")
+                    .long("message")
+                    .short('m')
+                    .help("Message describing the tag")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("list")
+                    .long("list")
+                    .short('l')
+                    .help("List the tags")
+                    .exclusive(true)
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("delete")
+                    .long("delete")
+                    .short('d')
+                    .help("Delete the tag")
+                    .exclusive(true)
+                    .action(clap::ArgAction::Set),
+            )
+    }
+
+    async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        let repo = LocalRepository::from_current_dir()?;
+
+        if let Some(name) = args.get_one::<String>("delete") {
+            let tag = repositories::tags::delete(&repo, name)?;
+            println!("Deleted tag {} (was {})", tag.name, tag.commit_id);
+            return Ok(());
+        }
+
+        let Some(name) = args.get_one::<String>("name") else {
+            // `oxen tag` on its own lists the tags, like `--list`
+            for tag in repositories::tags::list(&repo)? {
+                println!("{}\t{}\t{}", tag.name, tag.commit_id, tag.message);
+            }
+            return Ok(());
+        };
+
+        let revision = args.get_one::<String>("revision").expect("has a default");
+        let message = args
+            .get_one::<String>("message")
+            .map(String::as_str)
+            .unwrap_or_default();
+        let tag = repositories::tags::create(&repo, name, revision, message)?;
+        println!("Tagged commit {} as {}", tag.commit_id, tag.name);
+        Ok(())
+    }
+}
@@ -0,0 +1,60 @@
+use async_trait::async_trait;
+use clap::{Arg, Command};
+use liboxen::config::TelemetryConfig;
+use liboxen::error::OxenError;
+
+use crate::cmd::RunCmd;
+pub const NAME: &str = "telemetry";
+pub struct TelemetryCmd;
+
+#[async_trait]
+impl RunCmd for TelemetryCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        // Setups the CLI args for the command
+        Command::new(NAME)
+            .about("Opt in or out of anonymous usage telemetry. It is off by default.")
+            .subcommand_required(true)
+            .subcommand(
+                Command::new("on")
+                    .about("Send command names, timings and repo size buckets, never paths or data")
+                    .arg(
+                        Arg::new("endpoint")
+                            .long("endpoint")
+                            .help("URL to send telemetry events to")
+                            .action(clap::ArgAction::Set),
+                    ),
+            )
+            .subcommand(Command::new("off").about("Stop sending telemetry"))
+            .subcommand(Command::new("status").about("Show whether telemetry is on"))
+    }
+
+    async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        let mut config = TelemetryConfig::get()?;
+        match args.subcommand() {
+            Some(("on", sub_matches)) => {
+                let endpoint = sub_matches.get_one::<String>("endpoint").cloned();
+                config.enable(endpoint);
+                config.save_default()?;
+                println!("Telemetry is on, sending to {}", config.endpoint());
+            }
+            Some(("off", _)) => {
+                config.disable();
+                config.save_default()?;
+                println!("Telemetry is off");
+            }
+            Some(("status", _)) => {
+                if config.enabled {
+                    println!("Telemetry is on, sending to {}", config.endpoint());
+                } else {
+                    println!("Telemetry is off");
+                }
+            }
+            _ => unreachable!(),
+        }
+        Ok(())
+    }
+}
@@ -2,13 +2,16 @@ use async_trait::async_trait;
 use clap::{Arg, Command};
 use liboxen::core::v_latest::index::CommitMerkleTree;
 use liboxen::error::OxenError;
+use liboxen::model::merkle_tree::merkle_tree_node_cache;
 use liboxen::model::{Commit, LocalRepository, MerkleHash};
 use liboxen::repositories;
+use liboxen::util;
 use std::time::Instant;
 
 use std::str::FromStr;
 
 use crate::cmd::RunCmd;
+use crate::helpers::get_output_path;
 pub const NAME: &str = "tree";
 pub struct TreeCmd;
 
@@ -22,6 +25,61 @@ impl RunCmd for TreeCmd {
         // Setups the CLI args for the command
         Command::new(NAME)
             .about("Print the merkle tree 🌲 of a commit.")
+            .args_conflicts_with_subcommands(true)
+            .subcommand(
+                Command::new("export")
+                    .about("Write the merkle tree of a commit as Graphviz DOT or JSON. Ex: oxen tree export --format dot | dot -Tsvg > tree.svg")
+                    .arg(
+                        Arg::new("format")
+                            .long("format")
+                            .short('f')
+                            .help("Output format")
+                            .value_parser(["dot", "json"])
+                            .default_value("json")
+                            .action(clap::ArgAction::Set),
+                    )
+                    .arg(
+                        Arg::new("commit")
+                            .long("commit")
+                            .short('c')
+                            .help("The commit or branch to export the tree of.")
+                            .default_value("HEAD")
+                            .action(clap::ArgAction::Set),
+                    )
+                    .arg(
+                        Arg::new("path")
+                            .long("path")
+                            .short('p')
+                            .help("Only export the subtree of this directory.")
+                            .action(clap::ArgAction::Set),
+                    )
+                    .arg(
+                        Arg::new("depth")
+                            .long("depth")
+                            .short('d')
+                            .help("How many levels deep to export the tree. -1 for all.")
+                            .default_value("-1")
+                            .allow_negative_numbers(true)
+                            .value_parser(clap::value_parser!(i32))
+                            .action(clap::ArgAction::Set),
+                    )
+                    .arg(
+                        Arg::new("output")
+                            .long("output")
+                            .short('o')
+                            .help("File to write to instead of stdout")
+                            .action(clap::ArgAction::Set),
+                    ),
+            )
+            .subcommand(
+                Command::new("verify")
+                    .about("Check that every node in the tree of a commit can be read and is consistent with its parent")
+                    .arg(
+                        Arg::new("revision")
+                            .help("The commit or branch to verify")
+                            .default_value("HEAD"),
+                    ),
+            )
             .arg(
                 Arg::new("commit")
                     .long("commit")
@@ -58,9 +116,26 @@ impl RunCmd for TreeCmd {
                     .help("To use the legacy lookup method")
                     .action(clap::ArgAction::SetTrue),
             )
+            .arg(
+                Arg::new("cache-stats")
+                    .long("cache-stats")
+                    .help("Load the tree through the merkle node cache and print its hits, misses and evictions")
+                    .action(clap::ArgAction::SetTrue),
+            )
     }
 
     async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        match args.subcommand() {
+            Some(("export", sub_matches)) => return self.export(sub_matches),
+            Some(("verify", sub_matches)) => {
+                let revision = sub_matches
+                    .get_one::<String>("revision")
+                    .expect("has a default");
+                return self.verify(revision);
+            }
+            _ => {}
+        }
+
         // Parse Args
         let depth = args
             .get_one::<String>("depth")
@@ -84,6 +159,11 @@ impl RunCmd for TreeCmd {
             commit
         };
 
+        let cache_stats = args.get_flag("cache-stats");
+        if cache_stats {
+            merkle_tree_node_cache::enable();
+        }
+
         let path = args.get_one::<String>("path");
         if let Some(node) = args.get_one::<String>("node") {
             self.print_node(&repo, node, depth)?;
@@ -91,11 +171,79 @@ impl RunCmd for TreeCmd {
             self.print_tree(&repo, &commit, path, depth)?;
         }
 
+        if cache_stats {
+            let stats = merkle_tree_node_cache::stats(&repo);
+            println!(
+                "Merkle node cache: {} hits, {} misses, {} evictions, {} entries, {} / {} bytes",
+                stats.hits,
+                stats.misses,
+                stats.evictions,
+                stats.entries,
+                stats.bytes,
+                stats.max_bytes
+            );
+        }
+
         Ok(())
     }
 }
 
 impl TreeCmd {
+    fn export(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        let repo = LocalRepository::from_current_dir()?;
+        let revision = args.get_one::<String>("commit").expect("has a default");
+        let format = args.get_one::<String>("format").expect("has a default");
+        let depth = *args.get_one::<i32>("depth").expect("has a default");
+        let commit = repositories::revisions::get(&repo, revision)?
+            .ok_or(OxenError::revision_not_found(revision.into()))?;
+
+        let node = match args.get_one::<String>("path") {
+            Some(path) => repositories::tree::get_dir_with_children_recursive(
+                &repo, &commit, path,
+            )?
+            .ok_or(OxenError::basic_str(format!(
+                "Directory {path} not found in commit {}",
+                commit.id
+            )))?,
+            None => repositories::tree::get_root_with_children(&repo, &commit)?.ok_or(
+                OxenError::basic_str(format!("Merkle tree not found for commit {}", commit.id)),
+            )?,
+        };
+        let exported = match format.as_str() {
+            "dot" => CommitMerkleTree::node_to_dot(&node, depth),
+            _ => CommitMerkleTree::node_to_json(&node, depth)?,
+        };
+
+        match get_output_path(args) {
+            Some(output) => {
+                util::fs::write_to_path(output, &exported)?;
+                println!("Wrote the tree of commit {} to {output}", commit.id);
+            }
+            None => println!("{exported}"),
+        }
+        Ok(())
+    }
+
+    fn verify(&self, revision: &str) -> Result<(), OxenError> {
+        let repo = LocalRepository::from_current_dir()?;
+        let commit = repositories::revisions::get(&repo, revision)?
+            .ok_or(OxenError::revision_not_found(revision.into()))?;
+
+        let problems = repositories::tree::verify(&repo, &commit)?;
+        for problem in problems.iter() {
+            println!("{problem}");
+        }
+        if !problems.is_empty() {
+            return Err(OxenError::basic_str(format!(
+                "Tree of commit {} has {} inconsistent nodes",
+                commit.id,
+                problems.len()
+            )));
+        }
+        println!("Tree of commit {} is consistent", commit.id);
+        Ok(())
+    }
+
     fn print_node(&self, repo: &LocalRepository, node: &str, depth: i32) -> Result<(), OxenError> {
         let node_hash = MerkleHash::from_str(node)?;
         let tree = CommitMerkleTree::read_node(repo, &node_hash, true)?.unwrap();
@@ -14,6 +14,7 @@ use liboxen::error::OxenError;
 // use rocksdb::MultiThreaded;
 
 use crate::cmd::RunCmd;
+use crate::helpers::get_output_path;
 pub const NAME: &str = "unpack";
 pub struct UnpackCmd;
 
@@ -66,9 +67,8 @@ impl RunCmd for UnpackCmd {
             .parse::<usize>()
             .expect("number must be a valid integer.");
 
-        let reconstructed_path = args
-            .get_one::<String>("output")
-            .expect("Must supply output path");
+        let reconstructed_path =
+            get_output_path(args).ok_or(OxenError::basic_str("Must supply output path"))?;
 
         if paths.len() != 1 {
             return Err(OxenError::basic_str("Must supply exactly one file"));
@@ -0,0 +1,112 @@
+use async_trait::async_trait;
+use clap::{Arg, Command};
+use colored::Colorize;
+
+use liboxen::constants::DEFAULT_REMOTE_NAME;
+use liboxen::error::OxenError;
+use liboxen::model::LocalRepository;
+use liboxen::repositories;
+use liboxen::repositories::verify_remote::{BranchSyncState, RemoteVerifyReport};
+
+use crate::helpers::{
+    check_remote_version_blocking, check_repo_migration_needed, get_scheme_and_host_from_repo,
+};
+
+use crate::cmd::RunCmd;
+pub const NAME: &str = "verify-remote";
+pub struct VerifyRemoteCmd;
+
+#[async_trait]
+impl RunCmd for VerifyRemoteCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        Command::new(NAME)
+            .about("Check that the local repository and the remote have the same branches, commits and files")
+            .arg(
+                Arg::new("remote")
+                    .help("The remote to compare against")
+                    .default_value(DEFAULT_REMOTE_NAME)
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("json")
+                    .long("json")
+                    .help("Print the report as JSON")
+                    .action(clap::ArgAction::SetTrue),
+            )
+    }
+
+    async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        let remote_name = args.get_one::<String>("remote").expect("has default");
+        let repository = LocalRepository::from_current_dir()?;
+        let (scheme, host) = get_scheme_and_host_from_repo(&repository)?;
+
+        check_repo_migration_needed(&repository)?;
+        check_remote_version_blocking(scheme.clone(), host.clone()).await?;
+
+        let report = repositories::verify_remote(&repository, remote_name).await?;
+        if args.get_flag("json") {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        } else {
+            print_report(&report);
+        }
+
+        if report.is_synced() {
+            Ok(())
+        } else {
+            Err(OxenError::basic_str(format!(
+                "Local repository is not in sync with remote {remote_name}"
+            )))
+        }
+    }
+}
+
+fn print_report(report: &RemoteVerifyReport) {
+    println!("Branches:");
+    for branch in &report.branches {
+        // Pad before coloring, escape codes would count towards the width
+        let state = match branch.state {
+            BranchSyncState::InSync => format!("{:<12}", "in sync").green(),
+            BranchSyncState::Ahead => format!("{:<12}", "ahead").yellow(),
+            BranchSyncState::Behind => format!("{:<12}", "behind").yellow(),
+            BranchSyncState::Diverged => format!("{:<12}", "diverged").red(),
+            BranchSyncState::LocalOnly => format!("{:<12}", "local only").yellow(),
+            BranchSyncState::RemoteOnly => format!("{:<12}", "remote only").yellow(),
+        };
+        let local = branch.local_commit_id.as_deref().unwrap_or("-");
+        let remote = branch.remote_commit_id.as_deref().unwrap_or("-");
+        println!(
+            "  {:<24} {state} local {local} remote {remote}",
+            branch.name
+        );
+    }
+
+    print_missing(
+        "Commits missing on remote",
+        &report.commits_missing_on_remote,
+    );
+    print_missing("Commits missing locally", &report.commits_missing_locally);
+    print_missing("Files missing on remote", &report.files_missing_on_remote);
+    print_missing("Files missing locally", &report.files_missing_locally);
+    print_missing(
+        "Files missing locally and on remote",
+        &report.files_missing_everywhere,
+    );
+
+    if report.is_synced() {
+        println!("{}", "Everything is in sync".green());
+    }
+}
+
+fn print_missing(label: &str, ids: &[String]) {
+    if ids.is_empty() {
+        return;
+    }
+    println!("{label} ({}):", ids.len());
+    for id in ids {
+        println!("  {}", id.red());
+    }
+}
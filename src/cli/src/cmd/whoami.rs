@@ -0,0 +1,106 @@
+use async_trait::async_trait;
+use clap::{Arg, Command};
+use colored::Colorize;
+
+use liboxen::api;
+use liboxen::config::auth_config::HostConfig;
+use liboxen::config::{AuthConfig, UserConfig};
+use liboxen::constants::DEFAULT_SCHEME;
+use liboxen::error::OxenError;
+
+use crate::cmd::RunCmd;
+pub const NAME: &str = "whoami";
+pub struct WhoamiCmd;
+
+#[async_trait]
+impl RunCmd for WhoamiCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        Command::new(NAME)
+            .about("Show the configured identity and check each host's auth token with the server")
+            .arg(
+                Arg::new("host")
+                    .long("host")
+                    .help("Only check the token for this host")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("scheme")
+                    .long("scheme")
+                    .help("The scheme used to reach the hosts. Defaults to https")
+                    .default_value(DEFAULT_SCHEME)
+                    .action(clap::ArgAction::Set),
+            )
+    }
+
+    async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        let scheme = args.get_one::<String>("scheme").expect("has default");
+        let only_host = args.get_one::<String>("host");
+
+        match UserConfig::get() {
+            Ok(config) => {
+                println!("name:  {}", config.name);
+                println!("email: {}", config.email);
+            }
+            Err(_) => {
+                println!(
+                    "name and email are not set, set them with:\n\n  oxen config --name YOUR_NAME --email YOUR_EMAIL"
+                );
+            }
+        }
+
+        let auth_config = AuthConfig::get().unwrap_or(AuthConfig::new_empty());
+        let default_host = auth_config.default_host.clone().unwrap_or_default();
+        let mut hosts: Vec<HostConfig> = auth_config.host_configs.into_iter().collect();
+        hosts.sort_by(|a, b| a.host.cmp(&b.host));
+        if let Some(only_host) = only_host {
+            hosts.retain(|config| &config.host == only_host);
+            if hosts.is_empty() {
+                hosts.push(HostConfig::from_host(only_host));
+            }
+        }
+
+        if hosts.is_empty() {
+            println!(
+                "\nno hosts configured, add a token with:\n\n  oxen config --auth <HOST> <TOKEN>"
+            );
+            return Ok(());
+        }
+
+        for host_config in hosts {
+            let is_default = host_config.host == default_host;
+            println!(
+                "\n{}{}",
+                host_config.host.bold(),
+                if is_default { " (default)" } else { "" }
+            );
+
+            let Some(fingerprint) = host_config.token_fingerprint() else {
+                println!("  token:  none");
+                println!(
+                    "  status: {}, set one with `oxen config --auth {} <TOKEN>`",
+                    "no token".yellow(),
+                    host_config.host
+                );
+                continue;
+            };
+            println!("  token:  {fingerprint}");
+
+            let status = match api::client::users::whoami(scheme, &host_config.host).await {
+                Ok(Some(user)) => format!("{}, authenticated as {user}", "valid".green()),
+                Ok(None) => format!(
+                    "{}, the server does not require auth or did not issue this token",
+                    "not recognized".yellow()
+                ),
+                Err(OxenError::Authentication(err)) => format!("{}, {err}", "invalid".red()),
+                Err(err) => format!("{}, {err}", "could not check".yellow()),
+            };
+            println!("  status: {status}");
+        }
+
+        Ok(())
+    }
+}
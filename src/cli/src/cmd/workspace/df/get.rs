@@ -11,6 +11,7 @@ use liboxen::model::LocalRepository;
 use liboxen::opts::DFOpts;
 
 use crate::cmd::RunCmd;
+use crate::helpers::get_output_path;
 pub const NAME: &str = "get";
 
 pub struct WorkspaceDFGetCmd;
@@ -148,7 +149,7 @@ impl WorkspaceDFGetCmd {
         if let Some(output_column) = args.get_one::<String>("output-column") {
             opts.output_column = Some(output_column.to_string());
         }
-        if let Some(output) = args.get_one::<String>("output") {
+        if let Some(output) = get_output_path(args) {
             opts.output = Some(PathBuf::from(output));
         }
         if let Some(page) = args.get_one::<usize>("page") {
@@ -6,6 +6,7 @@ use clap::{Arg, ArgMatches, Command};
 use liboxen::{api, error::OxenError, model::LocalRepository};
 
 use crate::cmd::RunCmd;
+use crate::helpers::get_output_path;
 pub const NAME: &str = "download";
 pub struct WorkspaceDownloadCmd;
 
@@ -56,8 +57,7 @@ impl RunCmd for WorkspaceDownloadCmd {
 
         let workspace_name = args.get_one::<String>("workspace-name");
         let workspace_id = args.get_one::<String>("workspace-id");
-        let output_path = args
-            .get_one::<String>("output")
+        let output_path = get_output_path(args)
             .map(PathBuf::from)
             .unwrap_or_else(|| PathBuf::from(file_path));
 
@@ -1,9 +1,11 @@
 use async_trait::async_trait;
 use clap::{Arg, ArgMatches, Command};
+use colored::Colorize;
 
 use liboxen::api;
 use liboxen::error;
 use liboxen::error::OxenError;
+use liboxen::model::diff::WorkspaceChanges;
 use liboxen::model::staged_data::StagedDataOpts;
 use liboxen::model::LocalRepository;
 use liboxen::model::RemoteRepository;
@@ -109,6 +111,11 @@ impl RunCmd for WorkspaceStatusCmd {
         let repo_status = Self::status(&remote_repo, workspace_id, &directory, &opts).await?;
         repo_status.print_with_params(&opts);
 
+        let changes =
+            api::client::workspaces::changes::summary(&remote_repo, workspace_id, &directory)
+                .await?;
+        Self::print_row_counts(&changes);
+
         Ok(())
     }
 }
@@ -148,8 +155,42 @@ impl WorkspaceStatusCmd {
                     StagedEntry::empty_status(StagedEntryStatus::Modified),
                 )
             }));
-        status.staged_files = added_files.into_iter().chain(added_mods).collect();
+        let removed_files: HashMap<PathBuf, StagedEntry> =
+            HashMap::from_iter(remote_status.removed_files.entries.into_iter().map(|e| {
+                (
+                    PathBuf::from(e.filename()),
+                    StagedEntry::empty_status(StagedEntryStatus::Removed),
+                )
+            }));
+        status.staged_files = added_files
+            .into_iter()
+            .chain(added_mods)
+            .chain(removed_files)
+            .collect();
 
         Ok(status)
     }
+
+    fn print_row_counts(changes: &WorkspaceChanges) {
+        let edited: Vec<_> = changes
+            .modified_files
+            .iter()
+            .filter_map(|file| file.row_counts.as_ref().map(|counts| (&file.path, counts)))
+            .collect();
+        if edited.is_empty() {
+            return;
+        }
+
+        println!("Data frames edited since commit {}", changes.base_commit_id);
+        for (path, counts) in edited {
+            println!(
+                "  {}  {} {} {}",
+                path.display(),
+                format!("+{} added", counts.added).green(),
+                format!("-{} removed", counts.removed).red(),
+                format!("~{} modified", counts.modified).yellow(),
+            );
+        }
+        println!();
+    }
 }
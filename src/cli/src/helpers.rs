@@ -6,13 +6,28 @@ use liboxen::error::OxenError;
 use liboxen::model::LocalRepository;
 use liboxen::util::oxen_version::OxenVersion;
 
+use clap::ArgMatches;
 use colored::Colorize;
+use serde::Serialize;
 
 use std::collections::HashMap;
 use std::str::FromStr;
 
 use liboxen::command::migrate::AddChildCountsToNodesMigration;
 
+/// The path a command with its own `--output <path>` writes to. The global `--output` is
+/// copied into the args of every command, so `text` and `json` are always the output format.
+pub fn get_output_path(args: &ArgMatches) -> Option<&String> {
+    args.get_one::<String>("output")
+        .filter(|output| *output != "text" && *output != "json")
+}
+
+/// Print a response for `oxen --output json`
+pub fn print_json(response: &impl Serialize) -> Result<(), OxenError> {
+    println!("{}", serde_json::to_string_pretty(response)?);
+    Ok(())
+}
+
 pub fn get_scheme_and_host_or_default() -> Result<(String, String), OxenError> {
     let config = AuthConfig::get_or_create()?;
     let mut default_host = (
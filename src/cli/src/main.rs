@@ -1,11 +1,15 @@
 use std::collections::HashMap;
 use std::process::ExitCode;
+use std::time::Instant;
 
 use crate::cmd::RemoteModeCmd;
 use crate::cmd::WorkspaceCmd;
-use clap::Command;
+use clap::{Arg, Command};
+use liboxen::error::OxenError;
 use liboxen::model::LocalRepository;
 use liboxen::util;
+use liboxen::util::telemetry;
+use liboxen::view::StatusMessage;
 // use env_logger::Env;
 
 pub mod cmd;
@@ -29,40 +33,67 @@ async fn main() -> ExitCode {
 
     let cmds: Vec<Box<dyn cmd::RunCmd>> = vec![
         Box::new(cmd::AddCmd),
+        Box::new(cmd::AppendCmd),
+        Box::new(cmd::ArchiveCmd),
+        Box::new(cmd::BisectCmd),
+        Box::new(cmd::BlameCmd),
         Box::new(cmd::BranchCmd),
+        Box::new(cmd::BundleCmd),
+        Box::new(cmd::CacheCmd),
         Box::new(cmd::CheckoutCmd),
         Box::new(cmd::CloneCmd),
         Box::new(cmd::CommitCmd),
         Box::new(cmd::ConfigCmd),
+        Box::new(cmd::ConflictsCmd),
         Box::new(cmd::CreateRemoteCmd),
         Box::new(cmd::DbCmd),
+        Box::new(cmd::DedupCmd),
         Box::new(cmd::DeleteRemoteCmd),
         Box::new(cmd::DFCmd),
         Box::new(cmd::DiffCmd),
         Box::new(cmd::DownloadCmd),
         Box::new(cmd::FetchCmd),
+        Box::new(cmd::ForkCancelCmd),
         Box::new(cmd::EmbeddingsCmd),
+        Box::new(cmd::FsckCmd),
+        Box::new(cmd::GcCmd),
+        Box::new(cmd::ImportCmd),
         Box::new(cmd::InfoCmd),
         Box::new(cmd::InitCmd),
         Box::new(cmd::LoadCmd),
         Box::new(cmd::LogCmd),
+        Box::new(cmd::LsCmd),
         Box::new(cmd::MergeCmd),
         Box::new(cmd::MigrateCmd),
         Box::new(cmd::MooCmd),
+        Box::new(cmd::MvCmd),
         Box::new(cmd::NodeCmd),
         Box::new(cmd::NotebookCmd),
         // Box::new(cmd::PackCmd),
+        Box::new(cmd::PrCmd),
         Box::new(cmd::PullCmd),
         Box::new(cmd::PushCmd),
+        Box::new(cmd::QueryCmd),
+        Box::new(cmd::ReflogCmd),
+        Box::new(cmd::ResolveCmd),
         Box::new(cmd::RestoreCmd),
         Box::new(cmd::RemoteCmd),
         Box::new(cmd::RmCmd),
         Box::new(cmd::SaveCmd),
         Box::new(cmd::SchemasCmd),
+        Box::new(cmd::ShowCmd),
+        Box::new(cmd::SparseCheckoutCmd),
+        Box::new(cmd::SquashCmd),
+        Box::new(cmd::StashCmd),
+        Box::new(cmd::TagCmd),
+        Box::new(cmd::StatsCmd),
         Box::new(cmd::StatusCmd),
+        Box::new(cmd::TelemetryCmd),
         Box::new(cmd::TreeCmd),
         Box::new(cmd::UploadCmd),
         // Box::new(cmd::UnpackCmd),
+        Box::new(cmd::VerifyRemoteCmd),
+        Box::new(cmd::WhoamiCmd),
         Box::new(cmd::WorkspaceCmd),
     ];
 
@@ -72,7 +103,8 @@ async fn main() -> ExitCode {
         .long_about(LONG_ABOUT)
         .subcommand_required(true)
         .arg_required_else_help(true)
-        .allow_external_subcommands(true);
+        .allow_external_subcommands(true)
+        .arg(output_arg());
 
     // Add all the commands to the command line
     let mut runners: HashMap<String, Box<dyn cmd::RunCmd>> = HashMap::new();
@@ -88,6 +120,7 @@ async fn main() -> ExitCode {
 
     // Parse the command line args and run the appropriate command
     let matches = command.get_matches();
+    let as_json = matches.get_one::<String>("output").map(String::as_str) == Some("json");
     match matches.subcommand() {
         // TODO: Get these in the help command instead of just falling back
         Some((command, args)) => {
@@ -96,6 +129,17 @@ async fn main() -> ExitCode {
                 // If in a remote-mode repo, re-route to correct command
                 if is_remote_repo {
                     match command {
+                        // The re-routed commands only print text
+                        "add" | "df" | "diff" | "restore" | "rm" | "commit" | "checkout"
+                        | "status"
+                            if as_json =>
+                        {
+                            let err = OxenError::basic_str(format!(
+                                "`oxen --output json {command}` is not supported for remote-mode repositories"
+                            ));
+                            print_error(&err, as_json);
+                            return ExitCode::FAILURE;
+                        }
                         // Workspace commands
                         "add" | "df" | "diff" | "restore" | "rm" => {
                             match WorkspaceCmd::run_subcommands(command, args).await {
@@ -128,10 +172,20 @@ async fn main() -> ExitCode {
                     }
                 }
 
-                match runner.run(args).await {
+                let start = Instant::now();
+                let result = if as_json {
+                    runner.run_json(args).await
+                } else {
+                    runner.run(args).await
+                };
+                // Only records anything if the user ran `oxen telemetry on`
+                if command != cmd::telemetry::NAME {
+                    telemetry::record(command, start.elapsed(), result.is_ok()).await;
+                }
+                match result {
                     Ok(_) => {}
                     Err(err) => {
-                        eprintln!("{err}");
+                        print_error(&err, as_json);
                         return ExitCode::FAILURE;
                     }
                 }
@@ -145,3 +199,84 @@ async fn main() -> ExitCode {
 
     ExitCode::SUCCESS
 }
+
+/// `--output json` can go before or after the command. Commands with an `--output <path>`
+/// of their own, such as `oxen df`, share it, see [`helpers::get_output_path`].
+fn output_arg() -> Arg {
+    Arg::new("output")
+        .long("output")
+        .help("Print text, or JSON for scripts, ex: oxen status --output json")
+        .value_parser(["text", "json"])
+        .global(true)
+        .action(clap::ArgAction::Set)
+}
+
+/// With `--output json` errors are printed to stdout as a status message, so scripts only
+/// have to parse one stream
+fn print_error(err: &OxenError, as_json: bool) {
+    if as_json {
+        let message = StatusMessage::error(err.to_string());
+        match serde_json::to_string_pretty(&message) {
+            Ok(json) => println!("{json}"),
+            Err(_) => eprintln!("{err}"),
+        }
+    } else {
+        eprintln!("{err}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Command;
+
+    use crate::cmd::{DFCmd, RunCmd, StatusCmd, WorkspaceCmd};
+    use crate::helpers::get_output_path;
+
+    /// The output format and the output path the deepest command sees
+    fn output(args: &[&str]) -> (Option<String>, Option<String>) {
+        let command = Command::new("oxen")
+            .arg(super::output_arg())
+            .subcommand(StatusCmd.args())
+            .subcommand(DFCmd.args())
+            .subcommand(WorkspaceCmd.args());
+        let matches = command.try_get_matches_from(args).unwrap();
+        let format = matches.get_one::<String>("output").cloned();
+        let mut sub_matches = &matches;
+        while let Some((_, next)) = sub_matches.subcommand() {
+            sub_matches = next;
+        }
+        (format, get_output_path(sub_matches).cloned())
+    }
+
+    #[test]
+    fn test_output_before_or_after_the_command() {
+        let json = Some("json".to_string());
+        assert_eq!(
+            output(&["oxen", "--output", "json", "status"]),
+            (json.clone(), None)
+        );
+        assert_eq!(
+            output(&["oxen", "status", "--output", "json"]),
+            (json.clone(), None)
+        );
+        assert_eq!(output(&["oxen", "status"]), (None, None));
+        assert_eq!(
+            output(&["oxen", "--output", "json", "df", "data.csv"]),
+            (json, None)
+        );
+        // df keeps its own --output for the file it writes
+        let (_, df_output) = output(&["oxen", "df", "data.csv", "--output", "out.parquet"]);
+        assert_eq!(df_output, Some("out.parquet".to_string()));
+        // as do nested commands, the global value is copied all the way down
+        let args = [
+            "oxen",
+            "--output",
+            "json",
+            "workspace",
+            "df",
+            "get",
+            "data.csv",
+        ];
+        assert_eq!(output(&args), (Some("json".to_string()), None));
+    }
+}
@@ -19,18 +19,26 @@ pub mod compare;
 pub mod data_frames;
 pub mod diff;
 pub mod dir;
+pub mod embeddings;
 pub mod entries;
 pub mod file;
+pub mod fork;
+pub mod jobs;
 pub mod merger;
 pub mod metadata;
 pub mod notebooks;
 pub mod oxen_version;
+pub mod proposals;
 pub mod repositories;
 pub mod revisions;
 pub mod schemas;
 pub mod stats;
+pub mod tags;
 pub mod tree;
+pub mod uploads;
+pub mod users;
 pub mod versions;
+pub mod webhooks;
 pub mod workspaces;
 
 const VERSION: &str = crate::constants::OXEN_VERSION;
@@ -238,6 +246,12 @@ fn parse_status_and_message(
                 }
             }
 
+            if response.error_type() == Some(http::MSG_NON_FAST_FORWARD) {
+                return Err(OxenError::NonFastForward(
+                    response.error_detail().unwrap_or_default().into(),
+                ));
+            }
+
             Err(OxenError::basic_str(response.full_err_msg()))
         }
         status => Err(OxenError::basic_str(format!("Unknown status [{status}]"))),
@@ -1,12 +1,14 @@
 use crate::api;
 use crate::api::client;
+use crate::config::BranchProtectionRule;
 use crate::error::OxenError;
-use crate::model::{Branch, Commit, LocalRepository, RemoteRepository};
+use crate::model::{Branch, BranchCompare, Commit, LocalRepository, RemoteRepository};
 use crate::opts::PaginateOpts;
 use crate::view::{
-    BranchLockResponse, BranchNewFromBranchName, BranchNewFromCommitId, BranchRemoteMerge,
-    BranchResponse, CommitResponse, ListBranchesResponse, PaginatedEntryVersions,
-    PaginatedEntryVersionsResponse, StatusMessage,
+    BranchCompareResponse, BranchLockResponse, BranchNewFromBranchName, BranchNewFromCommitId,
+    BranchProtectionRuleResponse, BranchRemoteMerge, BranchResponse, BranchUpdate, CommitResponse,
+    ListBranchProtectionRulesResponse, ListBranchesResponse, PaginatedEntryVersions,
+    PaginatedEntryVersionsResponse, RefSignature, StatusMessage,
 };
 use serde_json::json;
 use std::path::Path;
@@ -15,6 +17,16 @@ pub async fn get_by_name(
     repository: &RemoteRepository,
     branch_name: impl AsRef<str>,
 ) -> Result<Option<Branch>, OxenError> {
+    Ok(get_signed_by_name(repository, branch_name)
+        .await?
+        .map(|(branch, _signature)| branch))
+}
+
+/// Get a branch along with the server's signature of its head, if the server signs refs
+pub async fn get_signed_by_name(
+    repository: &RemoteRepository,
+    branch_name: impl AsRef<str>,
+) -> Result<Option<(Branch, Option<RefSignature>)>, OxenError> {
     let branch_name = branch_name.as_ref();
     let uri = format!("/branches/{branch_name}");
     let url = api::endpoint::url_from_repo(repository, &uri)?;
@@ -28,7 +40,7 @@ pub async fn get_by_name(
 
     let body = client::parse_json_body(&url, res).await?;
     let response: BranchResponse = serde_json::from_str(&body)?;
-    Ok(Some(response.branch))
+    Ok(Some((response.branch, response.signature)))
 }
 
 /// Create a new branch from an existing branch
@@ -119,12 +131,38 @@ pub async fn update(
     branch_name: impl AsRef<str>,
     commit: &Commit,
 ) -> Result<Branch, OxenError> {
-    let branch_name = branch_name.as_ref();
+    let update = BranchUpdate {
+        commit_id: commit.id.clone(),
+        expected_commit_id: None,
+    };
+    put_update(repository, branch_name.as_ref(), &update).await
+}
+
+/// Update a remote branch to point to a new commit only if it still points to
+/// `expected_commit_id`. Fails with `OxenError::NonFastForward` if another push moved it.
+pub async fn compare_and_update(
+    repository: &RemoteRepository,
+    branch_name: impl AsRef<str>,
+    expected_commit_id: impl AsRef<str>,
+    commit: &Commit,
+) -> Result<Branch, OxenError> {
+    let update = BranchUpdate {
+        commit_id: commit.id.clone(),
+        expected_commit_id: Some(expected_commit_id.as_ref().to_string()),
+    };
+    put_update(repository, branch_name.as_ref(), &update).await
+}
+
+async fn put_update(
+    repository: &RemoteRepository,
+    branch_name: &str,
+    update: &BranchUpdate,
+) -> Result<Branch, OxenError> {
     let uri = format!("/branches/{branch_name}");
     let url = api::endpoint::url_from_repo(repository, &uri)?;
     log::debug!("api::client::branches::update url: {}", url);
 
-    let params = serde_json::to_string(&json!({ "commit_id": commit.id }))?;
+    let params = serde_json::to_string(update)?;
     let client = client::new_for_url(&url)?;
     let res = client.put(&url).body(params).send().await?;
     let body = client::parse_json_body(&url, res).await?;
@@ -270,6 +308,69 @@ pub async fn list_entry_versions(
     Ok(response.versions)
 }
 
+/// Compare the head branch to the base branch, see [`BranchCompare`]
+pub async fn compare(
+    repository: &RemoteRepository,
+    base_branch: &str,
+    head_branch: &str,
+    page_opts: &PaginateOpts,
+) -> Result<BranchCompare, OxenError> {
+    let uri = format!(
+        "/branches/{base_branch}/compare/{head_branch}?page={}&page_size={}",
+        page_opts.page_num, page_opts.page_size
+    );
+    let url = api::endpoint::url_from_repo(repository, &uri)?;
+    log::debug!("Comparing branches: {}", url);
+    let client = client::new_for_url(&url)?;
+    let res = client.get(&url).send().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: BranchCompareResponse = serde_json::from_str(&body)?;
+    Ok(response.compare)
+}
+
+/// List the branch protection rules on the remote
+pub async fn list_protection_rules(
+    repository: &RemoteRepository,
+) -> Result<Vec<BranchProtectionRule>, OxenError> {
+    let url = api::endpoint::url_from_repo(repository, "/protected_branches")?;
+    log::debug!("Listing branch protection rules: {}", url);
+    let client = client::new_for_url(&url)?;
+    let res = client.get(&url).send().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: ListBranchProtectionRulesResponse = serde_json::from_str(&body)?;
+    Ok(response.rules)
+}
+
+/// Add or replace a branch protection rule on the remote
+pub async fn protect(
+    repository: &RemoteRepository,
+    rule: &BranchProtectionRule,
+) -> Result<BranchProtectionRule, OxenError> {
+    let url = api::endpoint::url_from_repo(repository, "/protected_branches")?;
+    log::debug!("Protecting branch {:?}: {}", rule.branch, url);
+    let params = serde_json::to_string(rule)?;
+    let client = client::new_for_url(&url)?;
+    let res = client.put(&url).body(params).send().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: BranchProtectionRuleResponse = serde_json::from_str(&body)?;
+    Ok(response.rule)
+}
+
+/// Remove the branch protection rule for a branch name or pattern on the remote
+pub async fn unprotect(
+    repository: &RemoteRepository,
+    branch: &str,
+) -> Result<BranchProtectionRule, OxenError> {
+    let uri = format!("/protected_branches/{branch}");
+    let url = api::endpoint::url_from_repo(repository, &uri)?;
+    log::debug!("Unprotecting branch: {}", url);
+    let client = client::new_for_url(&url)?;
+    let res = client.delete(&url).send().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: BranchProtectionRuleResponse = serde_json::from_str(&body)?;
+    Ok(response.rule)
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -277,7 +378,7 @@ mod tests {
 
     use crate::api;
     use crate::command;
-    use crate::config::UserConfig;
+    use crate::config::{BranchProtectionRule, UserConfig};
     use crate::constants;
     use crate::constants::DEFAULT_BRANCH_NAME;
     use crate::error::OxenError;
@@ -342,6 +443,48 @@ mod tests {
         .await
     }
 
+    #[tokio::test]
+    async fn test_compare_remote_branches() -> Result<(), OxenError> {
+        test::run_empty_remote_repo_test(|mut local_repo, remote_repo| async move {
+            let new_file = local_repo.path.join("new_file.txt");
+            util::fs::write(&new_file, "I am a new file")?;
+            repositories::add(&local_repo, new_file).await?;
+            repositories::commit(&local_repo, "Added a new file")?;
+
+            let remote = test::repo_remote_url_from(&local_repo.dirname());
+            command::config::set_remote(&mut local_repo, constants::DEFAULT_REMOTE_NAME, &remote)?;
+
+            // Push main, then a branch with one more commit
+            repositories::push(&local_repo).await?;
+            repositories::branches::create_checkout(&local_repo, "feature")?;
+            let other_file = local_repo.path.join("other_file.txt");
+            util::fs::write(&other_file, "I am on a branch")?;
+            repositories::add(&local_repo, other_file).await?;
+            repositories::commit(&local_repo, "Added a file on a branch")?;
+            repositories::push::push_remote_branch(
+                &local_repo,
+                constants::DEFAULT_REMOTE_NAME,
+                "feature",
+            )
+            .await?;
+
+            let compare = api::client::branches::compare(
+                &remote_repo,
+                DEFAULT_BRANCH_NAME,
+                "feature",
+                &PaginateOpts::default(),
+            )
+            .await?;
+            assert_eq!(compare.ahead, 1);
+            assert_eq!(compare.behind, 0);
+            assert!(compare.is_mergeable);
+            assert_eq!(compare.counts.added, 1);
+
+            Ok(remote_repo)
+        })
+        .await
+    }
+
     #[tokio::test]
     async fn test_get_branch_by_name() -> Result<(), OxenError> {
         test::run_empty_remote_repo_test(|mut local_repo, remote_repo| async move {
@@ -452,6 +595,46 @@ mod tests {
         .await
     }
 
+    #[tokio::test]
+    async fn test_protected_branch_can_not_be_deleted() -> Result<(), OxenError> {
+        test::run_empty_remote_repo_test(|mut local_repo, remote_repo| async move {
+            let new_file = local_repo.path.join("new_file.txt");
+            util::fs::write(&new_file, "I am a new file")?;
+            repositories::add(&local_repo, new_file).await?;
+            repositories::commit(&local_repo, "Added a new file")?;
+
+            let remote = test::repo_remote_url_from(&local_repo.dirname());
+            command::config::set_remote(&mut local_repo, constants::DEFAULT_REMOTE_NAME, &remote)?;
+            repositories::push(&local_repo).await?;
+
+            let branch_name = "release/1.0";
+            api::client::branches::create_from_branch(
+                &remote_repo,
+                branch_name,
+                DEFAULT_BRANCH_NAME,
+            )
+            .await?;
+
+            let rule = BranchProtectionRule::new("release/*");
+            api::client::branches::protect(&remote_repo, &rule).await?;
+            let rules = api::client::branches::list_protection_rules(&remote_repo).await?;
+            assert_eq!(rules, vec![rule]);
+
+            let result = api::client::branches::delete(&remote_repo, branch_name).await;
+            assert!(result.is_err());
+            let branch = api::client::branches::get_by_name(&remote_repo, branch_name).await?;
+            assert!(branch.is_some());
+
+            api::client::branches::unprotect(&remote_repo, "release/*").await?;
+            api::client::branches::delete(&remote_repo, branch_name).await?;
+            let branch = api::client::branches::get_by_name(&remote_repo, branch_name).await?;
+            assert!(branch.is_none());
+
+            Ok(remote_repo)
+        })
+        .await
+    }
+
     #[tokio::test]
     async fn test_latest_synced_commit_no_lock() -> Result<(), OxenError> {
         test::run_empty_remote_repo_test(|mut local_repo, remote_repo| async move {
@@ -1,6 +1,7 @@
 use crate::api::client;
 use crate::constants::{DEFAULT_PAGE_NUM, DIRS_DIR, DIR_HASHES_DIR, HISTORY_DIR};
 
+use crate::core::cache::CacheJobRecord;
 use crate::error::OxenError;
 use crate::model::commit::CommitWithBranchName;
 use crate::model::entry::unsynced_commit_entry::UnsyncedCommitEntries;
@@ -13,8 +14,8 @@ use crate::{api, constants, repositories};
 use crate::{current_function, util};
 // use crate::util::ReadProgress;
 use crate::view::{
-    CommitResponse, ListCommitResponse, MerkleHashesResponse, PaginatedCommits, RootCommitResponse,
-    StatusMessage,
+    CacheJobResponse, CacheJobsResponse, CommitResponse, ListCommitResponse, MerkleHashesResponse,
+    PaginatedCommits, RootCommitResponse, StatusMessage,
 };
 
 use std::collections::HashSet;
@@ -245,6 +246,94 @@ pub async fn list_commit_history_paginated(
     }
 }
 
+/// Search the history of a revision on the remote by commit message and metadata
+pub async fn search_commits_paginated(
+    remote_repo: &RemoteRepository,
+    revision: &str,
+    pattern: &str,
+    page_opts: &PaginateOpts,
+) -> Result<PaginatedCommits, OxenError> {
+    let page_num = page_opts.page_num;
+    let page_size = page_opts.page_size;
+    let pattern = urlencoding::encode(pattern);
+    let revision = urlencoding::encode(revision);
+    let uri = format!(
+        "/commits/search?q={pattern}&revision={revision}&page={page_num}&page_size={page_size}"
+    );
+    let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
+
+    let client = client::new_for_url(&url)?;
+    match client.get(&url).send().await {
+        Ok(res) => {
+            let body = client::parse_json_body(&url, res).await?;
+            let response: Result<PaginatedCommits, serde_json::Error> = serde_json::from_str(&body);
+            match response {
+                Ok(j_res) => Ok(j_res),
+                Err(err) => Err(OxenError::basic_str(format!(
+                    "search_commits() Could not deserialize response [{err}]\n{body}"
+                ))),
+            }
+        }
+        Err(err) => Err(OxenError::basic_str(format!(
+            "search_commits() Request failed: {err}"
+        ))),
+    }
+}
+
+/// List the post-commit cache jobs the remote has run for a commit
+pub async fn list_cache_jobs(
+    remote_repo: &RemoteRepository,
+    commit_id: &str,
+) -> Result<Vec<CacheJobRecord>, OxenError> {
+    let uri = format!("/commits/{commit_id}/cache");
+    let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
+
+    let client = client::new_for_url(&url)?;
+    match client.get(&url).send().await {
+        Ok(res) => {
+            let body = client::parse_json_body(&url, res).await?;
+            let response: Result<CacheJobsResponse, serde_json::Error> =
+                serde_json::from_str(&body);
+            match response {
+                Ok(j_res) => Ok(j_res.jobs),
+                Err(err) => Err(OxenError::basic_str(format!(
+                    "list_cache_jobs() Could not deserialize response [{err}]\n{body}"
+                ))),
+            }
+        }
+        Err(err) => Err(OxenError::basic_str(format!(
+            "list_cache_jobs() Request failed: {err}"
+        ))),
+    }
+}
+
+/// Ask the remote to run a cacher on a commit again
+pub async fn recompute_cache_job(
+    remote_repo: &RemoteRepository,
+    commit_id: &str,
+    cacher: &str,
+) -> Result<CacheJobRecord, OxenError> {
+    let uri = format!("/commits/{commit_id}/cache/{cacher}");
+    let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
+
+    let client = client::new_for_url(&url)?;
+    match client.post(&url).send().await {
+        Ok(res) => {
+            let body = client::parse_json_body(&url, res).await?;
+            let response: Result<CacheJobResponse, serde_json::Error> = serde_json::from_str(&body);
+            match response {
+                Ok(j_res) => Ok(j_res.job),
+                Err(err) => Err(OxenError::basic_str(format!(
+                    "recompute_cache_job() Could not deserialize response [{err}]\n{body}"
+                ))),
+            }
+        }
+        Err(err) => Err(OxenError::basic_str(format!(
+            "recompute_cache_job() Request failed: {err}"
+        ))),
+    }
+}
+
 async fn list_all_commits_paginated(
     remote_repo: &RemoteRepository,
     page_opts: &PaginateOpts,
@@ -309,6 +398,23 @@ pub async fn download_dir_hashes_from_commit(
     download_dir_hashes_from_url(url, path).await
 }
 
+/// Download the dir hashes of the last `history_depth` commits of the history of a commit
+pub async fn download_dir_hashes_from_commit_with_history_depth(
+    remote_repo: &RemoteRepository,
+    commit_id: &str,
+    history_depth: usize,
+    path: impl AsRef<Path>,
+) -> Result<PathBuf, OxenError> {
+    let uri = format!("/commits/{commit_id}/download_dir_hashes_db?history_depth={history_depth}");
+    let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
+    log::debug!(
+        "calling download_dir_hashes_from_commit_with_history_depth for commit {} depth {}",
+        commit_id,
+        history_depth
+    );
+    download_dir_hashes_from_url(url, path).await
+}
+
 pub async fn download_base_head_dir_hashes(
     remote_repo: &RemoteRepository,
     base_commit_id: &str,
@@ -1025,11 +1131,14 @@ mod tests {
     use crate::command;
     use crate::constants;
     use crate::constants::DEFAULT_BRANCH_NAME;
+    use crate::core::cache::cachers::content_validator;
     use crate::error::OxenError;
 
     use crate::model::MerkleHash;
+    use crate::opts::PaginateOpts;
     use crate::repositories;
     use crate::test;
+    use crate::util;
 
     use std::str::FromStr;
 
@@ -1064,6 +1173,89 @@ mod tests {
         .await
     }
 
+    #[tokio::test]
+    async fn test_search_remote_commits() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed_async(|local_repo| async move {
+            let mut local_repo = local_repo;
+            let path = local_repo.path.join("cleanup.txt");
+            util::fs::write_to_path(&path, "cleanup")?;
+            repositories::add(&local_repo, &path).await?;
+            let commit =
+                repositories::commit(&local_repo, "Removed the corrupted batch from train")?;
+
+            // Set the proper remote
+            let name = local_repo.dirname();
+            let remote = test::repo_remote_url_from(&name);
+            command::config::set_remote(&mut local_repo, constants::DEFAULT_REMOTE_NAME, &remote)?;
+
+            // Create Remote
+            let remote_repo = test::create_remote_repo(&local_repo).await?;
+
+            // Push it
+            repositories::push(&local_repo).await?;
+
+            let page_opts = PaginateOpts {
+                page_num: 1,
+                page_size: 10,
+            };
+            let results = api::client::commits::search_commits_paginated(
+                &remote_repo,
+                DEFAULT_BRANCH_NAME,
+                "corrupted batch",
+                &page_opts,
+            )
+            .await?;
+            assert_eq!(results.commits.len(), 1);
+            assert_eq!(results.commits[0].id, commit.id);
+            assert_eq!(results.pagination.total_entries, 1);
+
+            api::client::repositories::delete(&remote_repo).await?;
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_remote_cache_jobs() -> Result<(), OxenError> {
+        test::run_one_commit_local_repo_test_async(|local_repo| async move {
+            let mut local_repo = local_repo;
+            let commit = repositories::commits::head_commit(&local_repo)?;
+
+            // Set the proper remote
+            let name = local_repo.dirname();
+            let remote = test::repo_remote_url_from(&name);
+            command::config::set_remote(&mut local_repo, constants::DEFAULT_REMOTE_NAME, &remote)?;
+
+            // Create Remote
+            let remote_repo = test::create_remote_repo(&local_repo).await?;
+
+            // Push it, which queues the cachers on the server
+            repositories::push(&local_repo).await?;
+
+            let job = api::client::commits::recompute_cache_job(
+                &remote_repo,
+                &commit.id,
+                content_validator::NAME,
+            )
+            .await?;
+            assert_eq!(job.cacher, content_validator::NAME);
+            assert_eq!(job.commit_id, commit.id);
+
+            let jobs = api::client::commits::list_cache_jobs(&remote_repo, &commit.id).await?;
+            assert!(jobs.iter().any(|job| job.cacher == content_validator::NAME));
+
+            let result =
+                api::client::commits::recompute_cache_job(&remote_repo, &commit.id, "fake").await;
+            assert!(result.is_err());
+
+            api::client::repositories::delete(&remote_repo).await?;
+
+            Ok(())
+        })
+        .await
+    }
+
     /* Commented out because it's expensive to find the initial commit id
     #[tokio::test]
     async fn test_list_commit_history_for_path() -> Result<(), OxenError> {
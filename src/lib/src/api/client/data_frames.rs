@@ -1,12 +1,17 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+use polars::prelude::DataFrame;
+use regex::Regex;
 
 use crate::api;
 use crate::api::client;
+use crate::constants;
 use crate::error::OxenError;
-use crate::model::RemoteRepository;
-use crate::opts::DFOpts;
+use crate::model::{Commit, NewCommitBody, RemoteRepository};
+use crate::opts::{AppendOpts, DFOpts, PaginateOpts};
 use crate::util;
-use crate::view::{JsonDataFrameViewResponse, StatusMessage};
+use crate::view::data_frames::DataFrameSqlRequest;
+use crate::view::{AppendResponse, JsonDataFrameViewResponse, StatusMessage};
 
 pub async fn get(
     remote_repo: &RemoteRepository,
@@ -36,6 +41,65 @@ pub async fn get(
     }
 }
 
+/// Run a SQL query against a data frame on the server without downloading it. The data
+/// frame is named by its path in the FROM clause, ie `SELECT * FROM 'train.csv'`, and every
+/// page of the result is fetched.
+pub async fn query(
+    remote_repo: &RemoteRepository,
+    commit_or_branch: &str,
+    sql: &str,
+) -> Result<DataFrame, OxenError> {
+    let (path, sql) = sql_with_df_table(sql)?;
+
+    // The server queries an indexed copy of the data frame, build it if this is the first query
+    if let Err(err) = index(remote_repo, commit_or_branch, &path).await {
+        log::debug!("query could not index {path:?}, it may already be indexed: {err}");
+    }
+
+    let mut result: Option<DataFrame> = None;
+    let mut page = 1;
+    loop {
+        let mut opts = DFOpts::empty();
+        opts.sql = Some(sql.clone());
+        opts.page = Some(page);
+        opts.page_size = Some(constants::DEFAULT_PAGE_SIZE);
+        let response = get(remote_repo, commit_or_branch, &path, opts).await?;
+        let view = &response.data_frame.view;
+        let df = view.to_df();
+        match result.as_mut() {
+            Some(result) => {
+                result.vstack_mut(&df)?;
+            }
+            None => result = Some(df),
+        }
+        if page >= view.pagination.total_pages {
+            break;
+        }
+        page += 1;
+    }
+    Ok(result.unwrap_or_else(DataFrame::empty))
+}
+
+/// Pull the quoted data frame path out of the FROM clause and point the query at the
+/// server's `df` table instead
+fn sql_with_df_table(sql: &str) -> Result<(PathBuf, String), OxenError> {
+    let re = Regex::new(r#"(?i)\bFROM\s+(?:'([^']+)'|"([^"]+)"|`([^`]+)`)"#).unwrap();
+    let Some(captures) = re.captures(sql) else {
+        return Err(OxenError::basic_str(
+            "Name the data frame to query by its path in the FROM clause, ie SELECT * FROM 'train.csv'",
+        ));
+    };
+    let path = captures
+        .iter()
+        .skip(1)
+        .flatten()
+        .next()
+        .map(|m| m.as_str())
+        .unwrap_or_default();
+    let sql = re.replace(sql, "FROM df").to_string();
+    Ok((PathBuf::from(path), sql))
+}
+
 pub async fn index(
     remote_repo: &RemoteRepository,
     commit_or_branch: &str,
@@ -62,6 +126,137 @@ pub async fn index(
     }
 }
 
+/// Run a SQL query against the data frame at `path` on any branch or commit, with the
+/// data frame as the table `df`. Returns one page of the result.
+pub async fn sql(
+    remote_repo: &RemoteRepository,
+    commit_or_branch: &str,
+    path: impl AsRef<Path>,
+    sql: &str,
+    page_opts: &PaginateOpts,
+) -> Result<JsonDataFrameViewResponse, OxenError> {
+    let path_str = util::fs::to_unix_str(path);
+    let uri = format!(
+        "/data_frames/sql/{commit_or_branch}/{path_str}?page={}&page_size={}",
+        page_opts.page_num, page_opts.page_size
+    );
+    let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
+
+    let client = client::new_for_url(&url)?;
+    let body = DataFrameSqlRequest {
+        sql: sql.to_string(),
+    };
+    let res = client.post(&url).json(&body).send().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: Result<JsonDataFrameViewResponse, serde_json::Error> =
+        serde_json::from_str(&body);
+    match response {
+        Ok(val) => Ok(val),
+        Err(err) => Err(OxenError::basic_str(format!(
+            "error parsing response from {url}\n\nErr {err:?} \n\n{body}"
+        ))),
+    }
+}
+
+/// Append rows to a data frame on a branch and commit them on the server, without a local
+/// clone. The rows are staged in a temporary workspace that is removed by the commit.
+pub async fn append_rows(
+    remote_repo: &RemoteRepository,
+    branch_name: &str,
+    path: impl AsRef<Path>,
+    rows: &[serde_json::Value],
+    commit: &NewCommitBody,
+) -> Result<Commit, OxenError> {
+    if rows.is_empty() {
+        return Err(OxenError::basic_str("No rows to append"));
+    }
+    let path = path.as_ref();
+    let workspace_id = uuid::Uuid::new_v4().to_string();
+    api::client::workspaces::create(remote_repo, branch_name, &workspace_id).await?;
+
+    let result =
+        stage_and_commit(remote_repo, branch_name, &workspace_id, path, rows, commit).await;
+    if result.is_err() {
+        // Don't leave the half staged workspace behind
+        if let Err(err) = api::client::workspaces::delete(remote_repo, &workspace_id).await {
+            log::error!("Could not delete workspace {workspace_id}: {err}");
+        }
+    }
+    result
+}
+
+async fn stage_and_commit(
+    remote_repo: &RemoteRepository,
+    branch_name: &str,
+    workspace_id: &str,
+    path: &Path,
+    rows: &[serde_json::Value],
+    commit: &NewCommitBody,
+) -> Result<Commit, OxenError> {
+    api::client::workspaces::data_frames::index(remote_repo, workspace_id, path).await?;
+    for row in rows {
+        api::client::workspaces::data_frames::rows::add(
+            remote_repo,
+            workspace_id,
+            path,
+            serde_json::to_string(row)?,
+        )
+        .await?;
+    }
+    api::client::workspaces::commits::commit(remote_repo, branch_name, workspace_id, commit).await
+}
+
+/// Send rows, CSV with a header or JSON, to the server's append buffer for a data frame
+/// on a branch. The server commits them once enough rows are buffered or the oldest has
+/// waited long enough, the response has the commit if this request triggered one.
+pub async fn append(
+    remote_repo: &RemoteRepository,
+    branch_name: &str,
+    path: impl AsRef<Path>,
+    data: String,
+    content_type: &str,
+    opts: &AppendOpts,
+) -> Result<AppendResponse, OxenError> {
+    let path_str = util::fs::to_unix_str(path);
+    let uri = format!("/df/{branch_name}/{path_str}/append");
+    let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
+
+    let mut query: Vec<(&str, String)> = vec![];
+    if let Some(commit_rows) = opts.commit_rows {
+        query.push(("commit_rows", commit_rows.to_string()));
+    }
+    if let Some(commit_after_secs) = opts.commit_after_secs {
+        query.push(("commit_after", commit_after_secs.to_string()));
+    }
+    if let Some(author) = &opts.author {
+        query.push(("author", author.name.clone()));
+        query.push(("email", author.email.clone()));
+    }
+    if let Some(message) = &opts.message {
+        query.push(("message", message.clone()));
+    }
+    if opts.commit {
+        query.push(("commit", "true".to_string()));
+    }
+
+    let client = client::new_for_url(&url)?;
+    let res = client
+        .post(&url)
+        .query(&query)
+        .header("Content-Type", content_type)
+        .body(data)
+        .send()
+        .await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: Result<AppendResponse, serde_json::Error> = serde_json::from_str(&body);
+    match response {
+        Ok(val) => Ok(val),
+        Err(err) => Err(OxenError::basic_str(format!(
+            "error parsing response from {url}\n\nErr {err:?} \n\n{body}"
+        ))),
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -73,13 +268,129 @@ mod tests {
     use crate::constants::DEFAULT_BRANCH_NAME;
     use crate::constants::DEFAULT_REMOTE_NAME;
     use crate::error::OxenError;
-    use crate::opts::DFOpts;
+    use crate::model::NewCommitBody;
+    use crate::opts::{DFOpts, PaginateOpts};
     use crate::repositories;
     use crate::test;
     use crate::util;
 
     use serde_json::json;
 
+    #[test]
+    fn test_sql_with_df_table() -> Result<(), OxenError> {
+        let (path, sql) = super::sql_with_df_table(
+            "SELECT label, count(*) FROM 'data/train.csv' GROUP BY label",
+        )?;
+        assert_eq!(path, PathBuf::from("data/train.csv"));
+        assert_eq!(sql, "SELECT label, count(*) FROM df GROUP BY label");
+
+        assert!(super::sql_with_df_table("SELECT 1").is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_remote_query_df() -> Result<(), OxenError> {
+        if std::env::consts::OS == "windows" {
+            return Ok(());
+        }
+
+        test::run_remote_repo_test_bounding_box_csv_pushed(|_local_repo, remote_repo| async move {
+            let df = api::client::data_frames::query(
+                &remote_repo,
+                DEFAULT_BRANCH_NAME,
+                "SELECT label, count(*) AS n FROM 'annotations/train/bounding_box.csv' GROUP BY label",
+            )
+            .await?;
+            assert_eq!(df.width(), 2);
+            assert!(df.height() > 0);
+
+            Ok(remote_repo)
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_remote_sql_at_revision() -> Result<(), OxenError> {
+        test::run_remote_repo_test_bounding_box_csv_pushed(|_local_repo, remote_repo| async move {
+            let path = PathBuf::from("annotations")
+                .join("train")
+                .join("bounding_box.csv");
+            let page_opts = PaginateOpts {
+                page_num: 1,
+                page_size: 1,
+            };
+            let response = api::client::data_frames::sql(
+                &remote_repo,
+                DEFAULT_BRANCH_NAME,
+                &path,
+                "SELECT label, count(*) AS n FROM df GROUP BY label",
+                &page_opts,
+            )
+            .await?;
+            let view = response.data_frame.view;
+            assert_eq!(view.size.width, 2);
+            assert_eq!(view.size.height, 1);
+            assert!(view.pagination.total_entries > 1);
+
+            Ok(remote_repo)
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_append_rows_commits_on_branch() -> Result<(), OxenError> {
+        test::run_remote_repo_test_bounding_box_csv_pushed(|_local_repo, remote_repo| async move {
+            let path = PathBuf::from("annotations")
+                .join("train")
+                .join("bounding_box.csv");
+            let before = api::client::data_frames::get(
+                &remote_repo,
+                DEFAULT_BRANCH_NAME,
+                &path,
+                DFOpts::empty(),
+            )
+            .await?;
+
+            let rows = vec![
+                json!({"file": "train/dog_4.jpg", "label": "dog", "min_x": 1.0, "min_y": 2.0, "width": 10, "height": 20}),
+                json!({"file": "train/cat_4.jpg", "label": "cat", "min_x": 3.0, "min_y": 4.0, "width": 30, "height": 40}),
+            ];
+            let commit_body = NewCommitBody {
+                message: "Append two rows".to_string(),
+                author: "Ox".to_string(),
+                email: "ox@oxen.ai".to_string(),
+            };
+            let commit = api::client::data_frames::append_rows(
+                &remote_repo,
+                DEFAULT_BRANCH_NAME,
+                &path,
+                &rows,
+                &commit_body,
+            )
+            .await?;
+            assert_eq!(commit.message, "Append two rows");
+
+            let after = api::client::data_frames::get(
+                &remote_repo,
+                DEFAULT_BRANCH_NAME,
+                &path,
+                DFOpts::empty(),
+            )
+            .await?;
+            assert_eq!(
+                after.data_frame.source.size.height,
+                before.data_frame.source.size.height + 2
+            );
+
+            // The temporary workspace was cleaned up by the commit
+            let workspaces = api::client::workspaces::list(&remote_repo).await?;
+            assert!(workspaces.is_empty());
+
+            Ok(remote_repo)
+        })
+        .await
+    }
+
     #[tokio::test]
     async fn test_fetch_schema_metadata() -> Result<(), OxenError> {
         test::run_empty_local_repo_test_async(|mut local_repo| async move {
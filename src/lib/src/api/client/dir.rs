@@ -6,7 +6,7 @@ use crate::constants;
 use crate::error::OxenError;
 use crate::model::metadata::generic_metadata::GenericMetadata;
 use crate::model::metadata::MetadataDir;
-use crate::model::RemoteRepository;
+use crate::model::{MetadataFilter, RemoteRepository};
 use crate::view::entries::EMetadataEntry;
 use crate::view::{PaginatedDirEntries, PaginatedDirEntriesResponse};
 
@@ -45,6 +45,56 @@ pub async fn list(
     }
 }
 
+/// List the entries in a directory whose metadata matches the filter, ie: `image.width>1024`
+pub async fn list_with_filter(
+    remote_repo: &RemoteRepository,
+    revision: impl AsRef<str>,
+    path: impl AsRef<Path>,
+    filter: &MetadataFilter,
+    page: usize,
+    page_size: usize,
+) -> Result<PaginatedDirEntries, OxenError> {
+    let revision = revision.as_ref();
+    let path = path.as_ref().to_string_lossy();
+    let filter = urlencoding::encode(&filter.to_string()).into_owned();
+    let uri = format!("/dir/{revision}/{path}?page={page}&page_size={page_size}&filter={filter}");
+    let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
+
+    let client = client::new_for_url(&url)?;
+    let res = client.get(&url).send().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: Result<PaginatedDirEntries, serde_json::Error> = serde_json::from_str(&body);
+    match response {
+        Ok(val) => Ok(val),
+        Err(err) => Err(OxenError::basic_str(format!(
+            "api::dir::list_with_filter error parsing response from {url}\n\nErr {err:?} \n\n{body}"
+        ))),
+    }
+}
+
+/// List every entry in a directory, requesting it from the server one page at a time so
+/// huge directories don't have to be sent in a single response
+pub async fn list_all(
+    remote_repo: &RemoteRepository,
+    revision: impl AsRef<str>,
+    path: impl AsRef<Path>,
+    page_size: usize,
+) -> Result<Vec<EMetadataEntry>, OxenError> {
+    let revision = revision.as_ref();
+    let path = path.as_ref();
+    let mut entries: Vec<EMetadataEntry> = vec![];
+    let mut page = constants::DEFAULT_PAGE_NUM;
+    loop {
+        let response = list(remote_repo, revision, path, page, page_size).await?;
+        entries.extend(response.entries);
+        if page >= response.total_pages {
+            break;
+        }
+        page += 1;
+    }
+    Ok(entries)
+}
+
 pub async fn file_counts(
     remote_repo: &RemoteRepository,
     revision: impl AsRef<str>,
@@ -364,6 +414,26 @@ mod tests {
         .await
     }
 
+    #[tokio::test]
+    async fn test_list_all_pages_through_dir() -> Result<(), OxenError> {
+        test::run_training_data_fully_sync_remote(|local_repo, remote_repo| async move {
+            let train_dir = local_repo.path.join("train");
+            let num_entries = util::fs::count_items_in_dir(&train_dir);
+            assert!(num_entries > 3);
+
+            let entries =
+                api::client::dir::list_all(&remote_repo, DEFAULT_BRANCH_NAME, "train", 3).await?;
+            assert_eq!(entries.len(), num_entries);
+            let mut names: Vec<&str> = entries.iter().map(|entry| entry.filename()).collect();
+            names.sort();
+            names.dedup();
+            assert_eq!(names.len(), num_entries);
+
+            Ok(remote_repo)
+        })
+        .await
+    }
+
     #[tokio::test]
     async fn test_get_dir_encoding() -> Result<(), OxenError> {
         test::run_readme_remote_repo_test(|local_repo, remote_repo| async move {
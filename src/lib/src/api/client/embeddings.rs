@@ -0,0 +1,29 @@
+use crate::api;
+use crate::api::client;
+use crate::error::OxenError;
+use crate::model::RemoteRepository;
+use crate::opts::EmbeddingNeighborsOpts;
+use crate::repositories::embeddings::EmbeddingNeighbors;
+use crate::view::EmbeddingNeighborsResponse;
+
+/// Nearest neighbors of a file or embedding in an embedding column at the revision
+pub async fn query(
+    remote_repo: &RemoteRepository,
+    revision: &str,
+    opts: &EmbeddingNeighborsOpts,
+) -> Result<EmbeddingNeighbors, OxenError> {
+    let uri = format!("/embeddings/{revision}/query");
+    let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
+
+    let client = client::new_for_url(&url)?;
+    let res = client.post(&url).json(opts).send().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: Result<EmbeddingNeighborsResponse, serde_json::Error> =
+        serde_json::from_str(&body);
+    match response {
+        Ok(val) => Ok(val.result),
+        Err(err) => Err(OxenError::basic_str(format!(
+            "error parsing response from {url}\n\nErr {err:?} \n\n{body}"
+        ))),
+    }
+}
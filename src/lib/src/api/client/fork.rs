@@ -0,0 +1,122 @@
+use crate::api;
+use crate::api::client;
+use crate::error::OxenError;
+use crate::model::RemoteRepository;
+use crate::opts::ForkOpts;
+use crate::view::fork::{ForkRequest, ForkStartResponse, ForkStatusResponse};
+
+/// Start forking the repository into `namespace`, keeping its name unless `new_repo_name`
+/// is set. The fork runs in the background, poll `get_status` on the new repository.
+pub async fn fork(
+    repository: &RemoteRepository,
+    namespace: impl AsRef<str>,
+    new_repo_name: Option<&str>,
+    opts: &ForkOpts,
+) -> Result<ForkStartResponse, OxenError> {
+    let url = api::endpoint::url_from_repo(repository, "/fork")?;
+    log::debug!("fork::fork {}", url);
+
+    let params = serde_json::to_string(&ForkRequest {
+        namespace: namespace.as_ref().to_string(),
+        new_repo_name: new_repo_name.map(String::from),
+        mode: opts.mode,
+        resume: opts.resume,
+    })?;
+    let client = client::new_for_url(&url)?;
+    let res = client.post(&url).body(params).send().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: ForkStartResponse = serde_json::from_str(&body)?;
+    Ok(response)
+}
+
+/// Status of the fork into `repository`
+pub async fn get_status(repository: &RemoteRepository) -> Result<ForkStatusResponse, OxenError> {
+    let url = api::endpoint::url_from_repo(repository, "/fork/status")?;
+
+    let client = client::new_for_url(&url)?;
+    let res = client.get(&url).send().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: ForkStatusResponse = serde_json::from_str(&body)?;
+    Ok(response)
+}
+
+/// Stop the fork into `repository`, it can be resumed with `ForkOpts::resume`
+pub async fn cancel(repository: &RemoteRepository) -> Result<ForkStatusResponse, OxenError> {
+    let url = api::endpoint::url_from_repo(repository, "/fork/cancel")?;
+    log::debug!("fork::cancel {}", url);
+
+    let client = client::new_for_url(&url)?;
+    let res = client.post(&url).send().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: ForkStatusResponse = serde_json::from_str(&body)?;
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::api;
+    use crate::constants::DEFAULT_NAMESPACE;
+    use crate::error::OxenError;
+    use crate::model::{Remote, RemoteRepository};
+    use crate::opts::ForkOpts;
+    use crate::test;
+
+    #[tokio::test]
+    async fn test_fork_remote_repo() -> Result<(), OxenError> {
+        test::run_training_data_fully_sync_remote(|_local_repo, remote_repo| async move {
+            let new_name = format!("{}-fork", remote_repo.name);
+            let response = api::client::fork::fork(
+                &remote_repo,
+                DEFAULT_NAMESPACE,
+                Some(&new_name),
+                &ForkOpts::default(),
+            )
+            .await?;
+            assert_eq!(
+                response.status_url,
+                Some(format!(
+                    "/api/repos/{DEFAULT_NAMESPACE}/{new_name}/fork/status"
+                ))
+            );
+
+            let (scheme, host) =
+                api::client::get_scheme_and_host_from_url(&remote_repo.remote.url)?;
+            let forked_repo = RemoteRepository {
+                namespace: DEFAULT_NAMESPACE.to_string(),
+                name: new_name.clone(),
+                remote: Remote {
+                    name: remote_repo.remote.name.clone(),
+                    url: api::endpoint::remote_url_from_namespace_name_scheme(
+                        &host,
+                        DEFAULT_NAMESPACE,
+                        &new_name,
+                        &scheme,
+                    ),
+                },
+                min_version: None,
+                is_empty: false,
+            };
+            let mut status = api::client::fork::get_status(&forked_repo).await?;
+            for _ in 0..100 {
+                if status.status == "complete" || status.status == "failed" {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                status = api::client::fork::get_status(&forked_repo).await?;
+            }
+            assert_eq!(status.status, "complete", "{status:?}");
+
+            // The fork can't escape the sync dir
+            let result =
+                api::client::fork::fork(&remote_repo, "..", Some(&new_name), &ForkOpts::default())
+                    .await;
+            assert!(result.is_err());
+
+            api::client::repositories::delete(&forked_repo).await?;
+            Ok(remote_repo)
+        })
+        .await
+    }
+}
@@ -0,0 +1,146 @@
+use crate::api;
+use crate::api::client;
+use crate::error::OxenError;
+use crate::model::{JobRun, RemoteRepository};
+use crate::view::{
+    JobNew, JobResponse, JobRunResponse, JobStatus, ListJobRunsResponse, ListJobsResponse,
+    StatusMessage,
+};
+
+/// List the scheduled jobs of the remote repository
+pub async fn list(repository: &RemoteRepository) -> Result<Vec<JobStatus>, OxenError> {
+    let url = api::endpoint::url_from_repo(repository, "/jobs")?;
+
+    let client = client::new_for_url(&url)?;
+    let res = client.get(&url).send().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: ListJobsResponse = serde_json::from_str(&body)?;
+    Ok(response.jobs)
+}
+
+/// Schedule a job on the remote repository
+pub async fn create(repository: &RemoteRepository, job: &JobNew) -> Result<JobStatus, OxenError> {
+    let url = api::endpoint::url_from_repo(repository, "/jobs")?;
+    log::debug!("jobs::create {}", url);
+
+    let params = serde_json::to_string(job)?;
+    let client = client::new_for_url(&url)?;
+    let res = client.post(&url).body(params).send().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: JobResponse = serde_json::from_str(&body)?;
+    Ok(response.job)
+}
+
+pub async fn get(
+    repository: &RemoteRepository,
+    id: impl AsRef<str>,
+) -> Result<JobStatus, OxenError> {
+    let id = id.as_ref();
+    let uri = format!("/jobs/{id}");
+    let url = api::endpoint::url_from_repo(repository, &uri)?;
+
+    let client = client::new_for_url(&url)?;
+    let res = client.get(&url).send().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: JobResponse = serde_json::from_str(&body)?;
+    Ok(response.job)
+}
+
+pub async fn delete(
+    repository: &RemoteRepository,
+    id: impl AsRef<str>,
+) -> Result<StatusMessage, OxenError> {
+    let id = id.as_ref();
+    let uri = format!("/jobs/{id}");
+    let url = api::endpoint::url_from_repo(repository, &uri)?;
+    log::debug!("Deleting job: {}", url);
+
+    let client = client::new_for_url(&url)?;
+    let res = client.delete(&url).send().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: StatusMessage = serde_json::from_str(&body)?;
+    Ok(response)
+}
+
+/// The run history of a job, newest first
+pub async fn runs(
+    repository: &RemoteRepository,
+    id: impl AsRef<str>,
+) -> Result<Vec<JobRun>, OxenError> {
+    let id = id.as_ref();
+    let uri = format!("/jobs/{id}/runs");
+    let url = api::endpoint::url_from_repo(repository, &uri)?;
+
+    let client = client::new_for_url(&url)?;
+    let res = client.get(&url).send().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: ListJobRunsResponse = serde_json::from_str(&body)?;
+    Ok(response.runs)
+}
+
+/// Start a run of the job now, the returned run is still running
+pub async fn run(repository: &RemoteRepository, id: impl AsRef<str>) -> Result<JobRun, OxenError> {
+    let id = id.as_ref();
+    let uri = format!("/jobs/{id}/runs");
+    let url = api::endpoint::url_from_repo(repository, &uri)?;
+    log::debug!("Running job: {}", url);
+
+    let client = client::new_for_url(&url)?;
+    let res = client.post(&url).send().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: JobRunResponse = serde_json::from_str(&body)?;
+    Ok(response.run)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api;
+    use crate::constants::DEFAULT_BRANCH_NAME;
+    use crate::error::OxenError;
+    use crate::model::{JobAction, JobRunStatus};
+    use crate::test;
+    use crate::view::JobNew;
+
+    #[tokio::test]
+    async fn test_create_run_and_delete_job() -> Result<(), OxenError> {
+        test::run_training_data_fully_sync_remote(|_local_repo, remote_repo| async move {
+            let job = api::client::jobs::create(
+                &remote_repo,
+                &JobNew {
+                    name: "nightly validation".to_string(),
+                    schedule: "0 3 * * *".to_string(),
+                    action: JobAction::Validate {
+                        branch: DEFAULT_BRANCH_NAME.to_string(),
+                    },
+                    enabled: None,
+                },
+            )
+            .await?;
+            assert!(job.next_run_at.is_some());
+            assert!(job.last_run.is_none());
+
+            let jobs = api::client::jobs::list(&remote_repo).await?;
+            assert_eq!(jobs.len(), 1);
+            assert_eq!(jobs[0].job, job.job);
+
+            let run = api::client::jobs::run(&remote_repo, &job.job.id).await?;
+            let mut runs = api::client::jobs::runs(&remote_repo, &job.job.id).await?;
+            for _ in 0..50 {
+                if runs[0].status != JobRunStatus::Running {
+                    break;
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+                runs = api::client::jobs::runs(&remote_repo, &job.job.id).await?;
+            }
+            assert_eq!(runs.len(), 1);
+            assert_eq!(runs[0].id, run.id);
+            assert_eq!(runs[0].status, JobRunStatus::Succeeded);
+
+            api::client::jobs::delete(&remote_repo, &job.job.id).await?;
+            assert!(api::client::jobs::list(&remote_repo).await?.is_empty());
+
+            Ok(remote_repo)
+        })
+        .await
+    }
+}
@@ -5,7 +5,9 @@ use crate::api;
 use crate::api::client;
 use crate::error::OxenError;
 use crate::model::RemoteRepository;
-use crate::view::merge::{MergeResult, MergeSuccessResponse, Mergeable, MergeableResponse};
+use crate::view::merge::{
+    MergeBranches, MergeResult, MergeSuccessResponse, Mergeable, MergeableResponse,
+};
 
 /// Can check the mergeability of head into base
 /// base or head are strings that can be branch names or commit ids
@@ -42,6 +44,29 @@ pub async fn merge(
     Ok(response.commits)
 }
 
+/// Merge the head branch into the base branch on the server. With `queue` the merge waits
+/// for earlier merges into base to finish instead of racing them.
+pub async fn merge_branches(
+    remote_repo: &RemoteRepository,
+    base: &str,
+    head: &str,
+    queue: bool,
+) -> Result<MergeResult, OxenError> {
+    let url = api::endpoint::url_from_repo(remote_repo, "/merge")?;
+    log::debug!("api::client::merger::merge_branches url: {url}");
+
+    let body = MergeBranches {
+        base: base.to_string(),
+        head: head.to_string(),
+        queue,
+    };
+    let client = client::new_for_url(&url)?;
+    let res = client.post(&url).json(&body).send().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: MergeSuccessResponse = serde_json::from_str(&body)?;
+    Ok(response.commits)
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -226,4 +251,30 @@ mod tests {
         })
         .await
     }
+
+    #[tokio::test]
+    async fn test_remote_merger_merge_branches_queued() -> Result<(), OxenError> {
+        test::run_training_data_fully_sync_remote(|local_repo, remote_repo| async move {
+            let base = "main";
+            let head = "queued-data";
+
+            repositories::branches::create_checkout(&local_repo, head)?;
+            let path = local_repo.path.join("queued_file.txt");
+            test::write_txt_file_to_path(&path, "hello")?;
+            repositories::add(&local_repo, &path).await?;
+            repositories::commit(&local_repo, "adding file")?;
+            repositories::push::push_remote_branch(&local_repo, DEFAULT_REMOTE_NAME, head).await?;
+
+            let result =
+                api::client::merger::merge_branches(&remote_repo, base, head, true).await?;
+
+            let remote_base = api::client::branches::get_by_name(&remote_repo, base)
+                .await?
+                .unwrap();
+            assert_eq!(remote_base.commit_id, result.merge.id);
+
+            Ok(remote_repo)
+        })
+        .await
+    }
 }
@@ -0,0 +1,221 @@
+use crate::api;
+use crate::api::client;
+use crate::error::OxenError;
+use crate::model::diff::diff_entries_counts::DiffEntriesCounts;
+use crate::model::{
+    MergeProposal, MergeProposalChecks, MergeProposalComment, MergeProposalStatus,
+    MergeProposalThread, RemoteRepository,
+};
+use crate::opts::PaginateOpts;
+use crate::view::{
+    ListMergeProposalsResponse, MergeProposalApprovalNew, MergeProposalChecksResponse,
+    MergeProposalCommentNew, MergeProposalCommentResponse, MergeProposalFilesResponse,
+    MergeProposalNew, MergeProposalResponse, MergeProposalThreadsResponse,
+};
+
+/// Propose merging one branch into another on the remote repository
+pub async fn create(
+    repository: &RemoteRepository,
+    proposal: &MergeProposalNew,
+) -> Result<MergeProposal, OxenError> {
+    let url = api::endpoint::url_from_repo(repository, "/proposals")?;
+    log::debug!("proposals::create {}", url);
+
+    let params = serde_json::to_string(proposal)?;
+    let client = client::new_for_url(&url)?;
+    let res = client.post(&url).body(params).send().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: MergeProposalResponse = serde_json::from_str(&body)?;
+    Ok(response.proposal)
+}
+
+/// List the proposals on the remote repository newest first, optionally only those with `status`
+pub async fn list(
+    repository: &RemoteRepository,
+    status: Option<MergeProposalStatus>,
+) -> Result<Vec<MergeProposal>, OxenError> {
+    let uri = match status {
+        Some(status) => format!("/proposals?status={status}"),
+        None => "/proposals".to_string(),
+    };
+    let url = api::endpoint::url_from_repo(repository, &uri)?;
+
+    let client = client::new_for_url(&url)?;
+    let res = client.get(&url).send().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: ListMergeProposalsResponse = serde_json::from_str(&body)?;
+    Ok(response.proposals)
+}
+
+pub async fn get(repository: &RemoteRepository, id: u64) -> Result<MergeProposal, OxenError> {
+    let url = api::endpoint::url_from_repo(repository, &format!("/proposals/{id}"))?;
+
+    let client = client::new_for_url(&url)?;
+    let res = client.get(&url).send().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: MergeProposalResponse = serde_json::from_str(&body)?;
+    Ok(response.proposal)
+}
+
+/// The files changed on the head branch since it split from base
+pub async fn files(
+    repository: &RemoteRepository,
+    id: u64,
+    page_opts: &PaginateOpts,
+) -> Result<DiffEntriesCounts, OxenError> {
+    let uri = format!(
+        "/proposals/{id}/files?page={}&page_size={}",
+        page_opts.page_num, page_opts.page_size
+    );
+    let url = api::endpoint::url_from_repo(repository, &uri)?;
+
+    let client = client::new_for_url(&url)?;
+    let res = client.get(&url).send().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: MergeProposalFilesResponse = serde_json::from_str(&body)?;
+    Ok(DiffEntriesCounts {
+        entries: response.entries,
+        counts: response.counts,
+        pagination: response.pagination,
+    })
+}
+
+pub async fn mergeable(
+    repository: &RemoteRepository,
+    id: u64,
+) -> Result<MergeProposalChecks, OxenError> {
+    let url = api::endpoint::url_from_repo(repository, &format!("/proposals/{id}/mergeable"))?;
+
+    let client = client::new_for_url(&url)?;
+    let res = client.get(&url).send().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: MergeProposalChecksResponse = serde_json::from_str(&body)?;
+    Ok(response.checks)
+}
+
+pub async fn threads(
+    repository: &RemoteRepository,
+    id: u64,
+) -> Result<Vec<MergeProposalThread>, OxenError> {
+    let url = api::endpoint::url_from_repo(repository, &format!("/proposals/{id}/comments"))?;
+
+    let client = client::new_for_url(&url)?;
+    let res = client.get(&url).send().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: MergeProposalThreadsResponse = serde_json::from_str(&body)?;
+    Ok(response.threads)
+}
+
+/// Comment on the proposal, or on a file or row it changes
+pub async fn comment(
+    repository: &RemoteRepository,
+    id: u64,
+    comment: &MergeProposalCommentNew,
+) -> Result<MergeProposalComment, OxenError> {
+    let url = api::endpoint::url_from_repo(repository, &format!("/proposals/{id}/comments"))?;
+
+    let params = serde_json::to_string(comment)?;
+    let client = client::new_for_url(&url)?;
+    let res = client.post(&url).body(params).send().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: MergeProposalCommentResponse = serde_json::from_str(&body)?;
+    Ok(response.comment)
+}
+
+/// Approve the head branch of the proposal as it is now
+pub async fn approve(
+    repository: &RemoteRepository,
+    id: u64,
+    approval: &MergeProposalApprovalNew,
+) -> Result<MergeProposal, OxenError> {
+    let url = api::endpoint::url_from_repo(repository, &format!("/proposals/{id}/approve"))?;
+
+    let params = serde_json::to_string(approval)?;
+    let client = client::new_for_url(&url)?;
+    let res = client.post(&url).body(params).send().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: MergeProposalResponse = serde_json::from_str(&body)?;
+    Ok(response.proposal)
+}
+
+/// Merge the proposal on the server, fails if head no longer merges cleanly into base or
+/// changes owned paths their owners have not approved
+pub async fn merge(repository: &RemoteRepository, id: u64) -> Result<MergeProposal, OxenError> {
+    let url = api::endpoint::url_from_repo(repository, &format!("/proposals/{id}/merge"))?;
+    log::debug!("proposals::merge {}", url);
+
+    let client = client::new_for_url(&url)?;
+    let res = client.post(&url).send().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: MergeProposalResponse = serde_json::from_str(&body)?;
+    Ok(response.proposal)
+}
+
+pub async fn close(repository: &RemoteRepository, id: u64) -> Result<MergeProposal, OxenError> {
+    let url = api::endpoint::url_from_repo(repository, &format!("/proposals/{id}/close"))?;
+
+    let client = client::new_for_url(&url)?;
+    let res = client.post(&url).send().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: MergeProposalResponse = serde_json::from_str(&body)?;
+    Ok(response.proposal)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api;
+    use crate::error::OxenError;
+    use crate::model::{MergeProposalStatus, User};
+    use crate::test;
+    use crate::view::{MergeProposalCommentNew, MergeProposalNew};
+
+    #[tokio::test]
+    async fn test_create_comment_and_close_proposal() -> Result<(), OxenError> {
+        test::run_training_data_fully_sync_remote(|_local_repo, remote_repo| async move {
+            api::client::branches::create_from_branch(&remote_repo, "labels", "main").await?;
+            let author = User {
+                name: "Ox".to_string(),
+                email: "ox@oxen.ai".to_string(),
+            };
+            let proposal = api::client::proposals::create(
+                &remote_repo,
+                &MergeProposalNew {
+                    title: "Relabel".to_string(),
+                    description: String::new(),
+                    base: "main".to_string(),
+                    head: "labels".to_string(),
+                    author: author.clone(),
+                },
+            )
+            .await?;
+
+            let checks = api::client::proposals::mergeable(&remote_repo, proposal.id).await?;
+            assert!(checks.is_mergeable);
+            assert_eq!(checks.num_commits, 0);
+
+            api::client::proposals::comment(
+                &remote_repo,
+                proposal.id,
+                &MergeProposalCommentNew {
+                    author,
+                    body: "Looks good".to_string(),
+                    path: Some("annotations/train/bounding_box.csv".into()),
+                    row: Some("3".to_string()),
+                },
+            )
+            .await?;
+            let threads = api::client::proposals::threads(&remote_repo, proposal.id).await?;
+            assert_eq!(threads.len(), 1);
+            assert_eq!(threads[0].row, Some("3".to_string()));
+
+            let closed = api::client::proposals::close(&remote_repo, proposal.id).await?;
+            assert_eq!(closed.status, MergeProposalStatus::Closed);
+            let open =
+                api::client::proposals::list(&remote_repo, Some(MergeProposalStatus::Open)).await?;
+            assert!(open.is_empty());
+
+            Ok(remote_repo)
+        })
+        .await
+    }
+}
@@ -0,0 +1,100 @@
+use crate::api;
+use crate::api::client;
+use crate::error::OxenError;
+use crate::model::{RemoteRepository, Tag};
+use crate::view::{ListTagsResponse, StatusMessage, TagResponse};
+
+/// List all tags on the remote
+pub async fn list(repository: &RemoteRepository) -> Result<Vec<Tag>, OxenError> {
+    let url = api::endpoint::url_from_repo(repository, "/tags")?;
+
+    let client = client::new_for_url(&url)?;
+    let res = client.get(&url).send().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: ListTagsResponse = serde_json::from_str(&body)?;
+    Ok(response.tags)
+}
+
+pub async fn get_by_name(
+    repository: &RemoteRepository,
+    tag_name: impl AsRef<str>,
+) -> Result<Option<Tag>, OxenError> {
+    let tag_name = tag_name.as_ref();
+    let uri = format!("/tags/{tag_name}");
+    let url = api::endpoint::url_from_repo(repository, &uri)?;
+
+    let client = client::new_for_url(&url)?;
+    let res = client.get(&url).send().await?;
+    if 404 == res.status() {
+        return Ok(None);
+    }
+
+    let body = client::parse_json_body(&url, res).await?;
+    let response: TagResponse = serde_json::from_str(&body)?;
+    Ok(Some(response.tag))
+}
+
+/// Create a tag on the remote, keeping its author and timestamp.
+/// The commit must already exist on the remote
+pub async fn create(repository: &RemoteRepository, tag: &Tag) -> Result<Tag, OxenError> {
+    let url = api::endpoint::url_from_repo(repository, "/tags")?;
+    log::debug!("tags::create {}", url);
+
+    let params = serde_json::to_string(tag)?;
+    let client = client::new_for_url(&url)?;
+    let res = client.post(&url).body(params).send().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: TagResponse = serde_json::from_str(&body)?;
+    Ok(response.tag)
+}
+
+pub async fn delete(
+    repository: &RemoteRepository,
+    tag_name: impl AsRef<str>,
+) -> Result<StatusMessage, OxenError> {
+    let tag_name = tag_name.as_ref();
+    let uri = format!("/tags/{tag_name}");
+    let url = api::endpoint::url_from_repo(repository, &uri)?;
+    log::debug!("Deleting tag: {}", url);
+
+    let client = client::new_for_url(&url)?;
+    let res = client.delete(&url).send().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: StatusMessage = serde_json::from_str(&body)?;
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api;
+    use crate::constants::DEFAULT_REMOTE_NAME;
+    use crate::error::OxenError;
+    use crate::repositories;
+    use crate::test;
+
+    #[tokio::test]
+    async fn test_push_tags() -> Result<(), OxenError> {
+        test::run_training_data_fully_sync_remote(|local_repo, remote_repo| async move {
+            let tag = repositories::tags::create(&local_repo, "v1.0", "HEAD", "Release")?;
+
+            let pushed = repositories::tags::push(&local_repo, DEFAULT_REMOTE_NAME).await?;
+            assert_eq!(pushed, vec![tag.clone()]);
+
+            let remote_tag = api::client::tags::get_by_name(&remote_repo, "v1.0").await?;
+            assert_eq!(remote_tag, Some(tag));
+            assert_eq!(api::client::tags::list(&remote_repo).await?.len(), 1);
+
+            // Pushing again is a no-op
+            let pushed = repositories::tags::push(&local_repo, DEFAULT_REMOTE_NAME).await?;
+            assert!(pushed.is_empty());
+
+            api::client::tags::delete(&remote_repo, "v1.0").await?;
+            assert!(api::client::tags::get_by_name(&remote_repo, "v1.0")
+                .await?
+                .is_none());
+
+            Ok(remote_repo)
+        })
+        .await
+    }
+}
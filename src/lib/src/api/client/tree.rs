@@ -288,7 +288,19 @@ pub async fn download_trees_from(
 }
 
 fn append_fetch_opts_to_uri(uri: String, fetch_opts: &FetchOpts) -> String {
-    append_subtree_paths_and_depth_to_uri(uri, &fetch_opts.subtree_paths, &fetch_opts.depth, false)
+    let uri = append_subtree_paths_and_depth_to_uri(
+        uri,
+        &fetch_opts.subtree_paths,
+        &fetch_opts.depth,
+        false,
+    );
+    match fetch_opts.history_depth {
+        Some(history_depth) => {
+            let separator = if uri.contains('?') { '&' } else { '?' };
+            format!("{uri}{separator}history_depth={history_depth}")
+        }
+        None => uri,
+    }
 }
 
 fn append_download_tree_opts_to_uri(uri: String, download_tree_opts: &DownloadTreeOpts) -> String {
@@ -634,10 +646,13 @@ mod tests {
             let fetch_opts = FetchOpts {
                 subtree_paths: Some(vec![PathBuf::from("annotations/test")]),
                 depth: Some(1),
+                history_depth: None,
+                unshallow: false,
                 all: false,
                 remote: remote_repo_clone.url().to_string(),
                 branch: "main".to_string(),
                 should_update_branch_head: true,
+                json_progress: false,
             };
             api::client::tree::download_trees_from(
                 &download_local_repo_2,
@@ -0,0 +1,175 @@
+//! Resumable uploads of large version files, see [`crate::repositories::uploads`]
+//!
+
+use std::io::{Read, Seek, SeekFrom};
+use std::time::Duration;
+
+use crate::api;
+use crate::api::client;
+use crate::constants::AVG_CHUNK_SIZE;
+use crate::error::OxenError;
+use crate::model::{RemoteRepository, UploadSession};
+use crate::view::{NewUploadSession, StatusMessage, UploadSessionResponse};
+
+const UPLOAD_OFFSET_HEADER: &str = "upload-offset";
+const BASE_WAIT_TIME: usize = 300;
+const MAX_WAIT_TIME: usize = 10_000;
+const MAX_RETRIES: usize = 5;
+
+/// Start the upload of a version file, or pick up the one in progress for the same hash.
+/// Returns None if the server does not support resumable uploads.
+pub async fn create(
+    remote_repo: &RemoteRepository,
+    hash: impl AsRef<str>,
+    size: u64,
+) -> Result<Option<UploadSession>, OxenError> {
+    let url = api::endpoint::url_from_repo(remote_repo, "/uploads")?;
+    log::debug!("api::client::uploads::create {}", url);
+
+    let client = client::new_for_url(&url)?;
+    let body = NewUploadSession {
+        hash: hash.as_ref().to_string(),
+        size,
+    };
+    let res = client.post(&url).json(&body).send().await?;
+    if res.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    let body = client::parse_json_body(&url, res).await?;
+    let response: UploadSessionResponse = serde_json::from_str(&body)?;
+    Ok(Some(response.upload))
+}
+
+pub async fn get(
+    remote_repo: &RemoteRepository,
+    upload_id: impl AsRef<str>,
+) -> Result<Option<UploadSession>, OxenError> {
+    let upload_id = upload_id.as_ref();
+    let url = api::endpoint::url_from_repo(remote_repo, &format!("/uploads/{upload_id}"))?;
+
+    let client = client::new_for_url(&url)?;
+    let res = client.get(&url).send().await?;
+    if res.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    let body = client::parse_json_body(&url, res).await?;
+    let response: UploadSessionResponse = serde_json::from_str(&body)?;
+    Ok(Some(response.upload))
+}
+
+/// Send the bytes at `offset`, returning the session with the offset to continue from. If
+/// the server has a different offset, nothing is written and its offset is returned.
+pub async fn append(
+    client: &reqwest::Client,
+    remote_repo: &RemoteRepository,
+    upload_id: impl AsRef<str>,
+    offset: u64,
+    data: Vec<u8>,
+) -> Result<UploadSession, OxenError> {
+    let upload_id = upload_id.as_ref();
+    let url = api::endpoint::url_from_repo(remote_repo, &format!("/uploads/{upload_id}"))?;
+
+    let res = client
+        .patch(&url)
+        .header(UPLOAD_OFFSET_HEADER, offset)
+        .header(
+            reqwest::header::CONTENT_TYPE,
+            "application/offset+octet-stream",
+        )
+        .body(data)
+        .send()
+        .await?;
+    if res.status() == reqwest::StatusCode::CONFLICT {
+        let body = res.text().await?;
+        let response: UploadSessionResponse = serde_json::from_str(&body)?;
+        log::debug!(
+            "upload {upload_id} is at offset {}, not {offset}",
+            response.upload.offset
+        );
+        return Ok(response.upload);
+    }
+    let body = client::parse_json_body(&url, res).await?;
+    let response: UploadSessionResponse = serde_json::from_str(&body)?;
+    Ok(response.upload)
+}
+
+/// Have the server check the upload against its hash and add it to the version store
+pub async fn complete(
+    remote_repo: &RemoteRepository,
+    upload_id: impl AsRef<str>,
+) -> Result<(), OxenError> {
+    let upload_id = upload_id.as_ref();
+    let url = api::endpoint::url_from_repo(remote_repo, &format!("/uploads/{upload_id}/complete"))?;
+
+    let client = client::new_for_url(&url)?;
+    let res = client.post(&url).send().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let _: StatusMessage = serde_json::from_str(&body)?;
+    Ok(())
+}
+
+/// Upload a version file of `size` bytes with content hash `hash` through a resumable
+/// session, starting from whatever the server already has. `on_progress` is called with the
+/// number of bytes each time the upload moves forward. Returns false, without uploading
+/// anything, if the server does not support resumable uploads.
+pub async fn upload_version(
+    remote_repo: &RemoteRepository,
+    hash: impl AsRef<str>,
+    size: u64,
+    reader: &mut (impl Read + Seek),
+    on_progress: impl Fn(u64),
+) -> Result<bool, OxenError> {
+    let hash = hash.as_ref();
+    let Some(mut session) = create(remote_repo, hash, size).await? else {
+        return Ok(false);
+    };
+    if session.offset > 0 {
+        log::debug!(
+            "resuming upload {} at {}/{}",
+            session.id,
+            session.offset,
+            session.size
+        );
+        on_progress(session.offset);
+    }
+
+    let client = api::client::builder_for_remote_repo(remote_repo)?.build()?;
+    let mut retries = 0;
+    while !session.is_complete() {
+        let len = AVG_CHUNK_SIZE.min(session.size - session.offset);
+        let mut buffer = vec![0u8; len as usize];
+        reader.seek(SeekFrom::Start(session.offset))?;
+        reader.read_exact(&mut buffer)?;
+
+        let offset = session.offset;
+        match append(&client, remote_repo, &session.id, offset, buffer).await {
+            Ok(next) => {
+                on_progress(next.offset.saturating_sub(offset));
+                session = next;
+                retries = 0;
+            }
+            Err(err) => {
+                if retries >= MAX_RETRIES {
+                    return Err(OxenError::basic_str(format!(
+                        "Failed to upload {hash} after {MAX_RETRIES} retries: {err}"
+                    )));
+                }
+                let wait_time = api::client::versions::exponential_backoff(
+                    BASE_WAIT_TIME,
+                    retries,
+                    MAX_WAIT_TIME,
+                );
+                tokio::time::sleep(Duration::from_millis(wait_time as u64)).await;
+                retries += 1;
+
+                // The chunk may have landed before the connection dropped
+                session = get(remote_repo, &session.id)
+                    .await?
+                    .ok_or(OxenError::basic_str(format!("Upload {hash} was removed")))?;
+            }
+        }
+    }
+
+    complete(remote_repo, &session.id).await?;
+    Ok(true)
+}
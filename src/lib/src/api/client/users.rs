@@ -0,0 +1,28 @@
+use crate::api::client;
+use crate::error::OxenError;
+use crate::model::User;
+use crate::view::user::WhoamiResponse;
+
+/// Ask the server who the configured auth token for `host` belongs to. Returns None if
+/// the server accepted the request but did not recognize a token, and an authentication
+/// error if the server rejected the token.
+pub async fn whoami(scheme: &str, host: &str) -> Result<Option<User>, OxenError> {
+    let url = format!("{scheme}://{host}/api/whoami");
+    log::debug!("Checking auth token at url {}", url);
+
+    let client = client::new_for_url(&url)?;
+    let res = client.get(&url).send().await?;
+    if res.status() == reqwest::StatusCode::UNAUTHORIZED {
+        return Err(OxenError::authentication(format!(
+            "{host} rejected the auth token (401 Unauthorized)"
+        )));
+    }
+    let body = client::parse_json_body(&url, res).await?;
+    let response: Result<WhoamiResponse, serde_json::Error> = serde_json::from_str(&body);
+    match response {
+        Ok(val) => Ok(val.user),
+        Err(err) => Err(OxenError::basic_str(format!(
+            "api::users::whoami {url} Err parsing response {err}\n\n{body}"
+        ))),
+    }
+}
@@ -5,11 +5,12 @@ use crate::error::OxenError;
 use crate::model::entry::commit_entry::Entry;
 use crate::model::{LocalRepository, MerkleHash, RemoteRepository};
 use crate::util::hasher;
+use crate::view::tree::merkle_hashes::MerkleHashes;
 use crate::view::versions::{
     CompleteVersionUploadRequest, CompletedFileUpload, CreateVersionUploadRequest,
     MultipartLargeFileUpload, MultipartLargeFileUploadStatus, VersionFile, VersionFileResponse,
 };
-use crate::view::{ErrorFileInfo, ErrorFilesResponse, FileWithHash};
+use crate::view::{ErrorFileInfo, ErrorFilesResponse, FileWithHash, MerkleHashesResponse};
 
 use flate2::write::GzEncoder;
 use flate2::Compression;
@@ -19,7 +20,7 @@ use http::header::CONTENT_LENGTH;
 use rand::{thread_rng, Rng};
 use tokio_util::codec::{BytesCodec, FramedRead};
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::io::SeekFrom;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
@@ -55,6 +56,31 @@ pub async fn has_version(
     Ok(get(repository, version_id).await?.is_some())
 }
 
+/// Check which of the content hashes the remote repository has no version file for,
+/// in a single request
+pub async fn list_missing_hashes(
+    repository: &RemoteRepository,
+    hashes: HashSet<MerkleHash>,
+) -> Result<HashSet<MerkleHash>, OxenError> {
+    let url = api::endpoint::url_from_repo(repository, "/versions/exists")?;
+    log::debug!("api::client::versions::list_missing_hashes {}", url);
+
+    let client = client::new_for_url(&url)?;
+    let res = client
+        .post(&url)
+        .json(&MerkleHashes { hashes })
+        .send()
+        .await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: Result<MerkleHashesResponse, serde_json::Error> = serde_json::from_str(&body);
+    match response {
+        Ok(response) => Ok(response.hashes),
+        Err(err) => Err(OxenError::basic_str(format!(
+            "api::client::versions::list_missing_hashes() Could not deserialize response [{err}]\n{body}"
+        ))),
+    }
+}
+
 /// Get the size of a version
 pub async fn get(
     repository: &RemoteRepository,
@@ -547,10 +573,13 @@ fn jitter() -> usize {
 
 #[cfg(test)]
 mod tests {
-    use std::path::PathBuf;
+    use std::collections::HashSet;
+    use std::path::{Path, PathBuf};
 
     use crate::api;
     use crate::error::OxenError;
+    use crate::model::MerkleHash;
+    use crate::repositories;
     use crate::test;
 
     #[tokio::test]
@@ -582,4 +611,27 @@ mod tests {
         })
         .await
     }
+
+    #[tokio::test]
+    async fn test_list_missing_version_hashes() -> Result<(), OxenError> {
+        test::run_remote_repo_test_bounding_box_csv_pushed(|local_repo, remote_repo| async move {
+            let commit = repositories::commits::head_commit(&local_repo)?;
+            let path = Path::new("annotations")
+                .join("train")
+                .join("bounding_box.csv");
+            let file_node = repositories::tree::get_file_by_path(&local_repo, &commit, &path)?
+                .expect("file is committed");
+            let missing_hash = MerkleHash::new(1234);
+
+            let missing = api::client::versions::list_missing_hashes(
+                &remote_repo,
+                HashSet::from([*file_node.hash(), missing_hash]),
+            )
+            .await?;
+            assert_eq!(missing, HashSet::from([missing_hash]));
+
+            Ok(remote_repo)
+        })
+        .await
+    }
 }
@@ -0,0 +1,82 @@
+use crate::api;
+use crate::api::client;
+use crate::error::OxenError;
+use crate::model::{RemoteRepository, Webhook};
+use crate::view::{ListWebhooksResponse, StatusMessage, WebhookNew, WebhookResponse};
+
+/// List the webhooks of the remote repository, their secrets are left out
+pub async fn list(repository: &RemoteRepository) -> Result<Vec<Webhook>, OxenError> {
+    let url = api::endpoint::url_from_repo(repository, "/webhooks")?;
+
+    let client = client::new_for_url(&url)?;
+    let res = client.get(&url).send().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: ListWebhooksResponse = serde_json::from_str(&body)?;
+    Ok(response.webhooks)
+}
+
+/// Register a webhook on the remote repository
+pub async fn create(
+    repository: &RemoteRepository,
+    webhook: &WebhookNew,
+) -> Result<Webhook, OxenError> {
+    let url = api::endpoint::url_from_repo(repository, "/webhooks")?;
+    log::debug!("webhooks::create {}", url);
+
+    let params = serde_json::to_string(webhook)?;
+    let client = client::new_for_url(&url)?;
+    let res = client.post(&url).body(params).send().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: WebhookResponse = serde_json::from_str(&body)?;
+    Ok(response.webhook)
+}
+
+pub async fn delete(
+    repository: &RemoteRepository,
+    id: impl AsRef<str>,
+) -> Result<StatusMessage, OxenError> {
+    let id = id.as_ref();
+    let uri = format!("/webhooks/{id}");
+    let url = api::endpoint::url_from_repo(repository, &uri)?;
+    log::debug!("Deleting webhook: {}", url);
+
+    let client = client::new_for_url(&url)?;
+    let res = client.delete(&url).send().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: StatusMessage = serde_json::from_str(&body)?;
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api;
+    use crate::error::OxenError;
+    use crate::model::WebhookEvent;
+    use crate::test;
+    use crate::view::WebhookNew;
+
+    #[tokio::test]
+    async fn test_create_list_and_delete_webhooks() -> Result<(), OxenError> {
+        test::run_training_data_fully_sync_remote(|_local_repo, remote_repo| async move {
+            let webhook = api::client::webhooks::create(
+                &remote_repo,
+                &WebhookNew {
+                    url: "https://example.com/hook".to_string(),
+                    events: vec![WebhookEvent::Push, WebhookEvent::TagCreated],
+                    secret: Some("secret".to_string()),
+                },
+            )
+            .await?;
+            assert_eq!(webhook.secret, None);
+
+            let webhooks = api::client::webhooks::list(&remote_repo).await?;
+            assert_eq!(webhooks, vec![webhook.clone()]);
+
+            api::client::webhooks::delete(&remote_repo, &webhook.id).await?;
+            assert!(api::client::webhooks::list(&remote_repo).await?.is_empty());
+
+            Ok(remote_repo)
+        })
+        .await
+    }
+}
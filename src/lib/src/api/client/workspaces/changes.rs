@@ -1,7 +1,9 @@
 use crate::api;
 
 use crate::api::client;
+use crate::constants;
 use crate::error::OxenError;
+use crate::model::diff::WorkspaceChanges;
 use crate::model::RemoteRepository;
 use crate::view::{RemoteStagedStatus, RemoteStagedStatusResponse};
 
@@ -14,6 +16,37 @@ pub async fn list(
     page: usize,
     page_size: usize,
 ) -> Result<RemoteStagedStatus, OxenError> {
+    let response = get(remote_repo, workspace_id, path, page, page_size).await?;
+    Ok(response.staged)
+}
+
+/// Every file added, modified or removed in the workspace below `path`, with the rows
+/// edited in data frames
+pub async fn summary(
+    remote_repo: &RemoteRepository,
+    workspace_id: impl AsRef<str>,
+    path: impl AsRef<Path>,
+) -> Result<WorkspaceChanges, OxenError> {
+    let response = get(
+        remote_repo,
+        workspace_id,
+        path,
+        constants::DEFAULT_PAGE_NUM,
+        constants::DEFAULT_PAGE_SIZE,
+    )
+    .await?;
+    response.changes.ok_or(OxenError::basic_str(
+        "Remote does not report workspace changes, upgrade the server to get them",
+    ))
+}
+
+async fn get(
+    remote_repo: &RemoteRepository,
+    workspace_id: impl AsRef<str>,
+    path: impl AsRef<Path>,
+    page: usize,
+    page_size: usize,
+) -> Result<RemoteStagedStatusResponse, OxenError> {
     let workspace_id = workspace_id.as_ref();
     let path = path.as_ref();
     let path_str = path.to_str().unwrap();
@@ -30,7 +63,7 @@ pub async fn list(
             let response: Result<RemoteStagedStatusResponse, serde_json::Error> =
                 serde_json::from_str(&body);
             match response {
-                Ok(val) => Ok(val.staged),
+                Ok(val) => Ok(val),
                 Err(err) => Err(OxenError::basic_str(format!(
                     "api::staging::status error parsing response from {url}\n\nErr {err:?} \n\n{body}"
                 ))),
@@ -3,13 +3,13 @@
 //! Interact with DataFrames
 //!
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use crate::core::df::tabular;
+use crate::core::df::{pretty_print, split, tabular};
 use crate::core::v_latest::index::CommitMerkleTree;
 use crate::error::OxenError;
 use crate::model::LocalRepository;
-use crate::opts::DFOpts;
+use crate::opts::{DFOpts, DFSplitOpts};
 use crate::{repositories, util};
 
 /// Interact with DataFrames
@@ -39,8 +39,15 @@ pub fn df_revision(
         format!("Revision {} not found", revision.as_ref()),
     ))?;
     let path = input.as_ref();
-    let tree = CommitMerkleTree::from_path(repo, &commit, path, false)?;
-    let mut df = tabular::show_node(repo.clone(), &tree.root, opts.clone())?;
+    let mut df = if let Some(sql) = &opts.sql {
+        // Query the version of the file at the revision, rather than the working copy
+        let df = repositories::data_frames::query_sql(repo, &commit, path, sql)?;
+        println!("{}", pretty_print::df_to_str(&df));
+        df
+    } else {
+        let tree = CommitMerkleTree::from_path(repo, &commit, path, false)?;
+        tabular::show_node(repo.clone(), &tree.root, opts.clone())?
+    };
 
     if let Some(output) = opts.output {
         println!("Writing {output:?}");
@@ -50,6 +57,36 @@ pub fn df_revision(
     Ok(())
 }
 
+/// Split a DataFrame into random parts, ie train, val and test, after applying the transforms
+/// in `opts`. Each part is written to `<output_dir>/<name>.<extension>` with the same
+/// extension as `input`, and the paths are returned in the order of the splits.
+pub fn split(
+    input: impl AsRef<Path>,
+    opts: DFOpts,
+    split_opts: &DFSplitOpts,
+) -> Result<Vec<PathBuf>, OxenError> {
+    let input = input.as_ref();
+    let extension = input
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .ok_or(OxenError::basic_str(format!(
+            "Could not get extension of {input:?}"
+        )))?;
+
+    let df = tabular::read_df(input, opts)?;
+    let splits = split::split_df(&df, split_opts)?;
+
+    util::fs::create_dir_all(&split_opts.output_dir)?;
+    let mut paths = vec![];
+    for (name, mut df) in splits {
+        let path = split_opts.output_dir.join(format!("{name}.{extension}"));
+        println!("Writing {} rows to {path:?}", df.height());
+        tabular::write_df(&mut df, &path)?;
+        paths.push(path);
+    }
+    Ok(paths)
+}
+
 /// Get a human readable schema for a DataFrame
 pub fn schema<P: AsRef<Path>>(input: P, flatten: bool, opts: DFOpts) -> Result<String, OxenError> {
     tabular::schema_to_string(input, flatten, &opts)
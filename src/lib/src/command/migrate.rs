@@ -5,6 +5,10 @@ use crate::{error::OxenError, model::LocalRepository};
 pub mod m20250111083535_add_child_counts_to_nodes;
 pub use m20250111083535_add_child_counts_to_nodes::AddChildCountsToNodesMigration;
 
+pub mod hash;
+pub mod repo;
+pub mod vnode_size;
+
 pub trait Migrate {
     fn up(&self, path: &Path, all: bool) -> Result<(), OxenError>;
     fn down(&self, path: &Path, all: bool) -> Result<(), OxenError>;
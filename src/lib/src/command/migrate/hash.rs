@@ -0,0 +1,401 @@
+//! Rehash the contents of existing repositories with a different [`HashAlgorithm`]
+//!
+//! Every version file is hashed again and stored under its new hash, then the merkle tree
+//! of every commit is rebuilt from the new file hashes. Commit ids do not depend on the
+//! content hashes, so branches and the commit history are left untouched.
+//!
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use rocksdb::{DBWithThreadMode, SingleThreaded};
+use xxhash_rust::xxh3::Xxh3;
+
+use crate::constants::{STAGED_DIR, WORKSPACES_DIR};
+use crate::core::db;
+use crate::core::db::key_val::str_val_db;
+use crate::core::db::merkle_node::MerkleNodeDB;
+use crate::error::OxenError;
+use crate::model::merkle_tree::merkle_tree_node_cache;
+use crate::model::merkle_tree::node::vnode::VNodeOpts;
+use crate::model::merkle_tree::node::{DirNode, EMerkleTreeNode, MerkleTreeNode, VNode};
+use crate::model::{Commit, LocalRepository, MerkleHash};
+use crate::repositories;
+use crate::util;
+use crate::util::hasher::{self, HashAlgorithm};
+use crate::util::progress_bar::{oxen_progress_bar, ProgressBarType};
+
+pub async fn rehash(path: &Path, algorithm: HashAlgorithm, all: bool) -> Result<(), OxenError> {
+    if all {
+        run_on_all_repos(path, algorithm).await?;
+    } else {
+        let mut repo = LocalRepository::from_dir(path)?;
+        run_on_one_repo(&mut repo, algorithm).await?;
+    }
+    Ok(())
+}
+
+async fn run_on_all_repos(path: &Path, algorithm: HashAlgorithm) -> Result<(), OxenError> {
+    let namespaces = repositories::list_namespaces(path)?;
+    let bar = oxen_progress_bar(namespaces.len() as u64, ProgressBarType::Counter);
+    for namespace in namespaces {
+        let namespace_path = path.join(namespace);
+        for mut repo in repositories::list_repos_in_namespace(&namespace_path) {
+            if let Err(err) = run_on_one_repo(&mut repo, algorithm).await {
+                log::error!(
+                    "Could not rehash repo {:?} with {}\nErr: {}",
+                    repo.path,
+                    algorithm,
+                    err
+                )
+            }
+        }
+        bar.inc(1);
+    }
+    Ok(())
+}
+
+async fn run_on_one_repo(
+    repo: &mut LocalRepository,
+    algorithm: HashAlgorithm,
+) -> Result<(), OxenError> {
+    let current = repo.hash_algorithm();
+    if current == algorithm {
+        println!("Repository {:?} already uses {}", repo.path, algorithm);
+        return Ok(());
+    }
+
+    // Staged files and workspaces were hashed with the current algorithm and are not rewritten
+    let hidden_dir = util::fs::oxen_hidden_dir(&repo.path);
+    if !is_empty_dir(hidden_dir.join(STAGED_DIR))? {
+        return Err(OxenError::basic_str(
+            "Cannot change the hash algorithm with staged changes, commit them first",
+        ));
+    }
+    if !is_empty_dir(hidden_dir.join(WORKSPACES_DIR))? {
+        return Err(OxenError::basic_str(
+            "Cannot change the hash algorithm while the repository has workspaces",
+        ));
+    }
+
+    log::info!(
+        "Rehashing repo {:?} from {} to {}",
+        repo.path,
+        current,
+        algorithm
+    );
+    let commits = repositories::commits::list_all(repo)?;
+
+    // Hash every version file again, the same contents show up in many commits
+    let mut old_hashes: HashSet<MerkleHash> = HashSet::new();
+    for commit in commits.iter() {
+        let Some(root) = repositories::tree::get_root_with_children(repo, commit)? else {
+            continue;
+        };
+        for file in repositories::tree::list_all_files(&root, &PathBuf::from(""))? {
+            old_hashes.insert(*file.file_node.hash());
+        }
+    }
+
+    let version_store = repo.version_store()?;
+    let mut rehashed: HashMap<MerkleHash, MerkleHash> = HashMap::new();
+    for old_hash in old_hashes.iter() {
+        let old_hash_str = old_hash.to_string();
+        let mut reader = version_store.open_version(&old_hash_str)?;
+        let new_hash = MerkleHash::new(algorithm.hash_reader(&mut reader)?);
+        let new_hash_str = new_hash.to_string();
+        if !version_store.version_exists(&new_hash_str)? {
+            if version_store.storage_type() == "local" {
+                let version_path = version_store.get_version_path(&old_hash_str)?;
+                version_store
+                    .store_version_from_path(&new_hash_str, &version_path)
+                    .await?;
+            } else {
+                let data = version_store.get_version(&old_hash_str).await?;
+                version_store.store_version(&new_hash_str, &data).await?;
+            }
+        }
+        rehashed.insert(*old_hash, new_hash);
+    }
+
+    // Rebuild the trees, the commit nodes are rewritten in place to point at the new root dirs
+    merkle_tree_node_cache::with_cache_disabled(|| -> Result<(), OxenError> {
+        for commit in commits.iter() {
+            rewrite_commit(repo, commit, &rehashed)?;
+        }
+        Ok(())
+    })?;
+    merkle_tree_node_cache::remove_from_cache(&repo.path)?;
+
+    repo.set_hash_algorithm(algorithm);
+    repo.save()?;
+
+    // Only remove the old versions once the repo points at the new ones
+    for (old_hash, new_hash) in rehashed.iter() {
+        if old_hash != new_hash {
+            version_store.delete_version(&old_hash.to_string()).await?;
+        }
+    }
+
+    if !repo.remotes().is_empty() {
+        println!(
+            "Rehashed {} files with {}, remotes and other clones of this repository must be migrated as well",
+            rehashed.len(),
+            algorithm
+        );
+    }
+
+    Ok(())
+}
+
+fn is_empty_dir(path: PathBuf) -> Result<bool, OxenError> {
+    if !path.exists() {
+        return Ok(true);
+    }
+    Ok(std::fs::read_dir(&path)?.next().is_none())
+}
+
+fn rewrite_commit(
+    repo: &LocalRepository,
+    commit: &Commit,
+    rehashed: &HashMap<MerkleHash, MerkleHash>,
+) -> Result<(), OxenError> {
+    let Some(root) = repositories::tree::get_root_with_children(repo, commit)? else {
+        return Err(OxenError::basic_str(format!(
+            "Merkle tree not found for commit {}",
+            commit.id
+        )));
+    };
+    let EMerkleTreeNode::Commit(commit_node) = &root.node else {
+        return Err(OxenError::basic_str("Root node must be a CommitNode"));
+    };
+    let root_dir = repositories::tree::get_root_dir(&root)?;
+
+    let mut dir_hashes: Vec<(PathBuf, MerkleHash)> = vec![];
+    let new_root_dir = rehash_dir(repo, root_dir, Path::new(""), rehashed, &mut dir_hashes)?;
+    let EMerkleTreeNode::Directory(new_root_dir_node) = &new_root_dir.node else {
+        return Err(OxenError::basic_str("Root dir must be a DirNode"));
+    };
+
+    let mut commit_db = MerkleNodeDB::open_read_write(repo, commit_node, root.parent_id)?;
+    commit_db.add_child(new_root_dir_node)?;
+    write_dir(repo, &new_root_dir, root.hash)?;
+
+    // Replace the dir hashes the commit uses to skip to a path in the tree
+    let commit_hash = commit.hash()?;
+    let dir_hash_db_path = repositories::tree::dir_hash_db_path_from_commit_id(repo, &commit_hash);
+    if dir_hash_db_path.exists() {
+        util::fs::remove_dir_all(&dir_hash_db_path)?;
+    }
+    let opts = db::key_val::opts::default();
+    let dir_hash_db: DBWithThreadMode<SingleThreaded> =
+        DBWithThreadMode::open(&opts, dunce::simplified(&dir_hash_db_path))?;
+    for (path, hash) in dir_hashes {
+        str_val_db::put(&dir_hash_db, path.to_str().unwrap(), &hash.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// Build the new version of a directory and all of its children in memory.
+/// The vnode buckets only depend on the paths, so the layout of the tree is unchanged.
+fn rehash_dir(
+    repo: &LocalRepository,
+    node: &MerkleTreeNode,
+    path: &Path,
+    rehashed: &HashMap<MerkleHash, MerkleHash>,
+    dir_hashes: &mut Vec<(PathBuf, MerkleHash)>,
+) -> Result<MerkleTreeNode, OxenError> {
+    let EMerkleTreeNode::Directory(dir) = &node.node else {
+        return Err(OxenError::basic_str(format!(
+            "Expected a directory at {:?}, got {:?}",
+            path,
+            node.node.node_type()
+        )));
+    };
+
+    let mut dir_hasher = Xxh3::new();
+    dir_hasher.update(b"dir");
+    dir_hasher.update(path.to_str().unwrap().as_bytes());
+
+    let mut vnode_entries: Vec<Vec<MerkleTreeNode>> = vec![];
+    for vnode in node.children.iter() {
+        let mut entries: Vec<MerkleTreeNode> = vec![];
+        for entry in vnode.children.iter() {
+            match &entry.node {
+                EMerkleTreeNode::File(file_node) => {
+                    let Some(new_hash) = rehashed.get(file_node.hash()) else {
+                        return Err(OxenError::basic_str(format!(
+                            "No new hash for {:?}",
+                            path.join(file_node.name())
+                        )));
+                    };
+                    let mut file_node = file_node.clone();
+                    let metadata_hash = file_node.metadata_hash().map(|h| h.to_u128());
+                    let combined_hash =
+                        hasher::get_combined_hash(metadata_hash, new_hash.to_u128())?;
+                    let combined_hash =
+                        hasher::get_combined_hash_with_mode(combined_hash, file_node.mode());
+                    file_node.set_hash(new_hash);
+                    file_node.set_combined_hash(&MerkleHash::new(combined_hash));
+
+                    dir_hasher.update(file_node.name().as_bytes());
+                    dir_hasher.update(&combined_hash.to_le_bytes());
+                    entries.push(MerkleTreeNode {
+                        hash: *new_hash,
+                        node: EMerkleTreeNode::File(file_node),
+                        parent_id: None,
+                        children: vec![],
+                    });
+                }
+                EMerkleTreeNode::Directory(child_dir) => {
+                    let child_path = path.join(child_dir.name());
+                    let child = rehash_dir(repo, entry, &child_path, rehashed, dir_hashes)?;
+                    dir_hasher.update(child_dir.name().as_bytes());
+                    dir_hasher.update(&child.hash.to_le_bytes());
+                    entries.push(child);
+                }
+                _ => {
+                    return Err(OxenError::basic_str(format!(
+                        "Unexpected node type in vnode: {:?}",
+                        entry.node.node_type()
+                    )));
+                }
+            }
+        }
+        vnode_entries.push(entries);
+    }
+
+    let dir_hash = MerkleHash::new(dir_hasher.digest128());
+    let mut dir_opts = dir.get_opts();
+    dir_opts.hash = dir_hash;
+    let new_dir = DirNode::new(repo, dir_opts)?;
+    dir_hashes.push((path.to_path_buf(), dir_hash));
+
+    // Include the new dir hash so vnodes are never shared between directories
+    let mut vnodes: Vec<MerkleTreeNode> = vec![];
+    for entries in vnode_entries {
+        let mut vnode_hasher = Xxh3::new();
+        vnode_hasher.update(b"vnode");
+        vnode_hasher.update(&dir_hash.to_le_bytes());
+        for entry in entries.iter() {
+            match &entry.node {
+                EMerkleTreeNode::File(file_node) => {
+                    vnode_hasher.update(&file_node.combined_hash().to_le_bytes())
+                }
+                _ => vnode_hasher.update(&entry.hash.to_le_bytes()),
+            }
+        }
+        let vnode = VNode::new(
+            repo,
+            VNodeOpts {
+                hash: MerkleHash::new(vnode_hasher.digest128()),
+                num_entries: entries.len() as u64,
+            },
+        )?;
+        vnodes.push(MerkleTreeNode {
+            hash: *vnode.hash(),
+            node: EMerkleTreeNode::VNode(vnode),
+            parent_id: Some(dir_hash),
+            children: entries,
+        });
+    }
+
+    Ok(MerkleTreeNode {
+        hash: dir_hash,
+        node: EMerkleTreeNode::Directory(new_dir),
+        parent_id: None,
+        children: vnodes,
+    })
+}
+
+fn write_dir(
+    repo: &LocalRepository,
+    node: &MerkleTreeNode,
+    parent_id: MerkleHash,
+) -> Result<(), OxenError> {
+    // Directories are content addressed, so if it was written by an earlier commit so was its subtree
+    if MerkleNodeDB::exists(repo, &node.hash) {
+        return Ok(());
+    }
+    let EMerkleTreeNode::Directory(dir) = &node.node else {
+        return Err(OxenError::basic_str("Expected a directory node"));
+    };
+
+    let mut dir_db = MerkleNodeDB::open_read_write(repo, dir, Some(parent_id))?;
+    for vnode in node.children.iter() {
+        let EMerkleTreeNode::VNode(vnode_node) = &vnode.node else {
+            return Err(OxenError::basic_str("Expected a vnode"));
+        };
+        dir_db.add_child(vnode_node)?;
+
+        let mut vnode_db = MerkleNodeDB::open_read_write(repo, vnode_node, Some(node.hash))?;
+        for entry in vnode.children.iter() {
+            match &entry.node {
+                EMerkleTreeNode::File(file_node) => vnode_db.add_child(file_node)?,
+                EMerkleTreeNode::Directory(dir_node) => {
+                    vnode_db.add_child(dir_node)?;
+                    write_dir(repo, entry, vnode.hash)?;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::error::OxenError;
+    use crate::model::LocalRepository;
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+    use crate::util::hasher::HashAlgorithm;
+
+    #[tokio::test]
+    async fn test_rehash_repo_with_sha256() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed_async(|repo| async move {
+            let readme = Path::new("README.md");
+            let head = repositories::commits::head_commit(&repo)?;
+            let old_node = repositories::tree::get_file_by_path(&repo, &head, readme)?.unwrap();
+            assert_eq!(repo.hash_algorithm(), HashAlgorithm::Xxh3);
+
+            super::rehash(&repo.path, HashAlgorithm::Sha256, false).await?;
+
+            let repo = LocalRepository::from_dir(&repo.path)?;
+            assert_eq!(repo.hash_algorithm(), HashAlgorithm::Sha256);
+
+            // Same commit, new content hash that points at a stored version
+            let new_head = repositories::commits::head_commit(&repo)?;
+            assert_eq!(head.id, new_head.id);
+            let new_node = repositories::tree::get_file_by_path(&repo, &head, readme)?.unwrap();
+            let expected = HashAlgorithm::Sha256.hash_file_contents(&repo.path.join(readme))?;
+            assert_eq!(new_node.hash().to_u128(), expected);
+            assert_ne!(old_node.hash(), new_node.hash());
+            let version_store = repo.version_store()?;
+            assert!(version_store.version_exists(&new_node.hash().to_string())?);
+            assert!(!version_store.version_exists(&old_node.hash().to_string())?);
+
+            // Nothing looks modified and new commits use the new algorithm
+            let status = repositories::status(&repo)?;
+            assert!(status.is_clean());
+
+            let readme_path = repo.path.join(readme);
+            util::fs::write_to_path(&readme_path, "Rehashed")?;
+            repositories::add(&repo, &readme_path).await?;
+            let commit = repositories::commit(&repo, "Update README")?;
+            let node = repositories::tree::get_file_by_path(&repo, &commit, readme)?.unwrap();
+            assert_eq!(
+                node.hash().to_u128(),
+                HashAlgorithm::Sha256.hash_buffer_128bit(b"Rehashed")
+            );
+
+            Ok(())
+        })
+        .await
+    }
+}
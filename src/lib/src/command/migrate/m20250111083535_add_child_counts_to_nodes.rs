@@ -4,6 +4,7 @@ use super::Migrate;
 
 use crate::config::RepositoryConfig;
 use crate::core::db::merkle_node::MerkleNodeDB;
+use crate::core::v_latest::index::tree_cache;
 use crate::core::versions::MinOxenVersion;
 use crate::error::OxenError;
 use crate::model::merkle_tree::merkle_tree_node_cache;
@@ -94,7 +95,8 @@ fn run_on_one_repo(repo: &LocalRepository) -> Result<(), OxenError> {
         }
 
         Ok(())
-    })
+    })?;
+    tree_cache::clear(repo)
 }
 
 fn run_on_commit(repository: &LocalRepository, commit: &Commit) -> Result<(), OxenError> {
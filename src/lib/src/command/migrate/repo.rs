@@ -0,0 +1,181 @@
+//! Upgrade a repository's on-disk format to the latest version
+//!
+//! Runs each migration between the version recorded in `.oxen/config.toml` and
+//! the latest version in order, so a repo created with an old oxen can be used
+//! with the current one without re-cloning.
+//!
+
+use std::path::Path;
+
+use super::{AddChildCountsToNodesMigration, Migrate};
+use crate::core::versions::MinOxenVersion;
+use crate::error::OxenError;
+use crate::model::LocalRepository;
+use crate::repositories;
+use crate::util::progress_bar::{oxen_progress_bar, ProgressBarType};
+
+/// One step of the upgrade, from one on-disk version to the next
+pub struct UpgradeStep {
+    pub from: MinOxenVersion,
+    pub to: MinOxenVersion,
+    pub description: &'static str,
+    migration: Option<Box<dyn Migrate>>,
+}
+
+impl UpgradeStep {
+    fn run(&self, repo: &LocalRepository) -> Result<(), OxenError> {
+        if let Some(migration) = &self.migration {
+            migration.up(&repo.path, false)?;
+        }
+        // Re-read the config in case the migration rewrote it
+        let mut repo = LocalRepository::from_dir(&repo.path)?;
+        repo.set_min_version(self.to.clone());
+        repo.save()
+    }
+}
+
+/// The steps needed to bring a repository up to the latest version, empty if it is
+/// already up to date
+pub fn plan(repo: &LocalRepository) -> Result<Vec<UpgradeStep>, OxenError> {
+    let mut steps = vec![];
+    let mut version = repo.min_version();
+    loop {
+        let step = match version {
+            MinOxenVersion::V0_10_0 => {
+                return Err(OxenError::basic_str(format!(
+                    "Repository {:?} uses the commit entry layout from before oxen v0.19.0, which this version of oxen can no longer read.\nUpgrade it with oxen v0.19.x first, then run `oxen migrate repo` again.",
+                    repo.path
+                )));
+            }
+            MinOxenVersion::V0_19_0 => UpgradeStep {
+                from: MinOxenVersion::V0_19_0,
+                to: MinOxenVersion::V0_25_0,
+                description: AddChildCountsToNodesMigration.description(),
+                migration: Some(Box::new(AddChildCountsToNodesMigration)),
+            },
+            MinOxenVersion::V0_25_0 => UpgradeStep {
+                from: MinOxenVersion::V0_25_0,
+                to: MinOxenVersion::LATEST,
+                description: "Record the latest version, v0.25.0 nodes are read as is",
+                migration: None,
+            },
+            MinOxenVersion::LATEST => break,
+        };
+        version = step.to.clone();
+        steps.push(step);
+    }
+    Ok(steps)
+}
+
+/// Upgrade one repository, or every repository under `path` if `all` is set.
+/// With `dry_run` the steps are only printed.
+pub fn upgrade(path: &Path, all: bool, dry_run: bool) -> Result<(), OxenError> {
+    if all {
+        run_on_all_repos(path, dry_run)
+    } else {
+        let repo = LocalRepository::from_dir(path)?;
+        run_on_one_repo(&repo, dry_run)
+    }
+}
+
+fn run_on_all_repos(path: &Path, dry_run: bool) -> Result<(), OxenError> {
+    let namespaces = repositories::list_namespaces(path)?;
+    let bar = oxen_progress_bar(namespaces.len() as u64, ProgressBarType::Counter);
+    for namespace in namespaces {
+        let namespace_path = path.join(namespace);
+        for repo in repositories::list_repos_in_namespace(&namespace_path) {
+            if let Err(err) = run_on_one_repo(&repo, dry_run) {
+                log::error!("Could not upgrade repo {:?}\nErr: {}", repo.path, err)
+            }
+        }
+        bar.inc(1);
+    }
+    Ok(())
+}
+
+fn run_on_one_repo(repo: &LocalRepository, dry_run: bool) -> Result<(), OxenError> {
+    let steps = plan(repo)?;
+    if steps.is_empty() {
+        println!(
+            "🐂 {:?} is already at the latest version {}",
+            repo.path,
+            MinOxenVersion::LATEST
+        );
+        return Ok(());
+    }
+
+    let num_commits = repositories::commits::list_all(repo)?.len();
+    println!(
+        "🐂 {} {:?} from {} to {} ({} commits)",
+        if dry_run {
+            "Would upgrade"
+        } else {
+            "Upgrading"
+        },
+        repo.path,
+        repo.min_version(),
+        MinOxenVersion::LATEST,
+        num_commits
+    );
+
+    for (i, step) in steps.iter().enumerate() {
+        println!(
+            "  [{}/{}] {} -> {}: {}",
+            i + 1,
+            steps.len(),
+            step.from,
+            step.to,
+            step.description
+        );
+        if !dry_run {
+            step.run(repo)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::versions::MinOxenVersion;
+    use crate::error::OxenError;
+    use crate::model::LocalRepository;
+    use crate::repositories;
+    use crate::test;
+
+    #[tokio::test]
+    async fn test_upgrade_repo_to_latest() -> Result<(), OxenError> {
+        test::run_one_commit_local_repo_test(|mut repo| {
+            let head = repositories::commits::head_commit(&repo)?;
+            repo.set_min_version(MinOxenVersion::V0_25_0);
+            repo.save()?;
+
+            // Dry run leaves the repo alone
+            super::upgrade(&repo.path, false, true)?;
+            let repo = LocalRepository::from_dir(&repo.path)?;
+            assert_eq!(repo.min_version(), MinOxenVersion::V0_25_0);
+            assert_eq!(super::plan(&repo)?.len(), 1);
+
+            super::upgrade(&repo.path, false, false)?;
+            let repo = LocalRepository::from_dir(&repo.path)?;
+            assert_eq!(repo.min_version(), MinOxenVersion::LATEST);
+            assert!(super::plan(&repo)?.is_empty());
+            assert_eq!(repositories::commits::head_commit(&repo)?.id, head.id);
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[test]
+    fn test_upgrade_pre_merkle_repo_errors() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|mut repo| {
+            repo.set_min_version(MinOxenVersion::V0_10_0);
+            repo.save()?;
+
+            let result = super::upgrade(&repo.path, false, true);
+            assert!(result.is_err());
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,118 @@
+//! Change the vnode size of existing repositories
+//!
+//! Directories keep the vnodes they were written with, so old commits stay readable.
+//! The new size is used for every directory that is rewritten by a future commit.
+//!
+
+use std::path::Path;
+
+use crate::error::OxenError;
+use crate::model::LocalRepository;
+use crate::repositories;
+use crate::util::progress_bar::{oxen_progress_bar, ProgressBarType};
+
+pub fn set_vnode_size(path: &Path, size: u64, all: bool) -> Result<(), OxenError> {
+    if size == 0 {
+        return Err(OxenError::basic_str("vnode size must be greater than 0"));
+    }
+
+    if all {
+        run_on_all_repos(path, size)?;
+    } else {
+        let mut repo = LocalRepository::from_dir(path)?;
+        run_on_one_repo(&mut repo, size)?;
+    }
+    Ok(())
+}
+
+fn run_on_all_repos(path: &Path, size: u64) -> Result<(), OxenError> {
+    let namespaces = repositories::list_namespaces(path)?;
+    let bar = oxen_progress_bar(namespaces.len() as u64, ProgressBarType::Counter);
+    for namespace in namespaces {
+        let namespace_path = path.join(namespace);
+        for mut repo in repositories::list_repos_in_namespace(&namespace_path) {
+            if let Err(err) = run_on_one_repo(&mut repo, size) {
+                log::error!(
+                    "Could not set vnode size for repo {:?}\nErr: {}",
+                    repo.path,
+                    err
+                )
+            }
+        }
+        bar.inc(1);
+    }
+    Ok(())
+}
+
+fn run_on_one_repo(repo: &mut LocalRepository, size: u64) -> Result<(), OxenError> {
+    log::info!(
+        "Changing vnode size from {} to {} for repo {:?}",
+        repo.vnode_size(),
+        size,
+        repo.path
+    );
+    repo.set_vnode_size(size);
+    repo.save()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::error::OxenError;
+    use crate::model::LocalRepository;
+    use crate::repositories;
+    use crate::test;
+    use crate::test::add_n_files_m_dirs;
+    use crate::util;
+
+    #[tokio::test]
+    async fn test_set_vnode_size_keeps_old_commits_readable() -> Result<(), OxenError> {
+        test::run_empty_dir_test_async(|dir| async move {
+            let mut repo = repositories::init::init(dir)?;
+            repo.set_vnode_size(3);
+            repo.save()?;
+
+            add_n_files_m_dirs(&repo, 20, 2).await?;
+            let first_commit = repositories::commit(&repo, "First commit")?;
+
+            super::set_vnode_size(&repo.path, 10, false)?;
+            let repo = LocalRepository::from_dir(&repo.path)?;
+            assert_eq!(repo.vnode_size(), 10);
+
+            let new_file = repo.path.join("files").join("dir_0").join("new_file.txt");
+            util::fs::write_to_path(&new_file, "new file")?;
+            repositories::add(&repo, &new_file).await?;
+            let second_commit = repositories::commit(&repo, "Second commit")?;
+
+            // dir_0 was rewritten with the new size, dir_1 kept its old vnodes
+            let dir_0 = Path::new("files").join("dir_0");
+            let dir_1 = Path::new("files").join("dir_1");
+            let node =
+                repositories::tree::get_node_by_path_with_children(&repo, &second_commit, &dir_0)?;
+            assert_eq!(node.unwrap().num_vnodes(), 2);
+            let node =
+                repositories::tree::get_node_by_path_with_children(&repo, &second_commit, &dir_1)?;
+            assert_eq!(node.unwrap().num_vnodes(), 4);
+
+            for commit in [&first_commit, &second_commit] {
+                for i in 0..20 {
+                    let path = Path::new("files")
+                        .join(format!("dir_{}", i % 2))
+                        .join(format!("file{}.txt", i));
+                    let node = repositories::tree::get_file_by_path(&repo, commit, &path)?;
+                    assert!(node.is_some(), "missing {:?} in {}", path, commit.id);
+                }
+            }
+            let node = repositories::tree::get_file_by_path(
+                &repo,
+                &second_commit,
+                dir_0.join("new_file.txt"),
+            )?;
+            assert!(node.is_some());
+
+            Ok(())
+        })
+        .await
+    }
+}
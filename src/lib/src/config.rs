@@ -2,21 +2,62 @@
 //!
 
 pub mod auth_config;
+pub mod backup_config;
+pub mod branch_protection_config;
+pub mod cache_config;
+pub mod case_conflict_config;
+pub mod checks_config;
+pub mod commit_message_config;
 pub mod embedding_config;
 pub mod endpoint;
+pub mod merge_config;
+pub mod mirror_config;
+pub mod owners_config;
+pub mod remote_config;
 pub mod repository_config;
+pub mod retention_config;
 pub mod runtime_config;
+pub mod telemetry_config;
 pub mod user_config;
 
 pub use crate::config::auth_config::AuthConfig;
 pub use crate::config::auth_config::AUTH_CONFIG_FILENAME;
 
+pub use crate::config::backup_config::BackupConfig;
+
+pub use crate::config::branch_protection_config::{
+    BranchProtectionConfig, BranchProtectionRule, BRANCH_PROTECTION_CONFIG_FILENAME,
+};
+
+pub use crate::config::cache_config::{CacheConfig, CacherConfig};
+
+pub use crate::config::case_conflict_config::CaseConflictMode;
+
+pub use crate::config::checks_config::{
+    CheckRule, ChecksConfig, ValueRange, CHECKS_CONFIG_FILENAME,
+};
+
+pub use crate::config::commit_message_config::{CommitMessageConfig, CommitMessageRule};
+
 pub use crate::config::embedding_config::EmbeddingConfig;
 pub use crate::config::embedding_config::EMBEDDING_CONFIG_FILENAME;
 
 pub use crate::config::user_config::UserConfig;
 pub use crate::config::user_config::USER_CONFIG_FILENAME;
 
+pub use crate::config::merge_config::{MergeConfig, MergeRule, MergeStrategy};
+
+pub use crate::config::mirror_config::{MirrorConfig, MirrorRepoConfig};
+
+pub use crate::config::owners_config::{OwnersConfig, OwnersRule, OWNERS_CONFIG_FILENAME};
+
+pub use crate::config::remote_config::RemoteConfig;
+
 pub use crate::config::repository_config::RepositoryConfig;
 
+pub use crate::config::retention_config::RetentionConfig;
+
 pub use crate::config::runtime_config::RuntimeConfig;
+
+pub use crate::config::telemetry_config::TelemetryConfig;
+pub use crate::config::telemetry_config::TELEMETRY_CONFIG_FILENAME;
@@ -13,6 +13,9 @@ pub const AUTH_CONFIG_FILENAME: &str = "auth_config.toml";
 pub struct HostConfig {
     pub host: String,
     pub auth_token: Option<String>,
+    /// Pinned public key the host signs branch heads with, checked on fetch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signing_key: Option<String>,
 }
 
 impl HostConfig {
@@ -20,8 +23,16 @@ impl HostConfig {
         HostConfig {
             host: String::from(host),
             auth_token: None,
+            signing_key: None,
         }
     }
+
+    /// Short, non-reversible id for the token so it can be shown without leaking it
+    pub fn token_fingerprint(&self) -> Option<String> {
+        self.auth_token
+            .as_ref()
+            .map(|token| format!("sha256:{}", &util::hasher::hash_str_sha256(token)[..12]))
+    }
 }
 
 // Hash on the id field so we can quickly look up
@@ -105,11 +116,26 @@ impl AuthConfig {
     }
 
     pub fn add_host_auth_token<S: AsRef<str>>(&mut self, host: S, token: S) {
-        let host = host.as_ref();
-        self.host_configs.replace(HostConfig {
-            host: String::from(host),
-            auth_token: Some(String::from(token.as_ref())),
-        });
+        let mut config = self.host_config_or_default(host.as_ref());
+        config.auth_token = Some(String::from(token.as_ref()));
+        self.host_configs.replace(config);
+    }
+
+    pub fn add_host_signing_key<S: AsRef<str>>(&mut self, host: S, public_key: S) {
+        let mut config = self.host_config_or_default(host.as_ref());
+        config.signing_key = Some(String::from(public_key.as_ref()));
+        self.host_configs.replace(config);
+    }
+
+    pub fn signing_key_for_host<S: AsRef<str>>(&self, host: S) -> Option<String> {
+        self.host_configs
+            .get(&HostConfig::from_host(host.as_ref()))
+            .and_then(|config| config.signing_key.clone())
+    }
+
+    fn host_config_or_default(&self, host: &str) -> HostConfig {
+        let config = HostConfig::from_host(host);
+        self.host_configs.get(&config).cloned().unwrap_or(config)
     }
 
     pub fn auth_token_for_host<S: AsRef<str>>(&self, host: S) -> Option<String> {
@@ -128,9 +154,22 @@ impl AuthConfig {
 
 #[cfg(test)]
 mod tests {
+    use crate::config::auth_config::HostConfig;
     use crate::config::AuthConfig;
     use crate::error::OxenError;
     use crate::test;
+    #[test]
+    fn test_token_fingerprint_does_not_contain_token() {
+        let mut host_config = HostConfig::from_host("hub.oxen.ai");
+        assert_eq!(host_config.token_fingerprint(), None);
+
+        host_config.auth_token = Some(String::from("my-secret-token"));
+        let fingerprint = host_config.token_fingerprint().unwrap();
+        assert!(fingerprint.starts_with("sha256:"));
+        assert!(!fingerprint.contains("my-secret-token"));
+        assert_eq!(host_config.token_fingerprint(), Some(fingerprint));
+    }
+
     #[test]
     fn test_second_auth_should_overwrite_first() -> Result<(), OxenError> {
         let mut auth_config = AuthConfig::new(&test::auth_cfg_file());
@@ -150,4 +189,24 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_signing_key_kept_when_token_changes() -> Result<(), OxenError> {
+        let mut auth_config = AuthConfig::new(&test::auth_cfg_file());
+
+        let host = "mirror.oxen.ai";
+        auth_config.add_host_signing_key(host, "abcd");
+        auth_config.add_host_auth_token(host, "1234");
+
+        assert_eq!(
+            auth_config.signing_key_for_host(host),
+            Some("abcd".to_string())
+        );
+        assert_eq!(
+            auth_config.auth_token_for_host(host),
+            Some("1234".to_string())
+        );
+
+        Ok(())
+    }
 }
@@ -0,0 +1,53 @@
+//! Scheduled snapshots of every repository on a server, passed to
+//! `oxen-server start --backup-config`
+//!
+//! ```toml
+//! dir = "/mnt/backups/oxen"
+//! # Take a snapshot every 6 hours
+//! interval_hours = 6
+//! # Every 4th snapshot is a full one, the rest only copy what changed
+//! full_every = 4
+//! ```
+//!
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::error::OxenError;
+use crate::util;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BackupConfig {
+    /// Where the snapshots are written
+    pub dir: PathBuf,
+    #[serde(default = "default_interval_hours")]
+    pub interval_hours: u64,
+    /// Take a full snapshot after this many snapshots, 1 makes every snapshot full
+    #[serde(default = "default_full_every")]
+    pub full_every: usize,
+}
+
+fn default_interval_hours() -> u64 {
+    24
+}
+
+fn default_full_every() -> usize {
+    7
+}
+
+impl BackupConfig {
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, OxenError> {
+        let contents = util::fs::read_from_path(&path)?;
+        let config: BackupConfig = toml::from_str(&contents)?;
+        if config.interval_hours == 0 {
+            return Err(OxenError::basic_str(
+                "Backup interval_hours must be greater than 0",
+            ));
+        }
+        Ok(config)
+    }
+
+    pub fn interval(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.interval_hours * 60 * 60)
+    }
+}
@@ -0,0 +1,59 @@
+//! Per repository branch protection rules, stored in `.oxen/branch_protection.toml` on the server
+//!
+//! ```toml
+//! [[rules]]
+//! branch = "main"
+//! require_merge = true
+//!
+//! [[rules]]
+//! branch = "release/*"
+//! ```
+//!
+
+use glob::Pattern;
+use serde::{Deserialize, Serialize};
+
+pub const BRANCH_PROTECTION_CONFIG_FILENAME: &str = "branch_protection.toml";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct BranchProtectionConfig {
+    #[serde(default)]
+    pub rules: Vec<BranchProtectionRule>,
+}
+
+/// Protected branches can not be deleted or moved to a commit that does not descend from their head
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BranchProtectionRule {
+    /// Branch name, or a glob matched against branch names
+    pub branch: String,
+    /// Only allow the branch to move through the merge endpoints, rejecting direct pushes
+    #[serde(default)]
+    pub require_merge: bool,
+}
+
+impl BranchProtectionRule {
+    pub fn new(branch: impl AsRef<str>) -> BranchProtectionRule {
+        BranchProtectionRule {
+            branch: branch.as_ref().to_string(),
+            require_merge: false,
+        }
+    }
+
+    pub fn matches(&self, branch_name: impl AsRef<str>) -> bool {
+        match Pattern::new(&self.branch) {
+            Ok(pattern) => pattern.matches(branch_name.as_ref()),
+            Err(err) => {
+                log::warn!("Invalid branch protection rule {:?}: {}", self.branch, err);
+                self.branch == branch_name.as_ref()
+            }
+        }
+    }
+}
+
+impl BranchProtectionConfig {
+    /// The first rule that matches the branch, if it is protected
+    pub fn rule(&self, branch_name: impl AsRef<str>) -> Option<&BranchProtectionRule> {
+        let branch_name = branch_name.as_ref();
+        self.rules.iter().find(|rule| rule.matches(branch_name))
+    }
+}
@@ -0,0 +1,91 @@
+//! Which post-commit cachers run and on what, set per repository under `[cache]` in
+//! `.oxen/config.toml`, or for a whole server with `oxen-server start --cache-config`
+//!
+//! ```toml
+//! [cache.cachers.content_validator]
+//! enabled = true
+//! # Only check that version files larger than 1GB exist, without re-hashing them
+//! max_file_size = 1000000000
+//! ```
+//!
+//! The server config file uses the same keys without the `cache.` prefix.
+//!
+//! `tree = true` under `[cache]` also keeps fully loaded commit trees on disk so that CLI
+//! commands don't walk the node dbs again on every run, see `core::v_latest::index::tree_cache`.
+//! `merkle_cache_max_bytes` caps how much the in-memory merkle node cache holds, per
+//! repository.
+//!
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::error::OxenError;
+use crate::util;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct CacheConfig {
+    /// Settings keyed by cacher name, cachers that are not listed run with the defaults
+    #[serde(default)]
+    pub cachers: BTreeMap<String, CacherConfig>,
+    /// Keep fully loaded commit trees in `.oxen/cache/tree`
+    #[serde(default)]
+    pub tree: bool,
+    /// Byte budget of the in-memory merkle node cache, see `merkle_tree_node_cache`
+    pub merkle_cache_max_bytes: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CacherConfig {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Files larger than this many bytes get the cheap version of the cacher, or are skipped
+    pub max_file_size: Option<u64>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl Default for CacherConfig {
+    fn default() -> Self {
+        CacherConfig {
+            enabled: default_enabled(),
+            max_file_size: None,
+        }
+    }
+}
+
+impl CacherConfig {
+    /// Whether a file of this size is within the cacher's threshold
+    pub fn within_max_file_size(&self, num_bytes: u64) -> bool {
+        self.max_file_size.is_none_or(|max| num_bytes <= max)
+    }
+}
+
+impl CacheConfig {
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, OxenError> {
+        let contents = util::fs::read_from_path(&path)?;
+        Ok(toml::from_str(&contents)?)
+    }
+
+    /// The settings for a cacher, or the defaults if it is not configured
+    pub fn cacher(&self, name: impl AsRef<str>) -> CacherConfig {
+        self.cachers.get(name.as_ref()).cloned().unwrap_or_default()
+    }
+
+    /// Combine the server settings with a repository's. A repository can turn cachers
+    /// off or lower their thresholds, but not turn back on what the server disabled.
+    pub fn restrict(&self, repo_config: &CacheConfig, name: impl AsRef<str>) -> CacherConfig {
+        let server = self.cacher(&name);
+        let repo = repo_config.cacher(&name);
+        let max_file_size = match (server.max_file_size, repo.max_file_size) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        CacherConfig {
+            enabled: server.enabled && repo.enabled,
+            max_file_size,
+        }
+    }
+}
@@ -0,0 +1,52 @@
+//! What to do with paths that only differ by case, set with `case_conflicts` in
+//! `.oxen/config.toml`
+//!
+//! ```toml
+//! # Refuse to add, commit or check out paths that collide on case-insensitive file systems
+//! case_conflicts = "error"
+//! ```
+//!
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::OxenError;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CaseConflictMode {
+    /// Never check, for repositories only used on case-sensitive file systems
+    Allow,
+    /// Print the colliding paths and carry on
+    #[default]
+    Warn,
+    /// Refuse to add, commit or check out colliding paths
+    Error,
+}
+
+impl fmt::Display for CaseConflictMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CaseConflictMode::Allow => write!(f, "allow"),
+            CaseConflictMode::Warn => write!(f, "warn"),
+            CaseConflictMode::Error => write!(f, "error"),
+        }
+    }
+}
+
+impl FromStr for CaseConflictMode {
+    type Err = OxenError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "allow" => Ok(CaseConflictMode::Allow),
+            "warn" => Ok(CaseConflictMode::Warn),
+            "error" => Ok(CaseConflictMode::Error),
+            _ => Err(OxenError::basic_str(format!(
+                "Unknown case conflict mode {s:?}, use allow, warn or error"
+            ))),
+        }
+    }
+}
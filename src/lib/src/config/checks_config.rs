@@ -0,0 +1,99 @@
+//! Per repository data validation rules, stored in `.oxen/checks.toml`
+//!
+//! ```toml
+//! [[rules]]
+//! path = "annotations/**/*.csv"
+//! required_columns = ["file", "label"]
+//! unique = ["file"]
+//!
+//! [rules.dtypes]
+//! min_x = "f64"
+//!
+//! [rules.max_null_fraction]
+//! label = 0.0
+//!
+//! [rules.ranges]
+//! min_x = { min = 0.0 }
+//! ```
+//!
+//! Every rule that matches a tabular file is checked when it is added or committed, see
+//! [`crate::repositories::checks`].
+//!
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use glob::Pattern;
+use serde::{Deserialize, Serialize};
+
+pub const CHECKS_CONFIG_FILENAME: &str = "checks.toml";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ChecksConfig {
+    #[serde(default)]
+    pub rules: Vec<CheckRule>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct CheckRule {
+    /// Glob matched against the path of the file relative to the repository root
+    pub path: String,
+    /// Columns that must be present
+    #[serde(default)]
+    pub required_columns: Vec<String>,
+    /// Expected dtype of a column, such as `str`, `i64` or `f64`
+    #[serde(default)]
+    pub dtypes: BTreeMap<String, String>,
+    /// Largest fraction of a column's values that may be null, 0.0 to require no nulls
+    #[serde(default)]
+    pub max_null_fraction: BTreeMap<String, f64>,
+    /// Bounds the numeric values of a column must fall within
+    #[serde(default)]
+    pub ranges: BTreeMap<String, ValueRange>,
+    /// Columns that together must identify each row
+    #[serde(default)]
+    pub unique: Vec<String>,
+}
+
+/// Inclusive bounds for the values of a column, either side may be left open
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct ValueRange {
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+impl ValueRange {
+    pub fn contains(&self, value: f64) -> bool {
+        self.min.is_none_or(|min| value >= min) && self.max.is_none_or(|max| value <= max)
+    }
+}
+
+impl CheckRule {
+    pub fn new(path: impl AsRef<str>) -> CheckRule {
+        CheckRule {
+            path: path.as_ref().to_string(),
+            ..CheckRule::default()
+        }
+    }
+
+    pub fn matches(&self, path: impl AsRef<Path>) -> bool {
+        match Pattern::new(&self.path) {
+            Ok(pattern) => pattern.matches_path(path.as_ref()),
+            Err(err) => {
+                log::warn!("Invalid check rule path {:?}: {}", self.path, err);
+                false
+            }
+        }
+    }
+}
+
+impl ChecksConfig {
+    /// All the rules that apply to a file
+    pub fn rules_for(&self, path: impl AsRef<Path>) -> Vec<&CheckRule> {
+        let path = path.as_ref();
+        self.rules
+            .iter()
+            .filter(|rule| rule.matches(path))
+            .collect()
+    }
+}
@@ -0,0 +1,100 @@
+//! Per repository commit message settings, stored under `[commit_message]` in `.oxen/config.toml`
+//!
+//! ```toml
+//! [commit_message]
+//! template = "OXEN-: \n\n# Describe what changed and why"
+//!
+//! [[commit_message.rules]]
+//! pattern = "^[A-Z]+-[0-9]+: "
+//! description = "a ticket id at the start, e.g. OXEN-123: "
+//! ```
+//!
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::error::OxenError;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct CommitMessageConfig {
+    /// Text the commit message editor starts from. Lines starting with `#` are dropped.
+    pub template: Option<String>,
+    #[serde(default)]
+    pub rules: Vec<CommitMessageRule>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CommitMessageRule {
+    /// Regex that must match somewhere in the commit message
+    pub pattern: String,
+    /// What the pattern requires, shown to the user when it does not match
+    pub description: Option<String>,
+}
+
+impl CommitMessageRule {
+    pub fn new(pattern: impl AsRef<str>) -> CommitMessageRule {
+        CommitMessageRule {
+            pattern: pattern.as_ref().to_string(),
+            description: None,
+        }
+    }
+
+    fn describe(&self) -> String {
+        match &self.description {
+            Some(description) => format!("{} (/{}/)", description, self.pattern),
+            None => format!("/{}/", self.pattern),
+        }
+    }
+}
+
+impl CommitMessageConfig {
+    /// Strip the `#` comment lines a message was edited with, like the template
+    pub fn clean(message: impl AsRef<str>) -> String {
+        message
+            .as_ref()
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .collect::<Vec<_>>()
+            .join("\n")
+            .trim()
+            .to_string()
+    }
+
+    /// Check a commit message against the rules, listing every rule it is missing
+    pub fn validate(&self, message: impl AsRef<str>) -> Result<(), OxenError> {
+        let message = message.as_ref();
+        let mut missing: Vec<String> = vec![];
+
+        if let Some(template) = &self.template {
+            let template = CommitMessageConfig::clean(template);
+            if !template.is_empty() && message.trim() == template {
+                missing.push("changes to the commit message template".to_string());
+            }
+        }
+
+        for rule in self.rules.iter() {
+            let regex = Regex::new(&rule.pattern).map_err(|err| {
+                OxenError::basic_str(format!(
+                    "Invalid commit message pattern {:?}: {}",
+                    rule.pattern, err
+                ))
+            })?;
+            if !regex.is_match(message) {
+                missing.push(rule.describe());
+            }
+        }
+
+        if missing.is_empty() {
+            return Ok(());
+        }
+
+        let missing = missing
+            .iter()
+            .map(|m| format!("  - {m}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        Err(OxenError::invalid_commit_message(format!(
+            "Commit message {message:?} is missing:\n{missing}\n"
+        )))
+    }
+}
@@ -0,0 +1,136 @@
+//! Per repository merge settings, stored under `[merge]` in `.oxen/config.toml`
+//!
+//! ```toml
+//! [[merge.rules]]
+//! path = "annotations/*/bounding_box.csv"
+//! keys = ["file", "min_x"]
+//!
+//! [[merge.rules]]
+//! path = "generated/**"
+//! strategy = "theirs"
+//!
+//! [[merge.rules]]
+//! path = "models/*.json"
+//! driver = "python merge_json.py %O %A %B"
+//! ```
+//!
+//! A driver is an external command run through the shell for each conflicting file that
+//! matches, with `%O` replaced by a copy of the common ancestor, `%A` by a copy of our
+//! version, `%B` by a copy of their version and `%P` by the path of the file. The driver
+//! writes the merged file to `%A` and exits with 0. On any other exit code the conflict is
+//! resolved with the strategy of the matching rules, or left for the user if there is none.
+//!
+
+use glob::Pattern;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::error::OxenError;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct MergeConfig {
+    #[serde(default)]
+    pub rules: Vec<MergeRule>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MergeRule {
+    /// Glob matched against the path of the file relative to the repository root
+    pub path: String,
+    /// Columns that uniquely identify a row when three-way merging a tabular file
+    pub keys: Option<Vec<String>>,
+    /// How to resolve conflicts in matching files instead of leaving them for the user
+    pub strategy: Option<MergeStrategy>,
+    /// External command that merges matching files, run before any strategy
+    pub driver: Option<String>,
+}
+
+/// How to automatically resolve a file that conflicts during a merge
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MergeStrategy {
+    /// Keep the version from the branch being merged into
+    Ours,
+    /// Take the version from the branch being merged in
+    Theirs,
+    /// Keep the rows or lines from both versions
+    Union,
+}
+
+impl fmt::Display for MergeStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let strategy = match self {
+            MergeStrategy::Ours => "ours",
+            MergeStrategy::Theirs => "theirs",
+            MergeStrategy::Union => "union",
+        };
+        write!(f, "{}", strategy)
+    }
+}
+
+impl FromStr for MergeStrategy {
+    type Err = OxenError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ours" => Ok(MergeStrategy::Ours),
+            "theirs" => Ok(MergeStrategy::Theirs),
+            "union" => Ok(MergeStrategy::Union),
+            _ => Err(OxenError::basic_str(format!(
+                "Invalid merge strategy {s:?}, must be one of ours, theirs or union"
+            ))),
+        }
+    }
+}
+
+impl MergeRule {
+    pub fn new(path: impl AsRef<str>) -> MergeRule {
+        MergeRule {
+            path: path.as_ref().to_string(),
+            keys: None,
+            strategy: None,
+            driver: None,
+        }
+    }
+
+    pub fn matches(&self, path: impl AsRef<Path>) -> bool {
+        match Pattern::new(&self.path) {
+            Ok(pattern) => pattern.matches_path(path.as_ref()),
+            Err(err) => {
+                log::warn!("Invalid merge rule path {:?}: {}", self.path, err);
+                false
+            }
+        }
+    }
+}
+
+impl MergeConfig {
+    /// Key columns for a tabular file, taken from the first matching rule that defines keys
+    pub fn tabular_keys(&self, path: impl AsRef<Path>) -> Option<Vec<String>> {
+        let path = path.as_ref();
+        self.rules
+            .iter()
+            .find(|rule| rule.keys.is_some() && rule.matches(path))
+            .and_then(|rule| rule.keys.clone())
+    }
+
+    /// Merge driver command for a file, taken from the first matching rule that defines a driver
+    pub fn driver(&self, path: impl AsRef<Path>) -> Option<String> {
+        let path = path.as_ref();
+        self.rules
+            .iter()
+            .find(|rule| rule.driver.is_some() && rule.matches(path))
+            .and_then(|rule| rule.driver.clone())
+    }
+
+    /// Conflict strategy for a file, taken from the first matching rule that defines a strategy
+    pub fn strategy(&self, path: impl AsRef<Path>) -> Option<MergeStrategy> {
+        let path = path.as_ref();
+        self.rules
+            .iter()
+            .find(|rule| rule.strategy.is_some() && rule.matches(path))
+            .and_then(|rule| rule.strategy)
+    }
+}
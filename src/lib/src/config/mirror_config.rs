@@ -0,0 +1,127 @@
+//! Repositories a server keeps in sync with an upstream server, passed to
+//! `oxen-server start --mirror-config`
+//!
+//! ```toml
+//! # Pull from the upstreams every 15 minutes
+//! interval_minutes = 15
+//!
+//! [[repos]]
+//! url = "https://hub.oxen.ai/ox/datasets"
+//! # Optional, defaults to the namespace and name in the url
+//! namespace = "ox"
+//! name = "datasets"
+//! # Optional, webhook triggered syncs must be signed with it. Mirrors without one only
+//! # sync on the interval.
+//! secret = "shared-secret"
+//! ```
+//!
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::error::OxenError;
+use crate::util;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MirrorConfig {
+    #[serde(default = "default_interval_minutes")]
+    pub interval_minutes: u64,
+    #[serde(default)]
+    pub repos: Vec<MirrorRepoConfig>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MirrorRepoConfig {
+    /// Url of the upstream repository
+    pub url: String,
+    /// Namespace of the mirror on this server
+    pub namespace: Option<String>,
+    /// Name of the mirror on this server
+    pub name: Option<String>,
+    /// Secret the upstream signs its webhooks with, webhooks are rejected without one
+    pub secret: Option<String>,
+}
+
+fn default_interval_minutes() -> u64 {
+    60
+}
+
+impl MirrorConfig {
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, OxenError> {
+        let contents = util::fs::read_from_path(&path)?;
+        let config: MirrorConfig = toml::from_str(&contents)?;
+        if config.interval_minutes == 0 {
+            return Err(OxenError::basic_str(
+                "Mirror interval_minutes must be greater than 0",
+            ));
+        }
+        for repo in &config.repos {
+            repo.local_namespace_and_name()?;
+        }
+        Ok(config)
+    }
+
+    pub fn interval(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.interval_minutes * 60)
+    }
+
+    /// Find the mirror stored as `namespace/name` on this server
+    pub fn get(&self, namespace: &str, name: &str) -> Option<&MirrorRepoConfig> {
+        self.repos.iter().find(|repo| {
+            repo.local_namespace_and_name()
+                .is_ok_and(|(ns, n)| ns == namespace && n == name)
+        })
+    }
+}
+
+impl MirrorRepoConfig {
+    /// The namespace and name of the mirror on this server, taken from the end of the
+    /// upstream url unless they are set
+    pub fn local_namespace_and_name(&self) -> Result<(String, String), OxenError> {
+        let url = url::Url::parse(&self.url)?;
+        let mut segments: Vec<&str> = url
+            .path_segments()
+            .map(|segments| segments.filter(|s| !s.is_empty()).collect())
+            .unwrap_or_default();
+        let url_name = segments.pop();
+        let url_namespace = segments.pop();
+        match (
+            self.namespace.as_deref().or(url_namespace),
+            self.name.as_deref().or(url_name),
+        ) {
+            (Some(namespace), Some(name)) => Ok((namespace.to_string(), name.to_string())),
+            _ => Err(OxenError::basic_str(format!(
+                "Mirror url {} does not end with a namespace and name, set them in the config",
+                self.url
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::MirrorConfig;
+    use crate::error::OxenError;
+
+    #[test]
+    fn test_mirror_config_names_from_url() -> Result<(), OxenError> {
+        let config: MirrorConfig = toml::from_str(
+            r#"
+            [[repos]]
+            url = "http://localhost:3000/ox/datasets"
+
+            [[repos]]
+            url = "http://localhost:3000/ox/images"
+            name = "images-standby"
+            "#,
+        )?;
+        assert_eq!(config.interval_minutes, 60);
+        assert_eq!(
+            config.repos[0].local_namespace_and_name()?,
+            ("ox".to_string(), "datasets".to_string())
+        );
+        assert!(config.get("ox", "images-standby").is_some());
+        assert!(config.get("ox", "images").is_none());
+        Ok(())
+    }
+}
@@ -0,0 +1,170 @@
+//! Per repository path owners, stored in `.oxen/owners` on the server
+//!
+//! ```text
+//! # Teams start with @ and list the names or emails of their members
+//! @data-eng = ada@oxen.ai grace@oxen.ai
+//!
+//! # A path glob followed by the users or teams that own it
+//! schemas/     @data-eng
+//! test/        @data-eng ox@oxen.ai
+//! *.parquet    ada@oxen.ai
+//! ```
+//!
+//! Like a CODEOWNERS file, the last rule that matches a file decides who owns it. A
+//! pattern ending in `/` owns everything in that directory, and a pattern without a `/`
+//! is also matched against file names anywhere in the repository.
+//!
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use glob::Pattern;
+use serde::{Deserialize, Serialize};
+
+use crate::error::OxenError;
+use crate::model::User;
+
+pub const OWNERS_CONFIG_FILENAME: &str = "owners";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct OwnersConfig {
+    /// Team name without the `@`, to the names or emails of its members
+    #[serde(default)]
+    pub teams: BTreeMap<String, Vec<String>>,
+    #[serde(default)]
+    pub rules: Vec<OwnersRule>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct OwnersRule {
+    /// Glob matched against the path of the file relative to the repository root
+    pub pattern: String,
+    /// Names or emails of users, or `@team`s
+    pub owners: Vec<String>,
+}
+
+impl OwnersRule {
+    pub fn matches(&self, path: impl AsRef<Path>) -> bool {
+        let path = path.as_ref();
+        let pattern = self.pattern.trim_start_matches('/');
+        if let Some(dir) = pattern.strip_suffix('/') {
+            return path.starts_with(dir);
+        }
+        if path.starts_with(pattern) {
+            return true;
+        }
+        match Pattern::new(pattern) {
+            Ok(glob) => {
+                glob.matches_path(path)
+                    || (!pattern.contains('/')
+                        && path
+                            .file_name()
+                            .is_some_and(|name| glob.matches(&name.to_string_lossy())))
+            }
+            Err(err) => {
+                log::warn!("Invalid owners pattern {:?}: {}", self.pattern, err);
+                false
+            }
+        }
+    }
+}
+
+impl OwnersConfig {
+    pub fn parse(data: &str) -> Result<OwnersConfig, OxenError> {
+        let mut config = OwnersConfig::default();
+        for (i, line) in data.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(team) = line.strip_prefix('@') {
+                let Some((name, members)) = team.split_once('=') else {
+                    return Err(OxenError::basic_str(format!(
+                        "Invalid owners file, line {}: teams are written `@name = member ...`",
+                        i + 1
+                    )));
+                };
+                config.teams.insert(
+                    name.trim().to_string(),
+                    members.split_whitespace().map(String::from).collect(),
+                );
+                continue;
+            }
+
+            let mut parts = line.split_whitespace();
+            let pattern = parts.next().unwrap_or_default().to_string();
+            let owners: Vec<String> = parts.map(String::from).collect();
+            if owners.is_empty() {
+                return Err(OxenError::basic_str(format!(
+                    "Invalid owners file, line {}: {pattern:?} has no owners",
+                    i + 1
+                )));
+            }
+            config.rules.push(OwnersRule { pattern, owners });
+        }
+        Ok(config)
+    }
+
+    /// The rule that decides who owns a file, the last one that matches
+    pub fn rule_for(&self, path: impl AsRef<Path>) -> Option<&OwnersRule> {
+        let path = path.as_ref();
+        self.rules.iter().rev().find(|rule| rule.matches(path))
+    }
+
+    /// Whether the user is one of the rule's owners, directly or through a team
+    pub fn is_owner(&self, rule: &OwnersRule, user: &User) -> bool {
+        let is_user =
+            |owner: &str| owner.eq_ignore_ascii_case(&user.email) || owner == user.name.as_str();
+        rule.owners
+            .iter()
+            .any(|owner| match owner.strip_prefix('@') {
+                Some(team) => self
+                    .teams
+                    .get(team)
+                    .is_some_and(|members| members.iter().any(|member| is_user(member))),
+                None => is_user(owner),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::OwnersConfig;
+    use crate::error::OxenError;
+    use crate::model::User;
+
+    #[test]
+    fn test_owners_config_last_matching_rule_wins() -> Result<(), OxenError> {
+        let config = OwnersConfig::parse(
+            "# Owners\n@data-eng = ada@oxen.ai grace@oxen.ai\n\n*.parquet ox@oxen.ai\nschemas/ @data-eng  # the schemas\n/test ox@oxen.ai\n",
+        )?;
+        assert_eq!(config.rules.len(), 3);
+
+        let rule = config.rule_for("schemas/images.json").unwrap();
+        assert_eq!(rule.pattern, "schemas/");
+        let rule = config.rule_for("schemas/train.parquet").unwrap();
+        assert_eq!(rule.pattern, "schemas/");
+        let rule = config.rule_for("data/train.parquet").unwrap();
+        assert_eq!(rule.pattern, "*.parquet");
+        let rule = config.rule_for("test/labels.csv").unwrap();
+        assert_eq!(rule.pattern, "/test");
+        assert!(config.rule_for("train/labels.csv").is_none());
+        assert!(config.rule_for("testing/labels.csv").is_none());
+
+        let schemas = config.rule_for("schemas/images.json").unwrap();
+        let grace = User {
+            name: "Grace".to_string(),
+            email: "Grace@oxen.ai".to_string(),
+        };
+        assert!(config.is_owner(schemas, &grace));
+        let ox = User {
+            name: "Ox".to_string(),
+            email: "ox@oxen.ai".to_string(),
+        };
+        assert!(!config.is_owner(schemas, &ox));
+
+        assert!(OwnersConfig::parse("schemas/\n").is_err());
+        Ok(())
+    }
+}
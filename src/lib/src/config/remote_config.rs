@@ -0,0 +1,18 @@
+//! How the repository talks to its remotes, set under `[remote]` in `.oxen/config.toml`
+//!
+//! ```toml
+//! [remote]
+//! # Download version files over 8 connections at once on pull and fetch
+//! download_concurrency = 8
+//! ```
+//!
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct RemoteConfig {
+    /// Number of connections to download version files over, see
+    /// `util::concurrency::num_threads_for_items` when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub download_concurrency: Option<usize>,
+}
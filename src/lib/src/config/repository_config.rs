@@ -1,11 +1,15 @@
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 
+use crate::config::{
+    CacheConfig, CaseConflictMode, CommitMessageConfig, MergeConfig, RemoteConfig, RetentionConfig,
+};
 use crate::constants::DEFAULT_VNODE_SIZE;
 use crate::error::OxenError;
 use crate::model::{LocalRepository, Remote};
 use crate::storage::StorageConfig;
 use crate::util;
+use crate::util::hasher::HashAlgorithm;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RepositoryConfig {
@@ -26,6 +30,22 @@ pub struct RepositoryConfig {
     /// Currently used only for remote mode
     pub workspace_name: Option<String>,
     pub workspaces: Option<Vec<String>>,
+    /// Merge rules, such as the key columns for tabular files
+    pub merge: Option<MergeConfig>,
+    /// Commit message template and the rules messages must follow
+    pub commit_message: Option<CommitMessageConfig>,
+    /// Which post-commit cachers run on the server
+    pub cache: Option<CacheConfig>,
+    /// How long the version files of old commits are kept
+    pub retention: Option<RetentionConfig>,
+    /// Algorithm used to hash file contents, xxh3 when unset
+    pub hash_algorithm: Option<HashAlgorithm>,
+    /// Reject all mutating operations, clone, pull and download still work
+    pub read_only: Option<bool>,
+    /// How the repository talks to its remotes, such as the download concurrency
+    pub remote: Option<RemoteConfig>,
+    /// What to do with paths that only differ by case, warn when unset
+    pub case_conflicts: Option<CaseConflictMode>,
 }
 
 impl Default for RepositoryConfig {
@@ -50,6 +70,14 @@ impl RepositoryConfig {
             remote_mode: None,
             workspace_name: None,
             workspaces: None,
+            merge: None,
+            commit_message: None,
+            cache: None,
+            retention: None,
+            hash_algorithm: None,
+            read_only: None,
+            remote: None,
+            case_conflicts: None,
         }
     }
 
@@ -0,0 +1,30 @@
+//! How long a repository keeps the version files of old commits, set under
+//! `[retention]` in `.oxen/config.toml`
+//!
+//! ```toml
+//! [retention]
+//! # Keep the files of every commit from the last 90 days
+//! keep_all_days = 90
+//! # Older commits only keep their files if a branch points at them or they are pinned
+//! pinned_commits = ["a1b2c3d4e5f6a7b8c9d0e1f2a3b4c5d6"]
+//! ```
+//!
+
+use serde::{Deserialize, Serialize};
+use time::{Duration, OffsetDateTime};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RetentionConfig {
+    /// Every commit newer than this many days keeps its files
+    pub keep_all_days: u64,
+    /// Commits that always keep their files, such as releases
+    #[serde(default)]
+    pub pinned_commits: Vec<String>,
+}
+
+impl RetentionConfig {
+    /// Commits made before this time are subject to pruning
+    pub fn cutoff(&self, now: OffsetDateTime) -> OffsetDateTime {
+        now - Duration::days(self.keep_all_days as i64)
+    }
+}
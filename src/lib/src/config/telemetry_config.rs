@@ -0,0 +1,67 @@
+use crate::constants::DEFAULT_TELEMETRY_ENDPOINT;
+use crate::error::OxenError;
+use crate::util;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+pub const TELEMETRY_CONFIG_FILENAME: &str = "telemetry_config.toml";
+
+/// Anonymous usage telemetry, off unless the user runs `oxen telemetry on`
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TelemetryConfig {
+    pub enabled: bool,
+    /// Where events are sent, defaults to DEFAULT_TELEMETRY_ENDPOINT
+    pub endpoint: Option<String>,
+    /// Random id created when telemetry is turned on, not derived from the user or machine
+    pub install_id: Option<String>,
+}
+
+impl TelemetryConfig {
+    fn config_file() -> Result<PathBuf, OxenError> {
+        let config_dir = util::fs::oxen_config_dir()?;
+        Ok(config_dir.join(Path::new(TELEMETRY_CONFIG_FILENAME)))
+    }
+
+    /// Read the telemetry config, telemetry is disabled if it has never been set
+    pub fn get() -> Result<TelemetryConfig, OxenError> {
+        let config_file = Self::config_file()?;
+        if !config_file.exists() {
+            return Ok(TelemetryConfig::default());
+        }
+        let contents = util::fs::read_from_path(&config_file)?;
+        Ok(toml::from_str(&contents)?)
+    }
+
+    pub fn endpoint(&self) -> String {
+        self.endpoint
+            .clone()
+            .unwrap_or(DEFAULT_TELEMETRY_ENDPOINT.to_string())
+    }
+
+    pub fn enable(&mut self, endpoint: Option<String>) {
+        self.enabled = true;
+        if endpoint.is_some() {
+            self.endpoint = endpoint;
+        }
+        if self.install_id.is_none() {
+            self.install_id = Some(uuid::Uuid::new_v4().to_string());
+        }
+    }
+
+    /// Turning telemetry off also forgets the install id
+    pub fn disable(&mut self) {
+        self.enabled = false;
+        self.install_id = None;
+    }
+
+    pub fn save_default(&self) -> Result<(), OxenError> {
+        let config_file = Self::config_file()?;
+        if let Some(config_dir) = config_file.parent() {
+            util::fs::create_dir_all(config_dir)?;
+        }
+        log::debug!("Saving telemetry config to {:?}", config_file);
+        let toml = toml::to_string(&self)?;
+        util::fs::write_to_path(&config_file, toml)?;
+        Ok(())
+    }
+}
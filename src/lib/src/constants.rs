@@ -23,6 +23,8 @@ pub const REPO_CONFIG_FILENAME: &str = "config.toml";
 pub const HEAD_FILE: &str = "HEAD";
 /// refs/ is a key,val store of branch names to commit ids
 pub const REFS_DIR: &str = "refs";
+/// refs/tags/ holds one json file per annotated tag
+pub const TAGS_DIR: &str = "tags";
 /// history/ dir is a list of directories named after commit ids
 pub const HISTORY_DIR: &str = "history";
 /// commits/ is a key-value database of commit ids to commit objects
@@ -90,6 +92,8 @@ pub const MAX_QUERYABLE_ROWS: usize = 5_000_000;
 pub const SYNC_STATUS_DIR: &str = "sync_status";
 /// Flag for if the repository was cloned in a shallow fashion
 pub const SHALLOW_FLAG: &str = "SHALLOW";
+/// File listing the oldest commits of a clone made with a history depth, whose parents were not fetched
+pub const SHALLOW_BOUNDARY_FILE: &str = "shallow_boundary";
 /// prefix for the commit indices
 pub const INDICES_DIR: &str = "indices";
 /// prefix for the schema fields that are indexed
@@ -114,6 +118,10 @@ pub const VERSION_FILE_NAME: &str = "data";
 pub const VERSION_CHUNK_FILE_NAME: &str = "chunk";
 /// Chunks directory for version files
 pub const VERSION_CHUNKS_DIR: &str = "chunks";
+/// Local cache of version files for stores that keep them remotely, such as S3
+pub const VERSIONS_CACHE_DIR: &str = "cache";
+/// Decompressed copies of version files when the repo stores them compressed
+pub const VERSIONS_DECOMPRESSED_DIR: &str = "decompressed";
 /// merge/ is where any merge conflicts are stored so that we can get rid of them
 pub const MERGE_DIR: &str = "merge";
 /// mods/ is where we can stage appends, modifications, deletions to files to be merged later
@@ -143,6 +151,8 @@ pub const DEFAULT_REMOTE_NAME: &str = "origin";
 pub const DEFAULT_HOST: &str = "hub.oxen.ai";
 /// Default remote scheme: https
 pub const DEFAULT_SCHEME: &str = "https";
+/// Where opt-in usage telemetry is sent unless the user configures another endpoint
+pub const DEFAULT_TELEMETRY_ENDPOINT: &str = "https://hub.oxen.ai/api/telemetry";
 
 /// Default Namespace: ox
 pub const DEFAULT_NAMESPACE: &str = "ox";
@@ -176,6 +186,8 @@ pub const EVAL_DURATION_COL: &str = "_oxen_eval_duration";
 // Average chunk size of ~10mb
 /// Average chunk size of ~10mb when chunking and sending data
 pub const AVG_CHUNK_SIZE: u64 = 1024 * 1024 * 10;
+/// Files larger than this are pushed through a resumable upload session
+pub const RESUMABLE_UPLOAD_THRESHOLD: u64 = AVG_CHUNK_SIZE * 10;
 // Retry and back off of requests N times
 /// Retry and back off of requests N times
 #[cfg(test)]
@@ -203,9 +215,39 @@ pub const TABULAR: &str = "tabular";
 pub const BINARY: &str = "binary";
 pub const DIR: &str = "dir";
 
+/// Mode committed for regular files, only the executable bit of a file is tracked
+pub const DEFAULT_FILE_MODE: u32 = 0o644;
+/// Mode committed for files that can be executed
+pub const EXECUTABLE_FILE_MODE: u32 = 0o755;
+
 /// Minimum allowable oxen version to push or pull data
 pub const MIN_OXEN_VERSION: MinOxenVersion = MinOxenVersion::LATEST;
 
+/// Append-only log of branch, fork and workspace events for the activity feed
+pub const ACTIVITY_FILE: &str = "activity.jsonl";
+/// Append-only log of every branch and HEAD movement, used to recover lost commits
+pub const REFLOG_FILE: &str = "reflog.jsonl";
+/// Directory in .oxen holding one json file per stash entry, the contents live in the version store
+pub const STASH_DIR: &str = "stash";
+/// State of an `oxen bisect` in progress: the good, bad and skipped commits and the HEAD to go back to
+pub const BISECT_FILE: &str = "bisect.json";
+/// Journal of the chunks a push in progress has uploaded, so a failed push can resume
+pub const PUSH_STATE_FILE: &str = "push-state";
+/// Resumable upload sessions in progress on the server, one directory per content hash
+pub const UPLOADS_DIR: &str = "uploads";
+/// Webhooks registered on a repository, with the secrets their payloads are signed with
+pub const HOOKS_FILE: &str = "hooks.toml";
+/// Scheduled jobs of a repository on the server, with their cron schedules and actions
+pub const JOBS_FILE: &str = "jobs.toml";
+/// Run history of the scheduled jobs on the server, one json file per job
+pub const JOB_RUNS_DIR: &str = "job_runs";
+/// Merge proposals of a repository on the server, one json file per proposal
+pub const PROPOSALS_DIR: &str = "proposals";
+/// Sync status of each mirrored repository, in the sync dir's .oxen dir
+pub const MIRRORS_DIR: &str = "mirrors";
+/// ed25519 key the server signs branch heads and tree roots with, in the sync dir's .oxen dir
+pub const SIGNING_KEY_FILE: &str = "signing_key";
+
 /// Filepath used to track repo and server-level migration status
 pub const LAST_MIGRATION_FILE: &str = "last_migration.txt";
 
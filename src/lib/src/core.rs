@@ -1,13 +1,16 @@
 //! Core functionality for Oxen
 //!
 
+pub mod cache;
 pub mod commit_sync_status;
 pub mod db;
 pub mod df;
+pub mod embeddings;
 pub mod merge;
 pub mod oxenignore;
 pub mod progress;
 pub mod refs;
+pub mod render;
 pub mod staged;
 pub mod v_latest;
 pub mod v_old;
@@ -0,0 +1,15 @@
+//! # Cache
+//!
+//! Post-commit processing for commits that land on the server, such as validating
+//! that every version file arrived intact. Each [`Cacher`] runs as a job on the
+//! [`CacheScheduler`], and the outcome is persisted as a [`CacheJobRecord`] so it
+//! can be inspected after the fact.
+//!
+
+pub mod cachers;
+pub mod job;
+pub mod scheduler;
+
+pub use cachers::Cacher;
+pub use job::{CacheJobRecord, CacheJobStatus};
+pub use scheduler::{CacheScheduler, CacheSchedulerOpts};
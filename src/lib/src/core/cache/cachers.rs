@@ -0,0 +1,29 @@
+//! The cachers that run on each commit
+
+pub mod content_validator;
+
+use crate::config::CacherConfig;
+use crate::error::OxenError;
+use crate::model::{Commit, LocalRepository};
+
+/// A unit of post-commit work, run once per commit
+#[derive(Clone, Copy, Debug)]
+pub struct Cacher {
+    /// Unique name, used to persist the job state and look the cacher up
+    pub name: &'static str,
+    /// Higher priority cachers run first when the scheduler is saturated
+    pub priority: u8,
+    /// Runs with the settings for this cacher, after the server and repo configs are combined
+    pub run: fn(&LocalRepository, &Commit, &CacherConfig) -> Result<(), OxenError>,
+}
+
+/// Every cacher, in the order they are submitted for a commit
+pub fn all() -> Vec<Cacher> {
+    vec![content_validator::CACHER]
+}
+
+/// Look up a cacher by name
+pub fn get(name: impl AsRef<str>) -> Option<Cacher> {
+    let name = name.as_ref();
+    all().into_iter().find(|cacher| cacher.name == name)
+}
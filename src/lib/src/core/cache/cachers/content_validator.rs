@@ -0,0 +1,71 @@
+//! Checks that every file in a commit has a version file matching its hash, and
+//! records the result at [`util::fs::commit_content_is_valid_path`]. Files over the
+//! configured `max_file_size` are only checked for existence.
+
+use std::path::PathBuf;
+
+use crate::config::CacherConfig;
+use crate::core::cache::Cacher;
+use crate::error::OxenError;
+use crate::model::{Commit, LocalRepository, MerkleHash};
+use crate::repositories;
+use crate::util;
+
+pub const NAME: &str = "content_validator";
+
+pub const CACHER: Cacher = Cacher {
+    name: NAME,
+    priority: 100,
+    run: validate,
+};
+
+/// Returns an error listing the files that are missing or corrupted, so the scheduler
+/// retries in case the versions are still being uploaded
+pub fn validate(
+    repo: &LocalRepository,
+    commit: &Commit,
+    config: &CacherConfig,
+) -> Result<(), OxenError> {
+    let Some(tree) = repositories::tree::get_root_with_children(repo, commit)? else {
+        return Err(OxenError::commit_id_does_not_exist(&commit.id));
+    };
+
+    // Only the local store can be re-hashed cheaply, remote stores are checked for existence
+    let version_store = repo.version_store()?;
+    let check_contents = version_store.storage_type() == "local";
+
+    let files = repositories::tree::list_all_files(&tree, &PathBuf::from(""))?;
+    let mut invalid: Vec<String> = vec![];
+    for file in files.iter() {
+        let path = file.dir.join(file.file_node.name());
+        let hash = file.file_node.hash();
+        if !version_store.version_exists(&hash.to_string())? {
+            invalid.push(format!("{} is missing", path.display()));
+            continue;
+        }
+
+        if check_contents && config.within_max_file_size(file.file_node.num_bytes()) {
+            let version_path = version_store.get_version_path(&hash.to_string())?;
+            let actual = repo.hash_algorithm().hash_file_contents(&version_path)?;
+            if MerkleHash::new(actual) != *hash {
+                invalid.push(format!("{} does not match its hash", path.display()));
+            }
+        }
+    }
+
+    let is_valid = invalid.is_empty();
+    let is_valid_path = util::fs::commit_content_is_valid_path(repo, commit);
+    if let Some(parent) = is_valid_path.parent() {
+        util::fs::create_dir_all(parent)?;
+    }
+    util::fs::write_to_path(&is_valid_path, is_valid.to_string())?;
+
+    if !is_valid {
+        return Err(OxenError::basic_str(format!(
+            "Commit {} has invalid content:\n{}",
+            commit.id,
+            invalid.join("\n")
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,144 @@
+//! Persisted state of the cache jobs run on each commit
+//!
+//! Each job is written to `.oxen/history/<commit_id>/cache/jobs/<cacher>.json` as it
+//! moves through the queue, and jobs that run out of retries are also appended to
+//! `.oxen/cache/dead_letters.jsonl`.
+//!
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+use crate::constants::{CACHE_DIR, HISTORY_DIR};
+use crate::error::OxenError;
+use crate::model::LocalRepository;
+use crate::util;
+
+const JOBS_DIR: &str = "jobs";
+const DEAD_LETTERS_FILE: &str = "dead_letters.jsonl";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CacheJobStatus {
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CacheJobRecord {
+    pub cacher: String,
+    pub commit_id: String,
+    pub status: CacheJobStatus,
+    pub attempts: u32,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub started_at: Option<OffsetDateTime>,
+    /// How long the latest attempt took
+    pub duration_ms: Option<u64>,
+    /// The error from the latest failed attempt
+    pub error: Option<String>,
+}
+
+impl CacheJobRecord {
+    pub fn queued(cacher: impl AsRef<str>, commit_id: impl AsRef<str>) -> CacheJobRecord {
+        CacheJobRecord {
+            cacher: cacher.as_ref().to_string(),
+            commit_id: commit_id.as_ref().to_string(),
+            status: CacheJobStatus::Queued,
+            attempts: 0,
+            started_at: None,
+            duration_ms: None,
+            error: None,
+        }
+    }
+}
+
+fn jobs_dir(repo: &LocalRepository, commit_id: impl AsRef<str>) -> PathBuf {
+    util::fs::oxen_hidden_dir(&repo.path)
+        .join(HISTORY_DIR)
+        .join(commit_id.as_ref())
+        .join(CACHE_DIR)
+        .join(JOBS_DIR)
+}
+
+fn dead_letters_path(repo: &LocalRepository) -> PathBuf {
+    util::fs::oxen_hidden_dir(&repo.path)
+        .join(CACHE_DIR)
+        .join(DEAD_LETTERS_FILE)
+}
+
+/// Get the state of one cacher for a commit
+pub fn get(
+    repo: &LocalRepository,
+    commit_id: impl AsRef<str>,
+    cacher: impl AsRef<str>,
+) -> Result<Option<CacheJobRecord>, OxenError> {
+    let path = jobs_dir(repo, commit_id).join(format!("{}.json", cacher.as_ref()));
+    if !path.exists() {
+        return Ok(None);
+    }
+    let data = util::fs::read_from_path(&path)?;
+    Ok(Some(serde_json::from_str(&data)?))
+}
+
+/// List the state of every cacher that has been queued for a commit
+pub fn list(
+    repo: &LocalRepository,
+    commit_id: impl AsRef<str>,
+) -> Result<Vec<CacheJobRecord>, OxenError> {
+    let dir = jobs_dir(repo, commit_id);
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+    let mut records: Vec<CacheJobRecord> = vec![];
+    for entry in std::fs::read_dir(&dir)? {
+        let data = util::fs::read_from_path(entry?.path())?;
+        records.push(serde_json::from_str(&data)?);
+    }
+    records.sort_by(|a, b| a.cacher.cmp(&b.cacher));
+    Ok(records)
+}
+
+/// Save the state of a job
+pub fn write(repo: &LocalRepository, record: &CacheJobRecord) -> Result<(), OxenError> {
+    // The repo may have been deleted while the job was queued, don't recreate it
+    if !util::fs::oxen_hidden_dir(&repo.path).exists() {
+        return Ok(());
+    }
+    let dir = jobs_dir(repo, &record.commit_id);
+    util::fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("{}.json", record.cacher));
+    util::fs::write_to_path(&path, serde_json::to_string(record)?)
+}
+
+/// Record a job that ran out of retries
+pub fn write_dead_letter(repo: &LocalRepository, record: &CacheJobRecord) -> Result<(), OxenError> {
+    if !util::fs::oxen_hidden_dir(&repo.path).exists() {
+        return Ok(());
+    }
+    let path = dead_letters_path(repo);
+    if let Some(parent) = path.parent() {
+        util::fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+    writeln!(file, "{}", serde_json::to_string(record)?)?;
+    Ok(())
+}
+
+/// List the jobs that ran out of retries, oldest first
+pub fn list_dead_letters(repo: &LocalRepository) -> Result<Vec<CacheJobRecord>, OxenError> {
+    let path = dead_letters_path(repo);
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let data = util::fs::read_from_path(&path)?;
+    let mut records: Vec<CacheJobRecord> = vec![];
+    for line in data.lines().filter(|line| !line.trim().is_empty()) {
+        records.push(serde_json::from_str(line)?);
+    }
+    Ok(records)
+}
@@ -0,0 +1,461 @@
+//! Runs cache jobs in the background
+//!
+//! Jobs wait in a priority queue and run on the blocking thread pool, at most
+//! `max_concurrency` at a time. A failed job is put back on the queue after an
+//! exponential backoff, and once it is out of attempts it is recorded as a dead
+//! letter, so one bad commit can't wedge the queue.
+//!
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering as AtomicOrdering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use time::OffsetDateTime;
+use tokio::sync::{Notify, Semaphore};
+
+use crate::config::{CacheConfig, CacherConfig};
+use crate::core::cache::job::{self, CacheJobRecord, CacheJobStatus};
+use crate::core::cache::{cachers, Cacher};
+use crate::error::OxenError;
+use crate::model::{Commit, LocalRepository};
+
+#[derive(Clone, Debug)]
+pub struct CacheSchedulerOpts {
+    /// How many jobs can run at once
+    pub max_concurrency: usize,
+    /// How many times a job is tried before it is recorded as a dead letter
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled on each retry after that
+    pub retry_delay: Duration,
+    /// Server wide cacher settings, which repos can only make stricter
+    pub config: CacheConfig,
+}
+
+impl Default for CacheSchedulerOpts {
+    fn default() -> Self {
+        CacheSchedulerOpts {
+            max_concurrency: 4,
+            max_attempts: 3,
+            retry_delay: Duration::from_secs(2),
+            config: CacheConfig::default(),
+        }
+    }
+}
+
+/// Cheap to clone, all clones share the same queue
+#[derive(Clone)]
+pub struct CacheScheduler {
+    inner: Arc<SchedulerInner>,
+}
+
+struct SchedulerInner {
+    opts: CacheSchedulerOpts,
+    queue: Mutex<BinaryHeap<QueuedJob>>,
+    permits: Arc<Semaphore>,
+    next_seq: AtomicU64,
+    // Jobs that are queued, running, or waiting to retry
+    pending: AtomicUsize,
+    idle: Notify,
+}
+
+struct QueuedJob {
+    priority: u8,
+    seq: u64,
+    repo: LocalRepository,
+    commit: Commit,
+    cacher: Cacher,
+    config: CacherConfig,
+    record: CacheJobRecord,
+}
+
+// Highest priority first, then first in first out
+impl Ord for QueuedJob {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+impl PartialOrd for QueuedJob {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for QueuedJob {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority && self.seq == other.seq
+    }
+}
+
+impl Eq for QueuedJob {}
+
+impl Default for CacheScheduler {
+    fn default() -> Self {
+        CacheScheduler::new(CacheSchedulerOpts::default())
+    }
+}
+
+impl CacheScheduler {
+    pub fn new(opts: CacheSchedulerOpts) -> CacheScheduler {
+        let max_concurrency = opts.max_concurrency.max(1);
+        CacheScheduler {
+            inner: Arc::new(SchedulerInner {
+                opts,
+                queue: Mutex::new(BinaryHeap::new()),
+                permits: Arc::new(Semaphore::new(max_concurrency)),
+                next_seq: AtomicU64::new(0),
+                pending: AtomicUsize::new(0),
+                idle: Notify::new(),
+            }),
+        }
+    }
+
+    /// The settings a cacher runs with on a repo
+    pub fn cacher_config(&self, repo: &LocalRepository, cacher: &Cacher) -> CacherConfig {
+        self.inner
+            .opts
+            .config
+            .restrict(&repo.cache_config(), cacher.name)
+    }
+
+    /// Queue every enabled cacher for a commit. Must be called from within a tokio runtime.
+    pub fn submit_all(&self, repo: &LocalRepository, commit: &Commit) -> Result<(), OxenError> {
+        for cacher in cachers::all() {
+            if !self.cacher_config(repo, &cacher).enabled {
+                log::debug!("Cacher {} is disabled for {:?}", cacher.name, repo.path);
+                continue;
+            }
+            self.submit(repo, commit, cacher)?;
+        }
+        Ok(())
+    }
+
+    /// Queue a single cacher for a commit, erroring if it is disabled. Must be called
+    /// from within a tokio runtime.
+    pub fn submit(
+        &self,
+        repo: &LocalRepository,
+        commit: &Commit,
+        cacher: Cacher,
+    ) -> Result<(), OxenError> {
+        let config = self.cacher_config(repo, &cacher);
+        if !config.enabled {
+            return Err(OxenError::basic_str(format!(
+                "Cacher {} is disabled",
+                cacher.name
+            )));
+        }
+
+        let record = CacheJobRecord::queued(cacher.name, &commit.id);
+        job::write(repo, &record)?;
+
+        self.inner.pending.fetch_add(1, AtomicOrdering::SeqCst);
+        self.inner.push(QueuedJob {
+            priority: cacher.priority,
+            seq: self.inner.next_seq.fetch_add(1, AtomicOrdering::SeqCst),
+            repo: repo.clone(),
+            commit: commit.clone(),
+            cacher,
+            config,
+            record,
+        });
+        tokio::spawn(self.inner.clone().run_next());
+        Ok(())
+    }
+
+    /// Wait until every submitted job has succeeded or been dead lettered
+    pub async fn wait_idle(&self) {
+        loop {
+            let idle = self.inner.idle.notified();
+            if self.inner.pending.load(AtomicOrdering::SeqCst) == 0 {
+                return;
+            }
+            idle.await;
+        }
+    }
+}
+
+impl SchedulerInner {
+    fn push(&self, job: QueuedJob) {
+        self.queue.lock().unwrap().push(job);
+    }
+
+    // Each queued job spawns one of these, which runs whichever job has the highest
+    // priority once a permit frees up. Retries wait out their backoff without a permit,
+    // so other jobs can run in the meantime.
+    async fn run_next(self: Arc<Self>) {
+        loop {
+            let Ok(permit) = self.permits.clone().acquire_owned().await else {
+                return;
+            };
+            let job = self.queue.lock().unwrap().pop();
+            let Some(job) = job else {
+                return;
+            };
+            let retry = self.run_job(job).await;
+            drop(permit);
+
+            let Some((job, delay)) = retry else {
+                return;
+            };
+            tokio::time::sleep(delay).await;
+            self.push(job);
+        }
+    }
+
+    /// Run one attempt of a job, returning it with its backoff if it should be retried
+    async fn run_job(&self, mut job: QueuedJob) -> Option<(QueuedJob, Duration)> {
+        job.record.attempts += 1;
+        job.record.status = CacheJobStatus::Running;
+        job.record.started_at = Some(OffsetDateTime::now_utc());
+        self.write_record(&job);
+
+        let start = Instant::now();
+        let (repo, commit, config) = (job.repo.clone(), job.commit.clone(), job.config.clone());
+        let run = job.cacher.run;
+        let result = match tokio::task::spawn_blocking(move || run(&repo, &commit, &config)).await {
+            Ok(result) => result,
+            Err(err) => Err(OxenError::basic_str(format!("Cacher panicked: {err}"))),
+        };
+        job.record.duration_ms = Some(start.elapsed().as_millis() as u64);
+
+        match result {
+            Ok(()) => {
+                log::debug!(
+                    "Cacher {} succeeded for commit {}",
+                    job.cacher.name,
+                    job.commit.id
+                );
+                job.record.status = CacheJobStatus::Succeeded;
+                job.record.error = None;
+                self.write_record(&job);
+                self.finish();
+                None
+            }
+            Err(err) if job.record.attempts >= self.opts.max_attempts => {
+                log::error!(
+                    "Cacher {} failed for commit {} after {} attempts: {}",
+                    job.cacher.name,
+                    job.commit.id,
+                    job.record.attempts,
+                    err
+                );
+                job.record.status = CacheJobStatus::Failed;
+                job.record.error = Some(err.to_string());
+                self.write_record(&job);
+                if let Err(err) = job::write_dead_letter(&job.repo, &job.record) {
+                    log::error!("Could not record dead letter: {}", err);
+                }
+                self.finish();
+                None
+            }
+            Err(err) => {
+                let delay = self.opts.retry_delay * 2u32.pow(job.record.attempts - 1);
+                log::warn!(
+                    "Cacher {} failed for commit {}, retrying in {:?}: {}",
+                    job.cacher.name,
+                    job.commit.id,
+                    delay,
+                    err
+                );
+                job.record.status = CacheJobStatus::Queued;
+                job.record.error = Some(err.to_string());
+                self.write_record(&job);
+                Some((job, delay))
+            }
+        }
+    }
+
+    fn write_record(&self, job: &QueuedJob) {
+        if let Err(err) = job::write(&job.repo, &job.record) {
+            log::error!("Could not write cache job {}: {}", job.cacher.name, err);
+        }
+    }
+
+    fn finish(&self) {
+        if self.pending.fetch_sub(1, AtomicOrdering::SeqCst) == 1 {
+            self.idle.notify_waiters();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    use crate::config::{CacheConfig, CacherConfig};
+    use crate::core::cache::cachers::content_validator;
+    use crate::core::cache::job;
+    use crate::core::cache::{CacheJobStatus, CacheScheduler, CacheSchedulerOpts, Cacher};
+    use crate::error::OxenError;
+    use crate::model::{Commit, LocalRepository};
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    static RUN_ORDER: Mutex<Vec<&'static str>> = Mutex::new(vec![]);
+
+    fn always_fails(
+        _repo: &LocalRepository,
+        _commit: &Commit,
+        _config: &CacherConfig,
+    ) -> Result<(), OxenError> {
+        Err(OxenError::basic_str("bad file"))
+    }
+
+    fn low(
+        _repo: &LocalRepository,
+        _commit: &Commit,
+        _config: &CacherConfig,
+    ) -> Result<(), OxenError> {
+        RUN_ORDER.lock().unwrap().push("low");
+        Ok(())
+    }
+
+    fn high(
+        _repo: &LocalRepository,
+        _commit: &Commit,
+        _config: &CacherConfig,
+    ) -> Result<(), OxenError> {
+        RUN_ORDER.lock().unwrap().push("high");
+        Ok(())
+    }
+
+    fn fast_opts(max_concurrency: usize) -> CacheSchedulerOpts {
+        CacheSchedulerOpts {
+            max_concurrency,
+            max_attempts: 3,
+            retry_delay: Duration::from_millis(1),
+            config: CacheConfig::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cache_scheduler_validates_content() -> Result<(), OxenError> {
+        test::run_one_commit_local_repo_test_async(|repo| async move {
+            let commit = repositories::commits::head_commit(&repo)?;
+            let scheduler = CacheScheduler::new(fast_opts(2));
+            scheduler.submit_all(&repo, &commit)?;
+            scheduler.wait_idle().await;
+
+            let record = job::get(&repo, &commit.id, content_validator::NAME)?.unwrap();
+            assert_eq!(record.status, CacheJobStatus::Succeeded);
+            assert_eq!(record.attempts, 1);
+            let is_valid_path = util::fs::commit_content_is_valid_path(&repo, &commit);
+            assert_eq!(util::fs::read_from_path(is_valid_path)?, "true");
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_cache_scheduler_retries_then_dead_letters() -> Result<(), OxenError> {
+        test::run_one_commit_local_repo_test_async(|repo| async move {
+            let commit = repositories::commits::head_commit(&repo)?;
+            let cacher = Cacher {
+                name: "always_fails",
+                priority: 0,
+                run: always_fails,
+            };
+            let scheduler = CacheScheduler::new(fast_opts(1));
+            scheduler.submit(&repo, &commit, cacher)?;
+            scheduler.wait_idle().await;
+
+            let record = job::get(&repo, &commit.id, "always_fails")?.unwrap();
+            assert_eq!(record.status, CacheJobStatus::Failed);
+            assert_eq!(record.attempts, 3);
+            assert_eq!(record.error, Some("bad file".to_string()));
+
+            let dead_letters = job::list_dead_letters(&repo)?;
+            assert_eq!(dead_letters.len(), 1);
+            assert_eq!(dead_letters[0].cacher, "always_fails");
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_cache_scheduler_runs_highest_priority_first() -> Result<(), OxenError> {
+        test::run_one_commit_local_repo_test_async(|repo| async move {
+            let commit = repositories::commits::head_commit(&repo)?;
+            let scheduler = CacheScheduler::new(fast_opts(1));
+            // Both are queued before the single worker slot picks one up
+            scheduler.submit(
+                &repo,
+                &commit,
+                Cacher {
+                    name: "low",
+                    priority: 1,
+                    run: low,
+                },
+            )?;
+            scheduler.submit(
+                &repo,
+                &commit,
+                Cacher {
+                    name: "high",
+                    priority: 10,
+                    run: high,
+                },
+            )?;
+            scheduler.wait_idle().await;
+
+            assert_eq!(*RUN_ORDER.lock().unwrap(), vec!["high", "low"]);
+            let records = job::list(&repo, &commit.id)?;
+            assert_eq!(records.len(), 2);
+            assert!(records
+                .iter()
+                .all(|record| record.status == CacheJobStatus::Succeeded));
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_cache_scheduler_skips_disabled_cachers() -> Result<(), OxenError> {
+        test::run_one_commit_local_repo_test_async(|mut repo| async move {
+            let commit = repositories::commits::head_commit(&repo)?;
+            let mut repo_config = CacheConfig::default();
+            repo_config.cachers.insert(
+                content_validator::NAME.to_string(),
+                CacherConfig {
+                    enabled: false,
+                    max_file_size: None,
+                },
+            );
+            repo.set_cache_config(Some(repo_config));
+
+            let scheduler = CacheScheduler::new(fast_opts(1));
+            scheduler.submit_all(&repo, &commit)?;
+            scheduler.wait_idle().await;
+            assert!(job::list(&repo, &commit.id)?.is_empty());
+            assert!(scheduler
+                .submit(&repo, &commit, content_validator::CACHER)
+                .is_err());
+
+            // The server can lower the repo's threshold, but not re-enable the cacher
+            let mut server_config = CacheConfig::default();
+            server_config.cachers.insert(
+                content_validator::NAME.to_string(),
+                CacherConfig {
+                    enabled: true,
+                    max_file_size: Some(10),
+                },
+            );
+            let config = server_config.restrict(&repo.cache_config(), content_validator::NAME);
+            assert!(!config.enabled);
+            assert_eq!(config.max_file_size, Some(10));
+
+            Ok(())
+        })
+        .await
+    }
+}
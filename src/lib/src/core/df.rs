@@ -3,5 +3,6 @@
 
 pub mod filter;
 pub mod pretty_print;
+pub mod split;
 pub mod sql;
 pub mod tabular;
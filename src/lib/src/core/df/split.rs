@@ -0,0 +1,177 @@
+//! Split a data frame into reproducible, optionally stratified, random parts
+//!
+
+use std::collections::BTreeMap;
+
+use polars::prelude::*;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+use crate::core::df::tabular;
+use crate::error::OxenError;
+use crate::opts::DFSplitOpts;
+
+/// Shuffle the rows of `df` and divide them by the fractions in `opts.splits`, returning
+/// each split's name and rows. With `opts.stratify` every value of that column is divided
+/// separately, so each split keeps the same proportion of each value. Rows keep the order
+/// they had in `df` within a split.
+pub fn split_df(df: &DataFrame, opts: &DFSplitOpts) -> Result<Vec<(String, DataFrame)>, OxenError> {
+    if opts.splits.is_empty() {
+        return Err(OxenError::basic_str("Must specify at least one split"));
+    }
+
+    let mut rng = match opts.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+
+    // Group the row indices by the value of the stratify column, sorted so the seed gives
+    // the same result no matter the order the values appear in
+    let mut groups: BTreeMap<String, Vec<IdxSize>> = BTreeMap::new();
+    match &opts.stratify {
+        Some(column) => {
+            let column = df.column(column).map_err(|_| {
+                OxenError::basic_str(format!("Stratify column {column:?} not found"))
+            })?;
+            for i in 0..df.height() {
+                let value = tabular::any_val_to_json(column.get(i)?).to_string();
+                groups.entry(value).or_default().push(i as IdxSize);
+            }
+        }
+        None => {
+            groups.insert(String::new(), (0..df.height() as IdxSize).collect());
+        }
+    }
+
+    // Spread each group evenly over one ordering of all the rows, then cut that ordering into
+    // the splits. Rounding each group on its own would give uneven strata too many or too few
+    // rows, ie: 25 cats split 80/10/10 rounds to 20/3/2 and the val split ends up too big.
+    let mut ordered: Vec<(f64, usize, IdxSize)> = Vec::with_capacity(df.height());
+    for (group, indices) in groups.values_mut().enumerate() {
+        indices.shuffle(&mut rng);
+        let n = indices.len() as f64;
+        for (rank, index) in indices.iter().enumerate() {
+            ordered.push(((rank as f64 + 0.5) / n, group, *index));
+        }
+    }
+    ordered.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+
+    let mut split_indices: Vec<Vec<IdxSize>> = vec![vec![]; opts.splits.len()];
+    let n = ordered.len() as f64;
+    let mut start = 0;
+    let mut cumulative = 0.0;
+    for (i, split) in opts.splits.iter().enumerate() {
+        cumulative += split.fraction;
+        let end = ((cumulative * n).round() as usize).min(ordered.len());
+        split_indices[i].extend(ordered[start..end].iter().map(|(_, _, index)| *index));
+        start = end;
+    }
+
+    let mut result = vec![];
+    for (split, mut indices) in opts.splits.iter().zip(split_indices) {
+        indices.sort();
+        let idx = IdxCa::new(PlSmallStr::from_str("idx"), &indices);
+        result.push((split.name.clone(), df.take(&idx)?));
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use polars::prelude::*;
+
+    use crate::core::df::split;
+    use crate::error::OxenError;
+    use crate::opts::DFSplitOpts;
+
+    fn count(df: &DataFrame, label: &str) -> Result<usize, OxenError> {
+        Ok(df.column("label")?.str()?.equal(label).sum().unwrap_or(0) as usize)
+    }
+
+    #[test]
+    fn test_split_df_stratified_and_reproducible() -> Result<(), OxenError> {
+        let labels: Vec<&str> = (0..100)
+            .map(|i| if i % 4 == 0 { "cat" } else { "dog" })
+            .collect();
+        let files: Vec<String> = (0..100).map(|i| format!("{i}.jpg")).collect();
+        let df = df!("file" => &files, "label" => &labels)?;
+
+        let opts = DFSplitOpts {
+            splits: DFSplitOpts::parse_splits("train=0.8,val=0.1,test=0.1")?,
+            stratify: Some("label".to_string()),
+            seed: Some(42),
+            output_dir: "splits".into(),
+        };
+        let splits = split::split_df(&df, &opts)?;
+        assert_eq!(splits.len(), 3);
+        assert_eq!(splits[0].0, "train");
+        assert_eq!(splits[0].1.height(), 80);
+        assert_eq!(splits[1].1.height(), 10);
+        assert_eq!(splits[2].1.height(), 10);
+
+        // Each split keeps the 1 in 4 ratio of cats
+        assert_eq!(count(&splits[0].1, "cat")?, 20);
+        let val_cats = count(&splits[1].1, "cat")?;
+        assert!((2..=3).contains(&val_cats));
+        assert_eq!(val_cats + count(&splits[2].1, "cat")?, 5);
+
+        // The same seed gives the same splits
+        let again = split::split_df(&df, &opts)?;
+        assert!(splits[0].1.equals(&again[0].1));
+        assert!(splits[2].1.equals(&again[2].1));
+
+        assert!(DFSplitOpts::parse_splits("train=0.8,test=0.3").is_err());
+        assert!(DFSplitOpts::parse_splits("train").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_df_uneven_strata() -> Result<(), OxenError> {
+        // 25 cats, 10 dogs and 5 birds don't divide evenly into 80/10/10
+        let labels: Vec<&str> = (0..40)
+            .map(|i| match i {
+                0..=24 => "cat",
+                25..=34 => "dog",
+                _ => "bird",
+            })
+            .collect();
+        let files: Vec<String> = (0..40).map(|i| format!("{i}.jpg")).collect();
+        let df = df!("file" => &files, "label" => &labels)?;
+
+        let opts = DFSplitOpts {
+            splits: DFSplitOpts::parse_splits("train=0.8,val=0.1,test=0.1")?,
+            stratify: Some("label".to_string()),
+            seed: Some(7),
+            output_dir: "splits".into(),
+        };
+        let splits = split::split_df(&df, &opts)?;
+        assert_eq!(splits[0].1.height(), 32);
+        assert_eq!(splits[1].1.height(), 4);
+        assert_eq!(splits[2].1.height(), 4);
+
+        // Every row lands in exactly one split
+        let mut all_files: Vec<String> = vec![];
+        for (_, split) in splits.iter() {
+            let files = split.column("file")?.str()?;
+            all_files.extend(files.into_no_null_iter().map(String::from));
+        }
+        all_files.sort();
+        all_files.dedup();
+        assert_eq!(all_files.len(), 40);
+
+        // Each value is off by at most one row from its share of a split
+        for (label, total) in [("cat", 25.0), ("dog", 10.0), ("bird", 5.0)] {
+            for ((_, split), fraction) in splits.iter().zip([0.8, 0.1, 0.1]) {
+                let expected = total * fraction;
+                let actual = count(split, label)? as f64;
+                assert!(
+                    (actual - expected).abs() < 1.0,
+                    "{actual} {label}s, expected about {expected}"
+                );
+            }
+        }
+        assert_eq!(count(&splits[0].1, "cat")?, 20);
+        Ok(())
+    }
+}
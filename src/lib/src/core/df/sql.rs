@@ -1,5 +1,6 @@
 use std::path::{Path, PathBuf};
 
+use crate::constants;
 use crate::core::df::tabular;
 use crate::core::v_latest::workspaces;
 use crate::model::LocalRepository;
@@ -7,6 +8,8 @@ use crate::opts::DFOpts;
 use crate::repositories;
 use crate::{core::db::data_frames::df_db, error::OxenError};
 use polars::frame::DataFrame;
+use polars::prelude::LazyFrame;
+use polars::sql::SQLContext;
 use uuid::Uuid;
 
 pub fn query_df_from_repo(
@@ -47,6 +50,15 @@ pub fn query_df(
     Ok(df)
 }
 
+/// Run a SQL query against a data frame with polars, without indexing it into duckdb.
+/// The data frame is the table `df`, ie `SELECT label, count(*) FROM df GROUP BY label`.
+pub fn query_lazy_df(df: LazyFrame, sql: impl AsRef<str>) -> Result<LazyFrame, OxenError> {
+    let mut ctx = SQLContext::new();
+    ctx.register(constants::TABLE_NAME, df);
+    ctx.execute(sql.as_ref())
+        .map_err(|e| OxenError::basic_str(format!("Could not run sql query: {e}")))
+}
+
 pub fn export_df(
     conn: &duckdb::Connection,
     sql: String,
@@ -1,4 +1,5 @@
 use duckdb::ToSql;
+use polars::io::avro::{AvroReader, AvroWriter};
 use polars::prelude::*;
 use serde_json::json;
 use std::collections::HashSet;
@@ -7,6 +8,7 @@ use std::num::NonZeroUsize;
 
 use crate::constants;
 use crate::constants::EXCLUDE_OXEN_COLS;
+use crate::core::db::data_frames::df_db;
 use crate::core::df::filter::DFLogicalOp;
 use crate::core::df::pretty_print;
 use crate::core::df::sql;
@@ -23,6 +25,7 @@ use crate::util::fs;
 use crate::util::hasher;
 
 use comfy_table::Table;
+use duckdb::arrow::record_batch::RecordBatch;
 use indicatif::ProgressBar;
 use serde_json::Value;
 use std::ffi::OsStr;
@@ -110,6 +113,138 @@ pub fn read_df_parquet(path: impl AsRef<Path>) -> Result<LazyFrame, OxenError> {
     })
 }
 
+/// Read a sheet of an Excel workbook (`.xlsx`, `.xls`, ...), the first sheet if `sheet` is
+/// None. The first row is used as the header, and a column is typed as bool, i64 or f64
+/// when all of its non-empty cells are, otherwise the cells are read as strings.
+pub fn read_df_excel(path: impl AsRef<Path>, sheet: Option<&str>) -> Result<LazyFrame, OxenError> {
+    use calamine::Reader;
+
+    let path = path.as_ref();
+    let error_str = format!("Could not read excel data from path {path:?}");
+    let mut workbook = calamine::open_workbook_auto(path)
+        .map_err(|e| OxenError::basic_str(format!("{error_str}: {e}")))?;
+    let sheet_names = workbook.sheet_names();
+    let sheet = match sheet {
+        Some(sheet) => {
+            if !sheet_names.iter().any(|name| name == sheet) {
+                return Err(OxenError::basic_str(format!(
+                    "{error_str}: sheet {sheet:?} not found, sheets are {sheet_names:?}"
+                )));
+            }
+            sheet.to_string()
+        }
+        None => sheet_names
+            .first()
+            .cloned()
+            .ok_or(OxenError::basic_str(format!("{error_str}: no sheets")))?,
+    };
+    let range = workbook
+        .worksheet_range(&sheet)
+        .map_err(|e| OxenError::basic_str(format!("{error_str}: {e}")))?;
+
+    let mut rows = range.rows();
+    let Some(header) = rows.next() else {
+        return Ok(DataFrame::empty().lazy());
+    };
+    let rows: Vec<&[calamine::Data]> = rows.collect();
+    let mut columns: Vec<Column> = vec![];
+    for (i, name) in header.iter().enumerate() {
+        let name = match name {
+            calamine::Data::Empty => format!("column_{i}"),
+            name => name.to_string(),
+        };
+        let cells: Vec<Option<&calamine::Data>> = rows.iter().map(|row| row.get(i)).collect();
+        columns.push(excel_column(&name, &cells));
+    }
+    let df = DataFrame::new(columns)?;
+    Ok(df.lazy())
+}
+
+/// Cells past the end of a row are None, and are treated like empty cells
+fn excel_column(name: &str, cells: &[Option<&calamine::Data>]) -> Column {
+    use calamine::Data;
+
+    let values = cells
+        .iter()
+        .flatten()
+        .filter(|cell| !matches!(cell, Data::Empty));
+    // A column with no values at all is left as strings
+    let has_values = values.clone().next().is_some();
+    let is_bool = has_values && values.clone().all(|cell| matches!(cell, Data::Bool(_)));
+    // xlsx stores every number as a float, so whole floats count as integers
+    let is_int = has_values
+        && values.clone().all(|cell| match cell {
+            Data::Int(_) => true,
+            Data::Float(f) => f.fract() == 0.0 && f.abs() < i64::MAX as f64,
+            _ => false,
+        });
+    let is_float = has_values
+        && values
+            .clone()
+            .all(|cell| matches!(cell, Data::Int(_) | Data::Float(_)));
+
+    if is_bool {
+        let values: Vec<Option<bool>> = cells
+            .iter()
+            .map(|cell| match cell {
+                Some(Data::Bool(b)) => Some(*b),
+                _ => None,
+            })
+            .collect();
+        Column::new(name.into(), values)
+    } else if is_int {
+        let values: Vec<Option<i64>> = cells
+            .iter()
+            .map(|cell| match cell {
+                Some(Data::Int(i)) => Some(*i),
+                Some(Data::Float(f)) => Some(*f as i64),
+                _ => None,
+            })
+            .collect();
+        Column::new(name.into(), values)
+    } else if is_float {
+        let values: Vec<Option<f64>> = cells
+            .iter()
+            .map(|cell| match cell {
+                Some(Data::Int(i)) => Some(*i as f64),
+                Some(Data::Float(f)) => Some(*f),
+                _ => None,
+            })
+            .collect();
+        Column::new(name.into(), values)
+    } else {
+        let values: Vec<Option<String>> = cells
+            .iter()
+            .map(|cell| match cell {
+                None | Some(Data::Empty) => None,
+                Some(cell) => Some(cell.to_string()),
+            })
+            .collect();
+        Column::new(name.into(), values)
+    }
+}
+
+pub fn read_df_avro(path: impl AsRef<Path>) -> Result<LazyFrame, OxenError> {
+    let path = path.as_ref();
+    let file = File::open(path)?;
+    let df = AvroReader::new(file)
+        .finish()
+        .map_err(|e| OxenError::basic_str(format!("Could not read avro file {path:?}: {e}")))?;
+    Ok(df.lazy())
+}
+
+pub fn read_df_orc(path: impl AsRef<Path>) -> Result<LazyFrame, OxenError> {
+    let path = path.as_ref();
+    let error_str = format!("Could not read orc file {path:?}");
+    let file = File::open(path)?;
+    let reader = orc_rust::ArrowReaderBuilder::try_new(file)
+        .map_err(|e| OxenError::basic_str(format!("{error_str}: {e}")))?
+        .build();
+    let batches = reader.collect::<Result<Vec<RecordBatch>, _>>()?;
+    let df = df_db::record_batches_to_polars_df(batches)?;
+    Ok(df.lazy())
+}
+
 fn read_df_arrow(path: impl AsRef<Path>) -> Result<LazyFrame, OxenError> {
     LazyFrame::scan_ipc(&path, ScanArgsIpc::default())
         .map_err(|_| OxenError::basic_str(format!("{}: {:?}", READ_ERROR, path.as_ref())))
@@ -240,6 +375,21 @@ pub fn add_row(df: LazyFrame, data: String) -> Result<LazyFrame, OxenError> {
     Ok(df)
 }
 
+/// Count the rows whose values in `cols` appear more than once, like [`n_duped_rows`] but
+/// without loading the frame, only the counts of each distinct value are held in memory.
+pub fn lazy_n_duped_rows(df: LazyFrame, cols: &[&str]) -> Result<u64, OxenError> {
+    let group_cols = cols.iter().map(|c| col(*c)).collect::<Vec<Expr>>();
+    let counts = df
+        .group_by(group_cols)
+        .agg([len().alias("n")])
+        .filter(col("n").gt(lit(1)))
+        .select([col("n").sum().cast(polars::prelude::DataType::UInt64)])
+        .with_streaming(true)
+        .collect()?;
+    let n_dupes = counts.column("n")?.u64()?.get(0).unwrap_or(0);
+    Ok(n_dupes)
+}
+
 pub fn n_duped_rows(df: &DataFrame, cols: &[&str]) -> Result<u64, OxenError> {
     let cols = cols
         .iter()
@@ -457,9 +607,29 @@ pub fn transform_lazy(mut df: LazyFrame, opts: DFOpts) -> Result<LazyFrame, Oxen
             df =
                 sql::query_df_from_repo(sql, &repo, &opts.path.clone().unwrap_or_default(), &opts)?
                     .lazy();
+        } else {
+            df = sql::query_lazy_df(df, sql)?;
         }
     }
 
+    if let Some(fraction) = opts.sample {
+        if !(0.0..=1.0).contains(&fraction) {
+            return Err(OxenError::basic_str("Sample must be between 0 and 1"));
+        }
+        log::debug!("transform_lazy sampling {fraction} of df");
+        let frac = Series::new("".into(), &[fraction]);
+        df = df
+            .collect()
+            .map_err(|e| OxenError::basic_str(format!("{e:?}")))?
+            .sample_frac(
+                &frac, false, // without replacement
+                false, // keep the original order
+                opts.seed,
+            )
+            .map_err(|e| OxenError::basic_str(format!("Failed to sample dataframe: {e:?}")))?
+            .lazy();
+    }
+
     if opts.should_randomize {
         log::debug!("transform_lazy randomizing df");
         let full_df = df
@@ -472,7 +642,7 @@ pub fn transform_lazy(mut df: LazyFrame, opts: DFOpts) -> Result<LazyFrame, Oxen
                 &n,    // no specific rows to sample, use n parameter instead
                 false, // without replacement
                 true,  // shuffle
-                None,  // seed
+                opts.seed,
             )
             .map_err(|e| OxenError::basic_str(format!("Failed to randomize dataframe: {e:?}")))?
             .lazy();
@@ -482,8 +652,14 @@ pub fn transform_lazy(mut df: LazyFrame, opts: DFOpts) -> Result<LazyFrame, Oxen
         df = unique_df(df, columns)?;
     }
 
-    if let Some(sort_by) = &opts.sort_by {
-        df = df.sort([sort_by], Default::default());
+    if let Some(sort_by) = opts.sort_by_columns() {
+        if !sort_by.is_empty() {
+            let (columns, descending): (Vec<String>, Vec<bool>) = sort_by.into_iter().unzip();
+            df = df.sort(
+                columns,
+                SortMultipleOptions::default().with_order_descending_multi(descending),
+            );
+        }
     }
 
     if opts.should_reverse {
@@ -691,7 +867,7 @@ pub fn any_val_to_bytes(value: &AnyValue) -> Vec<u8> {
     }
 }
 
-fn any_val_to_json(value: AnyValue) -> Value {
+pub fn any_val_to_json(value: AnyValue) -> Value {
     match value {
         AnyValue::Null => Value::Null,
         AnyValue::Boolean(b) => Value::Bool(b),
@@ -895,15 +1071,25 @@ pub fn df_hash_rows_on_cols(
     hash_fields: &[String],
     out_col_name: &str,
 ) -> Result<DataFrame, OxenError> {
-    let num_rows = df.height() as i64;
-
     log::debug!("df_hash_rows_on_cols df is {:?}", df);
-    log::debug!("df_hash_rows_on_cols hash_fields is {:?}", hash_fields);
-    log::debug!("df_hash_rows_on_cols out_col_name is {:?}", out_col_name);
+    let df = lazy_hash_rows_on_cols(df.lazy(), hash_fields, out_col_name)?.collect()?;
+    log::debug!("Hashed rows: {}", df);
+    Ok(df)
+}
+
+/// Add a column `out_col_name` with the hash of each row's values in `hash_fields`. The rows
+/// are hashed a batch at a time as the frame is collected, so it can be streamed.
+pub fn lazy_hash_rows_on_cols(
+    mut df: LazyFrame,
+    hash_fields: &[String],
+    out_col_name: &str,
+) -> Result<LazyFrame, OxenError> {
+    log::debug!("lazy_hash_rows_on_cols hash_fields is {:?}", hash_fields);
+    log::debug!("lazy_hash_rows_on_cols out_col_name is {:?}", out_col_name);
 
     // Create a vector to store columns to be hashed
     let mut col_names = vec![];
-    let schema = df.schema();
+    let schema = df.collect_schema()?;
     for field in schema.iter_fields() {
         let field_name = field.name().to_string();
         if hash_fields.contains(&field_name) {
@@ -914,50 +1100,38 @@ pub fn df_hash_rows_on_cols(
     // This is to allow asymmetric target hashing for added / removed cols in default behavior
     if col_names.is_empty() {
         let null_string_col = lit(Null {}).alias(out_col_name);
-        return Ok(df.lazy().with_column(null_string_col).collect()?);
-    }
-
-    // Continue as before
-    let df = df
-        .lazy()
-        .select([
-            all(),
-            as_struct(col_names)
-                .apply(
-                    move |s| {
-                        let pb = ProgressBar::new(num_rows as u64);
-                        // downcast to struct
-                        let ca = s.struct_()?;
-                        let s_a = &ca.fields_as_series();
-                        let num_rows = s_a[0].len();
-
-                        let mut hashes = vec![];
-                        for i in 0..num_rows {
-                            // log::debug!("row: {:?}", i);
-                            let mut buffer: Vec<u8> = vec![];
-                            for series in s_a.iter() {
-                                let elem = series.get(i).unwrap();
-                                // log::debug!("\telem: {:?}", elem);
-                                let mut elem_bytes = any_val_to_bytes(&elem);
-                                buffer.append(&mut elem_bytes);
-                            }
-                            pb.inc(1);
-                            let result = hasher::hash_buffer(&buffer);
-                            hashes.push(result);
+        return Ok(df.with_column(null_string_col));
+    }
+
+    let df = df.with_column(
+        as_struct(col_names)
+            .map(
+                move |s| {
+                    // downcast to struct
+                    let ca = s.struct_()?;
+                    let s_a = &ca.fields_as_series();
+                    let num_rows = s_a[0].len();
+
+                    let mut hashes = vec![];
+                    for i in 0..num_rows {
+                        let mut buffer: Vec<u8> = vec![];
+                        for series in s_a.iter() {
+                            let elem = series.get(i).unwrap();
+                            let mut elem_bytes = any_val_to_bytes(&elem);
+                            buffer.append(&mut elem_bytes);
                         }
-                        pb.finish_and_clear();
+                        let result = hasher::hash_buffer(&buffer);
+                        hashes.push(result);
+                    }
 
-                        Ok(Some(Column::Series(
-                            Series::new(PlSmallStr::from_str(""), hashes).into(),
-                        )))
-                    },
-                    GetOutput::from_type(polars::prelude::DataType::String),
-                )
-                .alias(out_col_name),
-        ])
-        .collect()
-        .unwrap();
-    log::debug!("Hashed rows: {}", df);
+                    Ok(Some(Column::Series(
+                        Series::new(PlSmallStr::from_str(""), hashes).into(),
+                    )))
+                },
+                GetOutput::from_type(polars::prelude::DataType::String),
+            )
+            .alias(out_col_name),
+    );
     Ok(df)
 }
 
@@ -1011,11 +1185,65 @@ pub fn read_df_with_extension(
     })?
 }
 
+/// Like [`read_df`] but returns the scan with the transforms in `opts` applied, without
+/// reading the file. Collect it with [`collect_streaming`] to bound memory to the result.
+pub fn read_df_lazy(path: impl AsRef<Path>, opts: &DFOpts) -> Result<LazyFrame, OxenError> {
+    let path = path.as_ref();
+    let Some(extension) = path.extension().and_then(OsStr::to_str) else {
+        return Err(OxenError::basic_str(format!(
+            "Could not load data frame with path: {path:?}"
+        )));
+    };
+    read_df_lazy_with_extension(path, extension, opts)
+}
+
+pub fn read_df_lazy_with_extension(
+    path: impl AsRef<Path>,
+    extension: impl AsRef<str>,
+    opts: &DFOpts,
+) -> Result<LazyFrame, OxenError> {
+    let path = path.as_ref();
+    let extension = extension.as_ref();
+    std::panic::catch_unwind(|| p_read_df_lazy_with_extension(path, extension, opts)).map_err(
+        |e| {
+            log::error!("Error Scanning DataFrame {e:?} - {:?}", path);
+            OxenError::DataFrameError(format!("Error Scanning DataFrame {e:?}").into())
+        },
+    )?
+}
+
+/// Count the rows of a lazy frame without collecting them
+pub fn count_rows_lazy(df: LazyFrame) -> Result<usize, OxenError> {
+    let counts = collect_streaming(df.select([len().alias("n")]))?;
+    let n = counts
+        .column("n")?
+        .cast(&polars::prelude::DataType::UInt64)?
+        .u64()?
+        .get(0)
+        .unwrap_or(0);
+    Ok(n as usize)
+}
+
+/// Collect a lazy frame with the streaming engine, so files larger than memory are
+/// processed in batches and only the result is held in memory
+pub fn collect_streaming(df: LazyFrame) -> Result<DataFrame, OxenError> {
+    Ok(df.with_streaming(true).collect()?)
+}
+
 fn p_read_df_with_extension(
     path: impl AsRef<Path>,
     extension: impl AsRef<str>,
     opts: &DFOpts,
 ) -> Result<DataFrame, OxenError> {
+    let df = p_read_df_lazy_with_extension(path, extension, opts)?;
+    collect_streaming(df)
+}
+
+fn p_read_df_lazy_with_extension(
+    path: impl AsRef<Path>,
+    extension: impl AsRef<str>,
+    opts: &DFOpts,
+) -> Result<LazyFrame, OxenError> {
     let path = path.as_ref();
     let extension = extension.as_ref();
     if !path.exists() {
@@ -1035,6 +1263,9 @@ fn p_read_df_with_extension(
         }
         "tsv" => read_df_csv(path, b'\t', quote_char),
         "parquet" => read_df_parquet(path),
+        "avro" => read_df_avro(path),
+        "orc" => read_df_orc(path),
+        "xlsx" | "xlsm" | "xlsb" | "xls" | "ods" => read_df_excel(path, opts.sheet.as_deref()),
         "arrow" => {
             if opts.sql.is_some() {
                 return Err(OxenError::basic_str(
@@ -1053,10 +1284,9 @@ fn p_read_df_with_extension(
 
     // log::debug!("Read finished");
     if opts.has_transform() {
-        let df = transform_new(df, opts.clone())?;
-        Ok(df.collect()?)
+        transform_new(df, opts.clone())
     } else {
-        Ok(df.collect()?)
+        Ok(df)
     }
 }
 
@@ -1154,6 +1384,10 @@ fn p_scan_df_with_extension(
             "tsv" => scan_df_csv(path, b'\t', quote_char, total_rows),
             "parquet" => scan_df_parquet(path, total_rows),
             "arrow" => scan_df_arrow(path, total_rows),
+            "avro" => read_df_avro(path),
+            "orc" => read_df_orc(path),
+            // Workbooks are zipped or binary, so they can only be read in full
+            "xlsx" | "xlsm" | "xlsb" | "xls" | "ods" => read_df_excel(path, opts.sheet.as_deref()),
             _ => Err(OxenError::basic_str(err)),
         },
         None => Err(OxenError::basic_str(err)),
@@ -1231,10 +1465,10 @@ fn p_get_size_with_extension(
                 let height = reader.finish().unwrap().height();
                 Ok(DataFrameSize { width, height })
             }
-            "json" => {
+            "json" | "avro" | "orc" | "xlsx" | "xlsm" | "xlsb" | "xls" | "ods" => {
                 let df = lazy_df
                     .collect()
-                    .map_err(|_| OxenError::basic_str("Could not collect json df"))?;
+                    .map_err(|_| OxenError::basic_str("Could not collect df"))?;
                 let height = df.height();
                 Ok(DataFrameSize { width, height })
             }
@@ -1310,6 +1544,44 @@ pub fn write_df_arrow<P: AsRef<Path>>(df: &mut DataFrame, output: P) -> Result<(
     Ok(())
 }
 
+pub fn write_df_avro<P: AsRef<Path>>(df: &mut DataFrame, output: P) -> Result<(), OxenError> {
+    let output = output.as_ref();
+    log::debug!("Writing file {:?}", output);
+    let f = std::fs::File::create(output)?;
+    AvroWriter::new(f)
+        .finish(df)
+        .map_err(|e| OxenError::basic_str(format!("{e:?}")))?;
+    Ok(())
+}
+
+/// Polars can't write orc, so the data frame goes through arrow ipc to arrow-rs batches
+pub fn write_df_orc<P: AsRef<Path>>(df: &mut DataFrame, output: P) -> Result<(), OxenError> {
+    let output = output.as_ref();
+    log::debug!("Writing file {:?}", output);
+    // The oldest compat level writes strings as LargeUtf8 instead of views, which orc supports
+    let mut buf = Vec::new();
+    IpcWriter::new(&mut buf)
+        .with_compat_level(CompatLevel::oldest())
+        .finish(df)
+        .map_err(|e| OxenError::basic_str(format!("{e:?}")))?;
+    let reader = arrow::ipc::reader::FileReader::try_new(Cursor::new(buf), None)?;
+    let schema = reader.schema();
+
+    let f = std::fs::File::create(output)?;
+    let mut writer = orc_rust::ArrowWriterBuilder::new(f, schema)
+        .try_build()
+        .map_err(|e| OxenError::basic_str(format!("Could not write orc file {output:?}: {e}")))?;
+    for batch in reader {
+        writer.write(&batch?).map_err(|e| {
+            OxenError::basic_str(format!("Could not write orc file {output:?}: {e}"))
+        })?;
+    }
+    writer
+        .close()
+        .map_err(|e| OxenError::basic_str(format!("Could not write orc file {output:?}: {e}")))?;
+    Ok(())
+}
+
 pub fn write_df(df: &mut DataFrame, path: impl AsRef<Path>) -> Result<(), OxenError> {
     let path = path.as_ref();
     let extension = path.extension().and_then(OsStr::to_str);
@@ -1324,6 +1596,8 @@ pub fn write_df(df: &mut DataFrame, path: impl AsRef<Path>) -> Result<(), OxenEr
             "csv" => write_df_csv(df, path, b','),
             "parquet" => write_df_parquet(df, path),
             "arrow" => write_df_arrow(df, path),
+            "avro" => write_df_avro(df, path),
+            "orc" => write_df_orc(df, path),
             _ => Err(OxenError::basic_str(err)),
         },
         None => Err(OxenError::basic_str(err)),
@@ -1523,6 +1797,7 @@ pub fn show_node(
 #[cfg(test)]
 mod tests {
     use crate::core::df::{filter, tabular};
+    use crate::test;
     use crate::view::JsonDataFrameView;
     use crate::{error::OxenError, opts::DFOpts};
     use polars::prelude::*;
@@ -1734,6 +2009,53 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_read_excel_sheets() -> Result<(), OxenError> {
+        // Defaults to the first sheet
+        let df = tabular::read_df("data/test/excel/labels.xlsx", DFOpts::empty())?;
+        assert_eq!(df.shape(), (3, 4));
+        assert_eq!(df.column("file")?.dtype(), &DataType::String);
+        assert_eq!(df.column("score")?.dtype(), &DataType::Float64);
+        assert_eq!(df.column("reviewed")?.dtype(), &DataType::Boolean);
+        assert_eq!(df.column("label")?.null_count(), 1);
+
+        let mut opts = DFOpts::empty();
+        opts.sheet = Some("test".to_string());
+        let df = tabular::read_df("data/test/excel/labels.xlsx", opts)?;
+        assert_eq!(df.shape(), (2, 3));
+        assert_eq!(df.column("count")?.dtype(), &DataType::Int64);
+
+        let mut opts = DFOpts::empty();
+        opts.sheet = Some("missing".to_string());
+        assert!(tabular::read_df("data/test/excel/labels.xlsx", opts).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_and_read_avro_and_orc() -> Result<(), OxenError> {
+        test::run_empty_dir_test(|dir| {
+            let mut df = df!(
+                "id" => &[1i64, 2, 3],
+                "label" => &["cat", "dog", "cat"],
+                "score" => &[0.5f64, 0.25, 1.0],
+            )?;
+
+            for extension in ["avro", "orc"] {
+                let path = dir.join(format!("labels.{extension}"));
+                tabular::write_df(&mut df, &path)?;
+                let read = tabular::read_df(&path, DFOpts::empty())?;
+                assert_eq!(read, df, "round trip through {extension}");
+
+                let size = tabular::get_size(&path)?;
+                assert_eq!(size.height, 3);
+                assert_eq!(size.width, 3);
+            }
+
+            Ok(())
+        })
+    }
+
     #[test]
     fn test_sniff_empty_rows_carriage_return_csv() -> Result<(), OxenError> {
         let opts = DFOpts::empty();
@@ -1923,4 +2245,23 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_lazy_hash_rows_and_dupes_match_eager() -> Result<(), OxenError> {
+        let df = df!(
+            "file" => &["a.jpg", "b.jpg", "a.jpg", "c.jpg"],
+            "label" => &["cat", "dog", "cat", "cat"],
+        )?;
+        let hash_fields = vec!["file".to_string(), "label".to_string()];
+
+        let eager = tabular::df_hash_rows_on_cols(df.clone(), &hash_fields, "_hash")?;
+        let lazy = tabular::lazy_hash_rows_on_cols(df.clone().lazy(), &hash_fields, "_hash")?;
+        let lazy = tabular::collect_streaming(lazy)?;
+        assert_eq!(eager.column("_hash")?, lazy.column("_hash")?);
+
+        assert_eq!(tabular::n_duped_rows(&eager, &["_hash"])?, 2);
+        assert_eq!(tabular::lazy_n_duped_rows(lazy.lazy(), &["_hash"])?, 2);
+        assert_eq!(tabular::lazy_n_duped_rows(df.lazy(), &["label"])?, 3);
+        Ok(())
+    }
 }
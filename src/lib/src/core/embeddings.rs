@@ -0,0 +1,244 @@
+//! # Embeddings
+//!
+//! Approximate nearest neighbor search over the vectors in an embedding column. The
+//! index clusters the normalized vectors with k-means (IVF-flat) and compares a query
+//! against the vectors of the closest clusters by cosine similarity. Small columns are
+//! searched exhaustively.
+//!
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::OxenError;
+use crate::util;
+
+/// Columns with at most this many vectors are searched exhaustively
+pub const EXACT_SEARCH_MAX_VECTORS: usize = 1024;
+/// Number of clusters compared against the query when none is given
+pub const DEFAULT_NPROBE: usize = 8;
+const KMEANS_ITERATIONS: usize = 10;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EmbeddingIndex {
+    pub dim: usize,
+    /// Row of the data frame each vector came from
+    rows: Vec<usize>,
+    /// Normalized vectors, in the same order as `rows`
+    vectors: Vec<Vec<f32>>,
+    /// Normalized cluster centers, empty when the index is searched exhaustively
+    centroids: Vec<Vec<f32>>,
+    /// Positions in `vectors` assigned to each cluster
+    lists: Vec<Vec<usize>>,
+}
+
+impl EmbeddingIndex {
+    /// Build an index from `(row, vector)` pairs, every vector must have the same length
+    pub fn build(embeddings: Vec<(usize, Vec<f32>)>) -> Result<EmbeddingIndex, OxenError> {
+        let dim = embeddings.first().map(|(_, v)| v.len()).unwrap_or(0);
+        let mut rows = Vec::with_capacity(embeddings.len());
+        let mut vectors = Vec::with_capacity(embeddings.len());
+        for (row, vector) in embeddings {
+            if vector.len() != dim {
+                return Err(OxenError::basic_str(format!(
+                    "Embedding in row {row} has {} dimensions, expected {dim}",
+                    vector.len()
+                )));
+            }
+            // Vectors of all zeros have no direction to compare
+            if let Some(vector) = normalize(vector) {
+                rows.push(row);
+                vectors.push(vector);
+            }
+        }
+
+        let n = vectors.len();
+        if n <= EXACT_SEARCH_MAX_VECTORS {
+            return Ok(EmbeddingIndex {
+                dim,
+                rows,
+                lists: vec![(0..n).collect()],
+                vectors,
+                centroids: vec![],
+            });
+        }
+
+        // Start from evenly spaced vectors so the same column always builds the same index
+        let n_lists = (n as f64).sqrt().round() as usize;
+        let mut centroids: Vec<Vec<f32>> = (0..n_lists)
+            .map(|i| vectors[i * n / n_lists].clone())
+            .collect();
+        let mut assignments = vec![0; n];
+        for _ in 0..KMEANS_ITERATIONS {
+            for (i, vector) in vectors.iter().enumerate() {
+                assignments[i] = closest(&centroids, vector);
+            }
+
+            let mut sums = vec![vec![0.0; dim]; n_lists];
+            for (vector, list) in vectors.iter().zip(assignments.iter()) {
+                for (sum, x) in sums[*list].iter_mut().zip(vector.iter()) {
+                    *sum += x;
+                }
+            }
+            // A cluster that lost all its vectors keeps its previous center
+            for (centroid, sum) in centroids.iter_mut().zip(sums) {
+                if let Some(sum) = normalize(sum) {
+                    *centroid = sum;
+                }
+            }
+        }
+
+        let mut lists = vec![vec![]; n_lists];
+        for (i, vector) in vectors.iter().enumerate() {
+            lists[closest(&centroids, vector)].push(i);
+        }
+
+        Ok(EmbeddingIndex {
+            dim,
+            rows,
+            vectors,
+            centroids,
+            lists,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// The normalized vector stored for a row of the data frame
+    pub fn vector(&self, row: usize) -> Option<&[f32]> {
+        self.rows
+            .iter()
+            .position(|r| *r == row)
+            .map(|i| self.vectors[i].as_slice())
+    }
+
+    /// The `k` rows most similar to `query`, most similar first, comparing against the
+    /// vectors in the `nprobe` closest clusters. `exclude` leaves a row out of the results,
+    /// such as the row the query came from.
+    pub fn search(
+        &self,
+        query: &[f32],
+        k: usize,
+        nprobe: usize,
+        exclude: Option<usize>,
+    ) -> Result<Vec<(usize, f32)>, OxenError> {
+        if query.len() != self.dim {
+            return Err(OxenError::basic_str(format!(
+                "Query has {} dimensions, expected {}",
+                query.len(),
+                self.dim
+            )));
+        }
+        let Some(query) = normalize(query.to_vec()) else {
+            return Err(OxenError::basic_str(
+                "Query embedding must not be all zeros",
+            ));
+        };
+
+        let probed: Vec<usize> = if self.centroids.is_empty() {
+            (0..self.lists.len()).collect()
+        } else {
+            let mut by_similarity: Vec<(usize, f32)> = self
+                .centroids
+                .iter()
+                .enumerate()
+                .map(|(i, centroid)| (i, dot(centroid, &query)))
+                .collect();
+            by_similarity.sort_by(|a, b| b.1.total_cmp(&a.1));
+            by_similarity
+                .into_iter()
+                .take(nprobe.max(1))
+                .map(|(i, _)| i)
+                .collect()
+        };
+
+        let mut results: Vec<(usize, f32)> = probed
+            .iter()
+            .flat_map(|list| self.lists[*list].iter())
+            .filter(|i| exclude != Some(self.rows[**i]))
+            .map(|i| (self.rows[*i], dot(&self.vectors[*i], &query)))
+            .collect();
+        results.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        results.truncate(k);
+        Ok(results)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<EmbeddingIndex, OxenError> {
+        let bytes = std::fs::read(path.as_ref())?;
+        Ok(rmp_serde::from_slice(&bytes)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), OxenError> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            util::fs::create_dir_all(parent)?;
+        }
+        let bytes = rmp_serde::to_vec(self)
+            .map_err(|err| OxenError::basic_str(format!("Could not encode index: {err}")))?;
+        std::fs::write(path, bytes)?;
+        Ok(())
+    }
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b.iter()).map(|(x, y)| x * y).sum()
+}
+
+fn normalize(mut vector: Vec<f32>) -> Option<Vec<f32>> {
+    let norm = dot(&vector, &vector).sqrt();
+    if norm == 0.0 || !norm.is_finite() {
+        return None;
+    }
+    for x in vector.iter_mut() {
+        *x /= norm;
+    }
+    Some(vector)
+}
+
+fn closest(centroids: &[Vec<f32>], vector: &[f32]) -> usize {
+    centroids
+        .iter()
+        .enumerate()
+        .map(|(i, centroid)| (i, dot(centroid, vector)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(i, _)| i)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::embeddings::{EmbeddingIndex, DEFAULT_NPROBE};
+    use crate::error::OxenError;
+
+    #[test]
+    fn test_embedding_index_clustered_search_finds_neighbors() -> Result<(), OxenError> {
+        // Two tight groups of vectors pointing in opposite directions, enough to cluster
+        let embeddings: Vec<(usize, Vec<f32>)> = (0..2000)
+            .map(|i| {
+                let jitter = (i % 100) as f32 / 1000.0;
+                let vector = if i % 2 == 0 {
+                    vec![1.0, jitter, 0.0]
+                } else {
+                    vec![-1.0, 0.0, jitter]
+                };
+                (i, vector)
+            })
+            .collect();
+        let index = EmbeddingIndex::build(embeddings)?;
+        assert_eq!(index.len(), 2000);
+
+        let neighbors = index.search(&[1.0, 0.0, 0.0], 5, DEFAULT_NPROBE, Some(0))?;
+        assert_eq!(neighbors.len(), 5);
+        assert!(neighbors.iter().all(|(row, _)| row % 2 == 0 && *row != 0));
+        assert!(neighbors[0].1 >= neighbors[4].1);
+
+        assert!(index.search(&[1.0, 0.0], 5, DEFAULT_NPROBE, None).is_err());
+        Ok(())
+    }
+}
@@ -7,6 +7,7 @@ pub mod entry_merge_conflict_writer;
 pub mod node_merge_conflict_db_reader;
 pub mod node_merge_conflict_reader;
 pub mod node_merge_conflict_writer;
+pub mod tabular_merge;
 
 use std::path::{Path, PathBuf};
 
@@ -0,0 +1,607 @@
+//! Row level three-way merge for tabular files
+//!
+//! Rows are matched across the LCA, base and merge versions of a file by their key columns.
+//! If no keys are configured for the path, every column is part of the key, so rows can only
+//! be added or removed, never modified. Changes that touch different rows or different cells
+//! of the same row are merged automatically, only cells changed differently on both sides
+//! are recorded as conflicts.
+//!
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use polars::prelude::*;
+use serde_json::Value;
+
+use crate::core::df::tabular;
+use crate::error::OxenError;
+use crate::model::merge_conflict::{RowMergeConflict, RowResolution};
+use crate::model::merkle_tree::node::FileNode;
+use crate::model::LocalRepository;
+use crate::opts::DFOpts;
+
+const KEY_SEPARATOR: &str = "\u{1f}";
+
+pub struct TabularMergeResult {
+    /// The merged data frame, conflicting rows keep the values from the base (ours) side
+    pub df: DataFrame,
+    pub conflicts: Vec<RowMergeConflict>,
+}
+
+/// Three-way merge the versions of a tabular file, using the key columns configured for the path
+pub fn merge_file_nodes(
+    repo: &LocalRepository,
+    path: impl AsRef<Path>,
+    lca: Option<&FileNode>,
+    base: &FileNode,
+    merge: &FileNode,
+) -> Result<TabularMergeResult, OxenError> {
+    let path = path.as_ref();
+    let keys = repo.merge_config().tabular_keys(path).unwrap_or_default();
+    log::debug!("tabular merge {:?} with keys {:?}", path, keys);
+
+    let lca_df = match lca {
+        Some(node) => Some(read_file_node(repo, node)?),
+        None => None,
+    };
+    let base_df = read_file_node(repo, base)?;
+    let merge_df = read_file_node(repo, merge)?;
+
+    merge_dfs(lca_df.as_ref(), &base_df, &merge_df, &keys)
+}
+
+/// Merge the edits in the file at `edited_path`, made starting from the `lca` version, into
+/// the `current` version on the branch. Merged rows are written back to `edited_path`, which
+/// is only left untouched if some rows conflict.
+pub fn merge_edited_file(
+    repo: &LocalRepository,
+    path: impl AsRef<Path>,
+    lca: Option<&FileNode>,
+    current: &FileNode,
+    edited_path: impl AsRef<Path>,
+) -> Result<TabularMergeResult, OxenError> {
+    let path = path.as_ref();
+    let edited_path = edited_path.as_ref();
+    let keys = repo.merge_config().tabular_keys(path).unwrap_or_default();
+
+    let lca_df = match lca {
+        Some(node) => Some(read_file_node(repo, node)?),
+        None => None,
+    };
+    let current_df = read_file_node(repo, current)?;
+    let edited_df = tabular::read_df(edited_path, DFOpts::empty())?;
+
+    let mut result = merge_dfs(lca_df.as_ref(), &current_df, &edited_df, &keys)?;
+    if result.conflicts.is_empty() {
+        tabular::write_df(&mut result.df, edited_path)?;
+    }
+    Ok(result)
+}
+
+/// Three-way merge of data frames, an empty list of keys uses every column as the key
+pub fn merge_dfs(
+    lca: Option<&DataFrame>,
+    base: &DataFrame,
+    merge: &DataFrame,
+    keys: &[String],
+) -> Result<TabularMergeResult, OxenError> {
+    let lca_cols = lca.map(column_names).unwrap_or_default();
+    let base_cols = column_names(base);
+    let merge_cols = column_names(merge);
+
+    let keys: Vec<String> = if keys.is_empty() {
+        // Without keys, rows are identified by their full contents, so the schemas have to line up
+        let base_set: HashSet<&String> = base_cols.iter().collect();
+        let merge_set: HashSet<&String> = merge_cols.iter().collect();
+        let lca_set: HashSet<&String> = lca_cols.iter().collect();
+        if base_set != merge_set || (lca.is_some() && lca_set != base_set) {
+            return Err(OxenError::basic_str(
+                "Cannot merge tabular files with different schemas without key columns",
+            ));
+        }
+        base_cols.clone()
+    } else {
+        keys.to_vec()
+    };
+
+    for key in keys.iter() {
+        let in_lca = lca.is_none() || lca_cols.contains(key);
+        if !in_lca || !base_cols.contains(key) || !merge_cols.contains(key) {
+            return Err(OxenError::basic_str(format!(
+                "Merge key column {key:?} must exist in every version of the file"
+            )));
+        }
+    }
+
+    // Columns removed on the merge side are dropped, columns added on the merge side are appended
+    let mut columns: Vec<String> = base_cols
+        .iter()
+        .filter(|c| !(lca_cols.contains(c) && !merge_cols.contains(c)))
+        .cloned()
+        .collect();
+    for col in merge_cols.iter() {
+        if !base_cols.contains(col) && !lca_cols.contains(col) {
+            columns.push(col.clone());
+        }
+    }
+
+    let lca_rows = match lca {
+        Some(df) => index_rows(df, &keys)?,
+        None => (vec![], HashMap::new()),
+    };
+    let (base_keys, base_rows) = index_rows(base, &keys)?;
+    let (merge_keys, merge_rows) = index_rows(merge, &keys)?;
+
+    // Keep the row order of the base, followed by the rows only found on the merge side
+    let mut ordered_keys = base_keys.clone();
+    for key in merge_keys.iter() {
+        if !base_rows.contains_key(key) {
+            ordered_keys.push(key.clone());
+        }
+    }
+
+    let mut rows: Vec<Vec<AnyValue<'static>>> = vec![];
+    let mut conflicts: Vec<RowMergeConflict> = vec![];
+    for key in ordered_keys.iter() {
+        let l_idx = lca_rows.1.get(key).copied();
+        let b_idx = base_rows.get(key).copied();
+        let m_idx = merge_rows.get(key).copied();
+
+        match (l_idx, b_idx, m_idx) {
+            (_, Some(b), Some(m)) => {
+                let mut row = vec![];
+                let mut conflict_columns: Vec<String> = vec![];
+                for col in columns.iter() {
+                    let l_val = match (lca, l_idx) {
+                        (Some(df), Some(l)) => cell(df, col, l)?,
+                        _ => None,
+                    };
+                    let b_val = cell(base, col, b)?;
+                    let m_val = cell(merge, col, m)?;
+
+                    let (l_repr, b_repr, m_repr) = (repr(&l_val), repr(&b_val), repr(&m_val));
+                    let value = if b_repr == m_repr || m_repr == l_repr {
+                        b_val
+                    } else if b_repr == l_repr {
+                        m_val
+                    } else {
+                        conflict_columns.push(col.clone());
+                        b_val
+                    };
+                    row.push(value.unwrap_or(AnyValue::Null));
+                }
+
+                if !conflict_columns.is_empty() {
+                    conflicts.push(row_conflict(
+                        &keys,
+                        lca.zip(l_idx),
+                        Some((base, b)),
+                        Some((merge, m)),
+                        conflict_columns,
+                    )?);
+                }
+                rows.push(row);
+            }
+            (Some(l), Some(b), None) => {
+                // Removed on the merge side, only safe if we did not modify the row
+                if !row_eq(lca.unwrap(), l, base, b)? {
+                    conflicts.push(row_conflict(
+                        &keys,
+                        lca.zip(l_idx),
+                        Some((base, b)),
+                        None,
+                        vec![],
+                    )?);
+                    rows.push(take_row(base, &columns, b)?);
+                }
+            }
+            (Some(l), None, Some(m)) => {
+                // Removed on the base side, only safe if they did not modify the row
+                if !row_eq(lca.unwrap(), l, merge, m)? {
+                    conflicts.push(row_conflict(
+                        &keys,
+                        lca.zip(l_idx),
+                        None,
+                        Some((merge, m)),
+                        vec![],
+                    )?);
+                }
+            }
+            (None, Some(b), None) => rows.push(take_row(base, &columns, b)?),
+            (None, None, Some(m)) => rows.push(take_row(merge, &columns, m)?),
+            _ => {}
+        }
+    }
+
+    let mut dtypes: Vec<DataType> = vec![];
+    for col in columns.iter() {
+        let dtype = match base.column(col) {
+            Ok(c) => c.dtype().clone(),
+            Err(_) => merge.column(col)?.dtype().clone(),
+        };
+        dtypes.push(dtype);
+    }
+    let df = build_df(&columns, &dtypes, &rows)?;
+
+    log::debug!(
+        "tabular merge produced {} rows with {} conflicts",
+        df.height(),
+        conflicts.len()
+    );
+    Ok(TabularMergeResult { df, conflicts })
+}
+
+/// Replaces the rows of each conflict in the merged data frame with the version that was picked.
+/// Picking ours or theirs only replaces the conflicting cells, keeping the cells that were
+/// merged. Conflicts resolved to a side that deleted the row remove it.
+pub fn resolve_rows(
+    df: &DataFrame,
+    conflicts: &[RowMergeConflict],
+    resolutions: &[RowResolution],
+) -> Result<DataFrame, OxenError> {
+    if conflicts.len() != resolutions.len() {
+        return Err(OxenError::basic_str(format!(
+            "Expected {} row resolutions, got {}",
+            conflicts.len(),
+            resolutions.len()
+        )));
+    }
+
+    // The key of each conflict, the row picked for it, and the columns to take from that row,
+    // all of them if empty
+    let resolved: Vec<(&Value, Option<Value>, &[String])> = conflicts
+        .iter()
+        .zip(resolutions.iter())
+        .map(|(conflict, resolution)| match resolution {
+            RowResolution::Ours => (
+                &conflict.key,
+                conflict.base_row.clone(),
+                conflict.columns.as_slice(),
+            ),
+            RowResolution::Theirs => (
+                &conflict.key,
+                conflict.merge_row.clone(),
+                conflict.columns.as_slice(),
+            ),
+            RowResolution::Edited(row) => (&conflict.key, Some(row.clone()), &[][..]),
+        })
+        .collect();
+
+    let columns = column_names(df);
+    let mut placed = vec![false; resolved.len()];
+    let mut rows: Vec<Vec<AnyValue<'static>>> = vec![];
+    for i in 0..df.height() {
+        let mut found = None;
+        for (j, (key, _, _)) in resolved.iter().enumerate() {
+            if key_matches(df, i, key)? {
+                found = Some(j);
+                break;
+            }
+        }
+
+        match found {
+            Some(j) => {
+                // The resolved row takes the place of the row that is in the merged file
+                if !placed[j] {
+                    placed[j] = true;
+                    let (_, row, resolved_columns) = &resolved[j];
+                    if let Some(row) = row {
+                        if resolved_columns.is_empty() {
+                            rows.push(json_to_row(&columns, row));
+                        } else {
+                            let mut merged = row_to_json(df, i)?;
+                            for col in resolved_columns.iter() {
+                                merged[col] = row.get(col).cloned().unwrap_or(Value::Null);
+                            }
+                            rows.push(json_to_row(&columns, &merged));
+                        }
+                    }
+                }
+            }
+            None => rows.push(take_row(df, &columns, i)?),
+        }
+    }
+
+    // Rows that were deleted on our side are not in the merged file, so append them
+    for (j, (_, row, _)) in resolved.iter().enumerate() {
+        if let (false, Some(row)) = (placed[j], row) {
+            rows.push(json_to_row(&columns, row));
+        }
+    }
+
+    let dtypes: Vec<DataType> = df.get_columns().iter().map(|c| c.dtype().clone()).collect();
+    build_df(&columns, &dtypes, &rows)
+}
+
+pub fn read_file_node(repo: &LocalRepository, node: &FileNode) -> Result<DataFrame, OxenError> {
+    let version_store = repo.version_store()?;
+    let version_path = version_store.get_version_path(&node.hash().to_string())?;
+    tabular::read_df_with_extension(version_path, node.extension(), &DFOpts::empty())
+}
+
+fn column_names(df: &DataFrame) -> Vec<String> {
+    df.get_column_names()
+        .iter()
+        .map(|name| name.to_string())
+        .collect()
+}
+
+/// Returns the keys in row order, and a lookup from key to row index.
+/// Errors if a key is duplicated, since we could not tell which row was changed.
+fn index_rows(
+    df: &DataFrame,
+    keys: &[String],
+) -> Result<(Vec<String>, HashMap<String, usize>), OxenError> {
+    let key_cols = keys
+        .iter()
+        .map(|k| df.column(k))
+        .collect::<Result<Vec<&Column>, PolarsError>>()?;
+
+    let mut ordered = Vec::with_capacity(df.height());
+    let mut lookup = HashMap::with_capacity(df.height());
+    for i in 0..df.height() {
+        let mut parts = Vec::with_capacity(key_cols.len());
+        for col in key_cols.iter() {
+            parts.push(col.get(i)?.to_string());
+        }
+        let key = parts.join(KEY_SEPARATOR);
+        if lookup.insert(key.clone(), i).is_some() {
+            return Err(OxenError::basic_str(format!(
+                "Duplicate merge key {:?} found in row {}",
+                parts, i
+            )));
+        }
+        ordered.push(key);
+    }
+    Ok((ordered, lookup))
+}
+
+fn cell(df: &DataFrame, col: &str, idx: usize) -> Result<Option<AnyValue<'static>>, OxenError> {
+    match df.column(col) {
+        Ok(c) => Ok(Some(c.get(idx)?.into_static())),
+        Err(_) => Ok(None),
+    }
+}
+
+fn repr(value: &Option<AnyValue>) -> Option<String> {
+    value.as_ref().map(|v| v.to_string())
+}
+
+fn take_row(
+    df: &DataFrame,
+    columns: &[String],
+    idx: usize,
+) -> Result<Vec<AnyValue<'static>>, OxenError> {
+    let mut row = Vec::with_capacity(columns.len());
+    for col in columns.iter() {
+        row.push(cell(df, col, idx)?.unwrap_or(AnyValue::Null));
+    }
+    Ok(row)
+}
+
+/// Compares two rows on the columns they share
+fn row_eq(
+    df_1: &DataFrame,
+    idx_1: usize,
+    df_2: &DataFrame,
+    idx_2: usize,
+) -> Result<bool, OxenError> {
+    for col in column_names(df_1) {
+        if let Some(other) = cell(df_2, &col, idx_2)? {
+            if repr(&cell(df_1, &col, idx_1)?) != repr(&Some(other)) {
+                return Ok(false);
+            }
+        }
+    }
+    Ok(true)
+}
+
+fn build_df(
+    columns: &[String],
+    dtypes: &[DataType],
+    rows: &[Vec<AnyValue<'static>>],
+) -> Result<DataFrame, OxenError> {
+    let mut series: Vec<Column> = vec![];
+    for (i, (col, dtype)) in columns.iter().zip(dtypes.iter()).enumerate() {
+        let values: Vec<AnyValue> = rows.iter().map(|row| row[i].clone()).collect();
+        let s =
+            Series::from_any_values_and_dtype(PlSmallStr::from_str(col), &values, dtype, false)?;
+        series.push(Column::from(s));
+    }
+    Ok(DataFrame::new(series)?)
+}
+
+fn key_matches(df: &DataFrame, idx: usize, key: &Value) -> Result<bool, OxenError> {
+    let Some(key) = key.as_object() else {
+        return Ok(false);
+    };
+    for (col, value) in key.iter() {
+        let Ok(column) = df.column(col) else {
+            return Ok(false);
+        };
+        if tabular::any_val_to_json(column.get(idx)?) != *value {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+fn json_to_row(columns: &[String], row: &Value) -> Vec<AnyValue<'static>> {
+    columns
+        .iter()
+        .map(|col| match row.get(col) {
+            None | Some(Value::Null) => AnyValue::Null,
+            Some(Value::Bool(b)) => AnyValue::Boolean(*b),
+            Some(Value::Number(n)) => match n.as_i64() {
+                Some(i) => AnyValue::Int64(i),
+                None => AnyValue::Float64(n.as_f64().unwrap_or_default()),
+            },
+            Some(Value::String(s)) => AnyValue::StringOwned(s.as_str().into()),
+            Some(value) => AnyValue::StringOwned(value.to_string().into()),
+        })
+        .collect()
+}
+
+fn row_to_json(df: &DataFrame, idx: usize) -> Result<Value, OxenError> {
+    let mut map = serde_json::Map::new();
+    for col in df.get_columns() {
+        map.insert(
+            col.name().to_string(),
+            tabular::any_val_to_json(col.get(idx)?),
+        );
+    }
+    Ok(Value::Object(map))
+}
+
+fn row_conflict(
+    keys: &[String],
+    lca: Option<(&DataFrame, usize)>,
+    base: Option<(&DataFrame, usize)>,
+    merge: Option<(&DataFrame, usize)>,
+    columns: Vec<String>,
+) -> Result<RowMergeConflict, OxenError> {
+    // At least one side of the conflict has the row, use it to read the key values
+    let (df, idx) = base.or(merge).or(lca).unwrap();
+    let mut key = serde_json::Map::new();
+    for k in keys.iter() {
+        key.insert(k.clone(), tabular::any_val_to_json(df.column(k)?.get(idx)?));
+    }
+
+    Ok(RowMergeConflict {
+        key: Value::Object(key),
+        lca_row: lca.map(|(df, i)| row_to_json(df, i)).transpose()?,
+        base_row: base.map(|(df, i)| row_to_json(df, i)).transpose()?,
+        merge_row: merge.map(|(df, i)| row_to_json(df, i)).transpose()?,
+        columns,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use polars::prelude::*;
+
+    use crate::core::merge::tabular_merge;
+    use crate::error::OxenError;
+    use crate::model::merge_conflict::RowResolution;
+
+    #[test]
+    fn test_tabular_merge_keyed_cells_auto_merge() -> Result<(), OxenError> {
+        let lca = df!(
+            "id" => &[1, 2, 3],
+            "label" => &["cat", "dog", "fish"],
+            "score" => &[0.1, 0.2, 0.3],
+        )?;
+        // Base changes the label of row 1 and removes row 3
+        let base = df!(
+            "id" => &[1, 2],
+            "label" => &["kitten", "dog"],
+            "score" => &[0.1, 0.2],
+        )?;
+        // Merge changes the score of row 1 and adds row 4
+        let merge = df!(
+            "id" => &[1, 2, 3, 4],
+            "label" => &["cat", "dog", "fish", "bird"],
+            "score" => &[0.9, 0.2, 0.3, 0.4],
+        )?;
+
+        let keys = vec![String::from("id")];
+        let result = tabular_merge::merge_dfs(Some(&lca), &base, &merge, &keys)?;
+
+        assert!(result.conflicts.is_empty());
+        assert_eq!(result.df.height(), 3);
+        let labels: Vec<Option<&str>> = result.df.column("label")?.str()?.into_iter().collect();
+        assert_eq!(labels, vec![Some("kitten"), Some("dog"), Some("bird")]);
+        let scores: Vec<Option<f64>> = result.df.column("score")?.f64()?.into_iter().collect();
+        assert_eq!(scores, vec![Some(0.9), Some(0.2), Some(0.4)]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_tabular_merge_keyed_same_cell_conflicts() -> Result<(), OxenError> {
+        let lca = df!("id" => &[1, 2], "label" => &["cat", "dog"])?;
+        let base = df!("id" => &[1, 2], "label" => &["kitten", "dog"])?;
+        let merge = df!("id" => &[1, 2], "label" => &["tiger", "dog"])?;
+
+        let keys = vec![String::from("id")];
+        let result = tabular_merge::merge_dfs(Some(&lca), &base, &merge, &keys)?;
+
+        assert_eq!(result.conflicts.len(), 1);
+        assert_eq!(result.conflicts[0].key["id"], 1);
+        // Conflicting rows keep our values
+        let labels: Vec<Option<&str>> = result.df.column("label")?.str()?.into_iter().collect();
+        assert_eq!(labels, vec![Some("kitten"), Some("dog")]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_tabular_merge_keyed_conflict_only_reports_conflicting_cells() -> Result<(), OxenError> {
+        let lca = df!("id" => &[1], "label" => &["cat"], "score" => &[0.1], "split" => &["train"])?;
+        // Both sides relabel row 1, base also moves it to test and merge changes its score
+        let base =
+            df!("id" => &[1], "label" => &["kitten"], "score" => &[0.1], "split" => &["test"])?;
+        let merge =
+            df!("id" => &[1], "label" => &["tiger"], "score" => &[0.7], "split" => &["train"])?;
+
+        let keys = vec![String::from("id")];
+        let result = tabular_merge::merge_dfs(Some(&lca), &base, &merge, &keys)?;
+
+        assert_eq!(result.conflicts.len(), 1);
+        assert_eq!(result.conflicts[0].columns, vec![String::from("label")]);
+        // The cells that only changed on one side are merged
+        let scores: Vec<Option<f64>> = result.df.column("score")?.f64()?.into_iter().collect();
+        assert_eq!(scores, vec![Some(0.7)]);
+        let splits: Vec<Option<&str>> = result.df.column("split")?.str()?.into_iter().collect();
+        assert_eq!(splits, vec![Some("test")]);
+
+        // Taking their label keeps the merged score and split
+        let df =
+            tabular_merge::resolve_rows(&result.df, &result.conflicts, &[RowResolution::Theirs])?;
+        let labels: Vec<Option<&str>> = df.column("label")?.str()?.into_iter().collect();
+        assert_eq!(labels, vec![Some("tiger")]);
+        let scores: Vec<Option<f64>> = df.column("score")?.f64()?.into_iter().collect();
+        assert_eq!(scores, vec![Some(0.7)]);
+        let splits: Vec<Option<&str>> = df.column("split")?.str()?.into_iter().collect();
+        assert_eq!(splits, vec![Some("test")]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_tabular_merge_resolve_rows() -> Result<(), OxenError> {
+        let lca = df!("id" => &[1, 2, 3], "label" => &["cat", "dog", "fish"])?;
+        let base = df!("id" => &[1, 2, 3], "label" => &["kitten", "puppy", "fish"])?;
+        let merge = df!("id" => &[1, 2, 3], "label" => &["tiger", "wolf", "shark"])?;
+
+        let keys = vec![String::from("id")];
+        let result = tabular_merge::merge_dfs(Some(&lca), &base, &merge, &keys)?;
+        assert_eq!(result.conflicts.len(), 2);
+
+        let resolutions = vec![
+            RowResolution::Theirs,
+            RowResolution::Edited(serde_json::json!({"id": 2, "label": "hound"})),
+        ];
+        let df = tabular_merge::resolve_rows(&result.df, &result.conflicts, &resolutions)?;
+
+        assert_eq!(df.height(), 3);
+        let labels: Vec<Option<&str>> = df.column("label")?.str()?.into_iter().collect();
+        assert_eq!(labels, vec![Some("tiger"), Some("hound"), Some("shark")]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_tabular_merge_without_keys_unions_added_rows() -> Result<(), OxenError> {
+        let lca = df!("file" => &["a.jpg"], "label" => &["cat"])?;
+        let base = df!("file" => &["a.jpg", "b.jpg"], "label" => &["cat", "dog"])?;
+        let merge = df!("file" => &["a.jpg", "c.jpg"], "label" => &["cat", "fish"])?;
+
+        let result = tabular_merge::merge_dfs(Some(&lca), &base, &merge, &[])?;
+
+        assert!(result.conflicts.is_empty());
+        assert_eq!(result.df.height(), 3);
+
+        Ok(())
+    }
+}
@@ -24,6 +24,21 @@ impl PullProgress {
         }
     }
 
+    /// Write progress as json lines on stdout instead of drawing a progress bar
+    pub fn json(self, json: bool) -> Self {
+        PullProgress {
+            sync_progress: self.sync_progress.json(json),
+        }
+    }
+
+    pub fn is_json(&self) -> bool {
+        self.sync_progress.is_json()
+    }
+
+    pub fn bytes_per_sec(&self) -> u64 {
+        self.sync_progress.bytes_per_sec()
+    }
+
     pub fn set_message(&self, message: impl Into<Cow<'static, str>>) {
         self.sync_progress.set_message(message);
     }
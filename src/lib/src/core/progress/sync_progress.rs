@@ -1,12 +1,17 @@
 use indicatif::{ProgressBar, ProgressStyle};
+use serde::Serialize;
 use std::{
     borrow::Cow,
     sync::{
         atomic::{AtomicU64, Ordering},
-        Arc,
+        Arc, Mutex,
     },
+    time::{Duration, Instant},
 };
 
+/// How often a progress event is written in json mode
+const JSON_EVENT_INTERVAL: Duration = Duration::from_millis(500);
+
 pub enum SyncType {
     Push,
     Pull,
@@ -21,6 +26,21 @@ impl SyncType {
     }
 }
 
+/// One line of machine readable progress, written to stdout when `--json` is set
+#[derive(Serialize, Debug)]
+pub struct SyncProgressEvent {
+    /// "start", "progress" or "done"
+    pub event: &'static str,
+    pub sync_type: String,
+    pub files: u64,
+    pub total_files: Option<u64>,
+    pub bytes: u64,
+    pub total_bytes: Option<u64>,
+    pub bytes_per_sec: u64,
+    pub eta_secs: Option<u64>,
+    pub elapsed_secs: f64,
+}
+
 pub struct SyncProgress {
     sync_type: SyncType,
     byte_counter: Arc<AtomicU64>,
@@ -28,6 +48,9 @@ pub struct SyncProgress {
     progress_bar: ProgressBar,
     total_files: Option<u64>,
     total_bytes: Option<u64>,
+    start: Instant,
+    // Set in json mode, when the last event was written
+    last_event: Option<Mutex<Instant>>,
 }
 
 impl SyncProgress {
@@ -43,6 +66,8 @@ impl SyncProgress {
             progress_bar,
             total_files: None,
             total_bytes: None,
+            start: Instant::now(),
+            last_event: None,
         }
     }
 
@@ -51,7 +76,7 @@ impl SyncProgress {
         progress_bar.set_style(
             ProgressStyle::default_bar()
                 .template(
-                    "{spinner:.green} {msg} [{elapsed_precise}] [{wide_bar}] {bytes}/{total_bytes}",
+                    "{spinner:.green} {msg} [{elapsed_precise}] [{wide_bar}] {bytes}/{total_bytes} {bytes_per_sec} ETA {eta}",
                 )
                 .unwrap()
                 .progress_chars("🌾🐂➖"),
@@ -64,6 +89,74 @@ impl SyncProgress {
             progress_bar,
             total_files: Some(total_files),
             total_bytes: Some(total_bytes),
+            start: Instant::now(),
+            last_event: None,
+        }
+    }
+
+    /// Write progress as json lines on stdout instead of drawing a progress bar
+    pub fn json(mut self, json: bool) -> Self {
+        if json {
+            self.progress_bar.finish_and_clear();
+            self.progress_bar = ProgressBar::hidden();
+            self.last_event = Some(Mutex::new(Instant::now()));
+            self.emit("start");
+        }
+        self
+    }
+
+    pub fn is_json(&self) -> bool {
+        self.last_event.is_some()
+    }
+
+    /// Bytes per second since the transfer started
+    pub fn bytes_per_sec(&self) -> u64 {
+        let elapsed = self.start.elapsed().as_secs_f64();
+        if elapsed <= 0.0 {
+            return 0;
+        }
+        (self.get_num_bytes() as f64 / elapsed) as u64
+    }
+
+    /// Seconds left at the current rate, None until the totals are known
+    pub fn eta_secs(&self) -> Option<u64> {
+        let total_bytes = self.total_bytes?;
+        let rate = self.bytes_per_sec();
+        if rate == 0 {
+            return None;
+        }
+        Some(total_bytes.saturating_sub(self.get_num_bytes()) / rate)
+    }
+
+    pub fn event(&self, event: &'static str) -> SyncProgressEvent {
+        SyncProgressEvent {
+            event,
+            sync_type: self.sync_type.as_str().to_string(),
+            files: self.get_num_files(),
+            total_files: self.total_files,
+            bytes: self.get_num_bytes(),
+            total_bytes: self.total_bytes,
+            bytes_per_sec: self.bytes_per_sec(),
+            eta_secs: self.eta_secs(),
+            elapsed_secs: self.start.elapsed().as_secs_f64(),
+        }
+    }
+
+    fn emit(&self, event: &'static str) {
+        match serde_json::to_string(&self.event(event)) {
+            Ok(line) => println!("{line}"),
+            Err(err) => log::warn!("Could not serialize progress event: {err}"),
+        }
+    }
+
+    fn maybe_emit_progress(&self) {
+        let Some(last_event) = &self.last_event else {
+            return;
+        };
+        let mut last_event = last_event.lock().unwrap();
+        if last_event.elapsed() >= JSON_EVENT_INTERVAL {
+            *last_event = Instant::now();
+            self.emit("progress");
         }
     }
 
@@ -101,14 +194,16 @@ impl SyncProgress {
             }
             _ => {
                 let message = format!(
-                    "🐂 {} ({} files {})",
+                    "🐂 {} ({} files {}, {}/s)",
                     self.sync_type.as_str(),
                     files,
-                    bytesize::ByteSize::b(bytes)
+                    bytesize::ByteSize::b(bytes),
+                    bytesize::ByteSize::b(self.bytes_per_sec())
                 );
                 self.progress_bar.set_message(message);
             }
         };
+        self.maybe_emit_progress();
     }
 
     pub fn add_files(&self, files: u64) {
@@ -131,5 +226,33 @@ impl SyncProgress {
 
     pub fn finish(&self) {
         self.progress_bar.finish_and_clear();
+        if self.is_json() {
+            self.emit("done");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::progress::sync_progress::{SyncProgress, SyncType};
+
+    #[test]
+    fn test_sync_progress_event_reports_totals() {
+        let progress = SyncProgress::new_with_totals(SyncType::Pull, 4, 1000);
+        progress.add_files(2);
+        progress.add_bytes(500);
+
+        let event = progress.event("progress");
+        assert_eq!(event.sync_type, "pull");
+        assert_eq!(event.files, 2);
+        assert_eq!(event.total_files, Some(4));
+        assert_eq!(event.bytes, 500);
+        assert_eq!(event.total_bytes, Some(1000));
+
+        // Without totals there is nothing to estimate against
+        let progress = SyncProgress::new(SyncType::Pull);
+        progress.add_bytes(500);
+        assert_eq!(progress.eta_secs(), None);
+        progress.finish();
     }
 }
@@ -1,4 +1,5 @@
 pub mod ref_manager;
 
+pub use ref_manager::checkpoint_if_open;
 pub use ref_manager::remove_from_cache;
 pub use ref_manager::with_ref_manager;
@@ -1,16 +1,17 @@
 use std::num::NonZeroUsize;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str;
 use std::sync::{Arc, LazyLock};
 
 use lru::LruCache;
 use parking_lot::Mutex;
+use rocksdb::checkpoint::Checkpoint;
 use rocksdb::{IteratorMode, DB};
 
 use crate::constants::{HEAD_FILE, REFS_DIR};
 use crate::core::db;
 use crate::error::OxenError;
-use crate::model::{Branch, Commit, LocalRepository};
+use crate::model::{Branch, Commit, LocalRepository, ReflogEntry};
 use crate::repositories;
 use crate::util;
 
@@ -46,6 +47,16 @@ pub fn remove_from_cache_with_children(
     Ok(())
 }
 
+/// Checkpoint the refs db at `db_dir` into `dst` through the handle this process has open,
+/// if it has one. Returns false if the db isn't open here.
+pub fn checkpoint_if_open(db_dir: &Path, dst: &Path) -> Result<bool, OxenError> {
+    let Some(db) = DB_INSTANCES.lock().peek(db_dir).cloned() else {
+        return Ok(false);
+    };
+    Checkpoint::new(&db)?.create_checkpoint(dst)?;
+    Ok(true)
+}
+
 pub struct RefManager {
     refs_db: Arc<DB>,
     head_file: PathBuf,
@@ -232,7 +243,19 @@ impl RefManager {
 
     pub fn set_head(&self, name: impl AsRef<str>) {
         let name = name.as_ref();
+        let old_head = self.read_head_ref().ok().flatten();
         util::fs::write_to_path(&self.head_file, name).expect("Could not write to head");
+        if old_head.as_deref() != Some(name) {
+            let from = old_head.as_deref().unwrap_or("nothing");
+            let action = format!("checkout: moving from {from} to {name}");
+            let old_id = old_head.and_then(|head| self.resolve_commit_id(&head));
+            self.record_reflog(ReflogEntry::new(
+                "HEAD",
+                old_id,
+                self.resolve_commit_id(name),
+                action,
+            ));
+        }
     }
 
     pub fn create_branch(
@@ -260,7 +283,7 @@ impl RefManager {
         }
     }
 
-    fn is_invalid_branch_name(&self, name: &str) -> bool {
+    pub fn is_invalid_branch_name(&self, name: &str) -> bool {
         // https://git-scm.com/docs/git-check-ref-format
         let invalid_substrings = vec!["..", "~", "^", ":", "?", "[", "*", "\\", " ", "@{"];
         for invalid in invalid_substrings {
@@ -294,7 +317,20 @@ impl RefManager {
         } else {
             let old_id = self.refs_db.get(old_name)?.unwrap();
             self.refs_db.delete(old_name)?;
-            self.refs_db.put(new_name, old_id)?;
+            self.refs_db.put(new_name, &old_id)?;
+            let commit_id = String::from(str::from_utf8(&old_id)?);
+            self.record_reflog(ReflogEntry::new(
+                old_name,
+                Some(commit_id.clone()),
+                None,
+                format!("branch: renamed to {new_name}"),
+            ));
+            self.record_reflog(ReflogEntry::new(
+                new_name,
+                None,
+                Some(commit_id),
+                format!("branch: renamed from {old_name}"),
+            ));
             Ok(())
         }
     }
@@ -305,6 +341,12 @@ impl RefManager {
             return Err(OxenError::basic_str(err));
         };
         self.refs_db.delete(name)?;
+        self.record_reflog(ReflogEntry::new(
+            name,
+            Some(branch.commit_id.clone()),
+            None,
+            "branch: deleted",
+        ));
         Ok(branch)
     }
 
@@ -315,17 +357,112 @@ impl RefManager {
     ) -> Result<(), OxenError> {
         let name = name.as_ref();
         let commit_id = commit_id.as_ref();
+        let old_id = self.get_commit_id_for_branch(name)?;
+        let action = self.branch_update_action(old_id.as_deref(), commit_id);
+        self.update_branch(name, old_id, commit_id, action)
+    }
+
+    /// Move a branch and record why in the reflog, for moves that can't be told apart
+    /// from the commits alone such as a reset
+    pub fn set_branch_commit_id_with_action(
+        &self,
+        name: impl AsRef<str>,
+        commit_id: impl AsRef<str>,
+        action: impl AsRef<str>,
+    ) -> Result<(), OxenError> {
+        let name = name.as_ref();
+        let old_id = self.get_commit_id_for_branch(name)?;
+        self.update_branch(name, old_id, commit_id.as_ref(), action)
+    }
+
+    fn update_branch(
+        &self,
+        name: &str,
+        old_id: Option<String>,
+        commit_id: &str,
+        action: impl AsRef<str>,
+    ) -> Result<(), OxenError> {
         self.refs_db.put(name, commit_id)?;
+        if old_id.as_deref() != Some(commit_id) {
+            self.record_reflog(ReflogEntry::new(
+                name,
+                old_id,
+                Some(commit_id.to_string()),
+                action,
+            ));
+        }
         Ok(())
     }
 
+    /// Describe a branch move from the new commit: a commit or merge on top of the old one,
+    /// otherwise the branch was moved to an existing commit (fast-forward, pull or reset)
+    fn branch_update_action(&self, old_id: Option<&str>, commit_id: &str) -> String {
+        let commit = repositories::commits::get_by_id(&self.repository, commit_id)
+            .ok()
+            .flatten();
+        let summary = |commit: &Commit| {
+            commit
+                .message
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .to_string()
+        };
+        match (old_id, commit) {
+            (None, Some(commit)) if commit.parent_ids.is_empty() => {
+                format!("commit (initial): {}", summary(&commit))
+            }
+            (None, _) => "branch: created".to_string(),
+            (Some(old_id), Some(commit)) if commit.parent_ids.iter().any(|id| id == old_id) => {
+                let kind = if commit.parent_ids.len() > 1 {
+                    "merge"
+                } else {
+                    "commit"
+                };
+                format!("{kind}: {}", summary(&commit))
+            }
+            (Some(old_id), _) => format!("update: moving from {old_id}"),
+        }
+    }
+
+    /// The commit a HEAD value points to, whether it is a branch or a detached commit
+    fn resolve_commit_id(&self, head: &str) -> Option<String> {
+        if let Ok(Some(commit_id)) = self.get_commit_id_for_branch(head) {
+            return Some(commit_id);
+        }
+        match repositories::commits::commit_id_exists(&self.repository, head) {
+            Ok(true) => Some(head.to_string()),
+            _ => None,
+        }
+    }
+
+    /// The reflog is a safety net, failing to write it should not fail the ref update
+    fn record_reflog(&self, entry: ReflogEntry) {
+        if let Err(err) = repositories::reflog::record(&self.repository, &entry) {
+            log::warn!(
+                "could not write reflog entry for {}: {}",
+                entry.ref_name,
+                err
+            );
+        }
+    }
+
     pub fn set_head_commit_id(&self, commit_id: &str) -> Result<(), OxenError> {
         let head_val = self.read_head_ref()?; // could be branch name or commit ID
         if let Some(head_val) = head_val {
             if self.has_branch(&head_val) {
                 self.set_head_branch_commit_id(commit_id)?;
             } else {
-                self.set_head(commit_id);
+                // Detached HEAD moves with the commit rather than a checkout
+                let old_id = self.resolve_commit_id(&head_val);
+                let action = self.branch_update_action(old_id.as_deref(), commit_id);
+                util::fs::write_to_path(&self.head_file, commit_id)?;
+                self.record_reflog(ReflogEntry::new(
+                    "HEAD",
+                    old_id,
+                    Some(commit_id.to_string()),
+                    action,
+                ));
             }
         }
         Ok(())
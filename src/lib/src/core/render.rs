@@ -0,0 +1,224 @@
+//! # Render
+//!
+//! Convert markdown and jupyter notebooks to sanitized HTML, so documentation can be
+//! previewed without the client implementing either format. Any HTML written in the
+//! source is passed through an allowlist sanitizer, scripts and event handlers never
+//! make it into the output.
+//!
+
+use pulldown_cmark::{html, Options, Parser};
+use serde_json::Value;
+
+use crate::error::OxenError;
+
+/// Image outputs of a notebook cell that are inlined as data urls
+const NOTEBOOK_IMAGE_TYPES: [&str; 3] = ["image/png", "image/jpeg", "image/gif"];
+
+pub fn markdown_to_html(markdown: &str) -> String {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+    options.insert(Options::ENABLE_TASKLISTS);
+    options.insert(Options::ENABLE_FOOTNOTES);
+
+    let mut unsafe_html = String::new();
+    html::push_html(&mut unsafe_html, Parser::new_ext(markdown, options));
+    sanitize(&unsafe_html)
+}
+
+/// Render the cells of an `.ipynb` in order, markdown cells as markdown and code cells
+/// as code followed by their saved outputs
+pub fn notebook_to_html(notebook: &str) -> Result<String, OxenError> {
+    let notebook: Value = serde_json::from_str(notebook)?;
+    let Some(cells) = notebook.get("cells").and_then(Value::as_array) else {
+        return Err(OxenError::basic_str("Invalid notebook, it has no cells"));
+    };
+    let language = notebook
+        .pointer("/metadata/kernelspec/language")
+        .or_else(|| notebook.pointer("/metadata/language_info/name"))
+        .and_then(Value::as_str)
+        .unwrap_or("python");
+
+    let mut html = String::from("<div class=\"notebook\">\n");
+    for cell in cells {
+        let source = multiline_text(cell.get("source"));
+        match cell.get("cell_type").and_then(Value::as_str) {
+            Some("markdown") => {
+                html.push_str("<div class=\"cell markdown\">\n");
+                html.push_str(&markdown_to_html(&source));
+                html.push_str("</div>\n");
+            }
+            Some("code") => {
+                html.push_str("<div class=\"cell code\">\n");
+                html.push_str(&format!(
+                    "<pre><code class=\"language-{}\">{}</code></pre>\n",
+                    escape(language),
+                    escape(&source)
+                ));
+                let outputs = cell.get("outputs").and_then(Value::as_array);
+                for output in outputs.into_iter().flatten() {
+                    html.push_str(&output_to_html(output));
+                }
+                html.push_str("</div>\n");
+            }
+            _ => {
+                html.push_str(&format!(
+                    "<div class=\"cell raw\">\n<pre>{}</pre>\n</div>\n",
+                    escape(&source)
+                ));
+            }
+        }
+    }
+    html.push_str("</div>\n");
+    Ok(html)
+}
+
+fn output_to_html(output: &Value) -> String {
+    let body = match output.get("output_type").and_then(Value::as_str) {
+        Some("stream") => format!("<pre>{}</pre>", escape(&multiline_text(output.get("text")))),
+        Some("error") => {
+            let traceback = output
+                .get("traceback")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+                .map(strip_ansi_codes)
+                .collect::<Vec<_>>()
+                .join("\n");
+            format!("<pre class=\"error\">{}</pre>", escape(&traceback))
+        }
+        // execute_result and display_data, richest type first
+        _ => {
+            let Some(data) = output.get("data") else {
+                return String::new();
+            };
+            if let Some((mime_type, image)) = NOTEBOOK_IMAGE_TYPES.iter().find_map(|mime_type| {
+                data.get(*mime_type)
+                    .map(|image| (mime_type, multiline_text(Some(image))))
+            }) {
+                let image: String = image.split_whitespace().collect();
+                if !image
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '/' | '='))
+                {
+                    return String::new();
+                }
+                format!("<img src=\"data:{mime_type};base64,{image}\">")
+            } else if let Some(text_html) = data.get("text/html") {
+                sanitize(&multiline_text(Some(text_html)))
+            } else if let Some(markdown) = data.get("text/markdown") {
+                markdown_to_html(&multiline_text(Some(markdown)))
+            } else {
+                format!(
+                    "<pre>{}</pre>",
+                    escape(&multiline_text(data.get("text/plain")))
+                )
+            }
+        }
+    };
+    format!("<div class=\"output\">\n{body}\n</div>\n")
+}
+
+/// Notebook text is either a string or a list of lines that already end in newlines
+fn multiline_text(value: Option<&Value>) -> String {
+    match value {
+        Some(Value::String(text)) => text.clone(),
+        Some(Value::Array(lines)) => lines.iter().filter_map(Value::as_str).collect(),
+        _ => String::new(),
+    }
+}
+
+/// Tracebacks are colored with terminal escape codes, ie: `\u{1b}[0;31m`
+fn strip_ansi_codes(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            // Skip to the letter that ends the sequence
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            stripped.push(c);
+        }
+    }
+    stripped
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn sanitize(html: &str) -> String {
+    ammonia::Builder::default()
+        .add_tag_attributes("code", &["class"])
+        .add_tag_attributes("input", &["type", "checked", "disabled"])
+        .add_tags(&["input"])
+        .clean(html)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::render;
+    use crate::error::OxenError;
+
+    #[test]
+    fn test_render_markdown_strips_scripts() {
+        let html = render::markdown_to_html(
+            "# Dataset\n\n| label | count |\n|---|---|\n| cat | 2 |\n\n<script>alert(1)</script>\n<a href=\"javascript:alert(1)\" onclick=\"alert(1)\">link</a>\n",
+        );
+        assert!(html.contains("<h1>Dataset</h1>"));
+        assert!(html.contains("<td>cat</td>"));
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("javascript:"));
+        assert!(!html.contains("onclick"));
+    }
+
+    #[test]
+    fn test_render_notebook_cells_and_outputs() -> Result<(), OxenError> {
+        let notebook = r###"{
+            "metadata": {"kernelspec": {"language": "python"}},
+            "cells": [
+                {"cell_type": "markdown", "source": ["## Load\n", "the <b>data</b>"]},
+                {
+                    "cell_type": "code",
+                    "source": "print(1 < 2)",
+                    "outputs": [
+                        {"output_type": "stream", "name": "stdout", "text": ["True\n"]},
+                        {"output_type": "display_data", "data": {
+                            "text/html": "<table><tr><td onmouseover=\"x()\">1</td></tr></table>",
+                            "text/plain": "df"
+                        }},
+                        {"output_type": "error", "traceback": ["\u001b[0;31mValueError\u001b[0m: bad"]}
+                    ]
+                }
+            ]
+        }"###;
+        let html = render::notebook_to_html(notebook)?;
+        assert!(html.contains("<h2>Load</h2>"));
+        assert!(html.contains("<b>data</b>"));
+        assert!(html.contains("<code class=\"language-python\">print(1 &lt; 2)</code>"));
+        assert!(html.contains("<pre>True\n</pre>"));
+        assert!(html.contains("<td>1</td>"));
+        assert!(html.contains("ValueError: bad"));
+        assert!(!html.contains("onmouseover"));
+
+        assert!(render::notebook_to_html("{}").is_err());
+        Ok(())
+    }
+}
@@ -1,5 +1,6 @@
 pub mod staged_db_manager;
 
+pub use staged_db_manager::checkpoint_if_open;
 pub use staged_db_manager::remove_from_cache;
 pub use staged_db_manager::remove_from_cache_with_children;
 pub use staged_db_manager::with_staged_db_manager;
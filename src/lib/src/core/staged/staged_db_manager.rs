@@ -11,6 +11,7 @@ use indicatif::ProgressBar;
 use lru::LruCache;
 use parking_lot::Mutex;
 use rmp_serde::Serializer;
+use rocksdb::checkpoint::Checkpoint;
 use rocksdb::{IteratorMode, DB};
 use serde::Serialize;
 
@@ -56,6 +57,17 @@ pub fn remove_from_cache_with_children(
     Ok(())
 }
 
+/// Checkpoint the staged db at `db_dir` into `dst` through the handle this process has
+/// open, if it has one. Returns false if the db isn't open here.
+pub fn checkpoint_if_open(db_dir: &Path, dst: &Path) -> Result<bool, OxenError> {
+    let Some(db_lock) = DB_INSTANCES.read().peek(db_dir).cloned() else {
+        return Ok(false);
+    };
+    let db = db_lock.read();
+    Checkpoint::new(&*db)?.create_checkpoint(dst)?;
+    Ok(true)
+}
+
 #[derive(Clone)]
 pub struct StagedDBManager {
     staged_db: Arc<RwLock<DB>>,
@@ -17,6 +17,7 @@ pub mod metadata;
 pub mod model;
 pub mod pull;
 pub mod push;
+pub mod push_state;
 pub mod resource;
 pub mod restore;
 pub mod revisions;
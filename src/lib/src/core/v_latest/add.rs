@@ -49,6 +49,7 @@ pub struct FileStatus {
     pub mtime: FileTime,
     pub previous_metadata: Option<GenericMetadata>,
     pub previous_file_node: Option<FileNode>,
+    pub mode: u32,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -135,6 +136,9 @@ pub async fn add<T: AsRef<Path>>(
 
     let _stats = add_files(repo, &repo_path, &expanded_paths, staged_db, &version_store).await?;
 
+    // Check everything staged so far, paths added earlier can collide with these
+    repositories::case_conflicts::check_staged(repo)?;
+
     Ok(())
 }
 
@@ -482,7 +486,7 @@ pub async fn process_add_dir(
                                             &path.file_name().unwrap_or_default().to_string_lossy();
                                         let file_status =
                                             core::v_latest::add::determine_file_status(
-                                                &dir_node, file_name, &path,
+                                                &repo, &dir_node, file_name, &path,
                                             )?;
 
                                         match process_add_file(
@@ -651,7 +655,7 @@ async fn add_file_inner(
     }
 
     let file_name = path.file_name().unwrap_or_default().to_string_lossy();
-    let file_status = determine_file_status(&maybe_dir_node, &file_name, path)?;
+    let file_status = determine_file_status(repo, &maybe_dir_node, &file_name, path)?;
     version_store
         .store_version_from_path(&file_status.hash.to_string(), path)
         .await?;
@@ -674,6 +678,7 @@ async fn add_file_inner(
 }
 
 pub fn determine_file_status(
+    repo: &LocalRepository,
     maybe_dir_node: &Option<MerkleTreeNode>,
     file_name: impl AsRef<str>,  // Name of the file in the repository
     data_path: impl AsRef<Path>, // Path to the data file (maybe in the version store)
@@ -688,6 +693,7 @@ pub fn determine_file_status(
     );
     let maybe_file_node = get_file_node(maybe_dir_node, file_path)?;
     let mut previous_oxen_metadata: Option<GenericMetadata> = None;
+    let mode = util::fs::file_mode(&util::fs::metadata(data_path)?);
     // This is ugly - but makes sure we don't have to rehash the file if it hasn't changed
     let (status, hash, num_bytes, mtime) = if let Some(file_node) = &maybe_file_node {
         log::debug!(
@@ -700,9 +706,9 @@ pub fn determine_file_status(
         let metadata = util::fs::metadata(data_path)?;
         let mtime = FileTime::from_last_modification_time(&metadata);
         previous_oxen_metadata = file_node.metadata();
-        if util::fs::is_modified_from_node(data_path, file_node)? {
+        if util::fs::is_modified_from_node(repo, data_path, file_node)? {
             log::debug!("has_different_modification_time true {}", file_node);
-            let hash = util::hasher::get_hash_given_metadata(data_path, &metadata)?;
+            let hash = repo.hash_algorithm().hash_file_contents(data_path)?;
             if file_node.hash().to_u128() != hash {
                 log::debug!(
                     "has_different_modification_time hash is different true {}",
@@ -715,6 +721,14 @@ pub fn determine_file_status(
                     num_bytes,
                     mtime,
                 )
+            } else if util::fs::is_mode_modified_from_node(&metadata, file_node) {
+                // Only the executable bit changed
+                (
+                    StagedEntryStatus::Modified,
+                    MerkleHash::new(hash),
+                    file_node.num_bytes(),
+                    mtime,
+                )
             } else {
                 (
                     StagedEntryStatus::Unmodified,
@@ -734,7 +748,7 @@ pub fn determine_file_status(
     } else {
         let metadata = util::fs::metadata(data_path)?;
         let mtime = FileTime::from_last_modification_time(&metadata);
-        let hash = util::hasher::get_hash_given_metadata(data_path, &metadata)?;
+        let hash = repo.hash_algorithm().hash_file_contents(data_path)?;
         (
             StagedEntryStatus::Added,
             MerkleHash::new(hash),
@@ -751,6 +765,7 @@ pub fn determine_file_status(
         mtime,
         previous_metadata: previous_oxen_metadata,
         previous_file_node: maybe_file_node,
+        mode,
     })
 }
 
@@ -834,6 +849,10 @@ pub fn process_add_file(
     } else {
         (hash, None, hash)
     };
+    let combined_hash = MerkleHash::new(util::hasher::get_combined_hash_with_mode(
+        combined_hash.to_u128(),
+        file_status.mode,
+    ));
     let file_node = FileNode::new(
         repo,
         FileNodeOpts {
@@ -848,6 +867,7 @@ pub fn process_add_file(
             metadata,
             mime_type: mime_type.clone(),
             extension: file_extension.to_string(),
+            mode: file_status.mode,
         },
     )?;
 
@@ -933,6 +953,10 @@ pub fn process_add_file_with_staged_db_manager(
     } else {
         (hash, None, hash)
     };
+    let combined_hash = MerkleHash::new(util::hasher::get_combined_hash_with_mode(
+        combined_hash.to_u128(),
+        file_status.mode,
+    ));
     let file_node = FileNode::new(
         repo,
         FileNodeOpts {
@@ -947,6 +971,7 @@ pub fn process_add_file_with_staged_db_manager(
             metadata,
             mime_type: mime_type.clone(),
             extension: file_extension.to_string(),
+            mode: file_status.mode,
         },
     )?;
 
@@ -988,7 +1013,7 @@ pub fn get_status_and_add_file(
     let file_name = dst_path.file_name().unwrap().to_string_lossy();
     let maybe_dir_node = None;
     let file_status =
-        core::v_latest::add::determine_file_status(&maybe_dir_node, &file_name, data_path)?;
+        core::v_latest::add::determine_file_status(repo, &maybe_dir_node, &file_name, data_path)?;
     let status = file_status.status.clone();
     // Don't have to add the file to the staged db if it hasn't changed
     if status == StagedEntryStatus::Unmodified {
@@ -1097,6 +1122,10 @@ pub fn generate_file_node(
     } else {
         (hash, None, hash)
     };
+    let combined_hash = MerkleHash::new(util::hasher::get_combined_hash_with_mode(
+        combined_hash.to_u128(),
+        file_status.mode,
+    ));
     let file_node = FileNode::new(
         repo,
         FileNodeOpts {
@@ -1111,6 +1140,7 @@ pub fn generate_file_node(
             metadata,
             mime_type: mime_type.clone(),
             extension: file_extension.to_string(),
+            mode: file_status.mode,
         },
     )?;
     Ok(Some(file_node))
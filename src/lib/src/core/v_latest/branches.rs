@@ -1,5 +1,6 @@
 use indicatif::{ProgressBar, ProgressStyle};
 
+use crate::config::CaseConflictMode;
 use crate::core::v_latest::fetch;
 use crate::core::v_latest::index::restore::{self, FileToRestore};
 use crate::core::v_latest::index::CommitMerkleTree;
@@ -64,9 +65,11 @@ impl CheckoutProgressBar {
 
 // files_to_restore: files present in the target tree but not the from tree
 // cannot_overwrite_entries: files that would be restored, but are modified from the from_tree, and thus would erase work if overwritten
+// dirs_to_create: empty dirs in the target tree missing from the working repo, no restored file will create them
 struct CheckoutResult {
     pub files_to_restore: Vec<FileToRestore>,
     pub cannot_overwrite_entries: Vec<PathBuf>,
+    pub dirs_to_create: Vec<PathBuf>,
 }
 
 impl CheckoutResult {
@@ -74,8 +77,21 @@ impl CheckoutResult {
         CheckoutResult {
             files_to_restore: vec![],
             cannot_overwrite_entries: vec![],
+            dirs_to_create: vec![],
         }
     }
+
+    fn create_empty_dirs(&self, repo: &LocalRepository) -> Result<(), OxenError> {
+        // Remote mode repos only have the files that were explicitly restored
+        if repo.is_remote_mode() {
+            return Ok(());
+        }
+        for dir_path in &self.dirs_to_create {
+            log::debug!("Creating empty dir: {:?}", dir_path);
+            util::fs::create_dir_all(repo.path.join(dir_path))?;
+        }
+        Ok(())
+    }
 }
 
 // seen_files: HashMap of MerkleHashes and PathBufs, removing the need to check files against the target tree in r_remove_if_not_in_target
@@ -199,6 +215,7 @@ pub async fn checkout_subtrees(
             log::error!("Cannot get subtree for commit: {}", to_commit);
             continue;
         };
+        check_case_conflicts(repo, &target_root, subtree_path)?;
 
         // Load in the target tree, collecting every dir and vnode hash for comparison with the from tree
         let mut shared_hashes = HashSet::new();
@@ -254,6 +271,7 @@ pub async fn checkout_subtrees(
         } else {
             log::debug!("head commit missing, no cleanup");
         }
+        results.create_empty_dirs(repo)?;
 
         if repo.is_remote_mode() {
             for file_to_restore in results.files_to_restore {
@@ -308,6 +326,23 @@ pub async fn checkout_commit(
     Ok(())
 }
 
+// Paths that only differ by case overwrite each other when checked out on a
+// case-insensitive file system, so only check there
+fn check_case_conflicts(
+    repo: &LocalRepository,
+    target_node: &MerkleTreeNode,
+    base_path: &Path,
+) -> Result<(), OxenError> {
+    if repo.is_remote_mode()
+        || repo.case_conflict_mode() == CaseConflictMode::Allow
+        || !util::fs::is_case_insensitive(&repo.path)
+    {
+        return Ok(());
+    }
+    let conflicts = repositories::case_conflicts::find_in_tree(target_node, base_path)?;
+    repositories::case_conflicts::check(repo, &conflicts)
+}
+
 // Notes for future optimizations:
 // If a dir or a vnode is shared between the trees, then all files under it will also be shared exactly
 // However, shared file nodes may not always fall under the same dirs and vnodes between the trees
@@ -328,6 +363,7 @@ pub async fn set_working_repo_to_commit(
             "Cannot get root node for target commit",
         ));
     };
+    check_case_conflicts(repo, &target_tree, Path::new(""))?;
 
     // If the from tree exists, load in the nodes not found in the target tree
     // Also collects a 'PartialNode' of every file node unique to the from tree
@@ -382,6 +418,7 @@ pub async fn set_working_repo_to_commit(
         log::debug!("Cleanup_removed_files");
         cleanup_removed_files(repo, &from_tree.unwrap(), &mut progress, &mut hashes).await?;
     }
+    results.create_empty_dirs(repo)?;
 
     for file_to_restore in results.files_to_restore {
         restore::restore_file(
@@ -471,7 +508,7 @@ fn r_remove_if_not_in_target(
                 // Before staging for removal, verify the path exists, doesn't refer to a different file in the target tree, and isn't modified
 
                 if full_path.exists() && !hashes.seen_paths.contains(&file_path) {
-                    if util::fs::is_modified_from_node(&full_path, file_node)? {
+                    if util::fs::is_modified_from_node(repo, &full_path, file_node)? {
                         cannot_overwrite_entries.push(file_path.clone());
                     } else {
                         // If in remote mode, save file to version store before removing
@@ -522,9 +559,9 @@ fn r_remove_if_not_in_target(
                 children.len()
             );
 
-            // Remove directory if it's empty
+            // Remove directory if it's empty, unless the target tree has it as well
             let full_dir_path = repo.path.join(&dir_path);
-            if full_dir_path.exists() {
+            if full_dir_path.exists() && !hashes.seen_paths.contains(&dir_path) {
                 paths_to_remove.push(full_dir_path.clone());
             }
         }
@@ -594,6 +631,8 @@ fn r_restore_missing_or_modified_files(
                     file_node.last_modified_nanoseconds(),
                 );
                 if last_modified == Some(target_last_modified) {
+                    // The contents match, the executable bit may not
+                    util::fs::set_file_mode(&full_path, file_node.mode())?;
                     return Ok(());
                 }
 
@@ -616,11 +655,12 @@ fn r_restore_missing_or_modified_files(
                 }
 
                 // Otherwise, check hashes
-                let working_hash = Some(util::hasher::get_hash_given_metadata(&full_path, &meta)?);
+                let working_hash = Some(repo.hash_algorithm().hash_file_contents(&full_path)?);
                 //log::debug!("Working hash: {:?}", working_hash);
                 let target_hash = target_node.hash.to_u128();
                 //log::debug!("Target hash: {:?}", MerkleHash::new(target_hash));
                 if working_hash == Some(target_hash) {
+                    util::fs::set_file_mode(&full_path, file_node.mode())?;
                     return Ok(());
                 }
 
@@ -645,11 +685,18 @@ fn r_restore_missing_or_modified_files(
         }
         EMerkleTreeNode::Directory(dir_node) => {
             let dir_path = path.join(dir_node.name());
+            hashes.seen_paths.insert(dir_path.clone());
             // Early exit if the directory is the same in the from and target trees
             if hashes.common_nodes.contains(&target_node.hash) {
                 return Ok(());
             };
 
+            // Empty dirs are committed as dir nodes without children
+            if dir_node.num_entries() == 0 && !repo.path.join(&dir_path).exists() {
+                results.dirs_to_create.push(dir_path.clone());
+                progress.increment_restored();
+            }
+
             let children = {
                 // Get vnodes for the from dir node
                 let dir_vnodes = &target_node.children;
@@ -10,7 +10,7 @@ use crate::error::OxenError;
 use crate::model::merkle_tree::node::commit_node::CommitNodeOpts;
 use crate::model::merkle_tree::node::{CommitNode, EMerkleTreeNode};
 use crate::model::{Commit, LocalRepository, MerkleHash, User};
-use crate::opts::PaginateOpts;
+use crate::opts::{CommitOpts, PaginateOpts};
 use crate::view::{PaginatedCommits, StatusMessage};
 use crate::{repositories, util};
 
@@ -24,6 +24,22 @@ pub fn commit(repo: &LocalRepository, message: impl AsRef<str>) -> Result<Commit
     repositories::commits::commit_writer::commit(repo, message)
 }
 
+pub fn commit_with_opts(
+    repo: &LocalRepository,
+    message: impl AsRef<str>,
+    opts: &CommitOpts,
+) -> Result<Commit, OxenError> {
+    repositories::commits::commit_writer::commit_with_opts(repo, message, opts)
+}
+
+pub fn squash(
+    repo: &LocalRepository,
+    n: usize,
+    message: Option<&str>,
+) -> Result<Commit, OxenError> {
+    repositories::commits::commit_writer::squash(repo, n, message)
+}
+
 pub fn commit_with_user(
     repo: &LocalRepository,
     message: impl AsRef<str>,
@@ -205,6 +221,8 @@ pub fn create_empty_commit(
             author: new_commit.author.clone(),
             message: new_commit.message.clone(),
             timestamp,
+            metadata: new_commit.metadata.clone(),
+            co_authors: new_commit.co_authors.clone(),
         },
     )?;
 
@@ -408,6 +426,41 @@ fn list_recursive_with_depth(
     Ok(())
 }
 
+/// Get the commits at most `history_depth` parents away from the revision, newest first
+pub fn list_from_with_history_depth(
+    repo: &LocalRepository,
+    revision: impl AsRef<str>,
+    history_depth: usize,
+) -> Result<Vec<Commit>, OxenError> {
+    let revision = revision.as_ref();
+    let depths = list_from_with_depth(repo, revision)?;
+    let commits = list_from(repo, revision)?
+        .into_iter()
+        .filter(|commit| {
+            depths
+                .get(commit)
+                .is_some_and(|depth| *depth < history_depth)
+        })
+        .collect();
+    Ok(commits)
+}
+
+/// Find the commits reachable from a branch whose parents are not in the repository,
+/// which is where the history of a shallow clone stops
+pub fn list_with_missing_parents(repo: &LocalRepository) -> Result<HashSet<String>, OxenError> {
+    let mut results = HashSet::new();
+    for commit in list_all(repo)? {
+        for parent_id in &commit.parent_ids {
+            let parent_id = MerkleHash::from_str(parent_id)?;
+            if get_by_hash(repo, &parent_id)?.is_none() {
+                results.insert(commit.id.clone());
+                break;
+            }
+        }
+    }
+    Ok(results)
+}
+
 /// List the history between two commits
 pub fn list_between(
     repo: &LocalRepository,
@@ -502,6 +555,59 @@ fn list_by_path_recursive_impl(
     Ok(())
 }
 
+pub fn list_by_path_follow(
+    repo: &LocalRepository,
+    revision: &str,
+    path: &Path,
+) -> Result<Vec<(Commit, PathBuf)>, OxenError> {
+    let commits = list_from(repo, revision)?;
+    let Some(head_commit) = commits.first() else {
+        return Ok(vec![]);
+    };
+    if repositories::tree::get_file_by_path(repo, head_commit, path)?.is_none() {
+        return Err(OxenError::basic_str(format!(
+            "Can only follow a file, {path:?} is not a file in {revision}"
+        )));
+    }
+
+    let mut path = path.to_path_buf();
+    let mut results = vec![];
+    for commit in commits {
+        let Some(file_node) = repositories::tree::get_file_by_path(repo, &commit, &path)? else {
+            continue;
+        };
+        let parent = match commit.parent_ids.first() {
+            Some(parent_id) => repositories::commits::get_by_id(repo, parent_id)?,
+            None => None,
+        };
+        let parent_node = match &parent {
+            Some(parent) => repositories::tree::get_file_by_path(repo, parent, &path)?,
+            None => None,
+        };
+
+        match parent_node {
+            Some(parent_node) if parent_node.hash() == file_node.hash() => continue,
+            Some(_) => results.push((commit, path.clone())),
+            None => {
+                // The file was either added or moved here, keep following it if it was moved
+                let old_path = match parent {
+                    Some(_) => repositories::diffs::renames::commit_renames(repo, &commit)?
+                        .into_iter()
+                        .find(|(_, new_path)| *new_path == path)
+                        .map(|(old_path, _)| old_path),
+                    None => None,
+                };
+                results.push((commit, path.clone()));
+                match old_path {
+                    Some(old_path) => path = old_path,
+                    None => break,
+                }
+            }
+        }
+    }
+    Ok(results)
+}
+
 /// Get paginated list of commits by path (directory or file)
 pub fn list_by_path_from_paginated(
     repo: &LocalRepository,
@@ -7,8 +7,8 @@ use crate::model::metadata::generic_metadata::GenericMetadata;
 use crate::model::metadata::metadata_tabular::MetadataTabularImpl;
 use crate::model::{Commit, DataFrameSize, LocalRepository, Schema, Workspace};
 use crate::opts::DFOpts;
-use crate::{repositories, util};
-use polars::prelude::IntoLazy as _;
+use crate::repositories;
+use polars::prelude::{IntoLazy as _, LazyFrame};
 
 use std::path::Path;
 
@@ -50,18 +50,31 @@ pub fn get_slice(
     if let Ok(response) = handle_sql_result {
         return Ok(response);
     }
-    // Read the data frame from the version path
-    let version_path = util::fs::version_path_from_hash(repo, file_node.hash().to_string());
-    let df = tabular::read_df_with_extension(version_path, file_node.extension(), opts)?;
-    log::debug!("get_slice df {:?}", df.height());
+    // Scan the data frame from the version path, the filters, sorts and column projection are
+    // applied lazily so only the requested page is held in memory
+    let version_store = repo.version_store()?;
+    let version_path = version_store.get_version_path(&file_node.hash().to_string())?;
+    let read_opts = DFOpts {
+        delimiter: opts.delimiter.clone(),
+        quote_char: opts.quote_char.clone(),
+        sheet: opts.sheet.clone(),
+        ..DFOpts::empty()
+    };
+    let mut df =
+        tabular::read_df_lazy_with_extension(version_path, file_node.extension(), &read_opts)?;
+    validate_columns(&mut df, opts)?;
+    let df = tabular::transform_lazy(df, opts.clone())?;
 
     // Check what the view height is
     let view_height = if opts.has_filter_transform() {
-        df.height()
+        tabular::count_rows_lazy(df.clone())?
     } else {
         data_frame_size.height
     };
 
+    let df = tabular::collect_streaming(tabular::transform_slice_lazy(df, opts.clone())?)?;
+    log::debug!("get_slice df {:?}", df.height());
+
     // Update the schema metadata from the source schema
     let mut slice_schema = Schema::from_polars(&df.schema());
     slice_schema.update_metadata_from_schema(&source_schema);
@@ -86,6 +99,50 @@ pub fn get_slice(
     })
 }
 
+/// Check the columns to select and sort by exist, so a typo is reported as a missing column
+/// rather than an error from deep within the query
+fn validate_columns(df: &mut LazyFrame, opts: &DFOpts) -> Result<(), OxenError> {
+    // A sql query can create new columns to select and sort by
+    if opts.sql.is_some() {
+        return Ok(());
+    }
+
+    let schema = df.collect_schema()?;
+    let columns = opts.columns_names().unwrap_or_default();
+    let sort_columns = opts
+        .sort_by_columns()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(name, _)| name);
+    for name in columns.into_iter().chain(sort_columns) {
+        if !name.is_empty() && !schema.contains(&name) {
+            return Err(OxenError::column_name_not_found(&name));
+        }
+    }
+    Ok(())
+}
+
+/// Run a SQL query with polars against the data frame at `path` as of `commit`, reading it
+/// straight from the version store so any revision can be queried without indexing it.
+pub fn query_sql(
+    repo: &LocalRepository,
+    commit: &Commit,
+    path: impl AsRef<Path>,
+    sql: impl AsRef<str>,
+) -> Result<polars::frame::DataFrame, OxenError> {
+    let path = path.as_ref();
+    let file_node = repositories::tree::get_file_by_path(repo, commit, path)?
+        .ok_or(OxenError::path_does_not_exist(path))?;
+
+    let version_store = repo.version_store()?;
+    let version_path = version_store.get_version_path(&file_node.hash().to_string())?;
+    let df =
+        tabular::read_df_with_extension(version_path, file_node.extension(), &DFOpts::empty())?;
+    let df = sql::query_lazy_df(df.lazy(), sql)?.collect()?;
+    log::debug!("query_sql {:?} got df {:?}", path, df.shape());
+    Ok(df)
+}
+
 fn handle_sql_querying(
     repo: &LocalRepository,
     commit: &Commit,
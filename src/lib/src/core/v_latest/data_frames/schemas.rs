@@ -330,6 +330,7 @@ pub fn add_schema_metadata(
     let oxen_metadata_hash = util::hasher::get_metadata_hash(oxen_metadata)?;
     let combined_hash =
         util::hasher::get_combined_hash(Some(oxen_metadata_hash), file_node.hash().to_u128())?;
+    let combined_hash = util::hasher::get_combined_hash_with_mode(combined_hash, file_node.mode());
 
     let mut file_node = staged_entry.node.file()?;
 
@@ -453,6 +454,7 @@ pub fn add_column_metadata(
     let oxen_metadata_hash = util::hasher::get_metadata_hash(oxen_metadata)?;
     let combined_hash =
         util::hasher::get_combined_hash(Some(oxen_metadata_hash), file_node.hash().to_u128())?;
+    let combined_hash = util::hasher::get_combined_hash_with_mode(combined_hash, file_node.mode());
 
     let mut file_node = staged_entry.node.file()?;
 
@@ -1,7 +1,9 @@
+use crate::core::v_latest::index::CommitMerkleTree;
 use crate::error::OxenError;
 use crate::model::diff::diff_entries_counts::DiffEntriesCounts;
 use crate::model::diff::diff_entry_status::DiffEntryStatus;
 use crate::model::diff::diff_file_node::DiffFileNode;
+use crate::model::diff::dir_changes::{DirChanges, DirChangesSummary};
 use crate::model::diff::generic_diff_summary::GenericDiffSummary;
 use crate::model::diff::AddRemoveModifyCounts;
 use crate::model::merkle_tree::node::{DirNodeWithPath, FileNode, FileNodeWithDir};
@@ -10,7 +12,7 @@ use crate::opts::DFOpts;
 use crate::repositories;
 use crate::util;
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
@@ -355,6 +357,95 @@ pub fn list_changed_dirs(
     Ok(changed_dirs)
 }
 
+/// Count the files added, removed and modified under `path` per directory. Only the head
+/// tree is loaded in full, the base tree is loaded without the dirs and vnodes it shares
+/// with the head, so files in unchanged subtrees are never compared.
+pub fn dir_changes(
+    repo: &LocalRepository,
+    base_commit: &Commit,
+    head_commit: &Commit,
+    path: impl AsRef<Path>,
+) -> Result<DirChangesSummary, OxenError> {
+    let path = path.as_ref();
+    let mut head_hashes = HashSet::new();
+    let Some(head_tree) =
+        CommitMerkleTree::root_with_children_and_hashes(repo, head_commit, &mut head_hashes)?
+    else {
+        return Err(OxenError::basic_str(format!(
+            "Failed to get head tree for commit: {}",
+            head_commit
+        )));
+    };
+    let mut shared_hashes = HashSet::new();
+    let mut partial_nodes = HashMap::new();
+    let Some(base_tree) = CommitMerkleTree::root_with_unique_children(
+        repo,
+        base_commit,
+        &mut head_hashes,
+        &mut shared_hashes,
+        &mut partial_nodes,
+    )?
+    else {
+        return Err(OxenError::basic_str(format!(
+            "Failed to get base tree for commit: {}",
+            base_commit
+        )));
+    };
+
+    let starting_path = PathBuf::from("");
+    let base_entries =
+        repositories::tree::unique_dir_entries(&starting_path, &base_tree, &shared_hashes)?;
+    let head_entries =
+        repositories::tree::unique_dir_entries(&starting_path, &head_tree, &shared_hashes)?;
+
+    let mut dirs: HashMap<PathBuf, DirChanges> = HashMap::new();
+    for (file_path, head) in &head_entries {
+        if !file_path.starts_with(path) {
+            continue;
+        }
+        match base_entries.get(file_path) {
+            Some(base) if base.hash() == head.hash() => {}
+            Some(base) => {
+                let changes = parent_dir_changes(&mut dirs, file_path);
+                changes.file_counts.modified += 1;
+                changes.byte_delta += head.num_bytes() as i64 - base.num_bytes() as i64;
+            }
+            None => {
+                let changes = parent_dir_changes(&mut dirs, file_path);
+                changes.file_counts.added += 1;
+                changes.byte_delta += head.num_bytes() as i64;
+            }
+        }
+    }
+    for (file_path, base) in &base_entries {
+        if !file_path.starts_with(path) || head_entries.contains_key(file_path) {
+            continue;
+        }
+        let changes = parent_dir_changes(&mut dirs, file_path);
+        changes.file_counts.removed += 1;
+        changes.byte_delta -= base.num_bytes() as i64;
+    }
+
+    let mut dirs: Vec<DirChanges> = dirs.into_values().collect();
+    dirs.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(DirChangesSummary {
+        base_commit_id: base_commit.id.clone(),
+        head_commit_id: head_commit.id.clone(),
+        dirs,
+    })
+}
+
+fn parent_dir_changes<'a>(
+    dirs: &'a mut HashMap<PathBuf, DirChanges>,
+    file_path: &Path,
+) -> &'a mut DirChanges {
+    let parent = file_path.parent().unwrap_or(Path::new("")).to_path_buf();
+    dirs.entry(parent.clone()).or_insert_with(|| DirChanges {
+        path: parent,
+        ..Default::default()
+    })
+}
+
 pub fn get_dir_diff_entry_with_summary(
     repo: &LocalRepository,
     dir: PathBuf,
@@ -656,8 +747,10 @@ fn collect_modified_entries(
             //     head_entry.file_node,
             //     base_entry.file_node
             // );
-            // HEAD entry has a different hash than BASE entry
-            if head_entry.file_node.hash() != base_entry.file_node.hash() {
+            // HEAD entry has a different hash or mode than BASE entry
+            if head_entry.file_node.hash() != base_entry.file_node.hash()
+                || head_entry.file_node.mode() != base_entry.file_node.mode()
+            {
                 diff_entries.push(DiffFileNode {
                     path: base_path.join(base_entry.dir.join(base_entry.file_node.name())),
                     base_entry: Some(base_entry.file_node.to_owned()),
@@ -686,7 +779,9 @@ fn subset_dir_diffs_to_direct_children(
 
         let status = DiffEntryStatus::from_str(&entry.status)?;
         let relevant_entry = match status {
-            DiffEntryStatus::Added | DiffEntryStatus::Modified => entry.head_entry.as_ref(),
+            DiffEntryStatus::Added | DiffEntryStatus::Modified | DiffEntryStatus::Renamed => {
+                entry.head_entry.as_ref()
+            }
             DiffEntryStatus::Removed => entry.base_entry.as_ref(),
         };
 
@@ -717,7 +812,9 @@ fn subset_file_diffs_to_direct_children(
 
     for entry in entries {
         let relevant_entry = match entry.status {
-            DiffEntryStatus::Added | DiffEntryStatus::Modified => entry.head_entry.as_ref(),
+            DiffEntryStatus::Added | DiffEntryStatus::Modified | DiffEntryStatus::Renamed => {
+                entry.head_entry.as_ref()
+            }
             DiffEntryStatus::Removed => entry.base_entry.as_ref(),
         };
 
@@ -108,6 +108,7 @@ async fn r_download_entries(
             &entries,
             local_repo_path,
             pull_progress,
+            None,
         )
         .await?;
     }
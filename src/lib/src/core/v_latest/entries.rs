@@ -6,7 +6,8 @@ use crate::model::merkle_tree::node::{DirNode, EMerkleTreeNode, FileNode, Merkle
 use crate::model::metadata::generic_metadata::GenericMetadata;
 use crate::model::metadata::MetadataDir;
 use crate::model::{
-    Commit, CommitEntry, EntryDataType, LocalRepository, MerkleHash, MetadataEntry, ParsedResource,
+    Commit, CommitEntry, EntryDataType, LocalRepository, MerkleHash, MetadataEntry, MetadataFilter,
+    ParsedResource,
 };
 use crate::opts::PaginateOpts;
 use crate::repositories;
@@ -39,10 +40,13 @@ pub fn get_file(
     Ok(file_node)
 }
 
+/// List a page of the entries directly in a dir. With a filter only the entries whose
+/// node metadata matches it are listed and counted.
 pub fn list_directory(
     repo: &LocalRepository,
     directory: impl AsRef<Path>,
     parsed_resource: &ParsedResource,
+    filter: Option<&MetadataFilter>,
     paginate_opts: &PaginateOpts,
 ) -> Result<PaginatedDirEntries, OxenError> {
     let directory = directory.as_ref();
@@ -90,11 +94,39 @@ pub fn list_directory(
         ))
     });
     log::debug!("list_directory dir_entry {:?}", dir_entry);
-    let entries: Vec<MetadataEntry> =
-        dir_entries(repo, &dir, directory, parsed_resource, &mut found_commits)?;
+
+    // Sort and page through the bare nodes so that only the entries on the requested page
+    // are turned into metadata entries, which have to look up their last commit
+    let mut children = sorted_dir_children(&dir);
+    if let Some(filter) = filter {
+        children.retain(|child| filter.matches(child));
+    }
+    let num_entries = dir_node.num_entries() as usize;
+    let total_entries = if filter.is_some() {
+        children.len()
+    } else if num_entries == children.len() {
+        num_entries
+    } else {
+        // Dirs written before child counts were tracked
+        log::debug!(
+            "list_directory {:?} num_entries {} does not match {} children",
+            directory,
+            num_entries,
+            children.len()
+        );
+        children.len()
+    };
+    let (children, pagination) =
+        util::paginate_with_total(children, page, page_size, total_entries);
+    let entries: Vec<MetadataEntry> = children
+        .into_iter()
+        .map(|child| child_to_metadata_entry(repo, child, parsed_resource, &mut found_commits))
+        .collect::<Result<Vec<_>, OxenError>>()?
+        .into_iter()
+        .flatten()
+        .collect();
     log::debug!("list_directory got {} entries", entries.len());
 
-    let (entries, pagination) = util::paginate(entries, page, page_size);
     let metadata: Option<MetadataDir> = Some(MetadataDir::new(dir_node.data_types()));
 
     let entries: Vec<EMetadataEntry> = if parsed_resource.workspace.is_some() {
@@ -203,6 +235,52 @@ pub fn dir_entries(
     Ok(entries)
 }
 
+/// The files and dirs directly in a dir loaded with its children, read across its vnodes and
+/// sorted the same way as `dir_entries`, dirs first and then by name
+fn sorted_dir_children(dir: &MerkleTreeNode) -> Vec<&MerkleTreeNode> {
+    let mut children: Vec<&MerkleTreeNode> = dir
+        .children
+        .iter()
+        .flat_map(|vnode| vnode.children.iter())
+        .filter(|child| match &child.node {
+            EMerkleTreeNode::Directory(dir_node) => !dir_node.name().is_empty(),
+            EMerkleTreeNode::File(_) => true,
+            _ => false,
+        })
+        .collect();
+    children.sort_by(|a, b| {
+        b.is_dir()
+            .cmp(&a.is_dir())
+            .then_with(|| child_name(a).cmp(child_name(b)))
+    });
+    children
+}
+
+fn child_name(node: &MerkleTreeNode) -> &str {
+    match &node.node {
+        EMerkleTreeNode::Directory(dir_node) => dir_node.name(),
+        EMerkleTreeNode::File(file_node) => file_node.name(),
+        _ => "",
+    }
+}
+
+fn child_to_metadata_entry(
+    repo: &LocalRepository,
+    child: &MerkleTreeNode,
+    parsed_resource: &ParsedResource,
+    found_commits: &mut HashMap<MerkleHash, Commit>,
+) -> Result<Option<MetadataEntry>, OxenError> {
+    match &child.node {
+        EMerkleTreeNode::Directory(_) => {
+            dir_node_to_metadata_entry(repo, child, parsed_resource, found_commits, true)
+        }
+        EMerkleTreeNode::File(file_node) => {
+            file_node_to_metadata_entry(repo, file_node, parsed_resource, found_commits)
+        }
+        _ => Ok(None),
+    }
+}
+
 fn dir_node_to_metadata_entry(
     repo: &LocalRepository,
     node: &MerkleTreeNode,
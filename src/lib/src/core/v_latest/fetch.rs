@@ -3,6 +3,7 @@ use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::sync::Arc;
 
+use crate::config::AuthConfig;
 use crate::constants::{AVG_CHUNK_SIZE, OXEN_HIDDEN_DIR};
 use crate::core;
 use crate::core::refs::with_ref_manager;
@@ -14,6 +15,8 @@ use crate::model::{Branch, Commit, CommitEntry};
 use crate::model::{LocalRepository, MerkleHash, RemoteBranch, RemoteRepository};
 use crate::repositories;
 use crate::util::concurrency;
+use crate::util::signing;
+use crate::view::RefSignature;
 use crate::{api, util};
 
 use crate::core::progress::pull_progress::PullProgress;
@@ -30,23 +33,30 @@ pub async fn fetch_remote_branch(
     let start = std::time::Instant::now();
 
     // Keep track of how many bytes we have downloaded
-    let pull_progress = Arc::new(PullProgress::new());
+    let pull_progress = Arc::new(PullProgress::new().json(fetch_opts.json_progress));
     pull_progress.set_message(format!("Fetching remote branch {}", fetch_opts.branch));
 
     // Find the head commit on the remote branch
-    let Some(remote_branch) =
-        api::client::branches::get_by_name(remote_repo, &fetch_opts.branch).await?
+    let Some((remote_branch, signature)) =
+        api::client::branches::get_signed_by_name(remote_repo, &fetch_opts.branch).await?
     else {
         return Err(OxenError::remote_branch_not_found(&fetch_opts.branch));
     };
 
+    if fetch_opts.unshallow && repo.has_shallow_history()? {
+        unshallow(repo, remote_repo, &pull_progress).await?;
+    }
+
     // We may not have a head commit if the repo is empty (initial clone)
     if let Some(head_commit) = repositories::commits::head_commit_maybe(repo)? {
         log::debug!("Head commit: {}", head_commit);
         log::debug!("Remote branch commit: {}", remote_branch.commit_id);
         // If the head commit is the same as the remote branch commit, we are up to date
         if head_commit.id == remote_branch.commit_id {
-            println!("Repository is up to date.");
+            if !fetch_opts.json_progress {
+                println!("Repository is up to date.");
+            }
+            verify_branch_signature(repo, remote_repo, &remote_branch, &signature)?;
             with_ref_manager(repo, |manager| {
                 manager.set_branch_commit_id(&remote_branch.name, &remote_branch.commit_id)
             })?;
@@ -69,7 +79,8 @@ pub async fn fetch_remote_branch(
             "Fetching all commits from remote branch {}",
             remote_branch.commit_id
         );
-        if fetch_opts.all {
+        // A shallow clone only downloads the trees of the last commits, even with all
+        if fetch_opts.all && fetch_opts.history_depth.is_none() {
             fetch_full_tree_and_hashes(repo, remote_repo, &remote_branch, &pull_progress).await?;
         } else {
             sync_tree_from_commit(
@@ -83,6 +94,14 @@ pub async fn fetch_remote_branch(
         }
     }
 
+    // Record where the fetched history stops, or clear it once the missing history arrived
+    if fetch_opts.history_depth.is_some() || repo.has_shallow_history()? {
+        repositories::commits::update_shallow_boundary(repo)?;
+    }
+
+    // Check the tree we synced is the one the server signed before moving any refs
+    verify_branch_signature(repo, remote_repo, &remote_branch, &signature)?;
+
     // Early exit for remote repo
     if repo.is_remote_mode() {
         // Write the new branch commit id to the local repo
@@ -125,11 +144,18 @@ pub async fn fetch_remote_branch(
     );
     let missing_entries: Vec<Entry> = missing_entries.into_iter().collect();
     pull_progress.finish();
-    let pull_progress = Arc::new(PullProgress::new_with_totals(
-        missing_entries.len() as u64,
-        total_bytes,
-    ));
-    pull_entries_to_versions_dir(remote_repo, &missing_entries, &repo.path, &pull_progress).await?;
+    let pull_progress = Arc::new(
+        PullProgress::new_with_totals(missing_entries.len() as u64, total_bytes)
+            .json(fetch_opts.json_progress),
+    );
+    pull_entries_to_versions_dir(
+        remote_repo,
+        &missing_entries,
+        &repo.path,
+        &pull_progress,
+        download_concurrency(repo, fetch_opts),
+    )
+    .await?;
 
     // If we fetched the data, we're no longer shallow
     repo.write_is_shallow(false)?;
@@ -147,16 +173,68 @@ pub async fn fetch_remote_branch(
     pull_progress.finish();
     let duration = std::time::Duration::from_millis(start.elapsed().as_millis() as u64);
 
-    println!(
-        "🐂 oxen downloaded {} ({} files) in {}",
-        bytesize::ByteSize::b(pull_progress.get_num_bytes()),
-        pull_progress.get_num_files(),
-        humantime::format_duration(duration)
-    );
+    if !pull_progress.is_json() {
+        println!(
+            "🐂 oxen downloaded {} ({} files) in {} ({}/s)",
+            bytesize::ByteSize::b(pull_progress.get_num_bytes()),
+            pull_progress.get_num_files(),
+            humantime::format_duration(duration),
+            bytesize::ByteSize::b(pull_progress.bytes_per_sec())
+        );
+    }
 
     Ok(remote_branch)
 }
 
+/// If a signing key is pinned for the remote's host, check the server signed this branch
+/// head and that the tree we have for it has the signed root hash
+fn verify_branch_signature(
+    repo: &LocalRepository,
+    remote_repo: &RemoteRepository,
+    branch: &Branch,
+    signature: &Option<RefSignature>,
+) -> Result<(), OxenError> {
+    let (_scheme, host) = api::client::get_scheme_and_host_from_url(remote_repo.url())?;
+    let Some(public_key) = AuthConfig::get()
+        .ok()
+        .and_then(|config| config.signing_key_for_host(&host))
+    else {
+        return Ok(());
+    };
+
+    let Some(signature) = signature else {
+        return Err(OxenError::basic_str(format!(
+            "{host} did not sign branch {}, but a signing key is pinned for it",
+            branch.name
+        )));
+    };
+    let commit = repositories::commits::get_by_id(repo, &branch.commit_id)?
+        .ok_or(OxenError::commit_id_does_not_exist(&branch.commit_id))?;
+    let root_hash = repositories::tree::get_root_dir_hash(repo, &commit)?.ok_or(
+        OxenError::basic_str(format!("Tree for commit {} not found", commit.id)),
+    )?;
+    if root_hash.to_string() != signature.root_hash {
+        return Err(OxenError::basic_str(format!(
+            "Tree for branch {} from {host} has root {root_hash}, but the server signed {}",
+            branch.name, signature.root_hash
+        )));
+    }
+
+    let message = signing::ref_message(
+        &remote_repo.namespace,
+        &remote_repo.name,
+        &branch.name,
+        &branch.commit_id,
+        &signature.root_hash,
+    );
+    signing::verify(public_key, message, &signature.signature).map_err(|err| {
+        OxenError::basic_str(format!(
+            "Could not verify branch {} from {host}: {err}",
+            branch.name
+        ))
+    })
+}
+
 async fn sync_from_head(
     repo: &LocalRepository,
     remote_repo: &RemoteRepository,
@@ -221,12 +299,45 @@ async fn sync_tree_from_commit(
     pull_progress.set_message(format!("Downloading commits from {}", commit_id.as_ref()));
     api::client::tree::download_trees_from(repo, remote_repo, &commit_id.as_ref(), fetch_opts)
         .await?;
-    api::client::commits::download_dir_hashes_from_commit(
-        remote_repo,
-        commit_id.as_ref(),
-        &repo_hidden_dir,
-    )
-    .await?;
+    if let Some(history_depth) = fetch_opts.history_depth {
+        api::client::commits::download_dir_hashes_from_commit_with_history_depth(
+            remote_repo,
+            commit_id.as_ref(),
+            history_depth,
+            &repo_hidden_dir,
+        )
+        .await?;
+    } else {
+        api::client::commits::download_dir_hashes_from_commit(
+            remote_repo,
+            commit_id.as_ref(),
+            &repo_hidden_dir,
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Download the history that a shallow clone left out, from each commit on the shallow
+/// boundary back to the first commit, and clear the boundary
+pub async fn unshallow(
+    repo: &LocalRepository,
+    remote_repo: &RemoteRepository,
+    pull_progress: &Arc<PullProgress>,
+) -> Result<(), OxenError> {
+    let boundary = repo.shallow_boundary()?;
+    log::debug!("unshallow from {} boundary commits", boundary.len());
+    for commit_id in &boundary {
+        sync_tree_from_commit(
+            repo,
+            remote_repo,
+            commit_id,
+            &FetchOpts::new(),
+            pull_progress,
+        )
+        .await?;
+    }
+    repositories::commits::update_shallow_boundary(repo)?;
     Ok(())
 }
 
@@ -321,6 +432,56 @@ fn collect_missing_entries(
     Ok(missing_entries)
 }
 
+/// Download the trees and version files of subtrees of a commit, such as the ones a sparse
+/// checkout adds to the paths that were checked out before
+pub async fn fetch_subtrees(
+    repo: &LocalRepository,
+    remote_repo: &RemoteRepository,
+    commit: &Commit,
+    subtree_paths: &[PathBuf],
+    depth: Option<i32>,
+) -> Result<(), OxenError> {
+    let fetch_opts = FetchOpts {
+        subtree_paths: Some(subtree_paths.to_vec()),
+        depth,
+        ..FetchOpts::new()
+    };
+    api::client::tree::download_trees_from(repo, remote_repo, &commit.id, &fetch_opts).await?;
+
+    let mut missing_entries: HashSet<Entry> = HashSet::new();
+    let mut total_bytes = 0;
+    for subtree_path in subtree_paths {
+        let Some(tree) =
+            repositories::tree::get_subtree(repo, commit, subtree_path, depth.unwrap_or(-1))?
+        else {
+            log::warn!("get_subtree returned None for path: {:?}", subtree_path);
+            continue;
+        };
+        collect_missing_entries_for_subtree(
+            &tree,
+            subtree_path,
+            &mut missing_entries,
+            &mut total_bytes,
+        )?;
+    }
+
+    let missing_entries: Vec<Entry> = missing_entries.into_iter().collect();
+    let pull_progress = Arc::new(PullProgress::new_with_totals(
+        missing_entries.len() as u64,
+        total_bytes,
+    ));
+    pull_entries_to_versions_dir(
+        remote_repo,
+        &missing_entries,
+        &repo.path,
+        &pull_progress,
+        download_concurrency(repo, &fetch_opts),
+    )
+    .await?;
+    pull_progress.finish();
+    Ok(())
+}
+
 fn collect_missing_entries_for_subtree(
     tree: &MerkleTreeNode,
     subtree_path: &PathBuf,
@@ -532,8 +693,14 @@ async fn r_download_entries(
             }
         }
 
-        pull_entries_to_versions_dir(remote_repo, &missing_entries, &repo.path, pull_progress)
-            .await?;
+        pull_entries_to_versions_dir(
+            remote_repo,
+            &missing_entries,
+            &repo.path,
+            pull_progress,
+            repo.remote_config().download_concurrency,
+        )
+        .await?;
     }
 
     if let EMerkleTreeNode::Commit(commit_node) = &node.node {
@@ -546,14 +713,30 @@ async fn r_download_entries(
     Ok(())
 }
 
+/// Number of connections to download version files over, `--jobs` wins over the repo config
+fn download_concurrency(repo: &LocalRepository, fetch_opts: &FetchOpts) -> Option<usize> {
+    fetch_opts
+        .jobs
+        .or(repo.remote_config().download_concurrency)
+}
+
 pub async fn pull_entries_to_versions_dir(
     remote_repo: &RemoteRepository,
     entries: &[Entry],
     dst: &Path,
     progress_bar: &Arc<PullProgress>,
+    num_workers: Option<usize>,
 ) -> Result<(), OxenError> {
     let to_working_dir = false;
-    pull_entries(remote_repo, entries, dst, to_working_dir, progress_bar).await?;
+    pull_entries(
+        remote_repo,
+        entries,
+        dst,
+        to_working_dir,
+        progress_bar,
+        num_workers,
+    )
+    .await?;
     Ok(())
 }
 
@@ -562,18 +745,30 @@ pub async fn pull_entries_to_working_dir(
     entries: &[Entry],
     dst: &Path,
     progress_bar: &Arc<PullProgress>,
+    num_workers: Option<usize>,
 ) -> Result<(), OxenError> {
     let to_working_dir = true;
-    pull_entries(remote_repo, entries, dst, to_working_dir, progress_bar).await?;
+    pull_entries(
+        remote_repo,
+        entries,
+        dst,
+        to_working_dir,
+        progress_bar,
+        num_workers,
+    )
+    .await?;
     Ok(())
 }
 
+/// Download the entries missing from `dst`. Files are fetched over `num_workers` concurrent
+/// connections that share the progress bar, see `concurrency::num_threads_for_items` when None.
 pub async fn pull_entries(
     remote_repo: &RemoteRepository,
     entries: &[Entry],
     dst: &Path,
     to_working_dir: bool,
     progress_bar: &Arc<PullProgress>,
+    num_workers: Option<usize>,
 ) -> Result<(), OxenError> {
     log::debug!("entries.len() {}", entries.len());
 
@@ -622,6 +817,7 @@ pub async fn pull_entries(
         &dst,
         large_entry_paths,
         progress_bar,
+        num_workers,
     );
 
     let small_entries_sync = pull_small_entries(
@@ -630,6 +826,7 @@ pub async fn pull_entries(
         &dst,
         small_entry_paths,
         progress_bar,
+        num_workers,
     );
 
     match tokio::join!(large_entries_sync, small_entries_sync) {
@@ -644,7 +841,12 @@ pub async fn pull_entries(
             let err = format!("Error syncing small entries: {err}");
             return Err(OxenError::basic_str(err));
         }
-        _ => return Err(OxenError::basic_str("Unknown error syncing entries")),
+        (Err(large_err), Err(small_err)) => {
+            let err = format!(
+                "Error syncing large entries: {large_err}\nError syncing small entries: {small_err}"
+            );
+            return Err(OxenError::basic_str(err));
+        }
     }
 
     Ok(())
@@ -656,6 +858,7 @@ async fn pull_large_entries(
     dst: impl AsRef<Path>,
     download_paths: Vec<PathBuf>,
     progress_bar: &Arc<PullProgress>,
+    num_workers: Option<usize>,
 ) -> Result<(), OxenError> {
     if entries.is_empty() {
         return Ok(());
@@ -687,7 +890,7 @@ async fn pull_large_entries(
         finished_queue.try_push(false).unwrap();
     }
 
-    let worker_count = concurrency::num_threads_for_items(entries.len());
+    let worker_count = concurrency::num_threads_for_items_with_limit(entries.len(), num_workers);
     log::debug!(
         "worker_count {} entries len {}",
         worker_count,
@@ -695,10 +898,12 @@ async fn pull_large_entries(
     );
     let tmp_dir = util::fs::oxen_hidden_dir(dst).join("tmp").join("pulled");
     log::debug!("Backing up pulls to tmp dir: {:?}", &tmp_dir);
+    let errors: Arc<parking_lot::Mutex<Vec<String>>> = Arc::new(parking_lot::Mutex::new(vec![]));
     for worker in 0..worker_count {
         let queue = queue.clone();
         let finished_queue = finished_queue.clone();
         let progress_bar = Arc::clone(progress_bar);
+        let errors = errors.clone();
         tokio::spawn(async move {
             loop {
                 let (remote_repo, entry, _dst, download_path) = queue.pop().await;
@@ -724,7 +929,8 @@ async fn pull_large_entries(
                         progress_bar.add_files(1);
                     }
                     Err(err) => {
-                        log::error!("Could not download chunk... {}", err)
+                        log::error!("Could not download chunk... {}", err);
+                        errors.lock().push(format!("{:?}: {}", remote_path, err));
                     }
                 }
 
@@ -739,6 +945,14 @@ async fn pull_large_entries(
     }
     log::debug!("All large file tasks done. :-)");
 
+    let errors = errors.lock();
+    if !errors.is_empty() {
+        return Err(OxenError::basic_str(format!(
+            "{} files failed to download\n{}",
+            errors.len(),
+            errors.join("\n")
+        )));
+    }
     Ok(())
 }
 
@@ -748,6 +962,7 @@ async fn pull_small_entries(
     dst: impl AsRef<Path>,
     content_ids: Vec<(String, PathBuf)>,
     progress_bar: &Arc<PullProgress>,
+    num_workers: Option<usize>,
 ) -> Result<(), OxenError> {
     if content_ids.is_empty() {
         return Ok(());
@@ -786,7 +1001,8 @@ async fn pull_small_entries(
         })
         .collect();
 
-    let worker_count = concurrency::num_threads_for_items(entries.len());
+    let worker_count = concurrency::num_threads_for_items_with_limit(entries.len(), num_workers);
+    let errors: Arc<parking_lot::Mutex<Vec<String>>> = Arc::new(parking_lot::Mutex::new(vec![]));
     let queue = Arc::new(TaskQueue::new(chunks.len()));
     let finished_queue = Arc::new(FinishedTaskQueue::new(entries.len()));
     for chunk in chunks {
@@ -798,6 +1014,7 @@ async fn pull_small_entries(
         let queue = queue.clone();
         let finished_queue = finished_queue.clone();
         let progress_bar = Arc::clone(progress_bar);
+        let errors = errors.clone();
         tokio::spawn(async move {
             loop {
                 let (remote_repo, chunk, path) = queue.pop().await;
@@ -815,7 +1032,10 @@ async fn pull_small_entries(
                         progress_bar.add_files(chunk.len() as u64);
                     }
                     Err(err) => {
-                        log::error!("Could not download entries... {}", err)
+                        log::error!("Could not download entries... {}", err);
+                        errors
+                            .lock()
+                            .push(format!("{} files: {}", chunk.len(), err));
                     }
                 }
 
@@ -829,6 +1049,14 @@ async fn pull_small_entries(
     }
     log::debug!("All tasks done. :-)");
 
+    let errors = errors.lock();
+    if !errors.is_empty() {
+        return Err(OxenError::basic_str(format!(
+            "{} batches of files failed to download\n{}",
+            errors.len(),
+            errors.join("\n")
+        )));
+    }
     Ok(())
 }
 
@@ -1,4 +1,9 @@
 pub mod commit_merkle_tree;
+pub mod export;
 pub mod file_chunker;
 pub mod restore;
+pub mod tree_cache;
+pub mod verify;
 pub use commit_merkle_tree::CommitMerkleTree;
+pub use export::ExportedNode;
+pub use verify::{TreeProblem, TreeProblemKind};
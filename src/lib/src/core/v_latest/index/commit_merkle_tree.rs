@@ -7,6 +7,7 @@ use rocksdb::{DBWithThreadMode, IteratorMode, MultiThreaded};
 use crate::constants::{DIR_HASHES_DIR, HISTORY_DIR};
 use crate::core::db;
 use crate::core::db::merkle_node::MerkleNodeDB;
+use crate::core::v_latest::index::tree_cache;
 
 use crate::model::merkle_tree::node::EMerkleTreeNode;
 
@@ -53,7 +54,7 @@ impl CommitMerkleTree {
         commit: &Commit,
     ) -> Result<Option<MerkleTreeNode>, OxenError> {
         let node_hash = MerkleHash::from_str(&commit.id)?;
-        CommitMerkleTree::read_node(repo, &node_hash, true)
+        CommitMerkleTree::read_commit_tree(repo, &node_hash)
     }
 
     // MODULARIZE: We don't need to use this directly, go through repositories::tree
@@ -126,7 +127,7 @@ impl CommitMerkleTree {
         log::debug!("Load tree from commit: {} in repo: {:?}", commit, repo.path);
         let node_hash = MerkleHash::from_str(&commit.id)?;
         let root =
-            CommitMerkleTree::read_node(repo, &node_hash, true)?.ok_or(OxenError::basic_str(
+            CommitMerkleTree::read_commit_tree(repo, &node_hash)?.ok_or(OxenError::basic_str(
                 format!("Merkle tree hash not found for commit: '{}'", commit.id),
             ))?;
         let dir_hashes = CommitMerkleTree::dir_hashes(repo, commit)?;
@@ -399,6 +400,21 @@ impl CommitMerkleTree {
         }
     }
 
+    // Fully loaded commit trees go through the persistent tree cache when it is enabled
+    fn read_commit_tree(
+        repo: &LocalRepository,
+        commit_hash: &MerkleHash,
+    ) -> Result<Option<MerkleTreeNode>, OxenError> {
+        if let Some(root) = tree_cache::get(repo, commit_hash) {
+            return Ok(Some(root));
+        }
+        let root = CommitMerkleTree::read_node(repo, commit_hash, true)?;
+        if let Some(root) = &root {
+            tree_cache::put(repo, commit_hash, root);
+        }
+        Ok(root)
+    }
+
     pub fn read_node(
         repo: &LocalRepository,
         hash: &MerkleHash,
@@ -780,15 +796,13 @@ impl CommitMerkleTree {
 
         // log::debug!("read_file vnodes: {}", vnodes.len());
 
-        // Calculate the total number of children in the vnodes
-        // And use this to skip to the correct vnode
+        // Use the number of vnodes the directory was actually written with to skip
+        // to the correct vnode, the repo's vnode size may have changed since then
         // log::debug!("read_file dir_node {:?}", dir_node);
         let total_children = dir_node.num_entries();
-        let vnode_size = repo.vnode_size();
-        let num_vnodes = (total_children as f32 / vnode_size as f32).ceil() as u128;
+        let num_vnodes = vnodes.len() as u128;
 
         log::debug!("read_file total_children: {}", total_children);
-        log::debug!("read_file vnode_size: {}", vnode_size);
         log::debug!("read_file num_vnodes: {}", num_vnodes);
 
         if num_vnodes == 0 {
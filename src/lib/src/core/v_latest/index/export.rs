@@ -0,0 +1,184 @@
+//! # Tree export
+//!
+//! Serialize a merkle tree, or the subtree under a node, to JSON for tools that inspect
+//! the structure of a repository, or to Graphviz DOT to draw it with `dot -Tsvg`. Like
+//! `print_depth`, a depth of -1 exports every level and otherwise stops after that many
+//! levels below the starting node.
+//!
+
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::v_latest::index::CommitMerkleTree;
+use crate::error::OxenError;
+use crate::model::merkle_tree::node::{EMerkleTreeNode, MerkleTreeNode};
+use crate::model::{MerkleHash, MerkleTreeNodeType};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ExportedNode {
+    pub hash: String,
+    pub node_type: MerkleTreeNodeType,
+    /// File or dir name, or the message of a commit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub children: Vec<ExportedNode>,
+}
+
+impl ExportedNode {
+    pub fn from_node(node: &MerkleTreeNode, depth: i32) -> ExportedNode {
+        ExportedNode::r_from_node(node, 0, depth)
+    }
+
+    fn r_from_node(node: &MerkleTreeNode, level: i32, depth: i32) -> ExportedNode {
+        let (name, num_bytes) = match &node.node {
+            EMerkleTreeNode::File(file_node) => (
+                Some(file_node.name().to_string()),
+                Some(file_node.num_bytes()),
+            ),
+            EMerkleTreeNode::Directory(dir_node) => (
+                Some(dir_node.name().to_string()),
+                Some(dir_node.num_bytes()),
+            ),
+            EMerkleTreeNode::Commit(commit_node) => (Some(commit_node.message().to_string()), None),
+            EMerkleTreeNode::VNode(_) | EMerkleTreeNode::FileChunk(_) => (None, None),
+        };
+        let children = if depth == -1 || level < depth {
+            node.children
+                .iter()
+                .map(|child| ExportedNode::r_from_node(child, level + 1, depth))
+                .collect()
+        } else {
+            vec![]
+        };
+        ExportedNode {
+            hash: node.hash.to_string(),
+            node_type: node.node.node_type(),
+            name,
+            num_bytes,
+            children,
+        }
+    }
+}
+
+impl CommitMerkleTree {
+    pub fn to_json(&self, depth: i32) -> Result<String, OxenError> {
+        CommitMerkleTree::node_to_json(&self.root, depth)
+    }
+
+    pub fn to_dot(&self, depth: i32) -> String {
+        CommitMerkleTree::node_to_dot(&self.root, depth)
+    }
+
+    pub fn node_to_json(node: &MerkleTreeNode, depth: i32) -> Result<String, OxenError> {
+        Ok(serde_json::to_string_pretty(&ExportedNode::from_node(
+            node, depth,
+        ))?)
+    }
+
+    /// Nodes shared by several parents are drawn once with an edge from each parent
+    pub fn node_to_dot(node: &MerkleTreeNode, depth: i32) -> String {
+        let mut dot = String::from("digraph merkle_tree {\n    node [fontname=\"monospace\"];\n");
+        let mut seen: HashSet<MerkleHash> = HashSet::new();
+        r_to_dot(node, 0, depth, &mut seen, &mut dot);
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+fn r_to_dot(
+    node: &MerkleTreeNode,
+    level: i32,
+    depth: i32,
+    seen: &mut HashSet<MerkleHash>,
+    dot: &mut String,
+) {
+    if !seen.insert(node.hash) {
+        return;
+    }
+
+    let hash = node.hash.to_short_str();
+    let (shape, label) = match &node.node {
+        EMerkleTreeNode::Commit(commit_node) => (
+            "doubleoctagon",
+            format!("commit {hash}\n{}", commit_node.message()),
+        ),
+        EMerkleTreeNode::Directory(dir_node) => {
+            let name = if dir_node.name().is_empty() {
+                "/"
+            } else {
+                dir_node.name()
+            };
+            ("folder", format!("{name}\n{hash}"))
+        }
+        EMerkleTreeNode::VNode(_) => ("ellipse", format!("vnode {hash}")),
+        EMerkleTreeNode::File(file_node) => ("note", format!("{}\n{hash}", file_node.name())),
+        EMerkleTreeNode::FileChunk(_) => ("box", format!("chunk {hash}")),
+    };
+    let _ = writeln!(
+        dot,
+        "    \"{}\" [shape={shape}, label=\"{}\"];",
+        node.hash,
+        escape(&label)
+    );
+
+    if depth != -1 && level >= depth {
+        return;
+    }
+    for child in &node.children {
+        let _ = writeln!(dot, "    \"{}\" -> \"{}\";", node.hash, child.hash);
+        r_to_dot(child, level + 1, depth, seen, dot);
+    }
+}
+
+fn escape(label: &str) -> String {
+    label
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::v_latest::index::CommitMerkleTree;
+    use crate::error::OxenError;
+    use crate::model::MerkleTreeNodeType;
+    use crate::repositories;
+    use crate::test;
+
+    use super::ExportedNode;
+
+    #[tokio::test]
+    async fn test_export_tree_to_json_and_dot() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed_async(|repo| async move {
+            let commit = repositories::commits::head_commit(&repo)?;
+            let tree = CommitMerkleTree::from_commit(&repo, &commit)?;
+
+            // commit -> root dir -> vnode -> entries of the root dir
+            let exported: ExportedNode = serde_json::from_str(&tree.to_json(3)?)?;
+            assert_eq!(exported.node_type, MerkleTreeNodeType::Commit);
+            assert_eq!(exported.hash, commit.id);
+            let root_dir = &exported.children[0];
+            assert_eq!(root_dir.node_type, MerkleTreeNodeType::Dir);
+            let vnode = &root_dir.children[0];
+            assert_eq!(vnode.node_type, MerkleTreeNodeType::VNode);
+            assert!(vnode.children.iter().any(|child| {
+                child.node_type == MerkleTreeNodeType::Dir
+                    && child.name.as_deref() == Some("train")
+                    && child.children.is_empty()
+            }));
+
+            let dot = tree.to_dot(-1);
+            assert!(dot.starts_with("digraph merkle_tree {"));
+            assert!(dot.contains(&format!("\"{}\" [shape=doubleoctagon", commit.id)));
+            assert!(dot.contains("shape=note, label=\"dog_1.jpg"));
+
+            Ok(())
+        })
+        .await
+    }
+}
@@ -247,7 +247,7 @@ pub fn should_restore_partial(
             }
 
             // If modified times are different, check hashes
-            let hash = MerkleHash::new(util::hasher::u128_hash_file_contents(&working_path)?);
+            let hash = MerkleHash::new(repo.hash_algorithm().hash_file_contents(&working_path)?);
 
             let base_node_hash = base_node.hash;
             if hash != base_node_hash {
@@ -267,7 +267,7 @@ pub fn should_restore_partial(
             }
 
             // If modified times are different, check hashes
-            let hash = MerkleHash::new(util::hasher::u128_hash_file_contents(&working_path)?);
+            let hash = MerkleHash::new(repo.hash_algorithm().hash_file_contents(&working_path)?);
             if hash != *file_node.hash() {
                 return Ok(false);
             }
@@ -306,7 +306,7 @@ pub fn should_restore_file(
             }
 
             // If modified times are different, check hashes
-            let hash = MerkleHash::new(util::hasher::u128_hash_file_contents(&working_path)?);
+            let hash = MerkleHash::new(repo.hash_algorithm().hash_file_contents(&working_path)?);
 
             let base_node_hash = base_node.hash();
             if hash != *base_node_hash {
@@ -326,7 +326,7 @@ pub fn should_restore_file(
             }
 
             // If modified times are different, check hashes
-            let hash = MerkleHash::new(util::hasher::u128_hash_file_contents(&working_path)?);
+            let hash = MerkleHash::new(repo.hash_algorithm().hash_file_contents(&working_path)?);
             if hash != *file_node.hash() {
                 return Ok(false);
             }
@@ -364,5 +364,6 @@ pub async fn restore_file(
         &working_path,
         filetime::FileTime::from_system_time(last_modified),
     )?;
+    util::fs::set_file_mode(&working_path, file_node.mode())?;
     Ok(())
 }
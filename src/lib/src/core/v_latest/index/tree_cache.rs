@@ -0,0 +1,139 @@
+//! Persistent cache of fully loaded commit merkle trees
+//!
+//! Loading a whole tree walks one node db per dir and vnode, which every CLI invocation pays
+//! for again since the LRU in `merkle_tree_node_cache` only lives as long as the process.
+//! When `tree = true` is set under `[cache]` in `.oxen/config.toml`, the first full load of a
+//! commit's tree is serialized to `.oxen/cache/tree/<commit_id>` and later loads read it back
+//! in one go.
+//!
+//! ```toml
+//! [cache]
+//! tree = true
+//! ```
+//!
+//! The tree of a commit never changes, so entries are only removed by `clear`. Subtree and
+//! depth clones are skipped because their trees are missing nodes that a later fetch fills in.
+//!
+
+use std::path::PathBuf;
+
+use crate::constants::{CACHE_DIR, TREE_DIR};
+use crate::error::OxenError;
+use crate::model::merkle_tree::node::MerkleTreeNode;
+use crate::model::{LocalRepository, MerkleHash};
+use crate::util;
+
+fn cache_dir(repo: &LocalRepository) -> PathBuf {
+    util::fs::oxen_hidden_dir(&repo.path)
+        .join(CACHE_DIR)
+        .join(TREE_DIR)
+}
+
+fn cache_path(repo: &LocalRepository, commit_hash: &MerkleHash) -> PathBuf {
+    cache_dir(repo).join(commit_hash.to_string())
+}
+
+/// Whether trees of this repository are written to and read from the cache
+pub fn is_enabled(repo: &LocalRepository) -> bool {
+    repo.cache_config().tree
+        && !repo.is_remote_mode()
+        && repo.subtree_paths().is_none()
+        && repo.depth().is_none()
+}
+
+/// The fully loaded tree of a commit, if it was cached. A cache file that can't be read is
+/// removed so the tree gets loaded from the node dbs and cached again.
+pub fn get(repo: &LocalRepository, commit_hash: &MerkleHash) -> Option<MerkleTreeNode> {
+    if !is_enabled(repo) {
+        return None;
+    }
+    let path = cache_path(repo, commit_hash);
+    let data = std::fs::read(&path).ok()?;
+    match rmp_serde::from_slice(&data) {
+        Ok(node) => Some(node),
+        Err(err) => {
+            log::warn!("Removing unreadable tree cache {path:?}: {err}");
+            let _ = util::fs::remove_file(&path);
+            None
+        }
+    }
+}
+
+/// Cache the fully loaded tree of a commit. Failing to write the cache only logs a warning
+/// since the tree can always be loaded from the node dbs.
+pub fn put(repo: &LocalRepository, commit_hash: &MerkleHash, root: &MerkleTreeNode) {
+    if !is_enabled(repo) {
+        return;
+    }
+    if let Err(err) = write(repo, commit_hash, root) {
+        log::warn!("Could not cache the tree of commit {commit_hash}: {err}");
+    }
+}
+
+fn write(
+    repo: &LocalRepository,
+    commit_hash: &MerkleHash,
+    root: &MerkleTreeNode,
+) -> Result<(), OxenError> {
+    let data = rmp_serde::to_vec(root).map_err(|err| OxenError::basic_str(err.to_string()))?;
+    let dir = cache_dir(repo);
+    util::fs::create_dir_all(&dir)?;
+    // Write to a temp file first so a concurrent reader never sees half a tree
+    let path = cache_path(repo, commit_hash);
+    let tmp_path = dir.join(format!("{commit_hash}.{}.tmp", uuid::Uuid::new_v4()));
+    std::fs::write(&tmp_path, data)?;
+    util::fs::rename(&tmp_path, &path)?;
+    Ok(())
+}
+
+/// Remove every cached tree, for when the node dbs are rewritten
+pub fn clear(repo: &LocalRepository) -> Result<(), OxenError> {
+    let dir = cache_dir(repo);
+    if dir.exists() {
+        util::fs::remove_dir_all(&dir)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::config::CacheConfig;
+    use crate::core::v_latest::index::tree_cache;
+    use crate::error::OxenError;
+    use crate::model::MerkleHash;
+    use crate::repositories;
+    use crate::test;
+
+    #[tokio::test]
+    async fn test_tree_cache_is_read_back() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed_async(|mut repo| async move {
+            let commit = repositories::commits::head_commit(&repo)?;
+            let hash = MerkleHash::from_str(&commit.id)?;
+
+            // Off by default
+            repositories::tree::get_root_with_children(&repo, &commit)?;
+            assert!(tree_cache::get(&repo, &hash).is_none());
+
+            repo.set_cache_config(Some(CacheConfig {
+                tree: true,
+                ..Default::default()
+            }));
+            let loaded = repositories::tree::get_root_with_children(&repo, &commit)?.unwrap();
+            let cached = tree_cache::get(&repo, &hash).expect("tree was cached");
+            assert_eq!(cached.list_dir_paths()?, loaded.list_dir_paths()?);
+            let mut num_loaded = 0;
+            loaded.walk_tree(|_| num_loaded += 1);
+            let mut num_cached = 0;
+            cached.walk_tree(|_| num_cached += 1);
+            assert_eq!(num_cached, num_loaded);
+
+            tree_cache::clear(&repo)?;
+            assert!(tree_cache::get(&repo, &hash).is_none());
+
+            Ok(())
+        })
+        .await
+    }
+}
@@ -0,0 +1,214 @@
+//! # Tree verification
+//!
+//! Walk a commit's merkle tree straight from the node dbs, bypassing the node caches, and
+//! check that every node can be read, that each node is stored under the hash its parent
+//! refers to it by, that nodes only have the children their type allows, and that the
+//! combined hash of each file still matches its content hash, metadata hash and mode.
+//!
+//! Dir and vnode hashes can't be recomputed from their children: the commit writer salts
+//! the vnodes of changed dirs with a random id so they are never reused, and mixes subdirs
+//! into a dir's hash in whatever order it visits them. Verification checks how nodes refer
+//! to each other rather than re-deriving those hashes.
+//!
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::db::merkle_node::MerkleNodeDB;
+use crate::core::v_latest::index::CommitMerkleTree;
+use crate::error::OxenError;
+use crate::model::merkle_tree::merkle_tree_node_cache;
+use crate::model::merkle_tree::node::{EMerkleTreeNode, FileNode, MerkleTreeNode};
+use crate::model::{Commit, LocalRepository, MerkleHash, MerkleTreeNodeType};
+use crate::util;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TreeProblemKind {
+    /// The node db could not be read, so nothing below it was checked
+    MissingNode,
+    /// The node is stored under a different hash than its parent refers to it by
+    HashMismatch,
+    /// The node has a child its type can't have, such as a file directly under a dir
+    UnexpectedChild,
+    /// The combined hash of a file does not match its content hash, metadata and mode
+    CombinedHashMismatch,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TreeProblem {
+    pub kind: TreeProblemKind,
+    /// The file or directory with the problem, empty for the root
+    pub path: PathBuf,
+    /// Hash of the node with the problem
+    pub hash: String,
+}
+
+impl fmt::Display for TreeProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let description = match self.kind {
+            TreeProblemKind::MissingNode => "node could not be read",
+            TreeProblemKind::HashMismatch => "node is stored under a different hash",
+            TreeProblemKind::UnexpectedChild => "node type is not allowed here",
+            TreeProblemKind::CombinedHashMismatch => "combined hash does not match the file",
+        };
+        write!(f, "{} {}: {description}", self.hash, self.path.display())
+    }
+}
+
+impl CommitMerkleTree {
+    /// Check the merkle tree of a commit, returning every inconsistent node with the path
+    /// of the subtree it is in. An empty list means the tree is consistent.
+    pub fn verify(repo: &LocalRepository, commit: &Commit) -> Result<Vec<TreeProblem>, OxenError> {
+        let commit_hash = MerkleHash::from_str(&commit.id)?;
+        let mut problems = vec![];
+        merkle_tree_node_cache::with_cache_disabled(|| {
+            verify_node(
+                repo,
+                &commit_hash,
+                MerkleTreeNodeType::Commit,
+                Path::new(""),
+                &mut problems,
+            )
+        })?;
+        Ok(problems)
+    }
+}
+
+fn verify_node(
+    repo: &LocalRepository,
+    hash: &MerkleHash,
+    node_type: MerkleTreeNodeType,
+    path: &Path,
+    problems: &mut Vec<TreeProblem>,
+) -> Result<(), OxenError> {
+    let mut problem = |kind: TreeProblemKind, path: &Path, hash: &MerkleHash| {
+        problems.push(TreeProblem {
+            kind,
+            path: path.to_path_buf(),
+            hash: hash.to_string(),
+        })
+    };
+
+    let children = match read_node(repo, hash) {
+        Ok((stored_hash, children)) => {
+            if stored_hash != *hash {
+                problem(TreeProblemKind::HashMismatch, path, hash);
+            }
+            children
+        }
+        Err(err) => {
+            log::debug!("verify could not read node {hash} at {path:?}: {err}");
+            problem(TreeProblemKind::MissingNode, path, hash);
+            return Ok(());
+        }
+    };
+
+    let mut subtrees: Vec<(MerkleHash, MerkleTreeNodeType, PathBuf)> = vec![];
+    for (child_hash, child) in children {
+        let child_path = match &child.node {
+            EMerkleTreeNode::Directory(dir_node) => path.join(dir_node.name()),
+            EMerkleTreeNode::File(file_node) => path.join(file_node.name()),
+            _ => path.to_path_buf(),
+        };
+        if child_hash != *child.node.hash() {
+            problem(TreeProblemKind::HashMismatch, &child_path, &child_hash);
+        }
+
+        let child_type = child.node.node_type();
+        let allowed = match node_type {
+            MerkleTreeNodeType::Commit => child_type == MerkleTreeNodeType::Dir,
+            MerkleTreeNodeType::Dir => child_type == MerkleTreeNodeType::VNode,
+            MerkleTreeNodeType::VNode => {
+                matches!(
+                    child_type,
+                    MerkleTreeNodeType::Dir | MerkleTreeNodeType::File
+                )
+            }
+            _ => false,
+        };
+        if !allowed {
+            problem(TreeProblemKind::UnexpectedChild, &child_path, &child_hash);
+            continue;
+        }
+
+        match &child.node {
+            EMerkleTreeNode::File(file_node) => {
+                if !combined_hash_matches(file_node)? {
+                    problem(
+                        TreeProblemKind::CombinedHashMismatch,
+                        &child_path,
+                        &child_hash,
+                    );
+                }
+            }
+            // The root dir of a commit is named by its path, which is empty
+            EMerkleTreeNode::Directory(_) if node_type == MerkleTreeNodeType::Commit => {
+                subtrees.push((child_hash, child_type, path.to_path_buf()));
+            }
+            _ => subtrees.push((child_hash, child_type, child_path)),
+        }
+    }
+
+    for (child_hash, child_type, child_path) in subtrees {
+        verify_node(repo, &child_hash, child_type, &child_path, problems)?;
+    }
+    Ok(())
+}
+
+// The hash the node db stores for its own node, and its children
+fn read_node(
+    repo: &LocalRepository,
+    hash: &MerkleHash,
+) -> Result<(MerkleHash, Vec<(MerkleHash, MerkleTreeNode)>), OxenError> {
+    let mut node_db = MerkleNodeDB::open_read_only(repo, hash)?;
+    let stored_hash = *node_db.node()?.hash();
+    let children = node_db.map()?;
+    Ok((stored_hash, children))
+}
+
+fn combined_hash_matches(file_node: &FileNode) -> Result<bool, OxenError> {
+    let combined = util::hasher::get_combined_hash(
+        file_node.metadata_hash().map(|hash| hash.to_u128()),
+        file_node.hash().to_u128(),
+    )?;
+    let combined = util::hasher::get_combined_hash_with_mode(combined, file_node.mode());
+    Ok(MerkleHash::new(combined) == *file_node.combined_hash())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::db::merkle_node::merkle_node_db::node_db_path;
+    use crate::core::v_latest::index::{CommitMerkleTree, TreeProblemKind};
+    use crate::error::OxenError;
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    #[tokio::test]
+    async fn test_verify_finds_missing_subtree() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed_async(|repo| async move {
+            let commit = repositories::commits::head_commit(&repo)?;
+            let problems = CommitMerkleTree::verify(&repo, &commit)?;
+            assert!(problems.is_empty(), "{problems:?}");
+
+            // Remove the node db of the train dir
+            let tree = repositories::tree::get_root_with_children(&repo, &commit)?.unwrap();
+            let train = tree.get_by_path("train")?.expect("train dir exists");
+            let node_dir = node_db_path(&repo, &train.hash);
+            util::fs::remove_dir_all(&node_dir)?;
+
+            let problems = CommitMerkleTree::verify(&repo, &commit)?;
+            assert_eq!(problems.len(), 1, "{problems:?}");
+            assert_eq!(problems[0].kind, TreeProblemKind::MissingNode);
+            assert_eq!(problems[0].path, std::path::PathBuf::from("train"));
+            assert_eq!(problems[0].hash, train.hash.to_string());
+
+            Ok(())
+        })
+        .await
+    }
+}
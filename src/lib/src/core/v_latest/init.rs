@@ -4,6 +4,7 @@ use crate::core::versions::MinOxenVersion;
 use crate::error::OxenError;
 use crate::model::LocalRepository;
 use crate::util;
+use crate::util::hasher::HashAlgorithm;
 
 pub fn init(path: &Path) -> Result<LocalRepository, OxenError> {
     let hidden_dir = util::fs::oxen_hidden_dir(path);
@@ -34,7 +35,8 @@ pub fn init_with_version(
         return Err(OxenError::basic_str(err));
     }
 
-    let repo = LocalRepository::new_from_version(path, version.to_string())?;
+    let mut repo = LocalRepository::new_from_version(path, version.to_string())?;
+    repo.set_hash_algorithm(HashAlgorithm::default());
     repo.save()?;
 
     Ok(repo)
@@ -1,23 +1,27 @@
+use crate::config::MergeStrategy;
 use crate::core::db;
+use crate::core::df::tabular;
 pub use crate::core::merge::entry_merge_conflict_db_reader::EntryMergeConflictDBReader;
 pub use crate::core::merge::node_merge_conflict_db_reader::NodeMergeConflictDBReader;
 use crate::core::merge::node_merge_conflict_reader::NodeMergeConflictReader;
+use crate::core::merge::tabular_merge::{self, TabularMergeResult};
 use crate::core::merge::{db_path, node_merge_conflict_writer};
 use crate::core::refs::with_ref_manager;
 use crate::core::v_latest::commits::{get_commit_or_head, list_between};
 use crate::core::v_latest::index::CommitMerkleTree;
 use crate::core::v_latest::{add, rm};
 use crate::error::OxenError;
-use crate::model::merge_conflict::NodeMergeConflict;
-use crate::model::merkle_tree::node::{EMerkleTreeNode, MerkleTreeNode};
-use crate::model::{Branch, Commit, LocalRepository};
+use crate::model::merge_conflict::{NodeMergeConflict, RowResolution};
+use crate::model::merkle_tree::node::{EMerkleTreeNode, FileNode, MerkleTreeNode};
+use crate::model::{Branch, Commit, EntryDataType, LocalRepository};
 use crate::model::{MerkleHash, PartialNode};
-use crate::opts::RmOpts;
+use crate::opts::{DFOpts, RmOpts};
 use crate::repositories;
 use crate::repositories::commits::commit_writer;
 use crate::repositories::merge::MergeCommits;
 use crate::util;
 
+use polars::frame::{DataFrame, UniqueKeepStrategy};
 use rocksdb::DB;
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
@@ -73,6 +77,61 @@ pub fn mark_conflict_as_resolved(repo: &LocalRepository, path: &Path) -> Result<
     node_merge_conflict_writer::mark_conflict_as_resolved_in_db(repo, path)
 }
 
+/// Writes the picked version of each conflicting row to the tabular file, and stages it
+pub async fn resolve_row_conflicts(
+    repo: &LocalRepository,
+    path: &Path,
+    resolutions: &[RowResolution],
+) -> Result<(), OxenError> {
+    let conflicts = list_conflicts(repo)?;
+    let Some(conflict) = conflicts.iter().find(|c| c.base_entry.1 == path) else {
+        return Err(OxenError::basic_str(format!(
+            "No merge conflict found for {path:?}"
+        )));
+    };
+    if conflict.row_conflicts.is_empty() {
+        return Err(OxenError::basic_str(format!(
+            "{path:?} does not have row conflicts, resolve it with `oxen checkout --ours` or `oxen checkout --theirs`"
+        )));
+    }
+
+    let full_path = repo.path.join(path);
+    let df = tabular::read_df(&full_path, DFOpts::empty())?;
+    let mut df = tabular_merge::resolve_rows(&df, &conflict.row_conflicts, resolutions)?;
+    tabular::write_df(&mut df, &full_path)?;
+
+    // Adding the file marks the conflict as resolved
+    repositories::add(repo, &full_path).await?;
+    Ok(())
+}
+
+/// Resolves a conflicting file as a whole with a strategy, and stages it
+pub async fn resolve_conflict(
+    repo: &LocalRepository,
+    path: &Path,
+    strategy: MergeStrategy,
+) -> Result<(), OxenError> {
+    let conflicts = list_conflicts(repo)?;
+    let Some(conflict) = conflicts.iter().find(|c| c.base_entry.1 == path) else {
+        return Err(OxenError::could_not_find_merge_conflict(path));
+    };
+
+    let resolved = match strategy {
+        MergeStrategy::Ours => resolve_ours(repo, conflict).await?,
+        MergeStrategy::Theirs => resolve_theirs(repo, conflict).await?,
+        MergeStrategy::Union => resolve_union(repo, conflict)?,
+    };
+    if !resolved {
+        return Err(OxenError::basic_str(format!(
+            "Could not resolve {path:?} with strategy {strategy}"
+        )));
+    }
+
+    // Adding the file marks the conflict as resolved
+    repositories::add(repo, repo.path.join(path)).await?;
+    Ok(())
+}
+
 /// Check if there are conflicts between the merge commit and the base commit
 /// Returns true if there are no conflicts, false if there are conflicts
 pub async fn can_merge_commits(
@@ -161,22 +220,36 @@ pub async fn list_conflicts_between_commits(
     base_commit: &Commit,
     merge_commit: &Commit,
 ) -> Result<Vec<PathBuf>, OxenError> {
+    let conflicts = find_conflicts_between_commits(repo, base_commit, merge_commit).await?;
+    Ok(conflicts
+        .iter()
+        .map(|c| {
+            let (_, path) = &c.base_entry;
+            path.to_owned()
+        })
+        .collect())
+}
+
+/// The conflicts merging `merge_commit` into `base_commit` would run into, without touching
+/// the working directory. Tabular files are merged row by row first, so only the rows that
+/// changed differently on both sides are left in `row_conflicts`.
+pub async fn find_conflicts_between_commits(
+    repo: &LocalRepository,
+    base_commit: &Commit,
+    merge_commit: &Commit,
+) -> Result<Vec<NodeMergeConflict>, OxenError> {
     let lca = lowest_common_ancestor_from_commits(repo, base_commit, merge_commit)?;
     let merge_commits = MergeCommits {
         lca,
         base: base_commit.clone(),
         merge: merge_commit.clone(),
     };
+    if merge_commits.is_fast_forward_merge() {
+        return Ok(vec![]);
+    }
     let write_to_disk = false;
     let mut _hashes = HashSet::new();
-    let conflicts = find_merge_conflicts(repo, &merge_commits, write_to_disk, &mut _hashes).await?;
-    Ok(conflicts
-        .iter()
-        .map(|c| {
-            let (_, path) = &c.base_entry;
-            path.to_owned()
-        })
-        .collect())
+    find_merge_conflicts(repo, &merge_commits, write_to_disk, &mut _hashes).await
 }
 
 /// Merge a branch into a base branch, returns the merge commit if successful, and None if there is conflicts
@@ -213,13 +286,23 @@ pub async fn merge_into_base(
         merge: merge_commit,
     };
 
-    merge_commits(repo, &commits).await
+    merge_commits(repo, &commits, None).await
 }
 
 /// Merge into the current branch, returns the merge commit if successful, and None if there is conflicts
 pub async fn merge(
     repo: &LocalRepository,
     branch_name: impl AsRef<str>,
+) -> Result<Option<Commit>, OxenError> {
+    merge_with_strategy(repo, branch_name, None).await
+}
+
+/// Merge into the current branch, resolving any conflicts with the given strategy.
+/// Without a strategy, only the strategies configured per path in the repository are applied.
+pub async fn merge_with_strategy(
+    repo: &LocalRepository,
+    branch_name: impl AsRef<str>,
+    strategy: Option<MergeStrategy>,
 ) -> Result<Option<Commit>, OxenError> {
     let branch_name = branch_name.as_ref();
 
@@ -234,7 +317,7 @@ pub async fn merge(
         base: base_commit,
         merge: merge_commit,
     };
-    merge_commits(repo, &commits).await
+    merge_commits(repo, &commits, strategy).await
 }
 
 pub async fn merge_commit_into_base(
@@ -255,7 +338,7 @@ pub async fn merge_commit_into_base(
         merge: merge_commit.to_owned(),
     };
 
-    merge_commits(repo, &commits).await
+    merge_commits(repo, &commits, None).await
 }
 
 pub async fn merge_commit_into_base_on_branch(
@@ -362,6 +445,7 @@ async fn merge_commits_on_branch(
         let mut shared_hashes = HashSet::new();
         let conflicts =
             find_merge_conflicts(repo, merge_commits, write_to_disk, &mut shared_hashes).await?;
+        let conflicts = resolve_conflicts_with_strategy(repo, conflicts, None).await?;
         log::debug!("Got {} conflicts", conflicts.len());
 
         if conflicts.is_empty() {
@@ -523,6 +607,10 @@ fn r_ff_merge_commit(
         EMerkleTreeNode::File(merge_file_node) => {
             let file_path = path.join(merge_file_node.name());
             seen_files.insert(file_path.clone());
+            // Files outside of a sparse checkout are not in the working tree
+            if !repo.is_in_subtree_paths(&file_path) {
+                return Ok(());
+            }
             // log::debug!("r_ff_merge_commit file_path {:?}", file_path);
             // log::debug!("merge_node {}", merge_node);
             // log::debug!("merge_file_node {}", merge_file_node);
@@ -697,6 +785,7 @@ fn r_ff_base_dir(
 async fn merge_commits(
     repo: &LocalRepository,
     merge_commits: &MergeCommits,
+    strategy: Option<MergeStrategy>,
 ) -> Result<Option<Commit>, OxenError> {
     // User output
     println!(
@@ -730,6 +819,7 @@ async fn merge_commits(
         let mut shared_hashes = HashSet::new();
         let conflicts =
             find_merge_conflicts(repo, merge_commits, write_to_disk, &mut shared_hashes).await?;
+        let conflicts = resolve_conflicts_with_strategy(repo, conflicts, strategy).await?;
 
         if !conflicts.is_empty() {
             println!(
@@ -903,6 +993,8 @@ pub async fn find_merge_conflicts(
     let mut conflicts: Vec<NodeMergeConflict> = vec![];
     let mut entries_to_restore: Vec<FileToRestore> = vec![];
     let mut cannot_overwrite_entries: Vec<PathBuf> = vec![];
+    // Tabular files that were merged row by row, written after the other entries are restored
+    let mut merged_dfs: Vec<(PathBuf, DataFrame)> = vec![];
 
     // Read all the entries from each commit into sets we can compare to one another
     let mut lca_hashes = HashSet::new();
@@ -1004,20 +1096,80 @@ pub async fn find_merge_conflicts(
                     && lca_file_node.hash() != merge_file_node.hash()
                     && base_file_node.hash() != merge_file_node.hash()
                 {
-                    conflicts.push(NodeMergeConflict {
-                        lca_entry: (lca_file_node.to_owned(), entry_path.to_path_buf()),
-                        base_entry: (base_file_node.to_owned(), entry_path.to_path_buf()),
-                        merge_entry: (merge_file_node.to_owned(), entry_path.to_path_buf()),
-                    });
+                    // Tabular files can often be merged row by row
+                    let row_conflicts = match try_tabular_merge(
+                        repo,
+                        entry_path,
+                        Some(lca_file_node),
+                        base_file_node,
+                        merge_file_node,
+                    ) {
+                        Some(result) => {
+                            if write_to_disk {
+                                if restore::should_restore_file(
+                                    repo,
+                                    Some(base_file_node.clone()),
+                                    merge_file_node,
+                                    entry_path,
+                                )? {
+                                    merged_dfs.push((entry_path.clone(), result.df));
+                                } else {
+                                    cannot_overwrite_entries.push(entry_path.clone());
+                                }
+                            }
+                            Some(result.conflicts)
+                        }
+                        None => None,
+                    };
+
+                    match row_conflicts {
+                        Some(row_conflicts) if row_conflicts.is_empty() => {}
+                        row_conflicts => conflicts.push(NodeMergeConflict {
+                            lca_entry: (lca_file_node.to_owned(), entry_path.to_path_buf()),
+                            base_entry: (base_file_node.to_owned(), entry_path.to_path_buf()),
+                            merge_entry: (merge_file_node.to_owned(), entry_path.to_path_buf()),
+                            row_conflicts: row_conflicts.unwrap_or_default(),
+                        }),
+                    }
                 }
             } else {
                 // merge entry doesn't exist in LCA, so just check if it's different from base
                 if base_file_node.hash() != merge_file_node.hash() {
-                    conflicts.push(NodeMergeConflict {
-                        lca_entry: (base_file_node.to_owned(), entry_path.to_path_buf()),
-                        base_entry: (base_file_node.to_owned(), entry_path.to_path_buf()),
-                        merge_entry: (merge_file_node.to_owned(), entry_path.to_path_buf()),
-                    });
+                    // Added on both sides, tabular files can still be merged with no ancestor
+                    let row_conflicts = match try_tabular_merge(
+                        repo,
+                        entry_path,
+                        None,
+                        base_file_node,
+                        merge_file_node,
+                    ) {
+                        Some(result) => {
+                            if write_to_disk {
+                                if restore::should_restore_file(
+                                    repo,
+                                    Some(base_file_node.clone()),
+                                    merge_file_node,
+                                    entry_path,
+                                )? {
+                                    merged_dfs.push((entry_path.clone(), result.df));
+                                } else {
+                                    cannot_overwrite_entries.push(entry_path.clone());
+                                }
+                            }
+                            Some(result.conflicts)
+                        }
+                        None => None,
+                    };
+
+                    match row_conflicts {
+                        Some(row_conflicts) if row_conflicts.is_empty() => {}
+                        row_conflicts => conflicts.push(NodeMergeConflict {
+                            lca_entry: (base_file_node.to_owned(), entry_path.to_path_buf()),
+                            base_entry: (base_file_node.to_owned(), entry_path.to_path_buf()),
+                            merge_entry: (merge_file_node.to_owned(), entry_path.to_path_buf()),
+                            row_conflicts: row_conflicts.unwrap_or_default(),
+                        }),
+                    }
                 }
             }
         } else if write_to_disk {
@@ -1041,6 +1193,9 @@ pub async fn find_merge_conflicts(
         for entry in entries_to_restore.iter() {
             restore::restore_file(repo, &entry.file_node, &entry.path, &version_store).await?;
         }
+        for (path, df) in merged_dfs.iter_mut() {
+            tabular::write_df(df, repo.path.join(path))?;
+        }
     } else {
         // If there are conflicts, return an error without restoring anything
         return Err(OxenError::cannot_overwrite_files(&cannot_overwrite_entries));
@@ -1048,3 +1203,257 @@ pub async fn find_merge_conflicts(
 
     Ok(conflicts)
 }
+
+/// Resolves conflicts with the strategy passed in, or else the strategy configured for the path.
+/// Returns the conflicts that are left for the user to resolve.
+async fn resolve_conflicts_with_strategy(
+    repo: &LocalRepository,
+    conflicts: Vec<NodeMergeConflict>,
+    strategy: Option<MergeStrategy>,
+) -> Result<Vec<NodeMergeConflict>, OxenError> {
+    let merge_config = repo.merge_config();
+    let mut remaining: Vec<NodeMergeConflict> = vec![];
+    for conflict in conflicts {
+        let path = &conflict.base_entry.1;
+        // A strategy passed in takes precedence over the drivers configured per path. If the
+        // driver fails the configured strategy gets a go.
+        if let (None, Some(driver)) = (strategy, merge_config.driver(path)) {
+            log::debug!("resolving conflict {:?} with driver {}", path, driver);
+            if resolve_with_driver(repo, &conflict, &driver)? {
+                continue;
+            }
+            println!("Merge driver {:?} could not resolve {:?}", driver, path);
+        }
+
+        let Some(strategy) = strategy.or_else(|| merge_config.strategy(path)) else {
+            remaining.push(conflict);
+            continue;
+        };
+
+        log::debug!("resolving conflict {:?} with strategy {}", path, strategy);
+        let resolved = match strategy {
+            // Our version, or the row level merge that favors our rows, is already on disk
+            MergeStrategy::Ours => true,
+            MergeStrategy::Theirs => resolve_theirs(repo, &conflict).await?,
+            MergeStrategy::Union => resolve_union(repo, &conflict)?,
+        };
+
+        if !resolved {
+            println!("Could not resolve {:?} with strategy {}", path, strategy);
+            remaining.push(conflict);
+        }
+    }
+    Ok(remaining)
+}
+
+async fn resolve_ours(
+    repo: &LocalRepository,
+    conflict: &NodeMergeConflict,
+) -> Result<bool, OxenError> {
+    let (base, path) = &conflict.base_entry;
+    let (merge, _) = &conflict.merge_entry;
+
+    if conflict.row_conflicts.is_empty() {
+        let version_store = repo.version_store()?;
+        restore::restore_file(repo, base, path, &version_store).await?;
+    } else {
+        // The row level merge that favors our rows
+        let mut result =
+            tabular_merge::merge_file_nodes(repo, path, conflict_lca(conflict), base, merge)?;
+        tabular::write_df(&mut result.df, repo.path.join(path))?;
+    }
+    Ok(true)
+}
+
+async fn resolve_theirs(
+    repo: &LocalRepository,
+    conflict: &NodeMergeConflict,
+) -> Result<bool, OxenError> {
+    let (base, path) = &conflict.base_entry;
+    let (merge, _) = &conflict.merge_entry;
+
+    if conflict.row_conflicts.is_empty() {
+        if !restore::should_restore_file(repo, Some(base.clone()), merge, path)? {
+            return Ok(false);
+        }
+        let version_store = repo.version_store()?;
+        restore::restore_file(repo, merge, path, &version_store).await?;
+    } else {
+        // Merge again with the sides swapped, so their rows win the conflicts
+        let mut result =
+            tabular_merge::merge_file_nodes(repo, path, conflict_lca(conflict), merge, base)?;
+        tabular::write_df(&mut result.df, repo.path.join(path))?;
+    }
+    Ok(true)
+}
+
+fn resolve_union(repo: &LocalRepository, conflict: &NodeMergeConflict) -> Result<bool, OxenError> {
+    let (base, path) = &conflict.base_entry;
+    let (merge, _) = &conflict.merge_entry;
+    let output_path = repo.path.join(path);
+
+    if conflict.row_conflicts.is_empty()
+        && !restore::should_restore_file(repo, Some(base.clone()), merge, path)?
+    {
+        return Ok(false);
+    }
+
+    match base.data_type() {
+        EntryDataType::Tabular => {
+            let (ours, theirs) = if conflict.row_conflicts.is_empty() {
+                (
+                    tabular_merge::read_file_node(repo, base)?,
+                    tabular_merge::read_file_node(repo, merge)?,
+                )
+            } else {
+                // Combine the row level merges that favor each side, keeping both versions of the conflicting rows
+                let ours = tabular::read_df(&output_path, DFOpts::empty())?;
+                let theirs = tabular_merge::merge_file_nodes(
+                    repo,
+                    path,
+                    conflict_lca(conflict),
+                    merge,
+                    base,
+                )?
+                .df
+                .select(ours.get_column_names_owned())?;
+                (ours, theirs)
+            };
+
+            let Ok(combined) = ours.vstack(&theirs) else {
+                log::debug!("Could not union {:?}, the schemas do not match", path);
+                return Ok(false);
+            };
+            let mut combined = combined.unique_stable(None, UniqueKeepStrategy::First, None)?;
+            tabular::write_df(&mut combined, &output_path)?;
+            Ok(true)
+        }
+        EntryDataType::Text => {
+            // Our lines, followed by the lines only they have
+            let version_store = repo.version_store()?;
+            let ours = util::fs::read_from_path(
+                version_store.get_version_path(&base.hash().to_string())?,
+            )?;
+            let theirs = util::fs::read_from_path(
+                version_store.get_version_path(&merge.hash().to_string())?,
+            )?;
+
+            let our_lines: HashSet<&str> = ours.lines().collect();
+            let mut lines: Vec<&str> = ours.lines().collect();
+            lines.extend(theirs.lines().filter(|line| !our_lines.contains(line)));
+
+            let mut contents = lines.join("\n");
+            contents.push('\n');
+            util::fs::write_to_path(&output_path, contents)?;
+            Ok(true)
+        }
+        _ => Ok(false),
+    }
+}
+
+/// Runs an external merge driver on copies of the ancestor, our and their version of the file,
+/// taking the file it leaves in place of ours if it succeeds. See `MergeConfig` for the
+/// placeholders the command can use.
+fn resolve_with_driver(
+    repo: &LocalRepository,
+    conflict: &NodeMergeConflict,
+    driver: &str,
+) -> Result<bool, OxenError> {
+    let (base, path) = &conflict.base_entry;
+    let (merge, _) = &conflict.merge_entry;
+
+    if conflict.row_conflicts.is_empty()
+        && !restore::should_restore_file(repo, Some(base.clone()), merge, path)?
+    {
+        return Ok(false);
+    }
+
+    let version_store = repo.version_store()?;
+    let tmp_dir = tempfile::tempdir()?;
+    let ancestor_path = tmp_dir.path().join("ancestor");
+    let ours_path = tmp_dir.path().join("ours");
+    let theirs_path = tmp_dir.path().join("theirs");
+    match conflict_lca(conflict) {
+        Some(lca) => util::fs::copy(
+            version_store.get_version_path(&lca.hash().to_string())?,
+            &ancestor_path,
+        )?,
+        // Added on both sides, so the ancestor is empty
+        None => util::fs::write_to_path(&ancestor_path, "")?,
+    }
+    util::fs::copy(
+        version_store.get_version_path(&base.hash().to_string())?,
+        &ours_path,
+    )?;
+    util::fs::copy(
+        version_store.get_version_path(&merge.hash().to_string())?,
+        &theirs_path,
+    )?;
+
+    let command = driver
+        .replace("%O", &shell_quote(&ancestor_path))
+        .replace("%A", &shell_quote(&ours_path))
+        .replace("%B", &shell_quote(&theirs_path))
+        .replace("%P", &shell_quote(path));
+    let status = shell_command(&command).current_dir(&repo.path).status()?;
+    if !status.success() {
+        log::debug!("merge driver {:?} exited with {}", command, status);
+        return Ok(false);
+    }
+
+    util::fs::copy(&ours_path, repo.path.join(path))?;
+    Ok(true)
+}
+
+fn shell_command(command: &str) -> std::process::Command {
+    if cfg!(windows) {
+        let mut cmd = std::process::Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    } else {
+        let mut cmd = std::process::Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    }
+}
+
+fn shell_quote(path: &Path) -> String {
+    let path = path.to_string_lossy();
+    if cfg!(windows) {
+        format!("\"{path}\"")
+    } else {
+        format!("'{}'", path.replace('\'', "'\\''"))
+    }
+}
+
+/// Files added on both sides store the base node as the LCA, since there is no common ancestor
+fn conflict_lca(conflict: &NodeMergeConflict) -> Option<&FileNode> {
+    let (lca, _) = &conflict.lca_entry;
+    let (base, _) = &conflict.base_entry;
+    if lca.hash() == base.hash() {
+        None
+    } else {
+        Some(lca)
+    }
+}
+
+/// Attempts a row level merge of a tabular file, returns None if the file has to be resolved as a whole
+fn try_tabular_merge(
+    repo: &LocalRepository,
+    path: &Path,
+    lca: Option<&FileNode>,
+    base: &FileNode,
+    merge: &FileNode,
+) -> Option<TabularMergeResult> {
+    if *base.data_type() != EntryDataType::Tabular || *merge.data_type() != EntryDataType::Tabular {
+        return None;
+    }
+
+    match tabular_merge::merge_file_nodes(repo, path, lca, base, merge) {
+        Ok(result) => Some(result),
+        Err(err) => {
+            log::debug!("Could not merge {:?} row by row: {}", path, err);
+            None
+        }
+    }
+}
@@ -24,7 +24,7 @@ pub fn get_cli(
         .file_name()
         .ok_or(OxenError::file_has_no_name(path))?;
     let size = repositories::metadata::get_file_size(path)?;
-    let hash = util::hasher::hash_file_contents(path)?;
+    let hash = format!("{:x}", repo.hash_algorithm().hash_file_contents(path)?);
     let mime_type = util::fs::file_mime_type(path);
     let data_type = util::fs::datatype_from_mimetype(path, mime_type.as_str());
     let extension = util::fs::file_extension(path);
@@ -1,9 +1,10 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use time::OffsetDateTime;
 
 use crate::{
     core::versions::MinOxenVersion,
-    model::{merkle_tree::node::commit_node::TCommitNode, MerkleHash, MerkleTreeNodeType},
+    model::{merkle_tree::node::commit_node::TCommitNode, MerkleHash, MerkleTreeNodeType, User},
 };
 
 #[derive(Deserialize, Serialize, Clone, PartialEq, Eq)]
@@ -15,6 +16,24 @@ pub struct CommitNodeData {
     pub author: String,
     pub email: String,
     pub timestamp: OffsetDateTime,
+    // Nodes are serialized positionally, so optional fields live together in one trailing
+    // struct that is skipped when empty, keeping commits without them byte for byte the same
+    #[serde(default, skip_serializing_if = "CommitNodeExtra::is_empty")]
+    pub extra: CommitNodeExtra,
+}
+
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug, Default)]
+pub struct CommitNodeExtra {
+    #[serde(default)]
+    pub metadata: BTreeMap<String, String>,
+    #[serde(default)]
+    pub co_authors: Vec<User>,
+}
+
+impl CommitNodeExtra {
+    pub fn is_empty(&self) -> bool {
+        self.metadata.is_empty() && self.co_authors.is_empty()
+    }
 }
 
 impl TCommitNode for CommitNodeData {
@@ -49,4 +68,12 @@ impl TCommitNode for CommitNodeData {
     fn timestamp(&self) -> &OffsetDateTime {
         &self.timestamp
     }
+
+    fn metadata(&self) -> Option<&BTreeMap<String, String>> {
+        Some(&self.extra.metadata)
+    }
+
+    fn co_authors(&self) -> &[User] {
+        &self.extra.co_authors
+    }
 }
@@ -44,6 +44,10 @@ pub struct FileNodeData {
 
     pub chunk_type: FileChunkType, // How the data is stored on disk
     pub storage_backend: FileStorageType, // Where the file is stored in the backend
+
+    // Unix mode of the file, 0 for nodes written before modes were tracked
+    #[serde(default)]
+    pub mode: u32,
 }
 
 impl TFileNode for FileNodeData {
@@ -59,6 +63,10 @@ impl TFileNode for FileNodeData {
         &self.hash
     }
 
+    fn set_hash(&mut self, hash: &MerkleHash) {
+        self.hash = *hash;
+    }
+
     fn name(&self) -> &str {
         &self.name
     }
@@ -142,4 +150,12 @@ impl TFileNode for FileNodeData {
     fn storage_backend(&self) -> &FileStorageType {
         &self.storage_backend
     }
+
+    fn mode(&self) -> u32 {
+        self.mode
+    }
+
+    fn set_mode(&mut self, mode: u32) {
+        self.mode = mode;
+    }
 }
@@ -2,14 +2,18 @@ use futures::prelude::*;
 use std::collections::HashSet;
 use std::io::{BufReader, Read};
 use std::path::PathBuf;
+use std::str::FromStr;
 use std::sync::Arc;
 use tokio::time::Duration;
 
 use crate::api::client::commits::ChunkParams;
-use crate::constants::AVG_CHUNK_SIZE;
 use crate::constants::DEFAULT_REMOTE_NAME;
+use crate::constants::{AVG_CHUNK_SIZE, RESUMABLE_UPLOAD_THRESHOLD};
+use crate::core;
+use crate::core::progress::pull_progress::PullProgress;
 use crate::core::progress::push_progress::PushProgress;
 use crate::core::v_latest::index::CommitMerkleTree;
+use crate::core::v_latest::push_state::PushState;
 use crate::error::OxenError;
 use crate::model::entry::commit_entry::Entry;
 use crate::model::merkle_tree::node::{EMerkleTreeNode, MerkleTreeNode};
@@ -76,6 +80,11 @@ async fn push_local_branch_to_remote_repo(
         ));
     };
 
+    // A shallow clone can only push on top of history the remote already has
+    if repo.has_shallow_history()? {
+        deepen_for_push(repo, remote_repo).await?;
+    }
+
     // Notify the server that we are starting a push
     api::client::repositories::pre_push(remote_repo, local_branch, &commit.id).await?;
 
@@ -93,6 +102,39 @@ async fn push_local_branch_to_remote_repo(
     Ok(())
 }
 
+/// If the remote is missing a commit on the shallow boundary, such as a new remote for a
+/// shallow clone, download the rest of the history from the origin so it can be pushed too
+async fn deepen_for_push(
+    repo: &LocalRepository,
+    remote_repo: &RemoteRepository,
+) -> Result<(), OxenError> {
+    let mut remote_has_boundary = true;
+    for commit_id in repo.shallow_boundary()? {
+        if !api::client::tree::has_node(remote_repo, MerkleHash::from_str(&commit_id)?).await? {
+            remote_has_boundary = false;
+            break;
+        }
+    }
+    if remote_has_boundary {
+        return Ok(());
+    }
+
+    let origin = repo
+        .get_remote(DEFAULT_REMOTE_NAME)
+        .ok_or(OxenError::remote_not_set(DEFAULT_REMOTE_NAME))?;
+    let Some(origin_repo) = api::client::repositories::get_by_remote(&origin).await? else {
+        return Err(OxenError::remote_repo_not_found(&origin.url));
+    };
+    println!(
+        "🐂 remote is missing history this shallow clone left out, fetching it from {}",
+        origin.url
+    );
+    let pull_progress = Arc::new(PullProgress::new());
+    core::v_latest::fetch::unshallow(repo, &origin_repo, &pull_progress).await?;
+    pull_progress.finish();
+    Ok(())
+}
+
 async fn push_to_new_branch(
     repo: &LocalRepository,
     remote_repo: &RemoteRepository,
@@ -176,8 +218,15 @@ async fn push_to_existing_branch(
 
     push_commits(repo, remote_repo, Some(latest_remote_commit), &commits).await?;
 
-    // Update the remote branch to point to the latest commit
-    api::client::branches::update(remote_repo, &remote_branch.name, commit).await?;
+    // Update the remote branch to point to the latest commit, unless another push moved it
+    // while we were uploading
+    api::client::branches::compare_and_update(
+        remote_repo,
+        &remote_branch.name,
+        &remote_branch.commit_id,
+        commit,
+    )
+    .await?;
 
     Ok(())
 }
@@ -334,10 +383,18 @@ async fn push_commits(
     ));
     log::debug!("pushing {} entries", missing_files.len());
     let commit = &history.last().unwrap();
-    push_entries(repo, remote_repo, &missing_files, commit, &progress).await?;
+
+    // Pick up where a failed push of the same commit left off
+    let state = Arc::new(PushState::load(repo, remote_repo.url(), &commit.id)?);
+    let num_uploaded_chunks = state.num_uploaded_chunks();
+    if num_uploaded_chunks > 0 {
+        println!("🐂 resuming push, {num_uploaded_chunks} chunks were already uploaded");
+    }
+    push_entries(repo, remote_repo, &missing_files, commit, &state, &progress).await?;
 
     // Mark commits as synced on the server
     api::client::commits::mark_commits_as_synced(remote_repo, missing_commit_hashes).await?;
+    state.clear()?;
 
     // Mark dirs/vnodes as synced on the server
     // TODO
@@ -352,6 +409,7 @@ pub async fn push_entries(
     remote_repo: &RemoteRepository,
     entries: &[Entry],
     commit: &Commit,
+    state: &Arc<PushState>,
     progress: &Arc<PushProgress>,
 ) -> Result<(), OxenError> {
     log::debug!(
@@ -383,6 +441,7 @@ pub async fn push_entries(
         larger_entries,
         commit,
         AVG_CHUNK_SIZE,
+        state,
         progress,
     );
     let small_entries_sync = bundle_and_send_small_entries(
@@ -417,6 +476,7 @@ async fn chunk_and_send_large_entries(
     entries: Vec<Entry>,
     commit: &Commit,
     chunk_size: u64,
+    state: &Arc<PushState>,
     progress: &Arc<PushProgress>,
 ) -> Result<(), OxenError> {
     if entries.is_empty() {
@@ -447,6 +507,9 @@ async fn chunk_and_send_large_entries(
         queue.try_push(entry.to_owned()).unwrap();
         finished_queue.try_push(false).unwrap();
     }
+    // Failed files are reported once every worker is done, the chunks that made it are
+    // in the push state for the next attempt
+    let errors: Arc<parking_lot::Mutex<Vec<String>>> = Arc::new(parking_lot::Mutex::new(vec![]));
 
     let worker_count = concurrency::num_threads_for_items(entries.len());
     log::debug!(
@@ -457,13 +520,21 @@ async fn chunk_and_send_large_entries(
     for worker in 0..worker_count {
         let queue = queue.clone();
         let finished_queue = finished_queue.clone();
+        let errors = errors.clone();
+        let state = Arc::clone(state);
         let bar = Arc::clone(progress);
         tokio::spawn(async move {
             loop {
                 let (entry, repo, commit, remote_repo) = queue.pop().await;
                 log::debug!("worker[{}] processing task...", worker);
 
-                upload_large_file_chunks(entry, repo, commit, remote_repo, chunk_size, &bar).await;
+                if let Err(err) =
+                    upload_large_file(&entry, repo, commit, remote_repo, chunk_size, &state, &bar)
+                        .await
+                {
+                    log::error!("Error uploading {:?}: {}", entry.path(), err);
+                    errors.lock().push(format!("{:?}: {}", entry.path(), err));
+                }
 
                 finished_queue.pop().await;
             }
@@ -479,31 +550,85 @@ async fn chunk_and_send_large_entries(
     // Sleep again to let things sync...
     sleep(Duration::from_millis(100)).await;
 
+    let errors = errors.lock();
+    if !errors.is_empty() {
+        return Err(OxenError::basic_str(format!(
+            "{} files failed to upload, run `oxen push` again to resume\n{}",
+            errors.len(),
+            errors.join("\n")
+        )));
+    }
     Ok(())
 }
 
-/// Chunk and send large file in parallel
+/// Send a large file through a resumable upload session if it is big enough to be worth
+/// resuming, the server keeps what it received when a push is interrupted. Servers without
+/// resumable uploads get the file in chunks.
+async fn upload_large_file(
+    entry: &Entry,
+    repo: LocalRepository,
+    commit: Commit,
+    remote_repo: RemoteRepository,
+    chunk_size: u64,
+    state: &Arc<PushState>,
+    progress: &Arc<PushProgress>,
+) -> Result<(), OxenError> {
+    if entry.num_bytes() > RESUMABLE_UPLOAD_THRESHOLD {
+        let version_store = repo.version_store()?;
+        let mut reader = version_store.open_version(&entry.hash())?;
+        let uploaded = api::client::uploads::upload_version(
+            &remote_repo,
+            entry.hash(),
+            entry.num_bytes(),
+            &mut reader,
+            |bytes| progress.add_bytes(bytes),
+        )
+        .await?;
+        if uploaded {
+            return Ok(());
+        }
+        log::debug!(
+            "remote does not support resumable uploads, sending {:?} in chunks",
+            entry.path()
+        );
+    }
+
+    upload_large_file_chunks(
+        entry,
+        repo,
+        commit,
+        remote_repo,
+        chunk_size,
+        state,
+        progress,
+    )
+    .await
+}
+
+/// Chunk and send large file in parallel, skipping the chunks the push state says the
+/// server already acknowledged
 async fn upload_large_file_chunks(
-    entry: Entry,
+    entry: &Entry,
     repo: LocalRepository,
     commit: Commit,
     remote_repo: RemoteRepository,
     chunk_size: u64,
+    state: &Arc<PushState>,
     progress: &Arc<PushProgress>,
-) {
+) -> Result<(), OxenError> {
     // Open versioned file
-    let version_store = repo.version_store().unwrap();
-    let file = version_store.open_version(&entry.hash()).unwrap();
+    let version_store = repo.version_store()?;
+    let file = version_store.open_version(&entry.hash())?;
     let mut reader = BufReader::new(file);
     // The version path is just being used for compatibility with the server endpoint,
     // we aren't using it to read the file.
     // TODO: This should be migrated to use the new versions API
-    let version_path = util::fs::version_path_for_entry(&repo, &entry);
+    let version_path = util::fs::version_path_for_entry(&repo, entry);
 
     // These variables are the same for every chunk
     // let is_compressed = false;
     let hidden_dir = util::fs::oxen_hidden_dir(&repo.path);
-    let path = util::fs::path_relative_to_dir(&version_path, &hidden_dir).unwrap();
+    let path = util::fs::path_relative_to_dir(&version_path, &hidden_dir)?;
     let file_name = Some(String::from(path.to_str().unwrap()));
 
     // Calculate chunk sizes
@@ -513,17 +638,11 @@ async fn upload_large_file_chunks(
     let mut chunk_size = chunk_size;
 
     // Create a client for uploading chunks
-    let client = Arc::new(
-        api::client::builder_for_remote_repo(&remote_repo)
-            .unwrap()
-            .build()
-            .unwrap(),
-    );
+    let client = Arc::new(api::client::builder_for_remote_repo(&remote_repo)?.build()?);
 
     // Create queues for sending data to workers
     type PieceOfWork = (
         Vec<u8>,
-        u64,   // chunk size
         usize, // chunk num
         usize, // total chunks
         u64,   // total size
@@ -540,7 +659,6 @@ async fn upload_large_file_chunks(
     let sub_chunk_size = concurrency::num_threads_for_items(total_chunks);
 
     let mut total_chunk_idx = 0;
-    let mut processed_chunk_idx = 0;
     let num_sub_chunks = (total_chunks / sub_chunk_size) + 1;
     log::debug!(
         "upload_large_file_chunks {:?} processing file in {} subchunks of size {} from total {} chunk size {} file size {}",
@@ -556,7 +674,7 @@ async fn upload_large_file_chunks(
             "upload_large_file_chunks Start reading subchunk {i}/{num_sub_chunks} of size {sub_chunk_size} from total {total_chunks} chunk size {chunk_size} file size {total_bytes_read}/{total_bytes}"
         );
         // Read and send the subset of buffers sequentially
-        let mut sub_buffers: Vec<Vec<u8>> = Vec::new();
+        let mut sub_buffers: Vec<(usize, Vec<u8>)> = Vec::new();
         for _ in 0..sub_chunk_size {
             // If we have read all the bytes, break
             if total_bytes_read >= total_bytes {
@@ -568,26 +686,32 @@ async fn upload_large_file_chunks(
                 chunk_size = total_bytes % chunk_size;
             }
 
+            let chunk_num = total_chunk_idx;
+            total_bytes_read += chunk_size;
+            total_chunk_idx += 1;
+
+            // Acknowledged by the server on an earlier attempt
+            if state.is_chunk_uploaded(&entry.hash(), chunk_num) {
+                reader.seek_relative(chunk_size as i64)?;
+                progress.add_bytes(chunk_size);
+                continue;
+            }
+
             let percent_read = (total_bytes_read as f64 / total_bytes as f64) * 100.0;
             log::debug!("upload_large_file_chunks has read {total_bytes_read}/{total_bytes} = {percent_read}% about to read {chunk_size}");
 
             // Only read as much as you need to send so we don't blow up memory on large files
             let mut buffer = vec![0u8; chunk_size as usize];
-            match reader.read_exact(&mut buffer) {
-                Ok(_) => {}
-                Err(err) => {
-                    log::error!("upload_large_file_chunks Error reading file {:?} chunk {total_chunk_idx}/{total_chunks} chunk size {chunk_size} total_bytes_read: {total_bytes_read} total_bytes: {total_bytes} {:?}", entry.path(), err);
-                    return;
-                }
+            if let Err(err) = reader.read_exact(&mut buffer) {
+                log::error!("upload_large_file_chunks Error reading file {:?} chunk {chunk_num}/{total_chunks} chunk size {chunk_size} total_bytes_read: {total_bytes_read} total_bytes: {total_bytes} {:?}", entry.path(), err);
+                return Err(err.into());
             }
-            total_bytes_read += chunk_size;
-            total_chunk_idx += 1;
 
-            sub_buffers.push(buffer);
+            sub_buffers.push((chunk_num, buffer));
         }
         log::debug!(
             "upload_large_file_chunks Done, have read subchunk {}/{} subchunk {}/{} of size {}",
-            processed_chunk_idx,
+            total_chunk_idx,
             total_chunks,
             i,
             num_sub_chunks,
@@ -595,14 +719,11 @@ async fn upload_large_file_chunks(
         );
 
         // Then send sub_buffers over network in parallel
-        // let queue = Arc::new(TaskQueue::new(sub_buffers.len()));
-        // let finished_queue = Arc::new(FinishedTaskQueue::new(sub_buffers.len()));
         let mut tasks: Vec<PieceOfWork> = Vec::new();
-        for buffer in sub_buffers.iter() {
+        for (chunk_num, buffer) in sub_buffers.into_iter() {
             tasks.push((
-                buffer.to_owned(),
-                chunk_size,
-                processed_chunk_idx, // Needs to be the overall chunk num
+                buffer,
+                chunk_num, // Needs to be the overall chunk num
                 total_chunks,
                 total_bytes,
                 client.clone(),
@@ -611,8 +732,6 @@ async fn upload_large_file_chunks(
                 commit.to_owned(),
                 file_name.to_owned(),
             ));
-            // finished_queue.try_push(false).unwrap();
-            processed_chunk_idx += 1;
         }
 
         // Setup the stream chunks in parallel
@@ -620,7 +739,6 @@ async fn upload_large_file_chunks(
             .map(|item| async move {
                 let (
                     buffer,
-                    chunk_size,
                     chunk_num,
                     total_chunks,
                     total_size,
@@ -645,7 +763,7 @@ async fn upload_large_file_chunks(
                 };
 
                 let is_compressed = false;
-                match api::client::commits::upload_data_chunk_to_server_with_retry(
+                api::client::commits::upload_data_chunk_to_server_with_retry(
                     &client,
                     &remote_repo,
                     &buffer,
@@ -654,41 +772,39 @@ async fn upload_large_file_chunks(
                     is_compressed,
                     &file_name,
                 )
-                .await
-                {
-                    Ok(_) => {
-                        log::debug!(
-                            "upload_large_file_chunks Successfully uploaded subchunk overall chunk {}/{}",
-                            chunk_num,
-                            total_chunks
-                        );
-                        Ok(chunk_size)
-                    }
-                    Err(err) => {
-                        log::error!("Error uploading chunk: {err}");
-                        Err(err)
-                    }
-                }
+                .await?;
+                log::debug!(
+                    "upload_large_file_chunks Successfully uploaded subchunk overall chunk {}/{}",
+                    chunk_num,
+                    total_chunks
+                );
+                Ok::<(usize, u64), OxenError>((chunk_num, size))
             })
             .buffer_unordered(sub_chunk_size);
 
-        // Wait for all requests to finish
-        bodies
-            .for_each(|b| async {
-                match b {
-                    Ok(_) => {
-                        progress.add_bytes(chunk_size);
-                    }
-                    Err(err) => {
-                        log::error!("Error uploading chunk: {err}")
-                    }
+        // Wait for all requests to finish, journaling each acknowledged chunk
+        let results: Vec<Result<(usize, u64), OxenError>> = bodies.collect().await;
+        let mut last_error = None;
+        for result in results {
+            match result {
+                Ok((chunk_num, size)) => {
+                    state.record_chunk(&entry.hash(), chunk_num)?;
+                    progress.add_bytes(size);
                 }
-            })
-            .await;
+                Err(err) => {
+                    log::error!("Error uploading chunk: {err}");
+                    last_error = Some(err);
+                }
+            }
+        }
+        if let Some(err) = last_error {
+            return Err(err);
+        }
 
         log::debug!("upload_large_file_chunks Subchunk {i}/{num_sub_chunks} tasks done. :-)");
     }
     progress.add_files(1);
+    Ok(())
 }
 
 /// Sends entries in tarballs of size ~chunk size
@@ -750,11 +866,13 @@ async fn bundle_and_send_small_entries(
         finished_queue.try_push(false).unwrap();
     }
 
-    // TODO: this needs some more robust error handling. What should we do if a single item fails?
-    // Currently no way to bubble up that error.
+    // Bundles that fail are reported once every worker is done, the server only lists
+    // what is still missing on the next push
+    let errors: Arc<parking_lot::Mutex<Vec<String>>> = Arc::new(parking_lot::Mutex::new(vec![]));
     for worker in 0..worker_count {
         let queue = queue.clone();
         let finished_queue = finished_queue.clone();
+        let errors = errors.clone();
         let bar = Arc::clone(progress);
         tokio::spawn(async move {
             loop {
@@ -765,7 +883,9 @@ async fn bundle_and_send_small_entries(
                     Ok(size) => size,
                     Err(e) => {
                         log::error!("Failed to compute entries size: {}", e);
-                        continue; // or break or decide on another error-handling strategy
+                        errors.lock().push(format!("{} files: {}", chunk.len(), e));
+                        finished_queue.pop().await;
+                        continue;
                     }
                 };
 
@@ -782,8 +902,8 @@ async fn bundle_and_send_small_entries(
                         log::debug!("Successfully uploaded data!")
                     }
                     Err(e) => {
-                        // TODO: Surface the error to the user
-                        log::error!("Error uploading chunk: {:?}", e)
+                        log::error!("Error uploading chunk: {:?}", e);
+                        errors.lock().push(format!("{} files: {}", chunk.len(), e));
                     }
                 }
 
@@ -802,6 +922,14 @@ async fn bundle_and_send_small_entries(
     // Sleep again to let things sync...
     sleep(Duration::from_millis(100)).await;
 
+    let errors = errors.lock();
+    if !errors.is_empty() {
+        return Err(OxenError::basic_str(format!(
+            "{} bundles failed to upload, run `oxen push` again to resume\n{}",
+            errors.len(),
+            errors.join("\n")
+        )));
+    }
     Ok(())
 }
 
@@ -0,0 +1,139 @@
+//! Journal of a push in progress, kept in `.oxen/push-state`. Every chunk of a large file
+//! the server acknowledges is recorded, so when a push dies part way through the next
+//! `oxen push` only sends the chunks the server does not have yet. The journal is removed
+//! once the push completes.
+//!
+
+use std::collections::{BTreeSet, HashMap};
+use std::path::PathBuf;
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+use crate::constants::PUSH_STATE_FILE;
+use crate::error::OxenError;
+use crate::model::LocalRepository;
+use crate::util;
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct PushStateData {
+    remote_url: String,
+    commit_id: String,
+    /// Chunk numbers the server acknowledged, by version file hash
+    uploaded_chunks: HashMap<String, BTreeSet<usize>>,
+}
+
+#[derive(Debug)]
+pub struct PushState {
+    path: PathBuf,
+    data: Mutex<PushStateData>,
+}
+
+impl PushState {
+    /// Load the journal for pushing `commit_id` to `remote_url`. If the last push that
+    /// failed was of something else, start over with an empty journal.
+    pub fn load(
+        repo: &LocalRepository,
+        remote_url: impl AsRef<str>,
+        commit_id: impl AsRef<str>,
+    ) -> Result<PushState, OxenError> {
+        let remote_url = remote_url.as_ref();
+        let commit_id = commit_id.as_ref();
+        let path = util::fs::oxen_hidden_dir(&repo.path).join(PUSH_STATE_FILE);
+
+        let previous: Option<PushStateData> = if path.exists() {
+            let data = util::fs::read_from_path(&path)?;
+            // A journal we can't read only costs re-uploading the chunks
+            serde_json::from_str(&data)
+                .map_err(|err| log::warn!("Ignoring unreadable push state {:?}: {}", path, err))
+                .ok()
+        } else {
+            None
+        };
+        let data = match previous {
+            Some(data) if data.remote_url == remote_url && data.commit_id == commit_id => data,
+            _ => PushStateData {
+                remote_url: remote_url.to_string(),
+                commit_id: commit_id.to_string(),
+                ..Default::default()
+            },
+        };
+
+        Ok(PushState {
+            path,
+            data: Mutex::new(data),
+        })
+    }
+
+    /// Number of chunks uploaded by earlier attempts at this push
+    pub fn num_uploaded_chunks(&self) -> usize {
+        self.data
+            .lock()
+            .uploaded_chunks
+            .values()
+            .map(BTreeSet::len)
+            .sum()
+    }
+
+    pub fn is_chunk_uploaded(&self, hash: &str, chunk_num: usize) -> bool {
+        self.data
+            .lock()
+            .uploaded_chunks
+            .get(hash)
+            .is_some_and(|chunks| chunks.contains(&chunk_num))
+    }
+
+    /// Record a chunk the server acknowledged and write the journal to disk
+    pub fn record_chunk(&self, hash: &str, chunk_num: usize) -> Result<(), OxenError> {
+        let mut data = self.data.lock();
+        data.uploaded_chunks
+            .entry(hash.to_string())
+            .or_default()
+            .insert(chunk_num);
+        // Write next to the journal and rename so a crash never leaves half a file
+        let tmp_path = self.path.with_extension("tmp");
+        util::fs::write_to_path(&tmp_path, serde_json::to_string(&*data)?)?;
+        util::fs::rename(&tmp_path, &self.path)
+    }
+
+    /// Remove the journal once the push is complete
+    pub fn clear(&self) -> Result<(), OxenError> {
+        if self.path.exists() {
+            util::fs::remove_file(&self.path)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PushState;
+    use crate::error::OxenError;
+    use crate::test;
+
+    #[test]
+    fn test_push_state_resumes_same_push_only() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            let url = "http://localhost:3000/ox/data";
+            let state = PushState::load(&repo, url, "abc")?;
+            state.record_chunk("hash", 0)?;
+            state.record_chunk("hash", 2)?;
+
+            // A new attempt at the same push sees the acknowledged chunks
+            let state = PushState::load(&repo, url, "abc")?;
+            assert_eq!(state.num_uploaded_chunks(), 2);
+            assert!(state.is_chunk_uploaded("hash", 2));
+            assert!(!state.is_chunk_uploaded("hash", 1));
+
+            // Pushing another commit starts over
+            let other = PushState::load(&repo, url, "def")?;
+            assert_eq!(other.num_uploaded_chunks(), 0);
+
+            state.clear()?;
+            let state = PushState::load(&repo, url, "abc")?;
+            assert_eq!(state.num_uploaded_chunks(), 0);
+
+            Ok(())
+        })
+    }
+}
@@ -1,5 +1,4 @@
 use crate::error::OxenError;
-use crate::util;
 use crate::{model::LocalRepository, repositories};
 use std::path::{Path, PathBuf};
 
@@ -17,6 +16,6 @@ pub fn get_version_file_from_commit_id(
     let file_node = repositories::tree::get_file_by_path(repo, &commit, path)?
         .ok_or(OxenError::entry_does_not_exist_in_commit(path, commit_id))?;
 
-    let version_path = util::fs::version_path_from_hash(repo, file_node.hash().to_string());
-    Ok(version_path)
+    let version_store = repo.version_store()?;
+    version_store.get_version_path(&file_node.hash().to_string())
 }
@@ -531,7 +531,7 @@ fn find_changes(
             // Either way, we know the directory is not all_untracked
             untracked.all_untracked = false;
             if let EMerkleTreeNode::File(file_node) = &node.node {
-                let is_modified = util::fs::is_modified_from_node(&path, file_node)?;
+                let is_modified = util::fs::is_modified_from_node(repo, &path, file_node)?;
                 log::debug!("is_modified {} {:?}", is_modified, relative_path);
                 if is_modified {
                     modified.insert(relative_path.clone());
@@ -545,7 +545,7 @@ fn find_changes(
             if let Some(search_node) = &search_node {
                 if let EMerkleTreeNode::File(file_node) = &search_node.node {
                     found_file = true;
-                    if util::fs::is_modified_from_node(&path, file_node)? {
+                    if util::fs::is_modified_from_node(repo, &path, file_node)? {
                         modified.insert(relative_path.clone());
                     }
                 }
@@ -576,7 +576,7 @@ fn find_changes(
     if let Some(dir_hash) = dir_hashes.get(search_node_path) {
         // if we have subtree paths, don't check for removed files that are outside of the subtree
         if let Some(subtree_paths) = repo.subtree_paths() {
-            if !subtree_paths.contains(&search_node_path.to_path_buf()) {
+            if !is_checked_out_subtree(repo, &subtree_paths, search_node_path) {
                 return Ok((untracked, modified, removed));
             }
 
@@ -631,6 +631,19 @@ fn find_changes(
     Ok((untracked, modified, removed))
 }
 
+/// Whether the files of a directory are in the working tree of a subtree clone or sparse checkout
+fn is_checked_out_subtree(
+    repo: &LocalRepository,
+    subtree_paths: &[PathBuf],
+    search_node_path: &Path,
+) -> bool {
+    if subtree_paths.contains(&search_node_path.to_path_buf()) {
+        return true;
+    }
+    // Without a depth limit, every directory below a subtree path is checked out too
+    repo.depth().is_none() && repo.is_in_subtree_paths(search_node_path)
+}
+
 fn find_local_changes(
     repo: &LocalRepository,
     opts: &StagedDataOpts,
@@ -723,7 +736,7 @@ fn find_local_changes(
             // Either way, we know the directory is not all_untracked
             untracked.all_untracked = false;
             if let EMerkleTreeNode::File(file_node) = &node.node {
-                let is_modified = util::fs::is_modified_from_node(&path, file_node)?;
+                let is_modified = util::fs::is_modified_from_node(repo, &path, file_node)?;
                 log::debug!("is_modified {} {:?}", is_modified, relative_path);
                 if is_modified {
                     modified.insert(relative_path.clone());
@@ -737,7 +750,7 @@ fn find_local_changes(
             if let Some(search_node) = &search_node {
                 if let EMerkleTreeNode::File(file_node) = &search_node.node {
                     found_file = true;
-                    if util::fs::is_modified_from_node(&path, file_node)? {
+                    if util::fs::is_modified_from_node(repo, &path, file_node)? {
                         modified.insert(relative_path.clone());
                     }
                 }
@@ -768,7 +781,7 @@ fn find_local_changes(
     if let Some(dir_hash) = dir_hashes.get(search_node_path) {
         // if we have subtree paths, don't check for removed files that are outside of the subtree
         if let Some(subtree_paths) = repo.subtree_paths() {
-            if !subtree_paths.contains(&search_node_path.to_path_buf()) {
+            if !is_checked_out_subtree(repo, &subtree_paths, search_node_path) {
                 return Ok((untracked, modified, removed));
             }
 
@@ -13,7 +13,7 @@ use crate::model::merkle_tree::node::{
 };
 use crate::model::metadata::generic_metadata::GenericMetadata;
 use crate::model::{
-    Branch, Commit, EntryDataType, MerkleHash, NewCommitBody, StagedEntryStatus, Workspace,
+    Branch, Commit, EntryDataType, MerkleHash, NewCommitBody, StagedEntryStatus, User, Workspace,
 };
 use crate::repositories;
 use crate::util;
@@ -26,6 +26,7 @@ pub fn commit(
     workspace: &Workspace,
     new_commit: &NewCommitBody,
     branch_name: impl AsRef<str>,
+    pusher: Option<&User>,
 ) -> Result<Commit, OxenError> {
     let branch_name = branch_name.as_ref();
     let repo = &workspace.base_repo;
@@ -63,6 +64,8 @@ pub fn commit(
         }
 
         let dir_entries = export_tabular_data_frames(workspace, dir_entries)?;
+        repositories::checks::run_on_staged_entries(&workspace.base_repo, &dir_entries)?
+            .into_result()?;
 
         repositories::commits::commit_writer::commit_dir_entries(
             &workspace.base_repo,
@@ -73,6 +76,10 @@ pub fn commit(
         )?
     };
 
+    // The commit moves the branch just like a push, so it has to follow the same rules
+    repositories::branches::protection::check_push(repo, &branch, &commit.id)?;
+    repositories::owners::check_push(repo, &branch, &commit, pusher)?;
+
     // Clear the staged db
     log::debug!("Removing staged_db_path: {staged_db_path:?}");
     remove_from_cache(&workspace.workspace_repo.path)?;
@@ -329,8 +336,12 @@ fn compute_staged_merkle_tree_node(
     // This logic is copied from add.rs but add has some optimizations that make it hard to be reused here
     let metadata = util::fs::metadata(path)?;
     let mtime = FileTime::from_last_modification_time(&metadata);
-    let hash = util::hasher::get_hash_given_metadata(path, &metadata)?;
+    let hash = workspace
+        .base_repo
+        .hash_algorithm()
+        .hash_file_contents(path)?;
     let num_bytes = metadata.len();
+    let mode = util::fs::file_mode(&metadata);
     let hash = MerkleHash::new(hash);
 
     // Get the data type of the file
@@ -358,7 +369,10 @@ fn compute_staged_merkle_tree_node(
     // Compute the metadata hash and combined hash
     let metadata_hash = util::hasher::get_metadata_hash(&metadata)?;
     let combined_hash = util::hasher::get_combined_hash(Some(metadata_hash), hash.to_u128())?;
-    let combined_hash = MerkleHash::new(combined_hash);
+    let combined_hash = MerkleHash::new(util::hasher::get_combined_hash_with_mode(
+        combined_hash,
+        mode,
+    ));
 
     // Copy the file to the versioned directory
     let dst_dir = util::fs::version_dir_from_hash(&workspace.base_repo.path, hash.to_string());
@@ -388,6 +402,7 @@ fn compute_staged_merkle_tree_node(
             metadata,
             mime_type: mime_type.clone(),
             extension: file_extension.to_string(),
+            mode,
         },
     )?;
 
@@ -8,6 +8,7 @@ use crate::model::diff::tabular_diff::{
 use crate::model::diff::{AddRemoveModifyCounts, DiffResult, TabularDiff};
 use crate::model::Workspace;
 use crate::repositories;
+use polars::frame::DataFrame;
 use std::path::Path;
 
 pub fn diff(workspace: &Workspace, path: impl AsRef<Path>) -> Result<DiffResult, OxenError> {
@@ -61,6 +62,7 @@ pub fn diff(workspace: &Workspace, path: impl AsRef<Path>) -> Result<DiffResult,
 
     let diff_result = TabularDiff {
         contents: diff_df,
+        modified_cells: DataFrame::empty(),
         parameters: TabularDiffParameters::empty(),
         summary: diff_summary,
         filename1: None,
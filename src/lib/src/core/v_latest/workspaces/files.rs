@@ -533,8 +533,12 @@ async fn p_add_file(
     }
 
     // See if this is a new file or a modified file
-    let file_status =
-        core::v_latest::add::determine_file_status(&maybe_dir_node, &file_name, &full_path)?;
+    let file_status = core::v_latest::add::determine_file_status(
+        base_repo,
+        &maybe_dir_node,
+        &file_name,
+        &full_path,
+    )?;
     log::debug!("File status: {file_status:?}");
     // Store the file in the version store using the hash as the key
     let hash_str = file_status.hash.to_string();
@@ -16,6 +16,7 @@ use crate::model::Schema;
 use crate::model::Workspace;
 use crate::model::{Commit, ParsedResource};
 use crate::model::{Remote, RepoNew};
+use crate::repositories::checks::ChecksReport;
 
 pub mod path_buf_error;
 pub mod string_error;
@@ -46,6 +47,7 @@ pub enum OxenError {
     LocalRepoNotFound(Box<PathBufError>),
     RepoAlreadyExists(Box<RepoNew>),
     RepoAlreadyExistsAtDestination(Box<StringError>),
+    RepoIsReadOnly(StringError),
 
     // Fork
     ForkStatusNotFound(StringError),
@@ -62,8 +64,13 @@ pub enum OxenError {
     RevisionNotFound(Box<StringError>),
     RootCommitDoesNotMatch(Box<Commit>),
     NothingToCommit(StringError),
+    InvalidCommitMessage(StringError),
+    ChecksFailed(Box<ChecksReport>),
     NoCommitsFound(StringError),
     HeadNotFound(StringError),
+    ProtectedBranch(StringError),
+    ApprovalRequired(StringError),
+    NonFastForward(StringError),
 
     // Workspaces
     WorkspaceNotFound(Box<StringError>),
@@ -142,7 +149,13 @@ pub enum OxenError {
 impl fmt::Display for OxenError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            OxenError::OxenUpdateRequired(err) | OxenError::Basic(err) => write!(f, "{}", err),
+            OxenError::OxenUpdateRequired(err)
+            | OxenError::InvalidCommitMessage(err)
+            | OxenError::RepoIsReadOnly(err)
+            | OxenError::NonFastForward(err)
+            | OxenError::ApprovalRequired(err)
+            | OxenError::Basic(err) => write!(f, "{}", err),
+            OxenError::ChecksFailed(report) => write!(f, "{}", report),
             _ => {
                 write!(f, "{:?}", self)
             }
@@ -222,6 +235,63 @@ impl OxenError {
         ))
     }
 
+    pub fn repo_is_read_only(repo: impl AsRef<str>) -> Self {
+        OxenError::RepoIsReadOnly(StringError::from(format!(
+            "Repository '{}' is read-only, it can be cloned, pulled and downloaded but not modified",
+            repo.as_ref()
+        )))
+    }
+
+    pub fn protected_branch(branch_name: impl AsRef<str>, reason: impl AsRef<str>) -> Self {
+        OxenError::ProtectedBranch(StringError::from(format!(
+            "Branch '{}' is protected: {}",
+            branch_name.as_ref(),
+            reason.as_ref()
+        )))
+    }
+
+    /// Changes to paths listed in `.oxen/owners` that none of their owners approved
+    pub fn approval_required(desc: impl AsRef<str>) -> Self {
+        OxenError::ApprovalRequired(StringError::from(desc.as_ref()))
+    }
+
+    /// Tabular files being added or committed failed the rules in `.oxen/checks.toml`
+    pub fn checks_failed(report: ChecksReport) -> Self {
+        OxenError::ChecksFailed(Box::new(report))
+    }
+
+    /// The branch moved between reading it and updating it, so the update would drop commits
+    pub fn non_fast_forward(
+        branch_name: impl AsRef<str>,
+        expected_commit_id: impl AsRef<str>,
+        commit_id: impl AsRef<str>,
+    ) -> Self {
+        OxenError::NonFastForward(StringError::from(format!(
+            "\nBranch '{}' is at {} but the push was based on {}, another push landed first. To fix run:\n\n  oxen pull\n\nThen push again.\n",
+            branch_name.as_ref(),
+            commit_id.as_ref(),
+            expected_commit_id.as_ref()
+        )))
+    }
+
+    /// The push would move the branch to a commit that doesn't build on its head
+    pub fn not_descendant(
+        branch_name: impl AsRef<str>,
+        commit_id: impl AsRef<str>,
+        new_commit_id: impl AsRef<str>,
+    ) -> Self {
+        OxenError::NonFastForward(StringError::from(format!(
+            "\nBranch '{}' is at {} which {} does not build on, the push would drop commits. To fix run:\n\n  oxen pull\n\nThen push again.\n",
+            branch_name.as_ref(),
+            commit_id.as_ref(),
+            new_commit_id.as_ref()
+        )))
+    }
+
+    pub fn invalid_commit_message(desc: impl AsRef<str>) -> Self {
+        OxenError::InvalidCommitMessage(StringError::from(desc.as_ref()))
+    }
+
     pub fn operation_cancelled() -> Self {
         OxenError::OperationCancelled(StringError::from("\nOperation cancelled.\n"))
     }
@@ -354,6 +424,10 @@ impl OxenError {
         OxenError::BranchNotFound(Box::new(StringError::from(err)))
     }
 
+    pub fn tag_not_found(name: impl AsRef<str>) -> OxenError {
+        OxenError::resource_not_found(format!("Tag '{}' not found", name.as_ref()))
+    }
+
     pub fn commit_db_corrupted(commit_id: impl AsRef<str>) -> OxenError {
         let err = format!(
             "Commit db corrupted, could not find commit: {}",
@@ -460,7 +534,7 @@ impl OxenError {
             .join("\n  ");
 
         OxenError::basic_str(format!(
-            "\nError: your local changes to the following files would be overwritten. Please commit the following changes before continuing:\n\n  {}\n",
+            "\nError: your local changes to the following files would be overwritten. Please commit or stash (`oxen stash`) the following changes before continuing:\n\n  {}\n",
             paths_str
         ))
     }
@@ -584,6 +658,12 @@ impl From<url::ParseError> for OxenError {
     }
 }
 
+impl From<zip::result::ZipError> for OxenError {
+    fn from(error: zip::result::ZipError) -> Self {
+        OxenError::basic_str(format!("Zip error: {error}"))
+    }
+}
+
 impl From<serde_json::Error> for OxenError {
     fn from(error: serde_json::Error) -> Self {
         OxenError::JSON(error)
@@ -1,6 +1,8 @@
 //! The structs and enums that are used to represent the data in the oxen library
 //!
 
+pub mod activity;
+pub mod append_buffer;
 pub mod base_head;
 pub mod branch;
 pub mod commit;
@@ -9,21 +11,29 @@ pub mod data_frame;
 pub mod diff;
 pub mod entry;
 pub mod file;
+pub mod job;
 pub mod merge_conflict;
+pub mod merge_proposal;
 pub mod merkle_tree;
 pub mod metadata;
+pub mod mirror;
 pub mod namespace;
 pub mod object_id;
 pub mod parsed_resource;
 pub mod partial_node;
+pub mod reflog_entry;
 pub mod remote;
 pub mod remote_branch;
 pub mod repository;
 pub mod staged_data;
 pub mod staged_dir_stats;
 pub mod staged_row_status;
+pub mod stash_entry;
 pub mod summarized_staged_dir_stats;
+pub mod tag;
+pub mod upload_session;
 pub mod user;
+pub mod webhook;
 pub mod workspace;
 
 // Namespace
@@ -35,13 +45,33 @@ pub use crate::model::repository::remote_repository::RemoteRepository;
 pub use crate::model::repository::repo_new::RepoNew;
 pub use crate::model::repository::repo_stats::{DataTypeStat, RepoStats};
 
+// Activity
+pub use crate::model::activity::{ActivityEvent, ActivityKind};
+
+// Append
+pub use crate::model::append_buffer::AppendBuffer;
+
 // Commit
 pub use crate::model::base_head::BaseHead;
 pub use crate::model::commit::{Commit, CommitStats, NewCommit, NewCommitBody};
 
 // Branch
-pub use crate::model::branch::Branch;
+pub use crate::model::branch::{Branch, BranchCompare};
+pub use crate::model::merge_proposal::{
+    MergeProposal, MergeProposalApproval, MergeProposalChecks, MergeProposalComment,
+    MergeProposalConflict, MergeProposalStatus, MergeProposalThread, RequiredApproval,
+};
+pub use crate::model::reflog_entry::ReflogEntry;
 pub use crate::model::remote_branch::RemoteBranch;
+pub use crate::model::stash_entry::{StashEntry, StashedChange, StashedFile};
+pub use crate::model::tag::Tag;
+pub use crate::model::webhook::{Webhook, WebhookEvent, WebhookPayload};
+
+// Jobs
+pub use crate::model::job::{Job, JobAction, JobRun, JobRunStatus};
+
+// Mirror
+pub use crate::model::mirror::{MirrorState, MirrorStatus};
 
 // Entry (TODO: These should just be nodes in the tree)
 pub use crate::model::content_type::ContentType;
@@ -49,6 +79,7 @@ pub use crate::model::diff::diff_entry::DiffEntry;
 pub use crate::model::entry::commit_entry::CommitEntry;
 pub use crate::model::entry::entry_data_type::EntryDataType;
 pub use crate::model::entry::metadata_entry::MetadataEntry;
+pub use crate::model::entry::metadata_filter::MetadataFilter;
 pub use crate::model::entry::mod_entry::ModEntry;
 pub use crate::model::entry::remote_entry::RemoteEntry;
 pub use crate::model::entry::staged_entry::{StagedEntry, StagedEntryStatus};
@@ -60,6 +91,7 @@ pub use crate::model::merge_conflict::NodeMergeConflict;
 
 pub use crate::model::data_frame::data_frame_size::DataFrameSize;
 
+pub use crate::model::upload_session::UploadSession;
 pub use crate::model::user::User;
 
 pub use crate::model::object_id::ObjectID;
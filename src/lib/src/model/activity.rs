@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+use crate::model::{Branch, Commit, Workspace};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivityKind {
+    Commit,
+    BranchCreated,
+    Fork,
+    WorkspaceMerge,
+}
+
+/// One entry in a repository's activity feed
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ActivityEvent {
+    pub kind: ActivityKind,
+    #[serde(with = "time::serde::rfc3339")]
+    pub timestamp: OffsetDateTime,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// `namespace/name` of the repository a fork was created as
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repository: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace_id: Option<String>,
+}
+
+impl ActivityEvent {
+    fn new(kind: ActivityKind, timestamp: OffsetDateTime) -> ActivityEvent {
+        ActivityEvent {
+            kind,
+            timestamp,
+            author: None,
+            branch: None,
+            commit_id: None,
+            message: None,
+            repository: None,
+            workspace_id: None,
+        }
+    }
+
+    pub fn commit(commit: &Commit) -> ActivityEvent {
+        ActivityEvent {
+            author: Some(commit.author.clone()),
+            commit_id: Some(commit.id.clone()),
+            message: Some(commit.message.clone()),
+            ..ActivityEvent::new(ActivityKind::Commit, commit.timestamp)
+        }
+    }
+
+    pub fn branch_created(branch: &Branch) -> ActivityEvent {
+        ActivityEvent {
+            branch: Some(branch.name.clone()),
+            commit_id: Some(branch.commit_id.clone()),
+            ..ActivityEvent::new(ActivityKind::BranchCreated, OffsetDateTime::now_utc())
+        }
+    }
+
+    pub fn fork(namespace: impl AsRef<str>, name: impl AsRef<str>) -> ActivityEvent {
+        ActivityEvent {
+            repository: Some(format!("{}/{}", namespace.as_ref(), name.as_ref())),
+            ..ActivityEvent::new(ActivityKind::Fork, OffsetDateTime::now_utc())
+        }
+    }
+
+    pub fn workspace_merge(
+        workspace: &Workspace,
+        branch_name: impl AsRef<str>,
+        commit: &Commit,
+    ) -> ActivityEvent {
+        ActivityEvent {
+            author: Some(commit.author.clone()),
+            branch: Some(branch_name.as_ref().to_string()),
+            commit_id: Some(commit.id.clone()),
+            message: Some(commit.message.clone()),
+            workspace_id: Some(workspace.id.clone()),
+            ..ActivityEvent::new(ActivityKind::WorkspaceMerge, commit.timestamp)
+        }
+    }
+}
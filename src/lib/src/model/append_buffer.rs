@@ -0,0 +1,38 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use time::{Duration, OffsetDateTime};
+
+use crate::model::User;
+
+/// Rows appended to a tabular file on a branch that have not been committed yet. The rows
+/// wait in a workspace until there are `commit_rows` of them or the oldest is
+/// `commit_after_secs` old, then they are committed together.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AppendBuffer {
+    pub branch: String,
+    pub path: PathBuf,
+    /// The workspace holding the rows
+    pub workspace_id: String,
+    pub num_rows: usize,
+    pub commit_rows: usize,
+    pub commit_after_secs: u64,
+    /// Author of the commit
+    pub author: User,
+    /// Message of the commit, describes the rows if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// When the first row that is not committed yet was appended
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+impl AppendBuffer {
+    /// Whether the rows should be committed now, because there are enough of them or they
+    /// have waited long enough
+    pub fn is_due(&self, now: OffsetDateTime) -> bool {
+        self.num_rows > 0
+            && (self.num_rows >= self.commit_rows
+                || now - self.created_at >= Duration::seconds(self.commit_after_secs as i64))
+    }
+}
@@ -1,5 +1,9 @@
 use serde::{Deserialize, Serialize};
 
+use crate::model::diff::AddRemoveModifyCounts;
+use crate::model::DiffEntry;
+use crate::view::Pagination;
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct Branch {
     pub name: String,
@@ -13,3 +17,19 @@ impl std::fmt::Display for Branch {
 }
 
 impl std::error::Error for Branch {}
+
+/// How far a head branch has diverged from a base branch
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct BranchCompare {
+    pub base: Branch,
+    pub head: Branch,
+    // Number of commits on head that are not on base
+    pub ahead: usize,
+    // Number of commits on base that are not on head
+    pub behind: usize,
+    pub is_mergeable: bool,
+    pub conflicts: Vec<String>,
+    pub counts: AddRemoveModifyCounts,
+    pub entries: Vec<DiffEntry>,
+    pub pagination: Pagination,
+}
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fmt;
 use std::hash::{Hash, Hasher};
 use time::OffsetDateTime;
@@ -26,6 +27,10 @@ pub struct NewCommit {
     pub email: String,
     #[serde(with = "time::serde::rfc3339")]
     pub timestamp: OffsetDateTime,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub co_authors: Vec<User>,
 }
 
 impl NewCommit {
@@ -36,6 +41,8 @@ impl NewCommit {
             author: commit.author.to_owned(),
             email: commit.email.to_owned(),
             timestamp: commit.timestamp.to_owned(),
+            metadata: commit.metadata.to_owned(),
+            co_authors: commit.co_authors.to_owned(),
         }
     }
 }
@@ -49,6 +56,12 @@ pub struct Commit {
     pub email: String,
     #[serde(with = "time::serde::rfc3339")]
     pub timestamp: OffsetDateTime,
+    /// User defined key-values attached at commit time, such as `run_id` or `model`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>,
+    /// Additional authors credited on the commit
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub co_authors: Vec<User>,
 }
 
 impl From<Commit> for WorkspaceCommit {
@@ -120,6 +133,8 @@ impl Commit {
             author: new_commit.author.to_owned(),
             email: new_commit.email.to_owned(),
             timestamp: new_commit.timestamp.to_owned(),
+            metadata: new_commit.metadata.to_owned(),
+            co_authors: new_commit.co_authors.to_owned(),
         }
     }
 
@@ -131,6 +146,8 @@ impl Commit {
             author: new_commit.author.to_owned(),
             email: new_commit.email.to_owned(),
             timestamp: new_commit.timestamp.to_owned(),
+            metadata: new_commit.metadata.to_owned(),
+            co_authors: new_commit.co_authors.to_owned(),
         }
     }
 
@@ -142,6 +159,8 @@ impl Commit {
             author: commit.author.to_owned(),
             email: commit.email.to_owned(),
             timestamp: commit.timestamp.to_owned(),
+            metadata: BTreeMap::new(),
+            co_authors: vec![],
         }
     }
 
@@ -153,6 +172,8 @@ impl Commit {
             author: commit.author.to_owned(),
             email: commit.email.to_owned(),
             timestamp: commit.timestamp.to_owned(),
+            metadata: BTreeMap::new(),
+            co_authors: vec![],
         }
     }
 
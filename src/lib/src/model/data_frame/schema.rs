@@ -1,11 +1,13 @@
 pub mod custom_data_type;
 pub mod data_type;
 pub mod field;
+pub mod schema_changes;
 pub mod staged_schema;
 
 pub use custom_data_type::CustomDataType;
 pub use data_type::DataType;
 pub use field::Field;
+pub use schema_changes::SchemaChanges;
 
 use crate::util::hasher;
 use itertools::Itertools;
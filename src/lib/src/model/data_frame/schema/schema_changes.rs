@@ -0,0 +1,49 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use super::Field;
+
+/// A column that kept its position and dtype but changed its name
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ColumnRename {
+    pub from: String,
+    pub to: String,
+    pub dtype: String,
+}
+
+/// A column that kept its name but changed its dtype
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DtypeChange {
+    pub name: String,
+    pub from: String,
+    pub to: String,
+}
+
+/// How the schema of a tabular file changed between two revisions, as printed by
+/// `oxen schemas diff <path> <rev1> <rev2>`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SchemaChanges {
+    pub path: PathBuf,
+    pub base_commit_id: String,
+    pub head_commit_id: String,
+    pub added: Vec<Field>,
+    pub removed: Vec<Field>,
+    pub renamed: Vec<ColumnRename>,
+    pub dtype_changed: Vec<DtypeChange>,
+}
+
+impl SchemaChanges {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.renamed.is_empty()
+            && self.dtype_changed.is_empty()
+    }
+
+    /// Removing, renaming or retyping a column breaks readers of the old schema,
+    /// adding one does not
+    pub fn is_breaking(&self) -> bool {
+        !self.removed.is_empty() || !self.renamed.is_empty() || !self.dtype_changed.is_empty()
+    }
+}
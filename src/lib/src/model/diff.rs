@@ -1,6 +1,9 @@
 pub mod add_remove_modify_counts;
 pub use add_remove_modify_counts::AddRemoveModifyCounts;
 
+pub mod binary_diff;
+pub use binary_diff::BinaryDiff;
+
 pub mod change_type;
 pub use change_type::ChangeType;
 
@@ -14,14 +17,23 @@ pub mod diff_file_node;
 pub mod diff_result;
 pub use diff_result::DiffResult;
 
+pub mod diff_stat;
+pub use diff_stat::DiffStat;
+
 pub mod generic_diff;
 pub mod generic_diff_summary;
 
+pub mod dir_changes;
+pub use dir_changes::DirChangesSummary;
+
 pub mod dir_diff;
 pub mod dir_diff_summary;
 
 pub mod schema_diff;
 
+pub mod structured_diff;
+pub use structured_diff::StructuredDiff;
+
 pub mod tabular_diff;
 pub use tabular_diff::TabularDiff;
 
@@ -29,3 +41,6 @@ pub mod tabular_diff_summary;
 
 pub mod text_diff;
 pub use text_diff::TextDiff;
+
+pub mod workspace_changes;
+pub use workspace_changes::{WorkspaceChanges, WorkspaceFileChange};
@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+
+/// A byte range within a file
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ChunkRange {
+    pub offset: u64,
+    pub len: u64,
+}
+
+/// Compares two binary files by their content-defined chunks. Unlike fixed size blocks,
+/// chunk boundaries follow the content, so an insertion only changes the chunks around
+/// it instead of shifting every block after it.
+#[derive(Default, Deserialize, Serialize, Debug, Clone)]
+pub struct BinaryDiff {
+    pub filename1: Option<String>,
+    pub filename2: Option<String>,
+    pub size1: u64,
+    pub size2: u64,
+    pub num_chunks1: usize,
+    pub num_chunks2: usize,
+    /// Chunks of the second file that also appear in the first
+    pub unchanged_chunks: usize,
+    pub unchanged_bytes: u64,
+    /// Regions of the second file that are not in the first, adjacent chunks are merged
+    pub changed_ranges: Vec<ChunkRange>,
+}
+
+impl BinaryDiff {
+    /// Fraction of the second file's bytes that were already in the first
+    pub fn unchanged_fraction(&self) -> f64 {
+        if self.size2 == 0 {
+            return if self.size1 == 0 { 1.0 } else { 0.0 };
+        }
+        self.unchanged_bytes as f64 / self.size2 as f64
+    }
+
+    pub fn changed_bytes(&self) -> u64 {
+        self.size2 - self.unchanged_bytes
+    }
+}
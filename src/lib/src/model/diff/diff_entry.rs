@@ -235,8 +235,18 @@ impl DiffEntry {
             }
         }
 
+        // JSON and YAML get a key by key diff whether they are detected as text or not
+        let structured_diff = match (&base_entry, &head_entry) {
+            (Some(base), Some(head)) if should_do_full_diff => {
+                repositories::diffs::diff_structured_file_nodes(repo, base, head)
+            }
+            _ => None,
+        };
+
         // TODO: handle all diff types more generically
-        let diff = if should_do_full_diff && data_type == EntryDataType::Text {
+        let diff = if let Some(diff) = structured_diff {
+            Some(GenericDiff::StructuredDiff(diff))
+        } else if should_do_full_diff && data_type == EntryDataType::Text {
             if base_entry.is_some() && head_entry.is_some() {
                 match repositories::diffs::diff_text_file_nodes(
                     repo,
@@ -6,6 +6,8 @@ pub enum DiffEntryStatus {
     Added,
     Modified,
     Removed,
+    /// Moved to another path without changing its contents
+    Renamed,
 }
 
 // Downcase the status
@@ -15,6 +17,7 @@ impl std::fmt::Display for DiffEntryStatus {
             DiffEntryStatus::Added => "added",
             DiffEntryStatus::Modified => "modified",
             DiffEntryStatus::Removed => "removed",
+            DiffEntryStatus::Renamed => "renamed",
         };
         write!(f, "{}", status)
     }
@@ -29,6 +32,7 @@ impl std::str::FromStr for DiffEntryStatus {
             "added" => Ok(DiffEntryStatus::Added),
             "modified" => Ok(DiffEntryStatus::Modified),
             "removed" => Ok(DiffEntryStatus::Removed),
+            "renamed" => Ok(DiffEntryStatus::Renamed),
             _ => Err(format!("Could not parse {} as a DiffEntryStatus", s)),
         }
     }
@@ -1,4 +1,6 @@
 // use crate::model::diff::dir_diff::DirDiff;
+use crate::model::diff::binary_diff::BinaryDiff;
+use crate::model::diff::structured_diff::StructuredDiff;
 use crate::model::diff::tabular_diff::TabularDiff;
 use crate::model::diff::text_diff::TextDiff;
 
@@ -6,4 +8,6 @@ use crate::model::diff::text_diff::TextDiff;
 pub enum DiffResult {
     Tabular(TabularDiff),
     Text(TextDiff),
+    Binary(BinaryDiff),
+    Structured(StructuredDiff),
 }
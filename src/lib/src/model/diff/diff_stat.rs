@@ -0,0 +1,70 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::diff::diff_entry_status::DiffEntryStatus;
+
+/// What the insertions and deletions of a file are counted in
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffStatUnit {
+    Lines,
+    Rows,
+    Bytes,
+}
+
+impl std::fmt::Display for DiffStatUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let unit = match self {
+            DiffStatUnit::Lines => "lines",
+            DiffStatUnit::Rows => "rows",
+            DiffStatUnit::Bytes => "bytes",
+        };
+        write!(f, "{}", unit)
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct FileDiffStat {
+    pub path: PathBuf,
+    /// Where a renamed file was moved from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub old_path: Option<PathBuf>,
+    pub status: DiffEntryStatus,
+    pub unit: DiffStatUnit,
+    pub insertions: u64,
+    pub deletions: u64,
+}
+
+impl FileDiffStat {
+    /// The path to print, `old => new` for renamed files
+    pub fn display_path(&self) -> String {
+        match &self.old_path {
+            Some(old_path) => format!(
+                "{} => {}",
+                old_path.to_string_lossy(),
+                self.path.to_string_lossy()
+            ),
+            None => self.path.to_string_lossy().to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct DiffStat {
+    pub files: Vec<FileDiffStat>,
+}
+
+impl DiffStat {
+    /// Insertions and deletions summed per unit
+    pub fn totals(&self) -> BTreeMap<DiffStatUnit, (u64, u64)> {
+        let mut totals: BTreeMap<DiffStatUnit, (u64, u64)> = BTreeMap::new();
+        for file in &self.files {
+            let total = totals.entry(file.unit).or_default();
+            total.0 += file.insertions;
+            total.1 += file.deletions;
+        }
+        totals
+    }
+}
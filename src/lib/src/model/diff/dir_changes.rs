@@ -0,0 +1,38 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::diff::AddRemoveModifyCounts;
+
+/// Files added, removed and modified directly in one directory
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct DirChanges {
+    pub path: PathBuf,
+    pub file_counts: AddRemoveModifyCounts,
+    /// Size of the directory's files in the head minus their size in the base
+    pub byte_delta: i64,
+}
+
+/// Changed files between two revisions grouped by the directory they are in, as printed
+/// by `oxen diff <base>..<head> <dir>`
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct DirChangesSummary {
+    pub base_commit_id: String,
+    pub head_commit_id: String,
+    /// Only directories with changed files, sorted by path
+    pub dirs: Vec<DirChanges>,
+}
+
+impl DirChangesSummary {
+    /// The counts and byte delta of every directory summed
+    pub fn total(&self) -> DirChanges {
+        let mut total = DirChanges::default();
+        for dir in &self.dirs {
+            total.file_counts.added += dir.file_counts.added;
+            total.file_counts.removed += dir.file_counts.removed;
+            total.file_counts.modified += dir.file_counts.modified;
+            total.byte_delta += dir.byte_delta;
+        }
+        total
+    }
+}
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 use crate::model::diff::dir_diff::DirDiff;
+use crate::model::diff::structured_diff::StructuredDiff;
 use crate::model::diff::text_diff::TextDiff;
 use crate::view::tabular_diff_view::TabularDiffView;
 
@@ -9,5 +10,6 @@ use crate::view::tabular_diff_view::TabularDiffView;
 pub enum GenericDiff {
     DirDiff(DirDiff),
     TabularDiff(TabularDiffView),
+    StructuredDiff(StructuredDiff),
     TextDiff(TextDiff),
 }
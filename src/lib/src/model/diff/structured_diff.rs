@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::model::diff::change_type::ChangeType;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StructuredFormat {
+    Json,
+    Yaml,
+}
+
+/// A key that was added, removed or changed. Objects and arrays that are on both sides are
+/// diffed key by key, so a modified key either has `children` or its `before` and `after`.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct KeyDiff {
+    /// Name of the key, or the index within an array
+    pub key: String,
+    pub modification: ChangeType,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub before: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub after: Option<Value>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub children: Vec<KeyDiff>,
+}
+
+/// Compares two parsed JSON or YAML documents by key instead of by line
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct StructuredDiff {
+    pub filename1: Option<String>,
+    pub filename2: Option<String>,
+    pub format: StructuredFormat,
+    /// Changed keys of the top level object, an empty key if the whole document changed
+    pub keys: Vec<KeyDiff>,
+}
+
+impl StructuredDiff {
+    /// Number of leaf keys that were added, removed or modified
+    pub fn num_changes(&self) -> usize {
+        fn count(keys: &[KeyDiff]) -> usize {
+            keys.iter()
+                .map(|key| {
+                    if key.children.is_empty() {
+                        1
+                    } else {
+                        count(&key.children)
+                    }
+                })
+                .sum()
+        }
+        count(&self.keys)
+    }
+}
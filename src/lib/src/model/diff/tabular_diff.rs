@@ -33,6 +33,8 @@ pub struct TabularDiff {
     pub summary: TabularDiffSummary,
     pub parameters: TabularDiffParameters,
     pub contents: DataFrame,
+    /// Key columns and `old → new` values of the cells that changed in modified rows
+    pub modified_cells: DataFrame,
 }
 
 #[derive(Debug, Clone)]
@@ -135,6 +137,7 @@ impl TabularDiff {
             summary: TabularDiffSummary::empty(),
             parameters: TabularDiffParameters::empty(),
             contents: DataFrame::empty(),
+            modified_cells: DataFrame::empty(),
             filename1: None,
             filename2: None,
         }
@@ -0,0 +1,32 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::diff::AddRemoveModifyCounts;
+
+/// A file with pending edits in a workspace
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct WorkspaceFileChange {
+    pub path: PathBuf,
+    /// Rows added, removed and modified in a data frame edited in the workspace, None for
+    /// files that were uploaded or deleted whole
+    pub row_counts: Option<AddRemoveModifyCounts>,
+}
+
+/// Everything that would be committed from a workspace, compared to the commit it was
+/// created from
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct WorkspaceChanges {
+    pub base_commit_id: String,
+    pub added_files: Vec<WorkspaceFileChange>,
+    pub modified_files: Vec<WorkspaceFileChange>,
+    pub removed_files: Vec<WorkspaceFileChange>,
+}
+
+impl WorkspaceChanges {
+    pub fn is_empty(&self) -> bool {
+        self.added_files.is_empty()
+            && self.modified_files.is_empty()
+            && self.removed_files.is_empty()
+    }
+}
@@ -2,6 +2,7 @@ pub mod commit_entry;
 pub mod entry_data_type;
 pub mod entry_status;
 pub mod metadata_entry;
+pub mod metadata_filter;
 pub mod mod_entry;
 pub mod remote_entry;
 pub mod staged_entry;
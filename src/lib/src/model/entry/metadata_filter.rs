@@ -0,0 +1,238 @@
+//! Filter the files in a listing by the properties stored on their nodes, such as
+//! `data_type=image,image.width>1024`
+//!
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde_json::Value;
+
+use crate::error::OxenError;
+use crate::model::merkle_tree::node::{EMerkleTreeNode, MerkleTreeNode};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterOp {
+    Eq,
+    NotEq,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+}
+
+impl FilterOp {
+    const ALL: [(&'static str, FilterOp); 7] = [
+        (">=", FilterOp::Gte),
+        ("<=", FilterOp::Lte),
+        ("!=", FilterOp::NotEq),
+        ("==", FilterOp::Eq),
+        (">", FilterOp::Gt),
+        ("<", FilterOp::Lt),
+        ("=", FilterOp::Eq),
+    ];
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            FilterOp::Eq => "=",
+            FilterOp::NotEq => "!=",
+            FilterOp::Gt => ">",
+            FilterOp::Gte => ">=",
+            FilterOp::Lt => "<",
+            FilterOp::Lte => "<=",
+        }
+    }
+}
+
+/// A single `key<op>value` comparison, where the key is a dotted path into the node's
+/// properties, ie: `image.width`
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetadataCondition {
+    pub key: String,
+    pub op: FilterOp,
+    pub value: String,
+}
+
+/// Conditions that must all hold for an entry to be listed
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MetadataFilter {
+    pub conditions: Vec<MetadataCondition>,
+}
+
+impl MetadataCondition {
+    pub fn parse(condition: &str) -> Result<MetadataCondition, OxenError> {
+        // The first operator in the condition, preferring `>=` over `>` at the same spot
+        let Some((index, op_str, op)) = FilterOp::ALL
+            .iter()
+            .filter_map(|(op_str, op)| condition.find(op_str).map(|i| (i, *op_str, *op)))
+            .min_by_key(|(i, op_str, _)| (*i, std::cmp::Reverse(op_str.len())))
+        else {
+            return Err(OxenError::basic_str(format!(
+                "Invalid filter {condition:?}, format must be key<op>value ie: image.width>1024"
+            )));
+        };
+
+        let key = condition[..index].trim();
+        let value = condition[index + op_str.len()..].trim();
+        if key.is_empty() || value.is_empty() {
+            return Err(OxenError::basic_str(format!(
+                "Invalid filter {condition:?}, must have a key and a value"
+            )));
+        }
+        Ok(MetadataCondition {
+            key: key.to_string(),
+            op,
+            value: value.to_string(),
+        })
+    }
+
+    fn matches(&self, properties: &Value) -> bool {
+        let Some(found) = self
+            .key
+            .split('.')
+            .try_fold(properties, |value, key| value.get(key))
+        else {
+            return false;
+        };
+
+        let ordering = match (found.as_f64(), self.value.parse::<f64>()) {
+            (Some(found), Ok(expected)) => found.partial_cmp(&expected),
+            _ => {
+                let found = match found {
+                    Value::String(found) => found.to_lowercase(),
+                    found => found.to_string(),
+                };
+                Some(found.as_str().cmp(self.value.to_lowercase().as_str()))
+            }
+        };
+        let Some(ordering) = ordering else {
+            return false;
+        };
+        match self.op {
+            FilterOp::Eq => ordering.is_eq(),
+            FilterOp::NotEq => ordering.is_ne(),
+            FilterOp::Gt => ordering.is_gt(),
+            FilterOp::Gte => ordering.is_ge(),
+            FilterOp::Lt => ordering.is_lt(),
+            FilterOp::Lte => ordering.is_le(),
+        }
+    }
+}
+
+impl MetadataFilter {
+    /// Parse comma separated conditions, ie: `data_type=image,image.width>1024`
+    pub fn parse(filter: &str) -> Result<MetadataFilter, OxenError> {
+        Self::from_conditions(filter.split(','))
+    }
+
+    pub fn from_conditions<'a>(
+        conditions: impl IntoIterator<Item = &'a str>,
+    ) -> Result<MetadataFilter, OxenError> {
+        let conditions = conditions
+            .into_iter()
+            .filter(|condition| !condition.trim().is_empty())
+            .map(MetadataCondition::parse)
+            .collect::<Result<Vec<_>, OxenError>>()?;
+        Ok(MetadataFilter { conditions })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.conditions.is_empty()
+    }
+
+    /// Whether a file or dir node passes every condition. Nodes expose their `name`,
+    /// `data_type`, `mime_type`, `extension` and `size` along with the fields of their
+    /// metadata, so an image can be matched on `image.width` or `image.exif.make`.
+    pub fn matches(&self, node: &MerkleTreeNode) -> bool {
+        if self.is_empty() {
+            return true;
+        }
+        let Some(properties) = node_properties(node) else {
+            return false;
+        };
+        self.conditions
+            .iter()
+            .all(|condition| condition.matches(&properties))
+    }
+}
+
+fn node_properties(node: &MerkleTreeNode) -> Option<Value> {
+    let (mut properties, metadata) = match &node.node {
+        EMerkleTreeNode::File(file_node) => (
+            serde_json::json!({
+                "name": file_node.name(),
+                "data_type": file_node.data_type().to_string(),
+                "mime_type": file_node.mime_type(),
+                "extension": file_node.extension(),
+                "size": file_node.num_bytes(),
+            }),
+            file_node
+                .metadata()
+                .and_then(|metadata| serde_json::to_value(metadata).ok()),
+        ),
+        EMerkleTreeNode::Directory(dir_node) => (
+            serde_json::json!({
+                "name": dir_node.name(),
+                "data_type": "dir",
+                "size": dir_node.num_bytes(),
+            }),
+            None,
+        ),
+        _ => return None,
+    };
+
+    // Metadata is keyed by its kind, ie: {"image": {"width": 28, "height": 28}}
+    if let (Some(Value::Object(metadata)), Some(properties)) =
+        (metadata, properties.as_object_mut())
+    {
+        properties.extend(metadata);
+    }
+    Some(properties)
+}
+
+impl FromStr for MetadataFilter {
+    type Err = OxenError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        MetadataFilter::parse(s)
+    }
+}
+
+impl fmt::Display for MetadataFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let conditions: Vec<String> = self
+            .conditions
+            .iter()
+            .map(|c| format!("{}{}{}", c.key, c.op.as_str(), c.value))
+            .collect();
+        write!(f, "{}", conditions.join(","))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::OxenError;
+    use crate::model::entry::metadata_filter::{FilterOp, MetadataCondition, MetadataFilter};
+
+    #[test]
+    fn test_parse_metadata_filter() -> Result<(), OxenError> {
+        let filter = MetadataFilter::parse("data_type=image, image.width>=1024,extension!=png")?;
+        assert_eq!(filter.conditions.len(), 3);
+        assert_eq!(
+            filter.conditions[1],
+            MetadataCondition {
+                key: "image.width".to_string(),
+                op: FilterOp::Gte,
+                value: "1024".to_string(),
+            }
+        );
+        assert_eq!(filter.conditions[2].op, FilterOp::NotEq);
+        assert_eq!(
+            filter.to_string(),
+            "data_type=image,image.width>=1024,extension!=png"
+        );
+
+        assert!(MetadataFilter::parse("image.width").is_err());
+        assert!(MetadataFilter::parse(">1024").is_err());
+        Ok(())
+    }
+}
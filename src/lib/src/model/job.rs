@@ -0,0 +1,75 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+use crate::model::User;
+
+/// What a scheduled job does when it runs
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum JobAction {
+    /// Sync the objects under an S3 prefix onto the repository's current branch and commit
+    /// what changed, as the user who created the job
+    ImportS3 {
+        url: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        region: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        endpoint: Option<String>,
+    },
+    /// Run the checks in `.oxen/checks.toml` against the tabular files on a branch
+    Validate { branch: String },
+}
+
+impl fmt::Display for JobAction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            JobAction::ImportS3 { url, .. } => write!(f, "import {url}"),
+            JobAction::Validate { branch } => write!(f, "validate {branch}"),
+        }
+    }
+}
+
+/// An action the server runs on a repository on a cron schedule
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Job {
+    pub id: String,
+    pub name: String,
+    /// Cron expression in UTC, `min hour day month weekday` or with seconds first
+    pub schedule: String,
+    pub action: JobAction,
+    /// Disabled jobs only run when triggered by hand
+    pub enabled: bool,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    /// The admin who created the job, the author of the commits it makes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_by: Option<User>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum JobRunStatus {
+    Running,
+    Succeeded,
+    Failed,
+}
+
+/// One run of a job, kept in its run history
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct JobRun {
+    pub id: String,
+    pub job_id: String,
+    pub status: JobRunStatus,
+    #[serde(with = "time::serde::rfc3339")]
+    pub started_at: OffsetDateTime,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub finished_at: Option<OffsetDateTime>,
+    /// What the run did, or why it failed
+    #[serde(default)]
+    pub message: String,
+    /// The commit the run made, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit_id: Option<String>,
+}
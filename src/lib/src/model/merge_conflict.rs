@@ -17,6 +17,9 @@ pub struct NodeMergeConflict {
     pub lca_entry: (FileNode, PathBuf),  // Least Common Ancestor Entry
     pub base_entry: (FileNode, PathBuf), // Entry that existed in the base commit
     pub merge_entry: (FileNode, PathBuf), // Entry we are trying to merge in
+    // Rows that could not be merged automatically, empty if the whole file is in conflict
+    #[serde(default)]
+    pub row_conflicts: Vec<RowMergeConflict>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -24,6 +27,33 @@ pub struct MergeConflict {
     pub lca_entry: MergeConflictEntry,
     pub base_entry: MergeConflictEntry,
     pub merge_entry: MergeConflictEntry,
+    #[serde(default)]
+    pub row_conflicts: Vec<RowMergeConflict>,
+}
+
+/// A row of a tabular file that was changed differently on both sides of a merge.
+/// Rows are stored as json objects of column name to value, and are None if the
+/// row does not exist on that side.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct RowMergeConflict {
+    pub key: serde_json::Value,
+    pub lca_row: Option<serde_json::Value>,
+    pub base_row: Option<serde_json::Value>,
+    pub merge_row: Option<serde_json::Value>,
+    /// The cells that were changed differently on both sides, the other columns of the row
+    /// were merged. Empty if the row was deleted on one side and changed on the other.
+    #[serde(default)]
+    pub columns: Vec<String>,
+}
+
+/// The version picked for a conflicting row when resolving a tabular merge conflict
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum RowResolution {
+    Ours,
+    Theirs,
+    // A json object of column name to value, entered by the user
+    Edited(serde_json::Value),
 }
 
 impl MergeConflict {
@@ -64,6 +94,7 @@ impl EntryMergeConflict {
             lca_entry: self.lca_entry.to_merge_conflict_entry(),
             base_entry: self.base_entry.to_merge_conflict_entry(),
             merge_entry: self.merge_entry.to_merge_conflict_entry(),
+            row_conflicts: vec![],
         }
     }
 }
@@ -74,6 +105,7 @@ impl NodeMergeConflict {
             lca_entry: to_merge_conflict_entry(&self.lca_entry.0, &self.lca_entry.1),
             base_entry: to_merge_conflict_entry(&self.base_entry.0, &self.base_entry.1),
             merge_entry: to_merge_conflict_entry(&self.merge_entry.0, &self.merge_entry.1),
+            row_conflicts: self.row_conflicts.clone(),
         }
     }
 }
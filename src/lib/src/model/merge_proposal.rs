@@ -0,0 +1,176 @@
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+use crate::error::OxenError;
+use crate::model::merge_conflict::RowMergeConflict;
+use crate::model::User;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeProposalStatus {
+    Open,
+    Merged,
+    Closed,
+}
+
+impl fmt::Display for MergeProposalStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MergeProposalStatus::Open => write!(f, "open"),
+            MergeProposalStatus::Merged => write!(f, "merged"),
+            MergeProposalStatus::Closed => write!(f, "closed"),
+        }
+    }
+}
+
+impl FromStr for MergeProposalStatus {
+    type Err = OxenError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "open" => Ok(MergeProposalStatus::Open),
+            "merged" => Ok(MergeProposalStatus::Merged),
+            "closed" => Ok(MergeProposalStatus::Closed),
+            _ => Err(OxenError::basic_str(format!(
+                "Invalid proposal status {s:?}, must be one of open, merged or closed"
+            ))),
+        }
+    }
+}
+
+/// A request to merge the `head` branch into `base`, reviewed and merged on the server
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MergeProposal {
+    /// Numbered from 1 in each repository
+    pub id: u64,
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    pub base: String,
+    pub head: String,
+    pub author: User,
+    pub status: MergeProposalStatus,
+    /// The commit the proposal was merged in, once it is merged
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merge_commit_id: Option<String>,
+    #[serde(default)]
+    pub comments: Vec<MergeProposalComment>,
+    #[serde(default)]
+    pub approvals: Vec<MergeProposalApproval>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub updated_at: OffsetDateTime,
+}
+
+/// A review comment. Comments on the same file and row form a thread.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MergeProposalComment {
+    /// Numbered from 1 in each proposal
+    pub id: u64,
+    pub author: User,
+    pub body: String,
+    /// The file the comment is about, None for the proposal as a whole
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+    /// The row of a tabular file the comment is about, such as its index or key
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub row: Option<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+/// A reviewer signing off on the head branch as it was at `commit_id`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MergeProposalApproval {
+    pub author: User,
+    pub commit_id: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MergeProposalThread {
+    pub path: Option<PathBuf>,
+    pub row: Option<String>,
+    /// Oldest first
+    pub comments: Vec<MergeProposalComment>,
+}
+
+/// Whether a proposal can be merged right now, and what is in the way if not
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MergeProposalChecks {
+    pub is_mergeable: bool,
+    pub base_commit_id: String,
+    pub head_commit_id: String,
+    /// Number of commits on head that are not on base
+    pub num_commits: usize,
+    pub conflicts: Vec<MergeProposalConflict>,
+    /// Owners that have to approve changes to their paths, see [`crate::config::OwnersConfig`]
+    #[serde(default)]
+    pub required_approvals: Vec<RequiredApproval>,
+}
+
+/// A file changed on both branches that can't be merged automatically
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MergeProposalConflict {
+    pub path: PathBuf,
+    /// Rows of a tabular file that were changed differently on both branches, empty if
+    /// the file has to be resolved as a whole
+    #[serde(default)]
+    pub row_conflicts: Vec<RowMergeConflict>,
+}
+
+/// Changed files that belong to the same owners rule, and the owner that approved them
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RequiredApproval {
+    pub pattern: String,
+    pub owners: Vec<String>,
+    pub paths: Vec<PathBuf>,
+    pub approved_by: Option<User>,
+}
+
+impl RequiredApproval {
+    pub fn is_approved(&self) -> bool {
+        self.approved_by.is_some()
+    }
+}
+
+impl MergeProposal {
+    pub fn is_open(&self) -> bool {
+        self.status == MergeProposalStatus::Open
+    }
+
+    /// Who approved the head branch at `commit_id`. Approvals of earlier commits no longer
+    /// count once more changes are pushed.
+    pub fn approvers(&self, commit_id: &str) -> Vec<User> {
+        self.approvals
+            .iter()
+            .filter(|approval| approval.commit_id == commit_id)
+            .map(|approval| approval.author.clone())
+            .collect()
+    }
+
+    /// The comments grouped by the file and row they are on, in the order each thread started
+    pub fn threads(&self) -> Vec<MergeProposalThread> {
+        let mut threads: Vec<MergeProposalThread> = vec![];
+        for comment in &self.comments {
+            match threads
+                .iter_mut()
+                .find(|thread| thread.path == comment.path && thread.row == comment.row)
+            {
+                Some(thread) => thread.comments.push(comment.clone()),
+                None => threads.push(MergeProposalThread {
+                    path: comment.path.clone(),
+                    row: comment.row.clone(),
+                    comments: vec![comment.clone()],
+                }),
+            }
+        }
+        threads
+    }
+}
@@ -1,15 +1,18 @@
 //! Wrapper around the CommitNodeData struct to support old versions of the commit node
 
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fmt;
 use std::str::FromStr;
 use time::OffsetDateTime;
 
-use crate::core::v_latest::model::merkle_tree::node::commit_node::CommitNodeData as CommitNodeDataV0_25_0;
+use crate::core::v_latest::model::merkle_tree::node::commit_node::{
+    CommitNodeData as CommitNodeDataV0_25_0, CommitNodeExtra,
+};
 use crate::core::v_old::v0_19_0::model::merkle_tree::node::commit_node::CommitNodeData as CommitNodeDataV0_19_0;
 use crate::core::versions::MinOxenVersion;
 use crate::error::OxenError;
-use crate::model::{Commit, LocalRepository};
+use crate::model::{Commit, LocalRepository, User};
 use crate::model::{MerkleHash, MerkleTreeNodeIdType, MerkleTreeNodeType, TMerkleTreeNode};
 
 pub trait TCommitNode {
@@ -21,6 +24,12 @@ pub trait TCommitNode {
     fn author(&self) -> &str;
     fn email(&self) -> &str;
     fn timestamp(&self) -> &OffsetDateTime;
+    fn metadata(&self) -> Option<&BTreeMap<String, String>> {
+        None
+    }
+    fn co_authors(&self) -> &[User] {
+        &[]
+    }
 }
 
 pub struct CommitNodeOpts {
@@ -30,6 +39,8 @@ pub struct CommitNodeOpts {
     pub author: String,
     pub message: String,
     pub timestamp: OffsetDateTime,
+    pub metadata: BTreeMap<String, String>,
+    pub co_authors: Vec<User>,
 }
 
 #[derive(Deserialize, Serialize, Clone, PartialEq, Eq)]
@@ -67,6 +78,10 @@ impl CommitNode {
                     message: opts.message,
                     timestamp: opts.timestamp,
                     node_type: MerkleTreeNodeType::Commit,
+                    extra: CommitNodeExtra {
+                        metadata: opts.metadata,
+                        co_authors: opts.co_authors,
+                    },
                 }),
             }),
             _ => Err(OxenError::basic_str(
@@ -89,6 +104,10 @@ impl CommitNode {
                 message: commit.message.clone(),
                 timestamp: commit.timestamp,
                 node_type: MerkleTreeNodeType::Commit,
+                extra: CommitNodeExtra {
+                    metadata: commit.metadata.clone(),
+                    co_authors: commit.co_authors.clone(),
+                },
             }),
         }
     }
@@ -101,6 +120,8 @@ impl CommitNode {
             author: self.author().to_owned(),
             message: self.message().to_owned(),
             timestamp: self.timestamp().to_owned(),
+            metadata: self.metadata().cloned().unwrap_or_default(),
+            co_authors: self.co_authors().to_vec(),
         }
     }
 
@@ -113,6 +134,8 @@ impl CommitNode {
                 author: commit.author.clone(),
                 message: commit.message.clone(),
                 timestamp: commit.timestamp,
+                metadata: commit.extra.metadata.clone(),
+                co_authors: commit.extra.co_authors.clone(),
             },
             ECommitNode::V0_19_0(ref commit) => CommitNodeOpts {
                 hash: commit.hash,
@@ -121,6 +144,8 @@ impl CommitNode {
                 author: commit.author.clone(),
                 message: commit.message.clone(),
                 timestamp: commit.timestamp,
+                metadata: BTreeMap::new(),
+                co_authors: vec![],
             },
         }
     }
@@ -177,6 +202,15 @@ impl CommitNode {
     pub fn timestamp(&self) -> &OffsetDateTime {
         self.node().timestamp()
     }
+
+    /// Key-values attached to the commit, empty for commit nodes from before they were supported
+    pub fn metadata(&self) -> Option<&BTreeMap<String, String>> {
+        self.node().metadata()
+    }
+
+    pub fn co_authors(&self) -> &[User] {
+        self.node().co_authors()
+    }
 }
 
 impl Default for CommitNode {
@@ -190,6 +224,7 @@ impl Default for CommitNode {
                 author: "".to_string(),
                 email: "".to_string(),
                 timestamp: OffsetDateTime::now_utc(),
+                extra: CommitNodeExtra::default(),
             }),
         }
     }
@@ -216,6 +251,12 @@ impl fmt::Debug for CommitNode {
         writeln!(f, "\tauthor: {}", self.author())?;
         writeln!(f, "\temail: {}", self.email())?;
         writeln!(f, "\ttimestamp: {}", self.timestamp())?;
+        if let Some(metadata) = self.metadata().filter(|m| !m.is_empty()) {
+            writeln!(f, "\tmetadata: {:?}", metadata)?;
+        }
+        for co_author in self.co_authors() {
+            writeln!(f, "\tco_author: {}", co_author)?;
+        }
         Ok(())
     }
 }
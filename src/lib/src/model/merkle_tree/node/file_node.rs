@@ -1,5 +1,6 @@
 //! Wrapper around the FileNodeData struct to support old versions of the file node
 
+use crate::constants::DEFAULT_FILE_MODE;
 use crate::core::v_latest::model::merkle_tree::node::file_node::FileNodeData as FileNodeDataV0_25_0;
 use crate::core::versions::MinOxenVersion;
 use crate::error::OxenError;
@@ -25,12 +26,14 @@ pub struct FileNodeOpts {
     pub metadata: Option<GenericMetadata>,
     pub mime_type: String,
     pub extension: String,
+    pub mode: u32,
 }
 
 pub trait TFileNode {
     fn version(&self) -> MinOxenVersion;
     fn node_type(&self) -> &MerkleTreeNodeType;
     fn hash(&self) -> &MerkleHash;
+    fn set_hash(&mut self, hash: &MerkleHash);
     fn name(&self) -> &str;
     fn set_name(&mut self, name: &str);
     fn combined_hash(&self) -> &MerkleHash;
@@ -52,6 +55,8 @@ pub trait TFileNode {
     fn set_chunk_hashes(&mut self, chunk_hashes: Vec<u128>);
     fn chunk_type(&self) -> &FileChunkType;
     fn storage_backend(&self) -> &FileStorageType;
+    fn mode(&self) -> u32;
+    fn set_mode(&mut self, mode: u32);
 }
 
 #[derive(Deserialize, Serialize, Clone)]
@@ -86,6 +91,7 @@ impl FileNode {
                         chunk_hashes: vec![],
                         chunk_type: FileChunkType::SingleFile,
                         storage_backend: FileStorageType::Disk,
+                        mode: opts.mode,
                     }),
                 })
             }
@@ -133,6 +139,10 @@ impl FileNode {
         self.node().hash()
     }
 
+    pub fn set_hash(&mut self, hash: &MerkleHash) {
+        self.mut_node().set_hash(hash);
+    }
+
     pub fn name(&self) -> &str {
         self.node().name()
     }
@@ -216,6 +226,22 @@ impl FileNode {
     pub fn storage_backend(&self) -> &FileStorageType {
         self.node().storage_backend()
     }
+
+    /// Unix mode of the file, nodes committed before modes were tracked are regular files
+    pub fn mode(&self) -> u32 {
+        match self.node().mode() {
+            0 => DEFAULT_FILE_MODE,
+            mode => mode,
+        }
+    }
+
+    pub fn set_mode(&mut self, mode: u32) {
+        self.mut_node().set_mode(mode);
+    }
+
+    pub fn is_executable(&self) -> bool {
+        self.mode() & 0o111 != 0
+    }
 }
 
 impl Default for FileNode {
@@ -238,6 +264,7 @@ impl Default for FileNode {
                 chunk_hashes: vec![],
                 chunk_type: FileChunkType::SingleFile,
                 storage_backend: FileStorageType::Disk,
+                mode: DEFAULT_FILE_MODE,
             }),
         }
     }
@@ -283,6 +310,7 @@ impl fmt::Debug for FileNode {
         writeln!(f, "\tchunk_hashes: {:?}", self.chunk_hashes())?;
         writeln!(f, "\tchunk_type: {:?}", self.chunk_type())?;
         writeln!(f, "\tstorage_backend: {:?}", self.storage_backend())?;
+        writeln!(f, "\tmode: {:o}", self.mode())?;
         writeln!(f, "\tlast_commit_id: {}", self.last_commit_id())?;
         writeln!(
             f,
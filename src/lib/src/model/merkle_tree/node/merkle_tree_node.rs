@@ -25,18 +25,10 @@ pub struct MerkleTreeNode {
 impl MerkleTreeNode {
     /// Create an empty root node with a hash
     pub fn from_hash(repo: &LocalRepository, hash: &MerkleHash) -> Result<Self, OxenError> {
-        // Check cache first
-        if let Some(cached_node) = merkle_tree_node_cache::get_cached_node(repo, hash) {
-            return Ok((*cached_node).clone());
-        }
-
-        // If not in cache, load from disk
-        let node = Self::from_hash_uncached(repo, hash)?;
-
-        // Cache the result
-        merkle_tree_node_cache::cache_node(repo, *hash, node.clone());
-
-        Ok(node)
+        let node = merkle_tree_node_cache::get_or_load_node(repo, hash, || {
+            Self::from_hash_uncached(repo, hash)
+        })?;
+        Ok((*node).clone())
     }
 
     /// Private implementation that loads from disk without caching
@@ -55,18 +47,10 @@ impl MerkleTreeNode {
         repo: &LocalRepository,
         hash: &MerkleHash,
     ) -> Result<Vec<(MerkleHash, MerkleTreeNode)>, OxenError> {
-        // Check cache first
-        if let Some(cached_children) = merkle_tree_node_cache::get_cached_children(repo, hash) {
-            return Ok((*cached_children).clone());
-        }
-
-        // If not in cache, load from disk
-        let children = Self::read_children_from_hash_uncached(repo, hash)?;
-
-        // Cache the result
-        merkle_tree_node_cache::cache_children(repo, *hash, children.clone());
-
-        Ok(children)
+        let children = merkle_tree_node_cache::get_or_load_children(repo, hash, || {
+            Self::read_children_from_hash_uncached(repo, hash)
+        })?;
+        Ok((*children).clone())
     }
 
     /// Private implementation that loads from disk without caching
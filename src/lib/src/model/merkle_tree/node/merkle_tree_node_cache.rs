@@ -18,13 +18,25 @@
 //!
 //! # Cache Size Configuration
 //!
-//! The default cache size can be configured using the `OXEN_DEFAULT_MERKLE_CACHE_SIZE`
-//! environment variable. If not set or invalid, it defaults to 1000 entries per cache.
+//! Each repository's cache holds up to a budget of estimated bytes, 256MB by default, and
+//! evicts the least recently used nodes past it. The default is set with the
+//! `OXEN_MERKLE_CACHE_MAX_BYTES` environment variable or `merkle_cache_max_bytes` in the
+//! server's `--cache-config`, and a repository can set its own under `[cache]` in its
+//! config.
 //!
 //! ```bash
-//! export OXEN_DEFAULT_MERKLE_CACHE_SIZE=5000
+//! export OXEN_MERKLE_CACHE_MAX_BYTES=1073741824
 //! ```
 //!
+//! `stats` and `total_stats` report the hits, misses and evictions of the caches.
+//!
+//! # Concurrency
+//!
+//! Each repository's cache is split into shards that are locked separately, and nodes are
+//! read from disk without holding any of them, so server requests loading different parts
+//! of a tree run side by side. `get_or_load_node` and `get_or_load_children` make threads
+//! that miss on the same node wait for one of them to load it.
+//!
 //! # Temporarily Disabling Cache
 //!
 //! Even when enabled, you can temporarily disable caching for specific operations:
@@ -39,15 +51,15 @@
 
 use std::cell::Cell;
 use std::collections::HashMap;
-use std::num::NonZeroUsize;
 use std::path::PathBuf;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::{Arc, LazyLock};
 
 use lru::LruCache;
-use parking_lot::Mutex;
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
 
-use super::MerkleTreeNode;
+use super::{EMerkleTreeNode, MerkleTreeNode};
 use crate::error::OxenError;
 use crate::model::{LocalRepository, MerkleHash};
 
@@ -79,6 +91,11 @@ pub fn enable() {
     CACHE_ENABLED.store(true, Ordering::Relaxed);
 }
 
+/// Whether the cache was enabled for this process
+pub fn is_enabled() -> bool {
+    CACHE_ENABLED.load(Ordering::Relaxed)
+}
+
 /// Check if caching is currently enabled
 fn is_cache_enabled() -> bool {
     CACHE_ENABLED.load(Ordering::Relaxed) && !THREAD_CACHE_DISABLED.with(|c| c.get())
@@ -133,57 +150,277 @@ where
     f()
 }
 
-// Default cache size if not specified via environment variable
-const DEFAULT_CACHE_SIZE: usize = 1000;
+// Default byte budget of each repository's cache if not configured
+const DEFAULT_CACHE_MAX_BYTES: usize = 256 * 1024 * 1024;
 
-/// Cache size configured at startup from environment variable
-pub static CACHE_SIZE: LazyLock<NonZeroUsize> = LazyLock::new(|| {
-    std::env::var("OXEN_DEFAULT_MERKLE_CACHE_SIZE")
+/// Byte budget of each repository's cache, read from the environment at startup and
+/// overridden by `set_max_bytes`
+static CACHE_MAX_BYTES: LazyLock<AtomicUsize> = LazyLock::new(|| {
+    let max_bytes = std::env::var("OXEN_MERKLE_CACHE_MAX_BYTES")
         .ok()
         .and_then(|s| s.parse::<usize>().ok())
-        .and_then(NonZeroUsize::new)
-        .unwrap_or_else(|| NonZeroUsize::new(DEFAULT_CACHE_SIZE).unwrap())
+        .unwrap_or(DEFAULT_CACHE_MAX_BYTES);
+    AtomicUsize::new(max_bytes)
 });
 
-// Type aliases for readability
-type NodeCache = Arc<Mutex<LruCache<MerkleHash, Arc<MerkleTreeNode>>>>;
-type ChildrenCache = Arc<Mutex<LruCache<MerkleHash, Arc<Vec<(MerkleHash, MerkleTreeNode)>>>>>;
-type NodeCacheMap = HashMap<PathBuf, NodeCache>;
-type ChildrenCacheMap = HashMap<PathBuf, ChildrenCache>;
+/// The byte budget of each repository's cache, unless the repository sets its own
+pub fn max_bytes() -> usize {
+    CACHE_MAX_BYTES.load(Ordering::Relaxed)
+}
+
+/// Set the byte budget of the caches of repositories that don't set their own. Caches that
+/// already exist keep the budget they were created with.
+pub fn set_max_bytes(max_bytes: usize) {
+    CACHE_MAX_BYTES.store(max_bytes, Ordering::Relaxed);
+}
 
-// Cache for individual nodes (from_hash results)
-static NODE_CACHES: LazyLock<Mutex<NodeCacheMap>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+#[derive(Clone, Copy, Hash, Eq, PartialEq)]
+enum CacheKey {
+    // from_hash results
+    Node(MerkleHash),
+    // read_children_from_hash results
+    Children(MerkleHash),
+}
 
-// Cache for children reads (read_children_from_hash results)
-static CHILDREN_CACHES: LazyLock<Mutex<ChildrenCacheMap>> =
-    LazyLock::new(|| Mutex::new(HashMap::new()));
+#[derive(Clone)]
+enum CacheValue {
+    Node(Arc<MerkleTreeNode>),
+    Children(Arc<Vec<(MerkleHash, MerkleTreeNode)>>),
+}
 
-/// Get or create a node cache for a repository
-pub fn get_node_cache(repo: &LocalRepository) -> NodeCache {
-    let mut caches = NODE_CACHES.lock();
-    caches
-        .entry(repo.path.clone())
-        .or_insert_with(|| Arc::new(Mutex::new(LruCache::new(*CACHE_SIZE))))
-        .clone()
+struct CacheEntry {
+    value: CacheValue,
+    num_bytes: usize,
+}
+
+/// Hits, misses and evictions of a cache, and how much it holds
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    pub entries: usize,
+    pub bytes: usize,
+    pub max_bytes: usize,
+}
+
+impl CacheStats {
+    fn add(&mut self, other: &CacheStats) {
+        self.hits += other.hits;
+        self.misses += other.misses;
+        self.evictions += other.evictions;
+        self.entries += other.entries;
+        self.bytes += other.bytes;
+        self.max_bytes += other.max_bytes;
+    }
+}
+
+// Number of independently locked shards each repository's cache is split into
+const NUM_SHARDS: usize = 16;
+
+// Nodes and children share one LRU per shard so the least recently used of either is
+// evicted first
+struct CacheShard {
+    entries: LruCache<CacheKey, CacheEntry>,
+    stats: CacheStats,
+}
+
+impl CacheShard {
+    fn new(max_bytes: usize) -> Self {
+        CacheShard {
+            entries: LruCache::unbounded(),
+            stats: CacheStats {
+                max_bytes,
+                ..Default::default()
+            },
+        }
+    }
+
+    fn get(&mut self, key: &CacheKey) -> Option<CacheValue> {
+        match self.entries.get(key) {
+            Some(entry) => {
+                self.stats.hits += 1;
+                Some(entry.value.clone())
+            }
+            None => {
+                self.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    fn put(&mut self, key: CacheKey, value: CacheValue, num_bytes: usize) {
+        // An entry bigger than the whole budget would evict everything and then itself
+        if num_bytes > self.stats.max_bytes {
+            return;
+        }
+        if let Some(old) = self.entries.put(key, CacheEntry { value, num_bytes }) {
+            self.stats.bytes -= old.num_bytes;
+        }
+        self.stats.bytes += num_bytes;
+        while self.stats.bytes > self.stats.max_bytes {
+            let Some((_, evicted)) = self.entries.pop_lru() else {
+                break;
+            };
+            self.stats.bytes -= evicted.num_bytes;
+            self.stats.evictions += 1;
+        }
+        self.stats.entries = self.entries.len();
+    }
+}
+
+// A repository's cache is split into shards by hash so that threads reading different
+// parts of the tree don't wait on each other. Loads from disk happen outside of the shard
+// locks, only threads loading the same node wait for each other.
+struct RepoCache {
+    shards: Vec<Mutex<CacheShard>>,
+    loading: Vec<Mutex<HashMap<CacheKey, Arc<Mutex<()>>>>>,
 }
 
-/// Get or create a children cache for a repository
-pub fn get_children_cache(repo: &LocalRepository) -> ChildrenCache {
-    let mut caches = CHILDREN_CACHES.lock();
+impl RepoCache {
+    fn new(max_bytes: usize) -> Self {
+        RepoCache {
+            shards: (0..NUM_SHARDS)
+                .map(|_| Mutex::new(CacheShard::new(max_bytes / NUM_SHARDS)))
+                .collect(),
+            loading: (0..NUM_SHARDS)
+                .map(|_| Mutex::new(HashMap::new()))
+                .collect(),
+        }
+    }
+
+    fn shard_index(key: &CacheKey) -> usize {
+        let hash = match key {
+            CacheKey::Node(hash) | CacheKey::Children(hash) => hash.to_u128(),
+        };
+        (hash % NUM_SHARDS as u128) as usize
+    }
+
+    fn get(&self, key: &CacheKey) -> Option<CacheValue> {
+        self.shards[Self::shard_index(key)].lock().get(key)
+    }
+
+    fn put(&self, key: CacheKey, value: CacheValue, num_bytes: usize) {
+        self.shards[Self::shard_index(&key)]
+            .lock()
+            .put(key, value, num_bytes);
+    }
+
+    // Look up an entry that another thread may have just loaded, without counting a hit or miss
+    fn peek(&self, key: &CacheKey) -> Option<CacheValue> {
+        self.shards[Self::shard_index(key)]
+            .lock()
+            .entries
+            .get(key)
+            .map(|entry| entry.value.clone())
+    }
+
+    fn load_lock(&self, key: CacheKey) -> Arc<Mutex<()>> {
+        self.loading[Self::shard_index(&key)]
+            .lock()
+            .entry(key)
+            .or_default()
+            .clone()
+    }
+
+    fn done_loading(&self, key: &CacheKey) {
+        self.loading[Self::shard_index(key)].lock().remove(key);
+    }
+
+    // Get an entry, or load it with only one thread loading each key at a time
+    fn get_or_load(
+        &self,
+        key: CacheKey,
+        load: impl FnOnce() -> Result<(CacheValue, usize), OxenError>,
+    ) -> Result<CacheValue, OxenError> {
+        if let Some(value) = self.get(&key) {
+            return Ok(value);
+        }
+
+        let load_lock = self.load_lock(key);
+        let _guard = load_lock.lock();
+        if let Some(value) = self.peek(&key) {
+            return Ok(value);
+        }
+        let result = load();
+        if let Ok((value, num_bytes)) = &result {
+            self.put(key, value.clone(), *num_bytes);
+        }
+        self.done_loading(&key);
+        result.map(|(value, _)| value)
+    }
+
+    fn stats(&self) -> CacheStats {
+        let mut stats = CacheStats::default();
+        for shard in &self.shards {
+            stats.add(&shard.lock().stats);
+        }
+        stats
+    }
+}
+
+// One cache per repository
+static REPO_CACHES: LazyLock<RwLock<HashMap<PathBuf, Arc<RepoCache>>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+// Get or create the cache of a repository. A repository can set its own byte budget with
+// `merkle_cache_max_bytes` under `[cache]` in its config.
+fn get_repo_cache(repo: &LocalRepository) -> Arc<RepoCache> {
+    if let Some(cache) = REPO_CACHES.read().get(&repo.path) {
+        return cache.clone();
+    }
+    let mut caches = REPO_CACHES.write();
     caches
         .entry(repo.path.clone())
-        .or_insert_with(|| Arc::new(Mutex::new(LruCache::new(*CACHE_SIZE))))
+        .or_insert_with(|| {
+            let budget = repo
+                .cache_config()
+                .merkle_cache_max_bytes
+                .map(|budget| budget as usize)
+                .unwrap_or_else(max_bytes);
+            Arc::new(RepoCache::new(budget))
+        })
         .clone()
 }
 
+/// Rough number of bytes a node and its loaded children take in memory
+pub fn estimated_bytes(node: &MerkleTreeNode) -> usize {
+    let data = match &node.node {
+        EMerkleTreeNode::File(file_node) => {
+            file_node.name().len()
+                + file_node.mime_type().len()
+                + file_node.extension().len()
+                + file_node.chunk_hashes().len() * std::mem::size_of::<u128>()
+        }
+        EMerkleTreeNode::Directory(dir_node) => {
+            dir_node.name().len() + dir_node.data_type_counts().len() * 64
+        }
+        EMerkleTreeNode::Commit(commit_node) => {
+            commit_node.message().len() + commit_node.author().len() + commit_node.email().len()
+        }
+        EMerkleTreeNode::VNode(_) | EMerkleTreeNode::FileChunk(_) => 0,
+    };
+    std::mem::size_of::<MerkleTreeNode>()
+        + data
+        + node.children.iter().map(estimated_bytes).sum::<usize>()
+}
+
+fn estimated_children_bytes(children: &[(MerkleHash, MerkleTreeNode)]) -> usize {
+    children
+        .iter()
+        .map(|(_, child)| std::mem::size_of::<MerkleHash>() + estimated_bytes(child))
+        .sum()
+}
+
 /// Get a node from cache
 pub fn get_cached_node(repo: &LocalRepository, hash: &MerkleHash) -> Option<Arc<MerkleTreeNode>> {
     if !is_cache_enabled() {
         return None;
     }
-    let cache = get_node_cache(repo);
-    let mut cache_guard = cache.lock();
-    cache_guard.get(hash).cloned()
+    match get_repo_cache(repo).get(&CacheKey::Node(*hash)) {
+        Some(CacheValue::Node(node)) => Some(node),
+        _ => None,
+    }
 }
 
 /// Put a node in cache
@@ -196,12 +433,38 @@ pub fn cache_node(
     if !is_cache_enabled() {
         return arc_node;
     }
-    let cache = get_node_cache(repo);
-    let mut cache_guard = cache.lock();
-    cache_guard.put(hash, arc_node.clone());
+    let num_bytes = estimated_bytes(&arc_node);
+    get_repo_cache(repo).put(
+        CacheKey::Node(hash),
+        CacheValue::Node(arc_node.clone()),
+        num_bytes,
+    );
     arc_node
 }
 
+/// Get a node from cache, or load it with `load` and cache it. Threads asking for the same
+/// node at the same time wait for the first one to load it rather than all reading it.
+pub fn get_or_load_node(
+    repo: &LocalRepository,
+    hash: &MerkleHash,
+    load: impl FnOnce() -> Result<MerkleTreeNode, OxenError>,
+) -> Result<Arc<MerkleTreeNode>, OxenError> {
+    if !is_cache_enabled() {
+        return Ok(Arc::new(load()?));
+    }
+    let value = get_repo_cache(repo).get_or_load(CacheKey::Node(*hash), || {
+        let node = load()?;
+        let num_bytes = estimated_bytes(&node);
+        Ok((CacheValue::Node(Arc::new(node)), num_bytes))
+    })?;
+    match value {
+        CacheValue::Node(node) => Ok(node),
+        CacheValue::Children(_) => Err(OxenError::basic_str(format!(
+            "Merkle node cache has children under node key {hash}"
+        ))),
+    }
+}
+
 /// Get children from cache
 pub fn get_cached_children(
     repo: &LocalRepository,
@@ -210,9 +473,10 @@ pub fn get_cached_children(
     if !is_cache_enabled() {
         return None;
     }
-    let cache = get_children_cache(repo);
-    let mut cache_guard = cache.lock();
-    cache_guard.get(hash).cloned()
+    match get_repo_cache(repo).get(&CacheKey::Children(*hash)) {
+        Some(CacheValue::Children(children)) => Some(children),
+        _ => None,
+    }
 }
 
 /// Put children in cache
@@ -225,28 +489,61 @@ pub fn cache_children(
     if !is_cache_enabled() {
         return arc_children;
     }
-    let cache = get_children_cache(repo);
-    let mut cache_guard = cache.lock();
-    cache_guard.put(hash, arc_children.clone());
+    let num_bytes = estimated_children_bytes(&arc_children);
+    get_repo_cache(repo).put(
+        CacheKey::Children(hash),
+        CacheValue::Children(arc_children.clone()),
+        num_bytes,
+    );
     arc_children
 }
 
-/// Remove a repository's caches
-pub fn remove_from_cache(repository_path: impl AsRef<std::path::Path>) -> Result<(), OxenError> {
-    let path = repository_path.as_ref().to_path_buf();
-
-    // Remove from node caches
-    {
-        let mut caches = NODE_CACHES.lock();
-        caches.remove(&path);
+/// Get the children of a node from cache, or load them with `load` and cache them, with
+/// only one thread loading the children of a node at a time
+pub fn get_or_load_children(
+    repo: &LocalRepository,
+    hash: &MerkleHash,
+    load: impl FnOnce() -> Result<Vec<(MerkleHash, MerkleTreeNode)>, OxenError>,
+) -> Result<Arc<Vec<(MerkleHash, MerkleTreeNode)>>, OxenError> {
+    if !is_cache_enabled() {
+        return Ok(Arc::new(load()?));
     }
+    let value = get_repo_cache(repo).get_or_load(CacheKey::Children(*hash), || {
+        let children = load()?;
+        let num_bytes = estimated_children_bytes(&children);
+        Ok((CacheValue::Children(Arc::new(children)), num_bytes))
+    })?;
+    match value {
+        CacheValue::Children(children) => Ok(children),
+        CacheValue::Node(_) => Err(OxenError::basic_str(format!(
+            "Merkle node cache has a node under children key {hash}"
+        ))),
+    }
+}
 
-    // Remove from children caches
-    {
-        let mut caches = CHILDREN_CACHES.lock();
-        caches.remove(&path);
+/// The stats of a repository's cache
+pub fn stats(repo: &LocalRepository) -> CacheStats {
+    REPO_CACHES
+        .read()
+        .get(&repo.path)
+        .map(|cache| cache.stats())
+        .unwrap_or_default()
+}
+
+/// The stats of the caches of all repositories added together, and how many there are
+pub fn total_stats() -> (usize, CacheStats) {
+    let caches = REPO_CACHES.read();
+    let mut total = CacheStats::default();
+    for cache in caches.values() {
+        total.add(&cache.stats());
     }
+    (caches.len(), total)
+}
 
+/// Remove a repository's caches
+pub fn remove_from_cache(repository_path: impl AsRef<std::path::Path>) -> Result<(), OxenError> {
+    let path = repository_path.as_ref().to_path_buf();
+    REPO_CACHES.write().remove(&path);
     Ok(())
 }
 
@@ -254,6 +551,7 @@ pub fn remove_from_cache(repository_path: impl AsRef<std::path::Path>) -> Result
 #[serial_test::serial]
 mod tests {
     use super::*;
+    use crate::config::CacheConfig;
     use crate::{repositories, test};
 
     #[test]
@@ -368,4 +666,86 @@ mod tests {
             Ok(())
         })
     }
+
+    #[test]
+    fn test_cache_evicts_past_max_bytes() -> Result<(), OxenError> {
+        test::run_empty_dir_test(|dir| {
+            enable();
+
+            let node = MerkleTreeNode::default();
+            let num_bytes = estimated_bytes(&node);
+            let mut repo = repositories::init(dir)?;
+            // Room for 3 nodes in each shard
+            let max_bytes = NUM_SHARDS * 3 * num_bytes;
+            repo.set_cache_config(Some(CacheConfig {
+                merkle_cache_max_bytes: Some(max_bytes as u64),
+                ..Default::default()
+            }));
+
+            // These all land in the first shard
+            let hashes: Vec<MerkleHash> = (0..4)
+                .map(|i| MerkleHash::new(i * NUM_SHARDS as u128))
+                .collect();
+            for hash in &hashes {
+                cache_node(&repo, *hash, node.clone());
+            }
+            // The first node was the least recently used
+            assert!(get_cached_node(&repo, &hashes[0]).is_none());
+            assert!(get_cached_node(&repo, &hashes[3]).is_some());
+
+            let cache_stats = stats(&repo);
+            assert_eq!(cache_stats.entries, 3);
+            assert_eq!(cache_stats.bytes, 3 * num_bytes);
+            assert_eq!(cache_stats.max_bytes, max_bytes);
+            assert_eq!(cache_stats.evictions, 1);
+            assert_eq!(cache_stats.hits, 1);
+            assert_eq!(cache_stats.misses, 1);
+
+            remove_from_cache(&repo.path)?;
+            assert_eq!(stats(&repo), CacheStats::default());
+
+            // Reset to disabled for other tests
+            CACHE_ENABLED.store(false, Ordering::Relaxed);
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_concurrent_loads_of_a_node_read_it_once() -> Result<(), OxenError> {
+        use std::sync::atomic::AtomicUsize;
+        use std::thread;
+        use std::time::Duration;
+
+        test::run_empty_dir_test(|dir| {
+            enable();
+
+            let repo = Arc::new(repositories::init(dir)?);
+            let hash = MerkleHash::new(55555);
+            let num_loads = Arc::new(AtomicUsize::new(0));
+
+            let handles: Vec<_> = (0..8)
+                .map(|_| {
+                    let repo = Arc::clone(&repo);
+                    let num_loads = Arc::clone(&num_loads);
+                    thread::spawn(move || {
+                        get_or_load_node(&repo, &hash, || {
+                            num_loads.fetch_add(1, Ordering::SeqCst);
+                            thread::sleep(Duration::from_millis(50));
+                            Ok(MerkleTreeNode::default())
+                        })
+                    })
+                })
+                .collect();
+            for handle in handles {
+                handle.join().unwrap()?;
+            }
+            assert_eq!(num_loads.load(Ordering::SeqCst), 1);
+
+            // Reset to disabled for other tests
+            CACHE_ENABLED.store(false, Ordering::Relaxed);
+
+            Ok(())
+        })
+    }
 }
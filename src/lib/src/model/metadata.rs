@@ -2,6 +2,7 @@
 pub mod metadata_audio;
 pub mod metadata_dir;
 pub mod metadata_image;
+pub mod metadata_pdf;
 pub mod metadata_tabular;
 pub mod metadata_text;
 pub mod metadata_video;
@@ -12,7 +13,8 @@ pub mod to_duckdb_sql;
 
 pub use metadata_audio::MetadataAudio;
 pub use metadata_dir::MetadataDir;
-pub use metadata_image::MetadataImage;
+pub use metadata_image::{MetadataImage, MetadataImageExif};
+pub use metadata_pdf::MetadataPdf;
 pub use metadata_tabular::MetadataTabular;
 pub use metadata_text::MetadataText;
 pub use metadata_video::MetadataVideo;
@@ -1,7 +1,8 @@
 use serde::{Deserialize, Serialize};
 
 use crate::model::metadata::{
-    MetadataAudio, MetadataDir, MetadataImage, MetadataTabular, MetadataText, MetadataVideo,
+    MetadataAudio, MetadataDir, MetadataImage, MetadataPdf, MetadataTabular, MetadataText,
+    MetadataVideo,
 };
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -13,6 +14,7 @@ pub enum GenericMetadata {
     MetadataVideo(MetadataVideo),
     MetadataAudio(MetadataAudio),
     MetadataTabular(MetadataTabular),
+    MetadataPdf(MetadataPdf),
 }
 
 impl std::fmt::Display for GenericMetadata {
@@ -24,6 +26,7 @@ impl std::fmt::Display for GenericMetadata {
             GenericMetadata::MetadataVideo(metadata) => write!(f, "{}", metadata),
             GenericMetadata::MetadataAudio(metadata) => write!(f, "{}", metadata),
             GenericMetadata::MetadataTabular(metadata) => write!(f, "{}", metadata),
+            GenericMetadata::MetadataPdf(metadata) => write!(f, "{}", metadata),
         }
     }
 }
@@ -31,6 +31,29 @@ pub struct MetadataImageImpl {
     pub width: u32,
     pub height: u32,
     pub color_space: Option<ImgColorSpace>,
+    #[serde(default)]
+    pub exif: Option<MetadataImageExif>,
+}
+
+/// The EXIF tags most useful for filtering images, missing tags are None
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+pub struct MetadataImageExif {
+    pub make: Option<String>,
+    pub model: Option<String>,
+    /// When the photo was taken, formatted as `YYYY-MM-DD HH:MM:SS`
+    pub taken_at: Option<String>,
+    /// EXIF orientation 1-8, 1 is upright
+    pub orientation: Option<u32>,
+    /// Exposure time in seconds
+    pub exposure_time: Option<f64>,
+    pub f_number: Option<f64>,
+    pub iso: Option<u32>,
+    /// Focal length in millimeters
+    pub focal_length: Option<f64>,
+    /// Decimal degrees, negative south of the equator
+    pub gps_latitude: Option<f64>,
+    /// Decimal degrees, negative west of Greenwich
+    pub gps_longitude: Option<f64>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -46,6 +69,7 @@ impl MetadataImage {
                 width,
                 height,
                 color_space: None,
+                exif: None,
             },
         }
     }
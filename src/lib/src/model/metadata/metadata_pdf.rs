@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct MetadataPdf {
+    pub pdf: MetadataPdfImpl,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct MetadataPdfImpl {
+    pub num_pages: usize,
+}
+
+impl MetadataPdf {
+    pub fn new(num_pages: usize) -> Self {
+        Self {
+            pdf: MetadataPdfImpl { num_pages },
+        }
+    }
+}
+
+impl std::fmt::Display for MetadataPdf {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "MetadataPdf({} pages)", self.pdf.num_pages)
+    }
+}
@@ -10,6 +10,9 @@ pub struct MetadataVideoImpl {
     pub num_seconds: f64,
     pub width: usize,
     pub height: usize,
+    /// Codec of the first video track, such as `h264`
+    #[serde(default)]
+    pub codec: Option<String>,
 }
 
 impl MetadataVideo {
@@ -19,6 +22,7 @@ impl MetadataVideo {
                 num_seconds,
                 width,
                 height,
+                codec: None,
             },
         }
     }
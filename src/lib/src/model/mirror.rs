@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MirrorState {
+    /// Configured but not synced yet
+    Pending,
+    Syncing,
+    Synced,
+    Failed,
+}
+
+/// Where a mirrored repository is at with its upstream
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MirrorStatus {
+    pub namespace: String,
+    pub name: String,
+    /// Url of the upstream repository
+    pub url: String,
+    pub state: MirrorState,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub last_started_at: Option<OffsetDateTime>,
+    /// When the mirror last finished a sync without errors
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub last_synced_at: Option<OffsetDateTime>,
+    pub last_error: Option<String>,
+    pub num_branches: usize,
+    pub num_tags: usize,
+}
@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+/// One movement of a branch or HEAD, as recorded in the reflog
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ReflogEntry {
+    /// Branch name, or `HEAD`
+    pub ref_name: String,
+    /// Commit the ref pointed to before, `None` when it was created
+    pub old_id: Option<String>,
+    /// Commit the ref points to now, `None` when it was deleted
+    pub new_id: Option<String>,
+    /// What moved the ref, such as `commit: Add images` or `checkout: moving from main to dev`
+    pub action: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub timestamp: OffsetDateTime,
+}
+
+impl ReflogEntry {
+    pub fn new(
+        ref_name: impl AsRef<str>,
+        old_id: Option<String>,
+        new_id: Option<String>,
+        action: impl AsRef<str>,
+    ) -> ReflogEntry {
+        ReflogEntry {
+            ref_name: ref_name.as_ref().to_string(),
+            old_id,
+            new_id,
+            action: action.as_ref().to_string(),
+            timestamp: OffsetDateTime::now_utc(),
+        }
+    }
+}
@@ -1,15 +1,20 @@
-use crate::config::RepositoryConfig;
-use crate::constants::SHALLOW_FLAG;
+use crate::config::{
+    CacheConfig, CaseConflictMode, CommitMessageConfig, MergeConfig, RemoteConfig,
+    RepositoryConfig, RetentionConfig,
+};
 use crate::constants::{self, DEFAULT_VNODE_SIZE, MIN_OXEN_VERSION};
+use crate::constants::{SHALLOW_BOUNDARY_FILE, SHALLOW_FLAG};
 use crate::core::versions::MinOxenVersion;
 use crate::error;
 use crate::error::OxenError;
 use crate::model::{MetadataEntry, Remote, RemoteRepository};
 use crate::storage::{create_version_store, StorageConfig, VersionStore};
 use crate::util;
+use crate::util::hasher::HashAlgorithm;
 use crate::view::RepositoryView;
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::fmt::Debug;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
@@ -27,6 +32,14 @@ pub struct LocalRepository {
     pub remote_mode: Option<bool>, // Flag for remote repositories
     pub workspace_name: Option<String>, // ID of the associated workspace for remote mode
     workspaces: Option<Vec<String>>, // List of workspaces for remote mode
+    merge: Option<MergeConfig>, // Merge rules for the repository
+    commit_message: Option<CommitMessageConfig>, // Commit message template and rules
+    cache: Option<CacheConfig>, // Which post-commit cachers run on the server
+    retention: Option<RetentionConfig>, // How long the version files of old commits are kept
+    hash_algorithm: Option<HashAlgorithm>, // Algorithm used to hash file contents
+    read_only: Option<bool>, // Reject all mutating operations, for archived or published datasets
+    remote: Option<RemoteConfig>, // How the repository talks to its remotes
+    case_conflicts: Option<CaseConflictMode>, // What to do with paths that only differ by case
 
     // Skip this field during serialization/deserialization
     #[serde(skip)]
@@ -58,6 +71,14 @@ impl LocalRepository {
             remote_mode: config.remote_mode,
             workspace_name: config.workspace_name,
             workspaces: config.workspaces,
+            merge: config.merge,
+            commit_message: config.commit_message,
+            cache: config.cache,
+            retention: config.retention,
+            hash_algorithm: config.hash_algorithm,
+            read_only: config.read_only,
+            remote: config.remote,
+            case_conflicts: config.case_conflicts,
         };
 
         // Initialize the version store based on config
@@ -89,6 +110,13 @@ impl LocalRepository {
         Ok(())
     }
 
+    /// Keep version files in the given store, for example an S3VersionStore so all the
+    /// versioned blobs live in a bucket. The store settings are written to the config on save.
+    pub fn with_version_store(mut self, store: Arc<dyn VersionStore>) -> Self {
+        self.version_store = Some(store);
+        self
+    }
+
     /// Initialize the default version store
     pub fn init_default_version_store(&mut self) -> Result<(), OxenError> {
         let store = create_version_store(&self.path, None)?;
@@ -123,6 +151,14 @@ impl LocalRepository {
             remote_mode: None,
             workspace_name: None,
             workspaces: None,
+            merge: None,
+            commit_message: None,
+            cache: None,
+            retention: None,
+            hash_algorithm: None,
+            read_only: None,
+            remote: None,
+            case_conflicts: None,
         };
 
         repo.init_default_version_store()?;
@@ -146,6 +182,14 @@ impl LocalRepository {
             remote_mode: None,
             workspace_name: None,
             workspaces: None,
+            merge: None,
+            commit_message: None,
+            cache: None,
+            retention: None,
+            hash_algorithm: None,
+            read_only: None,
+            remote: None,
+            case_conflicts: None,
         };
 
         repo.init_default_version_store()?;
@@ -165,6 +209,14 @@ impl LocalRepository {
             remote_mode: None,
             workspace_name: None,
             workspaces: None,
+            merge: None,
+            commit_message: None,
+            cache: None,
+            retention: None,
+            hash_algorithm: None,
+            read_only: None,
+            remote: None,
+            case_conflicts: None,
         };
 
         repo.init_default_version_store()?;
@@ -184,6 +236,14 @@ impl LocalRepository {
             remote_mode: None,
             workspace_name: None,
             workspaces: None,
+            merge: None,
+            commit_message: None,
+            cache: None,
+            retention: None,
+            hash_algorithm: None,
+            read_only: None,
+            remote: None,
+            case_conflicts: None,
         };
 
         local_repo.init_default_version_store()?;
@@ -238,6 +298,17 @@ impl LocalRepository {
         })
     }
 
+    /// True if the path is under one of the subtree paths, or if the whole tree is checked out
+    pub fn is_in_subtree_paths(&self, path: impl AsRef<Path>) -> bool {
+        let path = path.as_ref();
+        match self.subtree_paths() {
+            Some(subtree_paths) => subtree_paths
+                .iter()
+                .any(|subtree_path| path.starts_with(subtree_path)),
+            None => true,
+        }
+    }
+
     pub fn set_subtree_paths(&mut self, paths: Option<Vec<PathBuf>>) {
         self.subtree_paths = paths;
     }
@@ -258,6 +329,71 @@ impl LocalRepository {
         self.remote_mode.unwrap_or(false)
     }
 
+    pub fn merge_config(&self) -> MergeConfig {
+        self.merge.clone().unwrap_or_default()
+    }
+
+    pub fn set_merge_config(&mut self, merge: Option<MergeConfig>) {
+        self.merge = merge;
+    }
+
+    pub fn commit_message_config(&self) -> CommitMessageConfig {
+        self.commit_message.clone().unwrap_or_default()
+    }
+
+    pub fn set_commit_message_config(&mut self, commit_message: Option<CommitMessageConfig>) {
+        self.commit_message = commit_message;
+    }
+
+    pub fn cache_config(&self) -> CacheConfig {
+        self.cache.clone().unwrap_or_default()
+    }
+
+    pub fn set_cache_config(&mut self, cache: Option<CacheConfig>) {
+        self.cache = cache;
+    }
+
+    pub fn retention_config(&self) -> Option<RetentionConfig> {
+        self.retention.clone()
+    }
+
+    pub fn set_retention_config(&mut self, retention: Option<RetentionConfig>) {
+        self.retention = retention;
+    }
+
+    /// Repos created before the algorithm was recorded hash with xxh3
+    pub fn hash_algorithm(&self) -> HashAlgorithm {
+        self.hash_algorithm.unwrap_or_default()
+    }
+
+    pub fn set_hash_algorithm(&mut self, hash_algorithm: HashAlgorithm) {
+        self.hash_algorithm = Some(hash_algorithm);
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only.unwrap_or(false)
+    }
+
+    pub fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = Some(read_only);
+    }
+
+    pub fn remote_config(&self) -> RemoteConfig {
+        self.remote.clone().unwrap_or_default()
+    }
+
+    pub fn set_remote_config(&mut self, remote: Option<RemoteConfig>) {
+        self.remote = remote;
+    }
+
+    pub fn case_conflict_mode(&self) -> CaseConflictMode {
+        self.case_conflicts.unwrap_or_default()
+    }
+
+    pub fn set_case_conflict_mode(&mut self, mode: CaseConflictMode) {
+        self.case_conflicts = Some(mode);
+    }
+
     /// Save the repository configuration to disk
     pub fn save(&self) -> Result<(), OxenError> {
         let config_path = util::fs::config_filepath(&self.path);
@@ -266,6 +402,7 @@ impl LocalRepository {
         let storage = self.version_store.as_ref().map(|store| StorageConfig {
             type_: store.storage_type().to_string(),
             settings: store.storage_settings(),
+            compression_level: store.compression_level(),
         });
 
         let config = RepositoryConfig {
@@ -279,6 +416,14 @@ impl LocalRepository {
             remote_mode: self.remote_mode,
             workspace_name: self.workspace_name.clone(),
             workspaces: self.workspaces.clone(),
+            merge: self.merge.clone(),
+            commit_message: self.commit_message.clone(),
+            cache: self.cache.clone(),
+            retention: self.retention.clone(),
+            hash_algorithm: self.hash_algorithm,
+            read_only: self.read_only,
+            remote: self.remote.clone(),
+            case_conflicts: self.case_conflicts,
         };
 
         config.save(&config_path)
@@ -429,14 +574,56 @@ impl LocalRepository {
         }
         Ok(())
     }
+
+    /// The oldest commits of a clone with a limited history depth, whose parents are not
+    /// in the local repository. Empty if the full history was fetched.
+    pub fn shallow_boundary(&self) -> Result<HashSet<String>, OxenError> {
+        let boundary_path = util::fs::oxen_hidden_dir(&self.path).join(SHALLOW_BOUNDARY_FILE);
+        if !boundary_path.exists() {
+            return Ok(HashSet::new());
+        }
+        let contents = util::fs::read_from_path(&boundary_path)?;
+        Ok(contents
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .map(String::from)
+            .collect())
+    }
+
+    pub fn write_shallow_boundary(&self, commit_ids: &HashSet<String>) -> Result<(), OxenError> {
+        let boundary_path = util::fs::oxen_hidden_dir(&self.path).join(SHALLOW_BOUNDARY_FILE);
+        log::debug!("Write shallow boundary {commit_ids:?} to path: {boundary_path:?}");
+        if commit_ids.is_empty() {
+            if boundary_path.exists() {
+                util::fs::remove_file(&boundary_path)?;
+            }
+            return Ok(());
+        }
+        let mut commit_ids: Vec<&String> = commit_ids.iter().collect();
+        commit_ids.sort();
+        let contents: String = commit_ids.iter().map(|id| format!("{id}\n")).collect();
+        util::fs::write_to_path(&boundary_path, contents)?;
+        Ok(())
+    }
+
+    /// True if the repository was cloned with a limited history depth and not deepened since
+    pub fn has_shallow_history(&self) -> Result<bool, OxenError> {
+        Ok(!self.shallow_boundary()?.is_empty())
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::config::RepositoryConfig;
     use crate::error::OxenError;
     use crate::model::{LocalRepository, RepoNew};
+    use crate::storage::{S3VersionStore, StorageConfig};
     use crate::test;
+    use crate::util;
+    use std::collections::HashMap;
     use std::path::PathBuf;
+    use std::sync::Arc;
 
     #[test]
     fn test_get_dirname_from_url() -> Result<(), OxenError> {
@@ -522,4 +709,50 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_with_s3_version_store_saved_to_config() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            let store = S3VersionStore::new("oxen-versions", "repo")
+                .with_region("us-west-2")
+                .with_profile("oxen");
+            let repo = repo.with_version_store(Arc::new(store));
+            repo.save()?;
+
+            let repo = LocalRepository::from_dir(&repo.path)?;
+            let store = repo.version_store()?;
+            assert_eq!(store.storage_type(), "s3");
+            let settings = store.storage_settings();
+            assert_eq!(settings["bucket"], "oxen-versions");
+            assert_eq!(settings["prefix"], "repo");
+            assert_eq!(settings["region"], "us-west-2");
+            assert_eq!(settings["profile"], "oxen");
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_compression_level_from_config() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            let config_path = util::fs::config_filepath(&repo.path);
+            let mut config = RepositoryConfig::from_file(&config_path)?;
+            config.storage = Some(StorageConfig {
+                type_: "local".to_string(),
+                settings: HashMap::new(),
+                compression_level: Some(3),
+            });
+            config.save(&config_path)?;
+
+            let repo = LocalRepository::from_dir(&repo.path)?;
+            assert_eq!(repo.version_store()?.compression_level(), Some(3));
+
+            // Saving the repo keeps the level in the config
+            repo.save()?;
+            let repo = LocalRepository::from_dir(&repo.path)?;
+            assert_eq!(repo.version_store()?.compression_level(), Some(3));
+
+            Ok(())
+        })
+    }
 }
@@ -0,0 +1,36 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+/// A change to a file that was stashed, the contents are in the version store
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum StashedChange {
+    Modified { hash: String },
+    Removed,
+}
+
+/// The staged and unstaged changes to one file
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct StashedFile {
+    pub path: PathBuf,
+    /// What was staged, None if the file was not staged
+    pub staged: Option<StashedChange>,
+    /// What changed in the working directory on top of the staged or committed version
+    pub unstaged: Option<StashedChange>,
+}
+
+/// The changes saved by `oxen stash`, to be put back with `oxen stash pop`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct StashEntry {
+    pub id: String,
+    pub message: String,
+    /// Branch that was checked out, None if HEAD was detached
+    pub branch: Option<String>,
+    /// HEAD commit the changes were made on top of
+    pub commit_id: String,
+    pub files: Vec<StashedFile>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub timestamp: OffsetDateTime,
+}
@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+/// An annotated tag that marks a commit, such as a dataset release
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Tag {
+    pub name: String,
+    pub commit_id: String,
+    pub message: String,
+    pub author: String,
+    pub email: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub timestamp: OffsetDateTime,
+}
+
+impl std::fmt::Display for Tag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.name, self.commit_id)
+    }
+}
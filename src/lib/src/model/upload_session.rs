@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+/// A resumable upload of one version file. The server keeps the bytes received so far on
+/// disk, so a client that lost its connection, or a server that restarted, picks up at
+/// `offset` instead of starting over.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct UploadSession {
+    /// The content hash of the file, uploads of the same file share a session
+    pub id: String,
+    /// Total size of the file in bytes
+    pub size: u64,
+    /// Number of bytes received so far, the next chunk must start here
+    pub offset: u64,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+impl UploadSession {
+    pub fn is_complete(&self) -> bool {
+        self.offset == self.size
+    }
+}
@@ -1,7 +1,40 @@
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+use crate::error::OxenError;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct User {
     pub email: String,
     pub name: String,
 }
+
+/// Formats as `Name <email>`, the same form used for `--co-author`
+impl fmt::Display for User {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} <{}>", self.name, self.email)
+    }
+}
+
+impl FromStr for User {
+    type Err = OxenError;
+
+    /// Parse a user from `Name <email>`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let parsed = s
+            .strip_suffix('>')
+            .and_then(|s| s.rsplit_once('<'))
+            .map(|(name, email)| (name.trim(), email.trim()));
+        match parsed {
+            Some((name, email)) if !name.is_empty() && !email.is_empty() => Ok(User {
+                name: name.to_string(),
+                email: email.to_string(),
+            }),
+            _ => Err(OxenError::basic_str(format!(
+                "Invalid user {s:?}, must be of the form \"Name <email>\""
+            ))),
+        }
+    }
+}
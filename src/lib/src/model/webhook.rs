@@ -0,0 +1,137 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+use crate::error::OxenError;
+use crate::model::{Branch, Commit, Tag};
+
+/// What happened in a repository to fire a webhook
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    Push,
+    BranchCreated,
+    TagCreated,
+    WorkspaceCommit,
+}
+
+impl WebhookEvent {
+    pub fn all() -> Vec<WebhookEvent> {
+        vec![
+            WebhookEvent::Push,
+            WebhookEvent::BranchCreated,
+            WebhookEvent::TagCreated,
+            WebhookEvent::WorkspaceCommit,
+        ]
+    }
+}
+
+impl fmt::Display for WebhookEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WebhookEvent::Push => write!(f, "push"),
+            WebhookEvent::BranchCreated => write!(f, "branch_created"),
+            WebhookEvent::TagCreated => write!(f, "tag_created"),
+            WebhookEvent::WorkspaceCommit => write!(f, "workspace_commit"),
+        }
+    }
+}
+
+impl FromStr for WebhookEvent {
+    type Err = OxenError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "push" => Ok(WebhookEvent::Push),
+            "branch_created" => Ok(WebhookEvent::BranchCreated),
+            "tag_created" => Ok(WebhookEvent::TagCreated),
+            "workspace_commit" => Ok(WebhookEvent::WorkspaceCommit),
+            _ => Err(OxenError::basic_str(format!(
+                "Invalid webhook event {s:?}, must be one of push, branch_created, tag_created or workspace_commit"
+            ))),
+        }
+    }
+}
+
+/// An HTTP endpoint a repository calls when one of `events` happens
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Webhook {
+    pub id: String,
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    /// Key the payloads are signed with, the server never sends it back
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+}
+
+/// The json body sent to a webhook
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WebhookPayload {
+    pub event: WebhookEvent,
+    /// `namespace/name` of the repository the event happened in
+    pub repository: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub timestamp: OffsetDateTime,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace_id: Option<String>,
+}
+
+impl WebhookPayload {
+    fn new(event: WebhookEvent, repository: impl AsRef<str>) -> WebhookPayload {
+        WebhookPayload {
+            event,
+            repository: repository.as_ref().to_string(),
+            timestamp: OffsetDateTime::now_utc(),
+            branch: None,
+            commit_id: None,
+            tag: None,
+            workspace_id: None,
+        }
+    }
+
+    pub fn push(repository: impl AsRef<str>, branch: &Branch) -> WebhookPayload {
+        WebhookPayload {
+            branch: Some(branch.name.clone()),
+            commit_id: Some(branch.commit_id.clone()),
+            ..WebhookPayload::new(WebhookEvent::Push, repository)
+        }
+    }
+
+    pub fn branch_created(repository: impl AsRef<str>, branch: &Branch) -> WebhookPayload {
+        WebhookPayload {
+            branch: Some(branch.name.clone()),
+            commit_id: Some(branch.commit_id.clone()),
+            ..WebhookPayload::new(WebhookEvent::BranchCreated, repository)
+        }
+    }
+
+    pub fn tag_created(repository: impl AsRef<str>, tag: &Tag) -> WebhookPayload {
+        WebhookPayload {
+            tag: Some(tag.name.clone()),
+            commit_id: Some(tag.commit_id.clone()),
+            ..WebhookPayload::new(WebhookEvent::TagCreated, repository)
+        }
+    }
+
+    pub fn workspace_commit(
+        repository: impl AsRef<str>,
+        workspace_id: impl AsRef<str>,
+        branch_name: impl AsRef<str>,
+        commit: &Commit,
+    ) -> WebhookPayload {
+        WebhookPayload {
+            branch: Some(branch_name.as_ref().to_string()),
+            commit_id: Some(commit.id.clone()),
+            workspace_id: Some(workspace_id.as_ref().to_string()),
+            ..WebhookPayload::new(WebhookEvent::WorkspaceCommit, repository)
+        }
+    }
+}
@@ -0,0 +1,16 @@
+use crate::model::User;
+
+/// How rows appended to a tabular file are committed, see [`crate::model::AppendBuffer`]
+#[derive(Clone, Debug, Default)]
+pub struct AppendOpts {
+    /// Commit once this many rows are buffered, defaults to the buffer's current setting
+    pub commit_rows: Option<usize>,
+    /// Commit once the oldest buffered row is this many seconds old
+    pub commit_after_secs: Option<u64>,
+    /// Author of the commit
+    pub author: Option<User>,
+    /// Message of the commit
+    pub message: Option<String>,
+    /// Commit the buffered rows right away
+    pub commit: bool,
+}
@@ -0,0 +1,18 @@
+use std::collections::BTreeMap;
+
+use time::OffsetDateTime;
+
+use crate::model::User;
+
+/// Optional extras stored on a commit alongside the message and author
+#[derive(Clone, Debug, Default)]
+pub struct CommitOpts {
+    /// User defined key-values, such as `run_id=abc`
+    pub metadata: BTreeMap<String, String>,
+    /// Additional authors credited on the commit
+    pub co_authors: Vec<User>,
+    /// Skip the data validation rules in `.oxen/checks.toml`
+    pub no_verify: bool,
+    /// When the commit was made, now if not set. Used to keep the dates of imported history.
+    pub timestamp: Option<OffsetDateTime>,
+}
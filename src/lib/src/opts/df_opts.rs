@@ -50,6 +50,12 @@ pub struct DFOpts {
     pub item: Option<String>,
     pub quote_char: Option<String>,
     pub repo_dir: Option<PathBuf>,
+    /// Fraction of the rows to randomly keep, between 0 and 1
+    pub sample: Option<f64>,
+    /// Seed for `sample` and `should_randomize`, so the same rows are picked each time
+    pub seed: Option<u64>,
+    /// Sheet to read from an Excel workbook, defaults to the first sheet
+    pub sheet: Option<String>,
     pub should_randomize: bool,
     pub should_reverse: bool,
     pub should_page: bool,
@@ -100,6 +106,9 @@ impl DFOpts {
             row: None,
             quote_char: None,
             repo_dir: None,
+            sample: None,
+            seed: None,
+            sheet: None,
             should_page: false,
             should_randomize: false,
             should_reverse: false,
@@ -156,6 +165,7 @@ impl DFOpts {
             || self.text2sql.is_some()
             || self.unique.is_some()
             || self.filter.is_some()
+            || self.sample.is_some()
     }
 
     pub fn has_transform(&self) -> bool {
@@ -169,6 +179,7 @@ impl DFOpts {
             || self.page_size.is_some()
             || self.page.is_some()
             || self.row.is_some()
+            || self.sample.is_some()
             || self.should_randomize
             || self.should_reverse
             || self.sort_by.is_some()
@@ -226,6 +237,21 @@ impl DFOpts {
         None
     }
 
+    /// Columns to sort by and whether each is descending, from `sort_by` such as `label,-score`
+    /// where a leading `-` sorts that column descending
+    pub fn sort_by_columns(&self) -> Option<Vec<(String, bool)>> {
+        let sort_by = self.sort_by.as_ref()?;
+        let columns = sort_by
+            .split(',')
+            .filter(|name| !name.is_empty())
+            .map(|name| match name.strip_prefix('-') {
+                Some(name) => (name.to_string(), true),
+                None => (name.to_string(), false),
+            })
+            .collect();
+        Some(columns)
+    }
+
     pub fn unique_columns(&self) -> Option<Vec<String>> {
         if let Some(columns) = self.unique.clone() {
             let split = columns
@@ -0,0 +1,55 @@
+use std::path::PathBuf;
+
+use crate::error::OxenError;
+
+/// A named part of a split and the fraction of rows that go into it
+#[derive(Clone, Debug, PartialEq)]
+pub struct DFSplit {
+    pub name: String,
+    pub fraction: f64,
+}
+
+#[derive(Clone, Debug)]
+pub struct DFSplitOpts {
+    pub splits: Vec<DFSplit>,
+    /// Column whose values are kept in the same proportions in every split
+    pub stratify: Option<String>,
+    /// Seed for shuffling the rows, the same seed always gives the same splits
+    pub seed: Option<u64>,
+    /// Directory each split is written to as `<name>.<extension of the input>`
+    pub output_dir: PathBuf,
+}
+
+impl DFSplitOpts {
+    /// Parse splits of the form `train=0.8,val=0.1,test=0.1`
+    pub fn parse_splits(splits: impl AsRef<str>) -> Result<Vec<DFSplit>, OxenError> {
+        let mut result: Vec<DFSplit> = vec![];
+        for split in splits.as_ref().split(',') {
+            let Some((name, fraction)) = split.split_once('=') else {
+                return Err(OxenError::basic_str(format!(
+                    "Invalid split {split:?}, format must be name=fraction ie: train=0.8"
+                )));
+            };
+            let fraction = fraction.trim().parse::<f64>().map_err(|_| {
+                OxenError::basic_str(format!("Invalid fraction for split {name:?}: {fraction}"))
+            })?;
+            if !(0.0..=1.0).contains(&fraction) {
+                return Err(OxenError::basic_str(format!(
+                    "Fraction for split {name:?} must be between 0 and 1"
+                )));
+            }
+            result.push(DFSplit {
+                name: name.trim().to_string(),
+                fraction,
+            });
+        }
+
+        let total: f64 = result.iter().map(|s| s.fraction).sum();
+        if total > 1.0 + 1e-9 {
+            return Err(OxenError::basic_str(format!(
+                "Split fractions add up to {total}, they must not be more than 1"
+            )));
+        }
+        Ok(result)
+    }
+}
@@ -0,0 +1,50 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::OxenError;
+
+pub const DEFAULT_EMBEDDING_FILE_COLUMN: &str = "file";
+pub const DEFAULT_EMBEDDING_NEIGHBORS: usize = 10;
+
+/// Find the rows of a data frame whose embeddings are closest to a file's embedding, or
+/// to an embedding given directly. The data frame can be the annotations with a vector
+/// column, or a sidecar parquet that maps files to their embeddings.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct EmbeddingNeighborsOpts {
+    /// Path of the data frame holding the embeddings
+    pub path: PathBuf,
+    /// Column of vectors, either a list of floats or a JSON array string
+    pub column: String,
+    /// Column naming the file each row describes, defaults to `file`
+    pub file_column: Option<String>,
+    /// Find the neighbors of the embedding of this file
+    pub file: Option<String>,
+    /// Find the neighbors of this embedding
+    pub embedding: Option<Vec<f32>>,
+    /// Number of neighbors to return, defaults to 10
+    pub k: Option<usize>,
+    /// Number of clusters to search, higher is more accurate and slower
+    pub nprobe: Option<usize>,
+}
+
+impl EmbeddingNeighborsOpts {
+    pub fn file_column(&self) -> &str {
+        self.file_column
+            .as_deref()
+            .unwrap_or(DEFAULT_EMBEDDING_FILE_COLUMN)
+    }
+
+    pub fn k(&self) -> usize {
+        self.k.unwrap_or(DEFAULT_EMBEDDING_NEIGHBORS)
+    }
+
+    pub fn validate(&self) -> Result<(), OxenError> {
+        match (&self.file, &self.embedding) {
+            (Some(_), None) | (None, Some(_)) => Ok(()),
+            _ => Err(OxenError::basic_str(
+                "Must query by exactly one of a file or an embedding",
+            )),
+        }
+    }
+}
@@ -12,11 +12,19 @@ pub struct FetchOpts {
     pub subtree_paths: Option<Vec<PathBuf>>,
     // The depth at which to clone the subtree.
     pub depth: Option<i32>,
+    // Only fetch the last N commits of history, like a git shallow clone
+    pub history_depth: Option<usize>,
+    // Fetch the history that a shallow clone left out
+    pub unshallow: bool,
     // If true, recursively clones the whole repository history
     // by default, only the head commit is cloned to save time and disk space
     pub all: bool,
     // Defaults to true, but on pull we want to only update the branch head if there are no conflicts
     pub should_update_branch_head: bool,
+    // Report progress as json lines on stdout instead of a progress bar
+    pub json_progress: bool,
+    // Number of connections to download version files over, overrides `remote.download_concurrency`
+    pub jobs: Option<usize>,
 }
 
 impl Default for FetchOpts {
@@ -33,8 +41,12 @@ impl FetchOpts {
             branch: DEFAULT_BRANCH_NAME.to_string(),
             subtree_paths: None,
             depth: None,
+            history_depth: None,
+            unshallow: false,
             all: false,
             should_update_branch_head: true,
+            json_progress: false,
+            jobs: None,
         }
     }
 
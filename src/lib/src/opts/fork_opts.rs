@@ -0,0 +1,52 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::OxenError;
+
+/// How the files of the original repository end up in the fork
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ForkMode {
+    /// Copy every file byte by byte
+    #[default]
+    Copy,
+    /// Hard link the version files, which never change once written, and copy the rest
+    Hardlink,
+    /// Clone every file copy-on-write, needs a filesystem with reflinks such as btrfs or APFS
+    Reflink,
+}
+
+impl fmt::Display for ForkMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ForkMode::Copy => write!(f, "copy"),
+            ForkMode::Hardlink => write!(f, "hardlink"),
+            ForkMode::Reflink => write!(f, "reflink"),
+        }
+    }
+}
+
+impl FromStr for ForkMode {
+    type Err = OxenError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "copy" => Ok(ForkMode::Copy),
+            "hardlink" => Ok(ForkMode::Hardlink),
+            "reflink" => Ok(ForkMode::Reflink),
+            _ => Err(OxenError::basic_str(format!(
+                "Invalid fork mode {s:?}, must be one of copy, hardlink or reflink"
+            ))),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ForkOpts {
+    // How to copy the files over
+    pub mode: ForkMode,
+    // Continue a fork that failed, was cancelled or crashed, skipping the files it already copied
+    pub resume: bool,
+}
@@ -0,0 +1,53 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use time::format_description::well_known::Rfc3339;
+use time::{format_description, Date, OffsetDateTime};
+
+use crate::error::OxenError;
+
+/// Filters for the history `oxen log` shows. A commit has to match every filter that is
+/// set to be listed.
+#[derive(Clone, Debug, Default)]
+pub struct LogOpts {
+    /// The branch or commit to list the history of, HEAD if not set
+    pub revision: Option<String>,
+    /// Only commits that changed the file or dir at this path
+    pub path: Option<PathBuf>,
+    /// Only commits whose author name or email contains this, ignoring case
+    pub author: Option<String>,
+    /// Only commits made at or after this time
+    pub since: Option<OffsetDateTime>,
+    /// Only commits made at or before this time
+    pub until: Option<OffsetDateTime>,
+    /// Only commits whose message or `key=value` metadata match this case insensitive regex
+    pub grep: Option<String>,
+    /// Only commits that have all of these metadata key-values
+    pub metadata: BTreeMap<String, String>,
+    /// The most commits to list
+    pub limit: Option<usize>,
+}
+
+impl LogOpts {
+    /// Parse a `--since` or `--until` date, as `2024-05-01`, an RFC 3339 timestamp such as
+    /// `2024-05-01T12:00:00Z`, or a duration before now such as `2 weeks ago`
+    pub fn parse_date(date: &str) -> Result<OffsetDateTime, OxenError> {
+        let date = date.trim();
+        if let Ok(timestamp) = OffsetDateTime::parse(date, &Rfc3339) {
+            return Ok(timestamp);
+        }
+        let day_format = format_description::parse("[year]-[month]-[day]")
+            .map_err(|err| OxenError::basic_str(err.to_string()))?;
+        if let Ok(day) = Date::parse(date, &day_format) {
+            return Ok(day.midnight().assume_utc());
+        }
+        if let Some(duration) = date.strip_suffix("ago") {
+            if let Ok(duration) = humantime::parse_duration(duration.trim()) {
+                return Ok(OffsetDateTime::now_utc() - duration);
+            }
+        }
+        Err(OxenError::basic_str(format!(
+            "Invalid date {date:?}, use YYYY-MM-DD, an RFC 3339 timestamp or a duration such as \"2 weeks ago\""
+        )))
+    }
+}
@@ -0,0 +1,13 @@
+/// Options for `oxen import s3`
+#[derive(Clone, Debug, Default)]
+pub struct S3ImportOpts {
+    /// Update a repository imported before with the objects that changed since, instead of
+    /// creating a new one
+    pub sync: bool,
+    /// Region of the bucket, falls back to AWS_REGION or the aws profile
+    pub region: Option<String>,
+    /// Custom endpoint for S3 compatible stores such as MinIO or R2
+    pub endpoint: Option<String>,
+    /// Message of the import commit, describes the source if not set
+    pub message: Option<String>,
+}
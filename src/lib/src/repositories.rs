@@ -23,31 +23,60 @@ use jwalk::WalkDir;
 use std::fs::File;
 use std::path::Path;
 
+pub mod activity;
 pub mod add;
+pub mod append;
+pub mod archive;
+pub mod backup;
+pub mod bisect;
+pub mod blame;
 pub mod branches;
+pub mod bundle;
+pub mod case_conflicts;
 pub mod checkout;
+pub mod checks;
 pub mod clone;
 pub mod commits;
 pub mod data_frames;
+pub mod dedup;
 pub mod diffs;
 pub mod download;
+pub mod embeddings;
 pub mod entries;
 pub mod fetch;
 pub mod fork;
+pub mod fsck;
+pub mod gc;
+pub mod import;
 pub mod init;
+pub mod jobs;
 pub mod load;
 pub mod merge;
 pub mod metadata;
+pub mod mirror;
+pub mod mv;
+pub mod owners;
+pub mod proposals;
 pub mod pull;
 pub mod push;
+pub mod reflog;
+pub mod render;
 pub mod restore;
+pub mod retention;
 pub mod revisions;
 pub mod rm;
 pub mod save;
 pub mod size;
+pub mod sparse_checkout;
+pub mod stash;
 pub mod stats;
 pub mod status;
+pub mod tags;
+pub mod thumbnails;
 pub mod tree;
+pub mod uploads;
+pub mod verify_remote;
+pub mod webhooks;
 pub mod workspaces;
 
 pub use add::add;
@@ -58,6 +87,7 @@ pub use download::download;
 pub use fetch::{fetch_all, fetch_branch};
 pub use init::init;
 pub use load::load;
+pub use mv::mv;
 pub use pull::{pull, pull_all, pull_remote_branch};
 pub use push::push;
 pub use restore::restore;
@@ -65,6 +95,7 @@ pub use rm::rm;
 pub use save::save;
 pub use status::status;
 pub use status::status_from_dir;
+pub use verify_remote::verify_remote;
 
 pub fn get_by_namespace_and_name(
     sync_dir: &Path,
@@ -369,6 +400,7 @@ mod tests {
     use crate::repositories;
     use crate::test;
     use crate::util;
+    use std::collections::BTreeMap;
     use std::path::{Path, PathBuf};
     use time::OffsetDateTime;
 
@@ -386,6 +418,8 @@ mod tests {
                 author: String::from("Ox"),
                 email: String::from("ox@oxen.ai"),
                 timestamp,
+                metadata: BTreeMap::new(),
+                co_authors: vec![],
             };
             let repo_new = RepoNew::from_root_commit(namespace, name, root_commit);
             let _repo = repositories::create(&sync_dir, repo_new).await?;
@@ -550,6 +584,8 @@ mod tests {
                 author: String::from("Ox"),
                 email: String::from("ox@oxen.ai"),
                 timestamp,
+                metadata: BTreeMap::new(),
+                co_authors: vec![],
             };
             let repo_new = RepoNew::from_root_commit(old_namespace, name, root_commit);
             let _repo = repositories::create(&sync_dir, repo_new).await?;
@@ -0,0 +1,196 @@
+//! # Activity
+//!
+//! A feed of what changed recently in a repository. Commits come from the commit
+//! history, while events that leave no trace in it (branch creations, forks and
+//! workspace merges) are appended to `.oxen/activity.jsonl` as they happen.
+//!
+
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::constants::ACTIVITY_FILE;
+use crate::error::OxenError;
+use crate::model::{ActivityEvent, ActivityKind, Commit, LocalRepository};
+use crate::opts::PaginateOpts;
+use crate::repositories;
+use crate::util;
+use crate::view::PaginatedActivity;
+use time::OffsetDateTime;
+
+fn activity_path(repo: &LocalRepository) -> PathBuf {
+    util::fs::oxen_hidden_dir(&repo.path).join(ACTIVITY_FILE)
+}
+
+/// Append an event to the repository's activity log
+pub fn record(repo: &LocalRepository, event: &ActivityEvent) -> Result<(), OxenError> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(activity_path(repo))?;
+    writeln!(file, "{}", serde_json::to_string(event)?)?;
+    Ok(())
+}
+
+/// List the recorded events and every commit, newest first
+pub fn list(repo: &LocalRepository) -> Result<Vec<ActivityEvent>, OxenError> {
+    list_newest(repo, usize::MAX)
+}
+
+pub fn list_paginated(
+    repo: &LocalRepository,
+    pagination: PaginateOpts,
+) -> Result<PaginatedActivity, OxenError> {
+    // One event past the page is enough to know there is another page, so the total
+    // only counts the whole history when the page reaches its end
+    let page_end = pagination.page_num.max(1) * pagination.page_size;
+    let events = list_newest(repo, page_end.saturating_add(1))?;
+    let (activity, pagination) = util::paginate(events, pagination.page_num, pagination.page_size);
+    Ok(PaginatedActivity::success(activity, pagination))
+}
+
+/// The `limit` newest events. Commits are read newest first from the branch heads, so
+/// only as much of the history as the events reach back to is read.
+fn list_newest(repo: &LocalRepository, limit: usize) -> Result<Vec<ActivityEvent>, OxenError> {
+    let mut recorded: Vec<ActivityEvent> = vec![];
+    let path = activity_path(repo);
+    if path.exists() {
+        let data = util::fs::read_from_path(&path)?;
+        for line in data.lines().filter(|line| !line.trim().is_empty()) {
+            recorded.push(serde_json::from_str(line)?);
+        }
+    }
+    recorded.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+
+    // Workspace merges already describe their commit, don't list it twice
+    let merged: HashSet<String> = recorded
+        .iter()
+        .filter(|event| event.kind == ActivityKind::WorkspaceMerge)
+        .filter_map(|event| event.commit_id.clone())
+        .collect();
+
+    let mut history = HistoryWalk::default();
+    for branch in repositories::branches::list(repo)? {
+        history.queue(repo, &branch.commit_id)?;
+    }
+
+    let mut recorded = recorded.into_iter().peekable();
+    let mut events = vec![];
+    while events.len() < limit {
+        match (recorded.peek(), history.next_timestamp()) {
+            (Some(event), Some(timestamp)) if event.timestamp >= timestamp => {
+                events.extend(recorded.next());
+            }
+            (_, Some(_)) => {
+                let commit = history.pop().unwrap();
+                for parent_id in &commit.parent_ids {
+                    history.queue(repo, parent_id)?;
+                }
+                if !merged.contains(&commit.id) {
+                    events.push(ActivityEvent::commit(&commit));
+                }
+            }
+            (Some(_), None) => events.extend(recorded.next()),
+            (None, None) => break,
+        }
+    }
+    Ok(events)
+}
+
+/// Walks the commit history newest first, reading a commit only once one of its children
+/// was taken
+#[derive(Default)]
+struct HistoryWalk {
+    seen: HashSet<String>,
+    newest: BinaryHeap<(OffsetDateTime, String)>,
+    queued: HashMap<String, Commit>,
+}
+
+impl HistoryWalk {
+    fn queue(&mut self, repo: &LocalRepository, commit_id: &str) -> Result<(), OxenError> {
+        if !self.seen.insert(commit_id.to_string()) {
+            return Ok(());
+        }
+        if let Some(commit) = repositories::commits::get_by_id(repo, commit_id)? {
+            self.newest.push((commit.timestamp, commit.id.clone()));
+            self.queued.insert(commit.id.clone(), commit);
+        }
+        Ok(())
+    }
+
+    fn next_timestamp(&self) -> Option<OffsetDateTime> {
+        self.newest.peek().map(|(timestamp, _)| *timestamp)
+    }
+
+    fn pop(&mut self) -> Option<Commit> {
+        let (_, commit_id) = self.newest.pop()?;
+        self.queued.remove(&commit_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::OxenError;
+    use crate::model::{ActivityEvent, ActivityKind};
+    use crate::opts::PaginateOpts;
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    #[tokio::test]
+    async fn test_activity_feed_merges_commits_and_events() -> Result<(), OxenError> {
+        test::run_one_commit_local_repo_test_async(|repo| async move {
+            let first = repositories::commits::head_commit(&repo)?;
+
+            let text_path = repo.path.join("text.txt");
+            util::fs::write_to_path(&text_path, "Hello World")?;
+            repositories::add(&repo, &text_path).await?;
+            let merged = repositories::commit(&repo, "Merged from a workspace")?;
+            let mut event = ActivityEvent::commit(&merged);
+            event.kind = ActivityKind::WorkspaceMerge;
+            repositories::activity::record(&repo, &event)?;
+
+            let branch = repositories::branches::create_from_head(&repo, "feature")?;
+            repositories::activity::record(&repo, &ActivityEvent::branch_created(&branch))?;
+
+            let activity = repositories::activity::list(&repo)?;
+            let kinds: Vec<ActivityKind> = activity.iter().map(|event| event.kind).collect();
+            assert_eq!(
+                kinds,
+                vec![
+                    ActivityKind::BranchCreated,
+                    ActivityKind::WorkspaceMerge,
+                    ActivityKind::Commit
+                ]
+            );
+            assert_eq!(activity[0].branch, Some("feature".to_string()));
+            assert_eq!(activity[2].commit_id, Some(first.id));
+
+            let page = repositories::activity::list_paginated(
+                &repo,
+                PaginateOpts {
+                    page_num: 2,
+                    page_size: 2,
+                },
+            )?;
+            assert_eq!(page.activity.len(), 1);
+            assert_eq!(page.pagination.total_entries, 3);
+
+            // Earlier pages stop one event past their end
+            let page = repositories::activity::list_paginated(
+                &repo,
+                PaginateOpts {
+                    page_num: 1,
+                    page_size: 1,
+                },
+            )?;
+            assert_eq!(page.activity[0].kind, ActivityKind::BranchCreated);
+            assert_eq!(page.pagination.total_entries, 2);
+            assert_eq!(page.pagination.total_pages, 2);
+
+            Ok(())
+        })
+        .await
+    }
+}
@@ -0,0 +1,354 @@
+//! # Append
+//!
+//! Capture a stream of rows, such as events from a queue, into a tabular file on a branch.
+//! Appended rows are buffered in a workspace and committed together once there are enough
+//! of them or the oldest has waited long enough, see [`AppendBuffer`], so a busy stream
+//! makes one commit per batch instead of one per row.
+//!
+
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+
+use polars::prelude::{CsvReadOptions, SerReader};
+use time::OffsetDateTime;
+
+use crate::error::OxenError;
+use crate::model::{AppendBuffer, Commit, LocalRepository, NewCommitBody, User};
+use crate::opts::AppendOpts;
+use crate::repositories;
+use crate::util;
+use crate::view::JsonDataFrameView;
+
+pub const DEFAULT_COMMIT_ROWS: usize = 1000;
+pub const DEFAULT_COMMIT_AFTER_SECS: u64 = 60;
+
+const APPEND_DIR: &str = "append";
+const DEFAULT_AUTHOR: &str = "oxen append";
+
+/// Appends and commits are serialized, so a buffer's row count always matches its workspace
+static APPENDING: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+fn buffers_dir(repo: &LocalRepository) -> PathBuf {
+    util::fs::oxen_hidden_dir(&repo.path).join(APPEND_DIR)
+}
+
+fn buffer_path(repo: &LocalRepository, branch: &str, path: &Path) -> PathBuf {
+    let key = format!("{branch}:{}", util::fs::linux_path(path).to_string_lossy());
+    buffers_dir(repo).join(format!("{}.json", util::hasher::hash_str_sha256(key)))
+}
+
+/// The rows waiting to be committed to the file on the branch, if any
+pub fn get(
+    repo: &LocalRepository,
+    branch: &str,
+    path: impl AsRef<Path>,
+) -> Result<Option<AppendBuffer>, OxenError> {
+    let buffer_path = buffer_path(repo, branch, path.as_ref());
+    if !buffer_path.exists() {
+        return Ok(None);
+    }
+    Ok(Some(serde_json::from_str(&util::fs::read_from_path(
+        &buffer_path,
+    )?)?))
+}
+
+/// Every buffer in the repository with rows waiting to be committed
+pub fn list(repo: &LocalRepository) -> Result<Vec<AppendBuffer>, OxenError> {
+    let dir = buffers_dir(repo);
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+    let mut buffers = vec![];
+    for entry in std::fs::read_dir(&dir)? {
+        let path = entry?.path();
+        buffers.push(serde_json::from_str(&util::fs::read_from_path(&path)?)?);
+    }
+    Ok(buffers)
+}
+
+/// Parse the body of an append request into rows. CSV needs a header line, JSON can be an
+/// array of objects, `{"rows": [...]}`, a single object or one object per line.
+pub fn parse_rows(data: &str, content_type: &str) -> Result<Vec<serde_json::Value>, OxenError> {
+    let rows = if content_type.contains("csv") {
+        let mut df = CsvReadOptions::default()
+            .with_has_header(true)
+            .into_reader_with_file_handle(Cursor::new(data.as_bytes()))
+            .finish()?;
+        match JsonDataFrameView::json_from_df(&mut df) {
+            serde_json::Value::Array(rows) => rows,
+            _ => vec![],
+        }
+    } else {
+        match serde_json::from_str::<serde_json::Value>(data) {
+            Ok(serde_json::Value::Array(rows)) => rows,
+            Ok(serde_json::Value::Object(mut object)) => match object.remove("rows") {
+                Some(serde_json::Value::Array(rows)) => rows,
+                Some(_) => {
+                    return Err(OxenError::basic_str("rows must be an array of objects"));
+                }
+                None => vec![serde_json::Value::Object(object)],
+            },
+            Ok(_) => return Err(OxenError::basic_str("Rows must be JSON objects")),
+            Err(_) => data
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(serde_json::from_str)
+                .collect::<Result<Vec<_>, _>>()?,
+        }
+    };
+    if let Some(row) = rows.iter().find(|row| !row.is_object()) {
+        return Err(OxenError::basic_str(format!(
+            "Rows must be JSON objects, got {row}"
+        )));
+    }
+    Ok(rows)
+}
+
+/// Add rows to the end of the tabular file at `path` on the branch. The rows are committed
+/// once the buffer is due, or right away with `opts.commit`, returning the commit.
+pub fn append(
+    repo: &LocalRepository,
+    branch_name: &str,
+    path: impl AsRef<Path>,
+    rows: &[serde_json::Value],
+    opts: &AppendOpts,
+) -> Result<Option<Commit>, OxenError> {
+    let path = path.as_ref();
+    let _lock = APPENDING.lock().unwrap();
+
+    let branch = repositories::branches::get_by_name(repo, branch_name)?
+        .ok_or(OxenError::local_branch_not_found(branch_name))?;
+    let existing = match get(repo, branch_name, path)? {
+        Some(buffer) => repositories::workspaces::get(repo, &buffer.workspace_id)?
+            .map(|workspace| (buffer, workspace)),
+        None => None,
+    };
+    let (mut buffer, workspace) = match existing {
+        Some(existing) => existing,
+        None => {
+            let commit = repositories::commits::get_by_id(repo, &branch.commit_id)?
+                .ok_or(OxenError::commit_id_does_not_exist(&branch.commit_id))?;
+            let workspace = repositories::workspaces::create_with_name(
+                repo,
+                &commit,
+                uuid::Uuid::new_v4().to_string(),
+                Some(format!("{APPEND_DIR}/{branch_name}/{}", path.display())),
+                true,
+            )?;
+            repositories::workspaces::data_frames::index(repo, &workspace, path)?;
+            let buffer = AppendBuffer {
+                branch: branch_name.to_string(),
+                path: path.to_path_buf(),
+                workspace_id: workspace.id.clone(),
+                num_rows: 0,
+                commit_rows: DEFAULT_COMMIT_ROWS,
+                commit_after_secs: DEFAULT_COMMIT_AFTER_SECS,
+                author: User {
+                    name: DEFAULT_AUTHOR.to_string(),
+                    email: String::new(),
+                },
+                message: None,
+                created_at: OffsetDateTime::now_utc(),
+            };
+            (buffer, workspace)
+        }
+    };
+
+    if let Some(commit_rows) = opts.commit_rows {
+        buffer.commit_rows = commit_rows;
+    }
+    if let Some(commit_after_secs) = opts.commit_after_secs {
+        buffer.commit_after_secs = commit_after_secs;
+    }
+    if let Some(author) = &opts.author {
+        buffer.author = author.clone();
+    }
+    if let Some(message) = &opts.message {
+        buffer.message = Some(message.clone());
+    }
+
+    for row in rows {
+        repositories::workspaces::data_frames::rows::add(repo, &workspace, path, row)?;
+        buffer.num_rows += 1;
+    }
+    save(repo, &buffer)?;
+
+    if buffer.num_rows > 0 && (opts.commit || buffer.is_due(OffsetDateTime::now_utc())) {
+        return commit_buffer(repo, &buffer);
+    }
+    Ok(None)
+}
+
+/// Commit the rows waiting to be committed to the file on the branch now
+pub fn flush(
+    repo: &LocalRepository,
+    branch_name: &str,
+    path: impl AsRef<Path>,
+) -> Result<Option<Commit>, OxenError> {
+    let _lock = APPENDING.lock().unwrap();
+    match get(repo, branch_name, path)? {
+        Some(buffer) => commit_buffer(repo, &buffer),
+        None => Ok(None),
+    }
+}
+
+/// Commit every buffer in the repository that has waited long enough. A buffer that fails
+/// to commit is logged and left for the next call.
+pub fn flush_due(repo: &LocalRepository) -> Result<Vec<Commit>, OxenError> {
+    let _lock = APPENDING.lock().unwrap();
+    let now = OffsetDateTime::now_utc();
+    let mut commits = vec![];
+    for buffer in list(repo)? {
+        if !buffer.is_due(now) {
+            continue;
+        }
+        match commit_buffer(repo, &buffer) {
+            Ok(Some(commit)) => commits.push(commit),
+            Ok(None) => {}
+            Err(err) => log::error!(
+                "Could not commit rows appended to {:?} on {}: {}",
+                buffer.path,
+                buffer.branch,
+                err
+            ),
+        }
+    }
+    Ok(commits)
+}
+
+fn save(repo: &LocalRepository, buffer: &AppendBuffer) -> Result<(), OxenError> {
+    util::fs::create_dir_all(buffers_dir(repo))?;
+    util::fs::write_to_path(
+        buffer_path(repo, &buffer.branch, &buffer.path),
+        serde_json::to_string_pretty(buffer)?,
+    )
+}
+
+/// Commit the buffer's workspace to its branch and start the next buffer from scratch
+fn commit_buffer(
+    repo: &LocalRepository,
+    buffer: &AppendBuffer,
+) -> Result<Option<Commit>, OxenError> {
+    let buffer_path = buffer_path(repo, &buffer.branch, &buffer.path);
+    let Some(workspace) = repositories::workspaces::get(repo, &buffer.workspace_id)? else {
+        log::warn!(
+            "Workspace {} of the rows appended to {:?} is gone",
+            buffer.workspace_id,
+            buffer.path
+        );
+        util::fs::remove_file(&buffer_path)?;
+        return Ok(None);
+    };
+    if buffer.num_rows == 0 {
+        return Ok(None);
+    }
+
+    let message = match &buffer.message {
+        Some(message) => message.clone(),
+        None => format!(
+            "Appended {} rows to {}",
+            buffer.num_rows,
+            buffer.path.display()
+        ),
+    };
+    let new_commit = NewCommitBody {
+        message,
+        author: buffer.author.name.clone(),
+        email: buffer.author.email.clone(),
+    };
+    let commit = repositories::workspaces::commit(&workspace, &new_commit, &buffer.branch)?;
+    repositories::workspaces::delete(&workspace)?;
+    util::fs::remove_file(&buffer_path)?;
+    Ok(Some(commit))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use serde_json::json;
+
+    use crate::error::OxenError;
+    use crate::opts::AppendOpts;
+    use crate::repositories;
+    use crate::test;
+
+    #[tokio::test]
+    async fn test_append_commits_once_buffer_is_full() -> Result<(), OxenError> {
+        // Skip duckdb if on windows
+        if std::env::consts::OS == "windows" {
+            return Ok(());
+        }
+
+        test::run_training_data_repo_test_fully_committed_async(|repo| async move {
+            let branch = repositories::branches::current_branch(&repo)?.unwrap();
+            let path = Path::new("annotations")
+                .join("train")
+                .join("bounding_box.csv");
+            let row = json!({
+                "file": "dawg1.jpg",
+                "label": "dog",
+                "min_x": 13,
+                "min_y": 14,
+                "width": 100,
+                "height": 100
+            });
+            let opts = AppendOpts {
+                commit_rows: Some(3),
+                ..AppendOpts::default()
+            };
+
+            let commit = repositories::append::append(
+                &repo,
+                &branch.name,
+                &path,
+                &[row.clone(), row.clone()],
+                &opts,
+            )?;
+            assert!(commit.is_none());
+            let buffer = repositories::append::get(&repo, &branch.name, &path)?.unwrap();
+            assert_eq!(buffer.num_rows, 2);
+
+            let commit = repositories::append::append(
+                &repo,
+                &branch.name,
+                &path,
+                &[row],
+                &AppendOpts::default(),
+            )?
+            .unwrap();
+            assert_eq!(
+                commit.message,
+                "Appended 3 rows to annotations/train/bounding_box.csv"
+            );
+            assert!(repositories::append::get(&repo, &branch.name, &path)?.is_none());
+            let branch = repositories::branches::get_by_name(&repo, &branch.name)?.unwrap();
+            assert_eq!(branch.commit_id, commit.id);
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[test]
+    fn test_parse_rows() -> Result<(), OxenError> {
+        let rows = repositories::append::parse_rows(
+            "{\"label\": \"dog\"}\n{\"label\": \"cat\"}\n",
+            "application/x-ndjson",
+        )?;
+        assert_eq!(rows, vec![json!({"label": "dog"}), json!({"label": "cat"})]);
+
+        let rows = repositories::append::parse_rows(
+            "{\"rows\": [{\"label\": \"dog\"}]}",
+            "application/json",
+        )?;
+        assert_eq!(rows, vec![json!({"label": "dog"})]);
+
+        let rows = repositories::append::parse_rows("label,width\ndog,100\n", "text/csv")?;
+        assert_eq!(rows, vec![json!({"label": "dog", "width": 100})]);
+
+        assert!(repositories::append::parse_rows("[1, 2]", "application/json").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,240 @@
+//! # oxen archive
+//!
+//! Write the files of a commit, or of some paths in it, into a tar, tar.gz or zip file
+//! straight from the version store, without checking the commit out.
+//!
+
+use std::collections::HashSet;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::error::OxenError;
+use crate::model::merkle_tree::node::{EMerkleTreeNode, FileNodeWithDir};
+use crate::model::LocalRepository;
+use crate::repositories;
+use crate::storage::VersionStore;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ArchiveFormat {
+    Tar,
+    #[default]
+    #[serde(rename = "tar.gz")]
+    TarGz,
+    #[serde(rename = "zip")]
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// The format for an output file, by its extension
+    pub fn from_path(path: impl AsRef<Path>) -> Option<ArchiveFormat> {
+        let name = path.as_ref().file_name()?.to_str()?.to_lowercase();
+        if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(ArchiveFormat::TarGz)
+        } else if name.ends_with(".tar") {
+            Some(ArchiveFormat::Tar)
+        } else if name.ends_with(".zip") {
+            Some(ArchiveFormat::Zip)
+        } else {
+            None
+        }
+    }
+
+    pub fn mime_type(&self) -> &str {
+        match self {
+            ArchiveFormat::Tar => "application/x-tar",
+            ArchiveFormat::TarGz => "application/gzip",
+            ArchiveFormat::Zip => "application/zip",
+        }
+    }
+}
+
+impl fmt::Display for ArchiveFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ArchiveFormat::Tar => write!(f, "tar"),
+            ArchiveFormat::TarGz => write!(f, "tar.gz"),
+            ArchiveFormat::Zip => write!(f, "zip"),
+        }
+    }
+}
+
+impl FromStr for ArchiveFormat {
+    type Err = OxenError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "tar" => Ok(ArchiveFormat::Tar),
+            "tar.gz" | "tgz" => Ok(ArchiveFormat::TarGz),
+            "zip" => Ok(ArchiveFormat::Zip),
+            _ => Err(OxenError::basic_str(format!(
+                "Unknown archive format {s:?}, use tar, tar.gz or zip"
+            ))),
+        }
+    }
+}
+
+/// Write the files at `paths` in `revision` to an archive at `output`, or every file in
+/// the revision if `paths` is empty. Returns the number of files written.
+pub fn export(
+    repo: &LocalRepository,
+    revision: impl AsRef<str>,
+    paths: &[PathBuf],
+    format: ArchiveFormat,
+    output: impl AsRef<Path>,
+) -> Result<usize, OxenError> {
+    let mut files: Vec<FileNodeWithDir> = list_files(repo, revision.as_ref(), paths)?
+        .into_iter()
+        .collect();
+    files.sort_by(|a, b| {
+        a.dir
+            .join(a.file_node.name())
+            .cmp(&b.dir.join(b.file_node.name()))
+    });
+
+    let version_store = repo.version_store()?;
+    let output = BufWriter::new(File::create(output.as_ref())?);
+    match format {
+        ArchiveFormat::Tar => {
+            let mut tar = tar::Builder::new(output);
+            append_to_tar(&mut tar, &version_store, &files)?;
+            tar.into_inner()?.flush()?;
+        }
+        ArchiveFormat::TarGz => {
+            let mut tar = tar::Builder::new(GzEncoder::new(output, Compression::default()));
+            append_to_tar(&mut tar, &version_store, &files)?;
+            tar.into_inner()?.finish()?.flush()?;
+        }
+        ArchiveFormat::Zip => {
+            let mut zip = ZipWriter::new(output);
+            for file in &files {
+                let path = file.dir.join(file.file_node.name());
+                let options = SimpleFileOptions::default()
+                    .compression_method(CompressionMethod::Deflated)
+                    .large_file(file.file_node.num_bytes() >= u32::MAX as u64)
+                    .unix_permissions(file.file_node.mode());
+                zip.start_file(path.to_string_lossy(), options)?;
+                let mut reader = version_store.open_version(&file.file_node.hash().to_string())?;
+                std::io::copy(&mut reader, &mut zip)?;
+            }
+            zip.finish()?.flush()?;
+        }
+    }
+    Ok(files.len())
+}
+
+fn append_to_tar<W: Write>(
+    tar: &mut tar::Builder<W>,
+    version_store: &Arc<dyn VersionStore>,
+    files: &[FileNodeWithDir],
+) -> Result<(), OxenError> {
+    for file in files {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(file.file_node.num_bytes());
+        header.set_mode(file.file_node.mode());
+        header.set_mtime(file.file_node.last_modified_seconds().max(0) as u64);
+        header.set_cksum();
+        let reader = version_store.open_version(&file.file_node.hash().to_string())?;
+        tar.append_data(&mut header, file.dir.join(file.file_node.name()), reader)?;
+    }
+    Ok(())
+}
+
+fn list_files(
+    repo: &LocalRepository,
+    revision: &str,
+    paths: &[PathBuf],
+) -> Result<HashSet<FileNodeWithDir>, OxenError> {
+    let commit = repositories::revisions::get(repo, revision)?
+        .ok_or(OxenError::revision_not_found(revision.into()))?;
+    if paths.is_empty() {
+        let root = repositories::tree::get_root_with_children(repo, &commit)?.ok_or(
+            OxenError::basic_str(format!("Could not load the tree for commit {}", commit.id)),
+        )?;
+        return repositories::tree::list_all_files(&root, &PathBuf::new());
+    }
+
+    let mut files: HashSet<FileNodeWithDir> = HashSet::new();
+    for path in paths {
+        let node = repositories::tree::get_node_by_path_with_children(repo, &commit, path)?
+            .ok_or(OxenError::path_does_not_exist(path))?;
+        match &node.node {
+            EMerkleTreeNode::File(file_node) => {
+                files.insert(FileNodeWithDir {
+                    file_node: file_node.clone(),
+                    dir: path.parent().map(Path::to_path_buf).unwrap_or_default(),
+                });
+            }
+            _ => files.extend(repositories::tree::list_all_files(&node, path)?),
+        }
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    use std::path::PathBuf;
+
+    use flate2::read::GzDecoder;
+
+    use crate::error::OxenError;
+    use crate::repositories;
+    use crate::repositories::archive::ArchiveFormat;
+    use crate::test;
+    use crate::util;
+
+    #[tokio::test]
+    async fn test_export_archives_without_a_checkout() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            util::fs::create_dir_all(repo.path.join("images"))?;
+            util::fs::write_to_path(repo.path.join("README.md"), "readme")?;
+            util::fs::write_to_path(repo.path.join("images").join("cat.txt"), "cat")?;
+            repositories::add(&repo, &repo.path).await?;
+            repositories::commit(&repo, "Add files")?;
+
+            // The working directory does not matter
+            util::fs::remove_file(repo.path.join("README.md"))?;
+
+            let tar_path = repo.path.join("export.tar.gz");
+            let num_files =
+                repositories::archive::export(&repo, "main", &[], ArchiveFormat::TarGz, &tar_path)?;
+            assert_eq!(num_files, 2);
+            let mut archive = tar::Archive::new(GzDecoder::new(File::open(&tar_path)?));
+            let mut names: Vec<PathBuf> = archive
+                .entries()?
+                .map(|entry| Ok(entry?.path()?.into_owned()))
+                .collect::<Result<_, OxenError>>()?;
+            names.sort();
+            assert_eq!(
+                names,
+                vec![PathBuf::from("README.md"), PathBuf::from("images/cat.txt")]
+            );
+
+            let zip_path = repo.path.join("images.zip");
+            let num_files = repositories::archive::export(
+                &repo,
+                "main",
+                &[PathBuf::from("images")],
+                ArchiveFormat::from_path(&zip_path).expect("zip extension"),
+                &zip_path,
+            )?;
+            assert_eq!(num_files, 1);
+            let mut zip = zip::ZipArchive::new(File::open(&zip_path)?)?;
+            let cat = zip.by_name("images/cat.txt")?;
+            assert_eq!(cat.size(), 3);
+
+            Ok(())
+        })
+        .await
+    }
+}
@@ -0,0 +1,461 @@
+//! # Backup
+//!
+//! Snapshots of every repository in a server's sync dir, written to
+//! `<backup_dir>/<snapshot_id>/<namespace>/<repo>` next to a `manifest.json`.
+//! RocksDB databases are copied with a checkpoint so the server can keep writing
+//! to them while a snapshot is taken. Databases this process has open are checkpointed
+//! through its handles, the others are opened to checkpoint them. Only a database held
+//! open by another process is copied file by file, which may not be consistent.
+//!
+//! An incremental snapshot only copies the files that changed since the previous
+//! snapshot, and its manifest points at the snapshot that holds each unchanged file,
+//! so restoring it needs the earlier snapshots it refers to.
+//!
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use filetime::FileTime;
+use rocksdb::checkpoint::Checkpoint;
+use rocksdb::DB;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use walkdir::WalkDir;
+
+use crate::core::{self, db};
+use crate::error::OxenError;
+use crate::repositories;
+use crate::util;
+
+const MANIFEST_FILE: &str = "manifest.json";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BackupKind {
+    Full,
+    Incremental,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BackupFile {
+    pub size: u64,
+    pub modified_seconds: i64,
+    pub modified_nanoseconds: u32,
+    /// The snapshot that holds a copy of the file
+    pub snapshot: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RepoBackup {
+    pub namespace: String,
+    pub name: String,
+    /// Keyed by the path relative to the repository
+    pub files: BTreeMap<String, BackupFile>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BackupManifest {
+    pub id: String,
+    pub kind: BackupKind,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    /// The snapshot an incremental snapshot was taken against
+    pub base: Option<String>,
+    pub repos: Vec<RepoBackup>,
+}
+
+impl BackupManifest {
+    pub fn num_files(&self) -> usize {
+        self.repos.iter().map(|repo| repo.files.len()).sum()
+    }
+
+    /// Files copied into this snapshot rather than referenced from an earlier one
+    pub fn copied_files(&self) -> impl Iterator<Item = &BackupFile> {
+        self.repos
+            .iter()
+            .flat_map(|repo| repo.files.values())
+            .filter(|file| file.snapshot == self.id)
+    }
+
+    pub fn copied_bytes(&self) -> u64 {
+        self.copied_files().map(|file| file.size).sum()
+    }
+}
+
+/// Snapshot every repository in the sync dir. An incremental snapshot with no
+/// earlier snapshot to build on is taken as a full one.
+pub fn create(
+    sync_dir: &Path,
+    backup_dir: &Path,
+    kind: BackupKind,
+) -> Result<BackupManifest, OxenError> {
+    let base = match kind {
+        BackupKind::Full => None,
+        BackupKind::Incremental => latest(backup_dir)?,
+    };
+    let kind = if base.is_some() {
+        kind
+    } else {
+        BackupKind::Full
+    };
+
+    let created_at = OffsetDateTime::now_utc();
+    let id = snapshot_id(created_at);
+    let snapshot_dir = backup_dir.join(&id);
+    util::fs::create_dir_all(&snapshot_dir)?;
+    log::info!("Taking {:?} backup {} of {:?}", kind, id, sync_dir);
+
+    let mut repos: Vec<RepoBackup> = vec![];
+    for namespace in repositories::list_namespaces(sync_dir)? {
+        for repo in repositories::list_repos_in_namespace(&sync_dir.join(&namespace)) {
+            let name = repo.dirname();
+            let base_repo = base.as_ref().and_then(|base| {
+                base.repos
+                    .iter()
+                    .find(|r| r.namespace == namespace && r.name == name)
+            });
+
+            // Don't copy a repo in the middle of a push, keep the last good copy instead
+            if repositories::is_locked(&repo) {
+                log::warn!("Backup {} skipping locked repo {:?}", id, repo.path);
+                if let Some(base_repo) = base_repo {
+                    repos.push(base_repo.clone());
+                }
+                continue;
+            }
+
+            let dst = snapshot_dir.join(&namespace).join(&name);
+            let files = backup_repo(&repo.path, &dst, &id, base_repo)?;
+            repos.push(RepoBackup {
+                namespace: namespace.clone(),
+                name,
+                files,
+            });
+        }
+    }
+
+    let manifest = BackupManifest {
+        id,
+        kind,
+        created_at,
+        base: base.map(|base| base.id),
+        repos,
+    };
+
+    // Written last so that an interrupted snapshot is never listed
+    util::fs::write_to_path(
+        snapshot_dir.join(MANIFEST_FILE),
+        serde_json::to_string_pretty(&manifest)?,
+    )?;
+    Ok(manifest)
+}
+
+/// List the complete snapshots in the backup dir, oldest first
+pub fn list(backup_dir: &Path) -> Result<Vec<BackupManifest>, OxenError> {
+    if !backup_dir.exists() {
+        return Ok(vec![]);
+    }
+    let mut manifests: Vec<BackupManifest> = vec![];
+    for entry in std::fs::read_dir(backup_dir)? {
+        let manifest_path = entry?.path().join(MANIFEST_FILE);
+        if manifest_path.is_file() {
+            let data = util::fs::read_from_path(&manifest_path)?;
+            manifests.push(serde_json::from_str(&data)?);
+        }
+    }
+    manifests.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(manifests)
+}
+
+pub fn latest(backup_dir: &Path) -> Result<Option<BackupManifest>, OxenError> {
+    Ok(list(backup_dir)?.pop())
+}
+
+/// Whether the next scheduled snapshot should be full, so that restoring never needs
+/// more than `full_every` snapshots
+pub fn scheduled_kind(backup_dir: &Path, full_every: usize) -> Result<BackupKind, OxenError> {
+    let manifests = list(backup_dir)?;
+    let Some(last_full) = manifests
+        .iter()
+        .rposition(|manifest| manifest.kind == BackupKind::Full)
+    else {
+        return Ok(BackupKind::Full);
+    };
+    let since_full = manifests.len() - last_full;
+    if since_full >= full_every {
+        Ok(BackupKind::Full)
+    } else {
+        Ok(BackupKind::Incremental)
+    }
+}
+
+/// Restore the repositories in a snapshot, the latest one by default, into the sync dir.
+/// Fails without writing anything if any of the repositories already exist.
+pub fn restore(
+    backup_dir: &Path,
+    snapshot_id: Option<&str>,
+    sync_dir: &Path,
+) -> Result<BackupManifest, OxenError> {
+    let manifests = list(backup_dir)?;
+    let manifest = match snapshot_id {
+        Some(id) => manifests.into_iter().find(|manifest| manifest.id == id),
+        None => manifests.into_iter().last(),
+    };
+    let Some(manifest) = manifest else {
+        return Err(OxenError::basic_str(format!(
+            "No backup {} found in {:?}",
+            snapshot_id.unwrap_or("snapshots"),
+            backup_dir
+        )));
+    };
+
+    let existing: Vec<String> = manifest
+        .repos
+        .iter()
+        .filter(|repo| sync_dir.join(&repo.namespace).join(&repo.name).exists())
+        .map(|repo| format!("{}/{}", repo.namespace, repo.name))
+        .collect();
+    if !existing.is_empty() {
+        return Err(OxenError::basic_str(format!(
+            "Cannot restore over existing repositories: {}",
+            existing.join(", ")
+        )));
+    }
+
+    for repo in manifest.repos.iter() {
+        let dst_dir = sync_dir.join(&repo.namespace).join(&repo.name);
+        for (path, file) in repo.files.iter() {
+            let src = backup_dir
+                .join(&file.snapshot)
+                .join(&repo.namespace)
+                .join(&repo.name)
+                .join(path);
+            if !src.is_file() {
+                return Err(OxenError::basic_str(format!(
+                    "Backup {} is missing {:?}, was snapshot {} deleted?",
+                    manifest.id, src, file.snapshot
+                )));
+            }
+            let dst = dst_dir.join(path);
+            if let Some(parent) = dst.parent() {
+                util::fs::create_dir_all(parent)?;
+            }
+            util::fs::copy(&src, &dst)?;
+            let mtime = FileTime::from_unix_time(file.modified_seconds, file.modified_nanoseconds);
+            filetime::set_file_mtime(&dst, mtime)?;
+        }
+    }
+
+    Ok(manifest)
+}
+
+fn snapshot_id(created_at: OffsetDateTime) -> String {
+    format!(
+        "{:04}{:02}{:02}T{:02}{:02}{:02}{:06}Z",
+        created_at.year(),
+        created_at.month() as u8,
+        created_at.day(),
+        created_at.hour(),
+        created_at.minute(),
+        created_at.second(),
+        created_at.microsecond()
+    )
+}
+
+fn backup_repo(
+    src: &Path,
+    dst: &Path,
+    id: &str,
+    base: Option<&RepoBackup>,
+) -> Result<BTreeMap<String, BackupFile>, OxenError> {
+    let mut files: BTreeMap<String, BackupFile> = BTreeMap::new();
+    let mut walker = WalkDir::new(src).into_iter();
+    while let Some(entry) = walker.next() {
+        let entry = entry.map_err(std::io::Error::from)?;
+        let path = entry.path();
+        let relative = util::fs::path_relative_to_dir(path, src)?;
+
+        if entry.file_type().is_dir() {
+            if is_rocksdb(path)? {
+                walker.skip_current_dir();
+                let db_dst = dst.join(&relative);
+                if !checkpoint(path, &db_dst)? {
+                    log::warn!("Backup {} of {:?} may not be consistent", id, path);
+                }
+                for db_entry in WalkDir::new(&db_dst) {
+                    let db_entry = db_entry.map_err(std::io::Error::from)?;
+                    if db_entry.file_type().is_file() {
+                        let relative = util::fs::path_relative_to_dir(db_entry.path(), dst)?;
+                        let record = backup_file(db_entry.path(), id)?;
+                        files.insert(relative.to_string_lossy().to_string(), record);
+                    }
+                }
+            }
+            continue;
+        }
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        let key = relative.to_string_lossy().to_string();
+        let record = backup_file(path, id)?;
+        if let Some(previous) = base.and_then(|base| base.files.get(&key)) {
+            if previous.size == record.size
+                && previous.modified_seconds == record.modified_seconds
+                && previous.modified_nanoseconds == record.modified_nanoseconds
+            {
+                files.insert(key, previous.clone());
+                continue;
+            }
+        }
+
+        let file_dst = dst.join(&relative);
+        if let Some(parent) = file_dst.parent() {
+            util::fs::create_dir_all(parent)?;
+        }
+        util::fs::copy(path, &file_dst)?;
+        files.insert(key, record);
+    }
+    Ok(files)
+}
+
+fn backup_file(path: &Path, id: &str) -> Result<BackupFile, OxenError> {
+    let metadata = util::fs::metadata(path)?;
+    let mtime = FileTime::from_last_modification_time(&metadata);
+    Ok(BackupFile {
+        size: metadata.len(),
+        modified_seconds: mtime.unix_seconds(),
+        modified_nanoseconds: mtime.nanoseconds(),
+        snapshot: id.to_string(),
+    })
+}
+
+fn is_rocksdb(path: &Path) -> Result<bool, OxenError> {
+    if !path.join("CURRENT").is_file() {
+        return Ok(false);
+    }
+    for entry in std::fs::read_dir(path)? {
+        if entry?
+            .file_name()
+            .to_string_lossy()
+            .starts_with("MANIFEST-")
+        {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Copy a RocksDB database that may be open. Returns false if it had to be copied file by
+/// file because another process holds it open.
+fn checkpoint(src: &Path, dst: &Path) -> Result<bool, OxenError> {
+    if let Some(parent) = dst.parent() {
+        util::fs::create_dir_all(parent)?;
+    }
+    // RocksDB can't checkpoint a read only db, and a second read write handle can't be
+    // opened in the process that has one, so use the open handle
+    if core::refs::checkpoint_if_open(src, dst)? || core::staged::checkpoint_if_open(src, dst)? {
+        return Ok(true);
+    }
+    let opts = db::key_val::opts::default();
+    let result = DB::open(&opts, src).and_then(|db| Checkpoint::new(&db)?.create_checkpoint(dst));
+    if let Err(err) = result {
+        // Fall back to copying the files, SST files are immutable so this is usually consistent
+        log::warn!("Could not checkpoint {:?}, copying instead: {}", src, err);
+        if dst.exists() {
+            util::fs::remove_dir_all(dst)?;
+        }
+        util::fs::copy_dir_all(src, dst)?;
+        return Ok(false);
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::BackupKind;
+    use crate::constants::REFS_DIR;
+    use crate::core::db;
+    use crate::error::OxenError;
+    use crate::model::LocalRepository;
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    #[tokio::test]
+    async fn test_backup_incremental_and_restore() -> Result<(), OxenError> {
+        test::run_empty_dir_test_async(|dir| async move {
+            let sync_dir = dir.join("sync");
+            let backup_dir = dir.join("backups");
+            let restore_dir = dir.join("restored");
+
+            let repo_dir = sync_dir.join("ox").join("data");
+            util::fs::create_dir_all(&repo_dir)?;
+            let repo = repositories::init::init(&repo_dir)?;
+            let hello = repo.path.join("hello.txt");
+            util::fs::write_to_path(&hello, "hello")?;
+            repositories::add(&repo, &hello).await?;
+            repositories::commit(&repo, "Add hello")?;
+
+            let full = super::create(&sync_dir, &backup_dir, BackupKind::Full)?;
+            assert_eq!(full.repos.len(), 1);
+            assert_eq!(full.copied_files().count(), full.num_files());
+
+            let world = repo.path.join("world.txt");
+            util::fs::write_to_path(&world, "world")?;
+            repositories::add(&repo, &world).await?;
+            let head = repositories::commit(&repo, "Add world")?;
+
+            assert_eq!(
+                super::scheduled_kind(&backup_dir, 2)?,
+                BackupKind::Incremental
+            );
+            let incremental = super::create(&sync_dir, &backup_dir, BackupKind::Incremental)?;
+            assert_eq!(incremental.kind, BackupKind::Incremental);
+            assert_eq!(incremental.base, Some(full.id.clone()));
+            assert!(incremental.copied_files().count() < incremental.num_files());
+            assert_eq!(super::scheduled_kind(&backup_dir, 2)?, BackupKind::Full);
+
+            super::restore(&backup_dir, None, &restore_dir)?;
+            let restored = LocalRepository::from_dir(restore_dir.join("ox").join("data"))?;
+            let restored_head = repositories::commits::head_commit(&restored)?;
+            assert_eq!(restored_head.id, head.id);
+            let node = repositories::tree::get_file_by_path(
+                &restored,
+                &restored_head,
+                Path::new("world.txt"),
+            )?;
+            assert!(node.is_some());
+
+            // Restoring again would overwrite the restored repo
+            assert!(super::restore(&backup_dir, None, &restore_dir).is_err());
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_backup_checkpoints_open_dbs() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            let hello = repo.path.join("hello.txt");
+            util::fs::write_to_path(&hello, "hello")?;
+            repositories::add(&repo, &hello).await?;
+            let commit = repositories::commit(&repo, "Add hello")?;
+            // Written through the refs db this process keeps open, still in its memtable
+            repositories::branches::create(&repo, "feature", &commit.id)?;
+
+            let refs_dir = util::fs::oxen_hidden_dir(&repo.path).join(REFS_DIR);
+            let dst = repo.path.join("backup").join(REFS_DIR);
+            assert!(super::checkpoint(&refs_dir, &dst)?);
+
+            let opts = db::key_val::opts::default();
+            let snapshot = rocksdb::DB::open_for_read_only(&opts, &dst, false)?;
+            assert_eq!(snapshot.get("feature")?, Some(commit.id.into_bytes()));
+            Ok(())
+        })
+        .await
+    }
+}
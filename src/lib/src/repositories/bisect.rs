@@ -0,0 +1,318 @@
+//! # oxen bisect
+//!
+//! Binary search the history between a good and a bad commit for the commit that
+//! introduced a regression. Each step checks out the commit halfway between them, or only
+//! some subtrees of it, to be marked good or bad by hand or by a script. The state of a
+//! bisect lives in `.oxen/bisect.json` until `oxen bisect reset` puts HEAD back.
+//!
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+
+use crate::constants::BISECT_FILE;
+use crate::error::OxenError;
+use crate::model::{Commit, LocalRepository};
+use crate::repositories;
+use crate::util;
+
+/// Exit code a `bisect run` script returns when a commit cannot be tested
+pub const SKIP_EXIT_CODE: i32 = 125;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct BisectState {
+    /// Branch name or commit id HEAD pointed at before the bisect, restored on reset
+    pub original_head: String,
+    pub bad: Option<String>,
+    pub good: Vec<String>,
+    pub skipped: Vec<String>,
+    /// Only these subtrees are checked out for each candidate, everything if empty
+    pub paths: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone)]
+pub enum BisectStep {
+    /// Waiting for at least one good and one bad commit
+    Waiting { has_good: bool, has_bad: bool },
+    /// Checked out `commit` to be tested, `remaining` commits could still be the first bad
+    /// one, which takes about `steps` more steps to narrow down
+    Testing {
+        commit: Commit,
+        remaining: usize,
+        steps: usize,
+    },
+    /// The first bad commit
+    Found(Commit),
+    /// The first bad commit is one of these, the others were skipped
+    Inconclusive(Vec<Commit>),
+}
+
+fn state_path(repo: &LocalRepository) -> PathBuf {
+    util::fs::oxen_hidden_dir(&repo.path).join(BISECT_FILE)
+}
+
+/// The state of the bisect in progress, if there is one
+pub fn state(repo: &LocalRepository) -> Result<Option<BisectState>, OxenError> {
+    let path = state_path(repo);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let data = util::fs::read_from_path(&path)?;
+    Ok(Some(serde_json::from_str(&data)?))
+}
+
+fn save_state(repo: &LocalRepository, state: &BisectState) -> Result<(), OxenError> {
+    util::fs::write_to_path(state_path(repo), serde_json::to_string_pretty(state)?)?;
+    Ok(())
+}
+
+fn state_or_err(repo: &LocalRepository) -> Result<BisectState, OxenError> {
+    state(repo)?.ok_or(OxenError::basic_str(
+        "No bisect in progress, start one with `oxen bisect start`",
+    ))
+}
+
+/// Start a bisect, optionally with the bad and good revisions already known. If `paths`
+/// are given only those subtrees are checked out for each candidate.
+pub async fn start(
+    repo: &LocalRepository,
+    bad: Option<&str>,
+    good: &[String],
+    paths: &[PathBuf],
+) -> Result<BisectStep, OxenError> {
+    if state(repo)?.is_some() {
+        return Err(OxenError::basic_str(
+            "A bisect is already in progress, run `oxen bisect reset` to end it first",
+        ));
+    }
+    let status = repositories::status(repo)?;
+    if status.has_added_entries() || status.has_modified_entries() || status.has_removed_entries() {
+        return Err(OxenError::basic_str(
+            "Commit or stash your changes before starting a bisect",
+        ));
+    }
+
+    let original_head = match repositories::branches::current_branch(repo)? {
+        Some(branch) => branch.name,
+        None => repositories::commits::head_commit(repo)?.id,
+    };
+    let paths = if paths.is_empty() {
+        repo.subtree_paths().unwrap_or_default()
+    } else {
+        paths.to_vec()
+    };
+    let mut state = BisectState {
+        original_head,
+        paths,
+        ..Default::default()
+    };
+    if let Some(bad) = bad {
+        state.bad = Some(resolve(repo, Some(bad))?.id);
+    }
+    for revision in good {
+        state.good.push(resolve(repo, Some(revision))?.id);
+    }
+    save_state(repo, &state)?;
+    next(repo, &state).await
+}
+
+/// Mark `revision`, or the commit being tested, as good
+pub async fn good(repo: &LocalRepository, revision: Option<&str>) -> Result<BisectStep, OxenError> {
+    let mut state = state_or_err(repo)?;
+    let commit = resolve(repo, revision)?;
+    if !state.good.contains(&commit.id) {
+        state.good.push(commit.id);
+    }
+    save_state(repo, &state)?;
+    next(repo, &state).await
+}
+
+/// Mark `revision`, or the commit being tested, as bad
+pub async fn bad(repo: &LocalRepository, revision: Option<&str>) -> Result<BisectStep, OxenError> {
+    let mut state = state_or_err(repo)?;
+    state.bad = Some(resolve(repo, revision)?.id);
+    save_state(repo, &state)?;
+    next(repo, &state).await
+}
+
+/// Skip `revision`, or the commit being tested, because it cannot be tested
+pub async fn skip(repo: &LocalRepository, revision: Option<&str>) -> Result<BisectStep, OxenError> {
+    let mut state = state_or_err(repo)?;
+    let commit = resolve(repo, revision)?;
+    if !state.skipped.contains(&commit.id) {
+        state.skipped.push(commit.id);
+    }
+    save_state(repo, &state)?;
+    next(repo, &state).await
+}
+
+/// Run `command` in the repository on each candidate until the first bad commit is found.
+/// Exit code 0 marks the commit good, 125 skips it, any other code up to 127 marks it bad
+/// and anything else stops the bisect.
+pub async fn run(repo: &LocalRepository, command: &[String]) -> Result<BisectStep, OxenError> {
+    let Some((program, args)) = command.split_first() else {
+        return Err(OxenError::basic_str("Must supply a command to run"));
+    };
+    let state = state_or_err(repo)?;
+    let mut step = next(repo, &state).await?;
+    while let BisectStep::Testing { commit, .. } = &step {
+        let status = Command::new(program)
+            .args(args)
+            .current_dir(&repo.path)
+            .status()?;
+        step = match status.code() {
+            Some(0) => good(repo, None).await?,
+            Some(SKIP_EXIT_CODE) => skip(repo, None).await?,
+            Some(code) if (1..128).contains(&code) => bad(repo, None).await?,
+            _ => {
+                return Err(OxenError::basic_str(format!(
+                    "Stopping the bisect, {program} exited with {status} on commit {}",
+                    commit.id
+                )))
+            }
+        };
+    }
+    Ok(step)
+}
+
+/// End the bisect and check out what HEAD pointed at before it started
+pub async fn reset(repo: &LocalRepository) -> Result<BisectState, OxenError> {
+    let state = state_or_err(repo)?;
+    let commit = resolve(repo, Some(state.original_head.as_str()))?;
+    checkout(repo, &commit, &state.paths, &state.original_head).await?;
+    util::fs::remove_file(state_path(repo))?;
+    Ok(state)
+}
+
+/// The commits that could still be the first bad one, newest first. The bad commit is
+/// always the first of them.
+pub fn candidates(repo: &LocalRepository, state: &BisectState) -> Result<Vec<Commit>, OxenError> {
+    let Some(bad) = &state.bad else {
+        return Ok(vec![]);
+    };
+    let mut good_ancestors: HashSet<String> = HashSet::new();
+    for good in &state.good {
+        for commit in repositories::commits::list_from(repo, good)? {
+            good_ancestors.insert(commit.id);
+        }
+    }
+    if good_ancestors.contains(bad) {
+        return Err(OxenError::basic_str(format!(
+            "The bad commit {bad} is an ancestor of a good commit, check the commits you marked"
+        )));
+    }
+    Ok(repositories::commits::list_from(repo, bad)?
+        .into_iter()
+        .filter(|commit| !good_ancestors.contains(&commit.id))
+        .collect())
+}
+
+async fn next(repo: &LocalRepository, state: &BisectState) -> Result<BisectStep, OxenError> {
+    if state.bad.is_none() || state.good.is_empty() {
+        return Ok(BisectStep::Waiting {
+            has_good: !state.good.is_empty(),
+            has_bad: state.bad.is_some(),
+        });
+    }
+
+    let candidates = candidates(repo, state)?;
+    let testable: Vec<&Commit> = candidates
+        .iter()
+        .skip(1)
+        .filter(|commit| !state.skipped.contains(&commit.id))
+        .collect();
+    if testable.is_empty() {
+        return if candidates.len() == 1 {
+            Ok(BisectStep::Found(candidates[0].clone()))
+        } else {
+            Ok(BisectStep::Inconclusive(candidates))
+        };
+    }
+
+    let commit = testable[testable.len() / 2].clone();
+    checkout(repo, &commit, &state.paths, &commit.id).await?;
+    Ok(BisectStep::Testing {
+        commit,
+        remaining: testable.len(),
+        steps: (testable.len() + 1).ilog2() as usize,
+    })
+}
+
+async fn checkout(
+    repo: &LocalRepository,
+    commit: &Commit,
+    paths: &[PathBuf],
+    head: &str,
+) -> Result<(), OxenError> {
+    if paths.is_empty() {
+        let previous = repositories::commits::head_commit_maybe(repo)?;
+        repositories::branches::checkout_commit_from_commit(repo, commit, &previous).await?;
+    } else {
+        repositories::branches::checkout_subtrees_to_commit(repo, commit, paths, i32::MAX).await?;
+    }
+    repositories::branches::set_head(repo, head)
+}
+
+fn resolve(repo: &LocalRepository, revision: Option<&str>) -> Result<Commit, OxenError> {
+    let revision = revision.unwrap_or("HEAD");
+    repositories::revisions::get(repo, revision)?
+        .ok_or(OxenError::revision_not_found(revision.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::OxenError;
+    use crate::repositories;
+    use crate::repositories::bisect::BisectStep;
+    use crate::test;
+    use crate::util;
+
+    #[tokio::test]
+    async fn test_bisect_finds_the_first_bad_commit() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            // The accuracy drops below 0.9 at the 6th commit
+            let mut commits = vec![];
+            for (i, accuracy) in [95, 94, 96, 93, 92, 85, 86, 84].iter().enumerate() {
+                util::fs::write_to_path(repo.path.join("accuracy.txt"), accuracy.to_string())?;
+                repositories::add(&repo, &repo.path).await?;
+                commits.push(repositories::commit(&repo, &format!("Run {i}"))?);
+            }
+
+            let first = commits[0].id.clone();
+            let mut step = repositories::bisect::start(&repo, Some("HEAD"), &[first], &[]).await?;
+            let mut num_steps = 0;
+            while let BisectStep::Testing { commit, .. } = &step {
+                num_steps += 1;
+                let accuracy: usize = util::fs::read_from_path(repo.path.join("accuracy.txt"))?
+                    .parse()
+                    .expect("accuracy is a number");
+                assert_eq!(repositories::commits::head_commit(&repo)?.id, commit.id);
+                step = if accuracy >= 90 {
+                    repositories::bisect::good(&repo, None).await?
+                } else {
+                    repositories::bisect::bad(&repo, None).await?
+                };
+            }
+            match step {
+                BisectStep::Found(commit) => assert_eq!(commit.id, commits[5].id),
+                step => panic!("Expected to find the bad commit, got {step:?}"),
+            }
+            assert!(num_steps <= 3);
+
+            // Reset goes back to the branch
+            repositories::bisect::reset(&repo).await?;
+            assert!(repositories::bisect::state(&repo)?.is_none());
+            let branch = repositories::branches::current_branch(&repo)?.expect("on a branch");
+            assert_eq!(branch.commit_id, commits[7].id);
+            assert_eq!(
+                util::fs::read_from_path(repo.path.join("accuracy.txt"))?,
+                "84"
+            );
+
+            Ok(())
+        })
+        .await
+    }
+}
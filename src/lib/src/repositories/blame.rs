@@ -0,0 +1,338 @@
+//! # oxen blame
+//!
+//! Find the last commit that modified each line of a text file, or each row of a tabular
+//! file keyed by a column. The history of the file is followed through the last commit id
+//! stored on its file node and the first parent of each of those commits.
+//!
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use difference::{Changeset, Difference};
+use polars::prelude::DataType;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+use crate::constants::ROW_HASH_COL_NAME;
+use crate::core::df::tabular;
+use crate::error::OxenError;
+use crate::model::merkle_tree::node::FileNode;
+use crate::model::{Commit, LocalRepository};
+use crate::opts::DFOpts;
+use crate::repositories;
+use crate::util;
+
+/// The commit that last modified a line of a text file
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BlameLine {
+    /// 1 based line number in the blamed version of the file
+    pub line_number: usize,
+    pub text: String,
+    pub commit_id: String,
+    pub author: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub timestamp: OffsetDateTime,
+}
+
+/// The commit that last added or modified a row of a tabular file
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BlameRow {
+    /// Value of the key column for the row
+    pub key: String,
+    pub commit_id: String,
+    pub author: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub timestamp: OffsetDateTime,
+}
+
+/// Blame each line of the text file at `path` as of `revision`
+pub fn blame_text(
+    repo: &LocalRepository,
+    revision: impl AsRef<str>,
+    path: impl AsRef<Path>,
+) -> Result<Vec<BlameLine>, OxenError> {
+    let history = file_history(repo, revision.as_ref(), path.as_ref())?;
+    let version_store = repo.version_store()?;
+    let read_version = |node: &FileNode| -> Result<String, OxenError> {
+        let version_path = version_store.get_version_path(&node.hash().to_string())?;
+        if !util::fs::is_utf8(&version_path) {
+            return Err(OxenError::basic_str(format!(
+                "Cannot blame {:?}, it is not a text file",
+                path.as_ref()
+            )));
+        }
+        util::fs::read_from_path(&version_path)
+    };
+
+    let contents = read_version(&history[0].1)?;
+    let lines: Vec<&str> = contents.split('\n').collect();
+    // Where each line of the blamed version is in the version being looked at, None
+    // once the line has been attributed to a commit
+    let mut positions: Vec<Option<usize>> = (0..lines.len()).map(Some).collect();
+    let mut blamed: Vec<usize> = vec![0; lines.len()];
+
+    let mut newer = contents.clone();
+    for i in 0..history.len() {
+        let Some((_, older_node)) = history.get(i + 1) else {
+            // The oldest version added whatever is left
+            for (line, position) in positions.iter_mut().enumerate() {
+                if position.take().is_some() {
+                    blamed[line] = i;
+                }
+            }
+            break;
+        };
+        let older = read_version(older_node)?;
+        let mapping = map_lines(&older, &newer);
+        for (line, position) in positions.iter_mut().enumerate() {
+            let Some(newer_line) = *position else {
+                continue;
+            };
+            *position = mapping[newer_line];
+            if position.is_none() {
+                blamed[line] = i;
+            }
+        }
+        if positions.iter().all(Option::is_none) {
+            break;
+        }
+        newer = older;
+    }
+
+    // A trailing newline is not a line of its own
+    let num_lines = match lines.last() {
+        Some(&"") if lines.len() > 1 => lines.len() - 1,
+        _ => lines.len(),
+    };
+    Ok((0..num_lines)
+        .map(|line| {
+            let commit = &history[blamed[line]].0;
+            BlameLine {
+                line_number: line + 1,
+                text: lines[line].to_string(),
+                commit_id: commit.id.clone(),
+                author: commit.author.clone(),
+                timestamp: commit.timestamp,
+            }
+        })
+        .collect())
+}
+
+/// Blame each row of the tabular file at `path` as of `revision`. Rows are matched between
+/// versions by the value of `key_column`, so a row counts as modified when any of its
+/// values changed.
+pub fn blame_tabular(
+    repo: &LocalRepository,
+    revision: impl AsRef<str>,
+    path: impl AsRef<Path>,
+    key_column: impl AsRef<str>,
+) -> Result<Vec<BlameRow>, OxenError> {
+    let path = path.as_ref();
+    let key_column = key_column.as_ref();
+    let history = file_history(repo, revision.as_ref(), path)?;
+
+    let (keys, mut newer) = read_row_hashes(repo, &history[0].1, key_column)?;
+    let mut unresolved: HashSet<String> = keys.iter().cloned().collect();
+    let mut blamed: HashMap<String, usize> = HashMap::new();
+    for i in 0..history.len() {
+        let older = match history.get(i + 1) {
+            Some((_, older_node)) => Some(read_row_hashes(repo, older_node, key_column)?.1),
+            None => None,
+        };
+        unresolved.retain(|key| {
+            let unchanged = older
+                .as_ref()
+                .is_some_and(|older| older.get(key) == newer.get(key));
+            if !unchanged {
+                blamed.insert(key.clone(), i);
+            }
+            unchanged
+        });
+        match older {
+            Some(older) if !unresolved.is_empty() => newer = older,
+            _ => break,
+        }
+    }
+
+    Ok(keys
+        .into_iter()
+        .map(|key| {
+            let commit = &history[blamed[&key]].0;
+            BlameRow {
+                key,
+                commit_id: commit.id.clone(),
+                author: commit.author.clone(),
+                timestamp: commit.timestamp,
+            }
+        })
+        .collect())
+}
+
+/// The versions of the file that differ from their previous version, newest first, with
+/// the commit that wrote each of them
+fn file_history(
+    repo: &LocalRepository,
+    revision: &str,
+    path: &Path,
+) -> Result<Vec<(Commit, FileNode)>, OxenError> {
+    let commit = repositories::revisions::get(repo, revision)?
+        .ok_or(OxenError::revision_not_found(revision.into()))?;
+    let mut node = repositories::tree::get_file_by_path(repo, &commit, path)?
+        .ok_or(OxenError::path_does_not_exist(path))?;
+
+    let mut history: Vec<(Commit, FileNode)> = vec![];
+    loop {
+        let commit_id = node.last_commit_id().to_string();
+        let commit = repositories::commits::get_by_id(repo, &commit_id)?
+            .ok_or(OxenError::commit_id_does_not_exist(&commit_id))?;
+        // Parents can be missing in a shallow clone, blame stops at the boundary
+        let parent = match commit.parent_ids.first() {
+            Some(parent_id) => repositories::commits::get_by_id(repo, parent_id)?,
+            None => None,
+        };
+        let parent_node = match &parent {
+            Some(parent) => repositories::tree::get_file_by_path(repo, parent, path)?,
+            None => None,
+        };
+        history.push((commit, node));
+        match parent_node {
+            Some(parent_node) => node = parent_node,
+            None => break,
+        }
+    }
+    Ok(history)
+}
+
+/// For each line of `newer`, the line of `older` it was kept from, None if it was added
+fn map_lines(older: &str, newer: &str) -> Vec<Option<usize>> {
+    let Changeset { diffs, .. } = Changeset::new(older, newer, "\n");
+    let mut mapping: Vec<Option<usize>> = vec![];
+    let mut older_line = 0;
+    for diff in diffs {
+        match diff {
+            Difference::Same(text) => {
+                for _ in text.split('\n') {
+                    mapping.push(Some(older_line));
+                    older_line += 1;
+                }
+            }
+            Difference::Add(text) => {
+                mapping.extend(text.split('\n').map(|_| None));
+            }
+            Difference::Rem(text) => {
+                older_line += text.split('\n').count();
+            }
+        }
+    }
+    mapping
+}
+
+/// The keys of the rows in order, and the hash of each row by key
+fn read_row_hashes(
+    repo: &LocalRepository,
+    node: &FileNode,
+    key_column: &str,
+) -> Result<(Vec<String>, HashMap<String, String>), OxenError> {
+    let version_path = repo
+        .version_store()?
+        .get_version_path(&node.hash().to_string())?;
+    let df = tabular::read_df_with_extension(&version_path, node.extension(), &DFOpts::empty())?;
+    if df.column(key_column).is_err() {
+        return Err(OxenError::basic_str(format!(
+            "Key column {key_column:?} is not in {}",
+            node.name()
+        )));
+    }
+    let df = tabular::df_hash_rows(df)?;
+
+    let keys = df.column(key_column)?.cast(&DataType::String)?;
+    let hashes = df.column(ROW_HASH_COL_NAME)?;
+    let mut ordered_keys: Vec<String> = vec![];
+    let mut row_hashes: HashMap<String, String> = HashMap::new();
+    for (key, hash) in keys.str()?.into_iter().zip(hashes.str()?.into_iter()) {
+        let key = key.unwrap_or("null").to_string();
+        // With duplicate keys the last row wins
+        if row_hashes
+            .insert(key.clone(), hash.unwrap_or_default().to_string())
+            .is_none()
+        {
+            ordered_keys.push(key);
+        }
+    }
+    Ok((ordered_keys, row_hashes))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::OxenError;
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    #[tokio::test]
+    async fn test_blame_text_lines() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            let path = repo.path.join("notes.txt");
+            util::fs::write_to_path(&path, "one\ntwo\nthree\n")?;
+            repositories::add(&repo, &path).await?;
+            let first = repositories::commit(&repo, "Add notes")?;
+
+            util::fs::write_to_path(&path, "one\n2\nthree\nfour\n")?;
+            repositories::add(&repo, &path).await?;
+            let second = repositories::commit(&repo, "Edit notes")?;
+
+            let lines = repositories::blame::blame_text(&repo, "HEAD", "notes.txt")?;
+            let blamed: Vec<(&str, &str)> = lines
+                .iter()
+                .map(|line| (line.text.as_str(), line.commit_id.as_str()))
+                .collect();
+            assert_eq!(
+                blamed,
+                vec![
+                    ("one", first.id.as_str()),
+                    ("2", second.id.as_str()),
+                    ("three", first.id.as_str()),
+                    ("four", second.id.as_str()),
+                ]
+            );
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_blame_tabular_rows() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            let path = repo.path.join("labels.csv");
+            util::fs::write_to_path(&path, "id,label\n1,cat\n2,dog\n")?;
+            repositories::add(&repo, &path).await?;
+            let first = repositories::commit(&repo, "Add labels")?;
+
+            util::fs::write_to_path(&path, "id,label\n1,cat\n2,wolf\n3,bird\n")?;
+            repositories::add(&repo, &path).await?;
+            let second = repositories::commit(&repo, "Fix labels")?;
+
+            let rows = repositories::blame::blame_tabular(&repo, "HEAD", "labels.csv", "id")?;
+            let blamed: Vec<(&str, &str)> = rows
+                .iter()
+                .map(|row| (row.key.as_str(), row.commit_id.as_str()))
+                .collect();
+            assert_eq!(
+                blamed,
+                vec![
+                    ("1", first.id.as_str()),
+                    ("2", second.id.as_str()),
+                    ("3", second.id.as_str()),
+                ]
+            );
+
+            assert!(
+                repositories::blame::blame_tabular(&repo, "HEAD", "labels.csv", "nope").is_err()
+            );
+
+            Ok(())
+        })
+        .await
+    }
+}
@@ -9,10 +9,12 @@ use crate::constants::{BRANCH_LOCKS_DIR, OXEN_HIDDEN_DIR};
 use crate::core::refs::with_ref_manager;
 use crate::core::versions::MinOxenVersion;
 use crate::error::OxenError;
-use crate::model::{Branch, Commit, CommitEntry, LocalRepository};
+use crate::model::{Branch, BranchCompare, Commit, CommitEntry, LocalRepository};
 use crate::repositories;
 use crate::{core, util};
 
+pub mod protection;
+
 /// List all the local branches within a repo
 pub fn list(repo: &LocalRepository) -> Result<Vec<Branch>, OxenError> {
     with_ref_manager(repo, |manager| manager.list_branches())
@@ -112,6 +114,47 @@ pub fn create_checkout(repo: &LocalRepository, name: impl AsRef<str>) -> Result<
     })
 }
 
+/// # Start a new branch with no history
+/// Points HEAD at a branch that does not exist yet. The next commit has no parents
+/// and only contains what is staged, and creates the branch pointing to it.
+/// Files from the previous branch are left in the working directory untracked.
+pub fn create_orphan_checkout(
+    repo: &LocalRepository,
+    name: impl AsRef<str>,
+) -> Result<(), OxenError> {
+    let name = util::fs::linux_path_str(name.as_ref());
+    println!("Create and checkout orphan branch: {name}");
+
+    with_ref_manager(repo, |manager| {
+        if manager.is_invalid_branch_name(&name) {
+            let err = format!("'{name}' is not a valid branch name.");
+            return Err(OxenError::basic_str(err));
+        }
+        if manager.has_branch(&name) {
+            let err = format!("Branch already exists: {name}");
+            return Err(OxenError::basic_str(err));
+        }
+        manager.set_head(name);
+        Ok(())
+    })
+}
+
+/// The branch HEAD points to if it has no commits yet, as after `create_orphan_checkout`
+pub fn unborn_branch(repo: &LocalRepository) -> Result<Option<String>, OxenError> {
+    with_ref_manager(repo, |manager| {
+        let Some(head_ref) = manager.read_head_ref()? else {
+            return Ok(None);
+        };
+        if manager.has_branch(&head_ref)
+            || repositories::commits::commit_id_exists(repo, &head_ref)?
+        {
+            Ok(None)
+        } else {
+            Ok(Some(head_ref))
+        }
+    })
+}
+
 /// Update the branch name to point to a commit id
 pub fn update(
     repo: &LocalRepository,
@@ -437,6 +480,61 @@ pub async fn set_working_repo_to_commit(
     }
 }
 
+/// Compare a head branch to a base branch, counting the commits each is ahead of the other,
+/// whether head can be merged into base, and a page of the files that changed
+pub async fn compare(
+    repo: &LocalRepository,
+    base: &Branch,
+    head: &Branch,
+    page: usize,
+    page_size: usize,
+) -> Result<BranchCompare, OxenError> {
+    let base_commit = repositories::commits::get_by_id(repo, &base.commit_id)?
+        .ok_or(OxenError::revision_not_found(base.commit_id.clone().into()))?;
+    let head_commit = repositories::commits::get_by_id(repo, &head.commit_id)?
+        .ok_or(OxenError::revision_not_found(head.commit_id.clone().into()))?;
+
+    // Both lists start at the lowest common ancestor, which is on both branches
+    let ahead =
+        repositories::merge::list_commits_between_commits(repo, &base_commit, &head_commit)?
+            .len()
+            .saturating_sub(1);
+    let behind =
+        repositories::merge::list_commits_between_commits(repo, &head_commit, &base_commit)?
+            .len()
+            .saturating_sub(1);
+
+    let conflicts =
+        repositories::merge::list_conflicts_between_commits(repo, &base_commit, &head_commit)
+            .await?;
+    let conflicts: Vec<String> = conflicts
+        .iter()
+        .map(|path| path.to_string_lossy().to_string())
+        .collect();
+
+    let diff = repositories::diffs::list_diff_entries(
+        repo,
+        &base_commit,
+        &head_commit,
+        PathBuf::from(""),
+        PathBuf::from(""),
+        page,
+        page_size,
+    )?;
+
+    Ok(BranchCompare {
+        base: base.clone(),
+        head: head.clone(),
+        ahead,
+        behind,
+        is_mergeable: conflicts.is_empty(),
+        conflicts,
+        counts: diff.counts,
+        entries: diff.entries,
+        pagination: diff.pagination,
+    })
+}
+
 fn branch_name_no_slashes(name: &str) -> String {
     // Replace all slashes with dashes
     name.replace('/', "-")
@@ -637,4 +735,84 @@ mod tests {
         })
         .await
     }
+
+    #[tokio::test]
+    async fn test_orphan_branch_has_no_history() -> Result<(), OxenError> {
+        test::run_one_commit_local_repo_test_async(|repo| async move {
+            let og_branch = repositories::branches::current_branch(&repo)?.unwrap();
+            let og_commit = repositories::commits::head_commit(&repo)?;
+
+            let branch_name = "v2";
+            repositories::branches::create_orphan_checkout(&repo, branch_name)?;
+            assert_eq!(
+                repositories::branches::unborn_branch(&repo)?,
+                Some(branch_name.to_string())
+            );
+            // The branch does not exist until the first commit
+            assert!(!repositories::branches::exists(&repo, branch_name)?);
+
+            let file = repo.path.join("cleaned.csv");
+            util::fs::write_to_path(&file, "a,b\n1,2\n")?;
+            repositories::add(&repo, &file).await?;
+            let commit = repositories::commit(&repo, "Start v2")?;
+
+            assert!(commit.parent_ids.is_empty());
+            let branch = repositories::branches::current_branch(&repo)?.unwrap();
+            assert_eq!(branch.name, branch_name);
+            assert_eq!(branch.commit_id, commit.id);
+            assert_eq!(repositories::branches::unborn_branch(&repo)?, None);
+
+            // Only the new file is in the tree and the old branch is untouched
+            let files = repositories::tree::list_all_files(
+                &repositories::tree::get_root_with_children(&repo, &commit)?.unwrap(),
+                &Path::new("").to_path_buf(),
+            )?;
+            assert_eq!(files.len(), 1);
+            let og_branch_after = repositories::branches::get_by_name(&repo, &og_branch.name)?;
+            assert_eq!(og_branch_after.unwrap().commit_id, og_commit.id);
+
+            // Can't orphan onto an existing branch
+            let result = repositories::branches::create_orphan_checkout(&repo, &og_branch.name);
+            assert!(result.is_err());
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_compare_branches_ahead_behind() -> Result<(), OxenError> {
+        test::run_one_commit_local_repo_test_async(|repo| async move {
+            let main = repositories::branches::current_branch(&repo)?.unwrap();
+
+            // Two commits on the new branch
+            repositories::branches::create_checkout(&repo, "feature")?;
+            for name in ["b.txt", "c.txt"] {
+                let path = repo.path.join(name);
+                util::fs::write_to_path(&path, name)?;
+                repositories::add(&repo, &path).await?;
+                repositories::commit(&repo, &format!("Adding {name}"))?;
+            }
+
+            // One commit on main
+            repositories::checkout(&repo, &main.name).await?;
+            let path = repo.path.join("d.txt");
+            util::fs::write_to_path(&path, "d")?;
+            repositories::add(&repo, &path).await?;
+            repositories::commit(&repo, "Adding d.txt")?;
+
+            let main = repositories::branches::get_by_name(&repo, &main.name)?.unwrap();
+            let feature = repositories::branches::get_by_name(&repo, "feature")?.unwrap();
+            let compare = repositories::branches::compare(&repo, &main, &feature, 1, 10).await?;
+
+            assert_eq!(compare.ahead, 2);
+            assert_eq!(compare.behind, 1);
+            assert!(compare.is_mergeable);
+            assert!(compare.conflicts.is_empty());
+            assert_eq!(compare.counts.added, 2);
+
+            Ok(())
+        })
+        .await
+    }
 }
@@ -0,0 +1,222 @@
+//! # Branch Protection
+//!
+//! Rules that keep branches on a remote from being deleted, force pushed,
+//! or optionally pushed to at all outside of a merge.
+//!
+
+use std::path::PathBuf;
+
+use crate::config::{
+    BranchProtectionConfig, BranchProtectionRule, BRANCH_PROTECTION_CONFIG_FILENAME,
+};
+use crate::error::OxenError;
+use crate::model::{Branch, LocalRepository};
+use crate::repositories;
+use crate::util;
+
+fn config_path(repo: &LocalRepository) -> PathBuf {
+    util::fs::oxen_hidden_dir(&repo.path).join(BRANCH_PROTECTION_CONFIG_FILENAME)
+}
+
+fn read_config(repo: &LocalRepository) -> Result<BranchProtectionConfig, OxenError> {
+    let path = config_path(repo);
+    if !path.exists() {
+        return Ok(BranchProtectionConfig::default());
+    }
+    let config_data = util::fs::read_from_path(&path)?;
+    Ok(toml::from_str(&config_data)?)
+}
+
+fn write_config(repo: &LocalRepository, config: &BranchProtectionConfig) -> Result<(), OxenError> {
+    let config_str = toml::to_string(config)?;
+    std::fs::write(config_path(repo), config_str)?;
+    Ok(())
+}
+
+/// List all the branch protection rules in the repo
+pub fn list(repo: &LocalRepository) -> Result<Vec<BranchProtectionRule>, OxenError> {
+    Ok(read_config(repo)?.rules)
+}
+
+/// Get the rule protecting a branch, if any
+pub fn get(
+    repo: &LocalRepository,
+    branch_name: impl AsRef<str>,
+) -> Result<Option<BranchProtectionRule>, OxenError> {
+    Ok(read_config(repo)?.rule(branch_name).cloned())
+}
+
+/// Add a protection rule, replacing any existing rule for the same branch pattern
+pub fn protect(
+    repo: &LocalRepository,
+    rule: BranchProtectionRule,
+) -> Result<BranchProtectionRule, OxenError> {
+    let mut config = read_config(repo)?;
+    match config.rules.iter_mut().find(|r| r.branch == rule.branch) {
+        Some(existing) => *existing = rule.clone(),
+        None => config.rules.push(rule.clone()),
+    }
+    write_config(repo, &config)?;
+    Ok(rule)
+}
+
+/// Remove the protection rule for a branch pattern, returning it if it existed
+pub fn unprotect(
+    repo: &LocalRepository,
+    branch: impl AsRef<str>,
+) -> Result<Option<BranchProtectionRule>, OxenError> {
+    let branch = branch.as_ref();
+    let mut config = read_config(repo)?;
+    let Some(index) = config.rules.iter().position(|r| r.branch == branch) else {
+        return Ok(None);
+    };
+    let rule = config.rules.remove(index);
+    write_config(repo, &config)?;
+    Ok(Some(rule))
+}
+
+/// Make sure a branch can be deleted
+pub fn check_delete(repo: &LocalRepository, branch_name: impl AsRef<str>) -> Result<(), OxenError> {
+    let branch_name = branch_name.as_ref();
+    if get(repo, branch_name)?.is_some() {
+        return Err(OxenError::protected_branch(
+            branch_name,
+            "it can not be deleted",
+        ));
+    }
+    Ok(())
+}
+
+/// Make sure a push can move the branch to the new commit. Protected branches only
+/// accept fast-forwards, and rules with `require_merge` reject direct pushes entirely.
+pub fn check_push(
+    repo: &LocalRepository,
+    branch: &Branch,
+    new_commit_id: impl AsRef<str>,
+) -> Result<(), OxenError> {
+    let new_commit_id = new_commit_id.as_ref();
+    let Some(rule) = get(repo, &branch.name)? else {
+        return Ok(());
+    };
+
+    if rule.require_merge {
+        return Err(OxenError::protected_branch(
+            &branch.name,
+            "changes must be merged into it instead of pushed",
+        ));
+    }
+
+    if branch.commit_id == new_commit_id {
+        return Ok(());
+    }
+
+    let head = repositories::commits::get_by_id(repo, &branch.commit_id)?
+        .ok_or(OxenError::resource_not_found(&branch.commit_id))?;
+    let new_commit = repositories::commits::get_by_id(repo, new_commit_id)?
+        .ok_or(OxenError::resource_not_found(new_commit_id))?;
+    let lca = repositories::merge::lowest_common_ancestor_from_commits(repo, &head, &new_commit)?;
+    if lca.id != head.id {
+        return Err(OxenError::protected_branch(
+            &branch.name,
+            "force pushes are not allowed, pull and merge the remote changes first",
+        ));
+    }
+    Ok(())
+}
+
+/// Make sure a push can create a merge commit on the branch
+pub fn check_push_merge(repo: &LocalRepository, branch: &Branch) -> Result<(), OxenError> {
+    match get(repo, &branch.name)? {
+        Some(rule) if rule.require_merge => Err(OxenError::protected_branch(
+            &branch.name,
+            "changes must be merged into it instead of pushed",
+        )),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::BranchProtectionRule;
+    use crate::error::OxenError;
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    #[tokio::test]
+    async fn test_protected_branch_rejects_delete_and_force_push() -> Result<(), OxenError> {
+        test::run_one_commit_local_repo_test_async(|repo| async move {
+            let main = repositories::branches::current_branch(&repo)?.unwrap();
+            repositories::branches::protection::protect(
+                &repo,
+                BranchProtectionRule::new(&main.name),
+            )?;
+
+            // A commit on a branch that diverged from main
+            let root = repositories::commits::head_commit(&repo)?;
+            repositories::branches::create_checkout(&repo, "feature")?;
+            let path = repo.path.join("feature.txt");
+            util::fs::write_to_path(&path, "feature")?;
+            repositories::add(&repo, &path).await?;
+            let feature_commit = repositories::commit(&repo, "Adding feature.txt")?;
+
+            // Move main forward separately
+            repositories::checkout(&repo, &main.name).await?;
+            let path = repo.path.join("main.txt");
+            util::fs::write_to_path(&path, "main")?;
+            repositories::add(&repo, &path).await?;
+            repositories::commit(&repo, "Adding main.txt")?;
+            let main = repositories::branches::get_by_name(&repo, &main.name)?.unwrap();
+
+            assert!(repositories::branches::protection::check_delete(&repo, &main.name).is_err());
+            assert!(repositories::branches::protection::check_delete(&repo, "feature").is_ok());
+
+            // Rewinding or jumping to a diverged commit is a force push
+            let result = repositories::branches::protection::check_push(&repo, &main, &root.id);
+            assert!(matches!(result, Err(OxenError::ProtectedBranch(_))));
+            let result =
+                repositories::branches::protection::check_push(&repo, &main, &feature_commit.id);
+            assert!(matches!(result, Err(OxenError::ProtectedBranch(_))));
+
+            // Fast-forwards from the previous head are fine
+            let path = repo.path.join("main2.txt");
+            util::fs::write_to_path(&path, "main2")?;
+            repositories::add(&repo, &path).await?;
+            let next_commit = repositories::commit(&repo, "Adding main2.txt")?;
+            repositories::branches::protection::check_push(&repo, &main, &next_commit.id)?;
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_protected_branch_require_merge() -> Result<(), OxenError> {
+        test::run_one_commit_local_repo_test_async(|repo| async move {
+            let main = repositories::branches::current_branch(&repo)?.unwrap();
+            let rule = BranchProtectionRule {
+                branch: "rel*".to_string(),
+                require_merge: true,
+            };
+            repositories::branches::protection::protect(&repo, rule.clone())?;
+            let release = repositories::branches::create_from_head(&repo, "release")?;
+
+            assert!(repositories::branches::protection::check_push_merge(&repo, &main).is_ok());
+            assert!(repositories::branches::protection::check_push_merge(&repo, &release).is_err());
+            let result =
+                repositories::branches::protection::check_push(&repo, &release, &release.commit_id);
+            assert!(matches!(result, Err(OxenError::ProtectedBranch(_))));
+
+            let rules = repositories::branches::protection::list(&repo)?;
+            assert_eq!(rules, vec![rule.clone()]);
+
+            let removed = repositories::branches::protection::unprotect(&repo, "rel*")?;
+            assert_eq!(removed, Some(rule));
+            assert!(repositories::branches::protection::list(&repo)?.is_empty());
+            repositories::branches::protection::check_push_merge(&repo, &release)?;
+
+            Ok(())
+        })
+        .await
+    }
+}
@@ -0,0 +1,429 @@
+//! # oxen bundle
+//!
+//! Pack commits, their merkle tree nodes and their version files into a single file, so a
+//! repository can be moved to a machine without network access, like `git bundle`.
+//!
+//! A bundle is a gzipped tarball laid out like the `.oxen` dir it came from, with the
+//! version files under `versions/<hash>` and a `bundle.json` manifest listing the
+//! branches it carries. A bundle of a range of commits leaves out the history before the
+//! range, and can only be unbundled into a repository that has that history.
+//!
+
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use tar::Archive;
+use time::OffsetDateTime;
+
+use crate::constants::{HISTORY_DIR, NODES_DIR, TREE_DIR};
+use crate::core::commit_sync_status;
+use crate::core::db::merkle_node::merkle_node_db::{node_db_path, node_db_prefix};
+use crate::error::OxenError;
+use crate::model::merkle_tree::node::EMerkleTreeNode;
+use crate::model::{Branch, Commit, LocalRepository, MerkleHash};
+use crate::repositories;
+use crate::util;
+
+const MANIFEST_FILE: &str = "bundle.json";
+const VERSIONS_DIR: &str = "versions";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BundleManifest {
+    /// The branches in the bundle and the commits they point to
+    pub branches: Vec<Branch>,
+    /// Every commit in the bundle
+    pub commit_ids: Vec<String>,
+    /// Commits the bundle builds on but does not contain, the repository it is unbundled
+    /// into must already have them
+    pub prerequisites: Vec<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+/// Write a bundle of the repository to `path`. Without a revision the bundle has every
+/// branch and its full history. A revision bundles the history of that branch or commit,
+/// and a `base..head` range only the commits in head that are not in base.
+pub async fn create(
+    repo: &LocalRepository,
+    path: impl AsRef<Path>,
+    revision: Option<&str>,
+) -> Result<BundleManifest, OxenError> {
+    let (commits, branches) = bundle_commits(repo, revision)?;
+    if commits.is_empty() {
+        return Err(OxenError::basic_str("Refusing to create an empty bundle"));
+    }
+
+    let commit_ids: HashSet<&str> = commits.iter().map(|commit| commit.id.as_str()).collect();
+    let mut prerequisites: Vec<String> = commits
+        .iter()
+        .flat_map(|commit| commit.parent_ids.iter())
+        .filter(|parent_id| !commit_ids.contains(parent_id.as_str()))
+        .cloned()
+        .collect::<HashSet<String>>()
+        .into_iter()
+        .collect();
+    prerequisites.sort();
+
+    let manifest = BundleManifest {
+        branches,
+        commit_ids: commits.iter().map(|commit| commit.id.clone()).collect(),
+        prerequisites,
+        created_at: OffsetDateTime::now_utc(),
+    };
+
+    // The repository the bundle is unbundled into already has the prerequisite files
+    let mut known_hashes: HashMap<String, u64> = HashMap::new();
+    for commit_id in &manifest.prerequisites {
+        if let Some(commit) = repositories::commits::get_by_id(repo, commit_id)? {
+            collect_file_hashes(repo, &commit, &mut known_hashes)?;
+        }
+    }
+    let mut file_hashes: HashMap<String, u64> = HashMap::new();
+    for commit in &commits {
+        collect_file_hashes(repo, commit, &mut file_hashes)?;
+    }
+    let node_hashes =
+        repositories::tree::get_all_node_hashes_for_commits(repo, &commits, &None, &None, false)?;
+
+    let file = File::create(path.as_ref())?;
+    let mut tar = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+
+    let manifest_json = serde_json::to_vec_pretty(&manifest)?;
+    let mut header = tar::Header::new_gnu();
+    header.set_size(manifest_json.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    tar.append_data(&mut header, MANIFEST_FILE, manifest_json.as_slice())?;
+
+    for hash in &node_hashes {
+        let node_dir = node_db_path(repo, hash);
+        if node_dir.exists() {
+            let tar_subdir = Path::new(TREE_DIR)
+                .join(NODES_DIR)
+                .join(node_db_prefix(hash));
+            tar.append_dir_all(tar_subdir, node_dir)?;
+        }
+    }
+    let history_dir = util::fs::oxen_hidden_dir(&repo.path).join(HISTORY_DIR);
+    for commit in &commits {
+        let commit_dir = history_dir.join(&commit.id);
+        if commit_dir.exists() {
+            tar.append_dir_all(Path::new(HISTORY_DIR).join(&commit.id), commit_dir)?;
+        }
+    }
+
+    let version_store = repo.version_store()?;
+    let version_hashes: Vec<(&String, &u64)> = file_hashes
+        .iter()
+        .filter(|(hash, _)| !known_hashes.contains_key(*hash))
+        .collect();
+    for (hash, num_bytes) in &version_hashes {
+        if !version_store.version_exists(hash)? {
+            return Err(OxenError::basic_str(format!(
+                "Cannot bundle version {hash}, it is not in the version store. Pull the missing files first."
+            )));
+        }
+        // The size of the contents, a compressed store keeps fewer bytes on disk
+        let mut header = tar::Header::new_gnu();
+        header.set_size(**num_bytes);
+        header.set_mode(0o644);
+        header.set_cksum();
+        tar.append_data(
+            &mut header,
+            Path::new(VERSIONS_DIR).join(hash.as_str()),
+            version_store.open_version(hash)?,
+        )?;
+    }
+    tar.into_inner()?.finish()?;
+
+    log::debug!(
+        "bundled {} commits, {} nodes and {} versions into {:?}",
+        manifest.commit_ids.len(),
+        node_hashes.len(),
+        version_hashes.len(),
+        path.as_ref()
+    );
+    Ok(manifest)
+}
+
+/// Read the manifest of a bundle without unpacking it
+pub fn read_manifest(path: impl AsRef<Path>) -> Result<BundleManifest, OxenError> {
+    let mut archive = Archive::new(GzDecoder::new(File::open(path.as_ref())?));
+    for entry in archive.entries()? {
+        let entry = entry?;
+        if entry.path()?.as_os_str() == MANIFEST_FILE {
+            return Ok(serde_json::from_reader(entry)?);
+        }
+    }
+    Err(OxenError::basic_str(format!(
+        "{:?} is not an oxen bundle, it has no {MANIFEST_FILE}",
+        path.as_ref()
+    )))
+}
+
+/// Import the commits, trees and version files of a bundle into the repository without
+/// touching its branches. Fails if the repository is missing a prerequisite commit.
+pub async fn unbundle(
+    repo: &LocalRepository,
+    path: impl AsRef<Path>,
+) -> Result<BundleManifest, OxenError> {
+    let path = path.as_ref();
+    let manifest = read_manifest(path)?;
+    let mut missing: Vec<&str> = vec![];
+    for commit_id in &manifest.prerequisites {
+        if !repositories::commits::commit_id_exists(repo, commit_id)? {
+            missing.push(commit_id);
+        }
+    }
+    if !missing.is_empty() {
+        return Err(OxenError::basic_str(format!(
+            "The bundle builds on commits this repository does not have: {}",
+            missing.join(", ")
+        )));
+    }
+
+    let hidden_dir = util::fs::oxen_hidden_dir(&repo.path);
+    let tmp_dir = hidden_dir.join("tmp").join("bundle");
+    util::fs::create_dir_all(&tmp_dir)?;
+    let version_store = repo.version_store()?;
+
+    let mut archive = Archive::new(GzDecoder::new(File::open(path)?));
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let entry_path: PathBuf = entry.path()?.into_owned();
+        if entry_path.starts_with(VERSIONS_DIR) {
+            let Some(hash) = entry_path.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            if version_store.version_exists(hash)? {
+                continue;
+            }
+            let tmp_path = tmp_dir.join(hash);
+            entry.unpack(&tmp_path)?;
+            let contents_hash = repo.hash_algorithm().hash_file_contents(&tmp_path)?;
+            if MerkleHash::new(contents_hash).to_string() != hash {
+                util::fs::remove_dir_all(&tmp_dir)?;
+                return Err(OxenError::basic_str(format!(
+                    "Version {hash} in the bundle does not match its hash, the bundle is corrupt"
+                )));
+            }
+            version_store
+                .store_version_from_path(hash, &tmp_path)
+                .await?;
+            util::fs::remove_file(&tmp_path)?;
+        } else if entry_path.starts_with(TREE_DIR) || entry_path.starts_with(HISTORY_DIR) {
+            // Files from a bundle never overwrite what the repository already has
+            if !hidden_dir.join(&entry_path).exists() {
+                entry.unpack_in(&hidden_dir)?;
+            }
+        }
+    }
+    util::fs::remove_dir_all(&tmp_dir)?;
+
+    for commit_id in &manifest.commit_ids {
+        commit_sync_status::mark_commit_as_synced(repo, &MerkleHash::from_str(commit_id)?)?;
+    }
+    Ok(manifest)
+}
+
+/// Unbundle, then point the branches in the bundle at their commits. New branches are
+/// created, and existing branches are only moved forward so no local commits are lost.
+/// Returns the branches that were created or updated.
+pub async fn fetch(
+    repo: &LocalRepository,
+    path: impl AsRef<Path>,
+) -> Result<Vec<Branch>, OxenError> {
+    let manifest = unbundle(repo, path).await?;
+
+    let mut updates: Vec<Branch> = vec![];
+    for branch in manifest.branches {
+        match repositories::branches::get_by_name(repo, &branch.name)? {
+            Some(local) if local.commit_id == branch.commit_id => continue,
+            Some(local) => {
+                let history = repositories::commits::list_from(repo, &branch.commit_id)?;
+                if !history.iter().any(|commit| commit.id == local.commit_id) {
+                    return Err(OxenError::basic_str(format!(
+                        "Branch {} in the bundle does not build on local commit {}, merge or delete the local branch first",
+                        branch.name, local.commit_id
+                    )));
+                }
+                updates.push(branch);
+            }
+            None => updates.push(branch),
+        }
+    }
+    for branch in &updates {
+        repositories::branches::update(repo, &branch.name, &branch.commit_id)?;
+    }
+    Ok(updates)
+}
+
+/// The commits a bundle of the revision holds, and the branches it carries
+fn bundle_commits(
+    repo: &LocalRepository,
+    revision: Option<&str>,
+) -> Result<(Vec<Commit>, Vec<Branch>), OxenError> {
+    let Some(revision) = revision else {
+        let commits = repositories::commits::list_all(repo)?.into_iter().collect();
+        return Ok((commits, repositories::branches::list(repo)?));
+    };
+
+    let (base, head) = match revision.split_once("..") {
+        Some((base, head)) => (Some(base), head),
+        None => (None, revision),
+    };
+    let head_commit = repositories::revisions::get(repo, head)?
+        .ok_or(OxenError::revision_not_found(head.into()))?;
+    let mut commits = repositories::commits::list_from(repo, &head_commit.id)?;
+    if let Some(base) = base {
+        let base_commit = repositories::revisions::get(repo, base)?
+            .ok_or(OxenError::revision_not_found(base.into()))?;
+        let base_ids: HashSet<String> = repositories::commits::list_from(repo, &base_commit.id)?
+            .into_iter()
+            .map(|commit| commit.id)
+            .collect();
+        commits.retain(|commit| !base_ids.contains(&commit.id));
+    }
+
+    // Only a branch name carries a ref, a bare commit id is just its history
+    let branches = match repositories::branches::get_by_name(repo, head)? {
+        Some(branch) => vec![Branch {
+            commit_id: head_commit.id.clone(),
+            ..branch
+        }],
+        None => vec![],
+    };
+    Ok((commits, branches))
+}
+
+fn collect_file_hashes(
+    repo: &LocalRepository,
+    commit: &Commit,
+    hashes: &mut HashMap<String, u64>,
+) -> Result<(), OxenError> {
+    let Some(tree) = repositories::tree::get_root_with_children(repo, commit)? else {
+        return Err(OxenError::basic_str(format!(
+            "Could not load the tree for commit {}",
+            commit.id
+        )));
+    };
+    tree.walk_tree(|node| {
+        if let EMerkleTreeNode::File(file) = &node.node {
+            hashes.insert(node.hash.to_string(), file.num_bytes());
+        }
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    use std::io::Read;
+
+    use flate2::read::GzDecoder;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use tar::Archive;
+
+    use super::VERSIONS_DIR;
+    use crate::constants::DEFAULT_BRANCH_NAME;
+    use crate::error::OxenError;
+    use crate::opts::RestoreOpts;
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    #[tokio::test]
+    async fn test_bundle_and_fetch_into_another_repo() -> Result<(), OxenError> {
+        test::run_empty_dir_test_async(|dir| async move {
+            let repo = repositories::init(dir.join("source"))?;
+            let path = repo.path.join("data.txt");
+            util::fs::write_to_path(&path, "first")?;
+            repositories::add(&repo, &path).await?;
+            let first = repositories::commit(&repo, "First")?;
+
+            let full_bundle = dir.join("full.bundle");
+            let manifest = repositories::bundle::create(&repo, &full_bundle, None).await?;
+            assert_eq!(manifest.commit_ids, vec![first.id.clone()]);
+            assert!(manifest.prerequisites.is_empty());
+
+            util::fs::write_to_path(&path, "second")?;
+            repositories::add(&repo, &path).await?;
+            let second = repositories::commit(&repo, "Second")?;
+            let range_bundle = dir.join("range.bundle");
+            let range = format!("{}..{}", first.id, DEFAULT_BRANCH_NAME);
+            let manifest = repositories::bundle::create(&repo, &range_bundle, Some(&range)).await?;
+            assert_eq!(manifest.commit_ids, vec![second.id.clone()]);
+            assert_eq!(manifest.prerequisites, vec![first.id.clone()]);
+
+            // The range can't be unbundled without the history it builds on
+            let target = repositories::init(dir.join("target"))?;
+            assert!(repositories::bundle::unbundle(&target, &range_bundle)
+                .await
+                .is_err());
+
+            let updated = repositories::bundle::fetch(&target, &full_bundle).await?;
+            assert_eq!(updated.len(), 1);
+            let updated = repositories::bundle::fetch(&target, &range_bundle).await?;
+            assert_eq!(updated[0].commit_id, second.id);
+
+            repositories::restore(&target, RestoreOpts::from_path("data.txt")).await?;
+            assert_eq!(
+                util::fs::read_from_path(target.path.join("data.txt"))?,
+                "second"
+            );
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_unbundle_rejects_corrupt_versions() -> Result<(), OxenError> {
+        test::run_empty_dir_test_async(|dir| async move {
+            let repo = repositories::init(dir.join("source"))?;
+            let path = repo.path.join("data.txt");
+            util::fs::write_to_path(&path, "first")?;
+            repositories::add(&repo, &path).await?;
+            repositories::commit(&repo, "First")?;
+            let bundle_path = dir.join("full.bundle");
+            repositories::bundle::create(&repo, &bundle_path, None).await?;
+
+            // Copy the bundle, swapping the contents of its version files
+            let corrupt_path = dir.join("corrupt.bundle");
+            let mut corrupt = tar::Builder::new(GzEncoder::new(
+                File::create(&corrupt_path)?,
+                Compression::default(),
+            ));
+            let mut archive = Archive::new(GzDecoder::new(File::open(&bundle_path)?));
+            for entry in archive.entries()? {
+                let mut entry = entry?;
+                let entry_path = entry.path()?.into_owned();
+                let mut header = entry.header().clone();
+                let mut data = vec![];
+                entry.read_to_end(&mut data)?;
+                if entry_path.starts_with(VERSIONS_DIR) {
+                    data = b"tampered".to_vec();
+                    header.set_size(data.len() as u64);
+                }
+                corrupt.append_data(&mut header, &entry_path, data.as_slice())?;
+            }
+            corrupt.into_inner()?.finish()?;
+
+            let target = repositories::init(dir.join("target"))?;
+            assert!(repositories::bundle::unbundle(&target, &corrupt_path)
+                .await
+                .is_err());
+            repositories::bundle::unbundle(&target, &bundle_path).await?;
+
+            Ok(())
+        })
+        .await
+    }
+}
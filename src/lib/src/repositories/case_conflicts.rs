@@ -0,0 +1,270 @@
+//! # Case conflicts
+//!
+//! Paths that only differ by case, like `Data.csv` and `data.csv`, can be committed from a
+//! case-sensitive file system but overwrite each other when checked out on macOS or
+//! Windows. Adds and commits look for them among the staged entries, and checkouts on
+//! case-insensitive file systems look for them in the tree being checked out. Whether they
+//! are allowed, printed as a warning or refused is set by `case_conflicts` in the repo
+//! config.
+//!
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use indicatif::ProgressBar;
+use rocksdb::{DBWithThreadMode, SingleThreaded};
+
+use crate::config::CaseConflictMode;
+use crate::constants::STAGED_DIR;
+use crate::core::db;
+use crate::core::v_latest::status;
+use crate::error::OxenError;
+use crate::model::merkle_tree::node::{EMerkleTreeNode, MerkleTreeNode, StagedMerkleTreeNode};
+use crate::model::{LocalRepository, StagedEntryStatus};
+use crate::repositories;
+use crate::util;
+
+/// Group the `names` of the entries in `dir` that only differ by case. Each group is
+/// sorted and only has the names that collide.
+pub fn find_in_names(
+    dir: impl AsRef<Path>,
+    names: impl IntoIterator<Item = String>,
+) -> Vec<Vec<PathBuf>> {
+    let dir = dir.as_ref();
+    let mut by_lowercase: HashMap<String, HashSet<String>> = HashMap::new();
+    for name in names {
+        by_lowercase
+            .entry(name.to_lowercase())
+            .or_default()
+            .insert(name);
+    }
+
+    let mut conflicts: Vec<Vec<PathBuf>> = by_lowercase
+        .into_values()
+        .filter(|names| names.len() > 1)
+        .map(|names| {
+            let mut paths: Vec<PathBuf> = names.into_iter().map(|name| dir.join(name)).collect();
+            paths.sort();
+            paths
+        })
+        .collect();
+    conflicts.sort();
+    conflicts
+}
+
+/// Find the paths that only differ by case in a loaded tree, `base_path` being the path of
+/// `node` in the repository. Takes a commit node or a dir node.
+pub fn find_in_tree(
+    node: &MerkleTreeNode,
+    base_path: impl AsRef<Path>,
+) -> Result<Vec<Vec<PathBuf>>, OxenError> {
+    let dir = match &node.node {
+        EMerkleTreeNode::Commit(_) => repositories::tree::get_root_dir(node)?,
+        _ => node,
+    };
+    let mut conflicts = Vec::new();
+    r_find_in_tree(dir, base_path.as_ref(), &mut conflicts)?;
+    conflicts.sort();
+    Ok(conflicts)
+}
+
+fn r_find_in_tree(
+    dir: &MerkleTreeNode,
+    path: &Path,
+    conflicts: &mut Vec<Vec<PathBuf>>,
+) -> Result<(), OxenError> {
+    let children = repositories::tree::list_files_and_folders(dir)?;
+    let names = children.iter().filter_map(|child| entry_name(&child.node));
+    conflicts.extend(find_in_names(path, names));
+
+    for child in &children {
+        if let EMerkleTreeNode::Directory(_) = &child.node {
+            let name = entry_name(&child.node).unwrap_or_default();
+            r_find_in_tree(child, &path.join(name), conflicts)?;
+        }
+    }
+    Ok(())
+}
+
+/// Find the paths that would only differ by case once the staged entries are committed,
+/// comparing the staged entries of each dir with each other and with what the dir holds
+/// in the head commit
+pub fn find_in_staged(
+    repo: &LocalRepository,
+    dir_entries: &HashMap<PathBuf, Vec<StagedMerkleTreeNode>>,
+) -> Result<Vec<Vec<PathBuf>>, OxenError> {
+    let head_commit = repositories::commits::head_commit_maybe(repo)?;
+    let mut conflicts = Vec::new();
+    for (dir, entries) in dir_entries {
+        let mut names: HashSet<String> = HashSet::new();
+        let mut removed: HashSet<String> = HashSet::new();
+        for entry in entries {
+            let Some(name) = entry_name(&entry.node.node) else {
+                continue;
+            };
+            if entry.status == StagedEntryStatus::Removed {
+                removed.insert(name);
+            } else {
+                names.insert(name);
+            }
+        }
+        if names.is_empty() {
+            continue;
+        }
+
+        if let Some(head_commit) = &head_commit {
+            if let Some(dir_node) =
+                repositories::tree::get_dir_with_children(repo, head_commit, dir)?
+            {
+                for child in repositories::tree::list_files_and_folders(&dir_node)? {
+                    if let Some(name) = entry_name(&child.node) {
+                        if !removed.contains(&name) {
+                            names.insert(name);
+                        }
+                    }
+                }
+            }
+        }
+        conflicts.extend(find_in_names(dir, names));
+    }
+    conflicts.sort();
+    Ok(conflicts)
+}
+
+/// Check the entries in the staged db against the `case_conflicts` config of the repo
+pub fn check_staged(repo: &LocalRepository) -> Result<(), OxenError> {
+    if repo.case_conflict_mode() == CaseConflictMode::Allow {
+        return Ok(());
+    }
+    let db_path = util::fs::oxen_hidden_dir(&repo.path).join(STAGED_DIR);
+    if !db_path.exists() {
+        return Ok(());
+    }
+
+    let opts = db::key_val::opts::default();
+    let staged_db: DBWithThreadMode<SingleThreaded> =
+        DBWithThreadMode::open_for_read_only(&opts, dunce::simplified(&db_path), false)?;
+    let (dir_entries, _) = status::read_staged_entries(repo, &staged_db, &ProgressBar::hidden())?;
+    check_staged_entries(repo, &dir_entries)
+}
+
+/// Check staged entries that were already read against the `case_conflicts` config of the
+/// repo
+pub fn check_staged_entries(
+    repo: &LocalRepository,
+    dir_entries: &HashMap<PathBuf, Vec<StagedMerkleTreeNode>>,
+) -> Result<(), OxenError> {
+    if repo.case_conflict_mode() == CaseConflictMode::Allow {
+        return Ok(());
+    }
+    check(repo, &find_in_staged(repo, dir_entries)?)
+}
+
+/// Allow, warn about or refuse the `conflicts` depending on the `case_conflicts` config of
+/// the repo
+pub fn check(repo: &LocalRepository, conflicts: &[Vec<PathBuf>]) -> Result<(), OxenError> {
+    if conflicts.is_empty() {
+        return Ok(());
+    }
+
+    let groups = conflicts
+        .iter()
+        .map(|paths| {
+            let paths: Vec<String> = paths
+                .iter()
+                .map(|path| path.to_string_lossy().to_string())
+                .collect();
+            format!("  {}", paths.join(", "))
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+    match repo.case_conflict_mode() {
+        CaseConflictMode::Allow => Ok(()),
+        CaseConflictMode::Warn => {
+            eprintln!(
+                "Warning: these paths only differ by case and will overwrite each other on case-insensitive file systems:\n{groups}"
+            );
+            Ok(())
+        }
+        CaseConflictMode::Error => Err(OxenError::basic_str(format!(
+            "These paths only differ by case and would overwrite each other on case-insensitive file systems:\n{groups}\n\nRename them, or set case_conflicts = \"warn\" in .oxen/config.toml to allow them."
+        ))),
+    }
+}
+
+// Staged file and dir nodes are named by their full path, committed ones by their file name
+fn entry_name(node: &EMerkleTreeNode) -> Option<String> {
+    let name = match node {
+        EMerkleTreeNode::File(file_node) => file_node.name(),
+        EMerkleTreeNode::Directory(dir_node) => dir_node.name(),
+        _ => return None,
+    };
+    Path::new(name)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::config::CaseConflictMode;
+    use crate::error::OxenError;
+    use crate::opts::RmOpts;
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    #[test]
+    fn test_find_in_names() {
+        let names = ["Data.csv", "data.csv", "README.md", "DATA.CSV", "other.csv"]
+            .iter()
+            .map(|name| name.to_string());
+        let conflicts = repositories::case_conflicts::find_in_names("train", names);
+        assert_eq!(
+            conflicts,
+            vec![vec![
+                PathBuf::from("train/DATA.CSV"),
+                PathBuf::from("train/Data.csv"),
+                PathBuf::from("train/data.csv"),
+            ]]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_case_conflicts_error_mode() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|mut repo| async move {
+            // Both files can only exist on a case-sensitive file system
+            if util::fs::is_case_insensitive(&repo.path) {
+                return Ok(());
+            }
+
+            util::fs::write_to_path(repo.path.join("Data.csv"), "a,b\n1,2\n")?;
+            repositories::add(&repo, repo.path.join("Data.csv")).await?;
+            repositories::commit(&repo, "Add Data.csv")?;
+
+            // Warn by default
+            util::fs::write_to_path(repo.path.join("data.csv"), "a,b\n3,4\n")?;
+            repositories::add(&repo, repo.path.join("data.csv")).await?;
+
+            repo.set_case_conflict_mode(CaseConflictMode::Error);
+            repo.save()?;
+            assert!(repositories::commit(&repo, "Add data.csv").is_err());
+            assert!(repositories::add(&repo, repo.path.join("data.csv"))
+                .await
+                .is_err());
+
+            // Removing the committed file resolves the conflict
+            repositories::rm(&repo, &RmOpts::from_path("Data.csv"))?;
+            repositories::commit(&repo, "Rename Data.csv to data.csv")?;
+
+            let head = repositories::commits::head_commit(&repo)?;
+            let tree =
+                repositories::tree::get_root_with_children(&repo, &head)?.expect("tree exists");
+            assert!(repositories::case_conflicts::find_in_tree(&tree, "")?.is_empty());
+
+            Ok(())
+        })
+        .await
+    }
+}
@@ -33,12 +33,13 @@ pub async fn checkout(
             Some(paths_vec) => paths_vec, // If Some(vec), take the inner vector
             None => vec![Path::new("").to_path_buf()],
         };
-        let depth = match repo.depth() {
-            Some(d) => d,
-            None => i32::MAX,
-        }; //TODO: make repo depth not an option so that we use depth from the repo consistently.
-        repositories::branches::checkout_subtrees_to_commit(repo, &commit, &subtree_paths, depth)
-            .await?;
+        repositories::branches::checkout_subtrees_to_commit(
+            repo,
+            &commit,
+            &subtree_paths,
+            checkout_depth(repo),
+        )
+        .await?;
         repositories::branches::set_head(repo, value)?;
         repositories::branches::get_by_name(repo, value)
     } else {
@@ -51,9 +52,24 @@ pub async fn checkout(
         let commit = repositories::revisions::get(repo, value)?
             .ok_or(OxenError::revision_not_found(value.into()))?;
 
-        let previous_head_commit = repositories::commits::head_commit_maybe(repo)?;
-        repositories::branches::checkout_commit_from_commit(repo, &commit, &previous_head_commit)
+        if let Some(subtree_paths) = repo.subtree_paths() {
+            // Only materialize the subtrees of a subtree clone or sparse checkout
+            repositories::branches::checkout_subtrees_to_commit(
+                repo,
+                &commit,
+                &subtree_paths,
+                checkout_depth(repo),
+            )
             .await?;
+        } else {
+            let previous_head_commit = repositories::commits::head_commit_maybe(repo)?;
+            repositories::branches::checkout_commit_from_commit(
+                repo,
+                &commit,
+                &previous_head_commit,
+            )
+            .await?;
+        }
         repositories::branches::update(repo, value, &commit.id)?;
         repositories::branches::set_head(repo, value)?;
 
@@ -68,6 +84,11 @@ pub async fn checkout(
     }
 }
 
+//TODO: make repo depth not an option so that we use depth from the repo consistently.
+fn checkout_depth(repo: &LocalRepository) -> i32 {
+    repo.depth().unwrap_or(i32::MAX)
+}
+
 /// # Checkout a file and take their changes
 /// This overwrites the current file with the changes in the branch we are merging in
 pub async fn checkout_theirs(
@@ -772,6 +793,91 @@ mod tests {
         .await
     }
 
+    #[tokio::test]
+    async fn test_checkout_restores_empty_dir() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            let hello_file = repo.path.join("hello.txt");
+            util::fs::write_to_path(&hello_file, "Hello")?;
+            let empty_dir = repo.path.join("logs");
+            util::fs::create_dir_all(&empty_dir)?;
+
+            repositories::add(&repo, &repo.path).await?;
+            repositories::commit(&repo, "Added hello.txt and an empty dir")?;
+
+            // The committed empty dir is no longer untracked
+            let status = repositories::status(&repo)?;
+            assert!(status.untracked_dirs.is_empty());
+            assert!(status.is_clean());
+
+            let orig_branch = repositories::branches::current_branch(&repo)?.unwrap();
+            let branch_name = "feature/no-logs";
+            repositories::branches::create_checkout(&repo, branch_name)?;
+
+            util::fs::remove_dir_all(&empty_dir)?;
+            repositories::add(&repo, &empty_dir).await?;
+            repositories::commit(&repo, "Removed the empty dir")?;
+
+            // Checking out the original branch recreates the empty dir
+            repositories::checkout(&repo, orig_branch.name).await?;
+            assert!(empty_dir.is_dir());
+            assert!(repositories::status(&repo)?.is_clean());
+
+            // And the branch without it removes it again
+            repositories::checkout(&repo, branch_name).await?;
+            assert!(!empty_dir.exists());
+            assert!(hello_file.exists());
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_checkout_restores_executable_bit() -> Result<(), OxenError> {
+        use std::os::unix::fs::PermissionsExt;
+
+        test::run_empty_local_repo_test_async(|repo| async move {
+            let script = repo.path.join("run.sh");
+            util::fs::write_to_path(&script, "#!/bin/sh\necho hello\n")?;
+            std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755))?;
+
+            repositories::add(&repo, &script).await?;
+            let commit = repositories::commit(&repo, "Added an executable script")?;
+            let node = repositories::tree::get_file_by_path(&repo, &commit, "run.sh")?.unwrap();
+            assert!(node.is_executable());
+
+            let orig_branch = repositories::branches::current_branch(&repo)?.unwrap();
+            let branch_name = "feature/not-executable";
+            repositories::branches::create_checkout(&repo, branch_name)?;
+
+            // Only the mode changes, the contents and the mtime stay the same
+            std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o644))?;
+            let status = repositories::status(&repo)?;
+            assert!(status
+                .modified_files
+                .contains(&std::path::PathBuf::from("run.sh")));
+
+            repositories::add(&repo, &script).await?;
+            let commit = repositories::commit(&repo, "Made the script not executable")?;
+            let node = repositories::tree::get_file_by_path(&repo, &commit, "run.sh")?.unwrap();
+            assert!(!node.is_executable());
+            assert!(repositories::status(&repo)?.is_clean());
+
+            repositories::checkout(&repo, orig_branch.name).await?;
+            assert_eq!(
+                std::fs::metadata(&script)?.permissions().mode() & 0o111,
+                0o111
+            );
+
+            repositories::checkout(&repo, branch_name).await?;
+            assert_eq!(std::fs::metadata(&script)?.permissions().mode() & 0o111, 0);
+
+            Ok(())
+        })
+        .await
+    }
+
     // Test the default clone (not --all or --shallow) can revert to files that are not local
     #[tokio::test]
     async fn test_checkout_deleted_after_clone() -> Result<(), OxenError> {
@@ -0,0 +1,445 @@
+//! # Checks
+//!
+//! Validate tabular files against the rules in `.oxen/checks.toml` before they are added
+//! or committed: required columns, dtypes, null thresholds, value ranges and unique keys.
+//! See [`ChecksConfig`] for the format of the rules.
+//!
+
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use polars::prelude::{DataFrame, DataType};
+use serde::{Deserialize, Serialize};
+
+use crate::config::{CheckRule, ChecksConfig, CHECKS_CONFIG_FILENAME};
+use crate::core::df::tabular;
+use crate::core::v_latest::status;
+use crate::error::OxenError;
+use crate::model::merkle_tree::node::{EMerkleTreeNode, StagedMerkleTreeNode};
+use crate::model::{Commit, LocalRepository, Schema, StagedEntryStatus};
+use crate::opts::DFOpts;
+use crate::repositories;
+use crate::util;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckFailureKind {
+    /// The file matched a rule but could not be read as a data frame
+    Unreadable,
+    /// A column a rule refers to is not in the file
+    MissingColumn,
+    /// The column has a different dtype than the rule expects
+    Dtype,
+    /// More of the column is null than the rule allows
+    Nulls,
+    /// Values of the column fall outside the rule's range
+    Range,
+    /// Rows share the same values in the rule's unique columns
+    Unique,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CheckFailure {
+    pub kind: CheckFailureKind,
+    /// The file that failed, relative to the repository root
+    pub path: PathBuf,
+    /// The `path` pattern of the rule that failed
+    pub rule: String,
+    pub column: Option<String>,
+    pub message: String,
+}
+
+impl fmt::Display for CheckFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.column {
+            Some(column) => write!(
+                f,
+                "{} [{}] column {:?}: {}",
+                self.path.display(),
+                self.rule,
+                column,
+                self.message
+            ),
+            None => write!(
+                f,
+                "{} [{}]: {}",
+                self.path.display(),
+                self.rule,
+                self.message
+            ),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ChecksReport {
+    pub files_checked: usize,
+    pub failures: Vec<CheckFailure>,
+}
+
+impl ChecksReport {
+    pub fn is_ok(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// Turn a report with failures into an error, so callers can `?` it
+    pub fn into_result(self) -> Result<ChecksReport, OxenError> {
+        if self.is_ok() {
+            Ok(self)
+        } else {
+            Err(OxenError::checks_failed(self))
+        }
+    }
+}
+
+impl fmt::Display for ChecksReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} checks failed in {} files:",
+            self.failures.len(),
+            self.files_checked
+        )?;
+        for failure in self.failures.iter() {
+            writeln!(f, "  {failure}")?;
+        }
+        write!(f, "\nFix the data, or skip the checks with --no-verify")
+    }
+}
+
+fn config_path(repo: &LocalRepository) -> PathBuf {
+    util::fs::oxen_hidden_dir(&repo.path).join(CHECKS_CONFIG_FILENAME)
+}
+
+pub fn read_config(repo: &LocalRepository) -> Result<ChecksConfig, OxenError> {
+    let path = config_path(repo);
+    if !path.exists() {
+        return Ok(ChecksConfig::default());
+    }
+    let config_data = util::fs::read_from_path(&path)?;
+    Ok(toml::from_str(&config_data)?)
+}
+
+/// Check the tabular files in the working directory at or under the given paths
+pub fn run_on_paths<T: AsRef<Path>>(
+    repo: &LocalRepository,
+    paths: &[T],
+) -> Result<ChecksReport, OxenError> {
+    let config = read_config(repo)?;
+    let mut report = ChecksReport::default();
+    if config.rules.is_empty() {
+        return Ok(report);
+    }
+
+    for path in paths {
+        let path = path.as_ref();
+        let full_path = if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            repo.path.join(path)
+        };
+        let files = if full_path.is_dir() {
+            util::fs::rlist_files_in_dir(&full_path)
+        } else {
+            vec![full_path]
+        };
+        for file in files {
+            if !file.is_file() || !util::fs::is_tabular(&file) {
+                continue;
+            }
+            let relative_path = util::fs::path_relative_to_dir(&file, &repo.path)?;
+            check_file(&config, &relative_path, &file, &mut report);
+        }
+    }
+    Ok(report)
+}
+
+/// Check the added and modified tabular files staged in the repository
+pub fn run_on_staged(repo: &LocalRepository) -> Result<ChecksReport, OxenError> {
+    let config = read_config(repo)?;
+    if config.rules.is_empty() {
+        return Ok(ChecksReport::default());
+    }
+
+    let progress = indicatif::ProgressBar::hidden();
+    let (dir_entries, _) = status::read_staged_entries_with_staged_db_manager(repo, &progress)?;
+    check_staged_entries(repo, &config, &dir_entries)
+}
+
+/// Check the added and modified tabular files in a set of staged entries, such as the ones
+/// a workspace is about to commit. The files are read from `repo`'s version store.
+pub fn run_on_staged_entries(
+    repo: &LocalRepository,
+    dir_entries: &HashMap<PathBuf, Vec<StagedMerkleTreeNode>>,
+) -> Result<ChecksReport, OxenError> {
+    let config = read_config(repo)?;
+    if config.rules.is_empty() {
+        return Ok(ChecksReport::default());
+    }
+    check_staged_entries(repo, &config, dir_entries)
+}
+
+/// Check the tabular files in a commit, reading them from the version store so it works
+/// on a server without a working directory
+pub fn run_on_commit(repo: &LocalRepository, commit: &Commit) -> Result<ChecksReport, OxenError> {
+    let config = read_config(repo)?;
+    let mut report = ChecksReport::default();
+    if config.rules.is_empty() {
+        return Ok(report);
+    }
+
+    let root = repositories::tree::get_root_with_children(repo, commit)?.ok_or(
+        OxenError::basic_str(format!("Could not load the tree for commit {}", commit.id)),
+    )?;
+    let version_store = repo.version_store()?;
+    for file in repositories::tree::list_all_files(&root, &PathBuf::new())? {
+        let path = file.dir.join(file.file_node.name());
+        if !util::fs::is_tabular(&path) {
+            continue;
+        }
+        let version_path = version_store.get_version_path(&file.file_node.hash().to_string())?;
+        check_file(&config, &path, &version_path, &mut report);
+    }
+    Ok(report)
+}
+
+fn check_staged_entries(
+    repo: &LocalRepository,
+    config: &ChecksConfig,
+    dir_entries: &HashMap<PathBuf, Vec<StagedMerkleTreeNode>>,
+) -> Result<ChecksReport, OxenError> {
+    let version_store = repo.version_store()?;
+    let mut report = ChecksReport::default();
+    for (dir_path, entries) in dir_entries {
+        for entry in entries {
+            if entry.status == StagedEntryStatus::Removed {
+                continue;
+            }
+            let EMerkleTreeNode::File(file_node) = &entry.node.node else {
+                continue;
+            };
+            // Staged file nodes may be named by their full path or just the file name
+            let Some(file_name) = Path::new(file_node.name()).file_name() else {
+                continue;
+            };
+            let path = dir_path.join(file_name);
+            if !util::fs::is_tabular(&path) {
+                continue;
+            }
+            let version_path = version_store.get_version_path(&file_node.hash().to_string())?;
+            check_file(config, &path, &version_path, &mut report);
+        }
+    }
+    Ok(report)
+}
+
+/// Run every rule that matches `path` against the data frame stored at `data_path`
+fn check_file(config: &ChecksConfig, path: &Path, data_path: &Path, report: &mut ChecksReport) {
+    let rules = config.rules_for(path);
+    if rules.is_empty() {
+        return;
+    }
+    report.files_checked += 1;
+
+    let extension = util::fs::file_extension(path);
+    let df = match tabular::read_df_with_extension(data_path, &extension, &DFOpts::empty()) {
+        Ok(df) => df,
+        Err(err) => {
+            report.failures.push(CheckFailure {
+                kind: CheckFailureKind::Unreadable,
+                path: path.to_path_buf(),
+                rule: rules[0].path.clone(),
+                column: None,
+                message: format!("could not read data frame: {err}"),
+            });
+            return;
+        }
+    };
+
+    for rule in rules {
+        report.failures.extend(check_rule(rule, path, &df));
+    }
+}
+
+fn check_rule(rule: &CheckRule, path: &Path, df: &DataFrame) -> Vec<CheckFailure> {
+    let failure = |kind: CheckFailureKind, column: &str, message: String| CheckFailure {
+        kind,
+        path: path.to_path_buf(),
+        rule: rule.path.clone(),
+        column: Some(column.to_string()),
+        message,
+    };
+    let mut failures = vec![];
+
+    let referenced: BTreeSet<&String> = rule
+        .required_columns
+        .iter()
+        .chain(rule.dtypes.keys())
+        .chain(rule.max_null_fraction.keys())
+        .chain(rule.ranges.keys())
+        .chain(rule.unique.iter())
+        .collect();
+    let mut missing = false;
+    for column in referenced {
+        if df.column(column).is_err() {
+            missing = true;
+            failures.push(failure(
+                CheckFailureKind::MissingColumn,
+                column,
+                "column is missing".to_string(),
+            ));
+        }
+    }
+
+    let schema = Schema::from_polars(&df.schema());
+    for (column, dtype) in rule.dtypes.iter() {
+        let Some(field) = schema.get_field(column) else {
+            continue;
+        };
+        if &field.dtype != dtype {
+            failures.push(failure(
+                CheckFailureKind::Dtype,
+                column,
+                format!("expected dtype {} but found {}", dtype, field.dtype),
+            ));
+        }
+    }
+
+    for (column, max_fraction) in rule.max_null_fraction.iter() {
+        let Ok(values) = df.column(column) else {
+            continue;
+        };
+        if df.height() == 0 {
+            continue;
+        }
+        let fraction = values.null_count() as f64 / df.height() as f64;
+        if fraction > *max_fraction {
+            failures.push(failure(
+                CheckFailureKind::Nulls,
+                column,
+                format!(
+                    "{} of {} values are null, more than the allowed {}",
+                    values.null_count(),
+                    df.height(),
+                    max_fraction
+                ),
+            ));
+        }
+    }
+
+    for (column, range) in rule.ranges.iter() {
+        let Ok(values) = df.column(column) else {
+            continue;
+        };
+        let out_of_range = match values.cast(&DataType::Float64) {
+            Ok(values) => match values.f64() {
+                Ok(values) => values
+                    .into_iter()
+                    .flatten()
+                    .filter(|value| !range.contains(*value))
+                    .count(),
+                Err(_) => continue,
+            },
+            Err(_) => {
+                failures.push(failure(
+                    CheckFailureKind::Range,
+                    column,
+                    format!("values of dtype {} are not numeric", values.dtype()),
+                ));
+                continue;
+            }
+        };
+        if out_of_range > 0 {
+            failures.push(failure(
+                CheckFailureKind::Range,
+                column,
+                format!(
+                    "{} values are outside of [{}, {}]",
+                    out_of_range,
+                    range.min.map_or("-inf".to_string(), |min| min.to_string()),
+                    range.max.map_or("inf".to_string(), |max| max.to_string())
+                ),
+            ));
+        }
+    }
+
+    if !rule.unique.is_empty() && !missing {
+        let columns: Vec<&str> = rule.unique.iter().map(|c| c.as_str()).collect();
+        match tabular::n_duped_rows(df, &columns) {
+            Ok(0) => {}
+            Ok(n_dupes) => failures.push(failure(
+                CheckFailureKind::Unique,
+                &rule.unique.join(", "),
+                format!("{n_dupes} rows share their key with another row"),
+            )),
+            Err(err) => log::warn!("Could not check unique columns {:?}: {}", columns, err),
+        }
+    }
+
+    failures
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::{CheckRule, ChecksConfig, ValueRange, CHECKS_CONFIG_FILENAME};
+    use crate::error::OxenError;
+    use crate::repositories;
+    use crate::repositories::checks::CheckFailureKind;
+    use crate::test;
+    use crate::util;
+
+    #[tokio::test]
+    async fn test_checks_fail_commit_of_invalid_data() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            let mut rule = CheckRule::new("data/*.csv");
+            rule.required_columns = vec!["id".to_string(), "label".to_string()];
+            rule.dtypes.insert("score".to_string(), "f64".to_string());
+            rule.max_null_fraction.insert("label".to_string(), 0.0);
+            rule.ranges.insert(
+                "score".to_string(),
+                ValueRange {
+                    min: Some(0.0),
+                    max: Some(1.0),
+                },
+            );
+            rule.unique = vec!["id".to_string()];
+            let config = ChecksConfig { rules: vec![rule] };
+            util::fs::write_to_path(
+                util::fs::oxen_hidden_dir(&repo.path).join(CHECKS_CONFIG_FILENAME),
+                toml::to_string(&config)?,
+            )?;
+
+            let path = repo.path.join("data").join("scores.csv");
+            util::fs::create_dir_all(path.parent().unwrap())?;
+            test::write_txt_file_to_path(&path, "id,label,score\n1,cat,0.5\n1,,1.5\n")?;
+
+            let report = repositories::checks::run_on_paths(&repo, &[&path])?;
+            assert_eq!(report.files_checked, 1);
+            let kinds: Vec<CheckFailureKind> = report.failures.iter().map(|f| f.kind).collect();
+            assert!(kinds.contains(&CheckFailureKind::Nulls));
+            assert!(kinds.contains(&CheckFailureKind::Range));
+            assert!(kinds.contains(&CheckFailureKind::Unique));
+            assert!(!kinds.contains(&CheckFailureKind::Dtype));
+
+            // Committing the staged file runs the same checks
+            repositories::add(&repo, &path).await?;
+            let result = repositories::commit(&repo, "Adding scores");
+            assert!(matches!(result, Err(OxenError::ChecksFailed(_))));
+
+            // Files that no rule matches are not checked
+            let other = repo.path.join("other.csv");
+            test::write_txt_file_to_path(&other, "id\n1\n1\n")?;
+            let report = repositories::checks::run_on_paths(&repo, &[&other])?;
+            assert_eq!(report.files_checked, 0);
+
+            // Valid data commits
+            test::write_txt_file_to_path(&path, "id,label,score\n1,cat,0.5\n2,dog,1.0\n")?;
+            repositories::add(&repo, &path).await?;
+            repositories::commit(&repo, "Adding scores")?;
+
+            Ok(())
+        })
+        .await
+    }
+}
@@ -114,7 +114,9 @@ async fn clone_repo(
     remote_repo: RemoteRepository,
     opts: &CloneOpts,
 ) -> Result<LocalRepository, OxenError> {
-    println!("🐂 cloning repo {}", remote_repo.url());
+    if !opts.fetch_opts.json_progress {
+        println!("🐂 cloning repo {}", remote_repo.url());
+    }
     match remote_repo.min_version() {
         MinOxenVersion::V0_10_0 => panic!("v0.10.0 no longer supported"),
         _ => core::v_latest::clone::clone_repo(remote_repo, opts).await,
@@ -125,7 +127,9 @@ async fn clone_repo_remote_mode(
     remote_repo: RemoteRepository,
     opts: &CloneOpts,
 ) -> Result<LocalRepository, OxenError> {
-    println!("🐂 cloning repo {}", remote_repo.url());
+    if !opts.fetch_opts.json_progress {
+        println!("🐂 cloning repo {}", remote_repo.url());
+    }
     match remote_repo.min_version() {
         MinOxenVersion::V0_10_0 => panic!("v0.10.0 no longer supported"),
         _ => core::v_latest::clone::clone_repo_remote_mode(remote_repo, opts).await,
@@ -215,6 +219,27 @@ mod tests {
         .await
     }
 
+    #[tokio::test]
+    async fn test_clone_with_download_jobs() -> Result<(), OxenError> {
+        test::run_training_data_fully_sync_remote(|local_repo, remote_repo| async move {
+            let cloned_remote = remote_repo.clone();
+            test::run_empty_dir_test_async(|dir| async move {
+                let mut opts = CloneOpts::new(&remote_repo.remote.url, dir.join("new_repo"));
+                opts.fetch_opts.jobs = Some(2);
+                let cloned_repo = clone_remote(&opts).await?.unwrap();
+
+                let cloned_files = util::fs::rcount_files_in_dir(&cloned_repo.path);
+                let local_files = util::fs::rcount_files_in_dir(&local_repo.path);
+                assert_eq!(cloned_files, local_files);
+
+                Ok(())
+            })
+            .await?;
+            Ok(cloned_remote)
+        })
+        .await
+    }
+
     #[tokio::test]
     async fn test_clone_root_subtree_depth_1() -> Result<(), OxenError> {
         test::run_training_data_fully_sync_remote(|_local_repo, remote_repo| async move {
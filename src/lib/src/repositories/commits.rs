@@ -7,13 +7,14 @@ use crate::core::versions::MinOxenVersion;
 use crate::error::OxenError;
 use crate::model::User;
 use crate::model::{Commit, LocalRepository, MerkleHash};
-use crate::opts::PaginateOpts;
+use crate::opts::{CommitOpts, LogOpts, PaginateOpts};
 use crate::util;
 use crate::view::{PaginatedCommits, StatusMessage};
-use crate::{core, resource};
+use crate::{core, repositories, resource};
 
 use derive_more::FromStr;
-use std::collections::{HashMap, HashSet};
+use regex::{Regex, RegexBuilder};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
 pub mod commit_writer;
@@ -48,12 +49,43 @@ pub mod commit_writer;
 /// # }
 /// ```
 pub fn commit(repo: &LocalRepository, message: &str) -> Result<Commit, OxenError> {
+    repo.commit_message_config().validate(message)?;
+    repositories::checks::run_on_staged(repo)?.into_result()?;
     match repo.min_version() {
         MinOxenVersion::V0_10_0 => panic!("v0.10.0 no longer supported"),
         _ => core::v_latest::commits::commit(repo, message),
     }
 }
 
+/// Commit the staged files with key-values attached, see [`Commit::metadata`]
+pub fn commit_with_metadata(
+    repo: &LocalRepository,
+    message: &str,
+    metadata: BTreeMap<String, String>,
+) -> Result<Commit, OxenError> {
+    let opts = CommitOpts {
+        metadata,
+        ..CommitOpts::default()
+    };
+    commit_with_opts(repo, message, &opts)
+}
+
+/// Commit the staged files with extras such as metadata and co-authors
+pub fn commit_with_opts(
+    repo: &LocalRepository,
+    message: &str,
+    opts: &CommitOpts,
+) -> Result<Commit, OxenError> {
+    repo.commit_message_config().validate(message)?;
+    if !opts.no_verify {
+        repositories::checks::run_on_staged(repo)?.into_result()?;
+    }
+    match repo.min_version() {
+        MinOxenVersion::V0_10_0 => panic!("v0.10.0 no longer supported"),
+        _ => core::v_latest::commits::commit_with_opts(repo, message, opts),
+    }
+}
+
 pub fn commit_with_user(
     repo: &LocalRepository,
     message: &str,
@@ -139,6 +171,19 @@ pub fn create_empty_commit(
     }
 }
 
+/// Collapse the last `n` commits on the current branch into one commit with the same tree.
+/// Without a message, the messages of the squashed commits are joined oldest first.
+pub fn squash(
+    repo: &LocalRepository,
+    n: usize,
+    message: Option<&str>,
+) -> Result<Commit, OxenError> {
+    match repo.min_version() {
+        MinOxenVersion::V0_10_0 => panic!("squash not supported in v0.10.0"),
+        _ => core::v_latest::commits::squash(repo, n, message),
+    }
+}
+
 /// List commits on the current branch from HEAD
 pub fn list(repo: &LocalRepository) -> Result<Vec<Commit>, OxenError> {
     match repo.min_version() {
@@ -219,6 +264,30 @@ pub fn list_from_with_depth(
     }
 }
 
+/// List the last `history_depth` commits of the history of a revision, as fetched by a shallow clone
+pub fn list_from_with_history_depth(
+    repo: &LocalRepository,
+    revision: &str,
+    history_depth: usize,
+) -> Result<Vec<Commit>, OxenError> {
+    match repo.min_version() {
+        MinOxenVersion::V0_10_0 => Err(OxenError::basic_str(
+            "list_from_with_history_depth not supported in v0.10.0",
+        )),
+        _ => core::v_latest::commits::list_from_with_history_depth(repo, revision, history_depth),
+    }
+}
+
+/// Recompute the shallow boundary after fetching history, clearing it once every parent is local
+pub fn update_shallow_boundary(repo: &LocalRepository) -> Result<HashSet<String>, OxenError> {
+    let boundary = match repo.min_version() {
+        MinOxenVersion::V0_10_0 => panic!("v0.10.0 no longer supported"),
+        _ => core::v_latest::commits::list_with_missing_parents(repo)?,
+    };
+    repo.write_shallow_boundary(&boundary)?;
+    Ok(boundary)
+}
+
 /// List the history between two commits
 pub fn list_between(
     repo: &LocalRepository,
@@ -244,6 +313,124 @@ pub fn get_by_message(
     Ok(filtered)
 }
 
+/// List the history for a revision, keeping the commits that have all of the metadata key-values
+pub fn list_from_with_metadata(
+    repo: &LocalRepository,
+    revision: &str,
+    metadata: &BTreeMap<String, String>,
+) -> Result<Vec<Commit>, OxenError> {
+    let commits = list_from(repo, revision)?;
+    let filtered: Vec<Commit> = commits
+        .into_iter()
+        .filter(|commit| {
+            metadata
+                .iter()
+                .all(|(key, value)| commit.metadata.get(key) == Some(value))
+        })
+        .collect();
+    Ok(filtered)
+}
+
+/// Search the history for a revision, keeping the commits whose message or metadata
+/// match the pattern. The pattern is a case insensitive regex, matched against the
+/// message and against each metadata entry as `key=value`.
+pub fn search_from(
+    repo: &LocalRepository,
+    revision: &str,
+    pattern: &str,
+) -> Result<Vec<Commit>, OxenError> {
+    let regex = search_regex(pattern)?;
+    let commits = list_from(repo, revision)?;
+    let filtered: Vec<Commit> = commits
+        .into_iter()
+        .filter(|commit| matches_search(&regex, commit))
+        .collect();
+    Ok(filtered)
+}
+
+fn search_regex(pattern: &str) -> Result<Regex, OxenError> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .build()
+        .map_err(|err| OxenError::basic_str(format!("Invalid search pattern {pattern:?}: {err}")))
+}
+
+fn matches_search(regex: &Regex, commit: &Commit) -> bool {
+    regex.is_match(&commit.message)
+        || commit
+            .metadata
+            .iter()
+            .any(|(key, value)| regex.is_match(&format!("{key}={value}")))
+}
+
+/// List the history of a revision, newest first, keeping the commits that match every
+/// filter set in the opts
+pub fn list_with_opts(repo: &LocalRepository, opts: &LogOpts) -> Result<Vec<Commit>, OxenError> {
+    let revision = match &opts.revision {
+        Some(revision) => revision.clone(),
+        None => head_commit(repo)?.id,
+    };
+    let regex = opts.grep.as_deref().map(search_regex).transpose()?;
+    let author = opts.author.as_ref().map(|author| author.to_lowercase());
+
+    // The commits that changed the path, from walking back through its last commit ids
+    let path_commit_ids: Option<HashSet<String>> = match &opts.path {
+        Some(path) => {
+            let commit = repositories::revisions::get(repo, &revision)?
+                .ok_or(OxenError::revision_not_found(revision.clone().into()))?;
+            let mut commits = vec![];
+            core::v_latest::commits::list_by_path_recursive(repo, path, &commit, &mut commits)?;
+            Some(commits.into_iter().map(|commit| commit.id).collect())
+        }
+        None => None,
+    };
+
+    let commits = list_from(repo, &revision)?
+        .into_iter()
+        .filter(|commit| {
+            path_commit_ids
+                .as_ref()
+                .is_none_or(|ids| ids.contains(&commit.id))
+        })
+        .filter(|commit| {
+            author.as_ref().is_none_or(|author| {
+                commit.author.to_lowercase().contains(author)
+                    || commit.email.to_lowercase().contains(author)
+            })
+        })
+        .filter(|commit| opts.since.is_none_or(|since| commit.timestamp >= since))
+        .filter(|commit| opts.until.is_none_or(|until| commit.timestamp <= until))
+        .filter(|commit| {
+            regex
+                .as_ref()
+                .is_none_or(|regex| matches_search(regex, commit))
+        })
+        .filter(|commit| {
+            opts.metadata
+                .iter()
+                .all(|(key, value)| commit.metadata.get(key) == Some(value))
+        })
+        .take(opts.limit.unwrap_or(usize::MAX))
+        .collect();
+    Ok(commits)
+}
+
+/// Search the history for a revision by message and metadata, paginated
+pub fn search_from_paginated(
+    repo: &LocalRepository,
+    revision: &str,
+    pattern: &str,
+    pagination: PaginateOpts,
+) -> Result<PaginatedCommits, OxenError> {
+    let commits = search_from(repo, revision, pattern)?;
+    let (commits, pagination) = util::paginate(commits, pagination.page_num, pagination.page_size);
+    Ok(PaginatedCommits {
+        status: StatusMessage::resource_found(),
+        commits,
+        pagination,
+    })
+}
+
 /// Get the most recent commit by the commit message, starting at the HEAD commit
 pub fn first_by_message(
     repo: &LocalRepository,
@@ -301,6 +488,20 @@ pub fn list_by_path_from_paginated(
     }
 }
 
+/// List the commits that changed the file at `path` in the history of `revision`,
+/// following the file back through the commits that renamed it. Each commit comes with
+/// the path the file had in it.
+pub fn list_by_path_follow(
+    repo: &LocalRepository,
+    revision: &str,
+    path: impl AsRef<Path>,
+) -> Result<Vec<(Commit, PathBuf)>, OxenError> {
+    match repo.min_version() {
+        MinOxenVersion::V0_10_0 => panic!("v0.10.0 no longer supported"),
+        _ => core::v_latest::commits::list_by_path_follow(repo, revision, path.as_ref()),
+    }
+}
+
 pub fn commit_history_is_complete(
     repo: &LocalRepository,
     commit: &Commit,
@@ -342,11 +543,13 @@ mod tests {
     use std::path::Path;
     use std::str::FromStr;
 
+    use crate::config::{CommitMessageConfig, CommitMessageRule};
     use crate::error::OxenError;
     use crate::model::EntryDataType;
     use crate::model::MerkleHash;
     use crate::model::StagedEntryStatus;
     use crate::opts::CloneOpts;
+    use crate::opts::FetchOpts;
     use crate::opts::RmOpts;
     use crate::repositories;
     use crate::test;
@@ -625,6 +828,138 @@ mod tests {
         .await
     }
 
+    #[tokio::test]
+    async fn test_commit_message_must_follow_rules() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|mut repo| async move {
+            repo.set_commit_message_config(Some(CommitMessageConfig {
+                template: Some("TICKET-: \n# Describe the change".to_string()),
+                rules: vec![CommitMessageRule {
+                    pattern: "^[A-Z]+-[0-9]+: ".to_string(),
+                    description: Some("a ticket id".to_string()),
+                }],
+            }));
+            repo.save()?;
+
+            let text_path = repo.path.join("text.txt");
+            util::fs::write_to_path(&text_path, "Hello World")?;
+            repositories::add(&repo, &text_path).await?;
+
+            let result = repositories::commit(&repo, "Committing hello world");
+            match result {
+                Err(OxenError::InvalidCommitMessage(err)) => {
+                    assert!(err.to_string().contains("a ticket id"));
+                }
+                _ => panic!("expected an invalid commit message error"),
+            }
+
+            // The untouched template is rejected too
+            let result = repositories::commit(&repo, "TICKET-: ");
+            assert!(result.is_err());
+
+            // Rules are read from the saved repo config
+            let repo = LocalRepository::from_dir(&repo.path)?;
+            repositories::commit(&repo, "OXEN-123: Committing hello world")?;
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_commit_with_metadata() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            let text_path = repo.path.join("text.txt");
+            util::fs::write_to_path(&text_path, "Hello World")?;
+            repositories::add(&repo, &text_path).await?;
+            let metadata = BTreeMap::from([
+                ("run_id".to_string(), "abc".to_string()),
+                ("model".to_string(), "resnet50".to_string()),
+            ]);
+            let first =
+                repositories::commits::commit_with_metadata(&repo, "Training run", metadata)?;
+
+            util::fs::write_to_path(&text_path, "Goodbye World")?;
+            repositories::add(&repo, &text_path).await?;
+            repositories::commit(&repo, "No metadata")?;
+
+            // Metadata is read back from the commit node
+            let commit = repositories::commits::get_by_id(&repo, &first.id)?.unwrap();
+            assert_eq!(commit.metadata.get("run_id"), Some(&"abc".to_string()));
+            assert_eq!(commit.metadata.get("model"), Some(&"resnet50".to_string()));
+
+            let head = repositories::commits::head_commit(&repo)?;
+            assert!(head.metadata.is_empty());
+
+            let filter = BTreeMap::from([("run_id".to_string(), "abc".to_string())]);
+            let commits = repositories::commits::list_from_with_metadata(&repo, &head.id, &filter)?;
+            assert_eq!(commits.len(), 1);
+            assert_eq!(commits[0].id, first.id);
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_search_commits_by_message_and_metadata() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            let text_path = repo.path.join("text.txt");
+            util::fs::write_to_path(&text_path, "Hello World")?;
+            repositories::add(&repo, &text_path).await?;
+            let removed = repositories::commit(&repo, "Removed the corrupted batch")?;
+
+            util::fs::write_to_path(&text_path, "Goodbye World")?;
+            repositories::add(&repo, &text_path).await?;
+            let metadata = BTreeMap::from([("run_id".to_string(), "abc".to_string())]);
+            let run = repositories::commits::commit_with_metadata(&repo, "Training run", metadata)?;
+
+            let found = repositories::commits::search_from(&repo, &run.id, "corrupted")?;
+            assert_eq!(found.len(), 1);
+            assert_eq!(found[0].id, removed.id);
+
+            // Case insensitive, and metadata is matched as key=value
+            let found = repositories::commits::search_from(&repo, &run.id, "REMOVED.*batch")?;
+            assert_eq!(found.len(), 1);
+            let found = repositories::commits::search_from(&repo, &run.id, "run_id=abc")?;
+            assert_eq!(found.len(), 1);
+            assert_eq!(found[0].id, run.id);
+
+            let found = repositories::commits::search_from(&repo, &run.id, "no such commit")?;
+            assert!(found.is_empty());
+            assert!(repositories::commits::search_from(&repo, &run.id, "(").is_err());
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_commit_with_co_authors() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            let text_path = repo.path.join("text.txt");
+            util::fs::write_to_path(&text_path, "Hello World")?;
+            repositories::add(&repo, &text_path).await?;
+
+            let co_author = User::from_str("Ada Lovelace <ada@oxen.ai>")?;
+            assert_eq!(co_author.name, "Ada Lovelace");
+            assert_eq!(co_author.email, "ada@oxen.ai");
+            assert!(User::from_str("ada@oxen.ai").is_err());
+
+            let opts = CommitOpts {
+                co_authors: vec![co_author.clone()],
+                ..CommitOpts::default()
+            };
+            let commit = repositories::commits::commit_with_opts(&repo, "Pairing", &opts)?;
+
+            let commit = repositories::commits::get_by_id(&repo, &commit.id)?.unwrap();
+            assert_eq!(commit.co_authors, vec![co_author]);
+            assert!(commit.metadata.is_empty());
+
+            Ok(())
+        })
+        .await
+    }
+
     #[tokio::test]
     async fn test_commit_hash_on_modified_file() -> Result<(), OxenError> {
         test::run_empty_local_repo_test_async(|repo| async move {
@@ -1114,4 +1449,157 @@ A: Oxen.ai
         })
         .await
     }
+
+    #[tokio::test]
+    async fn test_squash_last_commits() -> Result<(), OxenError> {
+        test::run_one_commit_local_repo_test_async(|repo| async move {
+            let base = repositories::commits::head_commit(&repo)?;
+            for i in 0..3 {
+                let file = repo.path.join(format!("batch_{i}.txt"));
+                util::fs::write_to_path(&file, format!("batch {i}"))?;
+                repositories::add(&repo, &file).await?;
+                repositories::commit(&repo, &format!("Ingest batch {i}"))?;
+            }
+            let head = repositories::commits::head_commit(&repo)?;
+            let head_root = repositories::tree::get_root_with_children(&repo, &head)?.unwrap();
+
+            let squashed = repositories::commits::squash(&repo, 3, None)?;
+            assert_eq!(squashed.parent_ids, vec![base.id.clone()]);
+            assert_eq!(
+                squashed.message,
+                "Ingest batch 0\n\nIngest batch 1\n\nIngest batch 2"
+            );
+
+            // History is shorter but the tree is the same
+            let commits = repositories::commits::list(&repo)?;
+            assert_eq!(commits.len(), 2);
+            assert_eq!(repositories::commits::head_commit(&repo)?.id, squashed.id);
+            let root = repositories::tree::get_root_with_children(&repo, &squashed)?.unwrap();
+            assert_eq!(
+                root.children.first().unwrap().hash,
+                head_root.children.first().unwrap().hash
+            );
+            assert!(repositories::status(&repo)?.is_clean());
+
+            // Can't squash more commits than the branch has
+            assert!(repositories::commits::squash(&repo, 3, None).is_err());
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_clone_with_history_depth_then_unshallow() -> Result<(), OxenError> {
+        test::run_training_data_fully_sync_remote(|local_repo, remote_repo| async move {
+            let cloned_remote = remote_repo.clone();
+            let head_commit = repositories::commits::head_commit(&local_repo)?;
+            let full_history = repositories::commits::list_from(&local_repo, &head_commit.id)?;
+            assert!(full_history.len() > 2);
+
+            test::run_empty_dir_test_async(|dir| async move {
+                let mut opts = CloneOpts::new(&remote_repo.remote.url, dir.join("new_repo"));
+                opts.fetch_opts.history_depth = Some(2);
+                let shallow_repo = repositories::clone::clone(&opts).await?;
+
+                // Only the last two commits came down, the older one is on the boundary
+                let history = repositories::commits::list_from(&shallow_repo, &head_commit.id)?;
+                assert_eq!(history.len(), 2);
+                assert_eq!(history[0].id, full_history[0].id);
+                assert_eq!(
+                    shallow_repo.shallow_boundary()?,
+                    HashSet::from([full_history[1].id.clone()])
+                );
+
+                let fetch_opts = FetchOpts {
+                    unshallow: true,
+                    ..FetchOpts::new()
+                };
+                repositories::fetch::fetch_branch(&shallow_repo, &fetch_opts).await?;
+                let history = repositories::commits::list_from(&shallow_repo, &head_commit.id)?;
+                assert_eq!(history.len(), full_history.len());
+                assert!(!shallow_repo.has_shallow_history()?);
+
+                Ok(())
+            })
+            .await?;
+            Ok(cloned_remote)
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_list_with_opts_filters_history() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            let ada = User {
+                name: "Ada Lovelace".to_string(),
+                email: "ada@example.com".to_string(),
+            };
+            let alan = User {
+                name: "Alan Turing".to_string(),
+                email: "alan@example.com".to_string(),
+            };
+
+            util::fs::write_to_path(repo.path.join("README.md"), "readme")?;
+            repositories::add(&repo, repo.path.join("README.md")).await?;
+            let first = repositories::commits::commit_with_user(&repo, "Add readme", &ada)?;
+
+            util::fs::create_dir_all(repo.path.join("data"))?;
+            util::fs::write_to_path(repo.path.join("data").join("train.csv"), "a\n1\n")?;
+            repositories::add(&repo, repo.path.join("data")).await?;
+            let second =
+                repositories::commits::commit_with_user(&repo, "Add training data", &alan)?;
+
+            util::fs::write_to_path(repo.path.join("README.md"), "readme v2")?;
+            repositories::add(&repo, repo.path.join("README.md")).await?;
+            let third = repositories::commits::commit_with_user(&repo, "Update readme", &ada)?;
+
+            let ids = |opts: &LogOpts| -> Result<Vec<String>, OxenError> {
+                Ok(list_with_opts(&repo, opts)?
+                    .into_iter()
+                    .map(|commit| commit.id)
+                    .collect())
+            };
+
+            let by_path = LogOpts {
+                path: Some(PathBuf::from("README.md")),
+                ..LogOpts::default()
+            };
+            assert_eq!(ids(&by_path)?, vec![third.id.clone(), first.id.clone()]);
+
+            let by_author = LogOpts {
+                author: Some("ALAN".to_string()),
+                ..LogOpts::default()
+            };
+            assert_eq!(ids(&by_author)?, vec![second.id.clone()]);
+
+            let by_grep = LogOpts {
+                grep: Some("readme".to_string()),
+                limit: Some(1),
+                ..LogOpts::default()
+            };
+            assert_eq!(ids(&by_grep)?, vec![third.id.clone()]);
+
+            // Timestamps as stored in the history
+            let history = list_from(&repo, &third.id)?;
+            assert_eq!(history[1].id, second.id);
+            let by_date = LogOpts {
+                since: Some(history[1].timestamp),
+                until: Some(history[1].timestamp),
+                ..LogOpts::default()
+            };
+            assert_eq!(ids(&by_date)?, vec![second.id.clone()]);
+
+            let future = LogOpts {
+                since: Some(LogOpts::parse_date("2999-01-01")?),
+                ..LogOpts::default()
+            };
+            assert!(ids(&future)?.is_empty());
+            assert!(LogOpts::parse_date("3 days ago")? < third.timestamp);
+            assert!(LogOpts::parse_date("yesterday-ish").is_err());
+
+            Ok(())
+        })
+        .await
+    }
 }
@@ -34,6 +34,7 @@ use crate::model::NewCommit;
 use crate::model::NewCommitBody;
 use crate::model::User;
 use crate::model::{Commit, LocalRepository, StagedEntryStatus};
+use crate::opts::CommitOpts;
 
 use crate::util::hasher;
 use crate::{repositories, util};
@@ -74,7 +75,17 @@ impl EntryVNode {
 
 pub fn commit(repo: &LocalRepository, message: impl AsRef<str>) -> Result<Commit, OxenError> {
     let cfg = UserConfig::get()?;
-    commit_with_cfg(repo, message, &cfg, None)
+    commit_with_cfg(repo, message, &cfg, None, &CommitOpts::default())
+}
+
+/// Commit the staged files with extras such as metadata and co-authors
+pub fn commit_with_opts(
+    repo: &LocalRepository,
+    message: impl AsRef<str>,
+    commit_opts: &CommitOpts,
+) -> Result<Commit, OxenError> {
+    let cfg = UserConfig::get()?;
+    commit_with_cfg(repo, message, &cfg, None, commit_opts)
 }
 
 pub fn commit_with_parent_ids(
@@ -83,7 +94,13 @@ pub fn commit_with_parent_ids(
     parent_ids: Vec<String>,
 ) -> Result<Commit, OxenError> {
     let cfg = UserConfig::get()?;
-    commit_with_cfg(repo, message, &cfg, Some(parent_ids))
+    commit_with_cfg(
+        repo,
+        message,
+        &cfg,
+        Some(parent_ids),
+        &CommitOpts::default(),
+    )
 }
 
 pub fn commit_with_user(
@@ -95,7 +112,7 @@ pub fn commit_with_user(
         name: user.name.clone(),
         email: user.email.clone(),
     };
-    commit_with_cfg(repo, message, &cfg, None)
+    commit_with_cfg(repo, message, &cfg, None, &CommitOpts::default())
 }
 
 pub fn commit_with_cfg(
@@ -103,6 +120,7 @@ pub fn commit_with_cfg(
     message: impl AsRef<str>,
     cfg: &UserConfig,
     parent_ids: Option<Vec<String>>,
+    commit_opts: &CommitOpts,
 ) -> Result<Commit, OxenError> {
     // time the commit
     let start_time = Instant::now();
@@ -129,6 +147,7 @@ pub fn commit_with_cfg(
     if dir_entries.is_empty() {
         return Err(OxenError::basic_str("No changes to commit"));
     }
+    repositories::case_conflicts::check_staged_entries(repo, &dir_entries)?;
 
     // let mut dir_tree = entries_to_dir_tree(&dir_entries)?;
     // dir_tree.print();
@@ -140,8 +159,10 @@ pub fn commit_with_cfg(
         author: cfg.name.clone(),
         email: cfg.email.clone(),
     };
+    // An orphan branch is created by its first commit
+    let unborn_branch = repositories::branches::unborn_branch(repo)?;
     let branch = repositories::branches::current_branch(repo)?;
-    let maybe_branch_name = branch.map(|b| b.name);
+    let maybe_branch_name = branch.map(|b| b.name).or(unborn_branch.clone());
     let commit = if let Some(parent_ids) = parent_ids {
         log::debug!("parent ids: {:?}", parent_ids);
         commit_dir_entries_with_parents(
@@ -149,6 +170,7 @@ pub fn commit_with_cfg(
             parent_ids,
             dir_entries,
             &new_commit,
+            commit_opts,
             staged_db,
             &commit_progress_bar,
             maybe_branch_name
@@ -161,6 +183,7 @@ pub fn commit_with_cfg(
             repo,
             dir_entries,
             &new_commit,
+            commit_opts,
             staged_db,
             &commit_progress_bar,
         )?
@@ -175,8 +198,8 @@ pub fn commit_with_cfg(
     let head_path_exists = head_path.exists();
 
     with_ref_manager(repo, |manager| {
-        if !head_path_exists {
-            log::debug!("HEAD file does not exist, creating new branch");
+        if !head_path_exists || unborn_branch.is_some() {
+            log::debug!("HEAD has no commits yet, creating new branch {branch_name}");
             manager.set_head(&branch_name);
             manager.set_branch_commit_id(&branch_name, &commit_id)?;
         }
@@ -195,11 +218,121 @@ pub fn commit_with_cfg(
     Ok(commit)
 }
 
+/// Collapse the last `n` commits on the current branch into a single commit.
+/// The new commit points at the same tree as HEAD and takes the parents of the
+/// oldest squashed commit, so no files are rewritten.
+pub fn squash(
+    repo: &LocalRepository,
+    n: usize,
+    message: Option<&str>,
+) -> Result<Commit, OxenError> {
+    if n < 2 {
+        return Err(OxenError::basic_str("Must squash at least 2 commits"));
+    }
+    let Some(branch) = repositories::branches::current_branch(repo)? else {
+        return Err(OxenError::must_be_on_valid_branch());
+    };
+
+    // Walk back from HEAD, refusing to squash away the second parent of a merge
+    let head_commit = repositories::commits::head_commit(repo)?;
+    let mut squashed = vec![head_commit.clone()];
+    while squashed.len() < n {
+        let commit = squashed.last().unwrap();
+        let parent_id = match commit.parent_ids.as_slice() {
+            [] => {
+                return Err(OxenError::basic_str(format!(
+                    "Cannot squash {n} commits, branch {} only has {}",
+                    branch.name,
+                    squashed.len()
+                )));
+            }
+            [parent_id] => parent_id.clone(),
+            _ => {
+                return Err(OxenError::basic_str(format!(
+                    "Cannot squash across merge commit {}",
+                    commit.id
+                )));
+            }
+        };
+        let parent = repositories::commits::get_by_id(repo, &parent_id)?
+            .ok_or(OxenError::commit_id_does_not_exist(&parent_id))?;
+        squashed.push(parent);
+    }
+    let oldest = squashed.last().unwrap();
+
+    let message = match message {
+        Some(message) => message.to_string(),
+        None => squashed
+            .iter()
+            .rev()
+            .map(|commit| commit.message.as_str())
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+    };
+    let cfg = UserConfig::get()?;
+    let timestamp = OffsetDateTime::now_utc();
+    let new_commit = NewCommit {
+        parent_ids: oldest.parent_ids.clone(),
+        message: message.clone(),
+        author: cfg.name,
+        email: cfg.email,
+        timestamp,
+        metadata: Default::default(),
+        co_authors: vec![],
+    };
+    let commit_id = compute_commit_id(&new_commit)?;
+
+    let head_hash = head_commit.hash()?;
+    let head_node = repositories::tree::get_node_by_id_with_children(repo, &head_hash)?.ok_or(
+        OxenError::basic_str(format!(
+            "Merkle tree node not found for commit: '{}'",
+            head_commit.id
+        )),
+    )?;
+    let parent_hashes = new_commit
+        .parent_ids
+        .iter()
+        .map(|id| MerkleHash::from_str(id))
+        .collect::<Result<Vec<_>, _>>()?;
+    let node = CommitNode::new(
+        repo,
+        CommitNodeOpts {
+            hash: commit_id,
+            parent_ids: parent_hashes.clone(),
+            email: new_commit.email.clone(),
+            author: new_commit.author.clone(),
+            message,
+            timestamp,
+            metadata: new_commit.metadata.clone(),
+            co_authors: new_commit.co_authors.clone(),
+        },
+    )?;
+
+    // Reuse the root directory of HEAD, the tree is unchanged
+    let mut commit_db = MerkleNodeDB::open_read_write(repo, &node, parent_hashes.first().copied())?;
+    let dir_node = head_node.children.first().unwrap().dir()?;
+    commit_db.add_child(&dir_node)?;
+    repositories::tree::cp_dir_hashes_to(repo, &head_hash, node.hash())?;
+
+    with_ref_manager(repo, |manager| {
+        manager.set_branch_commit_id(&branch.name, node.hash().to_string())
+    })?;
+
+    println!(
+        "🐂 squashed {} commits on {} into {}",
+        squashed.len(),
+        branch.name,
+        node.hash()
+    );
+    Ok(node.to_commit())
+}
+
 pub fn commit_dir_entries_with_parents(
     repo: &LocalRepository,
     parent_commits: Vec<String>,
     dir_entries: HashMap<PathBuf, Vec<StagedMerkleTreeNode>>,
     new_commit: &NewCommitBody,
+    commit_opts: &CommitOpts,
     staged_db: DBWithThreadMode<SingleThreaded>,
     commit_progress_bar: &ProgressBar,
     target_branch: impl AsRef<str>,
@@ -240,9 +373,18 @@ pub fn commit_dir_entries_with_parents(
     // Sort children and split into VNodes
     let vnode_entries = split_into_vnodes(repo, &dir_entries, &existing_nodes, new_commit)?;
 
-    let timestamp = OffsetDateTime::now_utc();
+    let timestamp = commit_opts
+        .timestamp
+        .unwrap_or_else(OffsetDateTime::now_utc);
 
-    let new_commit = create_commit_data(repo, message, timestamp, parent_commits, new_commit)?;
+    let new_commit = create_commit_data(
+        repo,
+        message,
+        timestamp,
+        parent_commits,
+        new_commit,
+        commit_opts,
+    )?;
 
     // Compute the commit hash
     let commit_id = compute_commit_id(&new_commit)?;
@@ -264,6 +406,8 @@ pub fn commit_dir_entries_with_parents(
             author: new_commit.author.clone(),
             message: message.to_string(),
             timestamp,
+            metadata: new_commit.metadata.clone(),
+            co_authors: new_commit.co_authors.clone(),
         },
     )?;
 
@@ -311,6 +455,7 @@ pub fn commit_dir_entries_new(
     repo: &LocalRepository,
     dir_entries: HashMap<PathBuf, Vec<StagedMerkleTreeNode>>,
     new_commit: &NewCommitBody,
+    commit_opts: &CommitOpts,
     staged_db: DBWithThreadMode<SingleThreaded>,
     commit_progress_bar: &ProgressBar,
 ) -> Result<Commit, OxenError> {
@@ -339,13 +484,16 @@ pub fn commit_dir_entries_new(
     let vnode_entries = split_into_vnodes(repo, &dir_entries, &existing_nodes, new_commit)?;
 
     // Compute the commit hash
-    let timestamp = OffsetDateTime::now_utc();
+    let timestamp = commit_opts
+        .timestamp
+        .unwrap_or_else(OffsetDateTime::now_utc);
     let new_commit = create_commit_data(
         repo,
         message,
         timestamp,
         parent_ids.iter().map(|id| id.to_string()).collect(),
         new_commit,
+        commit_opts,
     )?;
 
     let commit_id = compute_commit_id(&new_commit)?;
@@ -363,6 +511,8 @@ pub fn commit_dir_entries_new(
             author: new_commit.author.clone(),
             message: message.to_string(),
             timestamp,
+            metadata: new_commit.metadata.clone(),
+            co_authors: new_commit.co_authors.clone(),
         },
     )?;
 
@@ -471,6 +621,8 @@ pub fn commit_dir_entries(
         author: new_commit.author.clone(),
         email: new_commit.email.clone(),
         timestamp,
+        metadata: Default::default(),
+        co_authors: vec![],
     };
     let commit_id = compute_commit_id(&new_commit)?;
 
@@ -483,6 +635,8 @@ pub fn commit_dir_entries(
             author: new_commit.author.clone(),
             message: message.to_string(),
             timestamp,
+            metadata: new_commit.metadata.clone(),
+            co_authors: new_commit.co_authors.clone(),
         },
     )?;
 
@@ -787,6 +941,14 @@ fn compute_commit_id(new_commit: &NewCommit) -> Result<MerkleHash, OxenError> {
     hasher.update(new_commit.author.as_bytes());
     hasher.update(new_commit.email.as_bytes());
     hasher.update(&new_commit.timestamp.unix_timestamp().to_le_bytes());
+    // Only hash the extras when present so ids of commits without them are unchanged
+    if !new_commit.metadata.is_empty() {
+        hasher.update(format!("{:?}", new_commit.metadata).as_bytes());
+    }
+    for co_author in new_commit.co_authors.iter() {
+        hasher.update(co_author.name.as_bytes());
+        hasher.update(co_author.email.as_bytes());
+    }
     Ok(MerkleHash::new(hasher.digest128()))
 }
 
@@ -1185,6 +1347,7 @@ fn create_merge_commit(
     message: &str,
     timestamp: OffsetDateTime,
     new_commit: &NewCommitBody,
+    commit_opts: &CommitOpts,
 ) -> Result<NewCommit, OxenError> {
     let hidden_dir = util::fs::oxen_hidden_dir(&repo.path);
     let merge_head_path = hidden_dir.join(MERGE_HEAD_FILE);
@@ -1204,6 +1367,8 @@ fn create_merge_commit(
         author: new_commit.author.clone(),
         email: new_commit.email.clone(),
         timestamp,
+        metadata: commit_opts.metadata.clone(),
+        co_authors: commit_opts.co_authors.clone(),
     })
 }
 
@@ -1219,9 +1384,10 @@ fn create_commit_data(
     timestamp: OffsetDateTime,
     parent_commits: Vec<String>,
     new_commit: &NewCommitBody,
+    commit_opts: &CommitOpts,
 ) -> Result<NewCommit, OxenError> {
     if is_merge_commit(repo) {
-        create_merge_commit(repo, message, timestamp, new_commit)
+        create_merge_commit(repo, message, timestamp, new_commit, commit_opts)
     } else {
         Ok(NewCommit {
             parent_ids: parent_commits,
@@ -1229,6 +1395,8 @@ fn create_commit_data(
             author: new_commit.author.clone(),
             email: new_commit.email.clone(),
             timestamp,
+            metadata: commit_opts.metadata.clone(),
+            co_authors: commit_opts.co_authors.clone(),
         })
     }
 }
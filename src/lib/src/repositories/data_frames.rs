@@ -5,6 +5,7 @@ use crate::model::data_frame::DataFrameSlice;
 use crate::model::{Commit, LocalRepository};
 use crate::opts::DFOpts;
 
+use polars::frame::DataFrame;
 use std::path::Path;
 
 pub mod schemas;
@@ -20,3 +21,91 @@ pub fn get_slice(
         _ => core::v_latest::data_frames::get_slice(repo, commit, path, opts),
     }
 }
+
+/// Run a SQL query against the data frame at `path` as of `commit`. The data frame is the
+/// table `df`, ie `SELECT label, count(*) FROM df GROUP BY label`.
+pub fn query_sql(
+    repo: &LocalRepository,
+    commit: &Commit,
+    path: impl AsRef<Path>,
+    sql: impl AsRef<str>,
+) -> Result<DataFrame, OxenError> {
+    match repo.min_version() {
+        MinOxenVersion::V0_10_0 => panic!("v0.10.0 no longer supported"),
+        _ => core::v_latest::data_frames::query_sql(repo, commit, path, sql),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use polars::prelude::*;
+
+    use crate::error::OxenError;
+    use crate::opts::DFOpts;
+    use crate::repositories;
+    use crate::test;
+
+    #[tokio::test]
+    async fn test_query_sql_at_revision() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            let path = repo.path.join("labels.csv");
+            test::write_txt_file_to_path(&path, "file,label\na.jpg,cat\nb.jpg,dog\nc.jpg,cat\n")?;
+            repositories::add(&repo, &path).await?;
+            let first = repositories::commit(&repo, "Adding labels")?;
+
+            test::write_txt_file_to_path(&path, "file,label\na.jpg,cat\n")?;
+            repositories::add(&repo, &path).await?;
+            repositories::commit(&repo, "Removing labels")?;
+
+            let sql = "SELECT label, count(*) AS n FROM df GROUP BY label ORDER BY label";
+            let df = repositories::data_frames::query_sql(&repo, &first, "labels.csv", sql)?;
+            assert_eq!(df.height(), 2);
+            assert_eq!(df.column("label")?.str()?.get(0), Some("cat"));
+            assert_eq!(
+                df.column("n")?.cast(&DataType::Int64)?.i64()?.get(0),
+                Some(2)
+            );
+
+            let head = repositories::commits::head_commit(&repo)?;
+            let df = repositories::data_frames::query_sql(&repo, &head, "labels.csv", sql)?;
+            assert_eq!(df.height(), 1);
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_get_slice_filters_sorts_and_projects_lazily() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            let path = repo.path.join("labels.csv");
+            test::write_txt_file_to_path(
+                &path,
+                "file,label,score\na.jpg,cat,0.1\nb.jpg,dog,0.9\nc.jpg,cat,0.7\nd.jpg,cat,0.3\n",
+            )?;
+            repositories::add(&repo, &path).await?;
+            let commit = repositories::commit(&repo, "Adding labels")?;
+
+            let mut opts = DFOpts::empty();
+            opts.columns = Some("file,score".to_string());
+            opts.filter = Some("label == cat".to_string());
+            opts.sort_by = Some("-score".to_string());
+            opts.slice = Some("0..2".to_string());
+            let slice = repositories::data_frames::get_slice(&repo, &commit, "labels.csv", &opts)?;
+
+            // The total counts every matching row, the slice only holds the page
+            assert_eq!(slice.total_entries, 3);
+            assert_eq!(slice.slice.height(), 2);
+            assert_eq!(slice.slice.width(), 2);
+            assert_eq!(slice.slice.column("file")?.str()?.get(0), Some("c.jpg"));
+            assert_eq!(slice.slice.column("file")?.str()?.get(1), Some("d.jpg"));
+
+            opts.sort_by = Some("missing".to_string());
+            let result = repositories::data_frames::get_slice(&repo, &commit, "labels.csv", &opts);
+            assert!(matches!(result, Err(OxenError::ColumnNameNotFound(_))));
+
+            Ok(())
+        })
+        .await
+    }
+}
@@ -10,6 +10,8 @@ use crate::core;
 use crate::core::versions::MinOxenVersion;
 
 use crate::error::OxenError;
+use crate::model::data_frame::schema::schema_changes::{ColumnRename, DtypeChange};
+use crate::model::data_frame::schema::{Field, SchemaChanges};
 use crate::model::{Commit, LocalRepository, Schema};
 use crate::repositories;
 
@@ -167,6 +169,85 @@ pub fn add_column_metadata(
     }
 }
 
+/// Compare the schema of a tabular file between two revisions. A column that is missing
+/// in the head but has an added column of the same dtype at its position is reported as
+/// renamed. A file that only exists in one of the revisions has all of its columns added
+/// or removed.
+pub fn diff(
+    repo: &LocalRepository,
+    path: impl AsRef<Path>,
+    base_revision: impl AsRef<str>,
+    head_revision: impl AsRef<str>,
+) -> Result<SchemaChanges, OxenError> {
+    let path = path.as_ref();
+    let base_revision = base_revision.as_ref();
+    let head_revision = head_revision.as_ref();
+    let base_commit = repositories::revisions::get(repo, base_revision)?
+        .ok_or(OxenError::revision_not_found(base_revision.into()))?;
+    let head_commit = repositories::revisions::get(repo, head_revision)?
+        .ok_or(OxenError::revision_not_found(head_revision.into()))?;
+
+    let base = get_by_path(repo, &base_commit, path)?;
+    let head = get_by_path(repo, &head_commit, path)?;
+    if base.is_none() && head.is_none() {
+        return Err(OxenError::schema_does_not_exist_for_file(path));
+    }
+    let base_fields = base.map(|schema| schema.fields).unwrap_or_default();
+    let head_fields = head.map(|schema| schema.fields).unwrap_or_default();
+
+    let mut changes = SchemaChanges {
+        path: path.to_path_buf(),
+        base_commit_id: base_commit.id,
+        head_commit_id: head_commit.id,
+        added: vec![],
+        removed: vec![],
+        renamed: vec![],
+        dtype_changed: vec![],
+    };
+
+    for field in base_fields.iter() {
+        match head_fields.iter().find(|f| f.name == field.name) {
+            Some(head_field) if head_field.dtype != field.dtype => {
+                changes.dtype_changed.push(DtypeChange {
+                    name: field.name.clone(),
+                    from: field.dtype.clone(),
+                    to: head_field.dtype.clone(),
+                })
+            }
+            Some(_) => {}
+            None => changes.removed.push(field.clone()),
+        }
+    }
+    changes.added = head_fields
+        .iter()
+        .filter(|field| !base_fields.iter().any(|f| f.name == field.name))
+        .cloned()
+        .collect();
+
+    // Pair up removed and added columns that sit at the same position with the same dtype
+    let position = |fields: &[Field], name: &str| fields.iter().position(|f| f.name == name);
+    changes.removed.retain(|removed| {
+        let index = position(&base_fields, &removed.name);
+        let renamed_to = changes.added.iter().position(|added| {
+            added.dtype == removed.dtype && position(&head_fields, &added.name) == index
+        });
+        match renamed_to {
+            Some(i) => {
+                let added = changes.added.remove(i);
+                changes.renamed.push(ColumnRename {
+                    from: removed.name.clone(),
+                    to: added.name,
+                    dtype: added.dtype,
+                });
+                false
+            }
+            None => true,
+        }
+    });
+
+    Ok(changes)
+}
+
 // unit tests
 #[cfg(test)]
 mod tests {
@@ -788,4 +869,46 @@ mod tests {
         })
         .await
     }
+
+    #[tokio::test]
+    async fn test_schemas_diff_between_revisions() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            let path = repo.path.join("data.csv");
+            test::write_txt_file_to_path(&path, "id,name,score,extra\n1,a,0.5,x\n")?;
+            repositories::add(&repo, &path).await?;
+            let base = repositories::commit(&repo, "Adding data")?;
+
+            test::write_txt_file_to_path(&path, "id,label,score,count\n1,a,high,3\n")?;
+            repositories::add(&repo, &path).await?;
+            let head = repositories::commit(&repo, "Changing columns")?;
+
+            let changes =
+                repositories::data_frames::schemas::diff(&repo, "data.csv", &base.id, &head.id)?;
+            assert_eq!(changes.renamed.len(), 1);
+            assert_eq!(changes.renamed[0].from, "name");
+            assert_eq!(changes.renamed[0].to, "label");
+            assert_eq!(changes.dtype_changed.len(), 1);
+            assert_eq!(changes.dtype_changed[0].name, "score");
+            assert_eq!(changes.dtype_changed[0].from, "f64");
+            assert_eq!(changes.dtype_changed[0].to, "str");
+            // Different dtypes so not a rename
+            assert_eq!(changes.removed.len(), 1);
+            assert_eq!(changes.removed[0].name, "extra");
+            assert_eq!(changes.added.len(), 1);
+            assert_eq!(changes.added[0].name, "count");
+            assert!(changes.is_breaking());
+
+            // Only adding a column is not breaking
+            test::write_txt_file_to_path(&path, "id,label,score,count,notes\n1,a,high,3,n\n")?;
+            repositories::add(&repo, &path).await?;
+            let next = repositories::commit(&repo, "Adding notes")?;
+            let changes =
+                repositories::data_frames::schemas::diff(&repo, "data.csv", &head.id, &next.id)?;
+            assert_eq!(changes.added.len(), 1);
+            assert!(!changes.is_breaking());
+
+            Ok(())
+        })
+        .await
+    }
 }
@@ -0,0 +1,156 @@
+//! # oxen dedup
+//!
+//! Find files in a revision that have the same contents. Every file node already carries
+//! the hash of its contents, so duplicates are found by grouping the file nodes of the
+//! merkle tree by hash without reading any data. The version store only keeps one copy of
+//! each, but every duplicate is still downloaded and checked out again.
+//!
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::OxenError;
+use crate::model::{Commit, LocalRepository, MerkleHash};
+use crate::repositories;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DuplicateGroup {
+    /// Hash of the contents the files share
+    pub hash: String,
+    /// Size of one of the files
+    pub num_bytes: u64,
+    pub paths: Vec<PathBuf>,
+}
+
+impl DuplicateGroup {
+    /// Bytes taken up by every copy after the first
+    pub fn wasted_bytes(&self) -> u64 {
+        self.num_bytes * (self.paths.len() as u64).saturating_sub(1)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct DedupReport {
+    pub commit_id: String,
+    pub files_checked: usize,
+    /// Groups of files with the same contents, the most wasted bytes first
+    pub groups: Vec<DuplicateGroup>,
+    pub wasted_bytes: u64,
+}
+
+/// Group the files at `revision` by their contents, optionally only the files under `path`
+pub fn report(
+    repo: &LocalRepository,
+    revision: impl AsRef<str>,
+    path: Option<&Path>,
+) -> Result<DedupReport, OxenError> {
+    let revision = revision.as_ref();
+    let commit = repositories::revisions::get(repo, revision)?
+        .ok_or(OxenError::revision_not_found(revision.into()))?;
+    report_for_commit(repo, &commit, path)
+}
+
+pub fn report_for_commit(
+    repo: &LocalRepository,
+    commit: &Commit,
+    path: Option<&Path>,
+) -> Result<DedupReport, OxenError> {
+    let path = path.unwrap_or(Path::new(""));
+    let node = if path == Path::new("") {
+        repositories::tree::get_root_with_children(repo, commit)?
+    } else {
+        repositories::tree::get_dir_with_children_recursive(repo, commit, path)?
+    }
+    .ok_or(OxenError::resource_not_found(path.to_string_lossy()))?;
+    let files = repositories::tree::list_all_files(&node, &path.to_path_buf())?;
+
+    let mut by_hash: HashMap<MerkleHash, DuplicateGroup> = HashMap::new();
+    for file in &files {
+        let hash = *file.file_node.hash();
+        by_hash
+            .entry(hash)
+            .or_insert_with(|| DuplicateGroup {
+                hash: hash.to_string(),
+                num_bytes: file.file_node.num_bytes(),
+                paths: vec![],
+            })
+            .paths
+            .push(file.dir.join(file.file_node.name()));
+    }
+
+    let mut groups: Vec<DuplicateGroup> = by_hash
+        .into_values()
+        .filter(|group| group.paths.len() > 1)
+        .map(|mut group| {
+            group.paths.sort();
+            group
+        })
+        .collect();
+    groups.sort_by(|a, b| {
+        b.wasted_bytes()
+            .cmp(&a.wasted_bytes())
+            .then_with(|| a.paths.cmp(&b.paths))
+    });
+
+    Ok(DedupReport {
+        commit_id: commit.id.clone(),
+        files_checked: files.len(),
+        wasted_bytes: groups.iter().map(DuplicateGroup::wasted_bytes).sum(),
+        groups,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use crate::error::OxenError;
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    #[tokio::test]
+    async fn test_dedup_report_groups_identical_files() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            util::fs::create_dir_all(repo.path.join("images"))?;
+            util::fs::create_dir_all(repo.path.join("labels"))?;
+            util::fs::write_to_path(repo.path.join("images/a.txt"), "same contents")?;
+            util::fs::write_to_path(repo.path.join("images/b.txt"), "same contents")?;
+            util::fs::write_to_path(repo.path.join("labels/a.txt"), "same contents")?;
+            util::fs::write_to_path(repo.path.join("labels/c.txt"), "cat")?;
+            util::fs::write_to_path(repo.path.join("labels/d.txt"), "cat")?;
+            util::fs::write_to_path(repo.path.join("README.md"), "unique")?;
+            repositories::add(&repo, &repo.path).await?;
+            repositories::commit(&repo, "Adding duplicates")?;
+
+            let report = repositories::dedup::report(&repo, "HEAD", None)?;
+            assert_eq!(report.files_checked, 6);
+            assert_eq!(report.groups.len(), 2);
+            assert_eq!(
+                report.groups[0].paths,
+                vec![
+                    PathBuf::from("images/a.txt"),
+                    PathBuf::from("images/b.txt"),
+                    PathBuf::from("labels/a.txt"),
+                ]
+            );
+            assert_eq!(report.groups[0].wasted_bytes(), 2 * 13);
+            assert_eq!(
+                report.groups[1].paths,
+                vec![PathBuf::from("labels/c.txt"), PathBuf::from("labels/d.txt")]
+            );
+            assert_eq!(report.wasted_bytes, 2 * 13 + 3);
+
+            // Only look under one dir
+            let report = repositories::dedup::report(&repo, "HEAD", Some(Path::new("labels")))?;
+            assert_eq!(report.files_checked, 3);
+            assert_eq!(report.groups.len(), 1);
+            assert_eq!(report.wasted_bytes, 3);
+
+            Ok(())
+        })
+        .await
+    }
+}
@@ -32,8 +32,8 @@ use crate::model::{
 use crate::view::Pagination;
 use crate::{constants, repositories, util};
 
-use polars::prelude::DataFrame;
 use polars::prelude::IntoLazy;
+use polars::prelude::{DataFrame, LazyFrame, Schema as PolarsSchema};
 
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
@@ -43,10 +43,18 @@ use crate::model::diff::diff_entries_counts::DiffEntriesCounts;
 use crate::model::diff::schema_diff::SchemaDiff;
 use crate::model::diff::AddRemoveModifyCounts;
 use crate::model::diff::DiffResult;
+use crate::model::diff::DirChangesSummary;
+use crate::model::diff::StructuredDiff;
 
 use crate::opts::{DFOpts, DiffOpts};
 
+pub use diff_stat::{commit_stat, diff_stat};
+
+pub mod binary_diff;
+pub mod diff_stat;
 pub mod join_diff;
+pub mod renames;
+pub mod structured_diff;
 pub mod utf8_diff;
 
 const TARGETS_HASH_COL: &str = "_targets_hash";
@@ -60,6 +68,24 @@ fn is_files_utf8(file_1: impl AsRef<Path>, file_2: impl AsRef<Path>) -> bool {
     util::fs::is_utf8(file_1.as_ref()) && util::fs::is_utf8(file_2.as_ref())
 }
 
+/// Diff the two versions of a JSON or YAML file by key. `None` if the name is not one, or
+/// if either version does not parse, in which case the file gets a line diff instead.
+fn try_structured_diff(
+    name: impl AsRef<Path>,
+    path_1: impl AsRef<Path>,
+    path_2: impl AsRef<Path>,
+) -> Option<StructuredDiff> {
+    let name = name.as_ref();
+    let format = structured_diff::format_from_path(name)?;
+    match structured_diff::diff(path_1, path_2, format) {
+        Ok(result) => Some(result),
+        Err(err) => {
+            log::debug!("Falling back to a line diff of {name:?}: {err}");
+            None
+        }
+    }
+}
+
 pub fn diff(opts: DiffOpts) -> Result<Vec<DiffResult>, OxenError> {
     log::debug!(
         "Starting diff function with keys: {:?} and targets: {:?}",
@@ -388,15 +414,18 @@ pub fn diff_files(
     if is_files_tabular(&path_1, &path_2) {
         let result = tabular(path_1, path_2, keys, targets, display)?;
         Ok(DiffResult::Tabular(result))
+    } else if let Some(mut result) = try_structured_diff(&path_2, &path_1, &path_2) {
+        result.filename1 = Some(path_1.as_ref().to_string_lossy().to_string());
+        result.filename2 = Some(path_2.as_ref().to_string_lossy().to_string());
+        Ok(DiffResult::Structured(result))
     } else if is_files_utf8(&path_1, &path_2) {
         let result = utf8_diff::diff(path_1, path_2)?;
         Ok(DiffResult::Text(result))
     } else {
-        Err(OxenError::invalid_file_type(format!(
-            "Compare not supported for files, found {:?} and {:?}",
-            path_1.as_ref(),
-            path_2.as_ref()
-        )))
+        let mut result = binary_diff::diff(&path_1, &path_2)?;
+        result.filename1 = Some(path_1.as_ref().to_string_lossy().to_string());
+        result.filename2 = Some(path_2.as_ref().to_string_lossy().to_string());
+        Ok(DiffResult::Binary(result))
     }
 }
 
@@ -409,6 +438,15 @@ pub fn diff_file_and_node(
     targets: Vec<String>,
     display: Vec<String>,
 ) -> Result<DiffResult, OxenError> {
+    if *file_node.data_type() != EntryDataType::Tabular {
+        let version_path = util::fs::version_path_from_hash(repo, file_node.hash().to_string());
+        if let Some(mut result) = try_structured_diff(&file_path, &version_path, &file_path) {
+            result.filename1 = Some(file_node.name().to_string());
+            result.filename2 = Some(file_path.as_ref().to_string_lossy().to_string());
+            return Ok(DiffResult::Structured(result));
+        }
+    }
+
     match file_node.data_type() {
         EntryDataType::Tabular => {
             let result = diff_tabular_file_and_file_node(
@@ -420,11 +458,13 @@ pub fn diff_file_and_node(
             let result = diff_text_file_and_node(repo, file_node, file_path)?;
             Ok(result)
         }
-        _ => Err(OxenError::invalid_file_type(format!(
-            "Compare not supported for files, found {:?} and {:?}",
-            file_path.as_ref(),
-            file_node.hash().to_string()
-        ))),
+        _ => {
+            let version_path = util::fs::version_path_from_hash(repo, file_node.hash().to_string());
+            let mut result = binary_diff::diff(&version_path, &file_path)?;
+            result.filename1 = Some(file_node.name().to_string());
+            result.filename2 = Some(file_path.as_ref().to_string_lossy().to_string());
+            Ok(DiffResult::Binary(result))
+        }
     }
 }
 
@@ -452,16 +492,22 @@ pub fn diff_file_nodes(
         result.filename1 = Some(file_1.name().to_string());
         result.filename2 = Some(file_2.name().to_string());
         Ok(DiffResult::Tabular(result))
+    } else if let Some(mut result) =
+        try_structured_diff(file_2.name(), &version_path_1, &version_path_2)
+    {
+        result.filename1 = Some(file_1.name().to_string());
+        result.filename2 = Some(file_2.name().to_string());
+        Ok(DiffResult::Structured(result))
     } else if is_files_utf8(&version_path_1, &version_path_2) {
         let mut result = utf8_diff::diff(version_path_1, version_path_2)?;
         result.filename1 = Some(file_1.name().to_string());
         result.filename2 = Some(file_2.name().to_string());
         Ok(DiffResult::Text(result))
     } else {
-        Err(OxenError::invalid_file_type(format!(
-            "Compare not supported for files, found {:?} and {:?}",
-            version_path_1, version_path_2
-        )))
+        let mut result = binary_diff::diff(&version_path_1, &version_path_2)?;
+        result.filename1 = Some(file_1.name().to_string());
+        result.filename2 = Some(file_2.name().to_string());
+        Ok(DiffResult::Binary(result))
     }
 }
 
@@ -475,15 +521,10 @@ pub fn diff_tabular_file_and_file_node(
 ) -> Result<TabularDiff, OxenError> {
     let file_node_path = util::fs::version_path_from_hash(repo, file_node.hash().to_string());
 
-    let df_1 = tabular::read_df(file_node_path, DFOpts::empty())?;
-    let df_2 = tabular::read_df(file_1_path, DFOpts::empty())?;
+    let df_1 = tabular::read_df_lazy(file_node_path, &DFOpts::empty())?;
+    let df_2 = tabular::read_df_lazy(file_1_path, &DFOpts::empty())?;
 
-    let schema_1 = Schema::from_polars(&df_1.schema());
-    let schema_2 = Schema::from_polars(&df_2.schema());
-
-    validate_required_fields(schema_1, schema_2, keys.clone(), targets.clone())?;
-
-    diff_dfs(&df_1, &df_2, keys, targets, display)
+    diff_lazy_dfs(df_1, df_2, keys, targets, display)
 }
 
 pub fn diff_tabular_file_nodes(
@@ -497,16 +538,11 @@ pub fn diff_tabular_file_nodes(
     let version_path_1 = util::fs::version_path_from_hash(repo, file_1.hash().to_string());
     let version_path_2 = util::fs::version_path_from_hash(repo, file_2.hash().to_string());
     let df_1 =
-        tabular::read_df_with_extension(version_path_1, file_1.extension(), &DFOpts::empty())?;
+        tabular::read_df_lazy_with_extension(version_path_1, file_1.extension(), &DFOpts::empty())?;
     let df_2 =
-        tabular::read_df_with_extension(version_path_2, file_2.extension(), &DFOpts::empty())?;
-
-    let schema_1 = Schema::from_polars(&df_1.schema());
-    let schema_2 = Schema::from_polars(&df_2.schema());
+        tabular::read_df_lazy_with_extension(version_path_2, file_2.extension(), &DFOpts::empty())?;
 
-    validate_required_fields(schema_1, schema_2, keys.clone(), targets.clone())?;
-
-    diff_dfs(&df_1, &df_2, keys, targets, display)
+    diff_lazy_dfs(df_1, df_2, keys, targets, display)
 }
 
 pub fn diff_text_file_and_node(
@@ -531,6 +567,21 @@ pub fn diff_text_file_nodes(
     Ok(DiffResult::Text(result))
 }
 
+/// Key by key diff of two versions of a JSON or YAML file, `None` if the file is not one
+/// or either version does not parse
+pub fn diff_structured_file_nodes(
+    repo: &LocalRepository,
+    file_1: &FileNode,
+    file_2: &FileNode,
+) -> Option<StructuredDiff> {
+    let version_path_1 = util::fs::version_path_from_hash(repo, file_1.hash().to_string());
+    let version_path_2 = util::fs::version_path_from_hash(repo, file_2.hash().to_string());
+    let mut result = try_structured_diff(file_2.name(), version_path_1, version_path_2)?;
+    result.filename1 = Some(file_1.name().to_string());
+    result.filename2 = Some(file_2.name().to_string());
+    Some(result)
+}
+
 pub fn tabular(
     file_1: impl AsRef<Path>,
     file_2: impl AsRef<Path>,
@@ -538,15 +589,10 @@ pub fn tabular(
     targets: Vec<String>,
     display: Vec<String>,
 ) -> Result<TabularDiff, OxenError> {
-    let df_1 = tabular::read_df(file_1, DFOpts::empty())?;
-    let df_2 = tabular::read_df(file_2, DFOpts::empty())?;
-
-    let schema_1 = Schema::from_polars(&df_1.schema());
-    let schema_2 = Schema::from_polars(&df_2.schema());
+    let df_1 = tabular::read_df_lazy(file_1, &DFOpts::empty())?;
+    let df_2 = tabular::read_df_lazy(file_2, &DFOpts::empty())?;
 
-    validate_required_fields(schema_1, schema_2, keys.clone(), targets.clone())?;
-
-    diff_dfs(&df_1, &df_2, keys, targets, display)
+    diff_lazy_dfs(df_1, df_2, keys, targets, display)
 }
 
 fn validate_required_fields(
@@ -581,35 +627,50 @@ pub fn diff_dfs(
     targets: Vec<String>,
     display: Vec<String>,
 ) -> Result<TabularDiff, OxenError> {
-    let schema_diff = get_schema_diff(df_1, df_2);
+    diff_lazy_dfs(
+        df_1.clone().lazy(),
+        df_2.clone().lazy(),
+        keys,
+        targets,
+        display,
+    )
+}
+
+/// Diff two data frames without reading them into memory. Only the schemas are resolved up
+/// front, the rows are hashed and joined as they are streamed and just the added, removed
+/// and modified rows are collected.
+pub fn diff_lazy_dfs(
+    mut df_1: LazyFrame,
+    mut df_2: LazyFrame,
+    keys: Vec<String>,
+    targets: Vec<String>,
+    display: Vec<String>,
+) -> Result<TabularDiff, OxenError> {
+    let schema_1 = df_1.collect_schema()?;
+    let schema_2 = df_2.collect_schema()?;
+
+    validate_required_fields(
+        Schema::from_polars(&schema_1),
+        Schema::from_polars(&schema_2),
+        keys.clone(),
+        targets.clone(),
+    )?;
+
+    let schema_diff = get_schema_diff(&schema_1, &schema_2);
 
     let (keys, targets) = get_keys_targets_smart_defaults(keys, targets, &schema_diff)?;
     let display = get_display_smart_defaults(&keys, &targets, display, &schema_diff);
 
-    log::debug!("df_1 is {:?}", df_1);
-    log::debug!("df_2 is {:?}", df_2);
-
-    let (df_1, df_2) = hash_dfs(df_1.clone(), df_2.clone(), &keys, &targets)?;
+    let (df_1, df_2) = hash_dfs(df_1, df_2, &keys, &targets)?;
 
-    let compare = join_diff::diff(&df_1, &df_2, schema_diff, &keys, &targets, &display)?;
+    let compare = join_diff::diff(df_1, df_2, schema_diff, &keys, &targets, &display)?;
 
     Ok(compare)
 }
 
-fn get_schema_diff(df1: &DataFrame, df2: &DataFrame) -> SchemaDiff {
-    let df1_cols = df1.get_column_names();
-    let df2_cols = df2.get_column_names();
-
-    let mut df1_set = HashSet::new();
-    let mut df2_set = HashSet::new();
-
-    for col in df1_cols.iter() {
-        df1_set.insert(col);
-    }
-
-    for col in df2_cols.iter() {
-        df2_set.insert(col);
-    }
+fn get_schema_diff(schema_1: &PolarsSchema, schema_2: &PolarsSchema) -> SchemaDiff {
+    let df1_set: HashSet<&str> = schema_1.iter_names().map(|name| name.as_str()).collect();
+    let df2_set: HashSet<&str> = schema_2.iter_names().map(|name| name.as_str()).collect();
 
     let added_cols: Vec<String> = df2_set
         .difference(&df1_set)
@@ -707,16 +768,16 @@ fn get_display_smart_defaults(
 }
 
 fn hash_dfs(
-    mut left_df: DataFrame,
-    mut right_df: DataFrame,
+    mut left_df: LazyFrame,
+    mut right_df: LazyFrame,
     keys: &[String],
     targets: &[String],
-) -> Result<(DataFrame, DataFrame), OxenError> {
-    left_df = tabular::df_hash_rows_on_cols(left_df, targets, TARGETS_HASH_COL)?;
-    right_df = tabular::df_hash_rows_on_cols(right_df, targets, TARGETS_HASH_COL)?;
+) -> Result<(LazyFrame, LazyFrame), OxenError> {
+    left_df = tabular::lazy_hash_rows_on_cols(left_df, targets, TARGETS_HASH_COL)?;
+    right_df = tabular::lazy_hash_rows_on_cols(right_df, targets, TARGETS_HASH_COL)?;
 
-    left_df = tabular::df_hash_rows_on_cols(left_df, keys, KEYS_HASH_COL)?;
-    right_df = tabular::df_hash_rows_on_cols(right_df, keys, KEYS_HASH_COL)?;
+    left_df = tabular::lazy_hash_rows_on_cols(left_df, keys, KEYS_HASH_COL)?;
+    right_df = tabular::lazy_hash_rows_on_cols(right_df, keys, KEYS_HASH_COL)?;
     Ok((left_df, right_df))
 }
 
@@ -1001,6 +1062,19 @@ pub fn list_changed_dirs(
     }
 }
 
+/// Files added, removed and modified under `path` between two commits, per directory
+pub fn dir_changes(
+    repo: &LocalRepository,
+    base_commit: &Commit,
+    head_commit: &Commit,
+    path: impl AsRef<Path>,
+) -> Result<DirChangesSummary, OxenError> {
+    match repo.min_version() {
+        MinOxenVersion::V0_10_0 => panic!("v0.10.0 no longer supported"),
+        _ => core::v_latest::diff::dir_changes(repo, base_commit, head_commit, path),
+    }
+}
+
 pub fn cache_tabular_diff(
     repo: &LocalRepository,
     compare_id: &str,
@@ -1073,34 +1147,36 @@ pub fn get_cached_diff(
     let left_entry = compare_entry_1.unwrap();
     let right_entry = compare_entry_2.unwrap();
 
-    // TODO this should be cached
-    let left_full_df = tabular::read_df(
+    // Only the schemas of the full files are needed, so scan rather than read them
+    let left_schema = tabular::read_df_lazy(
         repositories::revisions::get_version_file_from_commit_id(
             repo,
             left_entry.commit_id,
             &left_entry.path,
         )?,
-        DFOpts::empty(),
-    )?;
-    let right_full_df = tabular::read_df(
+        &DFOpts::empty(),
+    )?
+    .collect_schema()?;
+    let right_schema = tabular::read_df_lazy(
         repositories::revisions::get_version_file_from_commit_id(
             repo,
             right_entry.commit_id,
             &right_entry.path,
         )?,
-        DFOpts::empty(),
-    )?;
+        &DFOpts::empty(),
+    )?
+    .collect_schema()?;
 
     let schema_diff = TabularSchemaDiff::from_schemas(
-        &Schema::from_polars(&left_full_df.schema()),
-        &Schema::from_polars(&right_full_df.schema()),
+        &Schema::from_polars(&left_schema),
+        &Schema::from_polars(&right_schema),
     )?;
 
     let diff_df = tabular::read_df(get_diff_cache_path(repo, compare_id), DFOpts::empty())?;
 
     let schemas = TabularDiffSchemas {
-        left: Schema::from_polars(&left_full_df.schema()),
-        right: Schema::from_polars(&right_full_df.schema()),
+        left: Schema::from_polars(&left_schema),
+        right: Schema::from_polars(&right_schema),
         diff: Schema::from_polars(&diff_df.schema()),
     };
 
@@ -1119,6 +1195,7 @@ pub fn get_cached_diff(
         summary: tab_diff_summary,
         // Don't have or need server-updated keys, targets, display on cache hit
         parameters: TabularDiffParameters::empty(),
+        modified_cells: join_diff::modified_cells(&diff_df)?,
         contents: diff_df,
         filename1: None,
         filename2: None,
@@ -1246,7 +1323,7 @@ pub fn get_add_remove_modify_counts(entries: &[DiffEntry]) -> AddRemoveModifyCou
         match DiffEntryStatus::from_str(&entry.status).unwrap() {
             DiffEntryStatus::Added => added += 1,
             DiffEntryStatus::Removed => removed += 1,
-            DiffEntryStatus::Modified => modified += 1,
+            DiffEntryStatus::Modified | DiffEntryStatus::Renamed => modified += 1,
         }
     }
     AddRemoveModifyCounts {
@@ -1323,6 +1400,51 @@ mod tests {
         .await
     }
 
+    #[tokio::test]
+    async fn test_dir_changes_groups_files_by_dir() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            util::fs::create_dir_all(repo.path.join("images/cats"))?;
+            util::fs::create_dir_all(repo.path.join("labels"))?;
+            util::fs::write_to_path(repo.path.join("images/cats/1.txt"), "cat")?;
+            util::fs::write_to_path(repo.path.join("images/cats/2.txt"), "cat two")?;
+            util::fs::write_to_path(repo.path.join("labels/train.txt"), "labels")?;
+            util::fs::write_to_path(repo.path.join("README.md"), "readme")?;
+            repositories::add(&repo, &repo.path).await?;
+            let base_commit = repositories::commit(&repo, "Adding data")?;
+
+            util::fs::write_to_path(repo.path.join("images/cats/1.txt"), "cat one")?;
+            util::fs::write_to_path(repo.path.join("images/cats/3.txt"), "cat 3")?;
+            util::fs::write_to_path(repo.path.join("labels/test.txt"), "more")?;
+            repositories::add(&repo, &repo.path).await?;
+            util::fs::remove_file(repo.path.join("images/cats/2.txt"))?;
+            repositories::rm(&repo, &RmOpts::from_path("images/cats/2.txt"))?;
+            let head_commit = repositories::commit(&repo, "Changing data")?;
+
+            let summary = repositories::diffs::dir_changes(&repo, &base_commit, &head_commit, "")?;
+            assert_eq!(summary.dirs.len(), 2);
+            let cats = &summary.dirs[0];
+            assert_eq!(cats.path, PathBuf::from("images/cats"));
+            assert_eq!(cats.file_counts.added, 1);
+            assert_eq!(cats.file_counts.removed, 1);
+            assert_eq!(cats.file_counts.modified, 1);
+            // +4 bytes to 1.txt, +5 for 3.txt, -7 for 2.txt
+            assert_eq!(cats.byte_delta, 2);
+            let labels = &summary.dirs[1];
+            assert_eq!(labels.path, PathBuf::from("labels"));
+            assert_eq!(labels.file_counts.added, 1);
+            assert_eq!(summary.total().file_counts.added, 2);
+
+            // Only under one dir
+            let summary =
+                repositories::diffs::dir_changes(&repo, &base_commit, &head_commit, "labels")?;
+            assert_eq!(summary.dirs.len(), 1);
+            assert_eq!(summary.dirs[0].byte_delta, 4);
+
+            Ok(())
+        })
+        .await
+    }
+
     #[tokio::test]
     async fn test_diff_entries_modify_one_tabular() -> Result<(), OxenError> {
         test::run_bounding_box_csv_repo_test_fully_committed_async(|repo| async move {
@@ -1732,6 +1854,33 @@ train/cat_2.jpg,cat,30.5,44.0,333,396
         })
     }
 
+    #[test]
+    fn test_diff_json_files_by_key() -> Result<(), OxenError> {
+        test::run_empty_dir_test(|dir| {
+            let file1 = dir.join("file1.json");
+            let file2 = dir.join("file2.json");
+            util::fs::write_to_path(&file1, "{\"name\": \"cat\", \"count\": 1}")?;
+            util::fs::write_to_path(&file2, "{\"count\": 2, \"name\": \"cat\"}")?;
+
+            let diff = repositories::diffs::diff_files(&file1, &file2, vec![], vec![], vec![])?;
+            match diff {
+                DiffResult::Structured(result) => {
+                    assert_eq!(result.keys.len(), 1);
+                    assert_eq!(result.keys[0].key, "count");
+                    assert_eq!(result.keys[0].modification, ChangeType::Modified);
+                }
+                _ => panic!("expected structured result"),
+            }
+
+            // Falls back to a line diff when a side does not parse
+            util::fs::write_to_path(&file2, "{\"count\": 2,")?;
+            let diff = repositories::diffs::diff_files(&file1, &file2, vec![], vec![], vec![])?;
+            assert!(matches!(diff, DiffResult::Text(_)));
+
+            Ok(())
+        })
+    }
+
     #[tokio::test]
     async fn test_compare_one_added_one_removed_no_keys_no_targets() -> Result<(), OxenError> {
         test::run_empty_local_repo_test_async(|repo| async move {
@@ -2137,4 +2286,58 @@ train/cat_2.jpg,cat,30.5,44.0,333,396
         })
         .await
     }
+
+    #[tokio::test]
+    async fn test_compare_reports_modified_cells() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            let csv1 = "id,label,score\n1,cat,0.5\n2,dog,0.7\n";
+            let csv2 = "id,label,score\n1,cat,0.9\n2,bird,0.7\n3,fish,0.1\n";
+
+            let path_1 = PathBuf::from("file1.csv");
+            let path_2 = PathBuf::from("file2.csv");
+            tokio::fs::write(repo.path.join(&path_1), csv1).await?;
+            tokio::fs::write(repo.path.join(&path_2), csv2).await?;
+            repositories::add(&repo, repo.path.clone()).await?;
+            let commit = repositories::commit(&repo, "two files")?;
+
+            let c1 = CommitPath {
+                commit: Some(commit.clone()),
+                path: path_1,
+            };
+            let c2 = CommitPath {
+                commit: Some(commit.clone()),
+                path: path_2,
+            };
+            let compare_result = repositories::diffs::diff_commits(
+                &repo,
+                c1,
+                c2,
+                vec!["id".to_string()],
+                vec!["label".to_string(), "score".to_string()],
+                vec![],
+            )?;
+
+            let DiffResult::Tabular(result) = compare_result else {
+                panic!("expected tabular result");
+            };
+            // The added row is not a modified cell
+            let cells = result.modified_cells;
+            assert_eq!(cells.height(), 2);
+            let names: Vec<String> = cells
+                .get_column_names()
+                .iter()
+                .map(|name| name.to_string())
+                .collect();
+            assert_eq!(names, vec!["id", "label", "score"]);
+            let label = cells.column("label")?.str()?;
+            let score = cells.column("score")?.str()?;
+            assert_eq!(label.get(0), None);
+            assert_eq!(score.get(0), Some("0.5 → 0.9"));
+            assert_eq!(label.get(1), Some("dog → bird"));
+            assert_eq!(score.get(1), None);
+
+            Ok(())
+        })
+        .await
+    }
 }
@@ -0,0 +1,174 @@
+//! Diff binary files by content-defined chunks
+//!
+//! Files are split where a gear rolling hash over the last 64 bytes hits a boundary
+//! pattern (as in FastCDC), so chunk boundaries move with the content and an edit
+//! only changes the chunks that contain it.
+//!
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+use crate::error::OxenError;
+use crate::model::diff::binary_diff::{BinaryDiff, ChunkRange};
+use crate::util;
+
+const MIN_CHUNK_SIZE: usize = 16 * 1024;
+const MAX_CHUNK_SIZE: usize = 256 * 1024;
+/// The top bits of the gear hash mix in the most bytes, 16 of them gives chunks of
+/// about 64KB past the minimum
+const BOUNDARY_MASK: u64 = 0xFFFF << 48;
+const READ_BUFFER_SIZE: usize = 1024 * 1024;
+
+const GEAR: [u64; 256] = gear_table();
+
+/// Fixed pseudo random values for each byte, from splitmix64, so chunks are stable
+/// across versions of oxen
+const fn gear_table() -> [u64; 256] {
+    let mut table = [0u64; 256];
+    let mut state: u64 = 0;
+    let mut i = 0;
+    while i < 256 {
+        state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        table[i] = z ^ (z >> 31);
+        i += 1;
+    }
+    table
+}
+
+struct Chunk {
+    offset: u64,
+    len: u64,
+    hash: u128,
+}
+
+fn chunk_file(path: &Path) -> Result<(Vec<Chunk>, u64), OxenError> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut buffer = vec![0u8; READ_BUFFER_SIZE];
+    let mut chunks: Vec<Chunk> = vec![];
+    let mut current: Vec<u8> = Vec::with_capacity(MAX_CHUNK_SIZE);
+    let mut offset: u64 = 0;
+    let mut hash: u64 = 0;
+
+    let mut emit = |current: &mut Vec<u8>, offset: &mut u64| {
+        let len = current.len() as u64;
+        chunks.push(Chunk {
+            offset: *offset,
+            len,
+            hash: util::hasher::hash_buffer_128bit(current),
+        });
+        *offset += len;
+        current.clear();
+    };
+
+    loop {
+        let num_read = reader.read(&mut buffer)?;
+        if num_read == 0 {
+            break;
+        }
+        for &byte in &buffer[..num_read] {
+            current.push(byte);
+            hash = (hash << 1).wrapping_add(GEAR[byte as usize]);
+            if current.len() >= MAX_CHUNK_SIZE
+                || (current.len() >= MIN_CHUNK_SIZE && hash & BOUNDARY_MASK == 0)
+            {
+                emit(&mut current, &mut offset);
+                hash = 0;
+            }
+        }
+    }
+    if !current.is_empty() {
+        emit(&mut current, &mut offset);
+    }
+    Ok((chunks, offset))
+}
+
+pub fn diff(
+    version_file_1: impl AsRef<Path>,
+    version_file_2: impl AsRef<Path>,
+) -> Result<BinaryDiff, OxenError> {
+    let (chunks_1, size_1) = chunk_file(version_file_1.as_ref())?;
+    let (chunks_2, size_2) = chunk_file(version_file_2.as_ref())?;
+    let known: HashSet<u128> = chunks_1.iter().map(|chunk| chunk.hash).collect();
+
+    let mut result = BinaryDiff {
+        size1: size_1,
+        size2: size_2,
+        num_chunks1: chunks_1.len(),
+        num_chunks2: chunks_2.len(),
+        ..Default::default()
+    };
+    for chunk in chunks_2 {
+        if known.contains(&chunk.hash) {
+            result.unchanged_chunks += 1;
+            result.unchanged_bytes += chunk.len;
+            continue;
+        }
+        match result.changed_ranges.last_mut() {
+            Some(range) if range.offset + range.len == chunk.offset => range.len += chunk.len,
+            _ => result.changed_ranges.push(ChunkRange {
+                offset: chunk.offset,
+                len: chunk.len,
+            }),
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::OxenError;
+    use crate::repositories::diffs::binary_diff;
+    use crate::test;
+    use crate::util;
+
+    /// Deterministic bytes without long runs, so the chunker finds boundaries
+    fn noise(len: usize, seed: u64) -> Vec<u8> {
+        let mut state = seed;
+        (0..len)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                (state >> 56) as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_binary_diff_localizes_changes() -> Result<(), OxenError> {
+        test::run_empty_dir_test(|dir| {
+            let original = noise(4 * 1024 * 1024, 42);
+            let path_1 = dir.join("weights_1.bin");
+            util::fs::write(&path_1, &original)?;
+
+            let same = binary_diff::diff(&path_1, &path_1)?;
+            assert_eq!(same.unchanged_fraction(), 1.0);
+            assert!(same.changed_ranges.is_empty());
+
+            // Overwrite a region in the middle and insert bytes at the front, which
+            // would shift every fixed size block
+            let mut modified = b"header".to_vec();
+            modified.extend_from_slice(&original);
+            let edit_at = 2 * 1024 * 1024;
+            modified[edit_at..edit_at + 100].copy_from_slice(&noise(100, 7));
+            let path_2 = dir.join("weights_2.bin");
+            util::fs::write(&path_2, &modified)?;
+
+            let diff = binary_diff::diff(&path_1, &path_2)?;
+            assert_eq!(diff.size2, modified.len() as u64);
+            assert!(diff.unchanged_fraction() > 0.8);
+            assert!(diff.unchanged_chunks < diff.num_chunks2);
+            assert!(diff.changed_ranges.iter().any(|range| range.offset == 0));
+            assert!(diff.changed_ranges.iter().any(|range| {
+                range.offset <= edit_at as u64 && edit_at as u64 <= range.offset + range.len
+            }));
+
+            Ok(())
+        })
+    }
+}
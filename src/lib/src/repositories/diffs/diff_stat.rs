@@ -0,0 +1,329 @@
+//! Per-file insertion and deletion counts between two commits, as in `oxen diff --stat`
+//!
+//! Text files are counted in lines, tabular files in rows and everything else in bytes.
+//! Added and removed files are counted from their metadata, so only modified files
+//! are compared. A removed and an added file with the same contents are shown as one
+//! renamed file with no changes.
+//!
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::error::OxenError;
+use crate::model::diff::change_type::ChangeType;
+use crate::model::diff::diff_entry_status::DiffEntryStatus;
+use crate::model::diff::diff_stat::{DiffStat, DiffStatUnit, FileDiffStat};
+use crate::model::merkle_tree::node::FileNode;
+use crate::model::metadata::generic_metadata::GenericMetadata;
+use crate::model::{Commit, EntryDataType, LocalRepository};
+use crate::opts::CountLinesOpts;
+use crate::repositories;
+use crate::repositories::diffs::{binary_diff, renames, utf8_diff};
+use crate::util;
+
+/// Count the changes to every file under `path` between two commits
+pub fn diff_stat(
+    repo: &LocalRepository,
+    base_commit: &Commit,
+    head_commit: &Commit,
+    path: impl AsRef<Path>,
+) -> Result<DiffStat, OxenError> {
+    let base_files = list_files(repo, base_commit, path.as_ref())?;
+    let head_files = list_files(repo, head_commit, path.as_ref())?;
+    stat_files(repo, &base_files, &head_files)
+}
+
+/// Count the changes a commit made on top of its first parent. The first commit of a
+/// branch is compared to an empty tree, so every file is added.
+pub fn commit_stat(repo: &LocalRepository, commit: &Commit) -> Result<DiffStat, OxenError> {
+    let head_files = list_files(repo, commit, Path::new(""))?;
+    let base_files = match commit.parent_ids.first() {
+        Some(parent_id) => {
+            let parent = repositories::commits::get_by_id(repo, parent_id)?
+                .ok_or(OxenError::commit_id_does_not_exist(parent_id))?;
+            list_files(repo, &parent, Path::new(""))?
+        }
+        None => HashMap::new(),
+    };
+    stat_files(repo, &base_files, &head_files)
+}
+
+fn stat_files(
+    repo: &LocalRepository,
+    base_files: &HashMap<PathBuf, FileNode>,
+    head_files: &HashMap<PathBuf, FileNode>,
+) -> Result<DiffStat, OxenError> {
+    let renamed = renames::find_renames(base_files, head_files);
+    let renamed_from: HashMap<&PathBuf, &PathBuf> =
+        renamed.iter().map(|(old, new)| (new, old)).collect();
+    let moved_away: HashSet<&PathBuf> = renamed.iter().map(|(old, _)| old).collect();
+
+    let mut files: Vec<FileDiffStat> = vec![];
+    for (file_path, head) in head_files {
+        if let Some(old_path) = renamed_from.get(file_path) {
+            files.push(FileDiffStat {
+                path: file_path.clone(),
+                old_path: Some((*old_path).clone()),
+                status: DiffEntryStatus::Renamed,
+                unit: unit_for(head.data_type(), head.data_type()),
+                insertions: 0,
+                deletions: 0,
+            });
+            continue;
+        }
+        let stat = match base_files.get(file_path) {
+            Some(base) if base.hash() == head.hash() => continue,
+            Some(base) => modified_stat(repo, file_path, base, head)?,
+            None => {
+                let unit = unit_for(head.data_type(), head.data_type());
+                FileDiffStat {
+                    path: file_path.clone(),
+                    old_path: None,
+                    status: DiffEntryStatus::Added,
+                    unit,
+                    insertions: size_in(repo, head, unit)?,
+                    deletions: 0,
+                }
+            }
+        };
+        files.push(stat);
+    }
+    for (file_path, base) in base_files {
+        if head_files.contains_key(file_path) || moved_away.contains(file_path) {
+            continue;
+        }
+        let unit = unit_for(base.data_type(), base.data_type());
+        files.push(FileDiffStat {
+            path: file_path.clone(),
+            old_path: None,
+            status: DiffEntryStatus::Removed,
+            unit,
+            insertions: 0,
+            deletions: size_in(repo, base, unit)?,
+        });
+    }
+
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(DiffStat { files })
+}
+
+pub(crate) fn list_files(
+    repo: &LocalRepository,
+    commit: &Commit,
+    path: &Path,
+) -> Result<HashMap<PathBuf, FileNode>, OxenError> {
+    let Some(tree) = repositories::tree::get_root_with_children(repo, commit)? else {
+        return Err(OxenError::commit_id_does_not_exist(&commit.id));
+    };
+    let files = repositories::tree::list_all_files(&tree, &PathBuf::from(""))?;
+    Ok(files
+        .into_iter()
+        .map(|file| (file.dir.join(file.file_node.name()), file.file_node))
+        .filter(|(file_path, _)| file_path.starts_with(path))
+        .collect())
+}
+
+fn unit_for(base: &EntryDataType, head: &EntryDataType) -> DiffStatUnit {
+    match (base, head) {
+        (EntryDataType::Tabular, EntryDataType::Tabular) => DiffStatUnit::Rows,
+        (EntryDataType::Text, EntryDataType::Text) => DiffStatUnit::Lines,
+        _ => DiffStatUnit::Bytes,
+    }
+}
+
+/// The size of a whole file, for files that were added or removed
+fn size_in(repo: &LocalRepository, node: &FileNode, unit: DiffStatUnit) -> Result<u64, OxenError> {
+    match (unit, node.metadata()) {
+        (DiffStatUnit::Rows, Some(GenericMetadata::MetadataTabular(metadata))) => {
+            Ok(metadata.tabular.height as u64)
+        }
+        (DiffStatUnit::Lines, Some(GenericMetadata::MetadataText(metadata))) => {
+            Ok(metadata.text.num_lines as u64)
+        }
+        (DiffStatUnit::Lines, _) => {
+            let version_path = util::fs::version_path_from_hash(repo, node.hash().to_string());
+            let (num_lines, _) = util::fs::count_lines(version_path, CountLinesOpts::empty())?;
+            Ok(num_lines as u64)
+        }
+        _ => Ok(node.num_bytes()),
+    }
+}
+
+fn modified_stat(
+    repo: &LocalRepository,
+    file_path: &Path,
+    base: &FileNode,
+    head: &FileNode,
+) -> Result<FileDiffStat, OxenError> {
+    let unit = unit_for(base.data_type(), head.data_type());
+    let base_path = util::fs::version_path_from_hash(repo, base.hash().to_string());
+    let head_path = util::fs::version_path_from_hash(repo, head.hash().to_string());
+    let (insertions, deletions) = match unit {
+        DiffStatUnit::Rows => {
+            let diff = repositories::diffs::diff_tabular_file_nodes(
+                repo,
+                base,
+                head,
+                vec![],
+                vec![],
+                vec![],
+            )?;
+            let counts = diff.summary.modifications.row_counts;
+            (
+                (counts.added + counts.modified) as u64,
+                (counts.removed + counts.modified) as u64,
+            )
+        }
+        DiffStatUnit::Lines => {
+            let diff = utf8_diff::diff(&base_path, &head_path)?;
+            let count = |change: ChangeType| {
+                diff.lines
+                    .iter()
+                    .filter(|line| line.modification == change)
+                    .count() as u64
+            };
+            (count(ChangeType::Added), count(ChangeType::Removed))
+        }
+        DiffStatUnit::Bytes => {
+            let diff = binary_diff::diff(&base_path, &head_path)?;
+            (
+                diff.changed_bytes(),
+                diff.size1.saturating_sub(diff.unchanged_bytes),
+            )
+        }
+    };
+    Ok(FileDiffStat {
+        path: file_path.to_path_buf(),
+        old_path: None,
+        status: DiffEntryStatus::Modified,
+        unit,
+        insertions,
+        deletions,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::error::OxenError;
+    use crate::model::diff::diff_entry_status::DiffEntryStatus;
+    use crate::model::diff::diff_stat::DiffStatUnit;
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    #[tokio::test]
+    async fn test_diff_stat_counts_lines_rows_and_bytes() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            let text_path = repo.path.join("notes.txt");
+            let csv_path = repo.path.join("data.csv");
+            let bin_path = repo.path.join("weights.bin");
+            let removed_path = repo.path.join("old.txt");
+            util::fs::write_to_path(&text_path, "one\ntwo\nthree")?;
+            util::fs::write_to_path(&csv_path, "id,label\n1,cat\n2,dog\n")?;
+            util::fs::write(&bin_path, [0u8, 159, 146, 150])?;
+            util::fs::write_to_path(&removed_path, "gone")?;
+            repositories::add(&repo, &repo.path).await?;
+            let base = repositories::commit(&repo, "Base")?;
+
+            util::fs::write_to_path(&text_path, "one\n2\nthree\nfour")?;
+            util::fs::write_to_path(&csv_path, "id,label\n1,cat\n2,dog\n3,bird\n")?;
+            util::fs::write(&bin_path, [0u8, 159, 146, 150, 255])?;
+            util::fs::remove_file(&removed_path)?;
+            let added_path = repo.path.join("new.txt");
+            util::fs::write_to_path(&added_path, "a\nb")?;
+            repositories::add(&repo, &repo.path).await?;
+            let head = repositories::commit(&repo, "Head")?;
+
+            let stat = repositories::diffs::diff_stat(&repo, &base, &head, "")?;
+            let summary: Vec<(PathBuf, DiffEntryStatus, DiffStatUnit, u64, u64)> = stat
+                .files
+                .iter()
+                .map(|file| {
+                    (
+                        file.path.clone(),
+                        file.status.clone(),
+                        file.unit,
+                        file.insertions,
+                        file.deletions,
+                    )
+                })
+                .collect();
+            assert_eq!(
+                summary,
+                vec![
+                    (
+                        PathBuf::from("data.csv"),
+                        DiffEntryStatus::Modified,
+                        DiffStatUnit::Rows,
+                        1,
+                        0
+                    ),
+                    (
+                        PathBuf::from("new.txt"),
+                        DiffEntryStatus::Added,
+                        DiffStatUnit::Lines,
+                        2,
+                        0
+                    ),
+                    (
+                        PathBuf::from("notes.txt"),
+                        DiffEntryStatus::Modified,
+                        DiffStatUnit::Lines,
+                        2,
+                        1
+                    ),
+                    (
+                        PathBuf::from("old.txt"),
+                        DiffEntryStatus::Removed,
+                        DiffStatUnit::Lines,
+                        0,
+                        1
+                    ),
+                    (
+                        PathBuf::from("weights.bin"),
+                        DiffEntryStatus::Modified,
+                        DiffStatUnit::Bytes,
+                        5,
+                        4
+                    ),
+                ]
+            );
+            assert_eq!(stat.totals()[&DiffStatUnit::Lines], (4, 2));
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_commit_stat_compares_to_parent() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            let text_path = repo.path.join("notes.txt");
+            util::fs::write_to_path(&text_path, "one\ntwo")?;
+            repositories::add(&repo, &text_path).await?;
+            let first = repositories::commit(&repo, "First")?;
+
+            // The first commit adds everything
+            let stat = repositories::diffs::commit_stat(&repo, &first)?;
+            assert_eq!(stat.files.len(), 1);
+            assert_eq!(stat.files[0].status, DiffEntryStatus::Added);
+            assert_eq!(stat.files[0].insertions, 2);
+
+            let csv_path = repo.path.join("data.csv");
+            util::fs::write_to_path(&csv_path, "id,label\n1,cat\n")?;
+            repositories::add(&repo, &csv_path).await?;
+            let second = repositories::commit(&repo, "Second")?;
+
+            // Unchanged files from the parent are left out
+            let stat = repositories::diffs::commit_stat(&repo, &second)?;
+            assert_eq!(stat.files.len(), 1);
+            assert_eq!(stat.files[0].path, PathBuf::from("data.csv"));
+            assert_eq!(stat.files[0].unit, DiffStatUnit::Rows);
+
+            Ok(())
+        })
+        .await
+    }
+}
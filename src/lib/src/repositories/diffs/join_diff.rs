@@ -16,13 +16,12 @@ use crate::view::compare::{
 use polars::chunked_array::ops::SortMultipleOptions;
 use polars::datatypes::AnyValue;
 use polars::lazy::dsl::coalesce;
-use polars::lazy::dsl::{all, as_struct, col, GetOutput};
-use polars::lazy::frame::IntoLazy;
+use polars::lazy::dsl::{col, lit, when, Expr};
+use polars::lazy::frame::LazyFrame;
 use polars::prelude::ChunkCompareEq;
 use polars::prelude::PlSmallStr;
-use polars::prelude::SchemaExt;
 use polars::prelude::{Column, NamedFrom};
-use polars::prelude::{DataFrame, DataFrameJoinOps};
+use polars::prelude::{DataFrame, JoinArgs, JoinType, Schema as PolarsSchema};
 use polars::series::Series;
 
 use super::{tabular, SchemaDiff};
@@ -34,10 +33,11 @@ const DIFF_STATUS_ADDED: &str = "added";
 const DIFF_STATUS_REMOVED: &str = "removed";
 const DIFF_STATUS_MODIFIED: &str = "modified";
 const DIFF_STATUS_UNCHANGED: &str = "unchanged";
+const CELL_CHANGE_SEPARATOR: &str = " → ";
 
 pub fn diff(
-    df_1: &DataFrame,
-    df_2: &DataFrame,
+    mut df_1: LazyFrame,
+    mut df_2: LazyFrame,
     schema_diff: SchemaDiff,
     keys: &[impl AsRef<str>],
     targets: &[impl AsRef<str>],
@@ -54,54 +54,44 @@ pub fn diff(
     let targets: Vec<&str> = targets.iter().map(|k| k.as_ref()).collect();
     let display: Vec<&str> = display.iter().map(|k| k.as_ref()).collect();
 
+    let schema_1 = df_1.collect_schema()?;
+    let schema_2 = df_2.collect_schema()?;
+
     let output_columns = get_output_columns(
-        &Schema::from_polars(&df_2.schema()),
+        &Schema::from_polars(&schema_2),
         keys.clone(),
         targets.clone(),
         display.clone(),
         schema_diff.clone(),
     );
 
-    log::debug!("df_1 is {:?}", df_1);
-    log::debug!("df_2 is {:?}", df_2);
     log::debug!("keys are {:?}", keys);
     log::debug!("targets are {:?}", targets);
     log::debug!("display are {:?}", display);
     log::debug!("output_columns are {:?}", output_columns);
 
     let joined_df = join_hashed_dfs(
-        df_1,
-        df_2,
+        df_1.clone(),
+        df_2.clone(),
         keys.clone(),
         targets.clone(),
         schema_diff.clone(),
     )?;
 
-    log::debug!("joined_df is {:?}", joined_df);
-
     let joined_df = add_diff_status_column(joined_df, keys.clone(), targets.clone())?;
-    log::debug!("joined_df after add_diff_status_column is {:?}", joined_df);
-    let unchanged_vec = vec![DIFF_STATUS_UNCHANGED; joined_df.height()];
-    let unchanged_series = Column::Series(
-        Series::new(PlSmallStr::from_str(DIFF_STATUS_UNCHANGED), unchanged_vec).into(),
-    );
-    let mut joined_df = joined_df.filter(
-        &joined_df
-            .column(DIFF_STATUS_COL)?
-            .not_equal(&unchanged_series)?,
-    )?;
-    log::debug!("joined_df after filter is {:?}", joined_df);
+    let mut joined_df = joined_df.filter(col(DIFF_STATUS_COL).neq(lit(DIFF_STATUS_UNCHANGED)));
     // Once we've joined and calculated group membership based on .left and .right nullity, coalesce keys
     for key in keys.clone() {
-        joined_df = joined_df
-            .lazy()
-            .with_columns([coalesce(&[
-                col(format!("{}.right", key)),
-                col(format!("{}.left", key)),
-            ])
-            .alias(key)])
-            .collect()?;
+        joined_df = joined_df.with_columns([coalesce(&[
+            col(format!("{}.right", key)),
+            col(format!("{}.left", key)),
+        ])
+        .alias(key)]);
     }
+    let diff_schema = joined_df.collect_schema()?;
+
+    // Only the changed rows are collected, the full frames are streamed through the join
+    let joined_df = tabular::collect_streaming(joined_df)?;
     log::debug!("joined_df after coalesce is {:?}", joined_df);
     let modifications = calculate_compare_mods(&joined_df)?;
 
@@ -111,19 +101,22 @@ pub fn diff(
     let _result_fields =
         prepare_response_fields(&schema_diff, keys.clone(), targets.clone(), display.clone());
 
-    let schema_diff = build_compare_schema_diff(schema_diff, df_1, df_2)?;
+    let schema_diff = build_compare_schema_diff(schema_diff, &schema_1, &schema_2)?;
 
     let dupes = TabularDiffDupes {
-        left: tabular::n_duped_rows(df_1, &[KEYS_HASH_COL])?,
-        right: tabular::n_duped_rows(df_2, &[KEYS_HASH_COL])?,
+        left: tabular::lazy_n_duped_rows(df_1, &[KEYS_HASH_COL])?,
+        right: tabular::lazy_n_duped_rows(df_2, &[KEYS_HASH_COL])?,
     };
 
     let schemas = TabularDiffSchemas {
-        left: Schema::from_polars(&df_1.schema()),
-        right: Schema::from_polars(&df_2.schema()),
-        diff: Schema::from_polars(&joined_df.schema()),
+        left: Schema::from_polars(&schema_1),
+        right: Schema::from_polars(&schema_2),
+        diff: Schema::from_polars(&diff_schema),
     };
 
+    let contents = joined_df.select(output_columns)?;
+    let modified_cells = modified_cells(&contents)?;
+
     let diff = TabularDiff {
         summary: TabularDiffSummary {
             modifications: TabularDiffMods {
@@ -133,7 +126,8 @@ pub fn diff(
             schemas,
             dupes,
         },
-        contents: joined_df.select(output_columns)?,
+        contents,
+        modified_cells,
         parameters: TabularDiffParameters {
             keys: keys.iter().map(|s| s.to_string()).collect(),
             targets: targets.iter().map(|s| s.to_string()).collect(),
@@ -145,6 +139,62 @@ pub fn diff(
     Ok(diff)
 }
 
+/// The modified rows of a diff's contents with their key columns and one column for each
+/// compared column that changed. Changed cells read `old → new` and unchanged cells are null.
+/// Keys are the columns without a `.left` or `.right` suffix, so this also works on diffs
+/// read back from the cache without their parameters.
+pub fn modified_cells(contents: &DataFrame) -> Result<DataFrame, OxenError> {
+    let is_modified = contents
+        .column(DIFF_STATUS_COL)?
+        .str()?
+        .equal(DIFF_STATUS_MODIFIED);
+    let modified = contents.filter(&is_modified)?;
+
+    let names: Vec<String> = modified
+        .get_column_names()
+        .iter()
+        .map(|name| name.to_string())
+        .collect();
+    let mut columns: Vec<Column> = vec![];
+    for name in names.iter() {
+        if name != DIFF_STATUS_COL && !name.ends_with(".left") && !name.ends_with(".right") {
+            columns.push(modified.column(name)?.clone());
+        }
+    }
+    for name in names.iter() {
+        let Some(base) = name.strip_suffix(".left") else {
+            continue;
+        };
+        let Ok(right) = modified.column(&format!("{base}.right")) else {
+            continue;
+        };
+        let left = modified.column(name)?;
+        let mut changed = false;
+        let mut cells: Vec<Option<String>> = Vec::with_capacity(modified.height());
+        for i in 0..modified.height() {
+            let before = cell_to_string(left.get(i)?);
+            let after = cell_to_string(right.get(i)?);
+            if before == after {
+                cells.push(None);
+            } else {
+                changed = true;
+                cells.push(Some(format!("{before}{CELL_CHANGE_SEPARATOR}{after}")));
+            }
+        }
+        if changed {
+            columns.push(Column::from(Series::new(PlSmallStr::from_str(base), cells)));
+        }
+    }
+    Ok(DataFrame::new(columns)?)
+}
+
+fn cell_to_string(value: AnyValue) -> String {
+    match tabular::any_val_to_json(value) {
+        serde_json::Value::String(s) => s,
+        value => value.to_string(),
+    }
+}
+
 fn sort_df_on_keys(df: DataFrame, keys: Vec<&str>) -> Result<DataFrame, OxenError> {
     let mut sort_cols = vec![];
     for key in keys.iter() {
@@ -166,35 +216,31 @@ fn sort_df_on_keys(df: DataFrame, keys: Vec<&str>) -> Result<DataFrame, OxenErro
 
 fn build_compare_schema_diff(
     schema_diff: SchemaDiff,
-    df_1: &DataFrame,
-    df_2: &DataFrame,
+    schema_1: &PolarsSchema,
+    schema_2: &PolarsSchema,
 ) -> Result<TabularSchemaDiff, OxenError> {
+    let field = |schema: &PolarsSchema, col: &String| -> Result<Field, OxenError> {
+        let dtype = schema
+            .get(col)
+            .ok_or(OxenError::basic_str(format!("Column {col} not found")))?;
+        Ok(Field {
+            name: col.clone(),
+            dtype: dtype.to_string(),
+            metadata: None,
+            changes: None,
+        })
+    };
+
     let added_cols = schema_diff
         .added_cols
         .iter()
-        .map(|col| {
-            let dtype = df_2.column(col)?;
-            Ok(Field {
-                name: col.clone(),
-                dtype: dtype.dtype().to_string(),
-                metadata: None,
-                changes: None,
-            })
-        })
+        .map(|col| field(schema_2, col))
         .collect::<Result<Vec<Field>, OxenError>>()?;
 
     let removed_cols = schema_diff
         .removed_cols
         .iter()
-        .map(|col| {
-            let dtype = df_1.column(col)?;
-            Ok(Field {
-                name: col.clone(),
-                dtype: dtype.dtype().to_string(),
-                metadata: None,
-                changes: None,
-            })
-        })
+        .map(|col| field(schema_1, col))
         .collect::<Result<Vec<Field>, OxenError>>()?;
 
     Ok(TabularSchemaDiff {
@@ -280,17 +326,18 @@ fn order_columns_by_schema<'a>(
 }
 
 fn join_hashed_dfs(
-    left_df: &DataFrame,
-    right_df: &DataFrame,
+    left_df: LazyFrame,
+    right_df: LazyFrame,
     keys: Vec<&str>,
     targets: Vec<&str>,
     schema_diff: SchemaDiff,
-) -> Result<DataFrame, OxenError> {
-    log::debug!("left_df: {:?}", left_df);
-    log::debug!("right_df: {:?}", right_df);
-
-    let mut joined_df = left_df.full_join(right_df, [KEYS_HASH_COL], [KEYS_HASH_COL])?;
-    log::debug!("joined_df: {:?}", joined_df);
+) -> Result<LazyFrame, OxenError> {
+    let mut joined_df = left_df.join(
+        right_df,
+        [col(KEYS_HASH_COL)],
+        [col(KEYS_HASH_COL)],
+        JoinArgs::new(JoinType::Full),
+    );
 
     let mut cols_to_rename = targets.clone();
     for key in keys.iter() {
@@ -307,107 +354,63 @@ fn join_hashed_dfs(
         cols_to_rename.push(TARGETS_HASH_COL);
     }
 
+    let mut renames: HashMap<String, String> = HashMap::new();
     for col in schema_diff.added_cols.iter() {
-        if joined_df.schema().contains(col) {
-            joined_df.rename(col, PlSmallStr::from_str(&format!("{}.right", col)))?;
-        }
+        renames.insert(col.to_string(), format!("{}.right", col));
     }
 
     for col in schema_diff.removed_cols.iter() {
-        if joined_df.schema().contains(col) {
-            joined_df.rename(col, PlSmallStr::from_str(&format!("{}.left", col)))?;
-        }
+        renames.insert(col.to_string(), format!("{}.left", col));
     }
 
+    // Added and removed columns keep their side for asymetric targets
     for target in cols_to_rename.iter() {
-        log::debug!("trying to rename col: {}", target);
-        let left_before = target.to_string();
-        let left_after = format!("{}.left", target);
-        let right_before = format!("{}_right", target);
-        let right_after = format!("{}.right", target);
-        // Rename conditionally for asymetric targets
-        if joined_df.schema().contains(&left_before) {
-            joined_df.rename(&left_before, PlSmallStr::from_str(&left_after))?;
-        }
-        if joined_df.schema().contains(&right_before) {
-            joined_df.rename(&right_before, PlSmallStr::from_str(&right_after))?;
-        }
+        renames
+            .entry(target.to_string())
+            .or_insert_with(|| format!("{}.left", target));
+        renames
+            .entry(format!("{}_right", target))
+            .or_insert_with(|| format!("{}.right", target));
     }
 
-    Ok(joined_df)
+    let joined_schema = joined_df.collect_schema()?;
+    let columns = joined_schema
+        .iter_names()
+        .map(|name| match renames.get(name.as_str()) {
+            Some(renamed) => col(name.clone()).alias(renamed.as_str()),
+            None => col(name.clone()),
+        })
+        .collect::<Vec<Expr>>();
+
+    Ok(joined_df.select(columns))
 }
 
 fn add_diff_status_column(
-    joined_df: DataFrame,
+    joined_df: LazyFrame,
     keys: Vec<&str>,
     targets: Vec<&str>,
-) -> Result<DataFrame, OxenError> {
-    // Columns required for determining group membership in the closure
-    let col_names = [
-        format!("{}.left", keys[0]),
-        format!("{}.right", keys[0]),
-        format!("{}.left", TARGETS_HASH_COL),
-        format!("{}.right", TARGETS_HASH_COL),
-    ];
-
-    let mut field_names = vec![];
-    for col_name in &col_names {
-        if joined_df
-            .schema()
-            .iter_fields()
-            .any(|field| field.name() == col_name)
-        {
-            field_names.push(col(col_name));
-        }
-    }
+) -> Result<LazyFrame, OxenError> {
+    // A row is added or removed when its key is missing from one side, and modified when
+    // the hashes of its targets differ. A target missing from one side counts as a change.
+    let key_left = col(format!("{}.left", keys[0]));
+    let key_right = col(format!("{}.right", keys[0]));
+    let is_modified = if targets.is_empty() {
+        lit(false)
+    } else {
+        col(format!("{}.left", TARGETS_HASH_COL))
+            .neq_missing(col(format!("{}.right", TARGETS_HASH_COL)))
+    };
 
-    // For pulling into the closure
-    let has_targets = !targets.is_empty();
-    let joined_df = joined_df
-        .lazy()
-        .select([
-            all(),
-            as_struct(field_names)
-                .apply(
-                    move |s| {
-                        let ca = s.struct_()?;
-                        let s_a = &ca.fields_as_series();
-
-                        let num_rows = s_a[0].len();
-                        let num_columns = s_a.len();
-                        let mut results = vec![];
-                        for i in 0..num_rows {
-                            // log::debug!("row: {:?}", i);
-                            let mut row = vec![];
-                            for j_elem in s_a.iter().take(num_columns) {
-                                let elem = j_elem.get(i).unwrap();
-                                // log::debug!("  elem: {:?}", elem);
-                                row.push(elem);
-                            }
-                            let key_left = row.first();
-                            let key_right = row.get(1);
-                            let target_hash_left = row.get(2);
-                            let target_hash_right = row.get(3);
-
-                            results.push(test_function(
-                                key_left,
-                                key_right,
-                                target_hash_left,
-                                target_hash_right,
-                                has_targets,
-                            ));
-                        }
-                        Ok(Some(Column::Series(
-                            Series::new(PlSmallStr::from_str(""), results).into(),
-                        )))
-                    },
-                    GetOutput::from_type(polars::prelude::DataType::String),
-                )
-                .alias(DIFF_STATUS_COL),
-        ])
-        .collect()?;
+    let status = when(key_left.is_null())
+        .then(lit(DIFF_STATUS_ADDED))
+        .when(key_right.is_null())
+        .then(lit(DIFF_STATUS_REMOVED))
+        .when(is_modified)
+        .then(lit(DIFF_STATUS_MODIFIED))
+        .otherwise(lit(DIFF_STATUS_UNCHANGED))
+        .alias(DIFF_STATUS_COL);
 
-    Ok(joined_df)
+    Ok(joined_df.with_column(status))
 }
 
 fn calculate_compare_mods(joined_df: &DataFrame) -> Result<AddRemoveModifyCounts, OxenError> {
@@ -434,35 +437,6 @@ fn calculate_compare_mods(joined_df: &DataFrame) -> Result<AddRemoveModifyCounts
     })
 }
 
-fn test_function(
-    key_left: Option<&AnyValue>,
-    key_right: Option<&AnyValue>,
-    target_hash_left: Option<&AnyValue>,
-    target_hash_right: Option<&AnyValue>,
-    has_targets: bool,
-) -> String {
-    // TODO better error handling
-    if let Some(AnyValue::Null) = key_left {
-        return DIFF_STATUS_ADDED.to_string();
-    }
-
-    if let Some(AnyValue::Null) = key_right {
-        return DIFF_STATUS_REMOVED.to_string();
-    }
-
-    if !has_targets {
-        return DIFF_STATUS_UNCHANGED.to_string();
-    }
-    if let Some(target_hash_left) = target_hash_left {
-        if let Some(target_hash_right) = target_hash_right {
-            if target_hash_left != target_hash_right {
-                return DIFF_STATUS_MODIFIED.to_string();
-            }
-        }
-    }
-    DIFF_STATUS_UNCHANGED.to_string()
-}
-
 fn prepare_response_fields(
     schema_diff: &SchemaDiff,
     keys: Vec<&str>,
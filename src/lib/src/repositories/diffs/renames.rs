@@ -0,0 +1,71 @@
+//! Find the files that were moved between two trees
+//!
+//! A file counts as renamed when a path that was removed and a path that was added hold
+//! the same contents. Empty files all share a hash, so they are never paired up.
+//!
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::error::OxenError;
+use crate::model::merkle_tree::node::FileNode;
+use crate::model::{Commit, LocalRepository, MerkleHash};
+use crate::repositories;
+use crate::repositories::diffs::diff_stat;
+
+/// Pair the files removed from `base_files` with the files added in `head_files` that have
+/// the same hash, as `(old_path, new_path)`. When several removed files match, the one
+/// with the same file name wins, so moving one of many copies to another dir keeps its
+/// name.
+pub fn find_renames(
+    base_files: &HashMap<PathBuf, FileNode>,
+    head_files: &HashMap<PathBuf, FileNode>,
+) -> Vec<(PathBuf, PathBuf)> {
+    let mut removed_by_hash: HashMap<MerkleHash, Vec<&PathBuf>> = HashMap::new();
+    for (path, node) in base_files {
+        if node.num_bytes() > 0 && !head_files.contains_key(path) {
+            removed_by_hash.entry(*node.hash()).or_default().push(path);
+        }
+    }
+    for paths in removed_by_hash.values_mut() {
+        paths.sort();
+    }
+
+    let mut added: Vec<(&PathBuf, &FileNode)> = head_files
+        .iter()
+        .filter(|(path, node)| node.num_bytes() > 0 && !base_files.contains_key(*path))
+        .collect();
+    added.sort_by(|a, b| a.0.cmp(b.0));
+
+    let mut renames = vec![];
+    for (new_path, node) in added {
+        let Some(candidates) = removed_by_hash.get_mut(node.hash()) else {
+            continue;
+        };
+        if candidates.is_empty() {
+            continue;
+        }
+        let index = candidates
+            .iter()
+            .position(|old_path| old_path.file_name() == new_path.file_name())
+            .unwrap_or(0);
+        let old_path = candidates.remove(index);
+        renames.push((old_path.clone(), new_path.clone()));
+    }
+    renames
+}
+
+/// The files a commit moved compared to its first parent, as `(old_path, new_path)`
+pub fn commit_renames(
+    repo: &LocalRepository,
+    commit: &Commit,
+) -> Result<Vec<(PathBuf, PathBuf)>, OxenError> {
+    let Some(parent_id) = commit.parent_ids.first() else {
+        return Ok(vec![]);
+    };
+    let parent = repositories::commits::get_by_id(repo, parent_id)?
+        .ok_or(OxenError::commit_id_does_not_exist(parent_id))?;
+    let base_files = diff_stat::list_files(repo, &parent, Path::new(""))?;
+    let head_files = diff_stat::list_files(repo, commit, Path::new(""))?;
+    Ok(find_renames(&base_files, &head_files))
+}
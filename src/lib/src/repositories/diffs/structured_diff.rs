@@ -0,0 +1,203 @@
+//! Diff JSON and YAML documents by key
+//!
+//! Both sides are parsed and walked together, objects by key and arrays by index, so
+//! reformatting a file or reordering its keys is not reported as a change.
+//!
+
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::error::OxenError;
+use crate::model::diff::structured_diff::{KeyDiff, StructuredDiff, StructuredFormat};
+use crate::model::diff::ChangeType;
+use crate::util;
+
+/// The structured format of a file from its extension, if it has one
+pub fn format_from_path(path: impl AsRef<Path>) -> Option<StructuredFormat> {
+    let extension = path.as_ref().extension()?.to_str()?.to_lowercase();
+    match extension.as_str() {
+        "json" => Some(StructuredFormat::Json),
+        "yaml" | "yml" => Some(StructuredFormat::Yaml),
+        _ => None,
+    }
+}
+
+/// Parse both files as `format` and list the keys that differ. Fails if either file does
+/// not parse, so callers can fall back to a line diff.
+pub fn diff(
+    path_1: impl AsRef<Path>,
+    path_2: impl AsRef<Path>,
+    format: StructuredFormat,
+) -> Result<StructuredDiff, OxenError> {
+    let before = parse(path_1.as_ref(), format)?;
+    let after = parse(path_2.as_ref(), format)?;
+    Ok(StructuredDiff {
+        filename1: None,
+        filename2: None,
+        format,
+        keys: diff_values(&before, &after),
+    })
+}
+
+fn parse(path: &Path, format: StructuredFormat) -> Result<Value, OxenError> {
+    let contents = util::fs::read_from_path(path)?;
+    match format {
+        StructuredFormat::Json => Ok(serde_json::from_str(&contents)?),
+        StructuredFormat::Yaml => serde_yaml::from_str(&contents).map_err(|err| {
+            OxenError::basic_str(format!("Could not parse {path:?} as yaml: {err}"))
+        }),
+    }
+}
+
+/// The changed keys between two documents. Documents that aren't both objects or both
+/// arrays are reported as a single modified entry with an empty key.
+pub fn diff_values(before: &Value, after: &Value) -> Vec<KeyDiff> {
+    if before == after {
+        return vec![];
+    }
+    match diff_children(before, after) {
+        Some(children) => children,
+        None => diff_key(String::new(), before, after).into_iter().collect(),
+    }
+}
+
+fn diff_children(before: &Value, after: &Value) -> Option<Vec<KeyDiff>> {
+    let mut diffs = vec![];
+    match (before, after) {
+        (Value::Object(before), Value::Object(after)) => {
+            for (key, before_value) in before {
+                match after.get(key) {
+                    Some(after_value) => {
+                        diffs.extend(diff_key(key.clone(), before_value, after_value))
+                    }
+                    None => diffs.push(removed(key.clone(), before_value)),
+                }
+            }
+            for (key, after_value) in after {
+                if !before.contains_key(key) {
+                    diffs.push(added(key.clone(), after_value));
+                }
+            }
+        }
+        (Value::Array(before), Value::Array(after)) => {
+            for i in 0..before.len().max(after.len()) {
+                match (before.get(i), after.get(i)) {
+                    (Some(before_value), Some(after_value)) => {
+                        diffs.extend(diff_key(i.to_string(), before_value, after_value))
+                    }
+                    (Some(before_value), None) => diffs.push(removed(i.to_string(), before_value)),
+                    (None, Some(after_value)) => diffs.push(added(i.to_string(), after_value)),
+                    (None, None) => {}
+                }
+            }
+        }
+        _ => return None,
+    }
+    Some(diffs)
+}
+
+fn diff_key(key: String, before: &Value, after: &Value) -> Option<KeyDiff> {
+    if before == after {
+        return None;
+    }
+    let diff = match diff_children(before, after) {
+        Some(children) => KeyDiff {
+            key,
+            modification: ChangeType::Modified,
+            before: None,
+            after: None,
+            children,
+        },
+        None => KeyDiff {
+            key,
+            modification: ChangeType::Modified,
+            before: Some(before.clone()),
+            after: Some(after.clone()),
+            children: vec![],
+        },
+    };
+    Some(diff)
+}
+
+fn added(key: String, value: &Value) -> KeyDiff {
+    KeyDiff {
+        key,
+        modification: ChangeType::Added,
+        before: None,
+        after: Some(value.clone()),
+        children: vec![],
+    }
+}
+
+fn removed(key: String, value: &Value) -> KeyDiff {
+    KeyDiff {
+        key,
+        modification: ChangeType::Removed,
+        before: Some(value.clone()),
+        after: None,
+        children: vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::error::OxenError;
+    use crate::model::diff::structured_diff::StructuredFormat;
+    use crate::model::diff::ChangeType;
+    use crate::repositories::diffs::structured_diff;
+    use crate::test;
+    use crate::util;
+
+    #[test]
+    fn test_structured_diff_reports_changed_keys_as_tree() -> Result<(), OxenError> {
+        test::run_empty_dir_test(|dir| {
+            let path_1 = dir.join("config.yaml");
+            let path_2 = dir.join("config_2.yaml");
+            util::fs::write_to_path(
+                &path_1,
+                "model:\n  name: resnet\n  layers: 50\nlr: 0.1\ntags: [a, b]\n",
+            )?;
+            // Reordered keys are not a change
+            util::fs::write_to_path(
+                &path_2,
+                "lr: 0.1\nmodel:\n  layers: 101\n  name: resnet\n  dropout: 0.2\ntags: [a]\n",
+            )?;
+
+            let diff = structured_diff::diff(&path_1, &path_2, StructuredFormat::Yaml)?;
+            assert_eq!(diff.keys.len(), 2);
+            assert_eq!(diff.num_changes(), 3);
+
+            let model = &diff.keys[0];
+            assert_eq!(model.key, "model");
+            assert_eq!(model.modification, ChangeType::Modified);
+            assert!(model.before.is_none());
+            let layers = model.children.iter().find(|k| k.key == "layers").unwrap();
+            assert_eq!(layers.before, Some(json!(50)));
+            assert_eq!(layers.after, Some(json!(101)));
+            let dropout = model.children.iter().find(|k| k.key == "dropout").unwrap();
+            assert_eq!(dropout.modification, ChangeType::Added);
+
+            let tags = &diff.keys[1];
+            assert_eq!(tags.key, "tags");
+            assert_eq!(tags.children[0].key, "1");
+            assert_eq!(tags.children[0].modification, ChangeType::Removed);
+            assert_eq!(tags.children[0].before, Some(json!("b")));
+
+            // Same document in different formatting
+            let path_3 = dir.join("config.json");
+            util::fs::write_to_path(&path_3, "{\"a\": [1, 2]}")?;
+            let path_4 = dir.join("config_2.json");
+            util::fs::write_to_path(&path_4, "{\n  \"a\": [\n    1,\n    2\n  ]\n}\n")?;
+            let diff = structured_diff::diff(&path_3, &path_4, StructuredFormat::Json)?;
+            assert!(diff.keys.is_empty());
+
+            assert!(structured_diff::format_from_path("a/b.yml").is_some());
+            assert!(structured_diff::format_from_path("a/b.txt").is_none());
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,245 @@
+//! # Embeddings
+//!
+//! Find similar files in a revision by the embeddings stored alongside them. The
+//! embeddings live in a vector column of a data frame, either next to the annotations or
+//! in a sidecar parquet with a column naming each file. The nearest neighbor index for a
+//! column is built the first time it is queried and cached by the hash of the data frame,
+//! so every revision that shares the file shares the index.
+//!
+
+use std::path::PathBuf;
+
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::constants::CACHE_DIR;
+use crate::core::df::tabular;
+use crate::core::embeddings::{EmbeddingIndex, DEFAULT_NPROBE};
+use crate::error::OxenError;
+use crate::model::{Commit, LocalRepository};
+use crate::opts::{DFOpts, EmbeddingNeighborsOpts};
+use crate::repositories;
+use crate::util;
+use crate::util::hasher;
+
+const EMBEDDINGS_CACHE_DIR: &str = "embeddings";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EmbeddingNeighbor {
+    /// Row of the data frame the embedding is in
+    pub row: usize,
+    /// Value of the file column in that row, if the data frame has one
+    pub file: Option<String>,
+    /// Cosine similarity to the query, 1.0 is identical
+    pub similarity: f32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EmbeddingNeighbors {
+    pub commit_id: String,
+    pub path: PathBuf,
+    pub column: String,
+    /// The most similar rows first
+    pub neighbors: Vec<EmbeddingNeighbor>,
+}
+
+/// Find the nearest neighbors of a file or embedding in the data frame at `revision`
+pub fn neighbors(
+    repo: &LocalRepository,
+    revision: impl AsRef<str>,
+    opts: &EmbeddingNeighborsOpts,
+) -> Result<EmbeddingNeighbors, OxenError> {
+    let revision = revision.as_ref();
+    let commit = repositories::revisions::get(repo, revision)?
+        .ok_or(OxenError::revision_not_found(revision.into()))?;
+    neighbors_for_commit(repo, &commit, opts)
+}
+
+pub fn neighbors_for_commit(
+    repo: &LocalRepository,
+    commit: &Commit,
+    opts: &EmbeddingNeighborsOpts,
+) -> Result<EmbeddingNeighbors, OxenError> {
+    opts.validate()?;
+    let path = &opts.path;
+    let file_node = repositories::tree::get_file_by_path(repo, commit, path)?
+        .ok_or(OxenError::path_does_not_exist(path))?;
+
+    let version_store = repo.version_store()?;
+    let version_path = version_store.get_version_path(&file_node.hash().to_string())?;
+    let df =
+        tabular::read_df_with_extension(version_path, file_node.extension(), &DFOpts::empty())?;
+    let files = file_column(&df, opts.file_column())?;
+
+    let index_path = index_path(repo, &file_node.hash().to_string(), &opts.column);
+    let index = if index_path.exists() {
+        EmbeddingIndex::load(&index_path)?
+    } else {
+        log::debug!("Building embedding index for {:?} {:?}", path, opts.column);
+        let index = EmbeddingIndex::build(column_embeddings(&df, &opts.column)?)?;
+        index.save(&index_path)?;
+        index
+    };
+
+    let (query, exclude) = match (&opts.file, &opts.embedding) {
+        (Some(file), _) => {
+            let Some(files) = &files else {
+                return Err(OxenError::column_name_not_found(opts.file_column()));
+            };
+            let row = files
+                .iter()
+                .position(|f| f.as_deref() == Some(file.as_str()))
+                .ok_or_else(|| {
+                    OxenError::basic_str(format!("File {file:?} not found in {path:?}"))
+                })?;
+            let query = index.vector(row).ok_or_else(|| {
+                OxenError::basic_str(format!(
+                    "File {file:?} has no embedding in column {:?}",
+                    opts.column
+                ))
+            })?;
+            (query.to_vec(), Some(row))
+        }
+        (None, Some(embedding)) => (embedding.clone(), None),
+        (None, None) => unreachable!("validated above"),
+    };
+
+    let nprobe = opts.nprobe.unwrap_or(DEFAULT_NPROBE);
+    let neighbors = index
+        .search(&query, opts.k(), nprobe, exclude)?
+        .into_iter()
+        .map(|(row, similarity)| EmbeddingNeighbor {
+            row,
+            file: files.as_ref().and_then(|files| files[row].clone()),
+            similarity,
+        })
+        .collect();
+
+    Ok(EmbeddingNeighbors {
+        commit_id: commit.id.clone(),
+        path: path.clone(),
+        column: opts.column.clone(),
+        neighbors,
+    })
+}
+
+fn index_path(repo: &LocalRepository, file_hash: &str, column: &str) -> PathBuf {
+    util::fs::oxen_hidden_dir(&repo.path)
+        .join(CACHE_DIR)
+        .join(EMBEDDINGS_CACHE_DIR)
+        .join(file_hash)
+        .join(format!("{}.idx", hasher::hash_str(column)))
+}
+
+/// The values of the file column as strings, None if the data frame does not have one
+fn file_column(df: &DataFrame, column: &str) -> Result<Option<Vec<Option<String>>>, OxenError> {
+    let Ok(column) = df.column(column) else {
+        return Ok(None);
+    };
+    let column = column.cast(&DataType::String)?;
+    Ok(Some(
+        column
+            .str()?
+            .into_iter()
+            .map(|value| value.map(String::from))
+            .collect(),
+    ))
+}
+
+/// The `(row, vector)` pairs of an embedding column, skipping rows without a full vector
+fn column_embeddings(df: &DataFrame, column: &str) -> Result<Vec<(usize, Vec<f32>)>, OxenError> {
+    let column = df
+        .column(column)
+        .map_err(|_| OxenError::column_name_not_found(column))?;
+
+    let mut embeddings = vec![];
+    match column.dtype() {
+        DataType::String => {
+            for (row, value) in column.str()?.into_iter().enumerate() {
+                if let Some(value) = value {
+                    let vector: Vec<f32> = serde_json::from_str(value)?;
+                    embeddings.push((row, vector));
+                }
+            }
+        }
+        DataType::List(_) | DataType::Array(_, _) => {
+            let column = column.cast(&DataType::List(Box::new(DataType::Float32)))?;
+            for (row, value) in column.list()?.into_iter().enumerate() {
+                let Some(value) = value else {
+                    continue;
+                };
+                let value = value.f32()?;
+                if value.null_count() == 0 {
+                    embeddings.push((row, value.into_no_null_iter().collect()));
+                }
+            }
+        }
+        dtype => {
+            return Err(OxenError::basic_str(format!(
+                "Embedding column {:?} must be a list of floats, not {dtype}",
+                column.name()
+            )))
+        }
+    }
+    Ok(embeddings)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::error::OxenError;
+    use crate::opts::EmbeddingNeighborsOpts;
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    #[tokio::test]
+    async fn test_embedding_neighbors_of_file_at_revision() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            let path = repo.path.join("embeddings.jsonl");
+            util::fs::write_to_path(
+                &path,
+                r#"{"file": "images/cat_1.jpg", "embedding": [1.0, 0.1, 0.0]}
+{"file": "images/dog_1.jpg", "embedding": [0.0, 1.0, 0.1]}
+{"file": "images/cat_2.jpg", "embedding": [0.9, 0.2, 0.0]}
+{"file": "images/dog_2.jpg", "embedding": [0.1, 0.9, 0.0]}
+"#,
+            )?;
+            repositories::add(&repo, &path).await?;
+            let commit = repositories::commit(&repo, "Adding embeddings")?;
+
+            let mut opts = EmbeddingNeighborsOpts {
+                path: PathBuf::from("embeddings.jsonl"),
+                column: "embedding".to_string(),
+                file_column: None,
+                file: Some("images/cat_1.jpg".to_string()),
+                embedding: None,
+                k: Some(2),
+                nprobe: None,
+            };
+            let result = repositories::embeddings::neighbors(&repo, &commit.id, &opts)?;
+            assert_eq!(result.commit_id, commit.id);
+            assert_eq!(result.neighbors.len(), 2);
+            assert_eq!(result.neighbors[0].row, 2);
+            assert_eq!(
+                result.neighbors[0].file,
+                Some("images/cat_2.jpg".to_string())
+            );
+
+            // Query by embedding, using the cached index
+            opts.file = None;
+            opts.embedding = Some(vec![0.0, 1.0, 0.0]);
+            let result = repositories::embeddings::neighbors(&repo, "HEAD", &opts)?;
+            assert_eq!(
+                result.neighbors[0].file,
+                Some("images/dog_1.jpg".to_string())
+            );
+
+            opts.column = "missing".to_string();
+            assert!(repositories::embeddings::neighbors(&repo, "HEAD", &opts).is_err());
+            Ok(())
+        })
+        .await
+    }
+}
@@ -12,7 +12,7 @@ use rayon::prelude::*;
 
 use crate::constants::ROOT_PATH;
 use crate::model::{
-    Commit, CommitEntry, LocalRepository, MetadataEntry, ParsedResource, Workspace,
+    Commit, CommitEntry, LocalRepository, MetadataEntry, MetadataFilter, ParsedResource, Workspace,
 };
 use crate::view::PaginatedDirEntries;
 use std::collections::HashMap;
@@ -88,17 +88,38 @@ pub fn list_directory_w_version(
                 repo,
                 directory,
                 &parsed_resource,
+                None,
                 paginate_opts,
             )
         }
     }
 }
 
+/// List the entries in a directory whose metadata matches the filter, ie: `image.width>1024`
+pub fn list_directory_w_filter(
+    repo: &LocalRepository,
+    directory: impl AsRef<Path>,
+    revision: impl AsRef<str>,
+    filter: &MetadataFilter,
+    paginate_opts: &PaginateOpts,
+) -> Result<PaginatedDirEntries, OxenError> {
+    list_directory_w_workspace(
+        repo,
+        directory,
+        revision,
+        None,
+        Some(filter),
+        paginate_opts,
+        repo.min_version(),
+    )
+}
+
 pub fn list_directory_w_workspace(
     repo: &LocalRepository,
     directory: impl AsRef<Path>,
     revision: impl AsRef<str>,
     workspace: Option<Workspace>,
+    filter: Option<&MetadataFilter>,
     paginate_opts: &PaginateOpts,
     version: MinOxenVersion,
 ) -> Result<PaginatedDirEntries, OxenError> {
@@ -126,6 +147,7 @@ pub fn list_directory_w_workspace(
                 repo,
                 directory,
                 &parsed_resource,
+                filter,
                 paginate_opts,
             )
         }
@@ -304,6 +326,7 @@ mod tests {
     use uuid::Uuid;
 
     use crate::error::OxenError;
+    use crate::model::MetadataFilter;
     use crate::opts::PaginateOpts;
     use crate::repositories;
     use crate::test;
@@ -956,6 +979,111 @@ mod tests {
         .await
     }
 
+    #[tokio::test]
+    async fn test_list_directory_pages_are_stable_across_vnodes() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|mut repo| async move {
+            // Spread the entries of the root dir over several vnodes
+            repo.set_vnode_size(4);
+            let num_dirs = 5;
+            for n in 0..num_dirs {
+                let dir_path = repo.path.join(format!("dir_{n}"));
+                util::fs::create_dir_all(&dir_path)?;
+                util::fs::write(dir_path.join("data.txt"), format!("Hi {n}"))?;
+            }
+            let num_files = 12;
+            for n in 0..num_files {
+                let filepath = repo.path.join(format!("file_{n:0>2}.txt"));
+                util::fs::write(filepath, format!("hello {n}"))?;
+            }
+            repositories::add(&repo, &repo.path).await?;
+            let commit = repositories::commit(&repo, "Adding all the data")?;
+
+            let page_size = 5;
+            let mut listed: Vec<String> = vec![];
+            for page_num in 1..=4 {
+                let paginated = repositories::entries::list_directory(
+                    &repo,
+                    Path::new(""),
+                    &commit.id,
+                    &PaginateOpts {
+                        page_num,
+                        page_size,
+                    },
+                )?;
+                assert_eq!(paginated.total_entries, num_dirs + num_files);
+                assert_eq!(paginated.total_pages, 4);
+                listed.extend(
+                    paginated
+                        .entries
+                        .iter()
+                        .map(|entry| entry.filename().to_string()),
+                );
+            }
+
+            // Dirs first, then files, each sorted by name
+            let expected: Vec<String> = (0..num_dirs)
+                .map(|n| format!("dir_{n}"))
+                .chain((0..num_files).map(|n| format!("file_{n:0>2}.txt")))
+                .collect();
+            assert_eq!(listed, expected);
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_list_directory_w_filter_matches_node_metadata() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|mut repo| async move {
+            repo.set_vnode_size(2);
+            util::fs::create_dir_all(repo.path.join("images"))?;
+            util::fs::copy(
+                test::test_img_file_with_name("cat_1.jpg"),
+                repo.path.join("cat_1.jpg"),
+            )?;
+            util::fs::copy(
+                test::test_img_file_with_name("mnist_7.png"),
+                repo.path.join("mnist_7.png"),
+            )?;
+            util::fs::write(repo.path.join("README.md"), "# Images")?;
+            util::fs::write(repo.path.join("images").join("notes.txt"), "hi")?;
+            repositories::add(&repo, &repo.path).await?;
+            let commit = repositories::commit(&repo, "Adding images")?;
+
+            let list = |filter: &str, page_size: usize| {
+                repositories::entries::list_directory_w_filter(
+                    &repo,
+                    Path::new(""),
+                    &commit.id,
+                    &MetadataFilter::parse(filter)?,
+                    &PaginateOpts {
+                        page_num: 1,
+                        page_size,
+                    },
+                )
+            };
+
+            let images = list("data_type=image", 1)?;
+            assert_eq!(images.total_entries, 2);
+            assert_eq!(images.total_pages, 2);
+            assert_eq!(images.entries[0].filename(), "cat_1.jpg");
+
+            let large = list("data_type=image,image.width>100", 10)?;
+            assert_eq!(large.total_entries, 1);
+            assert_eq!(large.entries[0].filename(), "cat_1.jpg");
+
+            let dirs = list("data_type=dir", 10)?;
+            assert_eq!(dirs.total_entries, 1);
+            assert_eq!(dirs.entries[0].filename(), "images");
+
+            let none = list("image.height>=10000", 10)?;
+            assert_eq!(none.total_entries, 0);
+
+            Ok(())
+        })
+        .await
+    }
+
     #[tokio::test]
     async fn test_file_metadata_shows_is_indexed() -> Result<(), OxenError> {
         // skip on windows
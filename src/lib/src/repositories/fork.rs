@@ -1,24 +1,37 @@
+use crate::constants::{ACTIVITY_FILE, HOOKS_FILE, OXEN_HIDDEN_DIR, VERSIONS_DIR};
 use crate::error::OxenError;
+use crate::model::{ActivityEvent, LocalRepository};
+use crate::opts::{ForkMode, ForkOpts};
+use crate::repositories;
 use crate::util::fs as oxen_fs;
 use crate::view::fork::{ForkStartResponse, ForkStatus, ForkStatusFile, ForkStatusResponse};
+use std::collections::HashSet;
 use std::fs;
+use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::thread;
 use toml;
 
 pub const FORK_STATUS_FILE: &str = ".oxen/fork_status.toml";
+/// The files a fork finished copying, one path per line, so a resumed fork can skip them
+pub const FORK_JOURNAL_FILE: &str = ".oxen/fork_journal";
+/// Asks a running fork to stop after the file it is copying
+pub const FORK_CANCEL_FILE: &str = ".oxen/fork_cancel";
 
 fn write_status(repo_path: &Path, status: &ForkStatus) -> Result<(), OxenError> {
+    write_status_file(repo_path, &status.clone().into())
+}
+
+fn write_status_file(repo_path: &Path, status_file: &ForkStatusFile) -> Result<(), OxenError> {
     let status_path = repo_path.join(FORK_STATUS_FILE);
     if let Some(parent) = status_path.parent() {
         oxen_fs::create_dir_all(parent)?;
     }
-    let status_file: ForkStatusFile = status.clone().into();
-    fs::write(status_path, toml::to_string(&status_file)?)?;
+    fs::write(status_path, toml::to_string(status_file)?)?;
     Ok(())
 }
 
-fn read_status(repo_path: &Path) -> Result<Option<ForkStatus>, OxenError> {
+fn read_status_file(repo_path: &Path) -> Result<Option<ForkStatusFile>, OxenError> {
     let status_path = repo_path.join(FORK_STATUS_FILE);
     if !status_path.exists() {
         return Ok(None);
@@ -33,6 +46,13 @@ fn read_status(repo_path: &Path) -> Result<Option<ForkStatus>, OxenError> {
         );
         OxenError::basic_str(format!("Failed to parse fork status on file: {}", e))
     })?;
+    Ok(Some(status_file))
+}
+
+fn read_status(repo_path: &Path) -> Result<Option<ForkStatus>, OxenError> {
+    let Some(status_file) = read_status_file(repo_path)? else {
+        return Ok(None);
+    };
 
     let status = &status_file.status;
 
@@ -40,6 +60,7 @@ fn read_status(repo_path: &Path) -> Result<Option<ForkStatus>, OxenError> {
         ForkStatus::Started => ForkStatus::Started,
         ForkStatus::InProgress(_) => ForkStatus::InProgress(status_file.progress.unwrap_or(0.0)),
         ForkStatus::Complete => ForkStatus::Complete,
+        ForkStatus::Cancelled => ForkStatus::Cancelled,
         ForkStatus::Counting(_) => ForkStatus::Counting(status_file.progress.unwrap_or(0.0) as u32),
         ForkStatus::Failed(_) => ForkStatus::Failed(
             status_file
@@ -49,62 +70,126 @@ fn read_status(repo_path: &Path) -> Result<Option<ForkStatus>, OxenError> {
     }))
 }
 
+/// Fork the repository at `original_path` into `new_path` in a background thread, poll
+/// `get_fork_status` to follow it. With `opts.resume` an unfinished fork into `new_path`
+/// continues where it stopped, make sure it is not still running first.
 pub fn start_fork(
     original_path: PathBuf,
     new_path: PathBuf,
+    opts: &ForkOpts,
 ) -> Result<ForkStartResponse, OxenError> {
-    if new_path.exists() {
-        return Err(OxenError::basic_str(format!(
-            "A file already exists at the destination path: {}",
-            new_path.to_string_lossy()
-        )));
+    if opts.resume {
+        match read_status(&new_path)? {
+            None => return Err(OxenError::fork_status_not_found()),
+            Some(ForkStatus::Complete) => {
+                return Err(OxenError::basic_str(format!(
+                    "The fork into {} is already complete",
+                    new_path.to_string_lossy()
+                )));
+            }
+            Some(_) => {}
+        }
+    } else if new_path.exists() {
+        return Err(OxenError::repo_already_exists_at_destination(
+            format!(
+                "A file already exists at the destination path: {}",
+                new_path.to_string_lossy()
+            )
+            .into(),
+        ));
     }
 
     oxen_fs::create_dir_all(&new_path)?;
+    // A cancel meant for the run being resumed would stop this one right away
+    let cancel_path = new_path.join(FORK_CANCEL_FILE);
+    if cancel_path.exists() {
+        oxen_fs::remove_file(&cancel_path)?;
+    }
     write_status(&new_path, &ForkStatus::Counting(0))?;
 
     let new_path_clone = new_path.clone();
-    let mut current_count = 0;
+    let mode = opts.mode;
 
     thread::spawn(move || {
-        let total_items = match count_items(&original_path, &new_path, &mut current_count) {
-            Ok(count) => count as f32,
+        let status = match run_fork(&original_path, &new_path, mode) {
+            Ok(status) => status,
             Err(e) => {
-                log::error!("Failed to count items: {}", e);
-                write_status(&new_path, &ForkStatus::Failed(e.to_string())).unwrap_or_else(|e| {
-                    log::error!("Failed to write error status: {}", e);
-                });
-                return;
+                log::error!("Failed to fork {:?}: {}", original_path, e);
+                ForkStatus::Failed(e.to_string())
             }
         };
-        let mut copied_items = 0.0;
-        match copy_dir_recursive(
-            &original_path,
-            &new_path,
-            &new_path,
-            total_items,
-            &mut copied_items,
-        ) {
-            Ok(()) => {
-                write_status(&new_path, &ForkStatus::Complete).unwrap_or_else(|e| {
-                    log::error!("Failed to write completion status: {}", e);
-                });
-            }
-            Err(e) => {
-                write_status(&new_path, &ForkStatus::Failed(e.to_string())).unwrap_or_else(|e| {
-                    log::error!("Failed to write error status: {}", e);
-                });
-            }
+        if matches!(status, ForkStatus::Complete) {
+            record_fork(&original_path, &new_path).unwrap_or_else(|e| {
+                log::error!("Failed to record the fork of {:?}: {}", original_path, e);
+            });
         }
+        write_status(&new_path, &status).unwrap_or_else(|e| {
+            log::error!("Failed to write {} status: {}", status, e);
+        });
     });
 
     Ok(ForkStartResponse {
         repository: new_path_clone.to_string_lossy().to_string(),
         fork_status: ForkStatus::Started.to_string(),
+        status_url: None,
     })
 }
 
+/// Add the fork to the original repository's activity once it completed
+fn record_fork(original_path: &Path, new_path: &Path) -> Result<(), OxenError> {
+    let name = new_path.file_name().unwrap_or_default().to_string_lossy();
+    let namespace = new_path
+        .parent()
+        .and_then(|parent| parent.file_name())
+        .unwrap_or_default()
+        .to_string_lossy();
+    let repo = LocalRepository::from_dir(original_path)?;
+    repositories::activity::record(&repo, &ActivityEvent::fork(namespace, name))
+}
+
+/// The path to fork into for a namespace and repository name within the sync dir. Both
+/// must be a single directory name, so the fork can't land outside of the sync dir.
+pub fn destination_path(
+    sync_dir: &Path,
+    namespace: &str,
+    name: &str,
+) -> Result<PathBuf, OxenError> {
+    for part in [namespace, name] {
+        let mut components = Path::new(part).components();
+        let is_single_dir = matches!(
+            (components.next(), components.next()),
+            (Some(std::path::Component::Normal(_)), None)
+        );
+        if !is_single_dir || part.starts_with('.') {
+            return Err(OxenError::basic_str(format!(
+                "Invalid namespace or repository name for a fork: {part:?}"
+            )));
+        }
+    }
+    Ok(sync_dir.join(namespace).join(name))
+}
+
+/// Ask the fork into `repo_path` to stop. It stops after the file it is copying, and can be
+/// resumed later with `ForkOpts::resume`.
+pub fn cancel_fork(repo_path: &Path) -> Result<ForkStatusResponse, OxenError> {
+    let status = read_status(repo_path)?.ok_or_else(OxenError::fork_status_not_found)?;
+    if matches!(
+        status,
+        ForkStatus::Complete | ForkStatus::Cancelled | ForkStatus::Failed(_)
+    ) {
+        return Err(OxenError::basic_str(format!(
+            "The fork into {} is not running, it is {}",
+            repo_path.to_string_lossy(),
+            status
+        )));
+    }
+    oxen_fs::write_to_path(repo_path.join(FORK_CANCEL_FILE), "")?;
+    get_fork_status(repo_path)
+}
+
 pub fn get_fork_status(repo_path: &Path) -> Result<ForkStatusResponse, OxenError> {
+    let status_file = read_status_file(repo_path)?.ok_or_else(OxenError::fork_status_not_found)?;
+    let (copied_bytes, total_bytes) = (status_file.copied_bytes, status_file.total_bytes);
     let status = read_status(repo_path)?.ok_or_else(OxenError::fork_status_not_found)?;
 
     Ok(ForkStatusResponse {
@@ -114,6 +199,7 @@ pub fn get_fork_status(repo_path: &Path) -> Result<ForkStatusResponse, OxenError
             ForkStatus::Counting(_) => ForkStatus::Counting(0).to_string(),
             ForkStatus::InProgress(_) => ForkStatus::InProgress(0.0).to_string(),
             ForkStatus::Complete => ForkStatus::Complete.to_string(),
+            ForkStatus::Cancelled => ForkStatus::Cancelled.to_string(),
             ForkStatus::Failed(_) => ForkStatus::Failed("".to_string()).to_string(),
         },
         progress: match status {
@@ -125,58 +211,174 @@ pub fn get_fork_status(repo_path: &Path) -> Result<ForkStatusResponse, OxenError
             ForkStatus::Failed(e) => Some(e),
             _ => None,
         },
+        copied_bytes,
+        total_bytes,
     })
 }
 
-fn copy_dir_recursive(
-    src: &Path,
-    dst: &Path,
-    status_repo: &Path,
-    total_items: f32,
-    copied_items: &mut f32,
-) -> Result<(), OxenError> {
-    for entry in fs::read_dir(src)? {
-        let entry = entry?;
-        let path = entry.path();
-        let dest_path = dst.join(entry.file_name());
+// Workspaces, webhooks and the activity feed belong to the original repository, and the
+// fork bookkeeping files belong to the fork being written
+fn skip_path(path: &Path) -> bool {
+    path.ends_with(".oxen/workspaces")
+        || path.ends_with(Path::new(".oxen").join(ACTIVITY_FILE))
+        || path.ends_with(Path::new(".oxen").join(HOOKS_FILE))
+        || path.ends_with(FORK_STATUS_FILE)
+        || path.ends_with(FORK_JOURNAL_FILE)
+        || path.ends_with(FORK_CANCEL_FILE)
+}
 
-        if path.ends_with(".oxen/workspaces") {
-            continue;
+fn run_fork(
+    original_path: &Path,
+    new_path: &Path,
+    mode: ForkMode,
+) -> Result<ForkStatus, OxenError> {
+    let mut current_count = 0;
+    let mut total_bytes = 0;
+    count_items(
+        original_path,
+        new_path,
+        &mut current_count,
+        &mut total_bytes,
+    )?;
+
+    let journal_path = new_path.join(FORK_JOURNAL_FILE);
+    let copied: HashSet<PathBuf> = if journal_path.exists() {
+        fs::read_to_string(&journal_path)?
+            .lines()
+            .map(PathBuf::from)
+            .collect()
+    } else {
+        HashSet::new()
+    };
+    if !copied.is_empty() {
+        log::info!(
+            "Resuming fork into {:?}, {} files already copied",
+            new_path,
+            copied.len()
+        );
+    }
+    let journal = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&journal_path)?;
+
+    let mut copier = ForkCopier {
+        src_root: original_path,
+        dst_root: new_path,
+        mode,
+        copied,
+        journal,
+        copied_bytes: 0,
+        total_bytes,
+    };
+    if !copier.copy_dir_recursive(original_path, new_path)? {
+        log::info!("Fork into {:?} was cancelled", new_path);
+        oxen_fs::remove_file(new_path.join(FORK_CANCEL_FILE))?;
+        return Ok(ForkStatus::Cancelled);
+    }
+
+    oxen_fs::remove_file(&journal_path)?;
+    Ok(ForkStatus::Complete)
+}
+
+struct ForkCopier<'a> {
+    src_root: &'a Path,
+    dst_root: &'a Path,
+    mode: ForkMode,
+    // Relative paths the journal says are already in the fork
+    copied: HashSet<PathBuf>,
+    journal: fs::File,
+    copied_bytes: u64,
+    total_bytes: u64,
+}
+
+impl ForkCopier<'_> {
+    /// Returns false if the fork was cancelled before it finished
+    fn copy_dir_recursive(&mut self, src: &Path, dst: &Path) -> Result<bool, OxenError> {
+        for entry in fs::read_dir(src)? {
+            let entry = entry?;
+            let path = entry.path();
+            let dest_path = dst.join(entry.file_name());
+
+            if skip_path(&path) {
+                continue;
+            }
+
+            if path.is_dir() {
+                oxen_fs::create_dir_all(&dest_path)?;
+                if !self.copy_dir_recursive(&path, &dest_path)? {
+                    return Ok(false);
+                }
+                continue;
+            }
+
+            if self.dst_root.join(FORK_CANCEL_FILE).exists() {
+                return Ok(false);
+            }
+            let relative_path = path.strip_prefix(self.src_root)?;
+            if !self.copied.contains(relative_path) {
+                self.copy_file(&path, &dest_path, relative_path)?;
+                writeln!(self.journal, "{}", relative_path.to_string_lossy())?;
+            }
+            self.copied_bytes += entry.metadata()?.len();
         }
 
-        if path.is_dir() {
-            oxen_fs::create_dir_all(&dest_path)?;
-            copy_dir_recursive(&path, &dest_path, status_repo, total_items, copied_items)?;
+        let progress = if self.total_bytes > 0 {
+            (self.copied_bytes as f32 / self.total_bytes as f32) * 100.0
         } else {
-            fs::copy(&path, &dest_path)?;
-            *copied_items += 1.0;
-        }
+            100.0 // Assume completion if there are no bytes to copy
+        };
+        let mut status_file: ForkStatusFile = ForkStatus::InProgress(progress).into();
+        status_file.copied_bytes = Some(self.copied_bytes);
+        status_file.total_bytes = Some(self.total_bytes);
+        write_status_file(self.dst_root, &status_file)?;
+        Ok(true)
     }
 
-    let progress = if total_items > 0.0 {
-        (*copied_items / total_items) * 100.0
-    } else {
-        100.0 // Assume completion if there are no items to copy
-    };
-    write_status(status_repo, &ForkStatus::InProgress(progress))?;
-    Ok(())
+    fn copy_file(&self, src: &Path, dst: &Path, relative_path: &Path) -> Result<(), OxenError> {
+        let is_version_file =
+            relative_path.starts_with(Path::new(OXEN_HIDDEN_DIR).join(VERSIONS_DIR));
+        // Links can't replace the partial file a crashed fork left behind
+        if self.mode != ForkMode::Copy && dst.exists() {
+            fs::remove_file(dst)?;
+        }
+        match self.mode {
+            // Version files never change once written, everything else may so it is copied
+            ForkMode::Hardlink if is_version_file => fs::hard_link(src, dst)?,
+            ForkMode::Copy | ForkMode::Hardlink => {
+                fs::copy(src, dst)?;
+            }
+            ForkMode::Reflink => reflink_copy::reflink(src, dst).map_err(|e| {
+                OxenError::basic_str(format!(
+                    "Could not reflink {src:?}, the filesystem may not support it, fork with mode copy instead: {e}"
+                ))
+            })?,
+        }
+        Ok(())
+    }
 }
 
-fn count_items(path: &Path, status_repo: &Path, current_count: &mut u32) -> Result<u32, OxenError> {
+fn count_items(
+    path: &Path,
+    status_repo: &Path,
+    current_count: &mut u32,
+    total_bytes: &mut u64,
+) -> Result<(), OxenError> {
     for entry in fs::read_dir(path)? {
         let entry = entry?;
         let path = entry.path();
-        if path.ends_with(".oxen/workspaces") {
+        if skip_path(&path) {
             continue;
         }
         if path.is_dir() {
-            count_items(&path, status_repo, current_count)?;
+            count_items(&path, status_repo, current_count, total_bytes)?;
         } else {
             *current_count += 1;
+            *total_bytes += entry.metadata()?.len();
         }
     }
     write_status(status_repo, &ForkStatus::Counting(*current_count))?;
-    Ok(*current_count)
+    Ok(())
 }
 
 #[cfg(test)]
@@ -185,14 +387,15 @@ mod tests {
 
     use super::*;
     use crate::error::OxenError;
-    use crate::{repositories, test};
+    use crate::model::ActivityKind;
+    use crate::test;
 
     #[tokio::test]
     async fn test_fork_operations() -> Result<(), OxenError> {
         test::run_empty_dir_test_async(|test_dir| {
             async move {
                 let original_repo_path = test_dir.join("original");
-                let _original_repo = repositories::init(&original_repo_path)?;
+                let original_repo = repositories::init(&original_repo_path)?;
                 let forked_repo_path = test_dir.join("forked");
 
                 // Create a directory and add a file to it
@@ -207,7 +410,11 @@ mod tests {
                 let workspace_file = workspaces_path.join("test_workspace.txt");
                 std::fs::write(workspace_file, "test workspace content")?;
 
-                start_fork(original_repo_path.clone(), forked_repo_path.clone())?;
+                start_fork(
+                    original_repo_path.clone(),
+                    forked_repo_path.clone(),
+                    &ForkOpts::default(),
+                )?;
                 let mut current_status = "in_progress".to_string();
                 let mut attempts = 0;
                 const MAX_ATTEMPTS: u32 = 10; // 10 seconds timeout (10 * 1s)
@@ -257,6 +464,15 @@ mod tests {
                     "The content of test_file.txt should be the same in both repositories"
                 );
 
+                // The fork shows up in the original's activity once it completed
+                let activity = repositories::activity::list(&original_repo)?;
+                assert_eq!(activity.len(), 1);
+                assert_eq!(activity[0].kind, ActivityKind::Fork);
+                assert!(activity[0]
+                    .repository
+                    .as_ref()
+                    .is_some_and(|repository| repository.ends_with("/forked")));
+
                 // Verify that .oxen/workspaces was not copied
                 let new_workspaces_path = forked_repo_path.join(".oxen/workspaces");
                 assert!(
@@ -265,7 +481,11 @@ mod tests {
                 );
 
                 // Fork fails if repo exists
-                let result = start_fork(original_repo_path.clone(), forked_repo_path.clone());
+                let result = start_fork(
+                    original_repo_path.clone(),
+                    forked_repo_path.clone(),
+                    &ForkOpts::default(),
+                );
                 assert!(
                     result.is_err(),
                     "Expected an error because the repo already exists."
@@ -276,4 +496,74 @@ mod tests {
         })
         .await
     }
+
+    #[tokio::test]
+    async fn test_fork_resume_from_journal_with_hardlinks() -> Result<(), OxenError> {
+        test::run_empty_dir_test_async(|test_dir| async move {
+            let original_repo_path = test_dir.join("original");
+            let original_repo = repositories::init(&original_repo_path)?;
+            for name in ["a.txt", "b.txt"] {
+                let path = original_repo_path.join(name);
+                std::fs::write(&path, format!("contents of {name}"))?;
+                repositories::add(&original_repo, &path).await?;
+            }
+            repositories::commit(&original_repo, "Add a and b")?;
+
+            // A fork that crashed after copying a.txt
+            let forked_repo_path = test_dir.join("forked");
+            oxen_fs::create_dir_all(forked_repo_path.join(".oxen"))?;
+            std::fs::write(forked_repo_path.join("a.txt"), "left by the first run")?;
+            std::fs::write(forked_repo_path.join(FORK_JOURNAL_FILE), "a.txt\n")?;
+            write_status(
+                &forked_repo_path,
+                &ForkStatus::Failed("crashed".to_string()),
+            )?;
+
+            // Without resume the destination is taken
+            assert!(start_fork(
+                original_repo_path.clone(),
+                forked_repo_path.clone(),
+                &ForkOpts::default()
+            )
+            .is_err());
+
+            let opts = ForkOpts {
+                mode: ForkMode::Hardlink,
+                resume: true,
+            };
+            start_fork(original_repo_path.clone(), forked_repo_path.clone(), &opts)?;
+            let mut status = get_fork_status(&forked_repo_path)?;
+            for _ in 0..100 {
+                if status.status == "complete" || status.status == "failed" {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                status = get_fork_status(&forked_repo_path)?;
+            }
+            assert_eq!(status.status, "complete", "{status:?}");
+
+            // The journaled file was skipped and the rest was copied
+            let a_content = fs::read_to_string(forked_repo_path.join("a.txt"))?;
+            assert_eq!(a_content, "left by the first run");
+            let b_content = fs::read_to_string(forked_repo_path.join("b.txt"))?;
+            assert_eq!(b_content, "contents of b.txt");
+            assert!(!forked_repo_path.join(FORK_JOURNAL_FILE).exists());
+
+            // Version files are linked rather than copied
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::MetadataExt;
+                let versions_dir = forked_repo_path.join(OXEN_HIDDEN_DIR).join(VERSIONS_DIR);
+                let version_file = walkdir::WalkDir::new(&versions_dir)
+                    .into_iter()
+                    .filter_map(|entry| entry.ok())
+                    .find(|entry| entry.file_type().is_file())
+                    .expect("fork has version files");
+                assert!(fs::metadata(version_file.path())?.nlink() > 1);
+            }
+
+            Ok(())
+        })
+        .await
+    }
 }
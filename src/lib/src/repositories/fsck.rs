@@ -0,0 +1,247 @@
+//! # Fsck
+//!
+//! Check the integrity of a repository: that the merkle tree of every commit can be
+//! loaded and its nodes refer to each other consistently, that the dir_hashes db of each
+//! commit matches its tree, and that every file has a version file whose contents still
+//! hash to the file's hash.
+//!
+//! Unlike the content_validator cacher this re-hashes the version files of any store,
+//! downloading them from remote stores. A clone only has the version files of the
+//! commits it checked out, so check a single revision there.
+//!
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::versions::MinOxenVersion;
+use crate::error::OxenError;
+use crate::model::{Commit, LocalRepository, MerkleHash};
+use crate::repositories;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FsckProblemKind {
+    /// The merkle tree of the commit could not be loaded
+    MissingTree,
+    /// A node in the merkle tree is missing or inconsistent with its parent
+    InconsistentTree,
+    /// A directory in the tree has no entry in the commit's dir_hashes db
+    MissingDirHash,
+    /// The dir_hashes db points a directory at a different node than the tree
+    DirHashMismatch,
+    /// The version store does not have the file
+    MissingVersion,
+    /// The version file does not hash to the file's hash
+    CorruptVersion,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FsckProblem {
+    pub kind: FsckProblemKind,
+    pub commit_id: String,
+    /// The file or directory with the problem, empty for the commit itself
+    pub path: PathBuf,
+}
+
+impl fmt::Display for FsckProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let description = match self.kind {
+            FsckProblemKind::MissingTree => "merkle tree could not be loaded",
+            FsckProblemKind::InconsistentTree => "merkle tree node is missing or inconsistent",
+            FsckProblemKind::MissingDirHash => "directory is missing from dir_hashes",
+            FsckProblemKind::DirHashMismatch => "dir_hashes does not match the tree",
+            FsckProblemKind::MissingVersion => "version file is missing",
+            FsckProblemKind::CorruptVersion => "version file does not match its hash",
+        };
+        write!(
+            f,
+            "{} {}: {description}",
+            self.commit_id,
+            self.path.display()
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct FsckReport {
+    pub commits_checked: usize,
+    pub versions_checked: usize,
+    pub problems: Vec<FsckProblem>,
+}
+
+impl FsckReport {
+    pub fn is_ok(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+/// Check every commit in the history of every branch, or just the commit at `revision`
+pub fn run(repo: &LocalRepository, revision: Option<&str>) -> Result<FsckReport, OxenError> {
+    let commits: Vec<Commit> = match revision {
+        Some(revision) => {
+            let commit = repositories::revisions::get(repo, revision)?
+                .ok_or(OxenError::revision_not_found(revision.into()))?;
+            vec![commit]
+        }
+        None => repositories::commits::list_all(repo)?.into_iter().collect(),
+    };
+
+    let mut report = FsckReport::default();
+    // Version files are shared between commits, only check and report each one once
+    let mut checked: HashMap<MerkleHash, bool> = HashMap::new();
+    for commit in &commits {
+        check_commit(repo, commit, &mut checked, &mut report)?;
+        report.commits_checked += 1;
+    }
+    report.versions_checked = checked.len();
+
+    log::info!(
+        "fsck for {:?} checked {} commits and {} versions, found {} problems",
+        repo.path,
+        report.commits_checked,
+        report.versions_checked,
+        report.problems.len()
+    );
+    Ok(report)
+}
+
+fn check_commit(
+    repo: &LocalRepository,
+    commit: &Commit,
+    checked: &mut HashMap<MerkleHash, bool>,
+    report: &mut FsckReport,
+) -> Result<(), OxenError> {
+    let problem = |kind: FsckProblemKind, path: &Path| FsckProblem {
+        kind,
+        commit_id: commit.id.clone(),
+        path: path.to_path_buf(),
+    };
+
+    // Subtree and depth clones are missing the nodes they didn't fetch
+    let is_full_tree = repo.subtree_paths().is_none() && repo.depth().is_none();
+    if is_full_tree && repo.min_version() != MinOxenVersion::V0_19_0 {
+        for tree_problem in repositories::tree::verify(repo, commit)? {
+            log::debug!("fsck {}: {tree_problem}", commit.id);
+            report.problems.push(problem(
+                FsckProblemKind::InconsistentTree,
+                &tree_problem.path,
+            ));
+        }
+    }
+
+    let tree = match repositories::tree::get_root_with_children(repo, commit) {
+        Ok(Some(tree)) => tree,
+        Ok(None) | Err(_) => {
+            report
+                .problems
+                .push(problem(FsckProblemKind::MissingTree, Path::new("")));
+            return Ok(());
+        }
+    };
+
+    let dir_hashes = repositories::tree::dir_hashes(repo, commit).unwrap_or_default();
+    let mut seen_dirs: HashSet<PathBuf> = HashSet::new();
+    for dir in repositories::tree::list_all_dirs(&tree)? {
+        match dir_hashes.get(&dir.path) {
+            None => report
+                .problems
+                .push(problem(FsckProblemKind::MissingDirHash, &dir.path)),
+            Some(hash) if hash != dir.dir_node.hash() => report
+                .problems
+                .push(problem(FsckProblemKind::DirHashMismatch, &dir.path)),
+            Some(_) => {}
+        }
+        seen_dirs.insert(dir.path);
+    }
+    // Entries for directories that are not in the tree would send lookups to the wrong node
+    for path in dir_hashes.keys() {
+        if !seen_dirs.contains(path) {
+            report
+                .problems
+                .push(problem(FsckProblemKind::DirHashMismatch, path));
+        }
+    }
+
+    let version_store = repo.version_store()?;
+    let hash_algorithm = repo.hash_algorithm();
+    for file in repositories::tree::list_all_files(&tree, &PathBuf::from(""))? {
+        let hash = file.file_node.hash();
+        if checked.contains_key(hash) {
+            continue;
+        }
+        let path = file.dir.join(file.file_node.name());
+        let hash_str = hash.to_string();
+        if !version_store.version_exists(&hash_str)? {
+            report
+                .problems
+                .push(problem(FsckProblemKind::MissingVersion, &path));
+            checked.insert(*hash, false);
+            continue;
+        }
+
+        let version_path = version_store.get_version_path(&hash_str)?;
+        let actual = MerkleHash::new(hash_algorithm.hash_file_contents(&version_path)?);
+        let is_valid = actual == *hash;
+        if !is_valid {
+            report
+                .problems
+                .push(problem(FsckProblemKind::CorruptVersion, &path));
+        }
+        checked.insert(*hash, is_valid);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FsckProblemKind;
+    use crate::error::OxenError;
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    #[tokio::test]
+    async fn test_fsck_finds_missing_and_corrupt_versions() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            for name in ["a.txt", "b.txt"] {
+                let path = repo.path.join(name);
+                util::fs::write_to_path(&path, format!("Contents of {name}"))?;
+                repositories::add(&repo, &path).await?;
+            }
+            let commit = repositories::commit(&repo, "Add files")?;
+
+            let report = repositories::fsck::run(&repo, None)?;
+            assert!(report.is_ok(), "{:?}", report.problems);
+            assert_eq!(report.commits_checked, 1);
+            assert_eq!(report.versions_checked, 2);
+
+            let version_store = repo.version_store()?;
+            let a = repositories::tree::get_file_by_path(&repo, &commit, "a.txt")?
+                .expect("file exists");
+            let b = repositories::tree::get_file_by_path(&repo, &commit, "b.txt")?
+                .expect("file exists");
+            let a_path = version_store.get_version_path(&a.hash().to_string())?;
+            util::fs::write_to_path(&a_path, "Not what was committed")?;
+            version_store.delete_version(&b.hash().to_string()).await?;
+
+            let report = repositories::fsck::run(&repo, Some(&commit.id))?;
+            assert!(!report.is_ok());
+            let mut kinds: Vec<FsckProblemKind> =
+                report.problems.iter().map(|problem| problem.kind).collect();
+            kinds.sort_by_key(|kind| format!("{kind:?}"));
+            assert_eq!(
+                kinds,
+                vec![
+                    FsckProblemKind::CorruptVersion,
+                    FsckProblemKind::MissingVersion
+                ]
+            );
+
+            Ok(())
+        })
+        .await
+    }
+}
@@ -0,0 +1,204 @@
+//! # Garbage collection
+//!
+//! Delete the version files that nothing in the repository can reach anymore, such as
+//! the files of commits dropped by a force push or a deleted branch.
+//!
+//! A version file is reachable if it is in the tree of a commit in the history of a
+//! branch, a tag, a workspace or a reflog entry, if it is staged in the repository or in a
+//! workspace, or if a stash entry points at it. A push uploads its version files before
+//! it creates the commit that references them, so only run gc while no pushes are in
+//! flight.
+//!
+
+use std::collections::HashSet;
+
+use indicatif::ProgressBar;
+use serde::{Deserialize, Serialize};
+
+use crate::constants::STAGED_DIR;
+use crate::core;
+use crate::error::OxenError;
+use crate::model::merkle_tree::node::EMerkleTreeNode;
+use crate::model::{Commit, LocalRepository, StashedChange};
+use crate::repositories;
+use crate::util;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct GcReport {
+    pub reachable_versions: usize,
+    pub removed_versions: usize,
+    pub removed_bytes: u64,
+    pub dry_run: bool,
+}
+
+/// Delete every unreachable version file from the version store. With `dry_run`
+/// nothing is deleted, and the report says what would have been.
+pub async fn run(repo: &LocalRepository, dry_run: bool) -> Result<GcReport, OxenError> {
+    let reachable = reachable_hashes(repo)?;
+    let mut report = GcReport {
+        reachable_versions: reachable.len(),
+        dry_run,
+        ..Default::default()
+    };
+
+    let version_store = repo.version_store()?;
+    for hash in version_store.list_versions().await? {
+        if reachable.contains(&hash) {
+            continue;
+        }
+        // Versions with only chunks are uploads that have not been combined yet
+        if !version_store.version_exists(&hash)? {
+            continue;
+        }
+        let num_bytes = version_store.get_version_size(&hash).await?;
+        if !dry_run {
+            version_store.delete_version(&hash).await?;
+        }
+        report.removed_versions += 1;
+        report.removed_bytes += num_bytes;
+    }
+
+    log::info!(
+        "gc for {:?} removed {} versions ({} bytes), {} reachable, dry run: {}",
+        repo.path,
+        report.removed_versions,
+        report.removed_bytes,
+        report.reachable_versions,
+        dry_run
+    );
+    Ok(report)
+}
+
+fn reachable_hashes(repo: &LocalRepository) -> Result<HashSet<String>, OxenError> {
+    let mut commits: HashSet<Commit> = repositories::commits::list_all(repo)?;
+    let mut hashes: HashSet<String> = HashSet::new();
+
+    let mut roots: Vec<String> = vec![];
+    for tag in repositories::tags::list(repo)? {
+        roots.push(tag.commit_id);
+    }
+    for workspace in repositories::workspaces::list(repo)? {
+        roots.push(workspace.commit.id.clone());
+        collect_staged_hashes(&workspace.workspace_repo, &mut hashes)?;
+    }
+    for entry in repositories::reflog::list(repo)? {
+        roots.extend(entry.old_id);
+        roots.extend(entry.new_id);
+    }
+    for commit_id in roots {
+        let Some(commit) = repositories::commits::get_by_id(repo, &commit_id)? else {
+            continue;
+        };
+        if !commits.contains(&commit) {
+            commits.extend(repositories::commits::list_from(repo, &commit_id)?);
+        }
+    }
+
+    for commit in &commits {
+        collect_file_hashes(repo, commit, &mut hashes)?;
+    }
+    collect_staged_hashes(repo, &mut hashes)?;
+    collect_stash_hashes(repo, &mut hashes)?;
+    Ok(hashes)
+}
+
+fn collect_file_hashes(
+    repo: &LocalRepository,
+    commit: &Commit,
+    hashes: &mut HashSet<String>,
+) -> Result<(), OxenError> {
+    let Some(tree) = repositories::tree::get_root_with_children(repo, commit)? else {
+        // Keep everything rather than delete files a commit we can't read may need
+        return Err(OxenError::basic_str(format!(
+            "gc could not load the tree for commit {}",
+            commit.id
+        )));
+    };
+    tree.walk_tree(|node| {
+        if let EMerkleTreeNode::File(_) = &node.node {
+            hashes.insert(node.hash.to_string());
+        }
+    });
+    Ok(())
+}
+
+fn collect_staged_hashes(
+    repo: &LocalRepository,
+    hashes: &mut HashSet<String>,
+) -> Result<(), OxenError> {
+    // Don't create a staged db just to find out it is empty
+    if !util::fs::oxen_hidden_dir(&repo.path)
+        .join(STAGED_DIR)
+        .exists()
+    {
+        return Ok(());
+    }
+    let (dir_entries, _) = core::v_latest::status::read_staged_entries_with_staged_db_manager(
+        repo,
+        &ProgressBar::hidden(),
+    )?;
+    for entry in dir_entries.values().flatten() {
+        if let EMerkleTreeNode::File(_) = &entry.node.node {
+            hashes.insert(entry.node.hash.to_string());
+        }
+    }
+    Ok(())
+}
+
+fn collect_stash_hashes(
+    repo: &LocalRepository,
+    hashes: &mut HashSet<String>,
+) -> Result<(), OxenError> {
+    for entry in repositories::stash::list(repo)? {
+        for file in entry.files {
+            for change in [file.staged, file.unstaged].into_iter().flatten() {
+                if let StashedChange::Modified { hash } = change {
+                    hashes.insert(hash);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::OxenError;
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    #[tokio::test]
+    async fn test_gc_removes_unreachable_versions() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            let text_path = repo.path.join("text.txt");
+            util::fs::write_to_path(&text_path, "Committed")?;
+            repositories::add(&repo, &text_path).await?;
+            let commit = repositories::commit(&repo, "Add text")?;
+            let file = repositories::tree::get_file_by_path(&repo, &commit, "text.txt")?
+                .expect("file exists");
+
+            // A version file nothing points at, like one left behind by a force push
+            let version_store = repo.version_store()?;
+            let orphan_hash = "0123456789abcdef0123456789abcdef";
+            version_store.store_version(orphan_hash, b"orphan").await?;
+
+            let report = repositories::gc::run(&repo, true).await?;
+            assert_eq!(report.removed_versions, 1);
+            assert_eq!(report.removed_bytes, 6);
+            assert!(version_store.version_exists(orphan_hash)?);
+
+            let report = repositories::gc::run(&repo, false).await?;
+            assert_eq!(report.removed_versions, 1);
+            assert!(!version_store.version_exists(orphan_hash)?);
+            assert!(version_store.version_exists(&file.hash().to_string())?);
+
+            // Nothing left to collect
+            let report = repositories::gc::run(&repo, false).await?;
+            assert_eq!(report.removed_versions, 0);
+
+            Ok(())
+        })
+        .await
+    }
+}
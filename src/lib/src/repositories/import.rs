@@ -0,0 +1,37 @@
+//! # oxen import
+//!
+//! Create Oxen repositories from data that lives somewhere else, keeping where it came
+//! from in the commit metadata.
+//!
+
+use std::path::Path;
+
+use crate::error::OxenError;
+use crate::model::LocalRepository;
+
+pub mod git;
+pub mod s3;
+
+pub use git::git;
+pub use s3::s3;
+
+/// Imports create a new repository, so the destination must not exist or be empty
+fn check_empty_dir(dst: &Path) -> Result<(), OxenError> {
+    if dst.exists() && std::fs::read_dir(dst)?.next().is_some() {
+        return Err(OxenError::basic_str(format!(
+            "Cannot import into {dst:?}, the directory is not empty"
+        )));
+    }
+    Ok(())
+}
+
+/// Remove the directories a deleted file leaves empty, up to the repository root
+fn remove_empty_parents(repo: &LocalRepository, path: &Path) {
+    let mut dir = path.parent();
+    while let Some(parent) = dir {
+        if parent == repo.path || std::fs::remove_dir(parent).is_err() {
+            break;
+        }
+        dir = parent.parent();
+    }
+}
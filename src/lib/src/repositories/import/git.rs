@@ -0,0 +1,702 @@
+//! # oxen import git
+//!
+//! Convert a git repository into an Oxen repository commit by commit, keeping the author,
+//! date and message of every commit, the branches and the tags, so a dataset that lives
+//! in git can move to Oxen without losing its history.
+//!
+//! The history is read with the `git` binary. Each imported commit records the git commit
+//! it came from in its `git_commit` metadata. Files stored with git LFS are replaced by
+//! their content when importing with `lfs`, otherwise the LFS pointer files are kept.
+//! Symlinks and submodules are not imported, and a commit that only changed them is
+//! folded into its parent.
+//!
+
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+use time::OffsetDateTime;
+
+use crate::config::UserConfig;
+use crate::constants::{DEFAULT_BRANCH_NAME, OXEN_HIDDEN_DIR};
+use crate::error::OxenError;
+use crate::model::{Branch, LocalRepository, Tag};
+use crate::opts::CommitOpts;
+use crate::repositories;
+use crate::repositories::commits::commit_writer;
+use crate::util;
+
+/// Metadata key of the git commit an imported commit was converted from
+pub const GIT_COMMIT_METADATA_KEY: &str = "git_commit";
+
+/// Branch the history is rebuilt on, deleted once the git branches are created
+const IMPORT_BRANCH_NAME: &str = "oxen-git-import";
+
+const LFS_POINTER_PREFIX: &[u8] = b"version https://git-lfs.github.com/spec/v1";
+const LFS_POINTER_MAX_SIZE: usize = 1024;
+
+#[derive(Debug, Clone)]
+pub struct GitImport {
+    /// Where the Oxen repository was created
+    pub path: PathBuf,
+    /// Number of Oxen commits created
+    pub num_commits: usize,
+    pub branches: Vec<Branch>,
+    pub tags: Vec<Tag>,
+    /// Files whose content was read from git LFS
+    pub num_lfs_files: usize,
+    /// LFS pointer files imported as is, because LFS objects were not requested
+    pub num_lfs_pointers: usize,
+    /// Symlinks and submodules that were left out
+    pub num_skipped: usize,
+}
+
+/// Import the git repository at `src`, a local path or anything `git clone` accepts, into a
+/// new Oxen repository at `dst`. `dst` defaults to the name of the git repository in the
+/// current directory and must not exist or be empty.
+pub async fn git(
+    src: impl AsRef<str>,
+    dst: Option<&Path>,
+    lfs: bool,
+) -> Result<GitImport, OxenError> {
+    let src = src.as_ref();
+    git_output(Path::new("."), ["--version"])?;
+
+    let dst = match dst {
+        Some(dst) => dst.to_path_buf(),
+        None => std::env::current_dir()?.join(repo_name(src)?),
+    };
+    super::check_empty_dir(&dst)?;
+
+    // Remote repositories are mirrored to a temporary directory first
+    let clone_dir = if Path::new(src).exists() {
+        None
+    } else {
+        let clone_dir =
+            std::env::temp_dir().join(format!("oxen-git-import-{}", uuid::Uuid::new_v4()));
+        println!("Cloning {src}");
+        git_output(
+            Path::new("."),
+            [
+                OsStr::new("clone"),
+                OsStr::new("--mirror"),
+                OsStr::new(src),
+                clone_dir.as_os_str(),
+            ],
+        )?;
+        Some(clone_dir)
+    };
+    let source_dir = clone_dir.clone().unwrap_or_else(|| PathBuf::from(src));
+
+    let result = import_from(&source_dir, &dst, lfs).await;
+    if let Some(clone_dir) = clone_dir {
+        util::fs::remove_dir_all(clone_dir).ok();
+    }
+    result
+}
+
+async fn import_from(source_dir: &Path, dst: &Path, lfs: bool) -> Result<GitImport, OxenError> {
+    let git_dir = PathBuf::from(git_string(source_dir, ["rev-parse", "--absolute-git-dir"])?);
+    if lfs {
+        println!("Fetching LFS objects");
+        git_output(&git_dir, ["lfs", "fetch", "--all"])?;
+    }
+
+    let commits = list_commits(&git_dir)?;
+    if commits.is_empty() {
+        return Err(OxenError::basic_str(
+            "The git repository has no commits to import",
+        ));
+    }
+
+    util::fs::create_dir_all(dst)?;
+    let repo = repositories::init(util::fs::canonicalize(dst)?)?;
+
+    let mut import = GitImport {
+        path: repo.path.clone(),
+        num_commits: 0,
+        branches: vec![],
+        tags: vec![],
+        num_lfs_files: 0,
+        num_lfs_pointers: 0,
+        num_skipped: 0,
+    };
+    let mut blobs = GitCatFile::spawn(&git_dir)?;
+    // git commit id to the Oxen commit it was imported as
+    let mut imported: HashMap<String, String> = HashMap::new();
+    let mut import_branches: Vec<String> = vec![];
+
+    for (i, commit) in commits.iter().enumerate() {
+        println!(
+            "Importing git commit {} ({}/{})",
+            commit.id,
+            i + 1,
+            commits.len()
+        );
+
+        // Parents that were skipped because they were empty have no Oxen commit, the
+        // changes are read against the first parent that does
+        let base = commit
+            .parent_ids
+            .iter()
+            .find(|parent_id| imported.contains_key(*parent_id));
+        let mut parent_ids: Vec<String> = vec![];
+        for parent_id in &commit.parent_ids {
+            if let Some(oxen_id) = imported.get(parent_id) {
+                if !parent_ids.contains(oxen_id) {
+                    parent_ids.push(oxen_id.clone());
+                }
+            }
+        }
+
+        let changes: Vec<GitChange> = diff_tree(&git_dir, base.map(String::as_str), &commit.id)?
+            .into_iter()
+            .filter(|change| change.old_sha != change.new_sha)
+            .filter(|change| !change.path.starts_with(OXEN_HIDDEN_DIR))
+            .collect();
+        import.num_skipped += changes
+            .iter()
+            .filter(|change| !change.is_deleted() && !change.is_file())
+            .count();
+        // Only regular files are imported, so only they can change
+        let changes: Vec<GitChange> = changes
+            .into_iter()
+            .filter(|change| change.is_file() || change.was_file())
+            .collect();
+        if changes.is_empty() {
+            match parent_ids.first() {
+                Some(parent_id) => {
+                    imported.insert(commit.id.clone(), parent_id.clone());
+                }
+                None => log::warn!("Skipping empty git commit {}", commit.id),
+            }
+            continue;
+        }
+
+        // Start from the tree of the parent the changes are relative to
+        match parent_ids.first() {
+            Some(parent_id) => {
+                let head = repositories::commits::head_commit_maybe(&repo)?;
+                if head.as_ref().map(|head| &head.id) != Some(parent_id) {
+                    let parent = repositories::commits::get_by_id(&repo, parent_id)?
+                        .ok_or(OxenError::commit_id_does_not_exist(parent_id))?;
+                    repositories::branches::set_working_repo_to_commit(&repo, &parent, &head)
+                        .await?;
+                    let branch = import_branches.last().expect("a parent was imported");
+                    repositories::branches::update(&repo, branch, parent_id)?;
+                }
+            }
+            None => {
+                clear_working_dir(&repo)?;
+                let branch = match import_branches.len() {
+                    0 => IMPORT_BRANCH_NAME.to_string(),
+                    n => format!("{IMPORT_BRANCH_NAME}-{n}"),
+                };
+                repositories::branches::create_orphan_checkout(&repo, &branch)?;
+                import_branches.push(branch);
+            }
+        }
+
+        // Deletions first, a directory can be replaced by a file of the same name
+        for change in changes.iter().filter(|change| !change.is_file()) {
+            let path = repo.path.join(&change.path);
+            if path.is_file() {
+                util::fs::remove_file(&path)?;
+                super::remove_empty_parents(&repo, &path);
+                repositories::add(&repo, &change.path).await?;
+            }
+        }
+
+        let mut added: Vec<&PathBuf> = vec![];
+        for change in changes.iter().filter(|change| change.is_file()) {
+            let path = repo.path.join(&change.path);
+            if let Some(parent) = path.parent() {
+                util::fs::create_dir_all(parent)?;
+            }
+            let data = blobs.read(&change.new_sha)?;
+            match lfs_oid(&data) {
+                Some(oid) if lfs => {
+                    let object = lfs_object_path(&git_dir, &oid);
+                    if !object.exists() {
+                        return Err(OxenError::basic_str(format!(
+                            "LFS object {oid} of {:?} is missing, run `git lfs fetch --all` in the git repository and try again",
+                            change.path
+                        )));
+                    }
+                    util::fs::copy(&object, &path)?;
+                    import.num_lfs_files += 1;
+                }
+                Some(_) => {
+                    util::fs::write(&path, &data)?;
+                    import.num_lfs_pointers += 1;
+                }
+                None => util::fs::write(&path, &data)?,
+            }
+            added.push(&change.path);
+        }
+        if !added.is_empty() {
+            repositories::add_all(&repo, added).await?;
+        }
+
+        let message = match commit.message.trim() {
+            "" => format!("Imported git commit {}", commit.id),
+            message => message.to_string(),
+        };
+        let cfg = UserConfig {
+            name: commit.author.clone(),
+            email: commit.email.clone(),
+        };
+        let opts = CommitOpts {
+            metadata: [(GIT_COMMIT_METADATA_KEY.to_string(), commit.id.clone())].into(),
+            timestamp: Some(commit.timestamp),
+            ..CommitOpts::default()
+        };
+        let oxen_commit =
+            commit_writer::commit_with_cfg(&repo, &message, &cfg, Some(parent_ids), &opts)?;
+        imported.insert(commit.id.clone(), oxen_commit.id);
+        import.num_commits += 1;
+    }
+    blobs.close()?;
+
+    // Branches and tags point to the Oxen commits their git commits were imported as
+    for git_ref in list_refs(&git_dir)? {
+        let Some(commit_id) = imported.get(&git_ref.commit_id) else {
+            log::warn!(
+                "Skipping {}, its commit {} was not imported",
+                git_ref.name,
+                git_ref.commit_id
+            );
+            continue;
+        };
+        if let Some(name) = git_ref.name.strip_prefix("refs/heads/") {
+            let branch = repositories::branches::update(&repo, name, commit_id)?;
+            import.branches.push(Branch {
+                name: branch.name,
+                commit_id: commit_id.clone(),
+            });
+        } else if let Some(name) = git_ref.name.strip_prefix("refs/tags/") {
+            let commit = commits
+                .iter()
+                .find(|commit| commit.id == git_ref.commit_id)
+                .expect("imported commits were listed");
+            let tag = Tag {
+                name: name.to_string(),
+                commit_id: commit_id.clone(),
+                message: git_ref.message.unwrap_or_default(),
+                author: git_ref.tagger.unwrap_or_else(|| commit.author.clone()),
+                email: git_ref.tagger_email.unwrap_or_else(|| commit.email.clone()),
+                timestamp: git_ref.timestamp.unwrap_or(commit.timestamp),
+            };
+            repositories::tags::save(&repo, &tag)?;
+            import.tags.push(tag);
+        }
+    }
+
+    // Check out the branch git's HEAD points to and drop the branches used to import
+    let head_branch = git_string(&git_dir, ["symbolic-ref", "--short", "HEAD"]).ok();
+    let branch = match import
+        .branches
+        .iter()
+        .find(|b| Some(&b.name) == head_branch.as_ref())
+    {
+        Some(branch) => branch.clone(),
+        None => match import.branches.first() {
+            Some(branch) => branch.clone(),
+            None => {
+                let head = repositories::commits::head_commit(&repo)?;
+                let branch = repositories::branches::update(&repo, DEFAULT_BRANCH_NAME, &head.id)?;
+                import.branches.push(branch.clone());
+                branch
+            }
+        },
+    };
+    repositories::checkout(&repo, &branch.name).await?;
+    for name in import_branches {
+        repositories::branches::force_delete(&repo, name)?;
+    }
+
+    Ok(import)
+}
+
+/// The directory name `git clone` would use for the repository
+fn repo_name(src: &str) -> Result<String, OxenError> {
+    let name = src
+        .trim_end_matches(['/', '\\'])
+        .rsplit(['/', '\\', ':'])
+        .next()
+        .unwrap_or_default();
+    let name = name.strip_suffix(".git").unwrap_or(name);
+    if name.is_empty() {
+        return Err(OxenError::basic_str(format!(
+            "Cannot tell the repository name from {src:?}, pass a destination"
+        )));
+    }
+    Ok(name.to_string())
+}
+
+fn clear_working_dir(repo: &LocalRepository) -> Result<(), OxenError> {
+    for entry in std::fs::read_dir(&repo.path)? {
+        let path = entry?.path();
+        if path.file_name().is_some_and(|name| name == OXEN_HIDDEN_DIR) {
+            continue;
+        }
+        if path.is_dir() {
+            util::fs::remove_dir_all(&path)?;
+        } else {
+            util::fs::remove_file(&path)?;
+        }
+    }
+    Ok(())
+}
+
+fn lfs_oid(data: &[u8]) -> Option<String> {
+    if data.len() > LFS_POINTER_MAX_SIZE || !data.starts_with(LFS_POINTER_PREFIX) {
+        return None;
+    }
+    String::from_utf8_lossy(data)
+        .lines()
+        .find_map(|line| line.strip_prefix("oid sha256:"))
+        .map(|oid| oid.trim().to_string())
+        .filter(|oid| oid.len() == 64)
+}
+
+fn lfs_object_path(git_dir: &Path, oid: &str) -> PathBuf {
+    git_dir
+        .join("lfs")
+        .join("objects")
+        .join(&oid[0..2])
+        .join(&oid[2..4])
+        .join(oid)
+}
+
+struct GitCommit {
+    id: String,
+    parent_ids: Vec<String>,
+    author: String,
+    email: String,
+    timestamp: OffsetDateTime,
+    message: String,
+}
+
+/// Every commit on a branch or tag, parents before their children
+fn list_commits(git_dir: &Path) -> Result<Vec<GitCommit>, OxenError> {
+    let output = git_output(
+        git_dir,
+        [
+            "log",
+            "--topo-order",
+            "--reverse",
+            "--branches",
+            "--tags",
+            "-z",
+            "--format=%H%x00%P%x00%an%x00%ae%x00%at%x00%B",
+        ],
+    )?;
+    let output = String::from_utf8_lossy(&output);
+    let fields: Vec<&str> = output.trim_end_matches('\0').split('\0').collect();
+    if fields.len() % 6 != 0 {
+        return Err(OxenError::basic_str("Could not read the git log"));
+    }
+    fields
+        .chunks(6)
+        .map(|fields| {
+            Ok(GitCommit {
+                id: fields[0].trim().to_string(),
+                parent_ids: fields[1].split_whitespace().map(String::from).collect(),
+                author: fields[2].to_string(),
+                email: fields[3].to_string(),
+                timestamp: parse_timestamp(fields[4])?,
+                message: fields[5].to_string(),
+            })
+        })
+        .collect()
+}
+
+struct GitChange {
+    old_mode: String,
+    new_mode: String,
+    old_sha: String,
+    new_sha: String,
+    path: PathBuf,
+}
+
+impl GitChange {
+    fn is_file(&self) -> bool {
+        is_file_mode(&self.new_mode)
+    }
+
+    fn was_file(&self) -> bool {
+        is_file_mode(&self.old_mode)
+    }
+
+    fn is_deleted(&self) -> bool {
+        self.new_mode == "000000"
+    }
+}
+
+fn is_file_mode(mode: &str) -> bool {
+    mode == "100644" || mode == "100755"
+}
+
+/// The files changed from `parent`, or from nothing for a root commit
+fn diff_tree(
+    git_dir: &Path,
+    parent: Option<&str>,
+    commit: &str,
+) -> Result<Vec<GitChange>, OxenError> {
+    let mut args = vec!["diff-tree", "-r", "-z", "--no-renames", "--no-commit-id"];
+    match parent {
+        Some(parent) => args.extend([parent, commit]),
+        None => args.extend(["--root", commit]),
+    }
+    let output = git_output(git_dir, args)?;
+    let output = String::from_utf8_lossy(&output);
+    let fields: Vec<&str> = output.trim_end_matches('\0').split('\0').collect();
+
+    let mut changes = vec![];
+    for pair in fields.chunks(2) {
+        let [meta, path] = pair else {
+            continue;
+        };
+        let meta: Vec<&str> = meta.trim_start_matches(':').split_whitespace().collect();
+        if meta.len() < 4 {
+            return Err(OxenError::basic_str(format!(
+                "Could not read the changes of git commit {commit}"
+            )));
+        }
+        changes.push(GitChange {
+            old_mode: meta[0].to_string(),
+            new_mode: meta[1].to_string(),
+            old_sha: meta[2].to_string(),
+            new_sha: meta[3].to_string(),
+            path: PathBuf::from(path),
+        });
+    }
+    Ok(changes)
+}
+
+struct GitRef {
+    name: String,
+    commit_id: String,
+    tagger: Option<String>,
+    tagger_email: Option<String>,
+    timestamp: Option<OffsetDateTime>,
+    message: Option<String>,
+}
+
+fn list_refs(git_dir: &Path) -> Result<Vec<GitRef>, OxenError> {
+    let output = git_output(
+        git_dir,
+        [
+            "for-each-ref",
+            "--format=%(refname)%00%(objectname)%00%(*objectname)%00%(taggername)%00%(taggeremail)%00%(taggerdate:unix)%00%(contents:subject)",
+            "refs/heads",
+            "refs/tags",
+        ],
+    )?;
+    let output = String::from_utf8_lossy(&output);
+    let non_empty = |field: &str| Some(field.to_string()).filter(|field| !field.is_empty());
+
+    let mut refs = vec![];
+    for line in output.lines().filter(|line| !line.is_empty()) {
+        let fields: Vec<&str> = line.split('\0').collect();
+        let [name, object_id, peeled_id, tagger, tagger_email, date, subject] = fields[..] else {
+            return Err(OxenError::basic_str(format!(
+                "Could not read git ref {line:?}"
+            )));
+        };
+        // Annotated tags point to a tag object that points to the commit
+        let annotated = !peeled_id.is_empty();
+        refs.push(GitRef {
+            name: name.to_string(),
+            commit_id: non_empty(peeled_id).unwrap_or(object_id.to_string()),
+            tagger: non_empty(tagger),
+            tagger_email: non_empty(tagger_email.trim_matches(['<', '>'])),
+            timestamp: non_empty(date)
+                .map(|date| parse_timestamp(&date))
+                .transpose()?,
+            message: non_empty(subject).filter(|_| annotated),
+        });
+    }
+    Ok(refs)
+}
+
+fn parse_timestamp(seconds: &str) -> Result<OffsetDateTime, OxenError> {
+    OffsetDateTime::from_unix_timestamp(seconds.trim().parse()?)
+        .map_err(|err| OxenError::basic_str(format!("Invalid git timestamp {seconds:?}: {err}")))
+}
+
+/// Reads the content of git blobs through a single `git cat-file --batch` process
+struct GitCatFile {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl GitCatFile {
+    fn spawn(git_dir: &Path) -> Result<GitCatFile, OxenError> {
+        let mut child = git_command(git_dir)
+            .args(["cat-file", "--batch"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let stdin = child.stdin.take().expect("stdin is piped");
+        let stdout = BufReader::new(child.stdout.take().expect("stdout is piped"));
+        Ok(GitCatFile {
+            child,
+            stdin,
+            stdout,
+        })
+    }
+
+    fn read(&mut self, sha: &str) -> Result<Vec<u8>, OxenError> {
+        writeln!(self.stdin, "{sha}")?;
+        self.stdin.flush()?;
+
+        // <sha> <type> <size>, or <sha> missing
+        let mut header = String::new();
+        self.stdout.read_line(&mut header)?;
+        let size = match header.split_whitespace().collect::<Vec<_>>()[..] {
+            [_, "blob", size] => size.parse::<usize>()?,
+            _ => {
+                return Err(OxenError::basic_str(format!(
+                    "Could not read git object {sha}: {}",
+                    header.trim()
+                )));
+            }
+        };
+        let mut data = vec![0; size];
+        self.stdout.read_exact(&mut data)?;
+        let mut newline = [0; 1];
+        self.stdout.read_exact(&mut newline)?;
+        Ok(data)
+    }
+
+    fn close(self) -> Result<(), OxenError> {
+        let GitCatFile {
+            mut child, stdin, ..
+        } = self;
+        drop(stdin);
+        child.wait()?;
+        Ok(())
+    }
+}
+
+fn git_command(git_dir: &Path) -> Command {
+    let mut command = Command::new("git");
+    command.arg("-C").arg(git_dir);
+    command
+}
+
+fn git_output<I, S>(git_dir: &Path, args: I) -> Result<Vec<u8>, OxenError>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    match git_command(git_dir).args(args).output() {
+        Ok(output) if output.status.success() => Ok(output.stdout),
+        Ok(output) => Err(OxenError::basic_str(format!(
+            "git failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Err(OxenError::basic_str(
+            "Importing from git requires git to be installed",
+        )),
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn git_string<I, S>(git_dir: &Path, args: I) -> Result<String, OxenError>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    Ok(String::from_utf8_lossy(&git_output(git_dir, args)?)
+        .trim()
+        .to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use std::process::Command;
+
+    use crate::error::OxenError;
+    use crate::model::LocalRepository;
+    use crate::repositories;
+    use crate::repositories::import::git::GIT_COMMIT_METADATA_KEY;
+    use crate::test;
+    use crate::util;
+
+    fn git(dir: &Path, args: &[&str]) -> Result<String, OxenError> {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(["-c", "user.name=Ada", "-c", "user.email=ada@oxen.ai"])
+            .args(args)
+            .env("GIT_AUTHOR_DATE", "1700000000 +0000")
+            .env("GIT_COMMITTER_DATE", "1700000000 +0000")
+            .output()?;
+        assert!(output.status.success(), "git {args:?} failed");
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    #[tokio::test]
+    async fn test_import_git_history_branches_and_tags() -> Result<(), OxenError> {
+        test::run_empty_dir_test_async(|dir| async move {
+            let git_dir = dir.join("git");
+            util::fs::create_dir_all(&git_dir)?;
+            git(&git_dir, &["init", "-q", "-b", "main"])?;
+
+            util::fs::create_dir_all(git_dir.join("data"))?;
+            util::fs::write_to_path(git_dir.join("data").join("train.csv"), "text\nhello\n")?;
+            git(&git_dir, &["add", "."])?;
+            git(&git_dir, &["commit", "-q", "-m", "Adding training data"])?;
+            git(&git_dir, &["tag", "v1"])?;
+
+            git(&git_dir, &["checkout", "-q", "-b", "labels"])?;
+            util::fs::write_to_path(git_dir.join("labels.txt"), "cat\ndog\n")?;
+            git(&git_dir, &["add", "."])?;
+            git(&git_dir, &["commit", "-q", "-m", "Adding labels"])?;
+
+            git(&git_dir, &["checkout", "-q", "main"])?;
+            util::fs::remove_file(git_dir.join("data").join("train.csv"))?;
+            util::fs::write_to_path(git_dir.join("README.md"), "# Data")?;
+            git(&git_dir, &["add", "-A"])?;
+            git(&git_dir, &["commit", "-q", "-m", "Moving to a README"])?;
+            let main_sha = git(&git_dir, &["rev-parse", "main"])?;
+
+            let oxen_dir = dir.join("oxen");
+            let import =
+                repositories::import::git(git_dir.to_str().unwrap(), Some(&oxen_dir), false)
+                    .await?;
+            assert_eq!(import.num_commits, 3);
+            assert_eq!(import.branches.len(), 2);
+            assert_eq!(import.tags.len(), 1);
+
+            let repo = LocalRepository::from_dir(&oxen_dir)?;
+            let branches = repositories::branches::list(&repo)?;
+            assert_eq!(branches.len(), 2);
+            let main = repositories::branches::current_branch(&repo)?.unwrap();
+            assert_eq!(main.name, "main");
+
+            let head = repositories::commits::head_commit(&repo)?;
+            assert_eq!(head.message, "Moving to a README");
+            assert_eq!(head.author, "Ada");
+            assert_eq!(head.timestamp.unix_timestamp(), 1700000000);
+            assert_eq!(head.metadata.get(GIT_COMMIT_METADATA_KEY), Some(&main_sha));
+            assert!(oxen_dir.join("README.md").exists());
+            assert!(!oxen_dir.join("data").join("train.csv").exists());
+            assert!(!oxen_dir.join("labels.txt").exists());
+
+            let tag = repositories::tags::get_by_name(&repo, "v1")?.unwrap();
+            let tagged = repositories::commits::get_by_id(&repo, &tag.commit_id)?.unwrap();
+            assert_eq!(tagged.message, "Adding training data");
+
+            Ok(())
+        })
+        .await
+    }
+}
@@ -0,0 +1,522 @@
+//! # oxen import s3
+//!
+//! Snapshot the objects under an S3 prefix into an Oxen repository, with the object keys
+//! relative to the prefix as the file paths.
+//!
+//! The ETag and size of every imported object are kept in `.oxen/s3_import.json`. Running
+//! the import again with `sync` only downloads the objects that were added or changed since,
+//! removes the files whose objects were deleted, and commits the difference, so a bucket
+//! can be snapshotted on a schedule.
+//!
+//! The server syncs with [`sync_to_branch`], which stages the objects in a temporary
+//! workspace instead of the repository's working directory. It only reads the buckets in
+//! `OXEN_S3_IMPORT_ALLOWED_BUCKETS` and the custom endpoints in
+//! `OXEN_S3_IMPORT_ALLOWED_ENDPOINTS`, both comma separated lists.
+//!
+
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+
+use futures::prelude::*;
+use serde::{Deserialize, Serialize};
+use tokio::fs::{self, File};
+
+use crate::constants::{DEFAULT_NUM_WORKERS, OXEN_HIDDEN_DIR};
+use crate::error::OxenError;
+use crate::model::{Commit, LocalRepository, NewCommitBody, User};
+use crate::opts::{CommitOpts, S3ImportOpts};
+use crate::repositories;
+use crate::storage::s3 as s3_storage;
+use crate::util;
+
+/// Metadata key of the `s3://` url a commit was imported from
+pub const S3_SOURCE_METADATA_KEY: &str = "s3_source";
+
+/// Env var with the buckets the server may import from
+pub const ALLOWED_BUCKETS_ENV: &str = "OXEN_S3_IMPORT_ALLOWED_BUCKETS";
+/// Env var with the custom endpoints the server may import from
+pub const ALLOWED_ENDPOINTS_ENV: &str = "OXEN_S3_IMPORT_ALLOWED_ENDPOINTS";
+
+const MANIFEST_FILENAME: &str = "s3_import.json";
+
+#[derive(Debug, Clone)]
+pub struct S3Import {
+    /// Where the Oxen repository is
+    pub path: PathBuf,
+    /// The import commit, None if nothing changed since the last sync
+    pub commit: Option<Commit>,
+    pub num_added: usize,
+    pub num_updated: usize,
+    pub num_removed: usize,
+    /// Bytes downloaded from the bucket
+    pub num_bytes: u64,
+}
+
+/// What the bucket looked like at the last import
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+struct S3ImportManifest {
+    url: String,
+    /// Path in the repository to the object it was downloaded from
+    objects: BTreeMap<String, S3Object>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+struct S3Object {
+    key: String,
+    etag: String,
+    size: i64,
+}
+
+/// Import the objects under `url`, `s3://bucket/prefix`, into a new Oxen repository at
+/// `dst`, or with `sync` bring a repository it was imported into before up to date.
+/// `dst` defaults to the last part of the prefix, or the bucket name, in the current
+/// directory. Credentials come from the default aws credential chain.
+pub async fn s3(
+    url: impl AsRef<str>,
+    dst: Option<&Path>,
+    opts: &S3ImportOpts,
+) -> Result<S3Import, OxenError> {
+    let url = url.as_ref().trim_end_matches('/');
+    let (bucket, prefix) = parse_url(url)?;
+    let dst = match dst {
+        Some(dst) => dst.to_path_buf(),
+        None => {
+            let name = prefix
+                .trim_end_matches('/')
+                .rsplit('/')
+                .find(|part| !part.is_empty())
+                .unwrap_or(bucket.as_str());
+            std::env::current_dir()?.join(name)
+        }
+    };
+
+    let repo = if dst.join(OXEN_HIDDEN_DIR).exists() {
+        if !opts.sync {
+            return Err(OxenError::basic_str(format!(
+                "{dst:?} is already an Oxen repository, sync it to import the objects that changed"
+            )));
+        }
+        LocalRepository::from_dir(&dst)?
+    } else {
+        super::check_empty_dir(&dst)?;
+        util::fs::create_dir_all(&dst)?;
+        repositories::init(util::fs::canonicalize(&dst)?)?
+    };
+    let mut manifest = read_manifest(&repo)?;
+    if !manifest.url.is_empty() && manifest.url != url {
+        return Err(OxenError::basic_str(format!(
+            "{:?} was imported from {}, not {url}",
+            repo.path, manifest.url
+        )));
+    }
+    manifest.url = url.to_string();
+
+    let client = s3_storage::client(opts.region.as_deref(), opts.endpoint.as_deref(), None).await;
+    println!("Listing {url}");
+    let objects = list_objects(&client, &bucket, &prefix).await?;
+    if objects.is_empty() {
+        return Err(OxenError::basic_str(format!(
+            "No objects found under {url}"
+        )));
+    }
+
+    let mut import = S3Import {
+        path: repo.path.clone(),
+        commit: None,
+        num_added: 0,
+        num_updated: 0,
+        num_removed: 0,
+        num_bytes: 0,
+    };
+    let (changed, removed) = diff_objects(&manifest, &objects, &mut import, |path| {
+        Ok(repo.path.join(path).exists())
+    })?;
+    if changed.is_empty() && removed.is_empty() {
+        return Ok(import);
+    }
+
+    println!("Downloading {} objects", changed.len());
+    import.num_bytes = download_all(&client, &bucket, &changed, &repo.path).await?;
+
+    for path in &removed {
+        let full_path = repo.path.join(path);
+        if full_path.is_file() {
+            util::fs::remove_file(&full_path)?;
+            super::remove_empty_parents(&repo, &full_path);
+            repositories::add(&repo, path).await?;
+        }
+    }
+    if !changed.is_empty() {
+        repositories::add_all(&repo, changed.iter().map(|(path, _)| path)).await?;
+    }
+
+    let message = match &opts.message {
+        Some(message) => message.clone(),
+        None => format!("Imported {url}"),
+    };
+    let commit_opts = CommitOpts {
+        metadata: [(S3_SOURCE_METADATA_KEY.to_string(), url.to_string())].into(),
+        ..CommitOpts::default()
+    };
+    import.commit = Some(repositories::commits::commit_with_opts(
+        &repo,
+        &message,
+        &commit_opts,
+    )?);
+
+    manifest.objects = objects;
+    write_manifest(&repo, &manifest)?;
+    Ok(import)
+}
+
+/// Sync the objects under `url` onto a branch of a repository imported into before, or
+/// any repository on the first sync. The changes are staged in a temporary workspace and
+/// committed by `author`, the repository's working directory is never touched. The
+/// bucket and endpoint must be allowed by [`check_allowed`].
+pub async fn sync_to_branch(
+    repo: &LocalRepository,
+    url: impl AsRef<str>,
+    branch_name: impl AsRef<str>,
+    author: &User,
+    opts: &S3ImportOpts,
+) -> Result<S3Import, OxenError> {
+    let url = url.as_ref().trim_end_matches('/');
+    let branch_name = branch_name.as_ref();
+    check_allowed(url, opts.endpoint.as_deref())?;
+    let (bucket, prefix) = parse_url(url)?;
+    let mut manifest = read_manifest(repo)?;
+    if !manifest.url.is_empty() && manifest.url != url {
+        return Err(OxenError::basic_str(format!(
+            "{:?} was imported from {}, not {url}",
+            repo.path, manifest.url
+        )));
+    }
+    manifest.url = url.to_string();
+
+    let branch = repositories::branches::get_by_name(repo, branch_name)?
+        .ok_or(OxenError::local_branch_not_found(branch_name))?;
+    let commit = repositories::commits::get_by_id(repo, &branch.commit_id)?
+        .ok_or(OxenError::commit_id_does_not_exist(&branch.commit_id))?;
+
+    let client = s3_storage::client(opts.region.as_deref(), opts.endpoint.as_deref(), None).await;
+    let objects = list_objects(&client, &bucket, &prefix).await?;
+    if objects.is_empty() {
+        return Err(OxenError::basic_str(format!(
+            "No objects found under {url}"
+        )));
+    }
+
+    let mut import = S3Import {
+        path: repo.path.clone(),
+        commit: None,
+        num_added: 0,
+        num_updated: 0,
+        num_removed: 0,
+        num_bytes: 0,
+    };
+    let (changed, removed) = diff_objects(&manifest, &objects, &mut import, |path| {
+        Ok(repositories::tree::get_file_by_path(repo, &commit, path)?.is_some())
+    })?;
+    if changed.is_empty() && removed.is_empty() {
+        return Ok(import);
+    }
+
+    let workspace = repositories::workspaces::create_temporary(repo, &commit)?;
+    let workspace_dir = workspace.dir();
+    import.num_bytes = download_all(&client, &bucket, &changed, &workspace_dir).await?;
+    for (path, _) in &changed {
+        repositories::workspaces::files::add(&workspace, workspace_dir.join(path)).await?;
+    }
+    for path in &removed {
+        if repositories::tree::get_file_by_path(repo, &commit, path)?.is_some() {
+            repositories::workspaces::files::rm(&workspace, path).await?;
+        }
+    }
+
+    let message = match &opts.message {
+        Some(message) => message.clone(),
+        None => format!("Imported {url}"),
+    };
+    let new_commit = NewCommitBody {
+        message,
+        author: author.name.clone(),
+        email: author.email.clone(),
+    };
+    import.commit = Some(repositories::workspaces::commit_as(
+        &workspace,
+        &new_commit,
+        branch_name,
+        Some(author),
+    )?);
+
+    manifest.objects = objects;
+    write_manifest(repo, &manifest)?;
+    Ok(import)
+}
+
+/// Errors unless the bucket of `url` is in `OXEN_S3_IMPORT_ALLOWED_BUCKETS` and a custom
+/// endpoint is in `OXEN_S3_IMPORT_ALLOWED_ENDPOINTS`. Nothing is allowed if they aren't set.
+pub fn check_allowed(url: impl AsRef<str>, endpoint: Option<&str>) -> Result<(), OxenError> {
+    check_allowed_in(
+        url,
+        endpoint,
+        &env_list(ALLOWED_BUCKETS_ENV),
+        &env_list(ALLOWED_ENDPOINTS_ENV),
+    )
+}
+
+/// Errors unless the bucket of `url` is one of `allowed_buckets` and a custom endpoint is
+/// one of `allowed_endpoints`
+pub fn check_allowed_in(
+    url: impl AsRef<str>,
+    endpoint: Option<&str>,
+    allowed_buckets: &[String],
+    allowed_endpoints: &[String],
+) -> Result<(), OxenError> {
+    let url = url.as_ref();
+    let (bucket, _) = parse_url(url.trim_end_matches('/'))?;
+    if !allowed_buckets.iter().any(|allowed| *allowed == bucket) {
+        return Err(OxenError::basic_str(format!(
+            "Importing from bucket {bucket:?} is not allowed, add it to {ALLOWED_BUCKETS_ENV} on the server"
+        )));
+    }
+    if let Some(endpoint) = endpoint {
+        let endpoint = endpoint.trim_end_matches('/');
+        if !allowed_endpoints
+            .iter()
+            .any(|allowed| allowed.trim_end_matches('/').eq_ignore_ascii_case(endpoint))
+        {
+            return Err(OxenError::basic_str(format!(
+                "Importing from endpoint {endpoint:?} is not allowed, add it to {ALLOWED_ENDPOINTS_ENV} on the server"
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn env_list(name: &str) -> Vec<String> {
+    std::env::var(name)
+        .unwrap_or_default()
+        .split(',')
+        .map(|item| item.trim().to_string())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+/// The objects that were added or changed since the last import and the paths of the
+/// objects that were deleted, counted in `import`. `is_imported` tells whether the file of
+/// an object that didn't change is still there.
+fn diff_objects(
+    manifest: &S3ImportManifest,
+    objects: &BTreeMap<String, S3Object>,
+    import: &mut S3Import,
+    is_imported: impl Fn(&str) -> Result<bool, OxenError>,
+) -> Result<(Vec<(String, S3Object)>, Vec<String>), OxenError> {
+    let mut changed: Vec<(String, S3Object)> = vec![];
+    for (path, object) in objects {
+        match manifest.objects.get(path) {
+            Some(previous) if previous == object && is_imported(path)? => {}
+            Some(_) => {
+                import.num_updated += 1;
+                changed.push((path.clone(), object.clone()));
+            }
+            None => {
+                import.num_added += 1;
+                changed.push((path.clone(), object.clone()));
+            }
+        }
+    }
+    let removed: Vec<String> = manifest
+        .objects
+        .keys()
+        .filter(|path| !objects.contains_key(*path))
+        .cloned()
+        .collect();
+    import.num_removed = removed.len();
+    Ok((changed, removed))
+}
+
+/// Download the objects to their paths under `dir`, returns the bytes downloaded
+async fn download_all(
+    client: &aws_sdk_s3::Client,
+    bucket: &str,
+    objects: &[(String, S3Object)],
+    dir: &Path,
+) -> Result<u64, OxenError> {
+    let sizes: Vec<u64> = stream::iter(objects.iter())
+        .map(|(path, object)| async move {
+            download(client, bucket, &object.key, &dir.join(path)).await
+        })
+        .buffer_unordered(DEFAULT_NUM_WORKERS)
+        .try_collect()
+        .await?;
+    Ok(sizes.iter().sum())
+}
+
+/// The bucket and the key prefix, which is treated as a directory
+fn parse_url(url: &str) -> Result<(String, String), OxenError> {
+    let Some(path) = url.strip_prefix("s3://") else {
+        return Err(OxenError::basic_str(format!(
+            "Invalid S3 url {url:?}, must look like s3://bucket/prefix"
+        )));
+    };
+    let (bucket, prefix) = path.split_once('/').unwrap_or((path, ""));
+    if bucket.is_empty() {
+        return Err(OxenError::basic_str(format!(
+            "Invalid S3 url {url:?}, the bucket is missing"
+        )));
+    }
+    let prefix = match prefix.trim_end_matches('/') {
+        "" => String::new(),
+        prefix => format!("{prefix}/"),
+    };
+    Ok((bucket.to_string(), prefix))
+}
+
+/// Every object under the prefix by the path it is imported to
+async fn list_objects(
+    client: &aws_sdk_s3::Client,
+    bucket: &str,
+    prefix: &str,
+) -> Result<BTreeMap<String, S3Object>, OxenError> {
+    let mut pages = client
+        .list_objects_v2()
+        .bucket(bucket)
+        .prefix(prefix)
+        .into_paginator()
+        .send();
+
+    let mut objects = BTreeMap::new();
+    while let Some(page) = pages.next().await {
+        let page = page.map_err(s3_storage::s3_error)?;
+        for object in page.contents() {
+            let Some(key) = object.key() else {
+                continue;
+            };
+            let path = key.strip_prefix(prefix).unwrap_or(key);
+            // Keys ending in / are folder markers made by the S3 console
+            if path.is_empty() || path.ends_with('/') {
+                continue;
+            }
+            if !is_safe_path(path) {
+                log::warn!("Skipping {key:?}, it can not be written inside the repository");
+                continue;
+            }
+            objects.insert(
+                path.to_string(),
+                S3Object {
+                    key: key.to_string(),
+                    etag: object
+                        .e_tag()
+                        .unwrap_or_default()
+                        .trim_matches('"')
+                        .to_string(),
+                    size: object.size().unwrap_or_default(),
+                },
+            );
+        }
+    }
+    Ok(objects)
+}
+
+/// Keys are arbitrary strings, only import the ones that stay inside the repository
+fn is_safe_path(path: &str) -> bool {
+    let path = Path::new(path);
+    path.components()
+        .all(|component| matches!(component, Component::Normal(_)))
+        && !path.starts_with(OXEN_HIDDEN_DIR)
+}
+
+/// Stream an object into a file, writing to a temporary file first so a failed download
+/// never leaves a partial file behind
+async fn download(
+    client: &aws_sdk_s3::Client,
+    bucket: &str,
+    key: &str,
+    dest_path: &Path,
+) -> Result<u64, OxenError> {
+    let output = client
+        .get_object()
+        .bucket(bucket)
+        .key(key)
+        .send()
+        .await
+        .map_err(s3_storage::s3_error)?;
+
+    if let Some(parent) = dest_path.parent() {
+        fs::create_dir_all(parent).await?;
+    }
+    let file_name = dest_path.file_name().unwrap_or_default().to_string_lossy();
+    let tmp_path = dest_path.with_file_name(format!("{file_name}.part"));
+    let mut body = output.body.into_async_read();
+    let mut file = File::create(&tmp_path).await?;
+    let num_bytes = tokio::io::copy(&mut body, &mut file).await?;
+    fs::rename(&tmp_path, dest_path).await?;
+    Ok(num_bytes)
+}
+
+fn manifest_path(repo: &LocalRepository) -> PathBuf {
+    util::fs::oxen_hidden_dir(&repo.path).join(MANIFEST_FILENAME)
+}
+
+fn read_manifest(repo: &LocalRepository) -> Result<S3ImportManifest, OxenError> {
+    let path = manifest_path(repo);
+    if !path.exists() {
+        return Ok(S3ImportManifest::default());
+    }
+    Ok(serde_json::from_str(&util::fs::read_from_path(&path)?)?)
+}
+
+fn write_manifest(repo: &LocalRepository, manifest: &S3ImportManifest) -> Result<(), OxenError> {
+    util::fs::write_to_path(manifest_path(repo), serde_json::to_string_pretty(manifest)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_allowed_in, parse_url};
+    use crate::error::OxenError;
+
+    #[test]
+    fn test_parse_s3_url() -> Result<(), OxenError> {
+        assert_eq!(
+            parse_url("s3://datasets/images/train")?,
+            ("datasets".to_string(), "images/train/".to_string())
+        );
+        assert_eq!(
+            parse_url("s3://datasets/images/")?,
+            ("datasets".to_string(), "images/".to_string())
+        );
+        assert_eq!(
+            parse_url("s3://datasets")?,
+            ("datasets".to_string(), "".to_string())
+        );
+        assert!(parse_url("https://datasets/images").is_err());
+        assert!(parse_url("s3:///images").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_allowed_buckets_and_endpoints() -> Result<(), OxenError> {
+        let buckets = vec!["datasets".to_string(), "images".to_string()];
+        let endpoints = vec!["https://minio.example.com/".to_string()];
+        check_allowed_in("s3://datasets/train", None, &buckets, &endpoints)?;
+        check_allowed_in(
+            "s3://images",
+            Some("https://minio.example.com"),
+            &buckets,
+            &endpoints,
+        )?;
+        assert!(check_allowed_in("s3://secrets/keys", None, &buckets, &endpoints).is_err());
+        assert!(check_allowed_in(
+            "s3://datasets/train",
+            Some("http://169.254.169.254"),
+            &buckets,
+            &endpoints
+        )
+        .is_err());
+
+        // Nothing is allowed without allow lists
+        assert!(check_allowed_in("s3://datasets/train", None, &[], &[]).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,439 @@
+//! # Jobs
+//!
+//! Actions the server runs on a repository on a cron schedule, such as syncing an S3 prefix
+//! or validating a branch, so recurring dataset refreshes don't need an external scheduler.
+//! Jobs are stored in `.oxen/jobs.toml` and the history of each job's runs in
+//! `.oxen/job_runs/<job_id>.json`. Schedules are in UTC, a job that was due while the server
+//! was down runs once when it comes back.
+//!
+//! Imports only read the buckets and endpoints the server allows, see
+//! [`repositories::import::s3::check_allowed`], and are committed through a temporary
+//! workspace so the repository's working directory is never written to.
+//!
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::{LazyLock, Mutex};
+
+use cron::Schedule;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+use crate::constants::{JOBS_FILE, JOB_RUNS_DIR};
+use crate::error::OxenError;
+use crate::model::{Job, JobAction, JobRun, JobRunStatus, LocalRepository, User};
+use crate::opts::S3ImportOpts;
+use crate::repositories;
+use crate::util;
+use crate::view::{JobNew, JobStatus};
+
+/// Runs kept in a job's history, older ones are dropped
+const MAX_JOB_RUNS: usize = 100;
+
+/// Jobs and run histories are read, changed and written back under this lock
+static JOBS_FILES: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+/// `<repo path>:<job id>` of the jobs running in this process, so a slow run is never
+/// started again before it finishes
+static RUNNING: LazyLock<Mutex<HashSet<String>>> = LazyLock::new(|| Mutex::new(HashSet::new()));
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct JobsFile {
+    #[serde(default)]
+    jobs: Vec<Job>,
+}
+
+fn jobs_path(repo: &LocalRepository) -> PathBuf {
+    util::fs::oxen_hidden_dir(&repo.path).join(JOBS_FILE)
+}
+
+fn runs_path(repo: &LocalRepository, job_id: &str) -> PathBuf {
+    util::fs::oxen_hidden_dir(&repo.path)
+        .join(JOB_RUNS_DIR)
+        .join(format!("{job_id}.json"))
+}
+
+fn read_jobs(repo: &LocalRepository) -> Result<JobsFile, OxenError> {
+    let path = jobs_path(repo);
+    if !path.exists() {
+        return Ok(JobsFile::default());
+    }
+    let data = util::fs::read_from_path(&path)?;
+    toml::from_str(&data)
+        .map_err(|err| OxenError::basic_str(format!("Could not parse {path:?}: {err}")))
+}
+
+fn write_jobs(repo: &LocalRepository, jobs: &JobsFile) -> Result<(), OxenError> {
+    util::fs::write_to_path(jobs_path(repo), toml::to_string(jobs)?)
+}
+
+/// Parse a cron expression. The five field crontab format is accepted as well as the
+/// six and seven field formats that start with seconds.
+pub fn parse_schedule(expression: impl AsRef<str>) -> Result<Schedule, OxenError> {
+    let expression = expression.as_ref().trim();
+    let expression = if expression.split_whitespace().count() == 5 {
+        format!("0 {expression}")
+    } else {
+        expression.to_string()
+    };
+    Schedule::from_str(&expression)
+        .map_err(|err| OxenError::basic_str(format!("Invalid cron schedule {expression:?}: {err}")))
+}
+
+/// When the job is next due after `after`, None if the schedule has no more times
+pub fn next_run_at(job: &Job, after: OffsetDateTime) -> Result<Option<OffsetDateTime>, OxenError> {
+    let schedule = parse_schedule(&job.schedule)?;
+    let Some(after) = chrono::DateTime::from_timestamp(after.unix_timestamp(), 0) else {
+        return Ok(None);
+    };
+    match schedule.after(&after).next() {
+        Some(next) => OffsetDateTime::from_unix_timestamp(next.timestamp())
+            .map(Some)
+            .map_err(|err| OxenError::basic_str(format!("Invalid run time {next}: {err}"))),
+        None => Ok(None),
+    }
+}
+
+/// List the jobs of the repository
+pub fn list(repo: &LocalRepository) -> Result<Vec<Job>, OxenError> {
+    Ok(read_jobs(repo)?.jobs)
+}
+
+pub fn get(repo: &LocalRepository, id: impl AsRef<str>) -> Result<Option<Job>, OxenError> {
+    let id = id.as_ref();
+    Ok(list(repo)?.into_iter().find(|job| job.id == id))
+}
+
+/// The job with when it next runs and its latest run
+pub fn status(repo: &LocalRepository, job: Job) -> Result<JobStatus, OxenError> {
+    let last_run = runs(repo, &job.id)?.into_iter().next();
+    let next_run_at = if job.enabled {
+        let after = last_run
+            .as_ref()
+            .map(|run| run.started_at)
+            .unwrap_or(job.created_at);
+        next_run_at(&job, after)?
+    } else {
+        None
+    };
+    Ok(JobStatus {
+        job,
+        next_run_at,
+        last_run,
+    })
+}
+
+/// Register a job created by `created_by`, checking its schedule and action first
+pub fn add(repo: &LocalRepository, new_job: &JobNew, created_by: &User) -> Result<Job, OxenError> {
+    if new_job.name.trim().is_empty() {
+        return Err(OxenError::basic_str("Job name must not be empty"));
+    }
+    parse_schedule(&new_job.schedule)?;
+    match &new_job.action {
+        JobAction::ImportS3 { url, endpoint, .. } => {
+            repositories::import::s3::check_allowed(url, endpoint.as_deref())?;
+        }
+        JobAction::Validate { branch } => {
+            if repositories::branches::get_by_name(repo, branch)?.is_none() {
+                return Err(OxenError::local_branch_not_found(branch));
+            }
+        }
+    }
+
+    let job = Job {
+        id: uuid::Uuid::new_v4().to_string(),
+        name: new_job.name.trim().to_string(),
+        schedule: new_job.schedule.trim().to_string(),
+        action: new_job.action.clone(),
+        enabled: new_job.enabled.unwrap_or(true),
+        created_at: OffsetDateTime::now_utc(),
+        created_by: Some(created_by.clone()),
+    };
+    let _lock = JOBS_FILES.lock().unwrap();
+    let mut jobs = read_jobs(repo)?;
+    jobs.jobs.push(job.clone());
+    write_jobs(repo, &jobs)?;
+    Ok(job)
+}
+
+/// Remove a job and its run history
+pub fn remove(repo: &LocalRepository, id: impl AsRef<str>) -> Result<Job, OxenError> {
+    let id = id.as_ref();
+    let _lock = JOBS_FILES.lock().unwrap();
+    let mut jobs = read_jobs(repo)?;
+    let Some(index) = jobs.jobs.iter().position(|job| job.id == id) else {
+        return Err(OxenError::resource_not_found(format!("job {id}")));
+    };
+    let job = jobs.jobs.remove(index);
+    write_jobs(repo, &jobs)?;
+    let runs_path = runs_path(repo, id);
+    if runs_path.exists() {
+        util::fs::remove_file(&runs_path)?;
+    }
+    Ok(job)
+}
+
+/// The runs of a job, newest first
+pub fn runs(repo: &LocalRepository, job_id: impl AsRef<str>) -> Result<Vec<JobRun>, OxenError> {
+    let path = runs_path(repo, job_id.as_ref());
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    Ok(serde_json::from_str(&util::fs::read_from_path(&path)?)?)
+}
+
+/// Add the run to its job's history, or replace it if it is already there
+fn save_run(repo: &LocalRepository, run: &JobRun) -> Result<(), OxenError> {
+    let _lock = JOBS_FILES.lock().unwrap();
+    let mut runs = runs(repo, &run.job_id)?;
+    match runs.iter_mut().find(|other| other.id == run.id) {
+        Some(other) => *other = run.clone(),
+        None => runs.insert(0, run.clone()),
+    }
+    runs.truncate(MAX_JOB_RUNS);
+
+    let path = runs_path(repo, &run.job_id);
+    if let Some(parent) = path.parent() {
+        util::fs::create_dir_all(parent)?;
+    }
+    util::fs::write_to_path(&path, serde_json::to_string_pretty(&runs)?)
+}
+
+/// Whether an enabled job's next scheduled time since its last run has passed
+pub fn is_due(repo: &LocalRepository, job: &Job, now: OffsetDateTime) -> Result<bool, OxenError> {
+    if !job.enabled {
+        return Ok(false);
+    }
+    let last_run_at = runs(repo, &job.id)?
+        .first()
+        .map(|run| run.started_at)
+        .unwrap_or(job.created_at);
+    Ok(next_run_at(job, last_run_at)?.is_some_and(|next| next <= now))
+}
+
+fn running_key(repo: &LocalRepository, job: &Job) -> String {
+    format!("{}:{}", repo.path.display(), job.id)
+}
+
+/// Record a new run of the job as running. Fails if the job is already running.
+pub fn start(repo: &LocalRepository, job: &Job) -> Result<JobRun, OxenError> {
+    if !RUNNING.lock().unwrap().insert(running_key(repo, job)) {
+        return Err(OxenError::basic_str(format!(
+            "Job {} is already running",
+            job.name
+        )));
+    }
+    let run = JobRun {
+        id: uuid::Uuid::new_v4().to_string(),
+        job_id: job.id.clone(),
+        status: JobRunStatus::Running,
+        started_at: OffsetDateTime::now_utc(),
+        finished_at: None,
+        message: String::new(),
+        commit_id: None,
+    };
+    if let Err(err) = save_run(repo, &run) {
+        RUNNING.lock().unwrap().remove(&running_key(repo, job));
+        return Err(err);
+    }
+    Ok(run)
+}
+
+/// Run the action of a run returned by [`start`] and record how it went
+pub async fn finish(
+    repo: &LocalRepository,
+    job: &Job,
+    mut run: JobRun,
+) -> Result<JobRun, OxenError> {
+    match run_action(repo, job).await {
+        Ok((message, commit_id)) => {
+            run.status = JobRunStatus::Succeeded;
+            run.message = message;
+            run.commit_id = commit_id;
+        }
+        Err(err) => {
+            log::error!("Job {} on {:?} failed: {}", job.name, repo.path, err);
+            run.status = JobRunStatus::Failed;
+            run.message = err.to_string();
+        }
+    }
+    run.finished_at = Some(OffsetDateTime::now_utc());
+    let result = save_run(repo, &run);
+    RUNNING.lock().unwrap().remove(&running_key(repo, job));
+    result?;
+    Ok(run)
+}
+
+/// Run a job now, whether or not it is due
+pub async fn run(repo: &LocalRepository, job: &Job) -> Result<JobRun, OxenError> {
+    let run = start(repo, job)?;
+    finish(repo, job, run).await
+}
+
+/// What the job's action did and the commit it made
+async fn run_action(
+    repo: &LocalRepository,
+    job: &Job,
+) -> Result<(String, Option<String>), OxenError> {
+    match &job.action {
+        JobAction::ImportS3 {
+            url,
+            region,
+            endpoint,
+        } => {
+            let Some(author) = &job.created_by else {
+                return Err(OxenError::basic_str(format!(
+                    "Job {} has no creator to author its commits, create it again",
+                    job.name
+                )));
+            };
+            let branch = repositories::branches::current_branch(repo)?
+                .ok_or(OxenError::basic_str("The repository has no current branch"))?;
+            let opts = S3ImportOpts {
+                sync: true,
+                region: region.clone(),
+                endpoint: endpoint.clone(),
+                message: None,
+            };
+            let import =
+                repositories::import::s3::sync_to_branch(repo, url, &branch.name, author, &opts)
+                    .await?;
+            let message = format!(
+                "Added {}, updated {} and removed {} files from {url}",
+                import.num_added, import.num_updated, import.num_removed
+            );
+            Ok((message, import.commit.map(|commit| commit.id)))
+        }
+        JobAction::Validate { branch } => {
+            let branch = repositories::branches::get_by_name(repo, branch)?
+                .ok_or(OxenError::local_branch_not_found(branch))?;
+            let commit = repositories::commits::get_by_id(repo, &branch.commit_id)?
+                .ok_or(OxenError::commit_id_does_not_exist(&branch.commit_id))?;
+            let report = repositories::checks::run_on_commit(repo, &commit)?;
+            if !report.is_ok() {
+                let failures: Vec<String> = report
+                    .failures
+                    .iter()
+                    .map(|failure| failure.to_string())
+                    .collect();
+                return Err(OxenError::basic_str(format!(
+                    "{} checks failed in {} files on commit {}:\n{}",
+                    report.failures.len(),
+                    report.files_checked,
+                    commit.id,
+                    failures.join("\n")
+                )));
+            }
+            let message = format!(
+                "{} files on commit {} passed the checks",
+                report.files_checked, commit.id
+            );
+            Ok((message, None))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::{Duration, OffsetDateTime};
+
+    use crate::constants::DEFAULT_BRANCH_NAME;
+    use crate::error::OxenError;
+    use crate::model::{JobAction, JobRunStatus, User};
+    use crate::repositories;
+    use crate::test;
+    use crate::view::JobNew;
+
+    #[test]
+    fn test_parse_schedule() -> Result<(), OxenError> {
+        assert!(repositories::jobs::parse_schedule("0 3 * * *").is_ok());
+        assert!(repositories::jobs::parse_schedule("30 0 3 * * *").is_ok());
+        assert!(repositories::jobs::parse_schedule("every day").is_err());
+        Ok(())
+    }
+
+    fn admin() -> User {
+        User {
+            name: "Ox".to_string(),
+            email: "ox@oxen.ai".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_import_jobs_only_read_allowed_buckets() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            std::env::set_var(repositories::import::s3::ALLOWED_BUCKETS_ENV, "public-data");
+            let import = |url: &str, endpoint: Option<&str>| JobNew {
+                name: "nightly import".to_string(),
+                schedule: "0 3 * * *".to_string(),
+                action: JobAction::ImportS3 {
+                    url: url.to_string(),
+                    region: None,
+                    endpoint: endpoint.map(|endpoint| endpoint.to_string()),
+                },
+                enabled: None,
+            };
+
+            let job =
+                repositories::jobs::add(&repo, &import("s3://public-data/images", None), &admin())?;
+            assert_eq!(job.created_by, Some(admin()));
+            assert!(
+                repositories::jobs::add(&repo, &import("s3://private-data", None), &admin())
+                    .is_err()
+            );
+            assert!(repositories::jobs::add(
+                &repo,
+                &import("s3://public-data/images", Some("http://10.0.0.7:9000")),
+                &admin()
+            )
+            .is_err());
+            assert_eq!(repositories::jobs::list(&repo)?, vec![job]);
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_validate_job_runs_when_due() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed_async(|repo| async move {
+            let job = repositories::jobs::add(
+                &repo,
+                &JobNew {
+                    name: "nightly validation".to_string(),
+                    schedule: "0 3 * * *".to_string(),
+                    action: JobAction::Validate {
+                        branch: DEFAULT_BRANCH_NAME.to_string(),
+                    },
+                    enabled: None,
+                },
+                &admin(),
+            )?;
+            assert_eq!(repositories::jobs::list(&repo)?, vec![job.clone()]);
+
+            let next = repositories::jobs::next_run_at(&job, job.created_at)?.unwrap();
+            assert_eq!(next.hour(), 3);
+            assert!(!repositories::jobs::is_due(
+                &repo,
+                &job,
+                next - Duration::seconds(1)
+            )?);
+            assert!(repositories::jobs::is_due(&repo, &job, next)?);
+
+            let run = repositories::jobs::run(&repo, &job).await?;
+            assert_eq!(run.status, JobRunStatus::Succeeded);
+            assert_eq!(repositories::jobs::runs(&repo, &job.id)?, vec![run]);
+            assert!(!repositories::jobs::is_due(
+                &repo,
+                &job,
+                OffsetDateTime::now_utc()
+            )?);
+
+            repositories::jobs::remove(&repo, &job.id)?;
+            assert!(repositories::jobs::list(&repo)?.is_empty());
+            assert!(repositories::jobs::runs(&repo, &job.id)?.is_empty());
+            Ok(())
+        })
+        .await
+    }
+}
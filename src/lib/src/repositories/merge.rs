@@ -1,9 +1,10 @@
 use std::path::{Path, PathBuf};
 
+use crate::config::MergeStrategy;
 use crate::core;
 use crate::core::versions::MinOxenVersion;
 use crate::error::OxenError;
-use crate::model::merge_conflict::MergeConflict;
+use crate::model::merge_conflict::{MergeConflict, NodeMergeConflict, RowResolution};
 use crate::model::Commit;
 use crate::model::{Branch, LocalRepository};
 
@@ -51,6 +52,31 @@ pub fn mark_conflict_as_resolved(repo: &LocalRepository, path: &Path) -> Result<
     }
 }
 
+/// Resolves the row conflicts of a tabular file with one resolution per conflicting row, then stages the file
+pub async fn resolve_row_conflicts(
+    repo: &LocalRepository,
+    path: &Path,
+    resolutions: &[RowResolution],
+) -> Result<(), OxenError> {
+    match repo.min_version() {
+        MinOxenVersion::V0_10_0 => panic!("v0.10.0 no longer supported"),
+        _ => core::v_latest::merge::resolve_row_conflicts(repo, path, resolutions).await,
+    }
+}
+
+/// Resolves a conflicting file as a whole by taking our version, their version or the union of
+/// both, then stages the file
+pub async fn resolve_conflict(
+    repo: &LocalRepository,
+    path: &Path,
+    strategy: MergeStrategy,
+) -> Result<(), OxenError> {
+    match repo.min_version() {
+        MinOxenVersion::V0_10_0 => panic!("v0.10.0 no longer supported"),
+        _ => core::v_latest::merge::resolve_conflict(repo, path, strategy).await,
+    }
+}
+
 pub async fn can_merge_commits(
     repo: &LocalRepository,
     base_commit: &Commit,
@@ -112,6 +138,22 @@ pub async fn list_conflicts_between_commits(
     }
 }
 
+/// The conflicts merging `merge_commit` into `base_commit` would run into, with the rows
+/// of tabular files that could not be merged automatically
+pub async fn find_conflicts_between_commits(
+    repo: &LocalRepository,
+    base_commit: &Commit,
+    merge_commit: &Commit,
+) -> Result<Vec<NodeMergeConflict>, OxenError> {
+    match repo.min_version() {
+        MinOxenVersion::V0_10_0 => panic!("v0.10.0 no longer supported"),
+        _ => {
+            core::v_latest::merge::find_conflicts_between_commits(repo, base_commit, merge_commit)
+                .await
+        }
+    }
+}
+
 pub async fn merge_into_base(
     repo: &LocalRepository,
     merge_branch: &Branch,
@@ -133,6 +175,18 @@ pub async fn merge(
     }
 }
 
+/// Merge into the current branch, resolving conflicts with the given strategy
+pub async fn merge_with_strategy(
+    repo: &LocalRepository,
+    branch_name: impl AsRef<str>,
+    strategy: Option<MergeStrategy>,
+) -> Result<Option<Commit>, OxenError> {
+    match repo.min_version() {
+        MinOxenVersion::V0_10_0 => panic!("v0.10.0 no longer supported"),
+        _ => core::v_latest::merge::merge_with_strategy(repo, branch_name, strategy).await,
+    }
+}
+
 pub async fn merge_commit_into_base(
     repo: &LocalRepository,
     merge_commit: &Commit,
@@ -208,16 +262,55 @@ mod tests {
 
     use std::path::Path;
 
+    use crate::config::{MergeConfig, MergeRule, MergeStrategy};
     use crate::core::df::tabular;
     use crate::core::merge::node_merge_conflict_reader::NodeMergeConflictReader;
 
     use crate::error::OxenError;
+    use crate::model::merge_conflict::RowResolution;
     use crate::model::{Commit, LocalRepository};
     use crate::opts::DFOpts;
     use crate::repositories;
     use crate::test;
     use crate::util;
 
+    const DOG_2_ROW: &str = "train/dog_2.jpg,dog,7.0,29.5,246,247";
+
+    // dog_1 appears twice in bounding_box.csv, so the file alone is not a unique key
+    fn set_bbox_merge_keys(repo: &mut LocalRepository, path: &Path) -> Result<(), OxenError> {
+        let mut rule = MergeRule::new(path.to_string_lossy());
+        rule.keys = Some(vec![String::from("file"), String::from("min_x")]);
+        repo.set_merge_config(Some(MergeConfig { rules: vec![rule] }));
+        repo.save()
+    }
+
+    fn replace_in_file(path: &Path, from: &str, to: &str) -> Result<(), OxenError> {
+        let contents = util::fs::read_from_path(path)?;
+        util::fs::write_to_path(path, contents.replace(from, to))
+    }
+
+    // Both branches modify a.txt, returns the name of the branch to merge in
+    async fn populate_conflicting_txt_repo(repo: &LocalRepository) -> Result<String, OxenError> {
+        let a_branch = repositories::branches::current_branch(repo)?.unwrap();
+        let a_path = repo.path.join("a.txt");
+        util::fs::write_to_path(&a_path, "a")?;
+        repositories::add(repo, &a_path).await?;
+        repositories::commit(repo, "Committing a.txt file")?;
+
+        let merge_branch_name = "B";
+        repositories::branches::create_checkout(repo, merge_branch_name)?;
+        test::modify_txt_file(&a_path, "a modified from branch")?;
+        repositories::add(repo, &a_path).await?;
+        repositories::commit(repo, "Modifying a.txt on branch")?;
+
+        repositories::checkout(repo, &a_branch.name).await?;
+        test::modify_txt_file(&a_path, "a modified from main line")?;
+        repositories::add(repo, &a_path).await?;
+        repositories::commit(repo, "Modifying a.txt on main line")?;
+
+        Ok(merge_branch_name.to_string())
+    }
+
     async fn populate_threeway_merge_repo(
         repo: &LocalRepository,
         merge_branch_name: &str,
@@ -720,44 +813,143 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_command_merge_dataframe_conflict_both_added_rows_checkout_theirs(
-    ) -> Result<(), OxenError> {
+    async fn test_command_merge_dataframe_both_added_rows_merges_rows() -> Result<(), OxenError> {
         test::run_training_data_repo_test_fully_committed_async(|repo| async move {
             let og_branch = repositories::branches::current_branch(&repo)?.unwrap();
 
+            let bbox_filename = Path::new("annotations")
+                .join("train")
+                .join("bounding_box.csv");
+            let bbox_file = repo.path.join(&bbox_filename);
+
             // Add a more rows on this branch
             let branch_name = "ox-add-rows";
             repositories::branches::create_checkout(&repo, branch_name)?;
 
+            let row_from_branch = "train/cat_3.jpg,cat,41.0,31.5,410,427";
+            let bbox_file = test::append_line_txt_file(bbox_file, row_from_branch)?;
+
+            repositories::add(&repo, &bbox_file).await?;
+            repositories::commit(&repo, "Adding new annotation as an Ox on a branch.")?;
+
+            // Add a more rows on the main branch
+            repositories::checkout(&repo, og_branch.name).await?;
+
+            let row_from_main = "train/dog_4.jpg,dog,52.0,62.5,256,429";
+            let bbox_file = test::append_line_txt_file(bbox_file, row_from_main)?;
+
+            repositories::add(&repo, &bbox_file).await?;
+            repositories::commit(&repo, "Adding new annotation on main branch")?;
+
+            // Rows added on both sides do not conflict, so we get a merge commit
+            let commit = repositories::merge::merge(&repo, branch_name).await?;
+            assert!(commit.is_some());
+
+            let status = repositories::status(&repo)?;
+            assert_eq!(status.merge_conflicts.len(), 0);
+
+            // 6 original rows, plus one from each branch
+            let df = tabular::read_df(&bbox_file, DFOpts::empty())?;
+            assert_eq!(df.height(), 8);
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_command_merge_dataframe_keyed_rows_different_cells_merges(
+    ) -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed_async(|mut repo| async move {
+            let og_branch = repositories::branches::current_branch(&repo)?.unwrap();
+
+            let bbox_filename = Path::new("annotations")
+                .join("train")
+                .join("bounding_box.csv");
+            let bbox_file = repo.path.join(&bbox_filename);
+            set_bbox_merge_keys(&mut repo, &bbox_filename)?;
+
+            // Change the width of dog_2 on this branch
+            let branch_name = "ox-edit-width";
+            repositories::branches::create_checkout(&repo, branch_name)?;
+            replace_in_file(
+                &bbox_file,
+                DOG_2_ROW,
+                "train/dog_2.jpg,dog,7.0,29.5,300,247",
+            )?;
+
+            repositories::add(&repo, &bbox_file).await?;
+            repositories::commit(&repo, "Changing the width on a branch.")?;
+
+            // Change the height of dog_2 on the main branch
+            repositories::checkout(&repo, og_branch.name).await?;
+            replace_in_file(
+                &bbox_file,
+                DOG_2_ROW,
+                "train/dog_2.jpg,dog,7.0,29.5,246,300",
+            )?;
+
+            repositories::add(&repo, &bbox_file).await?;
+            repositories::commit(&repo, "Changing the height on main branch")?;
+
+            let commit = repositories::merge::merge(&repo, branch_name).await?;
+            assert!(commit.is_some());
+
+            // Both edits end up in the dog_2 row
+            let df = tabular::read_df(&bbox_file, DFOpts::empty())?;
+            assert_eq!(df.height(), 6);
+            assert_eq!(df.column("width")?.get(2)?.to_string(), "300");
+            assert_eq!(df.column("height")?.get(2)?.to_string(), "300");
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_command_merge_dataframe_conflict_keyed_row_checkout_theirs(
+    ) -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed_async(|mut repo| async move {
+            let og_branch = repositories::branches::current_branch(&repo)?.unwrap();
+
             let bbox_filename = Path::new("annotations")
                 .join("train")
                 .join("bounding_box.csv");
             let bbox_file = repo.path.join(&bbox_filename);
-            let bbox_file =
-                test::append_line_txt_file(bbox_file, "train/cat_3.jpg,cat,41.0,31.5,410,427")?;
+            set_bbox_merge_keys(&mut repo, &bbox_filename)?;
+
+            // Change the width of dog_2 on this branch
+            let branch_name = "ox-edit-width";
+            repositories::branches::create_checkout(&repo, branch_name)?;
+            replace_in_file(
+                &bbox_file,
+                DOG_2_ROW,
+                "train/dog_2.jpg,dog,7.0,29.5,300,247",
+            )?;
             let their_branch_contents = util::fs::read_from_path(&bbox_file)?;
 
             repositories::add(&repo, &bbox_file).await?;
-            repositories::commit(&repo, "Adding new annotation as an Ox on a branch.")?;
+            repositories::commit(&repo, "Changing the width on a branch.")?;
 
-            // Add a more rows on the main branch
+            // Change the same cell on the main branch
             repositories::checkout(&repo, og_branch.name).await?;
-
-            let bbox_file =
-                test::append_line_txt_file(bbox_file, "train/dog_4.jpg,dog,52.0,62.5,256,429")?;
+            replace_in_file(
+                &bbox_file,
+                DOG_2_ROW,
+                "train/dog_2.jpg,dog,7.0,29.5,250,247",
+            )?;
 
             repositories::add(&repo, &bbox_file).await?;
-            repositories::commit(&repo, "Adding new annotation on main branch")?;
+            repositories::commit(&repo, "Changing the width on main branch")?;
 
             // Try to merge in the changes
             repositories::merge::merge(&repo, branch_name).await?;
 
-            // We should have a conflict....
-            println!("status plz");
-            let status = repositories::status(&repo)?;
-            assert_eq!(status.merge_conflicts.len(), 1);
-
-            println!("checkout theirs plz");
+            // We should have a conflict on the single row
+            let conflicts = repositories::merge::list_conflicts(&repo)?;
+            assert_eq!(conflicts.len(), 1);
+            assert_eq!(conflicts[0].row_conflicts.len(), 1);
+            assert_eq!(conflicts[0].row_conflicts[0].key["file"], "train/dog_2.jpg");
 
             // Run repositories::checkout::checkout_theirs() and make sure their changes get kept
             repositories::checkout::checkout_theirs(&repo, &bbox_filename).await?;
@@ -770,36 +962,93 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_command_merge_dataframe_conflict_both_added_rows_combine_uniq(
-    ) -> Result<(), OxenError> {
-        test::run_training_data_repo_test_fully_committed_async(|repo| async move {
+    async fn test_merge_resolve_row_conflicts_theirs() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed_async(|mut repo| async move {
             let og_branch = repositories::branches::current_branch(&repo)?.unwrap();
 
             let bbox_filename = Path::new("annotations")
                 .join("train")
                 .join("bounding_box.csv");
             let bbox_file = repo.path.join(&bbox_filename);
+            set_bbox_merge_keys(&mut repo, &bbox_filename)?;
 
-            // Add a more rows on this branch
-            let branch_name = "ox-add-rows";
+            let branch_name = "ox-edit-width";
             repositories::branches::create_checkout(&repo, branch_name)?;
+            replace_in_file(
+                &bbox_file,
+                DOG_2_ROW,
+                "train/dog_2.jpg,dog,7.0,29.5,300,247",
+            )?;
+            repositories::add(&repo, &bbox_file).await?;
+            repositories::commit(&repo, "Changing the width on a branch.")?;
 
-            // Add in a line in this branch
-            let row_from_branch = "train/cat_3.jpg,cat,41.0,31.5,410,427";
-            let bbox_file = test::append_line_txt_file(bbox_file, row_from_branch)?;
+            repositories::checkout(&repo, og_branch.name).await?;
+            replace_in_file(
+                &bbox_file,
+                DOG_2_ROW,
+                "train/dog_2.jpg,dog,7.0,29.5,250,247",
+            )?;
+            repositories::add(&repo, &bbox_file).await?;
+            repositories::commit(&repo, "Changing the width on main branch")?;
+
+            repositories::merge::merge(&repo, branch_name).await?;
+            let conflicts = repositories::merge::list_conflicts(&repo)?;
+            assert_eq!(conflicts[0].row_conflicts.len(), 1);
+
+            repositories::merge::resolve_row_conflicts(
+                &repo,
+                &bbox_filename,
+                &[RowResolution::Theirs],
+            )
+            .await?;
+
+            // The file is staged and no longer in conflict
+            let status = repositories::status(&repo)?;
+            assert_eq!(status.merge_conflicts.len(), 0);
+
+            let df = tabular::read_df(&bbox_file, DFOpts::empty())?;
+            assert_eq!(df.height(), 6);
+            assert_eq!(df.column("width")?.get(2)?.to_string(), "300");
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_command_merge_dataframe_conflict_keyed_row_combine_uniq() -> Result<(), OxenError>
+    {
+        test::run_training_data_repo_test_fully_committed_async(|mut repo| async move {
+            let og_branch = repositories::branches::current_branch(&repo)?.unwrap();
+
+            let bbox_filename = Path::new("annotations")
+                .join("train")
+                .join("bounding_box.csv");
+            let bbox_file = repo.path.join(&bbox_filename);
+            set_bbox_merge_keys(&mut repo, &bbox_filename)?;
+
+            // Change the width of dog_2 on this branch
+            let branch_name = "ox-edit-width";
+            repositories::branches::create_checkout(&repo, branch_name)?;
+            replace_in_file(
+                &bbox_file,
+                DOG_2_ROW,
+                "train/dog_2.jpg,dog,7.0,29.5,300,247",
+            )?;
 
-            // Add the changes
             repositories::add(&repo, &bbox_file).await?;
-            repositories::commit(&repo, "Adding new annotation as an Ox on a branch.")?;
+            repositories::commit(&repo, "Changing the width on a branch.")?;
 
-            // Add a more rows on the main branch
+            // Change the same cell on the main branch
             repositories::checkout(&repo, og_branch.name).await?;
-
-            let row_from_main = "train/dog_4.jpg,dog,52.0,62.5,256,429";
-            let bbox_file = test::append_line_txt_file(bbox_file, row_from_main)?;
+            replace_in_file(
+                &bbox_file,
+                DOG_2_ROW,
+                "train/dog_2.jpg,dog,7.0,29.5,250,247",
+            )?;
 
             repositories::add(&repo, &bbox_file).await?;
-            repositories::commit(&repo, "Adding new annotation on main branch")?;
+            repositories::commit(&repo, "Changing the width on main branch")?;
 
             // Try to merge in the changes
             repositories::merge::merge(&repo, branch_name).await?;
@@ -808,12 +1057,220 @@ mod tests {
             let status = repositories::status(&repo)?;
             assert_eq!(status.merge_conflicts.len(), 1);
 
-            // Run repositories::checkout::checkout_theirs() and make sure their changes get kept
+            // Both versions of the conflicting row are kept
             repositories::checkout::checkout_combine(&repo, bbox_filename)?;
             let df = tabular::read_df(&bbox_file, DFOpts::empty())?;
+            assert_eq!(df.height(), 7);
 
-            // This doesn't guarantee order, but let's make sure we have 7 annotations now
-            assert_eq!(df.height(), 8);
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_merge_strategy_theirs_takes_their_file() -> Result<(), OxenError> {
+        test::run_one_commit_local_repo_test_async(|repo| async move {
+            let merge_branch_name = populate_conflicting_txt_repo(&repo).await?;
+
+            let commit = repositories::merge::merge_with_strategy(
+                &repo,
+                &merge_branch_name,
+                Some(MergeStrategy::Theirs),
+            )
+            .await?;
+            assert!(commit.is_some());
+
+            let contents = util::fs::read_from_path(repo.path.join("a.txt"))?;
+            assert_eq!(contents, "a modified from branch");
+
+            let conflicts = repositories::merge::list_conflicts(&repo)?;
+            assert!(conflicts.is_empty());
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_merge_strategy_union_keeps_both_lines() -> Result<(), OxenError> {
+        test::run_one_commit_local_repo_test_async(|repo| async move {
+            let merge_branch_name = populate_conflicting_txt_repo(&repo).await?;
+
+            let commit = repositories::merge::merge_with_strategy(
+                &repo,
+                &merge_branch_name,
+                Some(MergeStrategy::Union),
+            )
+            .await?;
+            assert!(commit.is_some());
+
+            let contents = util::fs::read_from_path(repo.path.join("a.txt"))?;
+            assert_eq!(
+                contents,
+                "a modified from main line\na modified from branch\n"
+            );
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_resolve_conflict_takes_side_and_stages() -> Result<(), OxenError> {
+        test::run_one_commit_local_repo_test_async(|repo| async move {
+            let merge_branch_name = populate_conflicting_txt_repo(&repo).await?;
+            let commit = repositories::merge::merge(&repo, &merge_branch_name).await?;
+            assert!(commit.is_none());
+            assert_eq!(repositories::merge::list_conflicts(&repo)?.len(), 1);
+
+            repositories::merge::resolve_conflict(&repo, Path::new("a.txt"), MergeStrategy::Theirs)
+                .await?;
+
+            let contents = util::fs::read_from_path(repo.path.join("a.txt"))?;
+            assert_eq!(contents, "a modified from branch");
+            let status = repositories::status(&repo)?;
+            assert_eq!(status.merge_conflicts.len(), 0);
+            assert!(status.staged_files.contains_key(Path::new("a.txt")));
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_merge_configured_strategy_ours_for_path() -> Result<(), OxenError> {
+        test::run_one_commit_local_repo_test_async(|mut repo| async move {
+            let mut rule = MergeRule::new("*.txt");
+            rule.strategy = Some(MergeStrategy::Ours);
+            repo.set_merge_config(Some(MergeConfig { rules: vec![rule] }));
+            repo.save()?;
+
+            let merge_branch_name = populate_conflicting_txt_repo(&repo).await?;
+
+            // No strategy passed in, the one configured for the path is used
+            let commit = repositories::merge::merge(&repo, &merge_branch_name).await?;
+            assert!(commit.is_some());
+
+            let contents = util::fs::read_from_path(repo.path.join("a.txt"))?;
+            assert_eq!(contents, "a modified from main line");
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_merge_configured_driver_for_path() -> Result<(), OxenError> {
+        test::run_one_commit_local_repo_test_async(|mut repo| async move {
+            // Joins their version and ours, and records the path it merged
+            let mut rule = MergeRule::new("*.txt");
+            rule.driver = Some(String::from(
+                "cat %B %A > merged && echo %P >> merged && mv merged %A",
+            ));
+            rule.strategy = Some(MergeStrategy::Ours);
+            repo.set_merge_config(Some(MergeConfig { rules: vec![rule] }));
+            repo.save()?;
+
+            let merge_branch_name = populate_conflicting_txt_repo(&repo).await?;
+
+            // The driver runs instead of the configured strategy
+            let commit = repositories::merge::merge(&repo, &merge_branch_name).await?;
+            assert!(commit.is_some());
+
+            let contents = util::fs::read_from_path(repo.path.join("a.txt"))?;
+            assert_eq!(
+                contents,
+                "a modified from brancha modified from main linea.txt\n"
+            );
+            assert!(!repo.path.join("merged").exists());
+            let conflicts = repositories::merge::list_conflicts(&repo)?;
+            assert!(conflicts.is_empty());
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_merge_failing_driver_falls_back_to_strategy() -> Result<(), OxenError> {
+        test::run_one_commit_local_repo_test_async(|mut repo| async move {
+            let mut rule = MergeRule::new("*.txt");
+            rule.driver = Some(String::from("echo partial > %A && exit 1"));
+            rule.strategy = Some(MergeStrategy::Theirs);
+            repo.set_merge_config(Some(MergeConfig { rules: vec![rule] }));
+            repo.save()?;
+
+            let merge_branch_name = populate_conflicting_txt_repo(&repo).await?;
+
+            let commit = repositories::merge::merge(&repo, &merge_branch_name).await?;
+            assert!(commit.is_some());
+
+            // Their version, not what the driver left behind
+            let contents = util::fs::read_from_path(repo.path.join("a.txt"))?;
+            assert_eq!(contents, "a modified from branch");
+            let conflicts = repositories::merge::list_conflicts(&repo)?;
+            assert!(conflicts.is_empty());
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_merge_strategy_theirs_keyed_row_conflict() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed_async(|mut repo| async move {
+            let og_branch = repositories::branches::current_branch(&repo)?.unwrap();
+
+            let bbox_filename = Path::new("annotations")
+                .join("train")
+                .join("bounding_box.csv");
+            let bbox_file = repo.path.join(&bbox_filename);
+            set_bbox_merge_keys(&mut repo, &bbox_filename)?;
+
+            // Change the width of dog_2 on this branch
+            let branch_name = "ox-edit-width";
+            repositories::branches::create_checkout(&repo, branch_name)?;
+            replace_in_file(
+                &bbox_file,
+                DOG_2_ROW,
+                "train/dog_2.jpg,dog,7.0,29.5,300,247",
+            )?;
+
+            repositories::add(&repo, &bbox_file).await?;
+            repositories::commit(&repo, "Changing the width on a branch.")?;
+
+            // Change the same cell and add a row on the main branch
+            repositories::checkout(&repo, og_branch.name).await?;
+            replace_in_file(
+                &bbox_file,
+                DOG_2_ROW,
+                "train/dog_2.jpg,dog,7.0,29.5,250,247",
+            )?;
+            let bbox_file =
+                test::append_line_txt_file(bbox_file, "train/dog_4.jpg,dog,52.0,62.5,256,429")?;
+
+            repositories::add(&repo, &bbox_file).await?;
+            repositories::commit(&repo, "Changing the width on main branch")?;
+
+            let commit = repositories::merge::merge_with_strategy(
+                &repo,
+                branch_name,
+                Some(MergeStrategy::Theirs),
+            )
+            .await?;
+            assert!(commit.is_some());
+
+            // Their width wins the conflict, and our new row is kept
+            let df = tabular::read_df(&bbox_file, DFOpts::empty())?;
+            assert_eq!(df.height(), 7);
+            let width = df.column("width")?;
+            let widths = (0..df.height())
+                .map(|i| Ok(width.get(i)?.to_string()))
+                .collect::<Result<Vec<String>, OxenError>>()?;
+            assert!(widths.contains(&String::from("300")));
+            assert!(!widths.contains(&String::from("250")));
 
             Ok(())
         })
@@ -16,6 +16,7 @@ use std::path::{Path, PathBuf};
 
 pub mod audio;
 pub mod image;
+pub mod pdf;
 pub mod tabular;
 pub mod text;
 pub mod video;
@@ -211,6 +212,15 @@ pub fn get_file_metadata_with_extension(
                 Ok(None)
             }
         },
+        EntryDataType::Binary if extension.eq_ignore_ascii_case("pdf") => {
+            match pdf::get_metadata(path) {
+                Ok(metadata) => Ok(Some(GenericMetadata::MetadataPdf(metadata))),
+                Err(err) => {
+                    log::warn!("could not compute pdf metadata: {}", err);
+                    Ok(None)
+                }
+            }
+        }
         _ => Ok(None),
     }
 }
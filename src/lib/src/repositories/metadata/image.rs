@@ -2,22 +2,28 @@
 //!
 
 use crate::error::OxenError;
-use crate::model::metadata::metadata_image::MetadataImage;
+use crate::model::metadata::metadata_image::{MetadataImage, MetadataImageExif};
 
 use std::fs::File;
 
+use exif::{In, Tag, Value};
 use image::ImageReader;
 use std::io::BufReader;
 use std::path::Path;
 
 /// Detects the image metadata for the given file.
 pub fn get_metadata(path: impl AsRef<Path>) -> Result<MetadataImage, OxenError> {
+    let path = path.as_ref();
     let file = File::open(path)?;
     let reader = BufReader::new(file);
     let reader = ImageReader::new(reader).with_guessed_format()?;
 
     match reader.into_dimensions() {
-        Ok((width, height)) => Ok(MetadataImage::new(width, height)),
+        Ok((width, height)) => {
+            let mut metadata = MetadataImage::new(width, height);
+            metadata.image.exif = get_exif(path);
+            Ok(metadata)
+        }
         Err(e) => {
             log::debug!("Could not get image metadata {:?}", e);
             Err(OxenError::basic_str("Could not get image metadata"))
@@ -25,6 +31,70 @@ pub fn get_metadata(path: impl AsRef<Path>) -> Result<MetadataImage, OxenError>
     }
 }
 
+/// Reads the EXIF tags of the image, None if it has none or they can't be parsed
+pub fn get_exif(path: impl AsRef<Path>) -> Option<MetadataImageExif> {
+    let file = File::open(path.as_ref()).ok()?;
+    let mut reader = BufReader::new(file);
+    let exif = match exif::Reader::new().read_from_container(&mut reader) {
+        Ok(exif) => exif,
+        Err(err) => {
+            log::debug!("No exif for {:?}: {}", path.as_ref(), err);
+            return None;
+        }
+    };
+
+    let field = |tag: Tag| exif.get_field(tag, In::PRIMARY);
+    let string = |tag: Tag| {
+        field(tag).and_then(|field| match &field.value {
+            Value::Ascii(values) => values
+                .first()
+                .map(|value| String::from_utf8_lossy(value).trim().to_string()),
+            _ => None,
+        })
+    };
+    let uint = |tag: Tag| field(tag).and_then(|field| field.value.get_uint(0));
+    let rational = |tag: Tag| {
+        field(tag).and_then(|field| match &field.value {
+            Value::Rational(values) => values.first().map(|value| value.to_f64()),
+            _ => None,
+        })
+    };
+    // GPS coordinates are stored as degrees, minutes and seconds plus a N/S or E/W ref
+    let coordinate = |tag: Tag, ref_tag: Tag, negative_ref: &str| {
+        let degrees = match &field(tag)?.value {
+            Value::Rational(values) if values.len() == 3 => {
+                values[0].to_f64() + values[1].to_f64() / 60.0 + values[2].to_f64() / 3600.0
+            }
+            _ => return None,
+        };
+        match string(ref_tag) {
+            Some(r) if r.eq_ignore_ascii_case(negative_ref) => Some(-degrees),
+            _ => Some(degrees),
+        }
+    };
+
+    let metadata = MetadataImageExif {
+        make: string(Tag::Make),
+        model: string(Tag::Model),
+        taken_at: string(Tag::DateTimeOriginal).map(|datetime| {
+            // EXIF separates the date with colons, ie: 2024:01:31 12:00:00
+            datetime.replacen(':', "-", 2)
+        }),
+        orientation: uint(Tag::Orientation),
+        exposure_time: rational(Tag::ExposureTime),
+        f_number: rational(Tag::FNumber),
+        iso: uint(Tag::PhotographicSensitivity),
+        focal_length: rational(Tag::FocalLength),
+        gps_latitude: coordinate(Tag::GPSLatitude, Tag::GPSLatitudeRef, "S"),
+        gps_longitude: coordinate(Tag::GPSLongitude, Tag::GPSLongitudeRef, "W"),
+    };
+    if metadata == MetadataImageExif::default() {
+        None
+    } else {
+        Some(metadata)
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -107,4 +177,37 @@ mod tests {
         assert_eq!(metadata.image.width, 28);
         assert_eq!(metadata.image.height, 28);
     }
+
+    #[test]
+    fn test_get_metadata_img_exif() {
+        let file = test::test_img_file_with_name("ignas_brazdeikis_exif.jpg");
+        let data = repositories::metadata::get(file).unwrap();
+
+        let metadata: MetadataImage = match data.metadata.unwrap() {
+            GenericMetadata::MetadataImage(metadata) => metadata,
+            _ => panic!("Wrong metadata type"),
+        };
+
+        let exif = metadata.image.exif.expect("exif");
+        assert_eq!(exif.make, Some("Oxen".to_string()));
+        assert_eq!(exif.model, Some("Ox 1".to_string()));
+        assert_eq!(exif.taken_at, Some("2024-01-31 12:30:00".to_string()));
+        assert_eq!(exif.orientation, Some(6));
+        assert_eq!(exif.iso, Some(200));
+        assert_eq!(exif.f_number, Some(2.8));
+        assert!((exif.gps_latitude.unwrap() - 37.775).abs() < 1e-6);
+        assert!((exif.gps_longitude.unwrap() + 122.416_666).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_get_metadata_img_without_exif() {
+        let file = test::test_img_file_with_name("cat_1.jpg");
+        let data = repositories::metadata::get(file).unwrap();
+
+        let metadata: MetadataImage = match data.metadata.unwrap() {
+            GenericMetadata::MetadataImage(metadata) => metadata,
+            _ => panic!("Wrong metadata type"),
+        };
+        assert!(metadata.image.exif.is_none());
+    }
 }
@@ -0,0 +1,65 @@
+//! Helper functions to get metadata from pdf documents.
+//!
+
+use crate::error::OxenError;
+use crate::model::metadata::MetadataPdf;
+
+use std::path::Path;
+
+/// Counts the pages of the given pdf.
+pub fn get_metadata(path: impl AsRef<Path>) -> Result<MetadataPdf, OxenError> {
+    let path = path.as_ref();
+    match lopdf::Document::load(path) {
+        Ok(document) => Ok(MetadataPdf::new(document.get_pages().len())),
+        Err(err) => {
+            let err = format!("Could not read pdf {:?}: {}", path, err);
+            Err(OxenError::basic_str(err))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::OxenError;
+    use crate::model::metadata::generic_metadata::GenericMetadata;
+    use crate::model::metadata::MetadataPdf;
+    use crate::model::EntryDataType;
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    #[test]
+    fn test_get_metadata_pdf_page_count() {
+        let file = test::test_pdf_file_with_name("two_pages.pdf");
+        let metadata = repositories::metadata::get(file).unwrap();
+
+        assert_eq!(metadata.data_type, EntryDataType::Binary);
+        assert_eq!(metadata.mime_type, "application/pdf");
+
+        let metadata: MetadataPdf = match metadata.metadata.unwrap() {
+            GenericMetadata::MetadataPdf(metadata) => metadata,
+            _ => panic!("Wrong metadata type"),
+        };
+        assert_eq!(metadata.pdf.num_pages, 2);
+    }
+
+    #[tokio::test]
+    async fn test_pdf_metadata_stored_on_file_node() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            let path = repo.path.join("paper.pdf");
+            util::fs::copy(test::test_pdf_file_with_name("two_pages.pdf"), &path)?;
+            repositories::add(&repo, &path).await?;
+            let commit = repositories::commit(&repo, "Adding a pdf")?;
+
+            let node = repositories::tree::get_file_by_path(&repo, &commit, "paper.pdf")?.unwrap();
+            match node.metadata() {
+                Some(GenericMetadata::MetadataPdf(metadata)) => {
+                    assert_eq!(metadata.pdf.num_pages, 2)
+                }
+                metadata => panic!("Wrong metadata {metadata:?}"),
+            }
+            Ok(())
+        })
+        .await
+    }
+}
@@ -33,11 +33,10 @@ pub fn get_metadata(path: impl AsRef<Path>) -> Result<MetadataVideo, OxenError>
                 .first()
                 .ok_or(OxenError::basic_str("Could not get video track"))?;
 
-            Ok(MetadataVideo::new(
-                duration,
-                video.width() as usize,
-                video.height() as usize,
-            ))
+            let mut metadata =
+                MetadataVideo::new(duration, video.width() as usize, video.height() as usize);
+            metadata.video.codec = video.media_type().ok().map(|codec| codec.to_string());
+            Ok(metadata)
         }
         Err(err) => {
             let err = format!("Could not get video metadata {:?}", err);
@@ -74,6 +73,7 @@ mod tests {
         assert_eq!(metadata.video.width, 128);
         assert_eq!(metadata.video.height, 176);
         assert_relative_eq!(metadata.video.num_seconds, 1.6);
+        assert!(metadata.video.codec.is_some());
     }
 
     #[test]
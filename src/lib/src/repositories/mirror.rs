@@ -0,0 +1,277 @@
+//! # Mirrors
+//!
+//! Keep a repository on this server in sync with a repository on an upstream server, such
+//! as a warm standby. Each sync fetches the history and version files of every upstream
+//! branch, then moves the local branches and tags to where they are upstream and drops
+//! the ones that were deleted there. Mirrors are read only so pushes can't make them
+//! diverge from the upstream.
+//!
+//! The status of each mirror is a json file in the sync dir's `.oxen/mirrors`.
+//!
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+
+use time::OffsetDateTime;
+
+use crate::api;
+use crate::config::{MirrorConfig, MirrorRepoConfig};
+use crate::constants::{DEFAULT_BRANCH_NAME, DEFAULT_REMOTE_NAME, HISTORY_DIR, MIRRORS_DIR};
+use crate::core::refs::with_ref_manager;
+use crate::error::OxenError;
+use crate::model::{LocalRepository, MirrorState, MirrorStatus, RemoteRepository};
+use crate::opts::FetchOpts;
+use crate::repositories;
+use crate::util;
+
+// Paths of the mirrors being synced, a webhook can arrive while a scheduled sync runs
+static SYNCING: LazyLock<Mutex<HashSet<PathBuf>>> = LazyLock::new(|| Mutex::new(HashSet::new()));
+
+fn status_path(sync_dir: &Path, namespace: &str, name: &str) -> PathBuf {
+    util::fs::oxen_hidden_dir(sync_dir)
+        .join(MIRRORS_DIR)
+        .join(namespace)
+        .join(format!("{name}.json"))
+}
+
+fn save_status(sync_dir: &Path, status: &MirrorStatus) -> Result<(), OxenError> {
+    let path = status_path(sync_dir, &status.namespace, &status.name);
+    if let Some(parent) = path.parent() {
+        util::fs::create_dir_all(parent)?;
+    }
+    util::fs::write_to_path(&path, serde_json::to_string_pretty(status)?)
+}
+
+/// The status of every mirror in the config, in the order they are configured
+pub fn list(sync_dir: &Path, config: &MirrorConfig) -> Result<Vec<MirrorStatus>, OxenError> {
+    config
+        .repos
+        .iter()
+        .map(|mirror| get_status(sync_dir, mirror))
+        .collect()
+}
+
+/// The status of a mirror, pending if it has never been synced
+pub fn get_status(sync_dir: &Path, mirror: &MirrorRepoConfig) -> Result<MirrorStatus, OxenError> {
+    let (namespace, name) = mirror.local_namespace_and_name()?;
+    let path = status_path(sync_dir, &namespace, &name);
+    if !path.exists() {
+        return Ok(MirrorStatus {
+            namespace,
+            name,
+            url: mirror.url.clone(),
+            state: MirrorState::Pending,
+            last_started_at: None,
+            last_synced_at: None,
+            last_error: None,
+            num_branches: 0,
+            num_tags: 0,
+        });
+    }
+
+    let data = util::fs::read_from_path(&path)?;
+    let mut status: MirrorStatus = serde_json::from_str(&data)?;
+    // The server went down in the middle of the last sync
+    if status.state == MirrorState::Syncing && !SYNCING.lock().unwrap().contains(&path) {
+        status.state = MirrorState::Failed;
+        status.last_error = Some("The sync was interrupted".to_string());
+    }
+    Ok(status)
+}
+
+/// Bring the mirror up to date with its upstream, creating it on the first sync. A failed
+/// sync is recorded in the returned status rather than returned as an error. If the
+/// mirror is already syncing, its status is returned without starting another sync.
+pub async fn sync(sync_dir: &Path, mirror: &MirrorRepoConfig) -> Result<MirrorStatus, OxenError> {
+    let mut status = get_status(sync_dir, mirror)?;
+    let path = status_path(sync_dir, &status.namespace, &status.name);
+    if !SYNCING.lock().unwrap().insert(path.clone()) {
+        return Ok(status);
+    }
+
+    status.state = MirrorState::Syncing;
+    status.url = mirror.url.clone();
+    status.last_started_at = Some(OffsetDateTime::now_utc());
+    let result = match save_status(sync_dir, &status) {
+        Ok(_) => sync_repo(sync_dir, mirror, &status.namespace, &status.name).await,
+        Err(err) => Err(err),
+    };
+    SYNCING.lock().unwrap().remove(&path);
+
+    match result {
+        Ok((num_branches, num_tags)) => {
+            status.state = MirrorState::Synced;
+            status.last_synced_at = Some(OffsetDateTime::now_utc());
+            status.last_error = None;
+            status.num_branches = num_branches;
+            status.num_tags = num_tags;
+        }
+        Err(err) => {
+            log::error!(
+                "Mirror {}/{} of {} failed to sync: {}",
+                status.namespace,
+                status.name,
+                mirror.url,
+                err
+            );
+            status.state = MirrorState::Failed;
+            status.last_error = Some(err.to_string());
+        }
+    }
+    save_status(sync_dir, &status)?;
+    Ok(status)
+}
+
+/// Returns the number of branches and tags the mirror has after the sync
+async fn sync_repo(
+    sync_dir: &Path,
+    mirror: &MirrorRepoConfig,
+    namespace: &str,
+    name: &str,
+) -> Result<(usize, usize), OxenError> {
+    let remote_repo = api::client::repositories::get_by_url(&mirror.url)
+        .await?
+        .ok_or(OxenError::remote_repo_not_found(&mirror.url))?;
+    let repo = match repositories::get_by_namespace_and_name(sync_dir, namespace, name)? {
+        Some(repo) => {
+            // Never overwrite a repository that was not created as a mirror of this upstream
+            let is_mirror = repo.is_read_only()
+                && repo
+                    .get_remote(DEFAULT_REMOTE_NAME)
+                    .is_some_and(|remote| remote.url == mirror.url);
+            if !is_mirror {
+                return Err(OxenError::basic_str(format!(
+                    "Repository {namespace}/{name} already exists and is not a mirror of {}",
+                    mirror.url
+                )));
+            }
+            repo
+        }
+        None => create_mirror_repo(sync_dir, namespace, name, mirror, remote_repo.clone())?,
+    };
+
+    let fetch_opts = FetchOpts {
+        all: true,
+        ..FetchOpts::new()
+    };
+    let branches = repositories::fetch::fetch_all(&repo, &fetch_opts).await?;
+    for branch in &branches {
+        repositories::branches::update(&repo, &branch.name, &branch.commit_id)?;
+    }
+    for branch in repositories::branches::list(&repo)? {
+        if branches.iter().any(|upstream| upstream.name == branch.name) {
+            continue;
+        }
+        // HEAD can't be deleted, it stays at its last commit
+        if let Err(err) = repositories::branches::force_delete(&repo, &branch.name) {
+            log::warn!("Mirror could not delete branch {}: {}", branch.name, err);
+        }
+    }
+
+    let tags = api::client::tags::list(&remote_repo).await?;
+    for tag in &tags {
+        match repositories::tags::get_by_name(&repo, &tag.name)? {
+            Some(local_tag) if local_tag == *tag => continue,
+            Some(_) => {
+                repositories::tags::delete(&repo, &tag.name)?;
+            }
+            None => {}
+        }
+        repositories::tags::save(&repo, tag)?;
+    }
+    for tag in repositories::tags::list(&repo)? {
+        if !tags.iter().any(|upstream| upstream.name == tag.name) {
+            repositories::tags::delete(&repo, &tag.name)?;
+        }
+    }
+
+    Ok((repositories::branches::list(&repo)?.len(), tags.len()))
+}
+
+fn create_mirror_repo(
+    sync_dir: &Path,
+    namespace: &str,
+    name: &str,
+    mirror: &MirrorRepoConfig,
+    remote_repo: RemoteRepository,
+) -> Result<LocalRepository, OxenError> {
+    let repo_path = sync_dir.join(namespace).join(name);
+    let hidden_dir = util::fs::oxen_hidden_dir(&repo_path);
+    util::fs::create_dir_all(hidden_dir.join(HISTORY_DIR))?;
+
+    let min_version = remote_repo.min_version();
+    let mut repo = LocalRepository::from_remote(remote_repo, &repo_path)?;
+    repo.set_remote(DEFAULT_REMOTE_NAME, &mirror.url);
+    repo.set_min_version(min_version);
+    repo.set_read_only(true);
+    repo.save()?;
+
+    with_ref_manager(&repo, |manager| {
+        manager.set_head(DEFAULT_BRANCH_NAME);
+        Ok(())
+    })?;
+    Ok(repo)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api;
+    use crate::config::MirrorRepoConfig;
+    use crate::constants::{DEFAULT_BRANCH_NAME, DEFAULT_REMOTE_NAME};
+    use crate::error::OxenError;
+    use crate::model::MirrorState;
+    use crate::repositories;
+    use crate::test;
+
+    #[tokio::test]
+    async fn test_mirror_branches_and_tags() -> Result<(), OxenError> {
+        test::run_training_data_fully_sync_remote(|local_repo, remote_repo| async move {
+            api::client::branches::create_from_branch(&remote_repo, "dev", DEFAULT_BRANCH_NAME)
+                .await?;
+            repositories::tags::create(&local_repo, "v1.0", DEFAULT_BRANCH_NAME, "Release")?;
+            repositories::tags::push(&local_repo, DEFAULT_REMOTE_NAME).await?;
+
+            let mirror = MirrorRepoConfig {
+                url: remote_repo.remote.url.clone(),
+                namespace: Some("standby".to_string()),
+                name: None,
+                secret: None,
+            };
+            let upstream = remote_repo.clone();
+            test::run_empty_dir_test_async(|sync_dir| async move {
+                let status = repositories::mirror::get_status(&sync_dir, &mirror)?;
+                assert_eq!(status.state, MirrorState::Pending);
+
+                let status = repositories::mirror::sync(&sync_dir, &mirror).await?;
+                assert_eq!(status.state, MirrorState::Synced, "{:?}", status.last_error);
+                assert_eq!(status.num_branches, 2);
+                assert_eq!(status.num_tags, 1);
+
+                let repo =
+                    repositories::get_by_namespace_and_name(&sync_dir, "standby", &upstream.name)?
+                        .expect("mirror was created");
+                assert!(repo.is_read_only());
+                let head = repositories::branches::get_by_name(&repo, DEFAULT_BRANCH_NAME)?
+                    .expect("main was mirrored");
+                let upstream_head =
+                    api::client::branches::get_by_name(&upstream, DEFAULT_BRANCH_NAME)
+                        .await?
+                        .expect("main exists upstream");
+                assert_eq!(head.commit_id, upstream_head.commit_id);
+
+                // Branches deleted upstream are deleted on the next sync
+                api::client::branches::delete(&upstream, "dev").await?;
+                let status = repositories::mirror::sync(&sync_dir, &mirror).await?;
+                assert_eq!(status.num_branches, 1);
+                assert!(repositories::branches::get_by_name(&repo, "dev")?.is_none());
+
+                Ok(())
+            })
+            .await?;
+
+            Ok(remote_repo)
+        })
+        .await
+    }
+}
@@ -0,0 +1,150 @@
+//! # oxen mv
+//!
+//! Move or rename a tracked file or directory and stage the move. The old path is staged
+//! as removed and the new path as added, and since the contents keep their hashes
+//! `oxen status` shows them as moved and `oxen log --follow` can follow a file back to
+//! its old path.
+//!
+
+use std::path::{Path, PathBuf};
+
+use crate::error::OxenError;
+use crate::model::LocalRepository;
+use crate::repositories;
+use crate::util;
+
+/// Move `src` to `dst` in the working directory and stage the move. If `dst` is an
+/// existing directory, `src` is moved into it. Paths are relative to the repository root
+/// or absolute. Returns the path `src` was moved to, relative to the repository root.
+pub async fn mv(
+    repo: &LocalRepository,
+    src: impl AsRef<Path>,
+    dst: impl AsRef<Path>,
+) -> Result<PathBuf, OxenError> {
+    let src_path = absolute_path(repo, src.as_ref());
+    let mut dst_path = absolute_path(repo, dst.as_ref());
+    let src = util::fs::path_relative_to_dir(&src_path, &repo.path)?;
+    if src == Path::new("") {
+        return Err(OxenError::basic_str(
+            "Cannot move the root of the repository",
+        ));
+    }
+    if !src_path.exists() {
+        return Err(OxenError::path_does_not_exist(&src_path));
+    }
+    let is_tracked = match repositories::commits::head_commit_maybe(repo)? {
+        Some(head_commit) => repositories::tree::has_path(repo, &head_commit, &src)?,
+        None => false,
+    };
+    if !is_tracked {
+        return Err(OxenError::basic_str(format!(
+            "{src:?} is not committed, move it on disk and `oxen add` it instead"
+        )));
+    }
+
+    if dst_path.is_dir() {
+        if let Some(file_name) = src.file_name() {
+            dst_path = dst_path.join(file_name);
+        }
+    }
+    let dst = util::fs::path_relative_to_dir(&dst_path, &repo.path)?;
+    if !dst_path.starts_with(&repo.path) || util::fs::is_in_oxen_hidden_dir(&dst) {
+        return Err(OxenError::basic_str(format!(
+            "Cannot move {src:?} outside of the repository to {dst_path:?}"
+        )));
+    }
+    if dst_path.exists() {
+        return Err(OxenError::basic_str(format!(
+            "Cannot move {src:?}, {dst:?} already exists"
+        )));
+    }
+    if dst.starts_with(&src) {
+        return Err(OxenError::basic_str(format!(
+            "Cannot move {src:?} into itself at {dst:?}"
+        )));
+    }
+
+    if let Some(parent) = dst_path.parent() {
+        util::fs::create_dir_all(parent)?;
+    }
+    util::fs::rename(&src_path, &dst_path)?;
+
+    // Adding the missing path stages its removal
+    repositories::add(repo, &src_path).await?;
+    repositories::add(repo, &dst_path).await?;
+    Ok(dst)
+}
+
+fn absolute_path(repo: &LocalRepository, path: &Path) -> PathBuf {
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        repo.path.join(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::error::OxenError;
+    use crate::model::diff::diff_entry_status::DiffEntryStatus;
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    #[tokio::test]
+    async fn test_mv_stages_a_rename_that_log_can_follow() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            util::fs::write_to_path(repo.path.join("labels.csv"), "id,label\n1,cat\n")?;
+            repositories::add(&repo, &repo.path).await?;
+            let first = repositories::commit(&repo, "Add labels")?;
+
+            util::fs::write_to_path(repo.path.join("labels.csv"), "id,label\n1,cat\n2,dog\n")?;
+            repositories::add(&repo, &repo.path).await?;
+            let second = repositories::commit(&repo, "Add a dog")?;
+
+            util::fs::create_dir_all(repo.path.join("annotations"))?;
+            let dst = repositories::mv(&repo, "labels.csv", "annotations").await?;
+            assert_eq!(dst, PathBuf::from("annotations").join("labels.csv"));
+            assert!(!repo.path.join("labels.csv").exists());
+            assert!(repo.path.join(&dst).exists());
+
+            let status = repositories::status(&repo)?;
+            assert_eq!(status.moved_files.len(), 1);
+            assert_eq!(status.moved_files[0].0, dst);
+            assert_eq!(status.moved_files[0].1, PathBuf::from("labels.csv"));
+            let moved = repositories::commit(&repo, "Move labels")?;
+
+            // The stat shows one renamed file rather than a removal and an addition
+            let stat = repositories::diffs::commit_stat(&repo, &moved)?;
+            assert_eq!(stat.files.len(), 1);
+            assert_eq!(stat.files[0].status, DiffEntryStatus::Renamed);
+            assert_eq!(stat.files[0].old_path, Some(PathBuf::from("labels.csv")));
+
+            let history = repositories::commits::list_by_path_follow(&repo, &moved.id, &dst)?;
+            let history: Vec<(String, PathBuf)> = history
+                .into_iter()
+                .map(|(commit, path)| (commit.id, path))
+                .collect();
+            assert_eq!(
+                history,
+                vec![
+                    (moved.id.clone(), dst.clone()),
+                    (second.id.clone(), PathBuf::from("labels.csv")),
+                    (first.id.clone(), PathBuf::from("labels.csv")),
+                ]
+            );
+
+            // Moving onto an existing path or an untracked file fails
+            util::fs::write_to_path(repo.path.join("notes.txt"), "notes")?;
+            assert!(repositories::mv(&repo, "notes.txt", "notes2.txt")
+                .await
+                .is_err());
+            assert!(repositories::mv(&repo, &dst, "notes.txt").await.is_err());
+
+            Ok(())
+        })
+        .await
+    }
+}
@@ -0,0 +1,231 @@
+//! # Owners
+//!
+//! Paths whose changes need an owner's approval before they land on a protected branch,
+//! configured in `.oxen/owners` on the server, see [`crate::config::OwnersConfig`].
+//! Merge proposals are approved by reviewers. Pushes straight to a protected branch have
+//! no reviewers, so they are only accepted if an owner pushed them. Who pushed comes from
+//! the access token the push was authenticated with, not from the commits, since anyone
+//! can set the author of a commit.
+//!
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::config::{OwnersConfig, OWNERS_CONFIG_FILENAME};
+use crate::error::OxenError;
+use crate::model::{Branch, Commit, LocalRepository, MerkleHash, RequiredApproval, User};
+use crate::repositories;
+use crate::util;
+
+fn config_path(repo: &LocalRepository) -> PathBuf {
+    util::fs::oxen_hidden_dir(&repo.path).join(OWNERS_CONFIG_FILENAME)
+}
+
+/// The owners of the repository's paths, empty if there is no owners file
+pub fn get(repo: &LocalRepository) -> Result<OwnersConfig, OxenError> {
+    let path = config_path(repo);
+    if !path.exists() {
+        return Ok(OwnersConfig::default());
+    }
+    OwnersConfig::parse(&util::fs::read_from_path(&path)?)
+}
+
+/// The approvals the changes from `base` to `head` need, one per owners rule that matches
+/// a changed file, and which of the `approvers` satisfied each of them
+pub fn required_approvals(
+    repo: &LocalRepository,
+    base: &Commit,
+    head: &Commit,
+    approvers: &[User],
+) -> Result<Vec<RequiredApproval>, OxenError> {
+    let config = get(repo)?;
+    if config.rules.is_empty() || base.id == head.id {
+        return Ok(vec![]);
+    }
+
+    let mut required: Vec<RequiredApproval> = vec![];
+    for path in changed_paths(repo, base, head)? {
+        let Some(rule) = config.rule_for(&path) else {
+            continue;
+        };
+        match required.iter_mut().find(|r| r.pattern == rule.pattern) {
+            Some(approval) => approval.paths.push(path),
+            None => required.push(RequiredApproval {
+                pattern: rule.pattern.clone(),
+                owners: rule.owners.clone(),
+                paths: vec![path],
+                approved_by: approvers
+                    .iter()
+                    .find(|user| config.is_owner(rule, user))
+                    .cloned(),
+            }),
+        }
+    }
+    Ok(required)
+}
+
+/// Fail with the paths that are still waiting on an owner
+pub fn check_approved(approvals: &[RequiredApproval]) -> Result<(), OxenError> {
+    let missing: Vec<String> = approvals
+        .iter()
+        .filter(|approval| !approval.is_approved())
+        .map(|approval| {
+            format!(
+                "{} changed {} file(s), approval required from {}",
+                approval.pattern,
+                approval.paths.len(),
+                approval.owners.join(", ")
+            )
+        })
+        .collect();
+    if missing.is_empty() {
+        return Ok(());
+    }
+    Err(OxenError::approval_required(format!(
+        "Changes to owned paths need an owner's approval:\n  {}",
+        missing.join("\n  ")
+    )))
+}
+
+/// Make sure a push that moves a protected branch to `new_commit` only changes owned
+/// paths if the `pusher` owns them. Without a pusher, owned paths can't be changed.
+pub fn check_push(
+    repo: &LocalRepository,
+    branch: &Branch,
+    new_commit: &Commit,
+    pusher: Option<&User>,
+) -> Result<(), OxenError> {
+    if branch.commit_id == new_commit.id
+        || repositories::branches::protection::get(repo, &branch.name)?.is_none()
+    {
+        return Ok(());
+    }
+    let config = get(repo)?;
+    if config.rules.is_empty() {
+        return Ok(());
+    }
+
+    let head = repositories::commits::get_by_id(repo, &branch.commit_id)?
+        .ok_or(OxenError::resource_not_found(&branch.commit_id))?;
+    let lca = repositories::merge::lowest_common_ancestor_from_commits(repo, &head, new_commit)?;
+    for path in changed_paths(repo, &lca, new_commit)? {
+        let Some(rule) = config.rule_for(&path) else {
+            continue;
+        };
+        match pusher {
+            Some(user) if config.is_owner(rule, user) => {}
+            Some(user) => {
+                return Err(OxenError::approval_required(format!(
+                    "{path:?} is owned by {}, {} can not push changes to it on '{}'. Propose the changes and have an owner approve them.",
+                    rule.owners.join(", "),
+                    user.email,
+                    branch.name
+                )));
+            }
+            None => {
+                return Err(OxenError::approval_required(format!(
+                    "{path:?} is owned by {}, pushing changes to it on '{}' needs an owner's access token",
+                    rule.owners.join(", "),
+                    branch.name
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Files added, removed or modified between the two commits
+fn changed_paths(
+    repo: &LocalRepository,
+    base: &Commit,
+    head: &Commit,
+) -> Result<Vec<PathBuf>, OxenError> {
+    let base_files = file_hashes(repo, base)?;
+    let mut head_files = file_hashes(repo, head)?;
+
+    let mut paths = vec![];
+    for (path, hash) in base_files {
+        match head_files.remove(&path) {
+            Some(head_hash) if head_hash == hash => {}
+            _ => paths.push(path),
+        }
+    }
+    paths.extend(head_files.into_keys());
+    paths.sort();
+    Ok(paths)
+}
+
+fn file_hashes(
+    repo: &LocalRepository,
+    commit: &Commit,
+) -> Result<BTreeMap<PathBuf, MerkleHash>, OxenError> {
+    let Some(root) = repositories::tree::get_root_with_children(repo, commit)? else {
+        return Ok(BTreeMap::new());
+    };
+    Ok(
+        repositories::tree::list_all_files(&root, &Path::new("").to_path_buf())?
+            .into_iter()
+            .map(|file| (file.dir.join(file.file_node.name()), *file.file_node.hash()))
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::{BranchProtectionRule, OWNERS_CONFIG_FILENAME};
+    use crate::error::OxenError;
+    use crate::model::User;
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    #[tokio::test]
+    async fn test_push_to_protected_branch_needs_owner() -> Result<(), OxenError> {
+        test::run_one_commit_local_repo_test_async(|repo| async move {
+            let main = repositories::branches::current_branch(&repo)?.unwrap();
+            util::fs::write_to_path(
+                util::fs::oxen_hidden_dir(&repo.path).join(OWNERS_CONFIG_FILENAME),
+                "schemas/ ada@oxen.ai\n",
+            )?;
+
+            let path = repo.path.join("schemas").join("images.json");
+            util::fs::write_to_path(&path, "{\"label\": \"str\"}")?;
+            repositories::add(&repo, &path).await?;
+            let schema_commit = repositories::commit(&repo, "Adding a schema")?;
+
+            // Not protected yet, anyone can push
+            repositories::owners::check_push(&repo, &main, &schema_commit, None)?;
+
+            repositories::branches::protection::protect(
+                &repo,
+                BranchProtectionRule::new(&main.name),
+            )?;
+            let result = repositories::owners::check_push(&repo, &main, &schema_commit, None);
+            assert!(matches!(result, Err(OxenError::ApprovalRequired(_))));
+
+            // The commit author doesn't matter, only who pushed
+            let bo = User {
+                name: "Bo".to_string(),
+                email: "bo@oxen.ai".to_string(),
+            };
+            let result = repositories::owners::check_push(&repo, &main, &schema_commit, Some(&bo));
+            assert!(matches!(result, Err(OxenError::ApprovalRequired(_))));
+            let ada = User {
+                name: "Ada".to_string(),
+                email: "ada@oxen.ai".to_string(),
+            };
+            repositories::owners::check_push(&repo, &main, &schema_commit, Some(&ada))?;
+
+            // Paths without owners are fine
+            let main = repositories::branches::get_by_name(&repo, &main.name)?.unwrap();
+            let path = repo.path.join("notes.txt");
+            util::fs::write_to_path(&path, "notes")?;
+            repositories::add(&repo, &path).await?;
+            let notes_commit = repositories::commit(&repo, "Adding notes")?;
+            repositories::owners::check_push(&repo, &main, &notes_commit, Some(&bo))?;
+
+            Ok(())
+        })
+        .await
+    }
+}
@@ -0,0 +1,514 @@
+//! # Proposals
+//!
+//! Merge proposals ask to merge a head branch into a base branch on the server. They hold
+//! the review comments, keyed by the file and row they are about, and are checked for
+//! conflicts with the same row level merge `oxen merge` uses before being merged. Changes
+//! to paths listed in `.oxen/owners` also need an owner's approval, see
+//! [`crate::repositories::owners`]. Each proposal is a json file in `.oxen/proposals`,
+//! named by its number.
+//!
+
+use std::fs::OpenOptions;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use time::OffsetDateTime;
+
+use crate::constants::PROPOSALS_DIR;
+use crate::error::OxenError;
+use crate::model::diff::diff_entries_counts::DiffEntriesCounts;
+use crate::model::{
+    Branch, Commit, LocalRepository, MergeProposal, MergeProposalApproval, MergeProposalChecks,
+    MergeProposalComment, MergeProposalConflict, MergeProposalStatus, User,
+};
+use crate::repositories;
+use crate::util;
+use crate::view::{MergeProposalCommentNew, MergeProposalNew};
+
+fn proposals_dir(repo: &LocalRepository) -> PathBuf {
+    util::fs::oxen_hidden_dir(&repo.path).join(PROPOSALS_DIR)
+}
+
+fn proposal_path(repo: &LocalRepository, id: u64) -> PathBuf {
+    proposals_dir(repo).join(format!("{id}.json"))
+}
+
+fn proposal_not_found(id: u64) -> OxenError {
+    OxenError::resource_not_found(format!("proposal {id}"))
+}
+
+/// Propose merging `head` into `base`. Both branches must exist and there can only be one
+/// open proposal between them.
+pub fn create(
+    repo: &LocalRepository,
+    new_proposal: &MergeProposalNew,
+) -> Result<MergeProposal, OxenError> {
+    let base = &new_proposal.base;
+    let head = &new_proposal.head;
+    if base == head {
+        return Err(OxenError::basic_str(format!(
+            "Cannot propose merging {head} into itself"
+        )));
+    }
+    for branch in [base, head] {
+        if !repositories::branches::exists(repo, branch)? {
+            return Err(OxenError::local_branch_not_found(branch));
+        }
+    }
+    if new_proposal.title.trim().is_empty() {
+        return Err(OxenError::basic_str("Proposal must have a title"));
+    }
+    if let Some(open) = list(repo, Some(MergeProposalStatus::Open))?
+        .into_iter()
+        .find(|proposal| &proposal.base == base && &proposal.head == head)
+    {
+        return Err(OxenError::basic_str(format!(
+            "Proposal {} to merge {head} into {base} is already open",
+            open.id
+        )));
+    }
+
+    let dir = proposals_dir(repo);
+    util::fs::create_dir_all(&dir)?;
+    let now = OffsetDateTime::now_utc();
+    let mut id = list(repo, None)?.iter().map(|p| p.id).max().unwrap_or(0) + 1;
+    loop {
+        let proposal = MergeProposal {
+            id,
+            title: new_proposal.title.trim().to_string(),
+            description: new_proposal.description.clone(),
+            base: base.clone(),
+            head: head.clone(),
+            author: new_proposal.author.clone(),
+            status: MergeProposalStatus::Open,
+            merge_commit_id: None,
+            comments: vec![],
+            approvals: vec![],
+            created_at: now,
+            updated_at: now,
+        };
+        // create_new so two proposals created at once can't take the same number
+        match OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(proposal_path(repo, id))
+        {
+            Ok(mut file) => {
+                file.write_all(serde_json::to_string(&proposal)?.as_bytes())?;
+                return Ok(proposal);
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => id += 1,
+            Err(err) => return Err(err.into()),
+        }
+    }
+}
+
+/// The proposals of the repository, newest first, optionally only the ones with `status`
+pub fn list(
+    repo: &LocalRepository,
+    status: Option<MergeProposalStatus>,
+) -> Result<Vec<MergeProposal>, OxenError> {
+    let dir = proposals_dir(repo);
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut proposals = vec![];
+    for entry in std::fs::read_dir(&dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            let proposal = read_proposal(&path)?;
+            if status.is_none_or(|status| proposal.status == status) {
+                proposals.push(proposal);
+            }
+        }
+    }
+    proposals.sort_by(|a, b| b.id.cmp(&a.id));
+    Ok(proposals)
+}
+
+pub fn get(repo: &LocalRepository, id: u64) -> Result<Option<MergeProposal>, OxenError> {
+    let path = proposal_path(repo, id);
+    if !path.exists() {
+        return Ok(None);
+    }
+    Ok(Some(read_proposal(&path)?))
+}
+
+fn read_proposal(path: &Path) -> Result<MergeProposal, OxenError> {
+    // Wait out a comment being written, the file is truncated before it is rewritten
+    let lock = fd_lock::RwLock::new(std::fs::File::open(path)?);
+    let file = lock.read()?;
+    let mut data = String::new();
+    (&*file).read_to_string(&mut data)?;
+    serde_json::from_str(&data)
+        .map_err(|err| OxenError::basic_str(format!("Could not parse {path:?}: {err}")))
+}
+
+/// Read, change and write back a proposal while holding a lock on its file
+fn update<T>(
+    repo: &LocalRepository,
+    id: u64,
+    f: impl FnOnce(&mut MergeProposal) -> Result<T, OxenError>,
+) -> Result<T, OxenError> {
+    let path = proposal_path(repo, id);
+    if !path.exists() {
+        return Err(proposal_not_found(id));
+    }
+    let file = OpenOptions::new().read(true).write(true).open(&path)?;
+    let mut lock = fd_lock::RwLock::new(file);
+    let mut file = lock.write()?;
+
+    let mut data = String::new();
+    file.read_to_string(&mut data)?;
+    let mut proposal: MergeProposal = serde_json::from_str(&data)?;
+    proposal.updated_at = OffsetDateTime::now_utc();
+    let result = f(&mut proposal)?;
+
+    let data = serde_json::to_string(&proposal)?;
+    file.seek(SeekFrom::Start(0))?;
+    file.set_len(0)?;
+    file.write_all(data.as_bytes())?;
+    Ok(result)
+}
+
+/// Add a comment, on the proposal as a whole or on a file and row it changes
+pub fn comment(
+    repo: &LocalRepository,
+    id: u64,
+    new_comment: &MergeProposalCommentNew,
+) -> Result<MergeProposalComment, OxenError> {
+    if new_comment.body.trim().is_empty() {
+        return Err(OxenError::basic_str("Comment must not be empty"));
+    }
+    if new_comment.row.is_some() && new_comment.path.is_none() {
+        return Err(OxenError::basic_str(
+            "Comment on a row must also name the file the row is in",
+        ));
+    }
+    update(repo, id, |proposal| {
+        let comment = MergeProposalComment {
+            id: proposal.comments.iter().map(|c| c.id).max().unwrap_or(0) + 1,
+            author: new_comment.author.clone(),
+            body: new_comment.body.clone(),
+            path: new_comment.path.clone(),
+            row: new_comment.row.clone(),
+            created_at: OffsetDateTime::now_utc(),
+        };
+        proposal.comments.push(comment.clone());
+        Ok(comment)
+    })
+}
+
+/// Approve the head branch as it is now. Pushing more changes to it needs a new approval.
+/// The `author` must be the authenticated user, never one named by the client.
+pub fn approve(repo: &LocalRepository, id: u64, author: &User) -> Result<MergeProposal, OxenError> {
+    let proposal = get(repo, id)?.ok_or(proposal_not_found(id))?;
+    let (_, _, _, head_commit) = branch_commits(repo, &proposal)?;
+    if author.email.eq_ignore_ascii_case(&proposal.author.email) {
+        return Err(OxenError::basic_str(format!(
+            "{} can not approve their own proposal",
+            author.email
+        )));
+    }
+
+    update(repo, id, |proposal| {
+        require_open(proposal)?;
+        proposal
+            .approvals
+            .retain(|approval| !approval.author.email.eq_ignore_ascii_case(&author.email));
+        proposal.approvals.push(MergeProposalApproval {
+            author: author.clone(),
+            commit_id: head_commit.id.clone(),
+            created_at: OffsetDateTime::now_utc(),
+        });
+        Ok(proposal.clone())
+    })
+}
+
+/// Close an open proposal without merging it
+pub fn close(repo: &LocalRepository, id: u64) -> Result<MergeProposal, OxenError> {
+    update(repo, id, |proposal| {
+        require_open(proposal)?;
+        proposal.status = MergeProposalStatus::Closed;
+        Ok(proposal.clone())
+    })
+}
+
+fn require_open(proposal: &MergeProposal) -> Result<(), OxenError> {
+    if proposal.is_open() {
+        Ok(())
+    } else {
+        Err(OxenError::basic_str(format!(
+            "Proposal {} is already {}",
+            proposal.id, proposal.status
+        )))
+    }
+}
+
+fn branch_commits(
+    repo: &LocalRepository,
+    proposal: &MergeProposal,
+) -> Result<(Branch, Commit, Branch, Commit), OxenError> {
+    let mut found = vec![];
+    for name in [&proposal.base, &proposal.head] {
+        let branch = repositories::branches::get_by_name(repo, name)?
+            .ok_or(OxenError::local_branch_not_found(name))?;
+        let commit = repositories::commits::get_by_id(repo, &branch.commit_id)?.ok_or(
+            OxenError::revision_not_found(branch.commit_id.clone().into()),
+        )?;
+        found.push((branch, commit));
+    }
+    let (head_branch, head_commit) = found.pop().unwrap();
+    let (base_branch, base_commit) = found.pop().unwrap();
+    Ok((base_branch, base_commit, head_branch, head_commit))
+}
+
+/// The files the head branch changed since it split from the base branch
+pub fn changes(
+    repo: &LocalRepository,
+    proposal: &MergeProposal,
+    page: usize,
+    page_size: usize,
+) -> Result<DiffEntriesCounts, OxenError> {
+    let (_, base_commit, _, head_commit) = branch_commits(repo, proposal)?;
+    let lca =
+        repositories::merge::lowest_common_ancestor_from_commits(repo, &base_commit, &head_commit)?;
+    repositories::diffs::list_diff_entries(
+        repo,
+        &lca,
+        &head_commit,
+        PathBuf::from(""),
+        PathBuf::from(""),
+        page,
+        page_size,
+    )
+}
+
+/// Check whether the head branch can be merged into the base branch as they are now.
+/// Tabular files changed on both branches are merged row by row, so they only conflict
+/// if the same cells were changed differently.
+pub async fn check(
+    repo: &LocalRepository,
+    proposal: &MergeProposal,
+) -> Result<MergeProposalChecks, OxenError> {
+    let (_, base_commit, _, head_commit) = branch_commits(repo, proposal)?;
+    let conflicts =
+        repositories::merge::find_conflicts_between_commits(repo, &base_commit, &head_commit)
+            .await?
+            .into_iter()
+            .map(|conflict| MergeProposalConflict {
+                path: conflict.base_entry.1,
+                row_conflicts: conflict.row_conflicts,
+            })
+            .collect::<Vec<_>>();
+    let lca =
+        repositories::merge::lowest_common_ancestor_from_commits(repo, &base_commit, &head_commit)?;
+    let num_commits = repositories::commits::list_between(repo, &lca, &head_commit)?
+        .into_iter()
+        .filter(|commit| commit.id != lca.id)
+        .count();
+    let required_approvals = repositories::owners::required_approvals(
+        repo,
+        &lca,
+        &head_commit,
+        &proposal.approvers(&head_commit.id),
+    )?;
+
+    Ok(MergeProposalChecks {
+        is_mergeable: conflicts.is_empty()
+            && required_approvals
+                .iter()
+                .all(|approval| approval.is_approved()),
+        base_commit_id: base_commit.id,
+        head_commit_id: head_commit.id,
+        num_commits,
+        conflicts,
+        required_approvals,
+    })
+}
+
+/// Merge the head branch into the base branch and mark the proposal as merged. Fails if
+/// it changes owned paths that their owners have not approved.
+pub async fn merge(repo: &LocalRepository, id: u64) -> Result<MergeProposal, OxenError> {
+    let proposal = get(repo, id)?.ok_or(proposal_not_found(id))?;
+    require_open(&proposal)?;
+    let (base_branch, base_commit, head_branch, head_commit) = branch_commits(repo, &proposal)?;
+
+    let lca =
+        repositories::merge::lowest_common_ancestor_from_commits(repo, &base_commit, &head_commit)?;
+    repositories::owners::check_approved(&repositories::owners::required_approvals(
+        repo,
+        &lca,
+        &head_commit,
+        &proposal.approvers(&head_commit.id),
+    )?)?;
+
+    let merge_commit = if base_commit.id == head_commit.id {
+        // Nothing left to merge, the changes already made it into base
+        base_commit
+    } else {
+        repositories::merge::merge_into_base(repo, &head_branch, &base_branch)
+            .await?
+            .ok_or_else(|| {
+                OxenError::merge_conflict(format!(
+                    "Unable to merge {} into {} due to conflicts",
+                    proposal.head, proposal.base
+                ))
+            })?
+    };
+
+    update(repo, id, |proposal| {
+        require_open(proposal)?;
+        proposal.status = MergeProposalStatus::Merged;
+        proposal.merge_commit_id = Some(merge_commit.id.clone());
+        Ok(proposal.clone())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::config::OWNERS_CONFIG_FILENAME;
+    use crate::error::OxenError;
+    use crate::model::{MergeProposalStatus, User};
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+    use crate::view::{MergeProposalCommentNew, MergeProposalNew};
+
+    fn user() -> User {
+        User {
+            name: "Ox".to_string(),
+            email: "ox@oxen.ai".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_proposal_check_comment_and_merge() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            let path = repo.path.join("labels.csv");
+            util::fs::write_to_path(&path, "file,label\na.jpg,cat\nb.jpg,dog\n")?;
+            repositories::add(&repo, &path).await?;
+            repositories::commit(&repo, "Adding labels")?;
+
+            repositories::branches::create_checkout(&repo, "relabel")?;
+            util::fs::write_to_path(&path, "file,label\na.jpg,cat\nb.jpg,wolf\n")?;
+            repositories::add(&repo, &path).await?;
+            repositories::commit(&repo, "Relabel b")?;
+            repositories::checkout(&repo, "main").await?;
+
+            let new_proposal = MergeProposalNew {
+                title: "Relabel b".to_string(),
+                description: String::new(),
+                base: "main".to_string(),
+                head: "relabel".to_string(),
+                author: user(),
+            };
+            let proposal = repositories::proposals::create(&repo, &new_proposal)?;
+            assert_eq!(proposal.id, 1);
+            // Only one open proposal between the same branches
+            assert!(repositories::proposals::create(&repo, &new_proposal).is_err());
+
+            let changes = repositories::proposals::changes(&repo, &proposal, 1, 10)?;
+            assert_eq!(changes.entries.len(), 1);
+            assert_eq!(changes.entries[0].filename, "labels.csv");
+
+            let checks = repositories::proposals::check(&repo, &proposal).await?;
+            assert!(checks.is_mergeable);
+            assert_eq!(checks.num_commits, 1);
+
+            let comment = |body: &str, row: Option<&str>| MergeProposalCommentNew {
+                author: user(),
+                body: body.to_string(),
+                path: Some(PathBuf::from("labels.csv")),
+                row: row.map(String::from),
+            };
+            repositories::proposals::comment(&repo, proposal.id, &comment("Wolf?", Some("1")))?;
+            repositories::proposals::comment(&repo, proposal.id, &comment("Looks fine", None))?;
+            repositories::proposals::comment(&repo, proposal.id, &comment("Yes", Some("1")))?;
+            let proposal = repositories::proposals::get(&repo, proposal.id)?.unwrap();
+            let threads = proposal.threads();
+            assert_eq!(threads.len(), 2);
+            assert_eq!(threads[0].comments.len(), 2);
+            assert_eq!(threads[0].comments[1].body, "Yes");
+
+            let merged = repositories::proposals::merge(&repo, proposal.id).await?;
+            assert_eq!(merged.status, MergeProposalStatus::Merged);
+            let main = repositories::branches::get_by_name(&repo, "main")?.unwrap();
+            assert_eq!(merged.merge_commit_id, Some(main.commit_id));
+            assert!(repositories::proposals::merge(&repo, proposal.id)
+                .await
+                .is_err());
+
+            assert!(
+                repositories::proposals::list(&repo, Some(MergeProposalStatus::Open))?.is_empty()
+            );
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_proposal_changing_owned_paths_needs_owner_approval() -> Result<(), OxenError> {
+        test::run_one_commit_local_repo_test_async(|repo| async move {
+            util::fs::write_to_path(
+                util::fs::oxen_hidden_dir(&repo.path).join(OWNERS_CONFIG_FILENAME),
+                "@data = ada@oxen.ai\nschemas/ @data\n",
+            )?;
+            let main = repositories::branches::current_branch(&repo)?.unwrap();
+            repositories::branches::create_checkout(&repo, "schema")?;
+            let path = repo.path.join("schemas").join("images.json");
+            util::fs::write_to_path(&path, "{\"label\": \"str\"}")?;
+            repositories::add(&repo, &path).await?;
+            repositories::commit(&repo, "Adding a schema")?;
+            repositories::checkout(&repo, &main.name).await?;
+
+            let proposal = repositories::proposals::create(
+                &repo,
+                &MergeProposalNew {
+                    title: "Image schema".to_string(),
+                    description: String::new(),
+                    base: main.name.clone(),
+                    head: "schema".to_string(),
+                    author: user(),
+                },
+            )?;
+            let checks = repositories::proposals::check(&repo, &proposal).await?;
+            assert!(!checks.is_mergeable);
+            assert_eq!(checks.required_approvals.len(), 1);
+            assert_eq!(checks.required_approvals[0].approved_by, None);
+            let result = repositories::proposals::merge(&repo, proposal.id).await;
+            assert!(matches!(result, Err(OxenError::ApprovalRequired(_))));
+
+            // Authors can't approve their own proposals, and approvals by non owners don't count
+            let approve = |name: &str, email: &str| User {
+                name: name.to_string(),
+                email: email.to_string(),
+            };
+            assert!(repositories::proposals::approve(
+                &repo,
+                proposal.id,
+                &approve("Ox", "ox@oxen.ai")
+            )
+            .is_err());
+            repositories::proposals::approve(&repo, proposal.id, &approve("Bo", "bo@oxen.ai"))?;
+            assert!(repositories::proposals::merge(&repo, proposal.id)
+                .await
+                .is_err());
+
+            let ada = approve("Ada", "ada@oxen.ai");
+            let approved = repositories::proposals::approve(&repo, proposal.id, &ada)?;
+            assert_eq!(approved.approvals.len(), 2);
+            let checks = repositories::proposals::check(&repo, &approved).await?;
+            assert!(checks.is_mergeable);
+            assert_eq!(checks.required_approvals[0].approved_by, Some(ada));
+
+            let merged = repositories::proposals::merge(&repo, proposal.id).await?;
+            assert_eq!(merged.status, MergeProposalStatus::Merged);
+            Ok(())
+        })
+        .await
+    }
+}
@@ -0,0 +1,293 @@
+//! # Reflog
+//!
+//! Every time a branch or HEAD moves (commit, checkout, merge, reset, branch create
+//! or delete) the old and new commit are appended to `.oxen/reflog.jsonl`, so a
+//! branch can be put back where it was after a bad reset, squash or delete. Commits no
+//! branch points to any more stay in `.oxen/history` until they are recovered onto a new
+//! branch.
+//!
+
+use std::collections::HashSet;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::constants::{HISTORY_DIR, REFLOG_FILE};
+use crate::core::refs::with_ref_manager;
+use crate::error::OxenError;
+use crate::model::{Branch, Commit, LocalRepository, ReflogEntry};
+use crate::repositories;
+use crate::util;
+
+fn reflog_path(repo: &LocalRepository) -> PathBuf {
+    util::fs::oxen_hidden_dir(&repo.path).join(REFLOG_FILE)
+}
+
+/// Append an entry to the repository's reflog
+pub fn record(repo: &LocalRepository, entry: &ReflogEntry) -> Result<(), OxenError> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(reflog_path(repo))?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)?;
+    Ok(())
+}
+
+/// List every ref movement, newest first
+pub fn list(repo: &LocalRepository) -> Result<Vec<ReflogEntry>, OxenError> {
+    let mut entries: Vec<ReflogEntry> = vec![];
+    let path = reflog_path(repo);
+    if path.exists() {
+        let data = util::fs::read_from_path(&path)?;
+        for line in data.lines().filter(|line| !line.trim().is_empty()) {
+            entries.push(serde_json::from_str(line)?);
+        }
+    }
+    entries.reverse();
+    Ok(entries)
+}
+
+/// List the movements of one ref newest first, so entry `n` is `ref_name@{n}`
+pub fn list_for_ref(
+    repo: &LocalRepository,
+    ref_name: impl AsRef<str>,
+) -> Result<Vec<ReflogEntry>, OxenError> {
+    let ref_name = ref_name.as_ref();
+    Ok(list(repo)?
+        .into_iter()
+        .filter(|entry| entry.ref_name == ref_name)
+        .collect())
+}
+
+/// Point a branch back at the commit it had at reflog entry `branch@{n}`, recreating it
+/// if it was deleted. If the branch is checked out the working directory is updated too.
+pub async fn restore_branch(
+    repo: &LocalRepository,
+    branch_name: impl AsRef<str>,
+    n: usize,
+) -> Result<Commit, OxenError> {
+    let branch_name = branch_name.as_ref();
+    let entries = list_for_ref(repo, branch_name)?;
+    let Some(entry) = entries.get(n) else {
+        return Err(OxenError::basic_str(format!(
+            "{branch_name}@{{{n}}} is not in the reflog, {branch_name} has {} entries",
+            entries.len()
+        )));
+    };
+    let Some(commit_id) = &entry.new_id else {
+        return Err(OxenError::basic_str(format!(
+            "{branch_name}@{{{n}}} is where {branch_name} was deleted, restore an older entry"
+        )));
+    };
+    let commit = repositories::commits::get_by_id(repo, commit_id)?
+        .ok_or(OxenError::commit_id_does_not_exist(commit_id))?;
+
+    let action = format!("reset: moving to {branch_name}@{{{n}}}");
+    if repositories::branches::is_checked_out(repo, branch_name) {
+        let status = repositories::status(repo)?;
+        if status.has_added_entries()
+            || status.has_modified_entries()
+            || status.has_removed_entries()
+        {
+            return Err(OxenError::basic_str(
+                "Commit or restore your changes before moving the checked out branch",
+            ));
+        }
+        let from_commit = repositories::commits::head_commit_maybe(repo)?;
+        with_ref_manager(repo, |manager| {
+            manager.set_branch_commit_id_with_action(branch_name, &commit.id, &action)
+        })?;
+        repositories::branches::set_working_repo_to_commit(repo, &commit, &from_commit).await?;
+    } else {
+        with_ref_manager(repo, |manager| {
+            manager.set_branch_commit_id_with_action(branch_name, &commit.id, &action)
+        })?;
+    }
+    Ok(commit)
+}
+
+/// The commits still in the object store that no branch or tag leads to, newest first
+pub fn list_orphaned(repo: &LocalRepository) -> Result<Vec<Commit>, OxenError> {
+    let mut reachable: HashSet<String> = repositories::commits::list_all(repo)?
+        .into_iter()
+        .map(|commit| commit.id)
+        .collect();
+    for tag in repositories::tags::list(repo)? {
+        if !reachable.contains(&tag.commit_id) {
+            for commit in repositories::commits::list_from(repo, &tag.commit_id)? {
+                reachable.insert(commit.id);
+            }
+        }
+    }
+
+    let mut orphaned: Vec<Commit> = vec![];
+    for commit_id in stored_commit_ids(repo)? {
+        if reachable.contains(&commit_id) {
+            continue;
+        }
+        if let Some(commit) = repositories::commits::get_by_id(repo, &commit_id)? {
+            orphaned.push(commit);
+        }
+    }
+    orphaned.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    Ok(orphaned)
+}
+
+/// Create a branch at a commit that is still in the object store, even if no branch or
+/// reflog entry points to it any more. `commit_id` can be a unique prefix of the id. The
+/// branch is named `recovered-<short id>` unless a name is given.
+pub fn recover(
+    repo: &LocalRepository,
+    commit_id: impl AsRef<str>,
+    branch_name: Option<&str>,
+) -> Result<Branch, OxenError> {
+    let commit_id = commit_id.as_ref();
+    let matches: Vec<String> = stored_commit_ids(repo)?
+        .into_iter()
+        .filter(|id| id.starts_with(commit_id))
+        .collect();
+    let commit = match matches.as_slice() {
+        [id] => repositories::commits::get_by_id(repo, id)?
+            .ok_or(OxenError::commit_id_does_not_exist(id))?,
+        [] => return Err(OxenError::commit_id_does_not_exist(commit_id)),
+        _ => {
+            return Err(OxenError::basic_str(format!(
+                "{commit_id} matches {} commits, use more of the commit id",
+                matches.len()
+            )))
+        }
+    };
+
+    let name = match branch_name {
+        Some(name) => name.to_string(),
+        None => format!("recovered-{}", &commit.id[..commit.id.len().min(8)]),
+    };
+    let action = format!("branch: recovered {}", commit.id);
+    with_ref_manager(repo, |manager| {
+        if manager.is_invalid_branch_name(&name) {
+            return Err(OxenError::basic_str(format!(
+                "'{name}' is not a valid branch name."
+            )));
+        }
+        if manager.has_branch(&name) {
+            return Err(OxenError::basic_str(format!(
+                "Branch already exists: {name}"
+            )));
+        }
+        manager.set_branch_commit_id_with_action(&name, &commit.id, &action)
+    })?;
+    Ok(Branch {
+        name,
+        commit_id: commit.id,
+    })
+}
+
+// Every commit has a directory in .oxen/history named by its id
+fn stored_commit_ids(repo: &LocalRepository) -> Result<Vec<String>, OxenError> {
+    let history_dir = util::fs::oxen_hidden_dir(&repo.path).join(HISTORY_DIR);
+    if !history_dir.exists() {
+        return Ok(vec![]);
+    }
+    Ok(util::fs::list_dirs_in_dir(&history_dir)?
+        .into_iter()
+        .filter_map(|dir| {
+            dir.file_name()
+                .map(|name| name.to_string_lossy().to_string())
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::OxenError;
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    #[tokio::test]
+    async fn test_reflog_records_commits_and_checkouts() -> Result<(), OxenError> {
+        test::run_one_commit_local_repo_test_async(|repo| async move {
+            let first = repositories::commits::head_commit(&repo)?;
+            repositories::branches::create_checkout(&repo, "dev")?;
+
+            let file = repo.path.join("dev.txt");
+            util::fs::write_to_path(&file, "dev")?;
+            repositories::add(&repo, &file).await?;
+            let second = repositories::commit(&repo, "Add dev file")?;
+
+            let dev = repositories::reflog::list_for_ref(&repo, "dev")?;
+            assert_eq!(dev.len(), 2);
+            assert_eq!(dev[0].action, "commit: Add dev file");
+            assert_eq!(dev[0].old_id, Some(first.id.clone()));
+            assert_eq!(dev[0].new_id, Some(second.id.clone()));
+            assert_eq!(dev[1].action, "branch: created");
+
+            let head = repositories::reflog::list_for_ref(&repo, "HEAD")?;
+            assert!(head[0].action.ends_with("to dev"));
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_restore_deleted_branch_from_reflog() -> Result<(), OxenError> {
+        test::run_one_commit_local_repo_test_async(|repo| async move {
+            let og_branch = repositories::branches::current_branch(&repo)?.unwrap();
+            repositories::branches::create_checkout(&repo, "experiment")?;
+            let file = repo.path.join("experiment.txt");
+            util::fs::write_to_path(&file, "experiment")?;
+            repositories::add(&repo, &file).await?;
+            let lost = repositories::commit(&repo, "Experiment")?;
+
+            repositories::checkout(&repo, &og_branch.name).await?;
+            repositories::branches::force_delete(&repo, "experiment")?;
+            assert!(!repositories::branches::exists(&repo, "experiment")?);
+
+            // experiment@{0} is the delete, experiment@{1} the commit
+            assert!(repositories::reflog::restore_branch(&repo, "experiment", 0)
+                .await
+                .is_err());
+            let restored = repositories::reflog::restore_branch(&repo, "experiment", 1).await?;
+            assert_eq!(restored.id, lost.id);
+            let branch = repositories::branches::get_by_name(&repo, "experiment")?.unwrap();
+            assert_eq!(branch.commit_id, lost.id);
+
+            let entries = repositories::reflog::list_for_ref(&repo, "experiment")?;
+            assert_eq!(entries[0].action, "reset: moving to experiment@{1}");
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_recover_orphaned_commit() -> Result<(), OxenError> {
+        test::run_one_commit_local_repo_test_async(|repo| async move {
+            let og_branch = repositories::branches::current_branch(&repo)?.unwrap();
+            repositories::branches::create_checkout(&repo, "scratch")?;
+            let file = repo.path.join("scratch.txt");
+            util::fs::write_to_path(&file, "scratch")?;
+            repositories::add(&repo, &file).await?;
+            let lost = repositories::commit(&repo, "Scratch work")?;
+
+            repositories::checkout(&repo, &og_branch.name).await?;
+            repositories::branches::force_delete(&repo, "scratch")?;
+
+            let orphaned = repositories::reflog::list_orphaned(&repo)?;
+            assert_eq!(orphaned.len(), 1);
+            assert_eq!(orphaned[0].id, lost.id);
+
+            let branch = repositories::reflog::recover(&repo, &lost.id[..10], None)?;
+            assert_eq!(branch.commit_id, lost.id);
+            assert_eq!(branch.name, format!("recovered-{}", &lost.id[..8]));
+            assert!(repositories::reflog::list_orphaned(&repo)?.is_empty());
+
+            // The name is taken now
+            assert!(repositories::reflog::recover(&repo, &lost.id, Some(&branch.name)).is_err());
+
+            Ok(())
+        })
+        .await
+    }
+}
@@ -0,0 +1,105 @@
+//! # Render
+//!
+//! Sanitized HTML previews of the markdown files and jupyter notebooks in a revision,
+//! see [`crate::core::render`]. The HTML is cached by the hash of the file, so every
+//! revision that shares the file shares the rendered copy.
+//!
+
+use std::path::{Path, PathBuf};
+
+use crate::constants::CACHE_DIR;
+use crate::core::render;
+use crate::error::OxenError;
+use crate::model::{Commit, LocalRepository};
+use crate::repositories;
+use crate::util;
+
+const RENDER_CACHE_DIR: &str = "render";
+
+/// Whether files with this extension can be rendered to HTML
+pub fn is_renderable(path: impl AsRef<Path>) -> bool {
+    matches!(
+        extension(path.as_ref()).as_str(),
+        "md" | "markdown" | "ipynb"
+    )
+}
+
+/// Render the markdown or notebook at `path` in the commit to sanitized HTML
+pub async fn to_html(
+    repo: &LocalRepository,
+    commit: &Commit,
+    path: impl AsRef<Path>,
+) -> Result<String, OxenError> {
+    let path = path.as_ref();
+    if !is_renderable(path) {
+        return Err(OxenError::basic_str(format!(
+            "Cannot render {path:?}, only markdown and notebook files can be rendered"
+        )));
+    }
+    let file_node = repositories::tree::get_file_by_path(repo, commit, path)?
+        .ok_or(OxenError::path_does_not_exist(path))?;
+
+    let hash = file_node.hash().to_string();
+    let cache_path = cache_path(repo, &hash);
+    if cache_path.exists() {
+        return util::fs::read_from_path(&cache_path);
+    }
+
+    let version_store = repo.version_store()?;
+    let contents = version_store.get_version(&hash).await?;
+    let source = String::from_utf8(contents)
+        .map_err(|_| OxenError::basic_str(format!("Cannot render {path:?}, it is not utf-8")))?;
+    let html = match extension(path).as_str() {
+        "ipynb" => render::notebook_to_html(&source)?,
+        _ => render::markdown_to_html(&source),
+    };
+
+    util::fs::write_to_path(&cache_path, &html)?;
+    Ok(html)
+}
+
+fn extension(path: &Path) -> String {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+fn cache_path(repo: &LocalRepository, hash: &str) -> PathBuf {
+    util::fs::oxen_hidden_dir(&repo.path)
+        .join(CACHE_DIR)
+        .join(RENDER_CACHE_DIR)
+        .join(format!("{hash}.html"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::OxenError;
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    #[tokio::test]
+    async fn test_render_markdown_at_revision() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            let path = repo.path.join("README.md");
+            util::fs::write_to_path(&path, "# Cats\n\n<script>alert(1)</script>\n")?;
+            repositories::add(&repo, &path).await?;
+            let commit = repositories::commit(&repo, "Adding a readme")?;
+
+            let html = repositories::render::to_html(&repo, &commit, "README.md").await?;
+            assert!(html.contains("<h1>Cats</h1>"));
+            assert!(!html.contains("<script"));
+
+            // Rendered from the cache the second time, edits after the commit do not show
+            util::fs::write_to_path(&path, "# Dogs\n")?;
+            let cached = repositories::render::to_html(&repo, &commit, "README.md").await?;
+            assert_eq!(cached, html);
+
+            assert!(repositories::render::to_html(&repo, &commit, "data.csv")
+                .await
+                .is_err());
+            Ok(())
+        })
+        .await
+    }
+}
@@ -0,0 +1,244 @@
+//! # Retention
+//!
+//! Prune the version files of old commits according to the repository's
+//! [`RetentionConfig`]. Commits stay in the history, but once their files are pruned
+//! they can no longer be checked out or downloaded.
+//!
+//! A commit keeps its files if it is newer than `keep_all_days`, a branch or tag points
+//! at it, it is pinned, or a workspace is based on it. Version files shared with any of
+//! those commits are never pruned.
+//!
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+use crate::config::RetentionConfig;
+use crate::error::OxenError;
+use crate::model::merkle_tree::node::EMerkleTreeNode;
+use crate::model::{Commit, LocalRepository, MerkleHash};
+use crate::repositories;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct RetentionReport {
+    /// Commits past the cutoff that are not kept for another reason
+    pub expired_commits: Vec<String>,
+    pub pruned_versions: usize,
+    pub pruned_bytes: u64,
+    pub dry_run: bool,
+}
+
+/// Apply the repository's retention policy, if it has one. With `dry_run` nothing is
+/// deleted, and the report says what would have been.
+pub async fn apply(
+    repo: &LocalRepository,
+    dry_run: bool,
+) -> Result<Option<RetentionReport>, OxenError> {
+    let Some(config) = repo.retention_config() else {
+        return Ok(None);
+    };
+    let report = apply_config(repo, &config, OffsetDateTime::now_utc(), dry_run).await?;
+    Ok(Some(report))
+}
+
+async fn apply_config(
+    repo: &LocalRepository,
+    config: &RetentionConfig,
+    now: OffsetDateTime,
+    dry_run: bool,
+) -> Result<RetentionReport, OxenError> {
+    let cutoff = config.cutoff(now);
+    let mut kept: HashSet<String> = config.pinned_commits.iter().cloned().collect();
+    for branch in repositories::branches::list(repo)? {
+        kept.insert(branch.commit_id);
+    }
+    for workspace in repositories::workspaces::list(repo)? {
+        kept.insert(workspace.commit.id);
+    }
+
+    // A tag can outlive the branch it was made on, so its history is walked as well
+    let mut commits = repositories::commits::list_all(repo)?;
+    for tag in repositories::tags::list(repo)? {
+        let Some(commit) = repositories::commits::get_by_id(repo, &tag.commit_id)? else {
+            continue;
+        };
+        if !commits.contains(&commit) {
+            commits.extend(repositories::commits::list_from(repo, &tag.commit_id)?);
+        }
+        kept.insert(tag.commit_id);
+    }
+
+    let (retained, expired): (Vec<Commit>, Vec<Commit>) = commits
+        .into_iter()
+        .partition(|commit| commit.timestamp >= cutoff || kept.contains(&commit.id));
+
+    let mut retained_hashes: HashMap<MerkleHash, u64> = HashMap::new();
+    for commit in &retained {
+        collect_file_hashes(repo, commit, &mut retained_hashes)?;
+    }
+    let mut expired_hashes: HashMap<MerkleHash, u64> = HashMap::new();
+    for commit in &expired {
+        collect_file_hashes(repo, commit, &mut expired_hashes)?;
+    }
+
+    let mut expired_commits: Vec<String> = expired.into_iter().map(|commit| commit.id).collect();
+    expired_commits.sort();
+    let mut report = RetentionReport {
+        expired_commits,
+        dry_run,
+        ..Default::default()
+    };
+
+    let version_store = repo.version_store()?;
+    for (hash, num_bytes) in expired_hashes {
+        if retained_hashes.contains_key(&hash) {
+            continue;
+        }
+        let hash = hash.to_string();
+        if !version_store.version_exists(&hash)? {
+            continue;
+        }
+        if !dry_run {
+            version_store.delete_version(&hash).await?;
+        }
+        report.pruned_versions += 1;
+        report.pruned_bytes += num_bytes;
+    }
+
+    log::info!(
+        "retention for {:?} pruned {} versions ({} bytes) from {} commits, dry run: {}",
+        repo.path,
+        report.pruned_versions,
+        report.pruned_bytes,
+        report.expired_commits.len(),
+        dry_run
+    );
+    Ok(report)
+}
+
+fn collect_file_hashes(
+    repo: &LocalRepository,
+    commit: &Commit,
+    hashes: &mut HashMap<MerkleHash, u64>,
+) -> Result<(), OxenError> {
+    let Some(tree) = repositories::tree::get_root_with_children(repo, commit)? else {
+        log::warn!("retention could not load the tree for commit {}", commit.id);
+        return Ok(());
+    };
+    tree.walk_tree(|node| {
+        if let EMerkleTreeNode::File(file) = &node.node {
+            hashes.insert(node.hash, file.num_bytes());
+        }
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use time::{Duration, OffsetDateTime};
+
+    use super::apply_config;
+    use crate::config::RetentionConfig;
+    use crate::constants::REFLOG_FILE;
+    use crate::error::OxenError;
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    #[tokio::test]
+    async fn test_retention_prunes_files_only_in_expired_commits() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            let text_path = repo.path.join("text.txt");
+            util::fs::write_to_path(&text_path, "First version")?;
+            repositories::add(&repo, &text_path).await?;
+            let first = repositories::commit(&repo, "First")?;
+            let old_file = repositories::tree::get_file_by_path(&repo, &first, "text.txt")?
+                .expect("file exists");
+
+            util::fs::write_to_path(&text_path, "Second version")?;
+            repositories::add(&repo, &text_path).await?;
+            let second = repositories::commit(&repo, "Second")?;
+            let new_file = repositories::tree::get_file_by_path(&repo, &second, "text.txt")?
+                .expect("file exists");
+
+            // Both commits are past the cutoff, but main still points at the second
+            let now = OffsetDateTime::now_utc() + Duration::days(2);
+            let version_store = repo.version_store()?;
+            let mut config = RetentionConfig {
+                keep_all_days: 1,
+                pinned_commits: vec![first.id.clone()],
+            };
+            let report = apply_config(&repo, &config, now, false).await?;
+            assert!(report.expired_commits.is_empty());
+            assert_eq!(report.pruned_versions, 0);
+
+            config.pinned_commits.clear();
+            let report = apply_config(&repo, &config, now, true).await?;
+            assert_eq!(report.expired_commits, vec![first.id.clone()]);
+            assert_eq!(report.pruned_versions, 1);
+            assert!(version_store.version_exists(&old_file.hash().to_string())?);
+
+            let report = apply_config(&repo, &config, now, false).await?;
+            assert_eq!(report.pruned_versions, 1);
+            assert_eq!(report.pruned_bytes, old_file.num_bytes());
+            assert!(!version_store.version_exists(&old_file.hash().to_string())?);
+            assert!(version_store.version_exists(&new_file.hash().to_string())?);
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_gc_and_retention_keep_tagged_files() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            let text_path = repo.path.join("text.txt");
+            util::fs::write_to_path(&text_path, "First version")?;
+            repositories::add(&repo, &text_path).await?;
+            let first = repositories::commit(&repo, "First")?;
+
+            // Tag a commit on a branch that is deleted afterwards
+            repositories::branches::create_checkout(&repo, "release")?;
+            let tagged_path = repo.path.join("tagged.txt");
+            util::fs::write_to_path(&tagged_path, "Released")?;
+            repositories::add(&repo, &tagged_path).await?;
+            let tagged = repositories::commit(&repo, "Release")?;
+            repositories::tags::create(&repo, "v1.0", &tagged.id, "First release")?;
+            let tagged_files = [
+                repositories::tree::get_file_by_path(&repo, &tagged, "text.txt")?
+                    .expect("file exists"),
+                repositories::tree::get_file_by_path(&repo, &tagged, "tagged.txt")?
+                    .expect("file exists"),
+            ];
+
+            repositories::checkout(&repo, "main").await?;
+            util::fs::write_to_path(&text_path, "Second version")?;
+            repositories::add(&repo, &text_path).await?;
+            repositories::commit(&repo, "Second")?;
+            repositories::branches::force_delete(&repo, "release")?;
+            // Without the reflog the tag is the only thing left pointing at the commit
+            util::fs::remove_file(util::fs::oxen_hidden_dir(&repo.path).join(REFLOG_FILE))?;
+
+            let report = repositories::gc::run(&repo, false).await?;
+            assert_eq!(report.removed_versions, 0);
+
+            let now = OffsetDateTime::now_utc() + Duration::days(2);
+            let config = RetentionConfig {
+                keep_all_days: 1,
+                pinned_commits: vec![],
+            };
+            let report = apply_config(&repo, &config, now, false).await?;
+            assert_eq!(report.expired_commits, vec![first.id.clone()]);
+            assert_eq!(report.pruned_versions, 0);
+
+            let version_store = repo.version_store()?;
+            for file in tagged_files {
+                assert!(version_store.version_exists(&file.hash().to_string())?);
+            }
+
+            Ok(())
+        })
+        .await
+    }
+}
@@ -1,4 +1,4 @@
-//! Revisions can either be commits by id or head commits on branches by name
+//! Revisions can either be commits by id, head commits on branches by name, or tagged commits
 
 use std::path::{Path, PathBuf};
 
@@ -8,7 +8,7 @@ use crate::error::OxenError;
 use crate::model::{Commit, LocalRepository};
 use crate::repositories;
 
-/// Get a commit object from a commit id or branch name
+/// Get a commit object from a commit id, branch name or tag name
 /// Returns Ok(None) if the revision does not exist
 pub fn get(repo: &LocalRepository, revision: impl AsRef<str>) -> Result<Option<Commit>, OxenError> {
     let revision = revision.as_ref();
@@ -23,6 +23,9 @@ pub fn get(repo: &LocalRepository, revision: impl AsRef<str>) -> Result<Option<C
         let branch = branch.ok_or(OxenError::local_branch_not_found(revision))?;
         let commit = repositories::commits::get_by_id(repo, &branch.commit_id)?;
         Ok(commit)
+    } else if let Some(tag) = repositories::tags::get_by_name(repo, revision)? {
+        log::debug!("revision is a tag: {}", revision);
+        repositories::commits::get_by_id(repo, &tag.commit_id)
     } else {
         log::debug!("revision is a commit id: {}", revision);
         let commit = repositories::commits::get_by_id(repo, revision)?;
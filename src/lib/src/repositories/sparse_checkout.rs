@@ -0,0 +1,236 @@
+//! # oxen sparse-checkout
+//!
+//! Only keep some directories of the repository in the working tree. The paths are saved
+//! as the subtree paths in the repo config, the same ones a clone with `--filter` sets,
+//! so checkout, status and pull only materialize and track those subtrees.
+//!
+
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
+
+use crate::api;
+use crate::constants::DEFAULT_REMOTE_NAME;
+use crate::core;
+use crate::core::v_latest::index::restore;
+use crate::error::OxenError;
+use crate::model::{Commit, LocalRepository};
+use crate::repositories;
+use crate::util;
+
+/// The directories that are checked out, None if it is the whole tree
+pub fn list(repo: &LocalRepository) -> Option<Vec<PathBuf>> {
+    repo.subtree_paths()
+}
+
+/// Check out only the given directories of the HEAD commit. Files of directories that are
+/// no longer selected are removed from the working tree, and the data for newly selected
+/// directories is downloaded from the remote if it is missing.
+pub async fn set(repo: &LocalRepository, paths: &[PathBuf]) -> Result<LocalRepository, OxenError> {
+    if repo.is_remote_mode() {
+        return Err(OxenError::basic_str(
+            "sparse-checkout is not supported in remote mode",
+        ));
+    }
+    if paths.is_empty() {
+        return Err(OxenError::basic_str(
+            "sparse-checkout needs at least one directory",
+        ));
+    }
+    let paths = paths
+        .iter()
+        .map(normalize_path)
+        .collect::<Result<Vec<PathBuf>, OxenError>>()?;
+
+    let status = repositories::status(repo)?;
+    if status.has_added_entries() || status.has_modified_entries() || status.has_removed_entries() {
+        return Err(OxenError::basic_str(
+            "You have uncommitted changes, commit or restore them before changing the sparse checkout",
+        ));
+    }
+
+    let head_commit = repositories::commits::head_commit(repo)?;
+    let dir_hashes = repositories::tree::dir_hashes(repo, &head_commit)?;
+    for path in &paths {
+        if !dir_hashes.contains_key(path) {
+            return Err(OxenError::basic_str(format!(
+                "Directory {path:?} does not exist in commit {}",
+                head_commit.id
+            )));
+        }
+    }
+
+    let previous_paths = repo
+        .subtree_paths()
+        .unwrap_or_else(|| vec![PathBuf::from("")]);
+    let mut repo = repo.clone();
+    repo.set_subtree_paths(Some(paths.clone()));
+
+    // Download what the previous paths did not cover before touching the working tree
+    let new_paths: Vec<PathBuf> = paths
+        .iter()
+        .filter(|path| {
+            !previous_paths
+                .iter()
+                .any(|previous| path.starts_with(previous))
+        })
+        .cloned()
+        .collect();
+    if !new_paths.is_empty() {
+        fetch_subtrees(&repo, &head_commit, &new_paths).await?;
+    }
+
+    remove_unselected_files(&repo, &head_commit, &previous_paths)?;
+    restore_selected_files(&repo, &head_commit, &new_paths).await?;
+
+    repo.save()?;
+    Ok(repo)
+}
+
+fn normalize_path(path: &PathBuf) -> Result<PathBuf, OxenError> {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => normalized.push(part),
+            Component::CurDir => {}
+            _ => {
+                return Err(OxenError::basic_str(format!(
+                    "sparse-checkout paths must be relative to the repository root: {path:?}"
+                )))
+            }
+        }
+    }
+    Ok(normalized)
+}
+
+async fn fetch_subtrees(
+    repo: &LocalRepository,
+    commit: &Commit,
+    paths: &[PathBuf],
+) -> Result<(), OxenError> {
+    let Some(remote) = repo.get_remote(DEFAULT_REMOTE_NAME) else {
+        log::debug!("sparse-checkout no remote, expecting the data to be local");
+        return Ok(());
+    };
+    let Some(remote_repo) = api::client::repositories::get_by_remote(&remote).await? else {
+        return Err(OxenError::remote_repo_not_found(&remote.url));
+    };
+    core::v_latest::fetch::fetch_subtrees(repo, &remote_repo, commit, paths, repo.depth()).await
+}
+
+/// Write the files of the newly selected paths to the working tree
+async fn restore_selected_files(
+    repo: &LocalRepository,
+    commit: &Commit,
+    paths: &[PathBuf],
+) -> Result<(), OxenError> {
+    let depth = repo.depth().unwrap_or(-1);
+    let version_store = repo.version_store()?;
+    for path in paths {
+        let Some(tree) = repositories::tree::get_subtree(repo, commit, path, depth)? else {
+            continue;
+        };
+        for file in repositories::tree::list_all_files(&tree, path)? {
+            let file_path = file.dir.join(file.file_node.name());
+            if repo.path.join(&file_path).exists() {
+                continue;
+            }
+            restore::restore_file(repo, &file.file_node, &file_path, &version_store).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Remove the files of the previously checked out paths that the new paths do not cover
+fn remove_unselected_files(
+    repo: &LocalRepository,
+    commit: &Commit,
+    previous_paths: &[PathBuf],
+) -> Result<(), OxenError> {
+    let depth = repo.depth().unwrap_or(-1);
+    let mut removed_dirs: HashSet<PathBuf> = HashSet::new();
+    for previous_path in previous_paths {
+        let Some(tree) = repositories::tree::get_subtree(repo, commit, previous_path, depth)?
+        else {
+            continue;
+        };
+        for file in repositories::tree::list_all_files(&tree, previous_path)? {
+            let path = file.dir.join(file.file_node.name());
+            if repo.is_in_subtree_paths(&path) {
+                continue;
+            }
+            let full_path = repo.path.join(&path);
+            if !full_path.exists() {
+                continue;
+            }
+            if util::fs::is_modified_from_node(repo, &full_path, &file.file_node)? {
+                log::warn!("sparse-checkout keeping modified file {:?}", path);
+                continue;
+            }
+            util::fs::remove_file(&full_path)?;
+            removed_dirs.insert(file.dir);
+        }
+    }
+
+    // Clean up the directories that are empty now, deepest first
+    let mut removed_dirs: Vec<PathBuf> = removed_dirs.into_iter().collect();
+    removed_dirs.sort_by_key(|dir| std::cmp::Reverse(dir.components().count()));
+    for dir in removed_dirs {
+        remove_empty_dirs(&repo.path, &dir)?;
+    }
+    Ok(())
+}
+
+fn remove_empty_dirs(repo_path: &Path, dir: &Path) -> Result<(), OxenError> {
+    let mut dir = dir.to_path_buf();
+    while dir != Path::new("") {
+        let full_path = repo_path.join(&dir);
+        if !full_path.is_dir() || std::fs::read_dir(&full_path)?.next().is_some() {
+            break;
+        }
+        std::fs::remove_dir(&full_path)?;
+        dir = dir.parent().map(Path::to_path_buf).unwrap_or_default();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::error::OxenError;
+    use crate::model::LocalRepository;
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    #[tokio::test]
+    async fn test_sparse_checkout_set() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            for (dir, name) in [("annotations", "train.csv"), ("images", "cat.jpg")] {
+                let path = repo.path.join(dir).join(name);
+                util::fs::create_dir_all(path.parent().unwrap())?;
+                util::fs::write_to_path(&path, format!("Contents of {name}"))?;
+                repositories::add(&repo, &path).await?;
+            }
+            repositories::commit(&repo, "Add annotations and images")?;
+
+            let paths = vec![PathBuf::from("annotations")];
+            let repo = repositories::sparse_checkout::set(&repo, &paths).await?;
+            assert!(repo.path.join("annotations").join("train.csv").exists());
+            assert!(!repo.path.join("images").exists());
+
+            // The paths are saved, and status does not report the images as removed
+            let repo = LocalRepository::from_dir(&repo.path)?;
+            assert_eq!(repositories::sparse_checkout::list(&repo), Some(paths));
+            let status = repositories::status(&repo)?;
+            assert!(status.is_clean(), "{status:?}");
+
+            // Selecting the root brings everything back
+            let repo = repositories::sparse_checkout::set(&repo, &[PathBuf::from(".")]).await?;
+            assert!(repo.path.join("images").join("cat.jpg").exists());
+
+            Ok(())
+        })
+        .await
+    }
+}
@@ -0,0 +1,302 @@
+//! # oxen stash
+//!
+//! Save the staged and unstaged changes of the working directory away and reset it to
+//! HEAD, then put them back later with `oxen stash pop`. Each stash entry is a json file
+//! in `.oxen/stash` that points at version files, so stashing large files does not copy
+//! them again.
+//!
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use time::OffsetDateTime;
+
+use crate::constants::STASH_DIR;
+use crate::error::OxenError;
+use crate::model::merkle_tree::node::EMerkleTreeNode;
+use crate::model::{
+    LocalRepository, MerkleHash, StagedEntryStatus, StashEntry, StashedChange, StashedFile,
+};
+use crate::opts::{RestoreOpts, RmOpts};
+use crate::repositories;
+use crate::storage::VersionStore;
+use crate::util;
+
+fn stash_dir(repo: &LocalRepository) -> PathBuf {
+    util::fs::oxen_hidden_dir(&repo.path).join(STASH_DIR)
+}
+
+/// List the stash entries newest first, so entry `n` is `stash@{n}`
+pub fn list(repo: &LocalRepository) -> Result<Vec<StashEntry>, OxenError> {
+    let dir = stash_dir(repo);
+    let mut entries: Vec<StashEntry> = vec![];
+    if !dir.exists() {
+        return Ok(entries);
+    }
+    for path in util::fs::list_files_in_dir(&dir) {
+        if path.extension().is_some_and(|ext| ext == "json") {
+            let data = util::fs::read_from_path(&path)?;
+            entries.push(serde_json::from_str(&data)?);
+        }
+    }
+    entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    Ok(entries)
+}
+
+/// Save the staged and unstaged changes to tracked files and reset them to HEAD. Untracked
+/// files are left alone. Returns None if there was nothing to stash.
+pub async fn push(
+    repo: &LocalRepository,
+    message: Option<&str>,
+) -> Result<Option<StashEntry>, OxenError> {
+    let Some(head_commit) = repositories::commits::head_commit_maybe(repo)? else {
+        return Err(OxenError::basic_str(
+            "Cannot stash changes before the first commit",
+        ));
+    };
+    let status = repositories::status(repo)?;
+    let version_store = repo.version_store()?;
+    let mut files: BTreeMap<PathBuf, StashedFile> = BTreeMap::new();
+
+    for (path, entry) in &status.staged_files {
+        let staged = match entry.status {
+            StagedEntryStatus::Added | StagedEntryStatus::Modified => StashedChange::Modified {
+                hash: entry.hash.clone(),
+            },
+            StagedEntryStatus::Removed => StashedChange::Removed,
+            StagedEntryStatus::Unmodified => continue,
+        };
+        // The working copy may have changed again since it was staged
+        let full_path = repo.path.join(path);
+        let unstaged = match (&staged, full_path.exists()) {
+            (StashedChange::Modified { hash }, true) => {
+                let working_hash = store_working_file(repo, &version_store, &full_path).await?;
+                (&working_hash != hash).then_some(StashedChange::Modified { hash: working_hash })
+            }
+            (StashedChange::Modified { .. }, false) => Some(StashedChange::Removed),
+            (StashedChange::Removed, true) => Some(StashedChange::Modified {
+                hash: store_working_file(repo, &version_store, &full_path).await?,
+            }),
+            (StashedChange::Removed, false) => None,
+        };
+        files.insert(
+            path.clone(),
+            StashedFile {
+                path: path.clone(),
+                staged: Some(staged),
+                unstaged,
+            },
+        );
+    }
+
+    for path in &status.modified_files {
+        if files.contains_key(path) {
+            continue;
+        }
+        let hash = store_working_file(repo, &version_store, &repo.path.join(path)).await?;
+        files.insert(
+            path.clone(),
+            StashedFile {
+                path: path.clone(),
+                staged: None,
+                unstaged: Some(StashedChange::Modified { hash }),
+            },
+        );
+    }
+
+    for path in &status.removed_files {
+        // Status reports a removed directory as a single entry
+        let removed_paths = match repositories::tree::get_node_by_path(repo, &head_commit, path)? {
+            Some(node) if matches!(node.node, EMerkleTreeNode::Directory(_)) => {
+                repositories::tree::list_all_files(&node, path)?
+                    .into_iter()
+                    .map(|file| file.dir.join(file.file_node.name()))
+                    .collect()
+            }
+            _ => vec![path.clone()],
+        };
+        for path in removed_paths {
+            files.entry(path.clone()).or_insert(StashedFile {
+                path,
+                staged: None,
+                unstaged: Some(StashedChange::Removed),
+            });
+        }
+    }
+
+    if files.is_empty() {
+        return Ok(None);
+    }
+
+    let branch = repositories::branches::current_branch(repo)?.map(|branch| branch.name);
+    let message = match message {
+        Some(message) => message.to_string(),
+        None => format!(
+            "WIP on {}: {} {}",
+            branch.as_deref().unwrap_or("HEAD"),
+            head_commit.id,
+            head_commit.message
+        ),
+    };
+    let entry = StashEntry {
+        id: uuid::Uuid::new_v4().to_string(),
+        message,
+        branch,
+        commit_id: head_commit.id.clone(),
+        files: files.into_values().collect(),
+        timestamp: OffsetDateTime::now_utc(),
+    };
+
+    // Save the entry before touching the working directory so nothing is lost on an error
+    let dir = stash_dir(repo);
+    util::fs::create_dir_all(&dir)?;
+    util::fs::write_to_path(
+        dir.join(format!("{}.json", entry.id)),
+        serde_json::to_string_pretty(&entry)?,
+    )?;
+
+    // Unstage everything first, then put the working files back to HEAD
+    for file in entry.files.iter().filter(|file| file.staged.is_some()) {
+        repositories::restore(repo, RestoreOpts::from_staged_path(&file.path)).await?;
+    }
+    for file in &entry.files {
+        let in_head =
+            repositories::tree::get_file_by_path(repo, &head_commit, &file.path)?.is_some();
+        if in_head {
+            repositories::restore(repo, RestoreOpts::from_path(&file.path)).await?;
+        } else {
+            let full_path = repo.path.join(&file.path);
+            if full_path.exists() {
+                util::fs::remove_file(&full_path)?;
+            }
+        }
+    }
+
+    Ok(Some(entry))
+}
+
+/// Put the changes of stash entry `stash@{index}` back and remove it from the stash. The
+/// changes are applied as they were saved, even if HEAD moved since.
+pub async fn pop(repo: &LocalRepository, index: usize) -> Result<StashEntry, OxenError> {
+    let entries = list(repo)?;
+    let Some(entry) = entries.into_iter().nth(index) else {
+        return Err(OxenError::basic_str(format!(
+            "No stash entry found for stash@{{{index}}}"
+        )));
+    };
+
+    // Refuse to overwrite changes made since the stash
+    let status = repositories::status(repo)?;
+    let conflicts: Vec<PathBuf> = entry
+        .files
+        .iter()
+        .map(|file| file.path.clone())
+        .filter(|path| {
+            status.staged_files.contains_key(path)
+                || status.modified_files.contains(path)
+                || status.removed_files.contains(path)
+                || status.untracked_files.contains(path)
+        })
+        .collect();
+    if !conflicts.is_empty() {
+        return Err(OxenError::cannot_overwrite_files(&conflicts));
+    }
+
+    let version_store = repo.version_store()?;
+    for file in &entry.files {
+        let full_path = repo.path.join(&file.path);
+        match &file.staged {
+            Some(StashedChange::Modified { hash }) => {
+                write_version(&version_store, hash, &full_path).await?;
+                repositories::add(repo, &full_path).await?;
+            }
+            Some(StashedChange::Removed) => {
+                repositories::rm(repo, &RmOpts::from_path(&file.path))?;
+            }
+            None => {}
+        }
+        match &file.unstaged {
+            Some(StashedChange::Modified { hash }) => {
+                write_version(&version_store, hash, &full_path).await?;
+            }
+            Some(StashedChange::Removed) => {
+                if full_path.exists() {
+                    util::fs::remove_file(&full_path)?;
+                }
+            }
+            None => {}
+        }
+    }
+
+    util::fs::remove_file(stash_dir(repo).join(format!("{}.json", entry.id)))?;
+    Ok(entry)
+}
+
+async fn store_working_file(
+    repo: &LocalRepository,
+    version_store: &Arc<dyn VersionStore>,
+    full_path: &Path,
+) -> Result<String, OxenError> {
+    let hash = MerkleHash::new(repo.hash_algorithm().hash_file_contents(full_path)?).to_string();
+    version_store
+        .store_version_from_path(&hash, full_path)
+        .await?;
+    Ok(hash)
+}
+
+async fn write_version(
+    version_store: &Arc<dyn VersionStore>,
+    hash: &str,
+    full_path: &Path,
+) -> Result<(), OxenError> {
+    if let Some(parent) = full_path.parent() {
+        util::fs::create_dir_all(parent)?;
+    }
+    version_store.copy_version_to_path(hash, full_path).await
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::OxenError;
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    #[tokio::test]
+    async fn test_stash_push_and_pop() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            let a_path = repo.path.join("a.txt");
+            util::fs::write_to_path(&a_path, "committed")?;
+            repositories::add(&repo, &a_path).await?;
+            repositories::commit(&repo, "Add a")?;
+
+            // An unstaged modification and a staged new file
+            util::fs::write_to_path(&a_path, "work in progress")?;
+            let c_path = repo.path.join("c.txt");
+            util::fs::write_to_path(&c_path, "new file")?;
+            repositories::add(&repo, &c_path).await?;
+
+            let entry = repositories::stash::push(&repo, None).await?;
+            assert!(entry.is_some());
+            assert_eq!(util::fs::read_from_path(&a_path)?, "committed");
+            assert!(!c_path.exists());
+            assert!(repositories::status(&repo)?.is_clean());
+            assert_eq!(repositories::stash::list(&repo)?.len(), 1);
+
+            // Nothing left to stash
+            assert!(repositories::stash::push(&repo, None).await?.is_none());
+
+            repositories::stash::pop(&repo, 0).await?;
+            assert_eq!(util::fs::read_from_path(&a_path)?, "work in progress");
+            assert_eq!(util::fs::read_from_path(&c_path)?, "new file");
+            let status = repositories::status(&repo)?;
+            assert_eq!(status.staged_files.len(), 1);
+            assert_eq!(status.modified_files.len(), 1);
+            assert!(repositories::stash::list(&repo)?.is_empty());
+
+            Ok(())
+        })
+        .await
+    }
+}
@@ -1,8 +1,22 @@
+//! # Repository stats
+//!
+//! Sizes and counts of the data in a repository. Everything is read from the dir, vnode and
+//! file nodes of the merkle tree, so no file contents are read.
+//!
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
 use crate::core;
 use crate::core::versions::MinOxenVersion;
 use crate::error::OxenError;
+use crate::model::merkle_tree::node::{EMerkleTreeNode, MerkleTreeNode};
 use crate::model::LocalRepository;
 use crate::model::RepoStats;
+use crate::repositories;
 
 pub fn get_stats(repo: &LocalRepository) -> Result<RepoStats, OxenError> {
     match repo.min_version() {
@@ -10,3 +24,216 @@ pub fn get_stats(repo: &LocalRepository) -> Result<RepoStats, OxenError> {
         _ => core::v_latest::stats::get_stats(repo),
     }
 }
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct DatasetStats {
+    pub commit_id: String,
+    /// The directory the stats are for, empty for the root
+    pub path: PathBuf,
+    pub num_files: u64,
+    pub num_bytes: u64,
+    /// Every directory under `path` including itself, sorted by path
+    pub dirs: Vec<DirStats>,
+    /// Number of files and bytes per extension, the most files first
+    pub extensions: Vec<ExtensionStats>,
+    /// The biggest files, largest first
+    pub largest_files: Vec<FileStats>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DirStats {
+    pub path: PathBuf,
+    /// Files in the directory and all of its subdirectories
+    pub num_files: u64,
+    pub num_bytes: u64,
+    /// Files and directories directly in the directory
+    pub num_entries: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ExtensionStats {
+    /// Extension of the files without the dot, empty for files without one
+    pub extension: String,
+    pub num_files: u64,
+    pub num_bytes: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FileStats {
+    pub num_bytes: u64,
+    pub path: PathBuf,
+}
+
+/// Stats of the directory at `path` in `revision`, listing the `num_largest` biggest files
+pub fn dataset_stats(
+    repo: &LocalRepository,
+    revision: impl AsRef<str>,
+    path: impl AsRef<Path>,
+    num_largest: usize,
+) -> Result<DatasetStats, OxenError> {
+    let revision = revision.as_ref();
+    let path = path.as_ref();
+    let commit = repositories::revisions::get(repo, revision)?
+        .ok_or(OxenError::revision_not_found(revision.into()))?;
+    let not_found = || OxenError::resource_not_found(path.to_string_lossy());
+    let root: MerkleTreeNode;
+    let dir = if path == Path::new("") {
+        root = repositories::tree::get_root_with_children(repo, &commit)?.ok_or_else(not_found)?;
+        repositories::tree::get_root_dir(&root)?
+    } else {
+        root = repositories::tree::get_dir_with_children_recursive(repo, &commit, path)?
+            .ok_or_else(not_found)?;
+        &root
+    };
+    let EMerkleTreeNode::Directory(dir_node) = &dir.node else {
+        return Err(not_found());
+    };
+
+    let mut stats = DatasetStats {
+        commit_id: commit.id.clone(),
+        path: path.to_path_buf(),
+        num_files: dir_node.num_files(),
+        num_bytes: dir_node.num_bytes(),
+        ..Default::default()
+    };
+    let mut extensions: HashMap<String, ExtensionStats> = HashMap::new();
+    // Min heap of the largest files seen so far
+    let mut largest: BinaryHeap<Reverse<FileStats>> = BinaryHeap::new();
+    r_dataset_stats(
+        dir,
+        path,
+        num_largest,
+        &mut stats,
+        &mut extensions,
+        &mut largest,
+    );
+
+    stats.dirs.sort_by(|a, b| a.path.cmp(&b.path));
+    stats.extensions = extensions.into_values().collect();
+    stats.extensions.sort_by(|a, b| {
+        b.num_files
+            .cmp(&a.num_files)
+            .then_with(|| a.extension.cmp(&b.extension))
+    });
+    stats.largest_files = largest
+        .into_sorted_vec()
+        .into_iter()
+        .map(|Reverse(file)| file)
+        .collect();
+    Ok(stats)
+}
+
+fn r_dataset_stats(
+    node: &MerkleTreeNode,
+    path: &Path,
+    num_largest: usize,
+    stats: &mut DatasetStats,
+    extensions: &mut HashMap<String, ExtensionStats>,
+    largest: &mut BinaryHeap<Reverse<FileStats>>,
+) {
+    match &node.node {
+        EMerkleTreeNode::Directory(dir_node) => {
+            stats.dirs.push(DirStats {
+                path: path.to_path_buf(),
+                num_files: dir_node.num_files(),
+                num_bytes: dir_node.num_bytes(),
+                num_entries: dir_node.num_entries(),
+            });
+        }
+        EMerkleTreeNode::File(file_node) => {
+            let extension = extensions
+                .entry(file_node.extension().to_string())
+                .or_insert_with(|| ExtensionStats {
+                    extension: file_node.extension().to_string(),
+                    num_files: 0,
+                    num_bytes: 0,
+                });
+            extension.num_files += 1;
+            extension.num_bytes += file_node.num_bytes();
+
+            if num_largest > 0 {
+                largest.push(Reverse(FileStats {
+                    num_bytes: file_node.num_bytes(),
+                    path: path.to_path_buf(),
+                }));
+                if largest.len() > num_largest {
+                    largest.pop();
+                }
+            }
+            return;
+        }
+        _ => {}
+    }
+
+    for child in &node.children {
+        let child_path = match &child.node {
+            EMerkleTreeNode::Directory(dir_node) => path.join(dir_node.name()),
+            EMerkleTreeNode::File(file_node) => path.join(file_node.name()),
+            _ => path.to_path_buf(),
+        };
+        r_dataset_stats(child, &child_path, num_largest, stats, extensions, largest);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::error::OxenError;
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    #[tokio::test]
+    async fn test_dataset_stats_counts_dirs_and_largest_files() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            util::fs::create_dir_all(repo.path.join("images/train"))?;
+            util::fs::write_to_path(repo.path.join("images/train/a.png"), "aaaa")?;
+            util::fs::write_to_path(repo.path.join("images/train/b.png"), "bbbbbbbb")?;
+            util::fs::write_to_path(repo.path.join("images/labels.csv"), "file,label")?;
+            util::fs::write_to_path(repo.path.join("README.md"), "hi")?;
+            repositories::add(&repo, &repo.path).await?;
+            repositories::commit(&repo, "Adding data")?;
+
+            let stats = repositories::stats::dataset_stats(&repo, "HEAD", "", 2)?;
+            assert_eq!(stats.num_files, 4);
+            assert_eq!(stats.num_bytes, 4 + 8 + 10 + 2);
+            let dir_paths: Vec<PathBuf> = stats.dirs.iter().map(|dir| dir.path.clone()).collect();
+            assert_eq!(
+                dir_paths,
+                vec![
+                    PathBuf::from(""),
+                    PathBuf::from("images"),
+                    PathBuf::from("images/train"),
+                ]
+            );
+            assert_eq!(stats.dirs[1].num_files, 3);
+            assert_eq!(stats.dirs[1].num_entries, 2);
+            assert_eq!(stats.extensions[0].extension, "png");
+            assert_eq!(stats.extensions[0].num_files, 2);
+            assert_eq!(stats.extensions[0].num_bytes, 12);
+            let largest: Vec<PathBuf> = stats
+                .largest_files
+                .iter()
+                .map(|file| file.path.clone())
+                .collect();
+            assert_eq!(
+                largest,
+                vec![
+                    PathBuf::from("images/labels.csv"),
+                    PathBuf::from("images/train/b.png")
+                ]
+            );
+
+            // Stats of a subdirectory
+            let stats = repositories::stats::dataset_stats(&repo, "HEAD", "images/train", 10)?;
+            assert_eq!(stats.num_files, 2);
+            assert_eq!(stats.dirs.len(), 1);
+            assert_eq!(stats.largest_files.len(), 2);
+            assert_eq!(stats.largest_files[0].num_bytes, 8);
+
+            Ok(())
+        })
+        .await
+    }
+}
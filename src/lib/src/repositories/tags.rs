@@ -0,0 +1,200 @@
+//! # oxen tag
+//!
+//! Annotated tags mark a commit with a name and a message, such as a dataset release.
+//! Each tag is a json file in `.oxen/refs/tags`, and unlike branches they never move.
+//!
+
+use std::path::PathBuf;
+
+use time::OffsetDateTime;
+
+use crate::api;
+use crate::config::UserConfig;
+use crate::constants::{REFS_DIR, TAGS_DIR};
+use crate::core::refs::with_ref_manager;
+use crate::error::OxenError;
+use crate::model::{LocalRepository, Tag};
+use crate::repositories;
+use crate::util;
+
+fn tags_dir(repo: &LocalRepository) -> PathBuf {
+    util::fs::oxen_hidden_dir(&repo.path)
+        .join(REFS_DIR)
+        .join(TAGS_DIR)
+}
+
+fn tag_path(repo: &LocalRepository, name: &str) -> PathBuf {
+    tags_dir(repo).join(format!("{name}.json"))
+}
+
+/// List all the tags in the repository sorted by name
+pub fn list(repo: &LocalRepository) -> Result<Vec<Tag>, OxenError> {
+    let dir = tags_dir(repo);
+    let mut tags: Vec<Tag> = vec![];
+    if !dir.exists() {
+        return Ok(tags);
+    }
+    for path in util::fs::list_files_in_dir(&dir) {
+        if path.extension().is_some_and(|ext| ext == "json") {
+            let data = util::fs::read_from_path(&path)?;
+            tags.push(serde_json::from_str(&data)?);
+        }
+    }
+    tags.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(tags)
+}
+
+/// Get a tag by name
+pub fn get_by_name(repo: &LocalRepository, name: &str) -> Result<Option<Tag>, OxenError> {
+    if is_invalid_tag_name(repo, name)? {
+        return Ok(None);
+    }
+    let path = tag_path(repo, name);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let data = util::fs::read_from_path(&path)?;
+    Ok(Some(serde_json::from_str(&data)?))
+}
+
+/// Tag the commit at `revision` with the current user as the author
+pub fn create(
+    repo: &LocalRepository,
+    name: impl AsRef<str>,
+    revision: impl AsRef<str>,
+    message: impl AsRef<str>,
+) -> Result<Tag, OxenError> {
+    let revision = revision.as_ref();
+    let commit = repositories::revisions::get(repo, revision)?
+        .ok_or(OxenError::revision_not_found(revision.into()))?;
+    let cfg = UserConfig::get()?;
+    let tag = Tag {
+        name: name.as_ref().to_string(),
+        commit_id: commit.id,
+        message: message.as_ref().to_string(),
+        author: cfg.name,
+        email: cfg.email,
+        timestamp: OffsetDateTime::now_utc(),
+    };
+    save(repo, &tag)?;
+    Ok(tag)
+}
+
+/// Save a tag as is, used when a tag is pushed. Fails if the name is taken or the commit
+/// does not exist in the repository.
+pub fn save(repo: &LocalRepository, tag: &Tag) -> Result<(), OxenError> {
+    if is_invalid_tag_name(repo, &tag.name)? {
+        return Err(OxenError::basic_str(format!(
+            "Invalid tag name: {}",
+            tag.name
+        )));
+    }
+    if get_by_name(repo, &tag.name)?.is_some() {
+        return Err(OxenError::basic_str(format!(
+            "Tag already exists: {}",
+            tag.name
+        )));
+    }
+    if !repositories::commits::commit_id_exists(repo, &tag.commit_id)? {
+        return Err(OxenError::commit_id_does_not_exist(&tag.commit_id));
+    }
+
+    util::fs::create_dir_all(tags_dir(repo))?;
+    util::fs::write_to_path(
+        tag_path(repo, &tag.name),
+        serde_json::to_string_pretty(tag)?,
+    )?;
+    Ok(())
+}
+
+/// Delete a tag, the commit it pointed to is left alone
+pub fn delete(repo: &LocalRepository, name: &str) -> Result<Tag, OxenError> {
+    let tag = get_by_name(repo, name)?.ok_or(OxenError::tag_not_found(name))?;
+    util::fs::remove_file(tag_path(repo, name))?;
+    Ok(tag)
+}
+
+/// Push every local tag the remote does not have yet. The tagged commits must already be
+/// on the remote. Returns the tags that were pushed.
+pub async fn push(repo: &LocalRepository, remote: impl AsRef<str>) -> Result<Vec<Tag>, OxenError> {
+    let remote = remote.as_ref();
+    let remote = repo
+        .get_remote(remote)
+        .ok_or(OxenError::remote_not_set(remote))?;
+    let remote_repo = api::client::repositories::get_by_remote(&remote)
+        .await?
+        .ok_or(OxenError::remote_repo_not_found(&remote.url))?;
+
+    let mut pushed: Vec<Tag> = vec![];
+    for tag in list(repo)? {
+        match api::client::tags::get_by_name(&remote_repo, &tag.name).await? {
+            Some(remote_tag) if remote_tag.commit_id == tag.commit_id => continue,
+            Some(remote_tag) => {
+                return Err(OxenError::basic_str(format!(
+                    "Tag {} is already on the remote and points to commit {}, delete it there first",
+                    tag.name, remote_tag.commit_id
+                )));
+            }
+            None => {}
+        }
+        if api::client::commits::get_by_id(&remote_repo, &tag.commit_id)
+            .await?
+            .is_none()
+        {
+            return Err(OxenError::basic_str(format!(
+                "Tag {} points to commit {} which is not on the remote, push it first",
+                tag.name, tag.commit_id
+            )));
+        }
+        pushed.push(api::client::tags::create(&remote_repo, &tag).await?);
+    }
+    Ok(pushed)
+}
+
+fn is_invalid_tag_name(repo: &LocalRepository, name: &str) -> Result<bool, OxenError> {
+    // Tags are flat files, so they can't be nested like branch names
+    if name.is_empty() || name.contains('/') || name.starts_with('.') {
+        return Ok(true);
+    }
+    with_ref_manager(repo, |manager| Ok(manager.is_invalid_branch_name(name)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::OxenError;
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    #[tokio::test]
+    async fn test_create_list_and_delete_tags() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            let path = repo.path.join("data.csv");
+            util::fs::write_to_path(&path, "a,b\n1,2\n")?;
+            repositories::add(&repo, &path).await?;
+            let commit = repositories::commit(&repo, "Add data")?;
+
+            let tag = repositories::tags::create(&repo, "v1.0", "main", "First release")?;
+            assert_eq!(tag.commit_id, commit.id);
+            assert_eq!(tag.message, "First release");
+
+            // Tags resolve as revisions
+            let tagged = repositories::revisions::get(&repo, "v1.0")?.expect("tag resolves");
+            assert_eq!(tagged.id, commit.id);
+
+            // Names are unique and must be valid
+            assert!(repositories::tags::create(&repo, "v1.0", "main", "Again").is_err());
+            assert!(repositories::tags::create(&repo, "bad..name", "main", "").is_err());
+
+            let tags = repositories::tags::list(&repo)?;
+            assert_eq!(tags, vec![tag.clone()]);
+
+            let deleted = repositories::tags::delete(&repo, "v1.0")?;
+            assert_eq!(deleted, tag);
+            assert!(repositories::tags::list(&repo)?.is_empty());
+
+            Ok(())
+        })
+        .await
+    }
+}
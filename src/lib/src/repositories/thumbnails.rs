@@ -0,0 +1,244 @@
+//! # Thumbnails
+//!
+//! Downscaled copies of the images in a revision, and poster frames of its videos, so
+//! anything browsing a dataset does not have to pull the full resolution originals.
+//! Thumbnails are cached by the hash of the file and the requested size, so every
+//! revision that shares the file shares its thumbnails.
+//!
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use image::{DynamicImage, ImageFormat, ImageReader};
+
+use crate::constants::CACHE_DIR;
+use crate::error::OxenError;
+use crate::model::merkle_tree::node::FileNode;
+use crate::model::{Commit, EntryDataType, LocalRepository};
+use crate::repositories;
+use crate::util;
+
+const THUMBNAILS_CACHE_DIR: &str = "thumbnails";
+/// Width of the thumbnail when neither a width nor a height is given
+pub const DEFAULT_THUMBNAIL_SIZE: u32 = 256;
+/// Largest width or height a thumbnail can be requested at
+pub const MAX_THUMBNAIL_SIZE: u32 = 2048;
+/// How long ffmpeg gets to decode a poster frame before it is killed
+const FFMPEG_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Path to a thumbnail of the image or video at `path` in the commit that fits within
+/// `width` x `height`, keeping the aspect ratio and never upscaling. Generated on the
+/// first request and cached after.
+pub fn get(
+    repo: &LocalRepository,
+    commit: &Commit,
+    path: impl AsRef<Path>,
+    width: Option<u32>,
+    height: Option<u32>,
+) -> Result<PathBuf, OxenError> {
+    let path = path.as_ref();
+    let file_node = repositories::tree::get_file_by_path(repo, commit, path)?
+        .ok_or(OxenError::path_does_not_exist(path))?;
+
+    let (width, height) = match (width, height) {
+        (None, None) => (Some(DEFAULT_THUMBNAIL_SIZE), None),
+        size => size,
+    };
+    for size in [width, height].into_iter().flatten() {
+        if size == 0 || size > MAX_THUMBNAIL_SIZE {
+            return Err(OxenError::basic_str(format!(
+                "Thumbnail size must be between 1 and {MAX_THUMBNAIL_SIZE}, got {size}"
+            )));
+        }
+    }
+
+    let format = thumbnail_format(&file_node)?;
+    let thumbnail_path = thumbnail_path(repo, &file_node, width, height, format);
+    if thumbnail_path.exists() {
+        return Ok(thumbnail_path);
+    }
+
+    let version_store = repo.version_store()?;
+    let version_path = version_store.get_version_path(&file_node.hash().to_string())?;
+    let img = match file_node.data_type() {
+        EntryDataType::Video => poster_frame(&version_path)?,
+        _ => ImageReader::open(&version_path)?
+            .with_guessed_format()?
+            .decode()?,
+    };
+
+    let max_width = width.unwrap_or(u32::MAX).min(img.width());
+    let max_height = height.unwrap_or(u32::MAX).min(img.height());
+    let thumbnail = img.thumbnail(max_width, max_height);
+
+    // Write next to the final path and rename, so concurrent requests never see a partial file
+    let parent = thumbnail_path.parent().unwrap();
+    util::fs::create_dir_all(parent)?;
+    let tmp_path = parent.join(format!("{}.tmp", uuid::Uuid::new_v4()));
+    let saved = match format {
+        ImageFormat::Jpeg => {
+            DynamicImage::ImageRgb8(thumbnail.to_rgb8()).save_with_format(&tmp_path, format)
+        }
+        _ => thumbnail.save_with_format(&tmp_path, format),
+    };
+    if let Err(err) = saved {
+        util::fs::remove_file(&tmp_path).ok();
+        return Err(err.into());
+    }
+    util::fs::rename(&tmp_path, &thumbnail_path)?;
+    log::debug!("Cached thumbnail {:?} -> {:?}", path, thumbnail_path);
+    Ok(thumbnail_path)
+}
+
+/// Photos and video frames are cached as jpegs, everything else as pngs to keep transparency
+fn thumbnail_format(file_node: &FileNode) -> Result<ImageFormat, OxenError> {
+    match file_node.data_type() {
+        EntryDataType::Video => Ok(ImageFormat::Jpeg),
+        EntryDataType::Image => match file_node.extension().to_lowercase().as_str() {
+            "jpg" | "jpeg" => Ok(ImageFormat::Jpeg),
+            _ => Ok(ImageFormat::Png),
+        },
+        data_type => Err(OxenError::basic_str(format!(
+            "Cannot create a thumbnail of {:?}, it is {data_type} not an image or video",
+            file_node.name()
+        ))),
+    }
+}
+
+fn thumbnail_path(
+    repo: &LocalRepository,
+    file_node: &FileNode,
+    width: Option<u32>,
+    height: Option<u32>,
+    format: ImageFormat,
+) -> PathBuf {
+    let size = |size: Option<u32>| size.map(|s| s.to_string()).unwrap_or_default();
+    let extension = format.extensions_str()[0];
+    util::fs::oxen_hidden_dir(&repo.path)
+        .join(CACHE_DIR)
+        .join(THUMBNAILS_CACHE_DIR)
+        .join(file_node.hash().to_string())
+        .join(format!("{}x{}.{extension}", size(width), size(height)))
+}
+
+/// A representative frame from the start of a video, decoded with the `ffmpeg` binary.
+/// ffmpeg is killed if it takes longer than [`FFMPEG_TIMEOUT`].
+fn poster_frame(video_path: &Path) -> Result<DynamicImage, OxenError> {
+    let frame_path = std::env::temp_dir().join(format!("{}.png", uuid::Uuid::new_v4()));
+    let child = Command::new("ffmpeg")
+        .arg("-v")
+        .arg("error")
+        .arg("-i")
+        .arg(video_path)
+        .args(["-vf", "thumbnail", "-frames:v", "1", "-y"])
+        .arg(&frame_path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn();
+    let result = match child {
+        Ok(child) => match wait_with_timeout(child, FFMPEG_TIMEOUT) {
+            Ok(output) if output.status.success() && frame_path.exists() => {
+                Ok(image::open(&frame_path)?)
+            }
+            Ok(output) => Err(OxenError::basic_str(format!(
+                "Could not read a frame from the video: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ))),
+            Err(err) => Err(err),
+        },
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Err(OxenError::basic_str(
+            "Video thumbnails require ffmpeg to be installed on the server",
+        )),
+        Err(err) => Err(err.into()),
+    };
+    if frame_path.exists() {
+        util::fs::remove_file(&frame_path).ok();
+    }
+    result
+}
+
+/// The output of the child, killing it if it is still running after `timeout`
+fn wait_with_timeout(
+    mut child: std::process::Child,
+    timeout: Duration,
+) -> Result<std::process::Output, OxenError> {
+    let started = Instant::now();
+    while child.try_wait()?.is_none() {
+        if started.elapsed() > timeout {
+            child.kill().ok();
+            child.wait()?;
+            return Err(OxenError::basic_str(format!(
+                "Gave up reading a frame from the video after {}s",
+                timeout.as_secs()
+            )));
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    Ok(child.wait_with_output()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::process::{Command, Stdio};
+    use std::time::{Duration, Instant};
+
+    use super::wait_with_timeout;
+    use crate::error::OxenError;
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    #[tokio::test]
+    async fn test_thumbnail_of_image_is_cached_and_not_upscaled() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            let path = repo.path.join("images").join("wide.png");
+            util::fs::create_dir_all(path.parent().unwrap())?;
+            image::RgbaImage::new(64, 32).save(&path)?;
+            repositories::add(&repo, &path).await?;
+            let commit = repositories::commit(&repo, "Adding an image")?;
+
+            let thumbnail =
+                repositories::thumbnails::get(&repo, &commit, "images/wide.png", Some(16), None)?;
+            assert_eq!(image::image_dimensions(&thumbnail)?, (16, 8));
+            assert_eq!(thumbnail.extension().unwrap(), "png");
+
+            // Served from the cache the second time
+            let cached =
+                repositories::thumbnails::get(&repo, &commit, "images/wide.png", Some(16), None)?;
+            assert_eq!(cached, thumbnail);
+
+            // Larger than the original keeps the original size
+            let thumbnail =
+                repositories::thumbnails::get(&repo, &commit, "images/wide.png", Some(512), None)?;
+            assert_eq!(image::image_dimensions(&thumbnail)?, (64, 32));
+
+            assert!(repositories::thumbnails::get(
+                &repo,
+                &commit,
+                "images/wide.png",
+                Some(0),
+                None
+            )
+            .is_err());
+            Ok(())
+        })
+        .await
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_wait_with_timeout_kills_slow_commands() -> Result<(), OxenError> {
+        let child = Command::new("sleep").arg("10").spawn()?;
+        let started = Instant::now();
+        assert!(wait_with_timeout(child, Duration::from_millis(100)).is_err());
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        let child = Command::new("true").stderr(Stdio::piped()).spawn()?;
+        assert!(wait_with_timeout(child, Duration::from_secs(5))?
+            .status
+            .success());
+        Ok(())
+    }
+}
@@ -15,6 +15,7 @@ use crate::core::db;
 use crate::core::db::merkle_node::merkle_node_db::{node_db_path, node_db_prefix};
 use crate::core::db::merkle_node::MerkleNodeDB;
 use crate::core::v_latest::index::CommitMerkleTree as CommitMerkleTreeLatest;
+use crate::core::v_latest::index::TreeProblem;
 use crate::core::v_old::v0_19_0::index::CommitMerkleTree as CommitMerkleTreeV0_19_0;
 use crate::core::versions::MinOxenVersion;
 use crate::error::OxenError;
@@ -83,6 +84,17 @@ pub fn get_root_dir(node: &MerkleTreeNode) -> Result<&MerkleTreeNode, OxenError>
     Ok(root_dir)
 }
 
+/// Hash of the root directory of a commit, None if the commit's tree is not on disk
+pub fn get_root_dir_hash(
+    repo: &LocalRepository,
+    commit: &Commit,
+) -> Result<Option<MerkleHash>, OxenError> {
+    let Some(root) = get_root(repo, commit)? else {
+        return Ok(None);
+    };
+    Ok(Some(get_root_dir(&root)?.hash))
+}
+
 pub fn get_node_by_id(
     repo: &LocalRepository,
     hash: &MerkleHash,
@@ -1143,6 +1155,18 @@ fn p_write_tree(
     Ok(())
 }
 
+/// Check that every node in the tree of a commit can be read and refers to its children
+/// by the hashes they are stored under. Returns the inconsistent nodes, empty if there are
+/// none.
+pub fn verify(repo: &LocalRepository, commit: &Commit) -> Result<Vec<TreeProblem>, OxenError> {
+    match repo.min_version() {
+        MinOxenVersion::V0_19_0 => Err(OxenError::basic_str(
+            "Tree verification is not supported for v0.19.0 repositories",
+        )),
+        _ => CommitMerkleTreeLatest::verify(repo, commit),
+    }
+}
+
 /// The dir hashes allow you to skip to a directory in the tree
 pub fn dir_hashes(
     repo: &LocalRepository,
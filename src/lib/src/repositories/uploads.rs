@@ -0,0 +1,211 @@
+//! # Uploads
+//!
+//! Resumable uploads of large version files, in the spirit of the tus protocol. A session
+//! is created for the content hash of a file, the client appends chunks at the offset the
+//! server reports, and completing the session checks the hash and moves the file into the
+//! version store. The bytes received are kept in `.oxen/uploads/<hash>`, so a session
+//! survives a dropped connection or a restart of the server.
+//!
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+
+use time::OffsetDateTime;
+
+use crate::constants::UPLOADS_DIR;
+use crate::error::OxenError;
+use crate::model::{LocalRepository, UploadSession};
+use crate::util;
+
+const SESSION_FILE: &str = "session.json";
+const DATA_FILE: &str = "data";
+
+/// Start an upload of the file with content hash `id`, or return the session already in
+/// progress for it so the client can resume from its offset
+pub fn create(
+    repo: &LocalRepository,
+    id: impl AsRef<str>,
+    size: u64,
+) -> Result<UploadSession, OxenError> {
+    let id = id.as_ref();
+    if let Some(session) = get(repo, id)? {
+        if session.size == size {
+            return Ok(session);
+        }
+        // The same hash with another size can't be the same file, start over
+        delete(repo, id)?;
+    }
+
+    let dir = session_dir(repo, id);
+    util::fs::create_dir_all(&dir)?;
+    std::fs::File::create(dir.join(DATA_FILE))?;
+    let session = UploadSession {
+        id: id.to_string(),
+        size,
+        offset: 0,
+        created_at: OffsetDateTime::now_utc(),
+    };
+    util::fs::write_to_path(dir.join(SESSION_FILE), serde_json::to_string(&session)?)?;
+    Ok(session)
+}
+
+pub fn get(
+    repo: &LocalRepository,
+    id: impl AsRef<str>,
+) -> Result<Option<UploadSession>, OxenError> {
+    let id = id.as_ref();
+    validate_id(id)?;
+    let dir = session_dir(repo, id);
+    let session_path = dir.join(SESSION_FILE);
+    if !session_path.exists() {
+        return Ok(None);
+    }
+
+    let mut session: UploadSession =
+        serde_json::from_str(&util::fs::read_from_path(&session_path)?)?;
+    // The data on disk is the source of truth for how much has arrived
+    session.offset = util::fs::metadata(dir.join(DATA_FILE))?.len();
+    Ok(Some(session))
+}
+
+/// Append a chunk starting at `offset`, which must be the number of bytes received so far
+pub fn append(
+    repo: &LocalRepository,
+    id: impl AsRef<str>,
+    offset: u64,
+    data: &[u8],
+) -> Result<UploadSession, OxenError> {
+    let id = id.as_ref();
+    let mut session = get(repo, id)?.ok_or(upload_not_found(id))?;
+
+    let file = OpenOptions::new()
+        .append(true)
+        .open(session_dir(repo, id).join(DATA_FILE))?;
+    // Hold the lock while checking the offset so two chunks can't both land at the end
+    let mut lock = fd_lock::RwLock::new(file);
+    let mut file = lock.write()?;
+    let received = file.metadata()?.len();
+    if offset != received {
+        return Err(OxenError::basic_str(format!(
+            "Upload {id} is at offset {received}, not {offset}"
+        )));
+    }
+    if received + data.len() as u64 > session.size {
+        return Err(OxenError::basic_str(format!(
+            "Upload {id} is {} bytes, chunk at {offset} of {} bytes is past the end",
+            session.size,
+            data.len()
+        )));
+    }
+    file.write_all(data)?;
+    file.sync_data()?;
+
+    session.offset = received + data.len() as u64;
+    Ok(session)
+}
+
+/// Check every byte arrived and matches the hash, then move the file into the version store
+pub async fn complete(repo: &LocalRepository, id: impl AsRef<str>) -> Result<(), OxenError> {
+    let id = id.as_ref();
+    let session = get(repo, id)?.ok_or(upload_not_found(id))?;
+    if !session.is_complete() {
+        return Err(OxenError::basic_str(format!(
+            "Upload {id} has received {} of {} bytes",
+            session.offset, session.size
+        )));
+    }
+
+    let data_path = session_dir(repo, id).join(DATA_FILE);
+    let hash = format!(
+        "{:x}",
+        repo.hash_algorithm().hash_file_contents(&data_path)?
+    );
+    if hash != session.id {
+        // The bytes are no good to anyone, so the next attempt starts clean
+        delete(repo, id)?;
+        return Err(OxenError::basic_str(format!(
+            "Upload {id} does not match its hash, got {hash}"
+        )));
+    }
+
+    let version_store = repo.version_store()?;
+    version_store
+        .store_version_from_path(&session.id, &data_path)
+        .await?;
+    delete(repo, id)
+}
+
+/// Abandon an upload and remove the bytes received
+pub fn delete(repo: &LocalRepository, id: impl AsRef<str>) -> Result<(), OxenError> {
+    let id = id.as_ref();
+    validate_id(id)?;
+    let dir = session_dir(repo, id);
+    if dir.exists() {
+        util::fs::remove_dir_all(&dir)?;
+    }
+    Ok(())
+}
+
+fn session_dir(repo: &LocalRepository, id: &str) -> PathBuf {
+    util::fs::oxen_hidden_dir(&repo.path)
+        .join(UPLOADS_DIR)
+        .join(id)
+}
+
+// The id ends up in a path, so only a content hash will do
+fn validate_id(id: &str) -> Result<(), OxenError> {
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(OxenError::basic_str(format!(
+            "Invalid upload id {id:?}, must be the hash of the file"
+        )));
+    }
+    Ok(())
+}
+
+fn upload_not_found(id: &str) -> OxenError {
+    OxenError::basic_str(format!("Upload not found: {id}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::OxenError;
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    #[tokio::test]
+    async fn test_resumable_upload_appends_at_offset_and_completes() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            let contents = b"resumable uploads keep every byte".to_vec();
+            let path = repo.path.join("upload.txt");
+            util::fs::write(&path, &contents)?;
+            let hash = format!("{:x}", repo.hash_algorithm().hash_file_contents(&path)?);
+            let size = contents.len() as u64;
+
+            let session = repositories::uploads::create(&repo, &hash, size)?;
+            assert_eq!(session.offset, 0);
+            let session = repositories::uploads::append(&repo, &hash, 0, &contents[..10])?;
+            assert_eq!(session.offset, 10);
+
+            // A chunk at the wrong offset is rejected
+            assert!(repositories::uploads::append(&repo, &hash, 0, &contents[..10]).is_err());
+            // Completing early is rejected
+            assert!(repositories::uploads::complete(&repo, &hash).await.is_err());
+
+            // Creating the session again resumes it from disk
+            let session = repositories::uploads::create(&repo, &hash, size)?;
+            assert_eq!(session.offset, 10);
+            repositories::uploads::append(&repo, &hash, 10, &contents[10..])?;
+            repositories::uploads::complete(&repo, &hash).await?;
+
+            let version_store = repo.version_store()?;
+            assert_eq!(version_store.get_version(&hash).await?, contents);
+            assert!(repositories::uploads::get(&repo, &hash)?.is_none());
+
+            assert!(repositories::uploads::get(&repo, "../config").is_err());
+            Ok(())
+        })
+        .await
+    }
+}
@@ -0,0 +1,237 @@
+//! # oxen verify-remote
+//!
+//! Check whether a local repository and its remote hold the same refs, commits and
+//! version files, without transferring any data.
+//!
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::api;
+use crate::error::OxenError;
+use crate::model::{Branch, Commit, LocalRepository, MerkleHash, RemoteRepository};
+use crate::repositories;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BranchSyncState {
+    InSync,
+    /// The local branch has commits the remote branch does not
+    Ahead,
+    /// The remote branch has commits the local branch does not
+    Behind,
+    /// Both branches have commits the other does not
+    Diverged,
+    LocalOnly,
+    RemoteOnly,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BranchSyncStatus {
+    pub name: String,
+    pub local_commit_id: Option<String>,
+    pub remote_commit_id: Option<String>,
+    pub state: BranchSyncState,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct RemoteVerifyReport {
+    pub branches: Vec<BranchSyncStatus>,
+    pub commits_missing_on_remote: Vec<String>,
+    pub commits_missing_locally: Vec<String>,
+    /// Version files of commits on both sides that only the local repository has
+    pub files_missing_on_remote: Vec<String>,
+    /// Version files of commits on both sides that only the remote has
+    pub files_missing_locally: Vec<String>,
+    /// Version files of commits on both sides that neither has
+    pub files_missing_everywhere: Vec<String>,
+}
+
+impl RemoteVerifyReport {
+    pub fn is_synced(&self) -> bool {
+        self.branches
+            .iter()
+            .all(|branch| branch.state == BranchSyncState::InSync)
+            && self.commits_missing_on_remote.is_empty()
+            && self.commits_missing_locally.is_empty()
+            && self.files_missing_on_remote.is_empty()
+            && self.files_missing_locally.is_empty()
+            && self.files_missing_everywhere.is_empty()
+    }
+}
+
+/// Compare the local repository against the remote with the given name
+pub async fn verify_remote(
+    repo: &LocalRepository,
+    remote_name: impl AsRef<str>,
+) -> Result<RemoteVerifyReport, OxenError> {
+    let remote_name = remote_name.as_ref();
+    let remote = repo
+        .get_remote(remote_name)
+        .ok_or(OxenError::remote_not_set(remote_name))?;
+    let remote_repo = api::client::repositories::get_by_remote(&remote)
+        .await?
+        .ok_or(OxenError::remote_not_found(remote.clone()))?;
+
+    let local_ids: HashSet<String> = repositories::commits::list_all(repo)?
+        .into_iter()
+        .map(|commit| commit.id)
+        .collect();
+    let remote_ids: HashSet<String> = api::client::commits::list_all(&remote_repo)
+        .await?
+        .into_iter()
+        .map(|commit| commit.id)
+        .collect();
+
+    let mut report = RemoteVerifyReport {
+        branches: verify_branches(repo, &remote_repo, &local_ids).await?,
+        commits_missing_on_remote: sorted(local_ids.difference(&remote_ids)),
+        commits_missing_locally: sorted(remote_ids.difference(&local_ids)),
+        ..Default::default()
+    };
+
+    // Only commits both sides have can be compared file by file
+    let shared: HashSet<MerkleHash> = local_ids
+        .intersection(&remote_ids)
+        .map(|id| id.parse::<MerkleHash>())
+        .collect::<Result<_, _>>()?;
+    if shared.is_empty() {
+        return Ok(report);
+    }
+    let missing_remote = api::client::tree::list_missing_file_hashes_from_commits(
+        repo,
+        &remote_repo,
+        shared.clone(),
+    )
+    .await?;
+    let missing_local = repositories::tree::list_missing_file_hashes_from_commits(
+        repo,
+        &shared,
+        &repo.subtree_paths(),
+        &repo.depth(),
+    )?;
+    report.files_missing_on_remote = sorted(missing_remote.difference(&missing_local));
+    report.files_missing_locally = sorted(missing_local.difference(&missing_remote));
+    report.files_missing_everywhere = sorted(missing_local.intersection(&missing_remote));
+
+    Ok(report)
+}
+
+async fn verify_branches(
+    repo: &LocalRepository,
+    remote_repo: &RemoteRepository,
+    local_ids: &HashSet<String>,
+) -> Result<Vec<BranchSyncStatus>, OxenError> {
+    let local: HashMap<String, Branch> = repositories::branches::list(repo)?
+        .into_iter()
+        .map(|branch| (branch.name.clone(), branch))
+        .collect();
+    let remote: HashMap<String, Branch> = api::client::branches::list(remote_repo)
+        .await?
+        .into_iter()
+        .map(|branch| (branch.name.clone(), branch))
+        .collect();
+
+    let names: BTreeSet<&String> = local.keys().chain(remote.keys()).collect();
+    let mut statuses = vec![];
+    for name in names {
+        let local_commit_id = local.get(name).map(|branch| branch.commit_id.clone());
+        let remote_commit_id = remote.get(name).map(|branch| branch.commit_id.clone());
+        let state = match (&local_commit_id, &remote_commit_id) {
+            (Some(local_id), Some(remote_id)) if local_id == remote_id => BranchSyncState::InSync,
+            (Some(local_id), Some(remote_id)) => {
+                compare_heads(repo, remote_repo, name, local_id, remote_id, local_ids).await?
+            }
+            (Some(_), None) => BranchSyncState::LocalOnly,
+            (None, _) => BranchSyncState::RemoteOnly,
+        };
+        statuses.push(BranchSyncStatus {
+            name: name.clone(),
+            local_commit_id,
+            remote_commit_id,
+            state,
+        });
+    }
+    Ok(statuses)
+}
+
+async fn compare_heads(
+    repo: &LocalRepository,
+    remote_repo: &RemoteRepository,
+    branch_name: &str,
+    local_id: &str,
+    remote_id: &str,
+    local_ids: &HashSet<String>,
+) -> Result<BranchSyncState, OxenError> {
+    let local_history = repositories::commits::list_from(repo, local_id)?;
+    if contains(&local_history, remote_id) {
+        return Ok(BranchSyncState::Ahead);
+    }
+
+    // Use the local copy of the remote history if it has been fetched
+    let remote_history = if local_ids.contains(remote_id) {
+        repositories::commits::list_from(repo, remote_id)?
+    } else {
+        api::client::commits::list_commit_history(remote_repo, branch_name).await?
+    };
+    if contains(&remote_history, local_id) {
+        Ok(BranchSyncState::Behind)
+    } else {
+        Ok(BranchSyncState::Diverged)
+    }
+}
+
+fn contains(history: &[Commit], commit_id: &str) -> bool {
+    history.iter().any(|commit| commit.id == commit_id)
+}
+
+fn sorted<T: ToString>(items: impl Iterator<Item = T>) -> Vec<String> {
+    let mut items: Vec<String> = items.map(|item| item.to_string()).collect();
+    items.sort();
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::constants::{DEFAULT_BRANCH_NAME, DEFAULT_REMOTE_NAME};
+    use crate::error::OxenError;
+    use crate::repositories;
+    use crate::repositories::verify_remote::BranchSyncState;
+    use crate::test;
+    use crate::util;
+
+    #[tokio::test]
+    async fn test_verify_remote_reports_unpushed_work() -> Result<(), OxenError> {
+        test::run_one_commit_sync_repo_test(|local_repo, remote_repo| async move {
+            let report = repositories::verify_remote(&local_repo, DEFAULT_REMOTE_NAME).await?;
+            assert!(report.is_synced());
+
+            let text_path = local_repo.path.join("text.txt");
+            util::fs::write_to_path(&text_path, "Not pushed yet")?;
+            repositories::add(&local_repo, &text_path).await?;
+            let commit = repositories::commit(&local_repo, "Local only")?;
+            repositories::branches::create_from_head(&local_repo, "feature")?;
+
+            let report = repositories::verify_remote(&local_repo, DEFAULT_REMOTE_NAME).await?;
+            assert!(!report.is_synced());
+            assert_eq!(report.commits_missing_on_remote, vec![commit.id]);
+            assert!(report.commits_missing_locally.is_empty());
+            let states: Vec<(&str, BranchSyncState)> = report
+                .branches
+                .iter()
+                .map(|branch| (branch.name.as_str(), branch.state))
+                .collect();
+            assert_eq!(
+                states,
+                vec![
+                    ("feature", BranchSyncState::LocalOnly),
+                    (DEFAULT_BRANCH_NAME, BranchSyncState::Ahead)
+                ]
+            );
+
+            Ok(remote_repo)
+        })
+        .await
+    }
+}
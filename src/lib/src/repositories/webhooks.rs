@@ -0,0 +1,396 @@
+//! # Webhooks
+//!
+//! HTTP callbacks the server calls when a repository is pushed to, or gets a new branch,
+//! tag or workspace commit. They are stored in `.oxen/hooks.toml`. Each delivery POSTs a
+//! json `WebhookPayload`, and hooks with a secret get the hex HMAC-SHA256 of the body in
+//! the `X-Oxen-Signature` header as `sha256=<hmac>`. Failed deliveries are retried with
+//! exponential backoff.
+//!
+//! Hooks are called from the server, so they may not point at loopback, private or link
+//! local addresses unless their host is listed in `OXEN_WEBHOOK_ALLOWED_HOSTS`, a comma
+//! separated list. Hosts are checked when the hook is added, and their addresses again
+//! when a payload is delivered.
+//!
+
+use std::net::{IpAddr, SocketAddr};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use ring::hmac;
+use serde::{Deserialize, Serialize};
+
+use crate::constants::{DEFAULT_TIMEOUT_SECS, HOOKS_FILE};
+use crate::error::OxenError;
+use crate::model::{LocalRepository, Webhook, WebhookEvent, WebhookPayload};
+use crate::util;
+
+/// Header holding the signature of the payload
+pub const SIGNATURE_HEADER: &str = "X-Oxen-Signature";
+/// Header holding the event that fired the webhook
+pub const EVENT_HEADER: &str = "X-Oxen-Event";
+/// Attempts to deliver a payload before giving up
+const MAX_DELIVERY_ATTEMPTS: u32 = 4;
+/// Env var with the hosts webhooks may call even though they are internal
+const ALLOWED_HOSTS_ENV: &str = "OXEN_WEBHOOK_ALLOWED_HOSTS";
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct HooksFile {
+    #[serde(default)]
+    webhooks: Vec<Webhook>,
+}
+
+fn hooks_path(repo: &LocalRepository) -> PathBuf {
+    util::fs::oxen_hidden_dir(&repo.path).join(HOOKS_FILE)
+}
+
+fn read_hooks(repo: &LocalRepository) -> Result<HooksFile, OxenError> {
+    let path = hooks_path(repo);
+    if !path.exists() {
+        return Ok(HooksFile::default());
+    }
+    let data = util::fs::read_from_path(&path)?;
+    toml::from_str(&data)
+        .map_err(|err| OxenError::basic_str(format!("Could not parse {path:?}: {err}")))
+}
+
+fn write_hooks(repo: &LocalRepository, hooks: &HooksFile) -> Result<(), OxenError> {
+    util::fs::write_to_path(hooks_path(repo), toml::to_string(hooks)?)
+}
+
+/// List the webhooks of the repository, including their secrets
+pub fn list(repo: &LocalRepository) -> Result<Vec<Webhook>, OxenError> {
+    Ok(read_hooks(repo)?.webhooks)
+}
+
+/// Register a webhook for `events`, or for every event if it is empty
+pub fn add(
+    repo: &LocalRepository,
+    url: impl AsRef<str>,
+    events: &[WebhookEvent],
+    secret: Option<String>,
+) -> Result<Webhook, OxenError> {
+    let url = url.as_ref();
+    check_url(url)?;
+    let events = if events.is_empty() {
+        WebhookEvent::all()
+    } else {
+        events.iter().fold(vec![], |mut events, event| {
+            if !events.contains(event) {
+                events.push(*event);
+            }
+            events
+        })
+    };
+
+    let webhook = Webhook {
+        id: uuid::Uuid::new_v4().to_string(),
+        url: url.to_string(),
+        events,
+        secret: secret.filter(|secret| !secret.is_empty()),
+    };
+    let mut hooks = read_hooks(repo)?;
+    hooks.webhooks.push(webhook.clone());
+    write_hooks(repo, &hooks)?;
+    Ok(webhook)
+}
+
+/// Remove a webhook by id
+pub fn remove(repo: &LocalRepository, id: impl AsRef<str>) -> Result<Webhook, OxenError> {
+    let id = id.as_ref();
+    let mut hooks = read_hooks(repo)?;
+    let Some(index) = hooks.webhooks.iter().position(|webhook| webhook.id == id) else {
+        return Err(OxenError::resource_not_found(format!("webhook {id}")));
+    };
+    let webhook = hooks.webhooks.remove(index);
+    write_hooks(repo, &hooks)?;
+    Ok(webhook)
+}
+
+/// The webhooks that want to hear about `event`
+pub fn for_event(repo: &LocalRepository, event: WebhookEvent) -> Result<Vec<Webhook>, OxenError> {
+    Ok(list(repo)?
+        .into_iter()
+        .filter(|webhook| webhook.events.contains(&event))
+        .collect())
+}
+
+/// The value of the signature header for a payload body
+pub fn sign(secret: impl AsRef<str>, body: &[u8]) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_ref().as_bytes());
+    let tag = hmac::sign(&key, body);
+    format!("sha256={}", hex::encode(tag.as_ref()))
+}
+
+/// Check a signature header a webhook was delivered with, in constant time
+pub fn verify(secret: impl AsRef<str>, body: &[u8], signature: &str) -> bool {
+    let Some(Ok(tag)) = signature.strip_prefix("sha256=").map(hex::decode) else {
+        return false;
+    };
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_ref().as_bytes());
+    hmac::verify(&key, body, &tag).is_ok()
+}
+
+/// Parse a webhook url, rejecting urls that aren't http or point at an internal host
+fn check_url(url: &str) -> Result<url::Url, OxenError> {
+    let parsed = match url::Url::parse(url) {
+        Ok(parsed) if parsed.scheme() == "http" || parsed.scheme() == "https" => parsed,
+        _ => {
+            return Err(OxenError::basic_str(format!(
+                "Invalid webhook url {url:?}, must be an http or https url"
+            )));
+        }
+    };
+    let internal = match parsed.host() {
+        Some(url::Host::Ipv4(ip)) => is_internal(IpAddr::V4(ip)),
+        Some(url::Host::Ipv6(ip)) => is_internal(IpAddr::V6(ip)),
+        Some(url::Host::Domain(domain)) => {
+            let domain = domain.trim_end_matches('.').to_lowercase();
+            domain == "localhost" || domain.ends_with(".localhost")
+        }
+        None => {
+            return Err(OxenError::basic_str(format!(
+                "Invalid webhook url {url:?}, it has no host"
+            )));
+        }
+    };
+    if internal && !is_allowed_host(&parsed) {
+        return Err(internal_host_error(url));
+    }
+    Ok(parsed)
+}
+
+fn is_allowed_host(url: &url::Url) -> bool {
+    let Some(host) = url.host_str() else {
+        return false;
+    };
+    std::env::var(ALLOWED_HOSTS_ENV)
+        .unwrap_or_default()
+        .split(',')
+        .any(|allowed| allowed.trim().eq_ignore_ascii_case(host))
+}
+
+/// Loopback, private, link local and other addresses that aren't on the public internet
+fn is_internal(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let octets = ip.octets();
+            ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                // Shared address space of carrier grade NAT, 100.64.0.0/10
+                || (octets[0] == 100 && octets[1] & 0xc0 == 64)
+        }
+        IpAddr::V6(ip) => {
+            let first = ip.segments()[0];
+            ip.is_loopback()
+                || ip.is_unspecified()
+                // Unique local fc00::/7 and link local fe80::/10
+                || first & 0xfe00 == 0xfc00
+                || first & 0xffc0 == 0xfe80
+                || ip
+                    .to_ipv4_mapped()
+                    .is_some_and(|ip| is_internal(IpAddr::V4(ip)))
+        }
+    }
+}
+
+fn internal_host_error(url: &str) -> OxenError {
+    OxenError::basic_str(format!(
+        "Webhook url {url:?} points at an internal address. Add its host to {ALLOWED_HOSTS_ENV} on the server to allow it."
+    ))
+}
+
+/// A client that only connects to the public addresses the webhook's host resolves to.
+/// The addresses are pinned so the host can't resolve to an internal one after the check.
+async fn client_for(webhook: &Webhook) -> Result<reqwest::Client, OxenError> {
+    let url = check_url(&webhook.url)?;
+    // Not the api client, the oxen auth token must not be sent to third parties. Redirects
+    // are not followed, they could lead to an internal address.
+    let mut builder = reqwest::Client::builder()
+        .timeout(Duration::from_secs(DEFAULT_TIMEOUT_SECS))
+        .redirect(reqwest::redirect::Policy::none());
+    if let Some(url::Host::Domain(domain)) = url.host() {
+        if !is_allowed_host(&url) {
+            let port = url.port_or_known_default().unwrap_or(80);
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((domain, port)).await?.collect();
+            if addrs.iter().any(|addr| is_internal(addr.ip())) {
+                return Err(internal_host_error(&webhook.url));
+            }
+            builder = builder.resolve_to_addrs(domain, &addrs);
+        }
+    }
+    Ok(builder.build()?)
+}
+
+/// POST the payload to the webhook, retrying with backoff until it answers with a success
+/// status or runs out of attempts
+pub async fn deliver(webhook: &Webhook, payload: &WebhookPayload) -> Result<(), OxenError> {
+    deliver_with_backoff(webhook, payload, Duration::from_secs(1)).await
+}
+
+async fn deliver_with_backoff(
+    webhook: &Webhook,
+    payload: &WebhookPayload,
+    backoff: Duration,
+) -> Result<(), OxenError> {
+    let body = serde_json::to_vec(payload)?;
+    let client = client_for(webhook).await?;
+
+    let mut last_error = String::new();
+    for attempt in 0..MAX_DELIVERY_ATTEMPTS {
+        if attempt > 0 {
+            tokio::time::sleep(backoff * 2u32.pow(attempt - 1)).await;
+        }
+        let mut request = client
+            .post(&webhook.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(EVENT_HEADER, payload.event.to_string())
+            .body(body.clone());
+        if let Some(secret) = &webhook.secret {
+            request = request.header(SIGNATURE_HEADER, sign(secret, &body));
+        }
+        match request.send().await {
+            Ok(res) if res.status().is_success() => return Ok(()),
+            Ok(res) => last_error = format!("status {}", res.status()),
+            Err(err) => last_error = err.to_string(),
+        }
+        log::warn!(
+            "webhook {} delivery {} of {} to {} failed: {}",
+            webhook.id,
+            attempt + 1,
+            MAX_DELIVERY_ATTEMPTS,
+            webhook.url,
+            last_error
+        );
+    }
+    Err(OxenError::basic_str(format!(
+        "Could not deliver {} to webhook {} after {} attempts: {}",
+        payload.event, webhook.url, MAX_DELIVERY_ATTEMPTS, last_error
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::error::OxenError;
+    use crate::model::{Branch, Webhook, WebhookEvent, WebhookPayload};
+    use crate::repositories;
+    use crate::repositories::webhooks::SIGNATURE_HEADER;
+    use crate::test;
+
+    #[tokio::test]
+    async fn test_add_list_and_remove_webhooks() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            let push_hook = repositories::webhooks::add(
+                &repo,
+                "https://example.com/push",
+                &[WebhookEvent::Push],
+                Some("secret".to_string()),
+            )?;
+            let all_hook = repositories::webhooks::add(&repo, "http://example.com/all", &[], None)?;
+            assert_eq!(all_hook.events, WebhookEvent::all());
+            assert!(repositories::webhooks::add(&repo, "ftp://example.com", &[], None).is_err());
+
+            // Hooks can't reach into the server's network
+            for url in [
+                "http://localhost:3000/hook",
+                "http://10.0.0.7/hook",
+                "http://169.254.169.254/latest/meta-data",
+                "http://[::1]/hook",
+                "http://[::ffff:192.168.1.1]/hook",
+            ] {
+                assert!(repositories::webhooks::add(&repo, url, &[], None).is_err());
+            }
+
+            let hooks = repositories::webhooks::list(&repo)?;
+            assert_eq!(hooks, vec![push_hook.clone(), all_hook.clone()]);
+            let tag_hooks = repositories::webhooks::for_event(&repo, WebhookEvent::TagCreated)?;
+            assert_eq!(tag_hooks, vec![all_hook.clone()]);
+
+            repositories::webhooks::remove(&repo, &push_hook.id)?;
+            assert_eq!(repositories::webhooks::list(&repo)?, vec![all_hook]);
+            assert!(repositories::webhooks::remove(&repo, &push_hook.id).is_err());
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_deliver_rejects_internal_hosts() -> Result<(), OxenError> {
+        // Added before the check existed, or edited into hooks.toml by hand
+        let webhook = Webhook {
+            id: "internal".to_string(),
+            url: "http://localhost:9/hook".to_string(),
+            events: WebhookEvent::all(),
+            secret: None,
+        };
+        let branch = Branch {
+            name: "main".to_string(),
+            commit_id: "abc123".to_string(),
+        };
+        let payload = WebhookPayload::push("ox/repo", &branch);
+        let result = repositories::webhooks::deliver_with_backoff(
+            &webhook,
+            &payload,
+            Duration::from_millis(10),
+        )
+        .await;
+        assert!(result.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_deliver_signs_and_retries() -> Result<(), OxenError> {
+        // The mock server listens on loopback
+        std::env::set_var("OXEN_WEBHOOK_ALLOWED_HOSTS", "127.0.0.1");
+        test::run_empty_local_repo_test_async(|repo| async move {
+            let mut server = mockito::Server::new_async().await;
+            let webhook = repositories::webhooks::add(
+                &repo,
+                format!("{}/hook", server.url()),
+                &[WebhookEvent::Push],
+                Some("secret".to_string()),
+            )?;
+            let branch = Branch {
+                name: "main".to_string(),
+                commit_id: "abc123".to_string(),
+            };
+            let payload = WebhookPayload::push("ox/repo", &branch);
+            let body = serde_json::to_vec(&payload)?;
+            let signature = repositories::webhooks::sign("secret", &body);
+            assert!(repositories::webhooks::verify("secret", &body, &signature));
+            assert!(!repositories::webhooks::verify("other", &body, &signature));
+
+            // The first attempt fails, the retry goes through
+            let failed = server
+                .mock("POST", "/hook")
+                .with_status(500)
+                .expect(1)
+                .create_async()
+                .await;
+            let delivered = server
+                .mock("POST", "/hook")
+                .match_header(SIGNATURE_HEADER, signature.as_str())
+                .match_body(mockito::Matcher::from(body.clone()))
+                .with_status(200)
+                .expect(1)
+                .create_async()
+                .await;
+            repositories::webhooks::deliver_with_backoff(
+                &webhook,
+                &payload,
+                Duration::from_millis(10),
+            )
+            .await?;
+            failed.assert_async().await;
+            delivered.assert_async().await;
+
+            Ok(())
+        })
+        .await
+    }
+}
@@ -7,7 +7,9 @@ use crate::model::{merkle_tree, MetadataEntry, ParsedResource, StagedData, Stage
 use crate::repositories;
 use crate::util;
 
-use crate::model::{workspace::WorkspaceConfig, Commit, LocalRepository, NewCommitBody, Workspace};
+use crate::model::{
+    workspace::WorkspaceConfig, Commit, LocalRepository, NewCommitBody, User, Workspace,
+};
 use crate::view::entries::EMetadataEntry;
 use crate::view::merge::Mergeable;
 
@@ -374,14 +376,31 @@ pub fn update_commit(workspace: &Workspace, new_commit_id: &str) -> Result<(), O
     Ok(())
 }
 
+/// Commit the workspace's changes onto a branch. Protected branches reject changes to
+/// owned paths, use [`commit_as`] to commit on behalf of a user who may own them.
 pub fn commit(
     workspace: &Workspace,
     new_commit: &NewCommitBody,
     branch_name: impl AsRef<str>,
 ) -> Result<Commit, OxenError> {
+    commit_as(workspace, new_commit, branch_name, None)
+}
+
+/// Commit the workspace's changes onto a branch, checking the branch protection and
+/// owners rules against the `pusher` like a push of the commit would.
+pub fn commit_as(
+    workspace: &Workspace,
+    new_commit: &NewCommitBody,
+    branch_name: impl AsRef<str>,
+    pusher: Option<&User>,
+) -> Result<Commit, OxenError> {
+    workspace
+        .base_repo
+        .commit_message_config()
+        .validate(&new_commit.message)?;
     match workspace.workspace_repo.min_version() {
         MinOxenVersion::V0_10_0 => panic!("v0.10.0 no longer supported"),
-        _ => core::v_latest::workspaces::commit::commit(workspace, new_commit, branch_name),
+        _ => core::v_latest::workspaces::commit::commit(workspace, new_commit, branch_name, pusher),
     }
 }
 
@@ -550,6 +569,7 @@ fn build_file_status_maps_for_file(
 mod tests {
     use super::*;
     use crate::api;
+    use crate::config::{BranchProtectionRule, OWNERS_CONFIG_FILENAME};
     use crate::constants::DEFAULT_BRANCH_NAME;
     use crate::repositories;
     use crate::test;
@@ -770,6 +790,65 @@ mod tests {
         .await
     }
 
+    #[tokio::test]
+    async fn test_workspace_commit_to_owned_path_on_protected_branch_needs_owner(
+    ) -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            let schema_file = repo.path.join("schemas").join("images.json");
+            util::fs::write_to_path(&schema_file, "{\"label\": \"str\"}")?;
+            repositories::add(&repo, &schema_file).await?;
+            let commit = repositories::commit(&repo, "Adding a schema")?;
+
+            util::fs::write_to_path(
+                util::fs::oxen_hidden_dir(&repo.path).join(OWNERS_CONFIG_FILENAME),
+                "schemas/ ada@oxen.ai\n",
+            )?;
+            repositories::branches::protection::protect(
+                &repo,
+                BranchProtectionRule::new(DEFAULT_BRANCH_NAME),
+            )?;
+
+            let temp_workspace = create_temporary(&repo, &commit)?;
+            let workspace_schema_file = temp_workspace.dir().join("schemas").join("images.json");
+            util::fs::write_to_path(&workspace_schema_file, "{\"label\": \"int\"}")?;
+            repositories::workspaces::files::add(&temp_workspace, workspace_schema_file).await?;
+            let new_commit = NewCommitBody {
+                message: "Updating the schema".to_string(),
+                author: "Bo".to_string(),
+                email: "bo@oxen.ai".to_string(),
+            };
+
+            // Neither an anonymous commit nor one by someone else can change owned paths
+            let result = commit(&temp_workspace, &new_commit, DEFAULT_BRANCH_NAME);
+            assert!(matches!(result, Err(OxenError::ApprovalRequired(_))));
+            let bo = User {
+                name: "Bo".to_string(),
+                email: "bo@oxen.ai".to_string(),
+            };
+            let result = commit_as(&temp_workspace, &new_commit, DEFAULT_BRANCH_NAME, Some(&bo));
+            assert!(matches!(result, Err(OxenError::ApprovalRequired(_))));
+            let main = repositories::branches::get_by_name(&repo, DEFAULT_BRANCH_NAME)?.unwrap();
+            assert_eq!(main.commit_id, commit.id);
+
+            // The owner can
+            let ada = User {
+                name: "Ada".to_string(),
+                email: "ada@oxen.ai".to_string(),
+            };
+            let owner_commit = commit_as(
+                &temp_workspace,
+                &new_commit,
+                DEFAULT_BRANCH_NAME,
+                Some(&ada),
+            )?;
+            let main = repositories::branches::get_by_name(&repo, DEFAULT_BRANCH_NAME)?.unwrap();
+            assert_eq!(main.commit_id, owner_commit.id);
+
+            Ok(())
+        })
+        .await
+    }
+
     #[tokio::test]
     async fn test_concurrent_workspace_commits() -> Result<(), OxenError> {
         test::run_one_commit_sync_repo_test(|repo, remote_repo| async move {
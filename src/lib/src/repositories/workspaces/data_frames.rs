@@ -245,6 +245,7 @@ pub fn full_diff(workspace: &Workspace, path: impl AsRef<Path>) -> Result<DiffRe
 
     let diff_result = TabularDiff {
         contents: diff_df,
+        modified_cells: DataFrame::empty(),
         parameters: TabularDiffParameters::empty(),
         summary: diff_summary,
         filename1: None,
@@ -9,7 +9,12 @@ use std::path::Path;
 use crate::core;
 use crate::core::versions::MinOxenVersion;
 use crate::error::OxenError;
-use crate::model::{StagedData, Workspace};
+use crate::model::diff::{
+    AddRemoveModifyCounts, DiffResult, WorkspaceChanges, WorkspaceFileChange,
+};
+use crate::model::{StagedData, StagedEntryStatus, Workspace};
+use crate::repositories::workspaces::data_frames;
+use crate::util;
 
 pub fn status(workspace: &Workspace) -> Result<StagedData, OxenError> {
     status_from_dir(workspace, Path::new(""))
@@ -24,3 +29,122 @@ pub fn status_from_dir(
         _ => core::v_latest::workspaces::status::status(workspace, directory),
     }
 }
+
+/// The files added, modified and removed in a workspace below `directory`, with the row
+/// counts of the data frames that were edited in place
+pub fn changes(
+    workspace: &Workspace,
+    directory: impl AsRef<Path>,
+) -> Result<WorkspaceChanges, OxenError> {
+    let staged = status_from_dir(workspace, directory)?;
+    changes_from_staged(workspace, &staged)
+}
+
+/// Summarize the files already read by [`status_from_dir`]
+pub fn changes_from_staged(
+    workspace: &Workspace,
+    staged: &StagedData,
+) -> Result<WorkspaceChanges, OxenError> {
+    let mut changes = WorkspaceChanges {
+        base_commit_id: workspace.commit.id.clone(),
+        ..WorkspaceChanges::default()
+    };
+    for (path, entry) in staged.staged_files.iter() {
+        match entry.status {
+            StagedEntryStatus::Added => changes.added_files.push(WorkspaceFileChange {
+                path: path.clone(),
+                row_counts: None,
+            }),
+            StagedEntryStatus::Modified => changes.modified_files.push(WorkspaceFileChange {
+                path: path.clone(),
+                row_counts: row_counts(workspace, path)?,
+            }),
+            StagedEntryStatus::Removed => changes.removed_files.push(WorkspaceFileChange {
+                path: path.clone(),
+                row_counts: None,
+            }),
+            StagedEntryStatus::Unmodified => {}
+        }
+    }
+    for files in [
+        &mut changes.added_files,
+        &mut changes.modified_files,
+        &mut changes.removed_files,
+    ] {
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+    }
+    Ok(changes)
+}
+
+/// Row counts of a data frame that has been indexed and edited in the workspace
+fn row_counts(
+    workspace: &Workspace,
+    path: &Path,
+) -> Result<Option<AddRemoveModifyCounts>, OxenError> {
+    if !util::fs::is_tabular(path)
+        || !data_frames::duckdb_path(workspace, path).exists()
+        || !data_frames::is_indexed(workspace, path)?
+    {
+        return Ok(None);
+    }
+    match data_frames::full_diff(workspace, path)? {
+        DiffResult::Tabular(diff) => Ok(Some(diff.summary.modifications.row_counts)),
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use serde_json::json;
+
+    use crate::config::UserConfig;
+    use crate::error::OxenError;
+    use crate::repositories;
+    use crate::repositories::workspaces;
+    use crate::test;
+
+    #[tokio::test]
+    async fn test_workspace_changes_counts_edited_rows() -> Result<(), OxenError> {
+        // Skip duckdb if on windows
+        if std::env::consts::OS == "windows" {
+            return Ok(());
+        }
+
+        test::run_training_data_repo_test_fully_committed_async(|repo| async move {
+            let commit = repositories::commits::head_commit(&repo)?;
+            let workspace_id = UserConfig::identifier()?;
+            let workspace = repositories::workspaces::create(&repo, &commit, workspace_id, true)?;
+            let file_path = Path::new("annotations")
+                .join("train")
+                .join("bounding_box.csv");
+
+            let changes = workspaces::status::changes(&workspace, "")?;
+            assert!(changes.is_empty());
+
+            workspaces::data_frames::index(&repo, &workspace, &file_path)?;
+            let json_data = json!({
+                "file": "dawg1.jpg",
+                "label": "dog",
+                "min_x": 13,
+                "min_y": 14,
+                "width": 100,
+                "height": 100
+            });
+            workspaces::data_frames::rows::add(&repo, &workspace, &file_path, &json_data)?;
+
+            let changes = workspaces::status::changes(&workspace, "")?;
+            assert_eq!(changes.base_commit_id, commit.id);
+            assert!(changes.added_files.is_empty());
+            assert_eq!(changes.modified_files.len(), 1);
+            assert_eq!(changes.modified_files[0].path, file_path);
+            let row_counts = changes.modified_files[0].row_counts.as_ref().unwrap();
+            assert_eq!(row_counts.added, 1);
+            assert_eq!(row_counts.removed, 0);
+
+            Ok(())
+        })
+        .await
+    }
+}
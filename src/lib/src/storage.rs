@@ -1,7 +1,9 @@
+pub mod compressed;
 pub mod local;
 pub mod s3;
 pub mod version_store;
 
+pub use compressed::CompressedVersionStore;
 pub use local::LocalVersionStore;
 pub use s3::S3VersionStore;
 pub use version_store::*;
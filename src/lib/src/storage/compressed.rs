@@ -0,0 +1,429 @@
+//! Transparent zstd compression around any [`VersionStore`]
+//!
+//! Blobs start with a zstd skippable frame that marks them as compressed by us, followed by
+//! the compressed data, so they can still be read with the standard zstd tools. Blobs
+//! without the marker, such as the ones written before compression was turned on, are read
+//! back as they are. Callers that need a path on disk get a decompressed copy in the cache dir,
+//! which is kept under a size limit by deleting the copies that were used least recently.
+
+use std::collections::HashMap;
+use std::io::{self, Read, Seek};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use async_compression::tokio::bufread::{ZstdDecoder, ZstdEncoder};
+use async_compression::Level;
+use async_trait::async_trait;
+use filetime::FileTime;
+use tokio::fs::{self, File};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeekExt, BufReader, SeekFrom};
+
+use crate::constants::VERSION_FILE_NAME;
+use crate::error::OxenError;
+use crate::storage::version_store::{ReadSeek, VersionStore};
+use crate::util;
+
+/// Skippable zstd frame (magic 0x184D2A50 with an 8 byte payload) marking a compressed blob
+const COMPRESSED_HEADER: [u8; 16] = [
+    0x50, 0x2A, 0x4D, 0x18, 0x08, 0x00, 0x00, 0x00, b'o', b'x', b'e', b'n', b'z', b's', b't', b'd',
+];
+
+/// Decompressed copies are evicted once they take up more than this many bytes
+pub const DEFAULT_MAX_CACHE_BYTES: u64 = 4 * 1024 * 1024 * 1024;
+
+/// Version store that zstd compresses blobs before handing them to the inner store
+#[derive(Debug)]
+pub struct CompressedVersionStore {
+    inner: Arc<dyn VersionStore>,
+    level: i32,
+    /// Where decompressed copies are kept for callers that need a local path
+    cache_dir: PathBuf,
+    max_cache_bytes: u64,
+}
+
+impl CompressedVersionStore {
+    /// Create a new CompressedVersionStore
+    ///
+    /// # Arguments
+    /// * `inner` - Store that keeps the compressed blobs
+    /// * `level` - zstd compression level
+    /// * `cache_dir` - Directory for decompressed copies of the version files
+    pub fn new(inner: Arc<dyn VersionStore>, level: i32, cache_dir: impl AsRef<Path>) -> Self {
+        Self {
+            inner,
+            level,
+            cache_dir: cache_dir.as_ref().to_path_buf(),
+            max_cache_bytes: DEFAULT_MAX_CACHE_BYTES,
+        }
+    }
+
+    /// Limit the bytes the decompressed copies take up
+    pub fn with_max_cache_bytes(mut self, max_cache_bytes: u64) -> Self {
+        self.max_cache_bytes = max_cache_bytes;
+        self
+    }
+
+    /// Get the path of the decompressed copy of a version file
+    fn cache_path(&self, hash: &str) -> PathBuf {
+        self.cache_dir
+            .join(&hash[..2])
+            .join(&hash[2..])
+            .join(VERSION_FILE_NAME)
+    }
+
+    /// Compress everything from the reader into the inner store
+    async fn store_compressed<R>(&self, hash: &str, reader: &mut R) -> Result<(), OxenError>
+    where
+        R: AsyncRead + Send + Unpin + ?Sized,
+    {
+        let encoder = ZstdEncoder::with_quality(BufReader::new(reader), Level::Precise(self.level));
+        let mut compressed = AsyncReadExt::chain(&COMPRESSED_HEADER[..], encoder);
+        self.inner
+            .store_version_from_reader(hash, &mut compressed)
+            .await
+    }
+
+    /// Delete the least recently used decompressed copies until the cache fits in its
+    /// limit, keeping the copy at `keep` that is about to be handed out. Copies are marked
+    /// as used by bumping their modification time.
+    fn evict(&self, keep: &Path) -> Result<(), OxenError> {
+        let mut copies: Vec<(FileTime, u64, PathBuf)> = vec![];
+        let mut total_bytes = 0;
+        for entry in walkdir::WalkDir::new(&self.cache_dir) {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(err) => {
+                    log::debug!("Could not read the decompressed cache: {err}");
+                    continue;
+                }
+            };
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let metadata = entry.metadata().map_err(io::Error::from)?;
+            total_bytes += metadata.len();
+            if entry.path() != keep {
+                let used_at = FileTime::from_last_modification_time(&metadata);
+                copies.push((used_at, metadata.len(), entry.into_path()));
+            }
+        }
+
+        copies.sort();
+        for (_, num_bytes, path) in copies {
+            if total_bytes <= self.max_cache_bytes {
+                break;
+            }
+            // Another process may be reading it, it is removed on the next eviction
+            match std::fs::remove_file(&path) {
+                Ok(()) => total_bytes -= num_bytes,
+                Err(err) => log::debug!("Could not evict {path:?}: {err}"),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl VersionStore for CompressedVersionStore {
+    async fn init(&self) -> Result<(), OxenError> {
+        self.inner.init().await?;
+        if !self.cache_dir.exists() {
+            fs::create_dir_all(&self.cache_dir).await?;
+        }
+        Ok(())
+    }
+
+    async fn store_version_from_path(&self, hash: &str, file_path: &Path) -> Result<(), OxenError> {
+        if self.inner.version_exists(hash)? {
+            return Ok(());
+        }
+        let mut file = File::open(file_path).await?;
+        self.store_compressed(hash, &mut file).await
+    }
+
+    async fn store_version_from_reader(
+        &self,
+        hash: &str,
+        reader: &mut (dyn AsyncRead + Send + Unpin),
+    ) -> Result<(), OxenError> {
+        self.store_compressed(hash, reader).await
+    }
+
+    async fn store_version(&self, hash: &str, data: &[u8]) -> Result<(), OxenError> {
+        let mut reader = data;
+        self.store_compressed(hash, &mut reader).await
+    }
+
+    async fn store_version_chunk(
+        &self,
+        hash: &str,
+        chunk_number: u32,
+        data: &[u8],
+    ) -> Result<(), OxenError> {
+        // Chunks are only kept until they are combined, so they are stored as is
+        self.inner
+            .store_version_chunk(hash, chunk_number, data)
+            .await
+    }
+
+    async fn get_version_chunk(
+        &self,
+        hash: &str,
+        offset: u64,
+        size: u64,
+    ) -> Result<Vec<u8>, OxenError> {
+        // Offsets are into the original file, so read from the decompressed copy
+        let path = self.get_version_path(hash)?;
+        let mut file = File::open(&path).await?;
+        let file_len = file.metadata().await?.len();
+        if offset >= file_len || offset + size > file_len {
+            return Err(OxenError::IO(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "beyond end of file",
+            )));
+        }
+
+        file.seek(SeekFrom::Start(offset)).await?;
+        let mut buffer = vec![0u8; size as usize];
+        file.read_exact(&mut buffer).await?;
+        Ok(buffer)
+    }
+
+    async fn list_version_chunks(&self, hash: &str) -> Result<Vec<u32>, OxenError> {
+        self.inner.list_version_chunks(hash).await
+    }
+
+    async fn combine_version_chunks(
+        &self,
+        hash: &str,
+        cleanup: bool,
+    ) -> Result<PathBuf, OxenError> {
+        // The inner store combines the chunks uncompressed. Keep that file as the
+        // decompressed copy and replace the stored version with a compressed one.
+        let combined_path = self.inner.combine_version_chunks(hash, cleanup).await?;
+        let cache_path = self.cache_path(hash);
+        if let Some(parent) = cache_path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        fs::rename(&combined_path, &cache_path).await?;
+
+        let mut file = File::open(&cache_path).await?;
+        self.store_compressed(hash, &mut file).await?;
+        self.evict(&cache_path)?;
+        Ok(cache_path)
+    }
+
+    fn open_version(&self, hash: &str) -> Result<Box<dyn ReadSeek + Send + Sync>, OxenError> {
+        let path = self.get_version_path(hash)?;
+        let file = std::fs::File::open(path)?;
+        Ok(Box::new(file))
+    }
+
+    async fn get_version(&self, hash: &str) -> Result<Vec<u8>, OxenError> {
+        let data = self.inner.get_version(hash).await?;
+        match data.strip_prefix(&COMPRESSED_HEADER[..]) {
+            Some(compressed) => Ok(zstd::decode_all(compressed)?),
+            None => Ok(data),
+        }
+    }
+
+    fn get_version_path(&self, hash: &str) -> Result<PathBuf, OxenError> {
+        let path = self.inner.get_version_path(hash)?;
+        if !path.exists() || !has_compressed_header(&path)? {
+            return Ok(path);
+        }
+
+        let cache_path = self.cache_path(hash);
+        if cache_path.exists() {
+            // Mark the copy as recently used so it is evicted last
+            if let Err(err) = filetime::set_file_mtime(&cache_path, FileTime::now()) {
+                log::debug!("Could not mark {cache_path:?} as used: {err}");
+            }
+            return Ok(cache_path);
+        }
+
+        util::fs::create_dir_all(self.cache_dir.join(&hash[..2]).join(&hash[2..]))?;
+        // Decompress next to the cache path first so readers never see a partial file
+        let tmp_path = cache_path.with_extension("part");
+        let mut file = std::fs::File::open(&path)?;
+        file.seek(io::SeekFrom::Start(COMPRESSED_HEADER.len() as u64))?;
+        let mut output = std::fs::File::create(&tmp_path)?;
+        zstd::stream::copy_decode(file, &mut output)?;
+        std::fs::rename(&tmp_path, &cache_path)?;
+        self.evict(&cache_path)?;
+        Ok(cache_path)
+    }
+
+    async fn copy_version_to_path(&self, hash: &str, dest_path: &Path) -> Result<(), OxenError> {
+        let cache_path = self.cache_path(hash);
+        if cache_path.exists() {
+            fs::copy(&cache_path, dest_path).await?;
+            return Ok(());
+        }
+
+        let path = self.inner.get_version_path(hash)?;
+        let mut file = File::open(&path).await?;
+        let mut header = [0u8; COMPRESSED_HEADER.len()];
+        let is_compressed =
+            file.read_exact(&mut header).await.is_ok() && header == COMPRESSED_HEADER;
+        if !is_compressed {
+            fs::copy(&path, dest_path).await?;
+            return Ok(());
+        }
+
+        // The file is already past the header, stream the rest through the decoder
+        let mut decoder = ZstdDecoder::new(BufReader::new(file));
+        let mut output = File::create(dest_path).await?;
+        tokio::io::copy(&mut decoder, &mut output).await?;
+        Ok(())
+    }
+
+    fn version_exists(&self, hash: &str) -> Result<bool, OxenError> {
+        self.inner.version_exists(hash)
+    }
+
+    async fn get_version_size(&self, hash: &str) -> Result<u64, OxenError> {
+        // The size of the contents, not of the compressed blob
+        let cache_path = self.cache_path(hash);
+        if cache_path.exists() {
+            return Ok(fs::metadata(&cache_path).await?.len());
+        }
+
+        let path = self.inner.get_version_path(hash)?;
+        let mut file = File::open(&path).await?;
+        let mut header = [0u8; COMPRESSED_HEADER.len()];
+        let is_compressed =
+            file.read_exact(&mut header).await.is_ok() && header == COMPRESSED_HEADER;
+        if !is_compressed {
+            return self.inner.get_version_size(hash).await;
+        }
+        let mut decoder = ZstdDecoder::new(BufReader::new(file));
+        Ok(tokio::io::copy(&mut decoder, &mut tokio::io::sink()).await?)
+    }
+
+    async fn delete_version(&self, hash: &str) -> Result<(), OxenError> {
+        self.inner.delete_version(hash).await?;
+        if let Some(cache_dir) = self.cache_path(hash).parent() {
+            if cache_dir.exists() {
+                fs::remove_dir_all(cache_dir).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn list_versions(&self) -> Result<Vec<String>, OxenError> {
+        self.inner.list_versions().await
+    }
+
+    fn storage_type(&self) -> &str {
+        self.inner.storage_type()
+    }
+
+    fn storage_settings(&self) -> HashMap<String, String> {
+        self.inner.storage_settings()
+    }
+
+    fn compression_level(&self) -> Option<i32> {
+        Some(self.level)
+    }
+}
+
+fn has_compressed_header(path: &Path) -> Result<bool, OxenError> {
+    let mut file = std::fs::File::open(path)?;
+    let mut header = [0u8; COMPRESSED_HEADER.len()];
+    match file.read_exact(&mut header) {
+        Ok(()) => Ok(header == COMPRESSED_HEADER),
+        Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => Ok(false),
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::LocalVersionStore;
+    use tempfile::TempDir;
+
+    async fn setup() -> (TempDir, Arc<LocalVersionStore>, CompressedVersionStore) {
+        let temp_dir = TempDir::new().unwrap();
+        let inner = Arc::new(LocalVersionStore::new(temp_dir.path().join("files")));
+        let store = CompressedVersionStore::new(inner.clone(), 3, temp_dir.path().join("cache"));
+        store.init().await.unwrap();
+        (temp_dir, inner, store)
+    }
+
+    #[tokio::test]
+    async fn test_compressed_store_round_trip() {
+        let (temp_dir, inner, store) = setup().await;
+        let hash = "abcdef1234567890";
+        let data = "a,b,c\n1,2,3\n".repeat(1000);
+
+        store.store_version(hash, data.as_bytes()).await.unwrap();
+
+        // The inner store only has the smaller compressed blob
+        let raw = inner.get_version(hash).await.unwrap();
+        assert!(raw.starts_with(&COMPRESSED_HEADER));
+        assert!(raw.len() < data.len());
+
+        assert_eq!(store.get_version(hash).await.unwrap(), data.as_bytes());
+        assert_eq!(
+            store.get_version_size(hash).await.unwrap(),
+            data.len() as u64
+        );
+        let path = store.get_version_path(hash).unwrap();
+        assert_eq!(std::fs::read(path).unwrap(), data.as_bytes());
+        assert_eq!(
+            store.get_version_chunk(hash, 6, 5).await.unwrap(),
+            &data.as_bytes()[6..11]
+        );
+
+        let dest_path = temp_dir.path().join("copy.csv");
+        store.copy_version_to_path(hash, &dest_path).await.unwrap();
+        assert_eq!(std::fs::read(dest_path).unwrap(), data.as_bytes());
+    }
+
+    #[tokio::test]
+    async fn test_compressed_store_reads_uncompressed_versions() {
+        let (temp_dir, inner, store) = setup().await;
+        let hash = "1234567890abcdef";
+        let data = b"written before compression was turned on";
+
+        inner.store_version(hash, data).await.unwrap();
+
+        assert_eq!(store.get_version(hash).await.unwrap(), data);
+        assert_eq!(
+            store.get_version_path(hash).unwrap(),
+            inner.get_version_path(hash).unwrap()
+        );
+
+        let dest_path = temp_dir.path().join("copy.txt");
+        store.copy_version_to_path(hash, &dest_path).await.unwrap();
+        assert_eq!(std::fs::read(dest_path).unwrap(), data);
+    }
+
+    #[tokio::test]
+    async fn test_compressed_store_evicts_least_recently_used_copies() {
+        let (temp_dir, inner, _) = setup().await;
+        let store = CompressedVersionStore::new(inner, 3, temp_dir.path().join("cache"))
+            .with_max_cache_bytes(2500);
+        let data = "x".repeat(1000);
+        let hashes = ["aaaa1234567890", "bbbb1234567890", "cccc1234567890"];
+        for hash in hashes {
+            store.store_version(hash, data.as_bytes()).await.unwrap();
+        }
+
+        let first = store.get_version_path(hashes[0]).unwrap();
+        let second = store.get_version_path(hashes[1]).unwrap();
+        // Using the first copy again makes the second the least recently used
+        filetime::set_file_mtime(&second, FileTime::from_unix_time(0, 0)).unwrap();
+        store.get_version_path(hashes[0]).unwrap();
+        let third = store.get_version_path(hashes[2]).unwrap();
+
+        assert!(first.exists());
+        assert!(!second.exists());
+        assert!(third.exists());
+        // Evicted copies are decompressed again when they are needed
+        let second = store.get_version_path(hashes[1]).unwrap();
+        assert_eq!(std::fs::read(second).unwrap(), data.as_bytes());
+    }
+}
@@ -138,6 +138,11 @@ impl VersionStore for LocalVersionStore {
         Ok(self.version_path(hash).exists())
     }
 
+    async fn get_version_size(&self, hash: &str) -> Result<u64, OxenError> {
+        let metadata = fs::metadata(self.version_path(hash)).await?;
+        Ok(metadata.len())
+    }
+
     async fn delete_version(&self, hash: &str) -> Result<(), OxenError> {
         let version_dir = self.version_dir(hash);
         if version_dir.exists() {
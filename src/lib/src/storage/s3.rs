@@ -1,17 +1,59 @@
+use crate::constants::{VERSION_CHUNKS_DIR, VERSION_CHUNK_FILE_NAME, VERSION_FILE_NAME};
 use crate::error::OxenError;
 use async_trait::async_trait;
+use aws_sdk_s3::config::{BehaviorVersion, Region};
+use aws_sdk_s3::error::DisplayErrorContext;
+use aws_sdk_s3::primitives::ByteStream;
+use aws_sdk_s3::types::{CompletedMultipartUpload, CompletedPart};
+use aws_sdk_s3::Client;
 use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
 use std::path::{Path, PathBuf};
+use tokio::fs::{self, File};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::runtime::RuntimeFlavor;
+use tokio::sync::OnceCell;
 
 use super::version_store::VersionStore;
 use crate::storage::version_store::ReadSeek;
 
+/// Files larger than this are uploaded in parts of this size. S3 requires every part
+/// but the last to be at least 5MiB.
+const MULTIPART_PART_SIZE: usize = 16 * 1024 * 1024;
+
 /// S3 implementation of version storage
-#[derive(Debug)]
+///
+/// Objects are laid out like the local store, `{prefix}/{hash[..2]}/{hash[2..]}/data`, with
+/// chunks under `chunks/{chunk_number}/chunk`. Callers that need a path on disk get a copy
+/// downloaded into the local cache dir.
+#[derive(Clone)]
 pub struct S3VersionStore {
     bucket: String,
     prefix: String,
-    // TODO: Add AWS client configuration
+    /// Region of the bucket, falls back to AWS_REGION or the aws profile
+    region: Option<String>,
+    /// Custom endpoint for S3 compatible stores such as MinIO or R2
+    endpoint: Option<String>,
+    /// Profile from the aws config files to take credentials from. The default aws
+    /// credential chain is used without one. Secrets are never kept in the repo config.
+    profile: Option<String>,
+    /// Where downloaded version files are kept for callers that need a local path
+    cache_dir: PathBuf,
+    client: OnceCell<Client>,
+}
+
+impl fmt::Debug for S3VersionStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("S3VersionStore")
+            .field("bucket", &self.bucket)
+            .field("prefix", &self.prefix)
+            .field("region", &self.region)
+            .field("endpoint", &self.endpoint)
+            .field("profile", &self.profile)
+            .field("cache_dir", &self.cache_dir)
+            .finish_non_exhaustive()
+    }
 }
 
 impl S3VersionStore {
@@ -21,113 +63,515 @@ impl S3VersionStore {
     /// * `bucket` - S3 bucket name
     /// * `prefix` - Prefix for all objects in the bucket
     pub fn new(bucket: impl Into<String>, prefix: impl Into<String>) -> Self {
+        let bucket = bucket.into();
+        let cache_dir = std::env::temp_dir().join("oxen").join("s3").join(&bucket);
         Self {
-            bucket: bucket.into(),
+            bucket,
             prefix: prefix.into(),
+            region: None,
+            endpoint: None,
+            profile: None,
+            cache_dir,
+            client: OnceCell::new(),
+        }
+    }
+
+    /// Use a specific region instead of the one from the environment
+    pub fn with_region(mut self, region: impl Into<String>) -> Self {
+        self.region = Some(region.into());
+        self
+    }
+
+    /// Talk to an S3 compatible endpoint instead of AWS, uses path style addressing
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = Some(endpoint.into());
+        self
+    }
+
+    /// Take credentials from a profile in the aws config files instead of the default
+    /// aws credential chain
+    pub fn with_profile(mut self, profile: impl Into<String>) -> Self {
+        self.profile = Some(profile.into());
+        self
+    }
+
+    /// Directory to download version files into when a local path is needed
+    pub fn with_cache_dir(mut self, cache_dir: impl AsRef<Path>) -> Self {
+        self.cache_dir = cache_dir.as_ref().to_path_buf();
+        self
+    }
+
+    async fn client(&self) -> Result<&Client, OxenError> {
+        self.client
+            .get_or_try_init(|| async {
+                Ok::<Client, OxenError>(
+                    client(
+                        self.region.as_deref(),
+                        self.endpoint.as_deref(),
+                        self.profile.as_deref(),
+                    )
+                    .await,
+                )
+            })
+            .await
+    }
+
+    /// Get the key prefix for everything stored for a version
+    fn version_dir_key(&self, hash: &str) -> String {
+        let topdir = &hash[..2];
+        let subdir = &hash[2..];
+        let prefix = self.prefix.trim_end_matches('/');
+        if prefix.is_empty() {
+            format!("{topdir}/{subdir}")
+        } else {
+            format!("{prefix}/{topdir}/{subdir}")
+        }
+    }
+
+    /// Get the key of a version file
+    fn version_key(&self, hash: &str) -> String {
+        format!("{}/{}", self.version_dir_key(hash), VERSION_FILE_NAME)
+    }
+
+    /// Get the key prefix for all the chunks of a version file
+    fn version_chunks_key(&self, hash: &str) -> String {
+        format!("{}/{}/", self.version_dir_key(hash), VERSION_CHUNKS_DIR)
+    }
+
+    /// Get the key of a single chunk of a version file
+    fn version_chunk_key(&self, hash: &str, chunk_number: u32) -> String {
+        format!(
+            "{}{}/{}",
+            self.version_chunks_key(hash),
+            chunk_number,
+            VERSION_CHUNK_FILE_NAME
+        )
+    }
+
+    /// Get the path a version file is downloaded to
+    fn cache_path(&self, hash: &str) -> PathBuf {
+        self.cache_dir
+            .join(&hash[..2])
+            .join(&hash[2..])
+            .join(VERSION_FILE_NAME)
+    }
+
+    async fn object_exists(&self, key: &str) -> Result<bool, OxenError> {
+        let client = self.client().await?;
+        match client
+            .head_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await
+        {
+            Ok(_) => Ok(true),
+            Err(err) if err.as_service_error().is_some_and(|e| e.is_not_found()) => Ok(false),
+            Err(err) => Err(s3_error(err)),
+        }
+    }
+
+    async fn list_keys(&self, prefix: &str) -> Result<Vec<String>, OxenError> {
+        let client = self.client().await?;
+        let mut pages = client
+            .list_objects_v2()
+            .bucket(&self.bucket)
+            .prefix(prefix)
+            .into_paginator()
+            .send();
+
+        let mut keys = Vec::new();
+        while let Some(page) = pages.next().await {
+            let page = page.map_err(s3_error)?;
+            keys.extend(
+                page.contents()
+                    .iter()
+                    .filter_map(|object| object.key().map(String::from)),
+            );
+        }
+        Ok(keys)
+    }
+
+    async fn delete_key(&self, key: &str) -> Result<(), OxenError> {
+        let client = self.client().await?;
+        client
+            .delete_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await
+            .map_err(s3_error)?;
+        Ok(())
+    }
+
+    async fn put_bytes(&self, key: &str, data: Vec<u8>) -> Result<(), OxenError> {
+        let client = self.client().await?;
+        client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .body(ByteStream::from(data))
+            .send()
+            .await
+            .map_err(s3_error)?;
+        Ok(())
+    }
+
+    /// Upload everything from the reader, with a single put for small files and a
+    /// multipart upload once the data is larger than one part
+    async fn put_from_reader<R>(&self, key: &str, reader: &mut R) -> Result<(), OxenError>
+    where
+        R: AsyncRead + Send + Unpin + ?Sized,
+    {
+        let first_part = read_part(reader).await?;
+        if first_part.len() < MULTIPART_PART_SIZE {
+            return self.put_bytes(key, first_part).await;
         }
+
+        let client = self.client().await?;
+        let upload = client
+            .create_multipart_upload()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await
+            .map_err(s3_error)?;
+        let upload_id = upload
+            .upload_id()
+            .ok_or_else(|| OxenError::basic_str("S3 did not return a multipart upload id"))?;
+
+        match self.upload_parts(key, upload_id, first_part, reader).await {
+            Ok(parts) => {
+                client
+                    .complete_multipart_upload()
+                    .bucket(&self.bucket)
+                    .key(key)
+                    .upload_id(upload_id)
+                    .multipart_upload(
+                        CompletedMultipartUpload::builder()
+                            .set_parts(Some(parts))
+                            .build(),
+                    )
+                    .send()
+                    .await
+                    .map_err(s3_error)?;
+                Ok(())
+            }
+            Err(err) => {
+                // Abort so the bucket is not billed for the parts that made it up
+                if let Err(abort_err) = client
+                    .abort_multipart_upload()
+                    .bucket(&self.bucket)
+                    .key(key)
+                    .upload_id(upload_id)
+                    .send()
+                    .await
+                {
+                    log::warn!(
+                        "Could not abort multipart upload of {key}: {}",
+                        DisplayErrorContext(abort_err)
+                    );
+                }
+                Err(err)
+            }
+        }
+    }
+
+    async fn upload_parts<R>(
+        &self,
+        key: &str,
+        upload_id: &str,
+        first_part: Vec<u8>,
+        reader: &mut R,
+    ) -> Result<Vec<CompletedPart>, OxenError>
+    where
+        R: AsyncRead + Send + Unpin + ?Sized,
+    {
+        let client = self.client().await?;
+        let mut parts = Vec::new();
+        let mut part = first_part;
+        let mut part_number = 1;
+        while !part.is_empty() {
+            log::debug!("Uploading part {part_number} of {key}");
+            let output = client
+                .upload_part()
+                .bucket(&self.bucket)
+                .key(key)
+                .upload_id(upload_id)
+                .part_number(part_number)
+                .body(ByteStream::from(part))
+                .send()
+                .await
+                .map_err(s3_error)?;
+            parts.push(
+                CompletedPart::builder()
+                    .set_e_tag(output.e_tag().map(String::from))
+                    .part_number(part_number)
+                    .build(),
+            );
+            part_number += 1;
+            part = read_part(reader).await?;
+        }
+        Ok(parts)
+    }
+
+    /// Stream an object into a file, writing to a temporary file first so a failed
+    /// download never leaves a partial file at the destination
+    async fn download_to_path(&self, key: &str, dest_path: &Path) -> Result<(), OxenError> {
+        let client = self.client().await?;
+        let output = client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await
+            .map_err(s3_error)?;
+
+        if let Some(parent) = dest_path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        let tmp_path = dest_path.with_extension("part");
+        let mut body = output.body.into_async_read();
+        let mut file = File::create(&tmp_path).await?;
+        tokio::io::copy(&mut body, &mut file).await?;
+        fs::rename(&tmp_path, dest_path).await?;
+        Ok(())
+    }
+
+    /// Download a version into the cache unless it is already there
+    async fn cache_version(&self, hash: &str) -> Result<PathBuf, OxenError> {
+        let cache_path = self.cache_path(hash);
+        if !cache_path.exists() {
+            self.download_to_path(&self.version_key(hash), &cache_path)
+                .await?;
+        }
+        Ok(cache_path)
     }
 }
 
 #[async_trait]
 impl VersionStore for S3VersionStore {
     async fn init(&self) -> Result<(), OxenError> {
-        // TODO: Implement S3 initialization
-        Err(OxenError::basic_str("S3VersionStore not yet implemented"))
+        self.client().await?;
+        if !self.cache_dir.exists() {
+            fs::create_dir_all(&self.cache_dir).await?;
+        }
+        Ok(())
     }
 
-    async fn store_version_from_path(
-        &self,
-        _hash: &str,
-        _file_path: &Path,
-    ) -> Result<(), OxenError> {
-        // TODO: Implement S3 version storage from path
-        Err(OxenError::basic_str("S3VersionStore not yet implemented"))
+    async fn store_version_from_path(&self, hash: &str, file_path: &Path) -> Result<(), OxenError> {
+        let key = self.version_key(hash);
+        if self.object_exists(&key).await? {
+            return Ok(());
+        }
+        let mut file = File::open(file_path).await?;
+        self.put_from_reader(&key, &mut file).await
     }
 
     async fn store_version_from_reader(
         &self,
-        _hash: &str,
-        _reader: &mut (dyn tokio::io::AsyncRead + Send + Unpin),
+        hash: &str,
+        reader: &mut (dyn tokio::io::AsyncRead + Send + Unpin),
     ) -> Result<(), OxenError> {
-        // TODO: Implement S3 version storage from reader
-        Err(OxenError::basic_str("S3VersionStore not yet implemented"))
+        self.put_from_reader(&self.version_key(hash), reader).await
     }
 
-    async fn store_version(&self, _hash: &str, _data: &[u8]) -> Result<(), OxenError> {
-        // TODO: Implement S3 version storage
-        Err(OxenError::basic_str("S3VersionStore not yet implemented"))
+    async fn store_version(&self, hash: &str, data: &[u8]) -> Result<(), OxenError> {
+        let mut reader = data;
+        self.put_from_reader(&self.version_key(hash), &mut reader)
+            .await
     }
 
     fn open_version(
         &self,
-        _hash: &str,
+        hash: &str,
     ) -> Result<Box<dyn ReadSeek + Send + Sync + 'static>, OxenError> {
-        // TODO: Implement S3 version opening
-        Err(OxenError::basic_str("S3VersionStore not yet implemented"))
+        let path = self.get_version_path(hash)?;
+        let file = std::fs::File::open(path)?;
+        Ok(Box::new(file))
     }
 
-    async fn get_version(&self, _hash: &str) -> Result<Vec<u8>, OxenError> {
-        // TODO: Implement S3 version retrieval
-        Err(OxenError::basic_str("S3VersionStore not yet implemented"))
+    async fn get_version(&self, hash: &str) -> Result<Vec<u8>, OxenError> {
+        let client = self.client().await?;
+        let output = client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(self.version_key(hash))
+            .send()
+            .await
+            .map_err(s3_error)?;
+        let data = output.body.collect().await.map_err(s3_error)?;
+        Ok(data.into_bytes().to_vec())
     }
 
-    fn get_version_path(&self, _hash: &str) -> Result<PathBuf, OxenError> {
-        // TODO: Implement S3 version path retrieval
-        Err(OxenError::basic_str("S3VersionStore not yet implemented"))
+    fn get_version_path(&self, hash: &str) -> Result<PathBuf, OxenError> {
+        let cache_path = self.cache_path(hash);
+        if cache_path.exists() {
+            return Ok(cache_path);
+        }
+        block_on(self.cache_version(hash))
     }
 
-    async fn copy_version_to_path(&self, _hash: &str, _dest_path: &Path) -> Result<(), OxenError> {
-        // TODO: Implement S3 version copying to path
-        Err(OxenError::basic_str("S3VersionStore not yet implemented"))
+    async fn copy_version_to_path(&self, hash: &str, dest_path: &Path) -> Result<(), OxenError> {
+        let cache_path = self.cache_path(hash);
+        if cache_path.exists() {
+            if let Some(parent) = dest_path.parent() {
+                fs::create_dir_all(parent).await?;
+            }
+            fs::copy(&cache_path, dest_path).await?;
+            return Ok(());
+        }
+        self.download_to_path(&self.version_key(hash), dest_path)
+            .await
     }
 
     async fn store_version_chunk(
         &self,
-        _hash: &str,
-        _chunk_number: u32,
-        _data: &[u8],
+        hash: &str,
+        chunk_number: u32,
+        data: &[u8],
     ) -> Result<(), OxenError> {
-        // TODO: Implement S3 version chunk storage
-        Err(OxenError::basic_str("S3VersionStore not yet implemented"))
+        self.put_bytes(&self.version_chunk_key(hash, chunk_number), data.to_vec())
+            .await
     }
 
     async fn get_version_chunk(
         &self,
-        _hash: &str,
-        _offset: u64,
-        _size: u64,
+        hash: &str,
+        offset: u64,
+        size: u64,
     ) -> Result<Vec<u8>, OxenError> {
-        // TODO: Implement S3 version chunk retrieval
-        Err(OxenError::basic_str("S3VersionStore not yet implemented"))
+        if size == 0 {
+            return Ok(Vec::new());
+        }
+        let client = self.client().await?;
+        let output = client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(self.version_key(hash))
+            .range(format!("bytes={}-{}", offset, offset + size - 1))
+            .send()
+            .await
+            .map_err(s3_error)?;
+        let data = output.body.collect().await.map_err(s3_error)?;
+        Ok(data.into_bytes().to_vec())
+    }
+
+    async fn list_version_chunks(&self, hash: &str) -> Result<Vec<u32>, OxenError> {
+        let chunks_key = self.version_chunks_key(hash);
+        let chunks = self
+            .list_keys(&chunks_key)
+            .await?
+            .iter()
+            .filter_map(|key| {
+                key.strip_prefix(&chunks_key)?
+                    .split('/')
+                    .next()?
+                    .parse::<u32>()
+                    .ok()
+            })
+            .collect();
+        Ok(chunks)
     }
 
-    async fn list_version_chunks(&self, _hash: &str) -> Result<Vec<u32>, OxenError> {
-        // TODO: Implement S3 version chunk listing
-        Err(OxenError::basic_str("S3VersionStore not yet implemented"))
+    fn version_exists(&self, hash: &str) -> Result<bool, OxenError> {
+        block_on(self.object_exists(&self.version_key(hash)))
     }
 
-    fn version_exists(&self, _hash: &str) -> Result<bool, OxenError> {
-        // TODO: Implement S3 version existence check
-        Err(OxenError::basic_str("S3VersionStore not yet implemented"))
+    async fn get_version_size(&self, hash: &str) -> Result<u64, OxenError> {
+        let client = self.client().await?;
+        let output = client
+            .head_object()
+            .bucket(&self.bucket)
+            .key(self.version_key(hash))
+            .send()
+            .await
+            .map_err(s3_error)?;
+        Ok(output.content_length().unwrap_or_default() as u64)
     }
 
-    async fn delete_version(&self, _hash: &str) -> Result<(), OxenError> {
-        // TODO: Implement S3 version deletion
-        Err(OxenError::basic_str("S3VersionStore not yet implemented"))
+    async fn delete_version(&self, hash: &str) -> Result<(), OxenError> {
+        let version_dir_key = format!("{}/", self.version_dir_key(hash));
+        for key in self.list_keys(&version_dir_key).await? {
+            self.delete_key(&key).await?;
+        }
+
+        if let Some(cache_dir) = self.cache_path(hash).parent() {
+            if cache_dir.exists() {
+                fs::remove_dir_all(cache_dir).await?;
+            }
+        }
+        Ok(())
     }
 
     async fn list_versions(&self) -> Result<Vec<String>, OxenError> {
-        // TODO: Implement S3 version listing
-        Err(OxenError::basic_str("S3VersionStore not yet implemented"))
+        let prefix = self.prefix.trim_end_matches('/');
+        let list_prefix = if prefix.is_empty() {
+            String::new()
+        } else {
+            format!("{prefix}/")
+        };
+
+        // Only the version files themselves, {topdir}/{subdir}/data, not the chunks
+        let versions = self
+            .list_keys(&list_prefix)
+            .await?
+            .iter()
+            .filter_map(|key| {
+                let parts: Vec<&str> = key.strip_prefix(&list_prefix)?.split('/').collect();
+                match parts.as_slice() {
+                    [topdir, subdir, file_name] if *file_name == VERSION_FILE_NAME => {
+                        Some(format!("{topdir}{subdir}"))
+                    }
+                    _ => None,
+                }
+            })
+            .collect();
+        Ok(versions)
     }
 
     async fn combine_version_chunks(
         &self,
-        _hash: &str,
-        _cleanup: bool,
+        hash: &str,
+        cleanup: bool,
     ) -> Result<PathBuf, OxenError> {
-        // TODO: Implement S3 version chunk combination
-        Err(OxenError::basic_str("S3VersionStore not yet implemented"))
+        // Chunks can be smaller than the minimum part size, so they are combined locally
+        // into the cache and uploaded from there
+        let cache_path = self.cache_path(hash);
+        if let Some(parent) = cache_path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+
+        let mut chunks = self.list_version_chunks(hash).await?;
+        chunks.sort();
+
+        let client = self.client().await?;
+        let mut output_file = File::create(&cache_path).await?;
+        for chunk_number in &chunks {
+            let output = client
+                .get_object()
+                .bucket(&self.bucket)
+                .key(self.version_chunk_key(hash, *chunk_number))
+                .send()
+                .await
+                .map_err(s3_error)?;
+            let mut body = output.body.into_async_read();
+            tokio::io::copy(&mut body, &mut output_file).await?;
+        }
+        drop(output_file);
+
+        let mut file = File::open(&cache_path).await?;
+        self.put_from_reader(&self.version_key(hash), &mut file)
+            .await?;
+
+        if cleanup {
+            for chunk_number in chunks {
+                self.delete_key(&self.version_chunk_key(hash, chunk_number))
+                    .await?;
+            }
+        }
+
+        Ok(cache_path)
     }
 
     fn storage_type(&self) -> &str {
@@ -138,6 +582,127 @@ impl VersionStore for S3VersionStore {
         let mut settings = HashMap::new();
         settings.insert("bucket".to_string(), self.bucket.clone());
         settings.insert("prefix".to_string(), self.prefix.clone());
+        if let Some(region) = &self.region {
+            settings.insert("region".to_string(), region.clone());
+        }
+        if let Some(endpoint) = &self.endpoint {
+            settings.insert("endpoint".to_string(), endpoint.clone());
+        }
+        if let Some(profile) = &self.profile {
+            settings.insert("profile".to_string(), profile.clone());
+        }
         settings
     }
 }
+
+/// An S3 client for the region and endpoint, or the ones from the environment. Without
+/// a profile the default aws credential chain is used.
+pub(crate) async fn client(
+    region: Option<&str>,
+    endpoint: Option<&str>,
+    profile: Option<&str>,
+) -> Client {
+    let mut loader = aws_config::defaults(BehaviorVersion::latest());
+    if let Some(region) = region {
+        loader = loader.region(Region::new(region.to_string()));
+    }
+    if let Some(profile) = profile {
+        loader = loader.profile_name(profile);
+    }
+    let sdk_config = loader.load().await;
+
+    let mut builder = aws_sdk_s3::config::Builder::from(&sdk_config);
+    if let Some(endpoint) = endpoint {
+        builder = builder.endpoint_url(endpoint).force_path_style(true);
+    }
+    Client::from_conf(builder.build())
+}
+
+pub(crate) fn s3_error<E: std::error::Error>(err: E) -> OxenError {
+    OxenError::basic_str(format!("S3 error: {}", DisplayErrorContext(err)))
+}
+
+/// Read up to one multipart part from the reader, a short read means we hit the end
+async fn read_part<R>(reader: &mut R) -> Result<Vec<u8>, OxenError>
+where
+    R: AsyncRead + Send + Unpin + ?Sized,
+{
+    let mut buf = Vec::with_capacity(MULTIPART_PART_SIZE);
+    (&mut *reader)
+        .take(MULTIPART_PART_SIZE as u64)
+        .read_to_end(&mut buf)
+        .await?;
+    Ok(buf)
+}
+
+/// Run a request from one of the sync trait methods. A worker of a multi threaded runtime
+/// hands its other tasks off while it blocks. Blocking the only thread of a current thread
+/// runtime, such as an actix worker, would deadlock it, so the request runs on its own
+/// runtime on another thread while the worker waits.
+fn block_on<T, F>(future: F) -> Result<T, OxenError>
+where
+    T: Send,
+    F: Future<Output = Result<T, OxenError>> + Send,
+{
+    match tokio::runtime::Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+            tokio::task::block_in_place(move || handle.block_on(future))
+        }
+        Ok(_) => std::thread::scope(|scope| {
+            scope
+                .spawn(move || new_runtime()?.block_on(future))
+                .join()
+                .map_err(|_| OxenError::basic_str("S3 request thread panicked"))?
+        }),
+        Err(_) => new_runtime()?.block_on(future),
+    }
+}
+
+fn new_runtime() -> Result<tokio::runtime::Runtime, OxenError> {
+    Ok(tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_s3_version_keys() {
+        let store = S3VersionStore::new("bucket", "versions/");
+        let hash = "abcdef1234567890";
+        assert_eq!(store.version_key(hash), "versions/ab/cdef1234567890/data");
+        assert_eq!(
+            store.version_chunk_key(hash, 3),
+            "versions/ab/cdef1234567890/chunks/3/chunk"
+        );
+
+        let store = S3VersionStore::new("bucket", "");
+        assert_eq!(store.version_key(hash), "ab/cdef1234567890/data");
+    }
+
+    #[tokio::test]
+    async fn test_block_on_current_thread_runtime() -> Result<(), OxenError> {
+        // tokio::test runs on a current thread runtime, like an actix worker
+        let value = block_on(async { Ok(42) })?;
+        assert_eq!(value, 42);
+        Ok(())
+    }
+
+    #[test]
+    fn test_s3_storage_settings_round_trip() {
+        let store = S3VersionStore::new("bucket", "versions")
+            .with_region("us-west-2")
+            .with_endpoint("http://localhost:9000")
+            .with_profile("oxen");
+        let settings = store.storage_settings();
+        assert_eq!(settings["bucket"], "bucket");
+        assert_eq!(settings["region"], "us-west-2");
+        assert_eq!(settings["endpoint"], "http://localhost:9000");
+        assert_eq!(settings["profile"], "oxen");
+
+        // Only the profile is saved, secrets stay in the aws config files
+        assert!(!settings.contains_key("secret_access_key"));
+    }
+}
@@ -10,7 +10,7 @@ use tokio::io::{AsyncRead, AsyncSeek};
 
 use crate::constants;
 use crate::error::OxenError;
-use crate::storage::{LocalVersionStore, S3VersionStore};
+use crate::storage::{CompressedVersionStore, LocalVersionStore, S3VersionStore};
 use crate::util;
 
 /// Configuration for version storage backend
@@ -22,6 +22,9 @@ pub struct StorageConfig {
     /// Backend-specific settings
     #[serde(default)]
     pub settings: HashMap<String, String>,
+    /// zstd level to compress version files with, stored uncompressed if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compression_level: Option<i32>,
 }
 
 /// Trait for async read and seek operations
@@ -139,6 +142,12 @@ pub trait VersionStore: Debug + Send + Sync + 'static {
     /// * `hash` - The content hash to check
     fn version_exists(&self, hash: &str) -> Result<bool, OxenError>;
 
+    /// Get the size in bytes of a stored version file
+    ///
+    /// # Arguments
+    /// * `hash` - The content hash of the version
+    async fn get_version_size(&self, hash: &str) -> Result<u64, OxenError>;
+
     /// Delete a version
     ///
     /// # Arguments
@@ -153,10 +162,14 @@ pub trait VersionStore: Debug + Send + Sync + 'static {
 
     /// Get the storage-specific settings
     fn storage_settings(&self) -> HashMap<String, String>;
+
+    /// Get the zstd level version files are compressed with, None if stored as is
+    fn compression_level(&self) -> Option<i32> {
+        None
+    }
 }
 
 /// Factory method to create the appropriate async version store (sync wrapper)
-/// TODO: Review this function when implementing S3 version store
 pub fn create_version_store(
     path: impl AsRef<Path>,
     storage_config: Option<&StorageConfig>,
@@ -187,6 +200,25 @@ pub async fn create_version_store_async(
     storage_config: Option<&StorageConfig>,
 ) -> Result<Arc<dyn VersionStore>, OxenError> {
     let path = path.as_ref();
+    let store = create_backend_version_store(path, storage_config).await?;
+    match storage_config.and_then(|config| config.compression_level) {
+        Some(level) => {
+            let cache_dir = util::fs::oxen_hidden_dir(path)
+                .join(constants::VERSIONS_DIR)
+                .join(constants::VERSIONS_DECOMPRESSED_DIR);
+            let store = CompressedVersionStore::new(store, level, cache_dir);
+            store.init().await?;
+            Ok(Arc::new(store))
+        }
+        None => Ok(store),
+    }
+}
+
+/// Create the store for the configured backend, without compression
+async fn create_backend_version_store(
+    path: &Path,
+    storage_config: Option<&StorageConfig>,
+) -> Result<Arc<dyn VersionStore>, OxenError> {
     match storage_config {
         Some(config) => match config.type_.as_str() {
             "local" => {
@@ -207,7 +239,19 @@ pub async fn create_version_store_async(
                     .get("prefix")
                     .cloned()
                     .unwrap_or_else(|| String::from("versions"));
-                let store = S3VersionStore::new(bucket, prefix);
+                let cache_dir = util::fs::oxen_hidden_dir(path)
+                    .join(constants::VERSIONS_DIR)
+                    .join(constants::VERSIONS_CACHE_DIR);
+                let mut store = S3VersionStore::new(bucket, prefix).with_cache_dir(cache_dir);
+                if let Some(region) = config.settings.get("region") {
+                    store = store.with_region(region);
+                }
+                if let Some(endpoint) = config.settings.get("endpoint") {
+                    store = store.with_endpoint(endpoint);
+                }
+                if let Some(profile) = config.settings.get("profile") {
+                    store = store.with_profile(profile);
+                }
                 store.init().await?;
                 Ok(Arc::new(store))
             }
@@ -1668,6 +1668,10 @@ pub fn test_img_file_with_name(name: &str) -> PathBuf {
     PathBuf::from("data").join("test").join("images").join(name)
 }
 
+pub fn test_pdf_file_with_name(name: &str) -> PathBuf {
+    PathBuf::from("data").join("test").join("pdf").join(name)
+}
+
 pub fn test_text_file_with_name(name: &str) -> PathBuf {
     PathBuf::from("data").join("test").join("text").join(name)
 }
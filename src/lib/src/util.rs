@@ -10,7 +10,9 @@ pub mod oxen_version;
 pub mod paginate;
 pub mod progress_bar;
 pub mod read_progress;
+pub mod signing;
 pub mod str;
+pub mod telemetry;
 
 pub use crate::util::read_progress::ReadProgress;
 pub use paginate::{paginate, paginate_with_total};
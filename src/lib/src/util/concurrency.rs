@@ -25,6 +25,15 @@ pub fn num_threads_for_items(num_items: usize) -> usize {
     }
 }
 
+/// Same as `num_threads_for_items`, but a number of workers set explicitly, such as by
+/// `--jobs` or the repo config, takes precedence over OXEN_NUM_THREADS and the default
+pub fn num_threads_for_items_with_limit(num_items: usize, num_workers: Option<usize>) -> usize {
+    match num_workers {
+        Some(num_workers) if num_workers > 0 => num_workers.min(num_items),
+        _ => num_threads_for_items(num_items),
+    }
+}
+
 fn get_default_num_workers() -> usize {
     // Check how many CPUs we have
     let num_cpus = num_cpus::get();
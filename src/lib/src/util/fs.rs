@@ -906,10 +906,13 @@ pub fn is_tabular_from_extension(data_path: impl AsRef<Path>, file_path: impl As
 /// Looks at the extension of the file to determine if it is tabular
 pub fn has_tabular_extension(file_path: impl AsRef<Path>) -> bool {
     let file_path = file_path.as_ref();
-    let exts: HashSet<String> = vec!["csv", "tsv", "parquet", "arrow", "ndjson", "jsonl"]
-        .into_iter()
-        .map(String::from)
-        .collect();
+    let exts: HashSet<String> = vec![
+        "csv", "tsv", "parquet", "arrow", "ndjson", "jsonl", "avro", "orc", "xlsx", "xlsm", "xlsb",
+        "xls", "ods",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect();
     contains_ext(file_path, &exts)
 }
 
@@ -969,6 +972,13 @@ pub fn data_type_from_extension(path: &Path) -> EntryDataType {
         "arrow" => EntryDataType::Tabular,
         "ndjson" => EntryDataType::Tabular,
         "jsonl" => EntryDataType::Tabular,
+        "avro" => EntryDataType::Tabular,
+        "orc" => EntryDataType::Tabular,
+        "xlsx" => EntryDataType::Tabular,
+        "xlsm" => EntryDataType::Tabular,
+        "xlsb" => EntryDataType::Tabular,
+        "xls" => EntryDataType::Tabular,
+        "ods" => EntryDataType::Tabular,
 
         "md" => EntryDataType::Text,
         "txt" => EntryDataType::Text,
@@ -1383,6 +1393,17 @@ pub fn is_in_oxen_hidden_dir(path: &Path) -> bool {
     false
 }
 
+/// Whether the file system a repository lives on ignores case, like the defaults on macOS
+/// and Windows. Checked by looking up the hidden dir with its name uppercased.
+pub fn is_case_insensitive(repo_path: impl AsRef<Path>) -> bool {
+    let hidden_dir = oxen_hidden_dir(&repo_path);
+    hidden_dir.exists()
+        && repo_path
+            .as_ref()
+            .join(constants::OXEN_HIDDEN_DIR.to_uppercase())
+            .exists()
+}
+
 pub fn is_canonical(path: impl AsRef<Path>) -> Result<bool, OxenError> {
     let path = path.as_ref();
     let canon_path = canonicalize(path)?;
@@ -1735,7 +1756,56 @@ pub fn remove_paths(src: &Path) -> Result<(), OxenError> {
     }
 }
 
-pub fn is_modified_from_node(path: &Path, node: &FileNode) -> Result<bool, OxenError> {
+/// The mode to commit for a file. Like git, only the executable bit is tracked, so this is
+/// either `EXECUTABLE_FILE_MODE` or `DEFAULT_FILE_MODE`.
+pub fn file_mode(meta: &std::fs::Metadata) -> u32 {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if meta.permissions().mode() & 0o111 != 0 {
+            return constants::EXECUTABLE_FILE_MODE;
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = meta;
+    constants::DEFAULT_FILE_MODE
+}
+
+/// Whether the executable bit of a file differs from the one committed in its node. Always
+/// false on platforms without an executable bit, so they never see mode changes.
+pub fn is_mode_modified_from_node(meta: &std::fs::Metadata, node: &FileNode) -> bool {
+    cfg!(unix) && file_mode(meta) != node.mode()
+}
+
+/// Set or clear the executable bits of a file to match a committed mode, for whoever can
+/// read the file. Does nothing on platforms without an executable bit.
+pub fn set_file_mode(path: impl AsRef<Path>, mode: u32) -> Result<(), OxenError> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let path = path.as_ref();
+        let mut permissions = metadata(path)?.permissions();
+        let current = permissions.mode();
+        let updated = if mode & 0o111 != 0 {
+            current | ((current & 0o444) >> 2)
+        } else {
+            current & !0o111
+        };
+        if updated != current {
+            permissions.set_mode(updated);
+            std::fs::set_permissions(path, permissions)?;
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = (path, mode);
+    Ok(())
+}
+
+pub fn is_modified_from_node(
+    repo: &LocalRepository,
+    path: &Path,
+    node: &FileNode,
+) -> Result<bool, OxenError> {
     // First, check if the file exists; return false if not
     if !path.exists() {
         log::debug!("is_modified_from_node found non-existant path {path:?}. Returning false");
@@ -1752,6 +1822,11 @@ pub fn is_modified_from_node(path: &Path, node: &FileNode) -> Result<bool, OxenE
         return Ok(true);
     }
 
+    // Changing the mode does not change the last modified time
+    if is_mode_modified_from_node(&meta, node) {
+        return Ok(true);
+    }
+
     // Third, check the last modified times
     let file_last_modified = FileTime::from_last_modification_time(&meta);
     let node_last_modified = util::fs::last_modified_time(
@@ -1765,7 +1840,7 @@ pub fn is_modified_from_node(path: &Path, node: &FileNode) -> Result<bool, OxenE
 
     // Finally, check the hashes
     let node_hash = node.hash().to_u128();
-    let working_hash = util::hasher::get_hash_given_metadata(path, &meta)?;
+    let working_hash = repo.hash_algorithm().hash_file_contents(path)?;
 
     if node_hash == working_hash {
         Ok(false)
@@ -1792,6 +1867,11 @@ pub fn is_modified_metadata_only(path: &Path, node: &FileNode) -> Result<bool, O
         return Ok(true);
     }
 
+    // Changing the mode does not change the last modified time
+    if is_mode_modified_from_node(&meta, node) {
+        return Ok(true);
+    }
+
     // Third, check the last modified times
     let file_last_modified = FileTime::from_last_modification_time(&meta);
     let node_last_modified = util::fs::last_modified_time(
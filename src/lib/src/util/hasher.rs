@@ -2,13 +2,107 @@ use crate::error::OxenError;
 use crate::model::metadata::generic_metadata::GenericMetadata;
 use crate::model::{ContentHashable, NewCommit};
 use crate::util;
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use std::fmt;
 use std::fs::File;
 use std::io::prelude::*;
 use std::io::BufReader;
 use std::path::Path;
+use std::str::FromStr;
 use xxhash_rust::xxh3::{xxh3_128, Xxh3};
 
+/// The algorithm used to hash file contents, recorded per repository so that
+/// it can be changed with `oxen migrate hash` without breaking old repos.
+/// Merkle tree node ids are derived from the content hashes, commit ids are not.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum HashAlgorithm {
+    #[default]
+    Xxh3,
+    /// The first 128 bits of a SHA-256 digest
+    Sha256,
+}
+
+impl HashAlgorithm {
+    pub fn hash_buffer_128bit(&self, buffer: &[u8]) -> u128 {
+        match self {
+            HashAlgorithm::Xxh3 => hash_buffer_128bit(buffer),
+            HashAlgorithm::Sha256 => truncate_sha256(Sha256::digest(buffer).as_slice()),
+        }
+    }
+
+    pub fn hash_reader(&self, reader: &mut impl Read) -> Result<u128, OxenError> {
+        let mut buffer = [0; 4096];
+        match self {
+            HashAlgorithm::Xxh3 => {
+                let mut hasher = Xxh3::new();
+                loop {
+                    let count = reader.read(&mut buffer)?;
+                    if count == 0 {
+                        break;
+                    }
+                    hasher.update(&buffer[..count]);
+                }
+                Ok(hasher.digest128())
+            }
+            HashAlgorithm::Sha256 => {
+                let mut hasher = Sha256::new();
+                loop {
+                    let count = reader.read(&mut buffer)?;
+                    if count == 0 {
+                        break;
+                    }
+                    hasher.update(&buffer[..count]);
+                }
+                Ok(truncate_sha256(hasher.finalize().as_slice()))
+            }
+        }
+    }
+
+    pub fn hash_file_contents(&self, path: &Path) -> Result<u128, OxenError> {
+        match self {
+            HashAlgorithm::Xxh3 => u128_hash_file_contents(path),
+            HashAlgorithm::Sha256 => {
+                let file = File::open(path).map_err(|err| {
+                    OxenError::basic_str(format!("Could not open file {:?} due to {:?}", path, err))
+                })?;
+                self.hash_reader(&mut BufReader::new(file))
+            }
+        }
+    }
+}
+
+fn truncate_sha256(digest: &[u8]) -> u128 {
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&digest[..16]);
+    u128::from_be_bytes(bytes)
+}
+
+impl fmt::Display for HashAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HashAlgorithm::Xxh3 => write!(f, "xxh3"),
+            HashAlgorithm::Sha256 => write!(f, "sha256"),
+        }
+    }
+}
+
+impl FromStr for HashAlgorithm {
+    type Err = OxenError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "xxh3" => Ok(HashAlgorithm::Xxh3),
+            "sha256" => Ok(HashAlgorithm::Sha256),
+            _ => Err(OxenError::basic_str(format!(
+                "Unknown hash algorithm '{}', expected one of: xxh3, sha256",
+                s
+            ))),
+        }
+    }
+}
+
 pub fn hash_buffer(buffer: &[u8]) -> String {
     let val = xxh3_128(buffer);
     format!("{val:x}")
@@ -102,6 +196,19 @@ pub fn get_combined_hash(
     }
 }
 
+/// Mix the mode of a file into its combined hash, so changing only the executable bit
+/// changes the tree. Regular files keep their combined hash, which keeps the hashes of
+/// trees committed before modes were tracked.
+pub fn get_combined_hash_with_mode(combined_hash: u128, mode: u32) -> u128 {
+    if mode & 0o111 == 0 {
+        return combined_hash;
+    }
+    let mut hasher = Xxh3::new();
+    hasher.update(&combined_hash.to_le_bytes());
+    hasher.update(&mode.to_le_bytes());
+    hasher.digest128()
+}
+
 pub fn maybe_get_metadata_hash(
     oxen_metadata: &Option<GenericMetadata>,
 ) -> Result<Option<u128>, OxenError> {
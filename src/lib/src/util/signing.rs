@@ -0,0 +1,124 @@
+//! Sign and verify ref data with an ed25519 server key
+//!
+//! The server signs each branch head together with the root hash of its tree. A client
+//! that has pinned the server's public key can then tell when a mirror or proxy hands
+//! back a branch that the server never published.
+//!
+
+use std::path::{Path, PathBuf};
+
+use ring::rand::SystemRandom;
+use ring::signature::{Ed25519KeyPair, KeyPair, UnparsedPublicKey, ED25519};
+
+use crate::constants::SIGNING_KEY_FILE;
+use crate::error::OxenError;
+use crate::util;
+
+pub struct SigningKey {
+    key_pair: Ed25519KeyPair,
+}
+
+impl SigningKey {
+    fn key_path(sync_dir: &Path) -> PathBuf {
+        util::fs::oxen_hidden_dir(sync_dir).join(SIGNING_KEY_FILE)
+    }
+
+    /// Load the server key from the sync dir, None if one has not been generated
+    pub fn load(sync_dir: &Path) -> Result<Option<SigningKey>, OxenError> {
+        let path = SigningKey::key_path(sync_dir);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let pkcs8 = hex::decode(util::fs::read_from_path(&path)?.trim()).map_err(|err| {
+            OxenError::basic_str(format!("Invalid signing key in {path:?}: {err}"))
+        })?;
+        let key_pair = Ed25519KeyPair::from_pkcs8(&pkcs8).map_err(|err| {
+            OxenError::basic_str(format!("Invalid signing key in {path:?}: {err}"))
+        })?;
+        Ok(Some(SigningKey { key_pair }))
+    }
+
+    /// Load the server key, generating and saving a new one if there is none yet
+    pub fn load_or_create(sync_dir: &Path) -> Result<SigningKey, OxenError> {
+        if let Some(key) = SigningKey::load(sync_dir)? {
+            return Ok(key);
+        }
+
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new())
+            .map_err(|_| OxenError::basic_str("Could not generate signing key"))?;
+        util::fs::create_dir_all(util::fs::oxen_hidden_dir(sync_dir))?;
+        util::fs::write_to_path(SigningKey::key_path(sync_dir), hex::encode(pkcs8.as_ref()))?;
+        SigningKey::load(sync_dir)?.ok_or(OxenError::basic_str("Could not save signing key"))
+    }
+
+    /// Hex encoded public key, what clients pin with `oxen config --signing-key`
+    pub fn public_key(&self) -> String {
+        hex::encode(self.key_pair.public_key().as_ref())
+    }
+
+    /// Hex encoded signature of the message
+    pub fn sign(&self, message: impl AsRef<str>) -> String {
+        hex::encode(self.key_pair.sign(message.as_ref().as_bytes()).as_ref())
+    }
+}
+
+/// The message signed for a branch, binding the repo, branch, head commit and the root
+/// hash of the head commit's tree
+pub fn ref_message(
+    namespace: impl AsRef<str>,
+    repo_name: impl AsRef<str>,
+    branch_name: impl AsRef<str>,
+    commit_id: impl AsRef<str>,
+    root_hash: impl AsRef<str>,
+) -> String {
+    format!(
+        "oxen-ref-v1\n{}/{}\n{}\n{}\n{}",
+        namespace.as_ref(),
+        repo_name.as_ref(),
+        branch_name.as_ref(),
+        commit_id.as_ref(),
+        root_hash.as_ref()
+    )
+}
+
+/// Check a hex encoded signature of the message against a hex encoded public key
+pub fn verify(
+    public_key: impl AsRef<str>,
+    message: impl AsRef<str>,
+    signature: impl AsRef<str>,
+) -> Result<(), OxenError> {
+    let public_key = hex::decode(public_key.as_ref().trim())
+        .map_err(|err| OxenError::basic_str(format!("Invalid public key: {err}")))?;
+    let signature = hex::decode(signature.as_ref())
+        .map_err(|err| OxenError::basic_str(format!("Invalid signature: {err}")))?;
+    UnparsedPublicKey::new(&ED25519, public_key)
+        .verify(message.as_ref().as_bytes(), &signature)
+        .map_err(|_| OxenError::basic_str("Signature does not match the pinned public key"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::OxenError;
+    use crate::test;
+    use crate::util::signing::{self, SigningKey};
+
+    #[test]
+    fn test_sign_and_verify_ref() -> Result<(), OxenError> {
+        test::run_empty_dir_test(|dir| {
+            assert!(SigningKey::load(dir)?.is_none());
+            let key = SigningKey::load_or_create(dir)?;
+            // Loading again returns the same key
+            let public_key = SigningKey::load_or_create(dir)?.public_key();
+            assert_eq!(key.public_key(), public_key);
+
+            let message = signing::ref_message("ox", "data", "main", "abc", "def");
+            let signature = key.sign(&message);
+            signing::verify(&public_key, &message, &signature)?;
+
+            let tampered = signing::ref_message("ox", "data", "main", "abc", "123");
+            assert!(signing::verify(&public_key, tampered, &signature).is_err());
+
+            Ok(())
+        })
+    }
+}
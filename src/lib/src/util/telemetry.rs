@@ -0,0 +1,177 @@
+//! Opt-in anonymous usage telemetry
+//!
+//! Nothing is recorded until the user runs `oxen telemetry on`. Each event holds the
+//! command name, how long it took, whether it succeeded and rough size buckets for the
+//! repository it ran in. Paths, file names, remotes, user names and data are never sent.
+//!
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::TelemetryConfig;
+use crate::constants::OXEN_VERSION;
+use crate::error::OxenError;
+use crate::model::LocalRepository;
+use crate::repositories;
+
+/// Telemetry must never hold up a command for long
+const SEND_TIMEOUT: Duration = Duration::from_secs(2);
+
+const FILE_COUNT_BUCKETS: [(u64, &str); 6] = [
+    (0, "0"),
+    (100, "1-100"),
+    (1_000, "101-1k"),
+    (10_000, "1k-10k"),
+    (100_000, "10k-100k"),
+    (1_000_000, "100k-1M"),
+];
+
+const BYTE_SIZE_BUCKETS: [(u64, &str); 6] = [
+    (0, "0"),
+    (1 << 20, "<1MB"),
+    (100 << 20, "1MB-100MB"),
+    (1 << 30, "100MB-1GB"),
+    (10 << 30, "1GB-10GB"),
+    (100 << 30, "10GB-100GB"),
+];
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TelemetryEvent {
+    pub install_id: String,
+    pub command: String,
+    pub duration_ms: u64,
+    pub success: bool,
+    pub oxen_version: String,
+    pub os: String,
+    /// Bucketed number of files at HEAD, when run inside a repository
+    pub repo_files: Option<String>,
+    /// Bucketed size of the files at HEAD, when run inside a repository
+    pub repo_size: Option<String>,
+}
+
+pub fn file_count_bucket(num_files: u64) -> &'static str {
+    FILE_COUNT_BUCKETS
+        .iter()
+        .find(|(max, _)| num_files <= *max)
+        .map(|(_, bucket)| *bucket)
+        .unwrap_or("1M+")
+}
+
+pub fn byte_size_bucket(num_bytes: u64) -> &'static str {
+    BYTE_SIZE_BUCKETS
+        .iter()
+        .find(|(max, _)| num_bytes <= *max)
+        .map(|(_, bucket)| *bucket)
+        .unwrap_or("100GB+")
+}
+
+/// Build the event for a command, reading the repository scale from the HEAD commit
+pub fn build_event(
+    config: &TelemetryConfig,
+    command: impl AsRef<str>,
+    duration: Duration,
+    success: bool,
+    repo: Option<&LocalRepository>,
+) -> TelemetryEvent {
+    let (repo_files, repo_size) = match repo.map(repo_scale) {
+        Some(Ok(Some((num_files, num_bytes)))) => (
+            Some(file_count_bucket(num_files).to_string()),
+            Some(byte_size_bucket(num_bytes).to_string()),
+        ),
+        _ => (None, None),
+    };
+
+    TelemetryEvent {
+        install_id: config.install_id.clone().unwrap_or_default(),
+        command: command.as_ref().to_string(),
+        duration_ms: duration.as_millis() as u64,
+        success,
+        oxen_version: OXEN_VERSION.to_string(),
+        os: std::env::consts::OS.to_string(),
+        repo_files,
+        repo_size,
+    }
+}
+
+fn repo_scale(repo: &LocalRepository) -> Result<Option<(u64, u64)>, OxenError> {
+    let Some(commit) = repositories::commits::head_commit_maybe(repo)? else {
+        return Ok(None);
+    };
+    let Some(root) = repositories::tree::get_dir_without_children(repo, &commit, "")? else {
+        return Ok(None);
+    };
+    let dir = root.dir()?;
+    Ok(Some((dir.num_files(), dir.num_bytes())))
+}
+
+/// Record a finished command if the user has opted in. Failures are only logged,
+/// telemetry should never change the outcome of a command.
+pub async fn record(command: impl AsRef<str>, duration: Duration, success: bool) {
+    let config = match TelemetryConfig::get() {
+        Ok(config) if config.enabled => config,
+        Ok(_) => return,
+        Err(err) => {
+            log::debug!("could not read telemetry config: {err}");
+            return;
+        }
+    };
+
+    let repo = LocalRepository::from_current_dir().ok();
+    let event = build_event(&config, command, duration, success, repo.as_ref());
+    if let Err(err) = send(&config.endpoint(), &event).await {
+        log::debug!("could not send telemetry: {err}");
+    }
+}
+
+async fn send(endpoint: &str, event: &TelemetryEvent) -> Result<(), OxenError> {
+    let client = reqwest::Client::builder().timeout(SEND_TIMEOUT).build()?;
+    client.post(endpoint).json(event).send().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::config::TelemetryConfig;
+    use crate::error::OxenError;
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+    use crate::util::telemetry;
+
+    #[test]
+    fn test_size_buckets() {
+        assert_eq!(telemetry::file_count_bucket(0), "0");
+        assert_eq!(telemetry::file_count_bucket(100), "1-100");
+        assert_eq!(telemetry::file_count_bucket(5_000), "1k-10k");
+        assert_eq!(telemetry::file_count_bucket(2_000_000), "1M+");
+        assert_eq!(telemetry::byte_size_bucket(512), "<1MB");
+        assert_eq!(telemetry::byte_size_bucket(200 << 30), "100GB+");
+    }
+
+    #[tokio::test]
+    async fn test_event_does_not_contain_paths() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            let file = repo.path.join("secret_name.txt");
+            util::fs::write_to_path(&file, "hello")?;
+            repositories::add(&repo, &file).await?;
+            repositories::commit(&repo, "Add file")?;
+
+            let mut config = TelemetryConfig::default();
+            config.enable(None);
+            let event =
+                telemetry::build_event(&config, "add", Duration::from_millis(5), true, Some(&repo));
+            assert_eq!(event.repo_files, Some("1-100".to_string()));
+            assert_eq!(event.repo_size, Some("<1MB".to_string()));
+
+            let json = serde_json::to_string(&event)?;
+            assert!(!json.contains("secret_name"));
+            assert!(!json.contains(&repo.path.to_string_lossy().to_string()));
+
+            Ok(())
+        })
+        .await
+    }
+}
@@ -1,37 +1,50 @@
 //! Views are the data structures that are returned by the API endpoints.
 //!
 
+pub mod activity;
+pub mod append;
 pub mod branch;
 pub mod commit;
 pub mod compare;
 pub mod data_frames;
 pub mod data_type_count;
+pub mod dedup;
 pub mod diff;
+pub mod embeddings;
 pub mod entries;
 pub mod entry_metadata;
 pub mod file_metadata;
 pub mod fork;
 pub mod health;
 pub mod http;
+pub mod job;
 pub mod json_data_frame;
 pub mod json_data_frame_view;
 pub mod merge;
+pub mod merge_proposals;
 pub mod message;
 pub mod mime_type_count;
+pub mod mirror;
 pub mod namespace;
 pub mod notebook;
 pub mod oxen_response;
 pub mod oxen_version;
 pub mod pagination;
+pub mod remote;
 pub mod remote_staged_status;
+pub mod repo_status;
 pub mod repository;
 pub mod revision;
 pub mod schema;
 pub mod sql_parse_error;
 pub mod status_message;
 pub mod tabular_diff_view;
+pub mod tag;
 pub mod tree;
+pub mod uploads;
+pub mod user;
 pub mod versions;
+pub mod webhook;
 pub mod workspaces;
 
 pub use crate::view::compare::CompareEntriesResponse;
@@ -60,34 +73,63 @@ pub use crate::view::entries::{
     PaginatedEntries, PaginatedEntryVersions, PaginatedEntryVersionsResponse, RemoteEntryResponse,
 };
 
+pub use crate::view::activity::PaginatedActivity;
+pub use crate::view::append::AppendResponse;
+
 pub use crate::view::commit::{
-    CommitResponse, CommitStatsResponse, ListCommitResponse, PaginatedCommits, RootCommitResponse,
+    CacheJobResponse, CacheJobsResponse, CommitResponse, CommitStatsResponse, ListCommitResponse,
+    PaginatedCommits, RootCommitResponse,
 };
 
 pub use crate::view::branch::{
-    BranchLockResponse, BranchNew, BranchNewFromBranchName, BranchNewFromCommitId,
-    BranchRemoteMerge, BranchResponse, BranchUpdate, ListBranchesResponse,
+    BranchCompareResponse, BranchLockResponse, BranchNew, BranchNewFromBranchName,
+    BranchNewFromCommitId, BranchProtectionRuleResponse, BranchRemoteMerge, BranchResponse,
+    BranchUpdate, ListBranchProtectionRulesResponse, ListBranchesResponse, RefSignature,
 };
 
 pub use crate::view::revision::ParseResourceResponse;
 
 pub use crate::view::compare::CompareResult;
 
+pub use crate::view::dedup::DedupReportResponse;
+pub use crate::view::embeddings::EmbeddingNeighborsResponse;
+
 pub use crate::view::entry_metadata::MetadataEntryResponse;
 
 pub use crate::view::pagination::Pagination;
 
-pub use crate::view::health::HealthResponse;
+pub use crate::view::health::{HealthResponse, MerkleCacheMetrics, MetricsResponse};
 pub use crate::view::oxen_response::OxenResponse;
 
+pub use crate::view::remote::{ListRemotesResponse, RemoteBranchStatus, RemoteShowResponse};
+
 pub use crate::view::remote_staged_status::{
     ListStagedFileModResponseDF, ListStagedFileModResponseRaw, RemoteStagedStatus,
     RemoteStagedStatusResponse, StagedFileModResponse,
 };
 
+pub use crate::view::repo_status::{RepoStatus, RepoStatusResponse};
+
 pub use crate::view::sql_parse_error::SQLParseError;
 
 pub use crate::view::tabular_diff_view::TabularDiffView;
+
+pub use crate::view::tag::{ListTagsResponse, TagResponse};
+pub use crate::view::uploads::{NewUploadSession, UploadSessionResponse};
+
+pub use crate::view::webhook::{ListWebhooksResponse, WebhookNew, WebhookResponse};
+
+pub use crate::view::job::{
+    JobNew, JobResponse, JobRunResponse, JobStatus, ListJobRunsResponse, ListJobsResponse,
+};
+
+pub use crate::view::merge_proposals::{
+    ListMergeProposalsResponse, MergeProposalApprovalNew, MergeProposalChecksResponse,
+    MergeProposalCommentNew, MergeProposalCommentResponse, MergeProposalFilesResponse,
+    MergeProposalNew, MergeProposalResponse, MergeProposalThreadsResponse,
+};
+
+pub use crate::view::mirror::{ListMirrorsResponse, MirrorResponse};
 pub use crate::view::workspaces::WorkspaceResponseView;
 
 pub use crate::view::tree::merkle_hashes::MerkleHashesResponse;
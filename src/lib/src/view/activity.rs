@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Pagination, StatusMessage};
+use crate::model::ActivityEvent;
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct PaginatedActivity {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub activity: Vec<ActivityEvent>,
+    #[serde(flatten)]
+    pub pagination: Pagination,
+}
+
+impl PaginatedActivity {
+    pub fn success(activity: Vec<ActivityEvent>, pagination: Pagination) -> PaginatedActivity {
+        PaginatedActivity {
+            status: StatusMessage::resource_found(),
+            activity,
+            pagination,
+        }
+    }
+}
@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+use super::StatusMessage;
+use crate::model::{AppendBuffer, Commit};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct AppendResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    /// Number of rows in the request
+    pub num_rows: usize,
+    /// The rows still waiting to be committed, None if they were all committed
+    pub buffer: Option<AppendBuffer>,
+    /// The commit the buffered rows were committed in, if this request filled the buffer
+    pub commit: Option<Commit>,
+}
@@ -1,4 +1,5 @@
-use crate::model::Branch;
+use crate::config::BranchProtectionRule;
+use crate::model::{Branch, BranchCompare};
 use serde::{Deserialize, Serialize};
 
 use super::StatusMessage;
@@ -8,6 +9,23 @@ pub struct BranchResponse {
     #[serde(flatten)]
     pub status: StatusMessage,
     pub branch: Branch,
+    /// Set when the server has a signing key, see `util::signing`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<RefSignature>,
+}
+
+/// Server signature over a branch head and the root hash of its tree
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct RefSignature {
+    pub root_hash: String,
+    pub signature: String,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct BranchCompareResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub compare: BranchCompare,
 }
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -51,6 +69,9 @@ pub struct BranchNewFromCommitId {
 #[derive(Deserialize, Serialize, Debug)]
 pub struct BranchUpdate {
     pub commit_id: String,
+    /// Only move the branch if it still points here, otherwise fail as non-fast-forward
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_commit_id: Option<String>,
 }
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -65,3 +86,17 @@ pub struct ListBranchesResponse {
     pub status: StatusMessage,
     pub branches: Vec<Branch>,
 }
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BranchProtectionRuleResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub rule: BranchProtectionRule,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ListBranchProtectionRulesResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub rules: Vec<BranchProtectionRule>,
+}
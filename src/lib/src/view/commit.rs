@@ -1,3 +1,4 @@
+use crate::core::cache::CacheJobRecord;
 use crate::model::{Commit, CommitStats};
 use serde::{Deserialize, Serialize};
 
@@ -10,6 +11,21 @@ pub struct CommitResponse {
     pub commit: Commit,
 }
 
+#[derive(Deserialize, Serialize, Debug)]
+pub struct CacheJobsResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub commit_id: String,
+    pub jobs: Vec<CacheJobRecord>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct CacheJobResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub job: CacheJobRecord,
+}
+
 #[derive(Deserialize, Serialize, Debug)]
 pub struct UploadCommitResponse {
     #[serde(flatten)]
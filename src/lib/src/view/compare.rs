@@ -184,6 +184,9 @@ pub struct CompareTabular {
     pub keys: Option<Vec<TabularCompareFieldBody>>,
     pub targets: Option<Vec<TabularCompareTargetBody>>,
     pub display: Option<Vec<TabularCompareTargetBody>>,
+    /// Key columns and `old → new` values of the cells that changed in modified rows
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modified_cells: Option<JsonDataFrame>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -522,6 +525,7 @@ impl CompareTabular {
             targets: Some(with_df.targets.clone()),
             display: Some(with_df.display.clone()),
             source_schemas,
+            modified_cells: None,
         }
     }
     pub fn from_with_df(with_df: &CompareTabularWithDF) -> CompareTabular {
@@ -533,6 +537,7 @@ impl CompareTabular {
             targets: Some(with_df.targets.clone()),
             display: Some(with_df.display.clone()),
             source_schemas: with_df.source_schemas.clone(),
+            modified_cells: None,
         }
     }
 }
@@ -589,6 +594,7 @@ impl From<TabularDiff> for CompareTabular {
                 left: diff.summary.schemas.left.clone(),
                 right: diff.summary.schemas.right.clone(),
             },
+            modified_cells: Some(JsonDataFrame::from_df(&mut diff.modified_cells.clone())),
         }
     }
 }
@@ -9,6 +9,12 @@ pub struct DataFramePayload {
     pub is_indexed: bool,
 }
 
+/// Body of a sql query against a data frame at a revision, the data frame is the table `df`
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct DataFrameSqlRequest {
+    pub sql: String,
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct DataFrameColumnChange {
     pub operation: String,
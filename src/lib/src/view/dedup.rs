@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+use super::StatusMessage;
+use crate::repositories::dedup::DedupReport;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DedupReportResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub report: DedupReport,
+}
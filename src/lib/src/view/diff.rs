@@ -3,7 +3,11 @@ use std::path::PathBuf;
 use serde::{Deserialize, Serialize};
 
 use crate::model::diff::diff_entry_status::DiffEntryStatus;
+use crate::model::diff::{
+    BinaryDiff, DiffResult, DiffStat, DirChangesSummary, StructuredDiff, TextDiff,
+};
 
+use super::compare::CompareTabular;
 use super::StatusMessage;
 #[derive(Deserialize, Serialize, Debug)]
 pub struct DirTreeDiffResponse {
@@ -26,3 +30,45 @@ pub struct DirDiffStatus {
     pub name: PathBuf,
     pub status: DiffEntryStatus,
 }
+
+/// A file diff as printed by `oxen --output json diff`, tagged by the kind of diff
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DiffResultView {
+    Tabular(CompareTabular),
+    Text(TextDiff),
+    Binary(BinaryDiff),
+    Structured(StructuredDiff),
+}
+
+impl From<DiffResult> for DiffResultView {
+    fn from(result: DiffResult) -> Self {
+        match result {
+            DiffResult::Tabular(diff) => DiffResultView::Tabular(CompareTabular::from(diff)),
+            DiffResult::Text(diff) => DiffResultView::Text(diff),
+            DiffResult::Binary(diff) => DiffResultView::Binary(diff),
+            DiffResult::Structured(diff) => DiffResultView::Structured(diff),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DiffResultsResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub diffs: Vec<DiffResultView>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DiffStatResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub stat: DiffStat,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DirChangesResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub summary: DirChangesSummary,
+}
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+use super::StatusMessage;
+use crate::repositories::embeddings::EmbeddingNeighbors;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EmbeddingNeighborsResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub result: EmbeddingNeighbors,
+}
@@ -2,10 +2,17 @@ use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::str::FromStr;
 
-#[derive(Deserialize)]
+use crate::opts::ForkMode;
+
+#[derive(Serialize, Deserialize)]
 pub struct ForkRequest {
     pub namespace: String,
     pub new_repo_name: Option<String>,
+    #[serde(default)]
+    pub mode: ForkMode,
+    /// Continue an unfinished fork into the destination instead of starting a new one
+    #[serde(default)]
+    pub resume: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -14,6 +21,7 @@ pub enum ForkStatus {
     Counting(u32),
     InProgress(f32),
     Complete,
+    Cancelled,
     Failed(String),
 }
 
@@ -22,12 +30,19 @@ pub struct ForkStatusFile {
     pub status: ForkStatus,
     pub progress: Option<f32>,
     pub error: Option<String>,
+    #[serde(default)]
+    pub copied_bytes: Option<u64>,
+    #[serde(default)]
+    pub total_bytes: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ForkStartResponse {
     pub repository: String,
     pub fork_status: String,
+    /// Where to poll the status of the fork, set by the server
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status_url: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -36,6 +51,10 @@ pub struct ForkStatusResponse {
     pub status: String,
     pub progress: Option<f32>,
     pub error: Option<String>,
+    #[serde(default)]
+    pub copied_bytes: Option<u64>,
+    #[serde(default)]
+    pub total_bytes: Option<u64>,
 }
 
 impl From<ForkStatus> for ForkStatusFile {
@@ -45,26 +64,43 @@ impl From<ForkStatus> for ForkStatusFile {
                 status: ForkStatus::Counting(c),
                 progress: Some(c as f32),
                 error: None,
+                copied_bytes: None,
+                total_bytes: None,
             },
             ForkStatus::InProgress(p) => ForkStatusFile {
                 status: ForkStatus::InProgress(p),
                 progress: Some(p),
                 error: None,
+                copied_bytes: None,
+                total_bytes: None,
             },
             ForkStatus::Complete => ForkStatusFile {
                 status: ForkStatus::Complete,
                 progress: None,
                 error: None,
+                copied_bytes: None,
+                total_bytes: None,
+            },
+            ForkStatus::Cancelled => ForkStatusFile {
+                status: ForkStatus::Cancelled,
+                progress: None,
+                error: None,
+                copied_bytes: None,
+                total_bytes: None,
             },
             ForkStatus::Failed(e) => ForkStatusFile {
                 status: ForkStatus::Failed(e.clone()),
                 progress: None,
                 error: Some(e),
+                copied_bytes: None,
+                total_bytes: None,
             },
             ForkStatus::Started => ForkStatusFile {
                 status: ForkStatus::Started,
                 progress: None,
                 error: None,
+                copied_bytes: None,
+                total_bytes: None,
             },
         }
     }
@@ -77,6 +113,7 @@ impl fmt::Display for ForkStatus {
             ForkStatus::Counting(_) => write!(f, "counting"),
             ForkStatus::InProgress(_) => write!(f, "in_progress"),
             ForkStatus::Complete => write!(f, "complete"),
+            ForkStatus::Cancelled => write!(f, "cancelled"),
             ForkStatus::Failed(_) => write!(f, "failed"),
         }
     }
@@ -90,6 +127,7 @@ impl FromStr for ForkStatus {
             "counting" => Ok(ForkStatus::Counting(0)),
             "in_progress" => Ok(ForkStatus::InProgress(0.0)),
             "complete" => Ok(ForkStatus::Complete),
+            "cancelled" => Ok(ForkStatus::Cancelled),
             "failed" => Ok(ForkStatus::Failed(String::new())),
             "started" => Ok(ForkStatus::Started),
             _ => Err(format!("Invalid status: {}", s)),
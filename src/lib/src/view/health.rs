@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 use super::StatusMessage;
+use crate::model::merkle_tree::merkle_tree_node_cache::CacheStats;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct HealthResponse {
@@ -16,3 +17,21 @@ pub struct DiskUsage {
     pub free_gb: f64,
     pub percent_used: f64,
 }
+
+/// Stats of the running server, served at `/api/metrics`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MetricsResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub merkle_cache: MerkleCacheMetrics,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MerkleCacheMetrics {
+    pub enabled: bool,
+    /// Number of repositories with a cache
+    pub repositories: usize,
+    /// Added up over the caches of all repositories
+    #[serde(flatten)]
+    pub stats: CacheStats,
+}
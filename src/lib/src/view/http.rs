@@ -7,6 +7,7 @@ pub const MSG_RESOURCE_DELETED: &str = "resource_deleted";
 pub const MSG_RESOURCE_FOUND: &str = "resource_found";
 pub const MSG_RESOURCE_NOT_FOUND: &str = "resource_not_found";
 pub const MSG_CONFLICT: &str = "conflict";
+pub const MSG_NON_FAST_FORWARD: &str = "non_fast_forward";
 pub const MSG_CONTENT_IS_INVALID: &str = "content_is_invalid";
 pub const MSG_BAD_REQUEST: &str = "bad_request";
 pub const MSG_RESOURCE_ALREADY_EXISTS: &str = "resource_already_exists";
@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+use super::StatusMessage;
+use crate::model::{Job, JobAction, JobRun};
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct JobNew {
+    pub name: String,
+    /// Cron expression in UTC, ie `0 3 * * *` to run every day at 03:00
+    pub schedule: String,
+    pub action: JobAction,
+    /// Defaults to true
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+}
+
+/// A job with when it runs next and how its latest run went
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct JobStatus {
+    #[serde(flatten)]
+    pub job: Job,
+    /// None if the job is disabled
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub next_run_at: Option<OffsetDateTime>,
+    pub last_run: Option<JobRun>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct JobResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub job: JobStatus,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct ListJobsResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub jobs: Vec<JobStatus>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct JobRunResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub run: JobRun,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct ListJobRunsResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    /// Newest first
+    pub runs: Vec<JobRun>,
+}
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 
+use crate::model::merge_conflict::RowMergeConflict;
 use crate::model::Commit;
 
 use super::StatusMessage;
@@ -31,9 +32,38 @@ pub struct MergeResult {
     pub merge: Commit,
 }
 
+/// Request to merge `head` into `base` on the server
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MergeBranches {
+    pub base: String,
+    pub head: String,
+    /// Wait for earlier merges into `base` to finish first. Merges into protected
+    /// branches are always queued.
+    #[serde(default)]
+    pub queue: bool,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct MergeSuccessResponse {
     #[serde(flatten)]
     pub status: StatusMessage,
     pub commits: MergeResult,
 }
+
+/// Returned with a 409 when a file was changed on the branch after the revision an edit
+/// was based on
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FileConflictResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub path: String,
+    /// The revision sent in the `oxen-based-on` header
+    pub based_on: String,
+    /// The last commit that changed the file on the branch, None if it was removed
+    pub current_revision: Option<String>,
+    /// The head of the branch the edit was written to
+    pub head_commit_id: String,
+    /// Rows that could not be merged when retrying a tabular file with merge
+    #[serde(default)]
+    pub row_conflicts: Vec<RowMergeConflict>,
+}
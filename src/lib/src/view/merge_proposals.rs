@@ -0,0 +1,82 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use super::{Pagination, StatusMessage};
+use crate::model::diff::AddRemoveModifyCounts;
+use crate::model::{
+    DiffEntry, MergeProposal, MergeProposalChecks, MergeProposalComment, MergeProposalThread, User,
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct MergeProposalNew {
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    /// Branch the changes are merged into
+    pub base: String,
+    /// Branch with the changes
+    pub head: String,
+    pub author: User,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct MergeProposalCommentNew {
+    pub author: User,
+    pub body: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub row: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct MergeProposalApprovalNew {
+    pub author: User,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct MergeProposalResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub proposal: MergeProposal,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct ListMergeProposalsResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub proposals: Vec<MergeProposal>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct MergeProposalChecksResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub checks: MergeProposalChecks,
+}
+
+/// The files the head branch changed since it split from the base branch
+#[derive(Deserialize, Serialize, Debug)]
+pub struct MergeProposalFilesResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub counts: AddRemoveModifyCounts,
+    pub entries: Vec<DiffEntry>,
+    #[serde(flatten)]
+    pub pagination: Pagination,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct MergeProposalThreadsResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub threads: Vec<MergeProposalThread>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct MergeProposalCommentResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub comment: MergeProposalComment,
+}
@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+
+use super::StatusMessage;
+use crate::model::MirrorStatus;
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct MirrorResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub mirror: MirrorStatus,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct ListMirrorsResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub mirrors: Vec<MirrorStatus>,
+}
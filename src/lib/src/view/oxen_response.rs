@@ -32,6 +32,14 @@ impl OxenResponse {
         }
     }
 
+    pub fn error_type(&self) -> Option<&str> {
+        self.error.as_ref().map(|err| err.error_type.as_str())
+    }
+
+    pub fn error_detail(&self) -> Option<&str> {
+        self.error.as_ref().and_then(|err| err.detail.as_deref())
+    }
+
     pub fn error_or_msg(&self) -> String {
         match self.error.to_owned() {
             Some(err) => err.title,
@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+
+use super::{RepositoryDataTypesView, StatusMessage};
+use crate::model::{Commit, Remote};
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct ListRemotesResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub remotes: Vec<Remote>,
+}
+
+/// What `oxen remote show` found out about a remote
+#[derive(Deserialize, Serialize, Debug)]
+pub struct RemoteShowResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub remote: Remote,
+    pub server_version: Option<String>,
+    /// None when the repository does not exist on the remote
+    pub repository: Option<RepositoryDataTypesView>,
+    pub head_commit: Option<Commit>,
+    pub branches: Vec<RemoteBranchStatus>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct RemoteBranchStatus {
+    pub name: String,
+    pub commit_id: String,
+    /// "up to date", "differs from local" or "not tracked locally"
+    pub local_status: String,
+}
@@ -4,8 +4,8 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     model::{
-        Commit, LocalRepository, MetadataEntry, ModEntry, StagedData, StagedEntry,
-        StagedEntryStatus, SummarizedStagedDirStats,
+        diff::WorkspaceChanges, Commit, LocalRepository, MetadataEntry, ModEntry, StagedData,
+        StagedEntry, StagedEntryStatus, SummarizedStagedDirStats,
     },
     util,
 };
@@ -66,6 +66,9 @@ pub struct RemoteStagedStatusResponse {
     #[serde(flatten)]
     pub status: StatusMessage,
     pub staged: RemoteStagedStatus,
+    /// Every pending file with the rows edited in data frames, not paginated
+    #[serde(default)]
+    pub changes: Option<WorkspaceChanges>,
 }
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -0,0 +1,162 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::{
+    Branch, Commit, EntryMergeConflict, StagedData, StagedEntryStatus, SummarizedStagedDirStats,
+};
+
+use super::StatusMessage;
+
+/// The status of a local repository, as printed by `oxen --output json status`
+#[derive(Deserialize, Serialize, Debug)]
+pub struct RepoStatus {
+    pub branch: Option<Branch>,
+    pub head_commit: Option<Commit>,
+    pub staged_dirs: SummarizedStagedDirStats,
+    pub staged_files: Vec<StagedFileStatus>,
+    pub moved_files: Vec<MovedFile>,
+    pub modified_files: Vec<PathBuf>,
+    pub removed_files: Vec<PathBuf>,
+    pub untracked_dirs: Vec<UntrackedDir>,
+    pub untracked_files: Vec<PathBuf>,
+    pub merge_conflicts: Vec<EntryMergeConflict>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct StagedFileStatus {
+    pub path: PathBuf,
+    pub status: StagedEntryStatus,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct MovedFile {
+    pub from: PathBuf,
+    pub to: PathBuf,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct UntrackedDir {
+    pub path: PathBuf,
+    pub num_files: usize,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct RepoStatusResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub repo_status: RepoStatus,
+}
+
+impl RepoStatus {
+    /// Paths are sorted so the output is stable between runs
+    pub fn from_staged(
+        staged: &StagedData,
+        branch: Option<Branch>,
+        head_commit: Option<Commit>,
+    ) -> RepoStatus {
+        let mut staged_files: Vec<StagedFileStatus> = staged
+            .staged_files
+            .iter()
+            .map(|(path, entry)| StagedFileStatus {
+                path: path.clone(),
+                status: entry.status.clone(),
+            })
+            .collect();
+        staged_files.sort_by(|a, b| a.path.cmp(&b.path));
+
+        let mut moved_files: Vec<MovedFile> = staged
+            .moved_files
+            .iter()
+            .map(|(to, from, _hash)| MovedFile {
+                from: from.clone(),
+                to: to.clone(),
+            })
+            .collect();
+        moved_files.sort_by(|a, b| a.to.cmp(&b.to));
+
+        let mut modified_files: Vec<PathBuf> = staged.modified_files.iter().cloned().collect();
+        modified_files.sort();
+        let mut removed_files: Vec<PathBuf> = staged.removed_files.iter().cloned().collect();
+        removed_files.sort();
+
+        let mut untracked_dirs: Vec<UntrackedDir> = staged
+            .untracked_dirs
+            .iter()
+            .map(|(path, num_files)| UntrackedDir {
+                path: path.clone(),
+                num_files: *num_files,
+            })
+            .collect();
+        untracked_dirs.sort_by(|a, b| a.path.cmp(&b.path));
+        let mut untracked_files = staged.untracked_files.clone();
+        untracked_files.sort();
+
+        RepoStatus {
+            branch,
+            head_commit,
+            staged_dirs: staged.staged_dirs.clone(),
+            staged_files,
+            moved_files,
+            modified_files,
+            removed_files,
+            untracked_dirs,
+            untracked_files,
+            merge_conflicts: staged.merge_conflicts.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::model::{StagedData, StagedEntry, StagedEntryStatus};
+
+    use super::RepoStatus;
+
+    #[test]
+    fn test_from_staged_sorts_paths() {
+        let mut staged = StagedData::empty();
+        staged.staged_files.insert(
+            PathBuf::from("b.txt"),
+            StagedEntry::empty_status(StagedEntryStatus::Modified),
+        );
+        staged.staged_files.insert(
+            PathBuf::from("a.txt"),
+            StagedEntry::empty_status(StagedEntryStatus::Added),
+        );
+        staged.modified_files.insert(PathBuf::from("z.csv"));
+        staged.modified_files.insert(PathBuf::from("y.csv"));
+        staged.moved_files.push((
+            PathBuf::from("new/d.txt"),
+            PathBuf::from("old/d.txt"),
+            "hash".to_string(),
+        ));
+        staged.untracked_dirs.push((PathBuf::from("images"), 3));
+        staged.untracked_dirs.push((PathBuf::from("audio"), 2));
+        staged.untracked_files = vec![PathBuf::from("c.txt"), PathBuf::from("b.txt")];
+
+        let status = RepoStatus::from_staged(&staged, None, None);
+
+        assert_eq!(status.staged_files.len(), 2);
+        assert_eq!(status.staged_files[0].path, PathBuf::from("a.txt"));
+        assert_eq!(status.staged_files[0].status, StagedEntryStatus::Added);
+        assert_eq!(status.staged_files[1].path, PathBuf::from("b.txt"));
+        assert_eq!(status.staged_files[1].status, StagedEntryStatus::Modified);
+        assert_eq!(
+            status.modified_files,
+            vec![PathBuf::from("y.csv"), PathBuf::from("z.csv")]
+        );
+        assert_eq!(status.moved_files[0].from, PathBuf::from("old/d.txt"));
+        assert_eq!(status.moved_files[0].to, PathBuf::from("new/d.txt"));
+        assert_eq!(status.untracked_dirs[0].path, PathBuf::from("audio"));
+        assert_eq!(status.untracked_dirs[1].num_files, 3);
+        assert_eq!(
+            status.untracked_files,
+            vec![PathBuf::from("b.txt"), PathBuf::from("c.txt")]
+        );
+        assert!(status.branch.is_none());
+        assert!(status.removed_files.is_empty());
+    }
+}
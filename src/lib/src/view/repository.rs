@@ -35,6 +35,12 @@ pub struct RepositoryDataTypesView {
     pub data_types: Vec<DataTypeCount>,
     pub min_version: Option<String>,
     pub is_empty: bool,
+    /// Where the server keeps the version files, such as "local" or "s3"
+    #[serde(default)]
+    pub storage_backend: Option<String>,
+    /// The branch HEAD points at on the server
+    #[serde(default)]
+    pub head: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -73,6 +79,18 @@ pub struct RepositoryResolveResponse {
     pub repository_api_url: String,
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RepositoryReadOnly {
+    pub read_only: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RepositoryReadOnlyResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub read_only: bool,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct RepositoryStatsResponse {
     #[serde(flatten)]
@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+
+use super::StatusMessage;
+use crate::model::Tag;
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct TagResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub tag: Tag,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct ListTagsResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub tags: Vec<Tag>,
+}
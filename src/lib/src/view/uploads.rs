@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+use super::StatusMessage;
+use crate::model::UploadSession;
+
+/// Start or resume the upload of a version file
+#[derive(Serialize, Deserialize, Debug)]
+pub struct NewUploadSession {
+    /// Content hash of the file
+    pub hash: String,
+    pub size: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UploadSessionResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub upload: UploadSession,
+}
@@ -0,0 +1,11 @@
+use super::StatusMessage;
+use crate::model::User;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct WhoamiResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    /// The user the request's token was issued to, None if no valid token was sent
+    pub user: Option<User>,
+}
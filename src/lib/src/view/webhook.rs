@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+use super::StatusMessage;
+use crate::model::{Webhook, WebhookEvent};
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct WebhookNew {
+    pub url: String,
+    /// Every event when empty
+    #[serde(default)]
+    pub events: Vec<WebhookEvent>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct WebhookResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub webhook: Webhook,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct ListWebhooksResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub webhooks: Vec<Webhook>,
+}
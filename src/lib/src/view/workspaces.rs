@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 use time::OffsetDateTime;
 
@@ -36,6 +37,8 @@ impl From<WorkspaceCommit> for Commit {
             email: val.email,
             timestamp: val.timestamp,
             parent_ids: vec![],
+            metadata: BTreeMap::new(),
+            co_authors: vec![],
         }
     }
 }
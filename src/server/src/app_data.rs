@@ -1,12 +1,31 @@
 use std::path::PathBuf;
+use std::sync::Arc;
+
+use liboxen::config::MirrorConfig;
+use liboxen::core::cache::CacheScheduler;
+use liboxen::util::signing::SigningKey;
 
 pub struct OxenAppData {
     pub path: PathBuf,
+    /// Runs the post-commit cachers in the background, shared by all workers
+    pub cache_scheduler: CacheScheduler,
+    /// Signs branch heads and tree roots when the server has a key
+    pub signing_key: Option<Arc<SigningKey>>,
+    /// Repositories this server keeps in sync with an upstream server
+    pub mirror_config: Option<Arc<MirrorConfig>>,
+    /// Serve thumbnails of images and videos, off unless the server is started with `--media`
+    pub media: bool,
 }
 
 impl OxenAppData {
     pub fn new(path: PathBuf) -> OxenAppData {
-        OxenAppData { path }
+        OxenAppData {
+            path,
+            cache_scheduler: CacheScheduler::default(),
+            signing_key: None,
+            mirror_config: None,
+            media: false,
+        }
     }
 }
 
@@ -14,6 +33,10 @@ impl Clone for OxenAppData {
     fn clone(&self) -> Self {
         OxenAppData {
             path: self.path.clone(),
+            cache_scheduler: self.cache_scheduler.clone(),
+            signing_key: self.signing_key.clone(),
+            mirror_config: self.mirror_config.clone(),
+            media: self.media,
         }
     }
 }
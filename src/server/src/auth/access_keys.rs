@@ -152,6 +152,17 @@ impl AccessKeyManager {
         }
     }
 
+    /// The user a token was issued to, None if the token is not valid on this server
+    pub fn user_for_token(&self, token: &str) -> Result<Option<User>, OxenError> {
+        if !self.token_is_valid(token) {
+            return Ok(None);
+        }
+        Ok(self.get_claim(token)?.map(|claim| User {
+            name: claim.name,
+            email: claim.email,
+        }))
+    }
+
     fn read_secret_key(&self) -> Result<String, OxenError> {
         let path = AccessKeyManager::secret_key_path(&self.sync_dir);
         util::fs::read_from_path(path)
@@ -224,6 +235,21 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_user_for_token() -> Result<(), OxenError> {
+        test::run_empty_sync_dir_test(|sync_dir| {
+            let keygen = AccessKeyManager::new(sync_dir)?;
+            let new_user = User {
+                name: String::from("Ox"),
+                email: String::from("ox@oxen.ai"),
+            };
+            let (_user, token) = keygen.create(&new_user)?;
+            assert_eq!(keygen.user_for_token(&token)?, Some(new_user));
+            assert_eq!(keygen.user_for_token("not-a-token")?, None);
+            Ok(())
+        })
+    }
+
     #[test]
     fn test_invalid_key() -> Result<(), OxenError> {
         test::run_empty_sync_dir_test(|sync_dir| {
@@ -1,22 +1,36 @@
 pub mod action;
+pub mod activity;
+pub mod append;
+pub mod archive;
 pub mod branches;
 pub mod commits;
 pub mod data_frames;
+pub mod dedup;
 pub mod diff;
 pub mod dir;
+pub mod embeddings;
 pub mod entries;
 pub mod file;
 pub mod fork;
 pub mod health;
+pub mod jobs;
 pub mod merger;
 pub mod metadata;
 pub mod migrations;
+pub mod mirrors;
 pub mod namespaces;
 pub mod not_found;
 pub mod oxen_version;
+pub mod proposals;
+pub mod render;
 pub mod repositories;
 pub mod revisions;
 pub mod schemas;
+pub mod tags;
+pub mod thumbnails;
 pub mod tree;
+pub mod uploads;
+pub mod users;
 pub mod versions;
+pub mod webhooks;
 pub mod workspaces;
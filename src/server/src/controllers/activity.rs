@@ -0,0 +1,25 @@
+use crate::errors::OxenHttpError;
+use crate::helpers::get_repo;
+use crate::params::{app_data, path_param, PageNumQuery};
+
+use actix_web::{web, HttpRequest, HttpResponse};
+use liboxen::opts::PaginateOpts;
+use liboxen::{constants, repositories};
+
+/// Recent commits, branch creations, forks and workspace merges, newest first
+pub async fn index(
+    req: HttpRequest,
+    query: web::Query<PageNumQuery>,
+) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let repo_name = path_param(&req, "repo_name")?;
+    let repo = get_repo(&app_data.path, namespace, repo_name)?;
+
+    let pagination = PaginateOpts {
+        page_num: query.page.unwrap_or(constants::DEFAULT_PAGE_NUM),
+        page_size: query.page_size.unwrap_or(constants::DEFAULT_PAGE_SIZE),
+    };
+    let activity = repositories::activity::list_paginated(&repo, pagination)?;
+    Ok(HttpResponse::Ok().json(activity))
+}
@@ -0,0 +1,89 @@
+use crate::errors::OxenHttpError;
+use crate::helpers::{get_repo, record_activity, schedule_cachers};
+use crate::params::{app_data, parse_resource, path_param, AppendQuery};
+
+use actix_web::{web, HttpRequest, HttpResponse};
+use liboxen::error::OxenError;
+use liboxen::model::{ActivityEvent, User};
+use liboxen::opts::AppendOpts;
+use liboxen::repositories;
+use liboxen::view::{AppendResponse, StatusMessage};
+
+/// Append the rows in the body, CSV with a header or JSON, to a tabular file on a branch.
+/// They are committed once enough rows are buffered or the oldest has waited long enough.
+pub async fn append(
+    req: HttpRequest,
+    query: web::Query<AppendQuery>,
+    body: String,
+) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let repo_name = path_param(&req, "repo_name")?;
+    let repo = get_repo(&app_data.path, namespace, repo_name)?;
+    let resource = parse_resource(&req, &repo)?;
+    let Some(branch) = resource.branch else {
+        return Err(OxenHttpError::BadRequest(
+            format!(
+                "Rows can only be appended to a branch, not {}",
+                resource.version.display()
+            )
+            .into(),
+        ));
+    };
+
+    let content_type = req
+        .headers()
+        .get("Content-Type")
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("application/json");
+    let rows = repositories::append::parse_rows(&body, content_type)
+        .map_err(|err| OxenHttpError::BadRequest(format!("{err}").into()))?;
+
+    let author = query.author.as_ref().map(|name| User {
+        name: name.clone(),
+        email: query.email.clone().unwrap_or_default(),
+    });
+    let opts = AppendOpts {
+        commit_rows: query.commit_rows,
+        commit_after_secs: query.commit_after,
+        author,
+        message: query.message.clone(),
+        commit: query.commit.unwrap_or(false),
+    };
+    if rows.is_empty() && !opts.commit {
+        return Err(OxenHttpError::BadRequest("No rows to append".into()));
+    }
+
+    // Committing without rows flushes the rows sent before
+    let result = if rows.is_empty() {
+        repositories::append::flush(&repo, &branch.name, &resource.path)
+    } else {
+        repositories::append::append(&repo, &branch.name, &resource.path, &rows, &opts)
+    };
+    let commit = match result {
+        Ok(commit) => commit,
+        Err(err @ OxenError::InvalidCommitMessage(_)) => return Err(err.into()),
+        Err(err) => {
+            log::error!(
+                "Could not append {} rows to {:?} on {}: {}",
+                rows.len(),
+                resource.path,
+                branch.name,
+                err
+            );
+            return Err(OxenHttpError::BadRequest(format!("{err}").into()));
+        }
+    };
+    if let Some(commit) = &commit {
+        schedule_cachers(app_data, &repo, commit);
+        record_activity(&repo, &ActivityEvent::commit(commit));
+    }
+
+    let buffer = repositories::append::get(&repo, &branch.name, &resource.path)?;
+    Ok(HttpResponse::Ok().json(AppendResponse {
+        status: StatusMessage::resource_created(),
+        num_rows: rows.len(),
+        buffer,
+        commit,
+    }))
+}
@@ -0,0 +1,78 @@
+use crate::errors::OxenHttpError;
+use crate::helpers::get_repo;
+use crate::params::{app_data, path_param, ArchiveQuery};
+
+use liboxen::error::OxenError;
+use liboxen::repositories;
+use liboxen::repositories::archive::ArchiveFormat;
+use liboxen::util;
+
+use actix_files::NamedFile;
+use actix_web::http::header::{ContentDisposition, DispositionParam, DispositionType};
+use actix_web::{web, HttpRequest, HttpResponse};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// Download the files of a revision as a tar.gz, tar or zip. The `format` query param
+/// picks the format and `paths` is a comma separated list of paths to limit it to.
+pub async fn download(
+    req: HttpRequest,
+    query: web::Query<ArchiveQuery>,
+) -> Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let repo_name = path_param(&req, "repo_name")?;
+    let revision = path_param(&req, "revision")?;
+    let repo = get_repo(&app_data.path, &namespace, &repo_name)?;
+
+    let format = match &query.format {
+        Some(format) => ArchiveFormat::from_str(format)?,
+        None => ArchiveFormat::default(),
+    };
+    let paths: Vec<PathBuf> = query
+        .paths
+        .as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+        .collect();
+
+    // Make sure the revision exists before doing any work
+    let commit = repositories::revisions::get(&repo, &revision)?
+        .ok_or(OxenError::revision_not_found(revision.clone().into()))?;
+    log::debug!(
+        "archive {namespace}/{repo_name} at {} as {format} with paths {paths:?}",
+        commit.id
+    );
+
+    let tmp_dir = util::fs::oxen_tmp_dir()?.join("archives");
+    util::fs::create_dir_all(&tmp_dir)?;
+    let archive_path = tmp_dir.join(format!("{}.{format}", uuid::Uuid::new_v4()));
+
+    let output = archive_path.clone();
+    let commit_id = commit.id.clone();
+    let written = web::block(move || {
+        repositories::archive::export(&repo, &commit_id, &paths, format, &output)
+    })
+    .await
+    .map_err(|err| OxenError::basic_str(err.to_string()))?;
+    if let Err(err) = written {
+        util::fs::remove_file(&archive_path).ok();
+        return Err(err.into());
+    }
+
+    // The open file keeps streaming after the path is removed
+    let file = NamedFile::open(&archive_path)?;
+    util::fs::remove_file(&archive_path).ok();
+
+    let filename = format!("{repo_name}-{}.{format}", revision.replace('/', "-"));
+    let file = file
+        .set_content_type(format.mime_type().parse().unwrap())
+        .set_content_disposition(ContentDisposition {
+            disposition: DispositionType::Attachment,
+            parameters: vec![DispositionParam::Filename(filename)],
+        });
+    Ok(file.into_response(&req))
+}
@@ -1,19 +1,23 @@
 use std::path::PathBuf;
 
 use crate::errors::OxenHttpError;
-use crate::helpers::get_repo;
+use crate::helpers::{fire_webhooks, get_repo, get_user, record_activity, schedule_cachers};
+use crate::merge_queue;
 use crate::params::{app_data, path_param, PageNumQuery};
 
 use actix_web::{web, HttpRequest, HttpResponse};
 
+use liboxen::config::BranchProtectionRule;
 use liboxen::error::OxenError;
-use liboxen::model::LocalRepository;
+use liboxen::model::{ActivityEvent, Branch, LocalRepository, WebhookPayload};
+use liboxen::util::signing::{self, SigningKey};
 use liboxen::util::{self, paginate};
 use liboxen::view::entries::ResourceVersion;
 use liboxen::view::{
-    BranchLockResponse, BranchNewFromBranchName, BranchNewFromCommitId, BranchRemoteMerge,
-    BranchResponse, BranchUpdate, CommitEntryVersion, CommitResponse, ListBranchesResponse,
-    PaginatedEntryVersions, PaginatedEntryVersionsResponse, StatusMessage,
+    BranchCompareResponse, BranchLockResponse, BranchNewFromBranchName, BranchNewFromCommitId,
+    BranchProtectionRuleResponse, BranchRemoteMerge, BranchResponse, BranchUpdate,
+    CommitEntryVersion, CommitResponse, ListBranchProtectionRulesResponse, ListBranchesResponse,
+    PaginatedEntryVersions, PaginatedEntryVersionsResponse, RefSignature, StatusMessage,
 };
 use liboxen::{constants, repositories};
 
@@ -37,25 +41,54 @@ pub async fn show(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpE
     let namespace = path_param(&req, "namespace")?;
     let name = path_param(&req, "repo_name")?;
     let branch_name = path_param(&req, "branch_name")?;
-    let repository = get_repo(&app_data.path, namespace, name)?;
+    let repository = get_repo(&app_data.path, &namespace, &name)?;
 
     log::debug!("show branch {:?}", branch_name);
     let branch = repositories::branches::get_by_name(&repository, &branch_name)?
         .ok_or(OxenError::remote_branch_not_found(&branch_name))?;
     log::debug!("show branch found {:?}", branch);
 
+    let signature = match &app_data.signing_key {
+        Some(key) => sign_branch(key, &repository, &namespace, &name, &branch)?,
+        None => None,
+    };
+
     let view = BranchResponse {
         status: StatusMessage::resource_found(),
         branch,
+        signature,
     };
 
     Ok(HttpResponse::Ok().json(view))
 }
 
+/// Sign the branch head and the root hash of its tree so clients can detect a tampered mirror
+fn sign_branch(
+    key: &SigningKey,
+    repo: &LocalRepository,
+    namespace: &str,
+    repo_name: &str,
+    branch: &Branch,
+) -> Result<Option<RefSignature>, OxenError> {
+    let Some(commit) = repositories::commits::get_by_id(repo, &branch.commit_id)? else {
+        return Ok(None);
+    };
+    let Some(root_hash) = repositories::tree::get_root_dir_hash(repo, &commit)? else {
+        return Ok(None);
+    };
+    let root_hash = root_hash.to_string();
+    let message = signing::ref_message(namespace, repo_name, &branch.name, &commit.id, &root_hash);
+    Ok(Some(RefSignature {
+        root_hash,
+        signature: key.sign(message),
+    }))
+}
+
 pub async fn create(req: HttpRequest, body: String) -> Result<HttpResponse, OxenHttpError> {
     let app_data = app_data(&req)?;
     let namespace = path_param(&req, "namespace")?;
     let repo_name = path_param(&req, "repo_name")?;
+    let full_name = format!("{namespace}/{repo_name}");
 
     let repo = get_repo(&app_data.path, namespace, repo_name)?;
 
@@ -65,14 +98,14 @@ pub async fn create(req: HttpRequest, body: String) -> Result<HttpResponse, Oxen
     let data: Result<BranchNewFromBranchName, serde_json::Error> = serde_json::from_str(&body);
     if let Ok(data) = data {
         log::debug!("Create from branch!");
-        return create_from_branch(&repo, &data);
+        return create_from_branch(&repo, &full_name, &data);
     }
 
     // Try to deserialize the body into a BranchNewFromCommitId
     let data: Result<BranchNewFromCommitId, serde_json::Error> = serde_json::from_str(&body);
     if let Ok(data) = data {
         log::debug!("Create from commit!");
-        return create_from_commit(&repo, &data);
+        return create_from_commit(&repo, &full_name, &data);
     }
 
     Ok(HttpResponse::BadRequest().json(StatusMessage::error("Invalid request body")))
@@ -80,6 +113,7 @@ pub async fn create(req: HttpRequest, body: String) -> Result<HttpResponse, Oxen
 
 fn create_from_branch(
     repo: &LocalRepository,
+    full_name: &str,
     data: &BranchNewFromBranchName,
 ) -> Result<HttpResponse, OxenHttpError> {
     let maybe_new_branch: Option<liboxen::model::Branch> =
@@ -88,6 +122,7 @@ fn create_from_branch(
         let view = BranchResponse {
             status: StatusMessage::resource_found(),
             branch,
+            signature: None,
         };
         return Ok(HttpResponse::Ok().json(view));
     }
@@ -96,22 +131,29 @@ fn create_from_branch(
         .ok_or(OxenHttpError::NotFound)?;
 
     let new_branch = repositories::branches::create(repo, &data.new_name, from_branch.commit_id)?;
+    record_activity(repo, &ActivityEvent::branch_created(&new_branch));
+    fire_webhooks(repo, WebhookPayload::branch_created(full_name, &new_branch));
 
     Ok(HttpResponse::Ok().json(BranchResponse {
         status: StatusMessage::resource_created(),
         branch: new_branch,
+        signature: None,
     }))
 }
 
 fn create_from_commit(
     repo: &LocalRepository,
+    full_name: &str,
     data: &BranchNewFromCommitId,
 ) -> Result<HttpResponse, OxenHttpError> {
     let new_branch = repositories::branches::create(repo, &data.new_name, &data.commit_id)?;
+    record_activity(repo, &ActivityEvent::branch_created(&new_branch));
+    fire_webhooks(repo, WebhookPayload::branch_created(full_name, &new_branch));
 
     Ok(HttpResponse::Ok().json(BranchResponse {
         status: StatusMessage::resource_created(),
         branch: new_branch,
+        signature: None,
     }))
 }
 
@@ -125,10 +167,12 @@ pub async fn delete(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHtt
     let branch = repositories::branches::get_by_name(&repository, &branch_name)?
         .ok_or(OxenError::remote_branch_not_found(&branch_name))?;
 
+    repositories::branches::protection::check_delete(&repository, &branch.name)?;
     repositories::branches::force_delete(&repository, &branch.name)?;
     Ok(HttpResponse::Ok().json(BranchResponse {
         status: StatusMessage::resource_deleted(),
         branch,
+        signature: None,
     }))
 }
 
@@ -140,16 +184,63 @@ pub async fn update(
     let namespace = path_param(&req, "namespace")?;
     let name = path_param(&req, "repo_name")?;
     let branch_name = path_param(&req, "branch_name")?;
+    let full_name = format!("{namespace}/{name}");
     let repository = get_repo(&app_data.path, namespace, name)?;
 
     let data: Result<BranchUpdate, serde_json::Error> = serde_json::from_str(&body);
     let data = data.map_err(|err| OxenHttpError::BadRequest(format!("{:?}", err).into()))?;
 
+    // Pushes to the same branch apply one at a time, so the check below and the
+    // update are atomic
+    let _guard = merge_queue::enqueue(&repository.path, &branch_name).await;
+    let branch = repositories::branches::get_by_name(&repository, &branch_name)?;
+    if let Some(expected_commit_id) = &data.expected_commit_id {
+        let commit_id = branch.as_ref().map(|b| b.commit_id.as_str()).unwrap_or("");
+        if commit_id != expected_commit_id {
+            return Err(
+                OxenError::non_fast_forward(&branch_name, expected_commit_id, commit_id).into(),
+            );
+        }
+
+        // Only a force push, which doesn't say where it expects the branch, can rewind it
+        if let Some(branch) = branch.as_ref().filter(|b| b.commit_id != data.commit_id) {
+            let head = repositories::commits::get_by_id(&repository, &branch.commit_id)?
+                .ok_or(OxenError::resource_not_found(&branch.commit_id))?;
+            let new_commit = repositories::commits::get_by_id(&repository, &data.commit_id)?
+                .ok_or(OxenError::resource_not_found(&data.commit_id))?;
+            let lca = repositories::merge::lowest_common_ancestor_from_commits(
+                &repository,
+                &head,
+                &new_commit,
+            )?;
+            if lca.id != head.id {
+                return Err(OxenError::not_descendant(
+                    &branch_name,
+                    &branch.commit_id,
+                    &data.commit_id,
+                )
+                .into());
+            }
+        }
+    }
+    if let Some(branch) = branch {
+        repositories::branches::protection::check_push(&repository, &branch, &data.commit_id)?;
+        if let Some(new_commit) = repositories::commits::get_by_id(&repository, &data.commit_id)? {
+            let pusher = get_user(app_data, &req)?;
+            repositories::owners::check_push(&repository, &branch, &new_commit, pusher.as_ref())?;
+        }
+    }
+
     let branch = repositories::branches::update(&repository, branch_name, data.commit_id)?;
+    if let Some(commit) = repositories::commits::get_by_id(&repository, &branch.commit_id)? {
+        schedule_cachers(app_data, &repository, &commit);
+    }
+    fire_webhooks(&repository, WebhookPayload::push(&full_name, &branch));
 
     Ok(HttpResponse::Ok().json(BranchResponse {
         status: StatusMessage::resource_updated(),
         branch,
+        signature: None,
     }))
 }
 
@@ -162,8 +253,10 @@ pub async fn maybe_create_merge(
     let name = path_param(&req, "repo_name")?;
     let repository = get_repo(&app_data.path, namespace, name)?;
     let branch_name = path_param(&req, "branch_name")?;
+    let _guard = merge_queue::enqueue(&repository.path, &branch_name).await;
     let branch = repositories::branches::get_by_name(&repository, &branch_name)?
         .ok_or(OxenError::remote_branch_not_found(&branch_name))?;
+    repositories::branches::protection::check_push_merge(&repository, &branch)?;
 
     let data: Result<BranchRemoteMerge, serde_json::Error> = serde_json::from_str(&body);
     let data = data.map_err(|err| OxenHttpError::BadRequest(format!("{:?}", err).into()))?;
@@ -179,6 +272,8 @@ pub async fn maybe_create_merge(
         "maybe_create_merge got client head commit {:?}",
         incoming_commit_id
     );
+    let pusher = get_user(app_data, &req)?;
+    repositories::owners::check_push(&repository, &branch, &incoming_commit, pusher.as_ref())?;
 
     let maybe_merge_commit = repositories::merge::merge_commit_into_base_on_branch(
         &repository,
@@ -191,6 +286,7 @@ pub async fn maybe_create_merge(
     // Return what will become the new head of the repo after push is complete.
     if let Some(merge_commit) = maybe_merge_commit {
         log::debug!("returning merge commit {:?}", merge_commit);
+        schedule_cachers(app_data, &repository, &merge_commit);
         // Update branch head
         Ok(HttpResponse::Ok().json(CommitResponse {
             status: StatusMessage::resource_created(),
@@ -206,6 +302,36 @@ pub async fn maybe_create_merge(
         }))
     }
 }
+
+pub async fn compare(
+    req: HttpRequest,
+    query: web::Query<PageNumQuery>,
+) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let repository = get_repo(&app_data.path, namespace, name)?;
+    let base_name = path_param(&req, "branch_name")?;
+    let head_name = path_param(&req, "head_branch")?;
+
+    let base = repositories::branches::get_by_name(&repository, &base_name)?
+        .ok_or(OxenError::remote_branch_not_found(&base_name))?;
+    let head = repositories::branches::get_by_name(&repository, &head_name)?
+        .ok_or(OxenError::remote_branch_not_found(&head_name))?;
+
+    let page = query.page.unwrap_or(constants::DEFAULT_PAGE_NUM);
+    let page_size = query.page_size.unwrap_or(constants::DEFAULT_PAGE_SIZE);
+
+    log::debug!("compare branch {} to {}", head_name, base_name);
+    let compare =
+        repositories::branches::compare(&repository, &base, &head, page, page_size).await?;
+
+    Ok(HttpResponse::Ok().json(BranchCompareResponse {
+        status: StatusMessage::resource_found(),
+        compare,
+    }))
+}
+
 pub async fn latest_synced_commit(
     req: HttpRequest,
 ) -> actix_web::Result<HttpResponse, OxenHttpError> {
@@ -281,6 +407,59 @@ pub async fn is_locked(req: HttpRequest) -> actix_web::Result<HttpResponse, Oxen
     }))
 }
 
+pub async fn list_protection_rules(
+    req: HttpRequest,
+) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let repository = get_repo(&app_data.path, namespace, name)?;
+
+    let rules = repositories::branches::protection::list(&repository)?;
+
+    Ok(HttpResponse::Ok().json(ListBranchProtectionRulesResponse {
+        status: StatusMessage::resource_found(),
+        rules,
+    }))
+}
+
+pub async fn protect(
+    req: HttpRequest,
+    body: String,
+) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let repository = get_repo(&app_data.path, namespace, name)?;
+
+    let data: Result<BranchProtectionRule, serde_json::Error> = serde_json::from_str(&body);
+    let data = data.map_err(|err| OxenHttpError::BadRequest(format!("{:?}", err).into()))?;
+
+    log::debug!("protect branch {:?}", data);
+    let rule = repositories::branches::protection::protect(&repository, data)?;
+
+    Ok(HttpResponse::Ok().json(BranchProtectionRuleResponse {
+        status: StatusMessage::resource_updated(),
+        rule,
+    }))
+}
+
+pub async fn unprotect(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let branch = path_param(&req, "branch")?;
+    let repository = get_repo(&app_data.path, namespace, name)?;
+
+    let rule = repositories::branches::protection::unprotect(&repository, &branch)?
+        .ok_or(OxenError::resource_not_found(&branch))?;
+
+    Ok(HttpResponse::Ok().json(BranchProtectionRuleResponse {
+        status: StatusMessage::resource_deleted(),
+        rule,
+    }))
+}
+
 pub async fn list_entry_versions(
     req: HttpRequest,
     query: web::Query<PageNumQuery>,
@@ -358,16 +537,19 @@ mod tests {
     use actix_web::http::{self};
 
     use actix_web::body::to_bytes;
+    use std::sync::Arc;
 
     use liboxen::constants::DEFAULT_BRANCH_NAME;
     use liboxen::error::OxenError;
     use liboxen::repositories;
     use liboxen::util;
+    use liboxen::util::signing::{self, SigningKey};
     use liboxen::view::http::STATUS_SUCCESS;
     use liboxen::view::{
-        BranchNewFromBranchName, BranchResponse, CommitResponse, ListBranchesResponse,
+        BranchNewFromBranchName, BranchResponse, BranchUpdate, CommitResponse, ListBranchesResponse,
     };
 
+    use crate::app_data::OxenAppData;
     use crate::controllers;
     use crate::test;
 
@@ -466,6 +648,52 @@ mod tests {
         Ok(())
     }
 
+    #[actix_web::test]
+    async fn test_controllers_branch_show_signed() -> Result<(), OxenError> {
+        let sync_dir = test::get_sync_dir()?;
+        let namespace = "Testing-Namespace";
+        let repo_name = "Testing-Branches-Signed";
+        let repo = test::create_local_repo(&sync_dir, namespace, repo_name)?;
+        let hello_file = repo.path.join("hello.txt");
+        util::fs::write_to_path(&hello_file, "Hello")?;
+        repositories::add(&repo, &hello_file).await?;
+        let commit = repositories::commit(&repo, "First commit")?;
+
+        let key = SigningKey::load_or_create(&sync_dir)?;
+        let public_key = key.public_key();
+        let mut data = OxenAppData::new(sync_dir.clone());
+        data.signing_key = Some(Arc::new(key));
+        let uri = format!("/oxen/{namespace}/{repo_name}/branches/{DEFAULT_BRANCH_NAME}");
+        let req = actix_web::test::TestRequest::with_uri(&uri)
+            .app_data(data)
+            .param("namespace", namespace)
+            .param("repo_name", repo_name)
+            .param("branch_name", DEFAULT_BRANCH_NAME)
+            .to_http_request();
+
+        let resp = controllers::branches::show(req).await.unwrap();
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        let body = to_bytes(resp.into_body()).await.unwrap();
+        let text = std::str::from_utf8(&body).unwrap();
+        let branch_resp: BranchResponse = serde_json::from_str(text)?;
+        let signature = branch_resp.signature.unwrap();
+        let root_hash = repositories::tree::get_root_dir_hash(&repo, &commit)?.unwrap();
+        assert_eq!(signature.root_hash, root_hash.to_string());
+        let message = signing::ref_message(
+            namespace,
+            repo_name,
+            DEFAULT_BRANCH_NAME,
+            &commit.id,
+            &signature.root_hash,
+        );
+        signing::verify(&public_key, &message, &signature.signature)?;
+
+        // cleanup
+        test::cleanup_sync_dir(&sync_dir)?;
+
+        Ok(())
+    }
+
     #[actix_web::test]
     async fn test_controllers_branch_create() -> Result<(), OxenError> {
         let sync_dir = test::get_sync_dir()?;
@@ -503,6 +731,95 @@ mod tests {
         Ok(())
     }
 
+    #[actix_web::test]
+    async fn test_controllers_branch_update_rejects_non_fast_forward() -> Result<(), OxenError> {
+        let sync_dir = test::get_sync_dir()?;
+        let namespace = "Testing-Namespace";
+        let name = "Testing-Branches-Update-CAS";
+        let repo = test::create_local_repo(&sync_dir, namespace, name)?;
+        let hello_file = repo.path.join("hello.txt");
+        util::fs::write_to_path(&hello_file, "Hello")?;
+        repositories::add(&repo, &hello_file).await?;
+        let first = repositories::commit(&repo, "First commit")?;
+        util::fs::write_to_path(&hello_file, "Hello again")?;
+        repositories::add(&repo, &hello_file).await?;
+        let second = repositories::commit(&repo, "Second commit")?;
+
+        // Another push already moved the branch to the second commit
+        let params = BranchUpdate {
+            commit_id: first.id.clone(),
+            expected_commit_id: Some(first.id.clone()),
+        };
+        let uri = format!("/oxen/{namespace}/{name}/branches/{DEFAULT_BRANCH_NAME}");
+        let req = test::repo_request_with_param(
+            &sync_dir,
+            &uri,
+            namespace,
+            name,
+            "branch_name",
+            DEFAULT_BRANCH_NAME,
+        );
+        let result = controllers::branches::update(req, serde_json::to_string(&params)?).await;
+        let err = result.expect_err("stale expected commit should be rejected");
+        assert_eq!(
+            actix_web::ResponseError::status_code(&err),
+            http::StatusCode::CONFLICT
+        );
+        let branch = repositories::branches::get_by_name(&repo, DEFAULT_BRANCH_NAME)?.unwrap();
+        assert_eq!(branch.commit_id, second.id);
+
+        // Rewinding the branch isn't a fast-forward even when it is where the client expects
+        let params = BranchUpdate {
+            commit_id: first.id.clone(),
+            expected_commit_id: Some(second.id.clone()),
+        };
+        let req = test::repo_request_with_param(
+            &sync_dir,
+            &uri,
+            namespace,
+            name,
+            "branch_name",
+            DEFAULT_BRANCH_NAME,
+        );
+        let result = controllers::branches::update(req, serde_json::to_string(&params)?).await;
+        let err = result.expect_err("rewinding the branch should be rejected");
+        assert_eq!(
+            actix_web::ResponseError::status_code(&err),
+            http::StatusCode::CONFLICT
+        );
+        let branch = repositories::branches::get_by_name(&repo, DEFAULT_BRANCH_NAME)?.unwrap();
+        assert_eq!(branch.commit_id, second.id);
+
+        // Succeeds when the branch is where the client expects and the push builds on it
+        util::fs::write_to_path(&hello_file, "Hello once more")?;
+        repositories::add(&repo, &hello_file).await?;
+        let third = repositories::commit(&repo, "Third commit")?;
+        repositories::branches::update(&repo, DEFAULT_BRANCH_NAME, &second.id)?;
+        let params = BranchUpdate {
+            commit_id: third.id.clone(),
+            expected_commit_id: Some(second.id.clone()),
+        };
+        let req = test::repo_request_with_param(
+            &sync_dir,
+            &uri,
+            namespace,
+            name,
+            "branch_name",
+            DEFAULT_BRANCH_NAME,
+        );
+        let resp = controllers::branches::update(req, serde_json::to_string(&params)?)
+            .await
+            .map_err(|_err| OxenError::basic_str("OxenHttpError - could not update branch"))?;
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        let branch = repositories::branches::get_by_name(&repo, DEFAULT_BRANCH_NAME)?.unwrap();
+        assert_eq!(branch.commit_id, third.id);
+
+        // cleanup
+        test::cleanup_sync_dir(&sync_dir)?;
+
+        Ok(())
+    }
+
     #[actix_web::test]
     async fn test_controllers_branch_get_latest() -> Result<(), OxenError> {
         let sync_dir = test::get_sync_dir()?;
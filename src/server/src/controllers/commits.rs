@@ -5,6 +5,7 @@ use liboxen::constants::DIR_HASHES_DIR;
 use liboxen::constants::HISTORY_DIR;
 use liboxen::constants::VERSION_FILE_NAME;
 
+use liboxen::core::cache::{self, CacheJobRecord};
 use liboxen::core::commit_sync_status;
 use liboxen::error::OxenError;
 use liboxen::model::{Commit, LocalRepository};
@@ -15,17 +16,17 @@ use liboxen::view::branch::BranchName;
 use liboxen::view::tree::merkle_hashes::MerkleHashes;
 use liboxen::view::MerkleHashesResponse;
 use liboxen::view::{
-    CommitResponse, ListCommitResponse, PaginatedCommits, Pagination, RootCommitResponse,
-    StatusMessage,
+    CacheJobResponse, CacheJobsResponse, CommitResponse, ListCommitResponse, PaginatedCommits,
+    Pagination, RootCommitResponse, StatusMessage,
 };
 use os_path::OsPath;
 
 use crate::app_data::OxenAppData;
 use crate::errors::OxenHttpError;
-use crate::helpers::get_repo;
+use crate::helpers::{get_repo, schedule_cachers};
 use crate::params::parse_resource;
-use crate::params::PageNumQuery;
 use crate::params::{app_data, path_param};
+use crate::params::{HistoryDepthQuery, PageNumQuery};
 
 use actix_web::{web, Error, HttpRequest, HttpResponse};
 use async_compression::tokio::bufread::GzipDecoder;
@@ -53,6 +54,14 @@ pub struct ChunkedDataUploadQuery {
     filename: Option<String>, // maybe a file name if !compressed
 }
 
+#[derive(Deserialize, Debug)]
+pub struct CommitSearchQuery {
+    q: String,                // case insensitive regex over the message and metadata
+    revision: Option<String>, // branch or commit to search the history of, defaults to main
+    page: Option<usize>,
+    page_size: Option<usize>,
+}
+
 // List commits for a repository
 pub async fn index(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
     let app_data = app_data(&req)?;
@@ -126,6 +135,52 @@ pub async fn history(
     }
 }
 
+// Search the history of a revision by commit message and metadata
+pub async fn search(
+    req: HttpRequest,
+    query: web::Query<CommitSearchQuery>,
+) -> Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let repo_name = path_param(&req, "repo_name")?;
+    let repo = get_repo(&app_data.path, namespace, repo_name)?;
+
+    let pagination = PaginateOpts {
+        page_num: query.page.unwrap_or(constants::DEFAULT_PAGE_NUM),
+        page_size: query.page_size.unwrap_or(constants::DEFAULT_PAGE_SIZE),
+    };
+
+    if repositories::is_empty(&repo)? {
+        return Ok(HttpResponse::Ok().json(PaginatedCommits::success(
+            vec![],
+            Pagination::empty(pagination),
+        )));
+    }
+
+    // Reject bad patterns as a bad request rather than an internal error
+    if let Err(err) = regex::Regex::new(&query.q) {
+        return Err(OxenHttpError::BadRequest(
+            format!("Invalid search pattern {:?}: {}", query.q, err).into(),
+        ));
+    }
+
+    let revision = query
+        .revision
+        .clone()
+        .unwrap_or(constants::DEFAULT_BRANCH_NAME.to_string());
+    let commit = repositories::revisions::get(&repo, &revision)?
+        .ok_or(OxenError::revision_not_found(revision.into()))?;
+
+    let commits =
+        repositories::commits::search_from_paginated(&repo, &commit.id, &query.q, pagination)?;
+    log::debug!(
+        "commit search {:?} got {} commits",
+        query.q,
+        commits.commits.len()
+    );
+    Ok(HttpResponse::Ok().json(commits))
+}
+
 // List all commits in the repository
 pub async fn list_all(
     req: HttpRequest,
@@ -225,6 +280,58 @@ pub async fn show(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpE
     }))
 }
 
+/// List the post-commit cache jobs for a commit, with their outcome and duration
+pub async fn list_cache_jobs(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let repo_name = path_param(&req, "repo_name")?;
+    let commit_id = path_param(&req, "commit_id")?;
+    let repo = get_repo(&app_data.path, namespace, repo_name)?;
+    let commit = repositories::revisions::get(&repo, &commit_id)?
+        .ok_or(OxenError::revision_not_found(commit_id.into()))?;
+
+    let jobs = cache::job::list(&repo, &commit.id)?;
+    Ok(HttpResponse::Ok().json(CacheJobsResponse {
+        status: StatusMessage::resource_found(),
+        commit_id: commit.id,
+        jobs,
+    }))
+}
+
+/// Queue a cacher to run again on a commit, even if it already succeeded
+pub async fn recompute_cache_job(
+    req: HttpRequest,
+) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let repo_name = path_param(&req, "repo_name")?;
+    let commit_id = path_param(&req, "commit_id")?;
+    let cacher_name = path_param(&req, "cacher")?;
+    let repo = get_repo(&app_data.path, namespace, repo_name)?;
+    let commit = repositories::revisions::get(&repo, &commit_id)?
+        .ok_or(OxenError::revision_not_found(commit_id.into()))?;
+    let cacher = cache::cachers::get(&cacher_name).ok_or(OxenHttpError::BadRequest(
+        format!("Unknown cacher {cacher_name:?}").into(),
+    ))?;
+    if !app_data
+        .cache_scheduler
+        .cacher_config(&repo, &cacher)
+        .enabled
+    {
+        return Err(OxenHttpError::BadRequest(
+            format!("Cacher {cacher_name:?} is disabled").into(),
+        ));
+    }
+
+    app_data.cache_scheduler.submit(&repo, &commit, cacher)?;
+    let job = cache::job::get(&repo, &commit.id, cacher.name)?
+        .unwrap_or(CacheJobRecord::queued(cacher.name, &commit.id));
+    Ok(HttpResponse::Ok().json(CacheJobResponse {
+        status: StatusMessage::resource_created(),
+        job,
+    }))
+}
+
 pub async fn parents(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
     let app_data = app_data(&req)?;
     let namespace = path_param(&req, "namespace")?;
@@ -278,6 +385,7 @@ fn compress_commits_db(repository: &LocalRepository) -> Result<Vec<u8>, OxenErro
 /// Download the database of all entries given a specific commit
 pub async fn download_dir_hashes_db(
     req: HttpRequest,
+    query: web::Query<HistoryDepthQuery>,
 ) -> actix_web::Result<HttpResponse, OxenHttpError> {
     let app_data = app_data(&req)?;
     let namespace = path_param(&req, "namespace")?;
@@ -301,6 +409,8 @@ pub async fn download_dir_hashes_db(
             .ok_or(OxenError::revision_not_found(head_commit_id.into()))?;
 
         repositories::commits::list_between(&repository, &base_commit, &head_commit)?
+    } else if let Some(history_depth) = query.history_depth {
+        repositories::commits::list_from_with_history_depth(&repository, &base_head, history_depth)?
     } else {
         repositories::commits::list_from(&repository, &base_head)?
     };
@@ -832,6 +942,7 @@ pub async fn complete(req: HttpRequest) -> Result<HttpResponse, Error> {
         Ok(Some(repo)) => {
             match repositories::commits::get_by_id(&repo, commit_id) {
                 Ok(Some(commit)) => {
+                    schedule_cachers(app_data, &repo, &commit);
                     let response = CommitResponse {
                         status: StatusMessage::resource_created(),
                         commit: commit.clone(),
@@ -5,9 +5,11 @@ use crate::params::{app_data, parse_resource, path_param};
 
 use liboxen::constants;
 use liboxen::error::PathBufError;
-use liboxen::model::DataFrameSize;
+use liboxen::model::data_frame::DataFrameSchemaSize;
+use liboxen::model::{DataFrameSize, Schema};
 use liboxen::opts::df_opts::DFOptsView;
 use liboxen::repositories;
+use liboxen::view::data_frames::DataFrameSqlRequest;
 use liboxen::view::entries::ResourceVersion;
 
 use actix_web::{web, HttpRequest, HttpResponse};
@@ -101,6 +103,69 @@ pub async fn get(
     Ok(HttpResponse::Ok().json(response))
 }
 
+/// Run a sql query against a data frame at any revision without indexing or downloading it
+pub async fn sql(
+    req: HttpRequest,
+    query: web::Query<DFOptsQuery>,
+    body: web::Json<DataFrameSqlRequest>,
+) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let repo_name = path_param(&req, "repo_name")?;
+    let repo = get_repo(&app_data.path, namespace, repo_name)?;
+    let resource = parse_resource(&req, &repo)?;
+    let commit = resource.clone().commit.ok_or(OxenHttpError::NotFound)?;
+
+    let source_df =
+        repositories::data_frames::query_sql(&repo, &commit, &resource.path, &body.sql)?;
+    let source_schema = Schema::from_polars(&source_df.schema());
+    let total_entries = source_df.height();
+
+    let page = query.page.unwrap_or(constants::DEFAULT_PAGE_NUM);
+    let page_size = query.page_size.unwrap_or(constants::DEFAULT_PAGE_SIZE);
+    let start = if page == 0 { 0 } else { page_size * (page - 1) };
+    let mut df = source_df.slice(start as i64, page_size);
+    let total_pages = (total_entries as f64 / page_size as f64).ceil() as usize;
+
+    let mut opts = DFOpts::empty();
+    opts.sql = Some(body.sql.clone());
+    let resource_version = ResourceVersion {
+        path: resource.path.to_string_lossy().into(),
+        version: resource.version.to_string_lossy().into(),
+    };
+    let response = JsonDataFrameViewResponse {
+        status: StatusMessage::resource_found(),
+        data_frame: JsonDataFrameViews {
+            source: DataFrameSchemaSize {
+                size: DataFrameSize {
+                    height: total_entries,
+                    width: source_df.width(),
+                },
+                schema: source_schema.clone(),
+            },
+            view: JsonDataFrameView {
+                schema: source_schema,
+                size: DataFrameSize {
+                    height: df.height(),
+                    width: df.width(),
+                },
+                data: JsonDataFrameView::json_from_df(&mut df),
+                pagination: Pagination {
+                    page_number: page,
+                    page_size,
+                    total_pages,
+                    total_entries,
+                },
+                opts: DFOptsView::from_df_opts(&opts),
+            },
+        },
+        commit: Some(commit.clone()),
+        resource: Some(resource_version),
+        derived_resource: None,
+    };
+    Ok(HttpResponse::Ok().json(response))
+}
+
 pub async fn index(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
     let app_data = app_data(&req)?;
     let namespace = path_param(&req, "namespace")?;
@@ -0,0 +1,76 @@
+use crate::errors::OxenHttpError;
+use crate::helpers::get_repo;
+use crate::params::{app_data, parse_resource, path_param};
+
+use liboxen::error::OxenError;
+use liboxen::repositories;
+use liboxen::view::{DedupReportResponse, StatusMessage};
+
+use actix_web::{HttpRequest, HttpResponse};
+
+/// Groups of files with the same contents in the revision, or in the dir it points at
+pub async fn report(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let repo_name = path_param(&req, "repo_name")?;
+    let repo = get_repo(&app_data.path, namespace, repo_name)?;
+    let resource = parse_resource(&req, &repo)?;
+    let commit = resource.commit.ok_or_else(|| {
+        OxenError::revision_not_found(resource.version.to_string_lossy().to_string().into())
+    })?;
+
+    let report = repositories::dedup::report_for_commit(&repo, &commit, Some(&resource.path))?;
+    Ok(HttpResponse::Ok().json(DedupReportResponse {
+        status: StatusMessage::resource_found(),
+        report,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::body::to_bytes;
+
+    use liboxen::error::OxenError;
+    use liboxen::repositories;
+    use liboxen::util;
+    use liboxen::view::DedupReportResponse;
+
+    use crate::controllers;
+    use crate::test;
+
+    #[actix_web::test]
+    async fn test_controllers_dedup_report() -> Result<(), OxenError> {
+        let sync_dir = test::get_sync_dir()?;
+        let namespace = "Testing-Namespace";
+        let repo_name = "Testing-Repo";
+        let repo = test::create_local_repo(&sync_dir, namespace, repo_name)?;
+
+        util::fs::create_dir_all(repo.path.join("images"))?;
+        util::fs::write_to_path(repo.path.join("images/a.txt"), "same")?;
+        util::fs::write_to_path(repo.path.join("images/b.txt"), "same")?;
+        util::fs::write_to_path(repo.path.join("README.md"), "same")?;
+        repositories::add(&repo, &repo.path).await?;
+        repositories::commit(&repo, "Adding duplicates")?;
+
+        let uri = format!("/oxen/{namespace}/{repo_name}/dedup/main/images");
+        let req = test::repo_request_with_param(
+            &sync_dir,
+            &uri,
+            namespace,
+            repo_name,
+            "resource",
+            "main/images",
+        );
+        let resp = controllers::dedup::report(req).await.unwrap();
+        assert_eq!(resp.status(), actix_web::http::StatusCode::OK);
+        let body = to_bytes(resp.into_body()).await.unwrap();
+        let response: DedupReportResponse = serde_json::from_slice(&body)?;
+
+        assert_eq!(response.report.files_checked, 2);
+        assert_eq!(response.report.groups.len(), 1);
+        assert_eq!(response.report.wasted_bytes, 4);
+
+        test::cleanup_sync_dir(&sync_dir)?;
+        Ok(())
+    }
+}
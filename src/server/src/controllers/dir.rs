@@ -3,6 +3,7 @@ use crate::helpers::get_repo;
 use crate::params::{app_data, parse_resource, path_param, PageNumVersionQuery};
 
 use liboxen::core::versions::MinOxenVersion;
+use liboxen::model::MetadataFilter;
 use liboxen::opts::PaginateOpts;
 use liboxen::view::PaginatedDirEntriesResponse;
 use liboxen::{constants, repositories};
@@ -22,6 +23,11 @@ pub async fn get(
     let page: usize = query.page.unwrap_or(constants::DEFAULT_PAGE_NUM);
     let page_size: usize = query.page_size.unwrap_or(constants::DEFAULT_PAGE_SIZE);
     let api_version = MinOxenVersion::or_latest(query.api_version.clone())?;
+    let filter = query
+        .filter
+        .as_deref()
+        .map(MetadataFilter::parse)
+        .transpose()?;
 
     log::debug!(
         "{} resource {namespace}/{repo_name}/{resource}",
@@ -39,6 +45,7 @@ pub async fn get(
         &resource.path,
         revision,
         resource.workspace.clone(),
+        filter.as_ref(),
         &PaginateOpts {
             page_num: page,
             page_size,
@@ -112,4 +119,56 @@ mod tests {
 
         Ok(())
     }
+
+    #[actix_web::test]
+    async fn test_controllers_dir_list_directory_with_filter() -> Result<(), OxenError> {
+        test::init_test_env();
+
+        let sync_dir = test::get_sync_dir()?;
+        let namespace = "Testing-Namespace";
+        let name = "Testing-Name";
+        let repo = test::create_local_repo(&sync_dir, namespace, name)?;
+
+        liboxen::test::populate_dir_with_training_data(&repo.path)?;
+        repositories::add(&repo, &repo.path).await?;
+        let commit = repositories::commit(&repo, "adding all the data")?;
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(OxenAppData::new(sync_dir.clone()))
+                .route(
+                    "/oxen/{namespace}/{repo_name}/dir/{resource:.*}",
+                    web::get().to(controllers::dir::get),
+                ),
+        )
+        .await;
+
+        // Only the jpgs, with one per page
+        let uri = format!(
+            "/oxen/{}/{}/dir/{}/train?filter=extension%3Djpg,size%3E0&page_size=1",
+            namespace, name, commit.id
+        );
+        let req = actix_web::test::TestRequest::get().uri(&uri).to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        let bytes = actix_http::body::to_bytes(resp.into_body()).await.unwrap();
+        let entries_resp: PaginatedDirEntries = serde_json::from_slice(&bytes)?;
+        assert_eq!(entries_resp.total_entries, 5);
+        assert_eq!(entries_resp.total_pages, 5);
+        assert_eq!(entries_resp.entries.len(), 1);
+
+        // Nothing in the root dir is a video
+        let uri = format!(
+            "/oxen/{}/{}/dir/{}/?filter=data_type%3Dvideo",
+            namespace, name, commit.id
+        );
+        let req = actix_web::test::TestRequest::get().uri(&uri).to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        let bytes = actix_http::body::to_bytes(resp.into_body()).await.unwrap();
+        let entries_resp: PaginatedDirEntries = serde_json::from_slice(&bytes)?;
+        assert_eq!(entries_resp.total_entries, 0);
+
+        test::cleanup_sync_dir(&sync_dir)?;
+
+        Ok(())
+    }
 }
@@ -0,0 +1,87 @@
+use crate::errors::OxenHttpError;
+use crate::helpers::get_repo;
+use crate::params::{app_data, path_param};
+
+use liboxen::opts::EmbeddingNeighborsOpts;
+use liboxen::repositories;
+use liboxen::view::{EmbeddingNeighborsResponse, StatusMessage};
+
+use actix_web::{web, HttpRequest, HttpResponse};
+
+/// Nearest neighbors of a file or embedding in an embedding column of a data frame
+/// at the revision
+pub async fn query(
+    req: HttpRequest,
+    body: web::Json<EmbeddingNeighborsOpts>,
+) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let repo_name = path_param(&req, "repo_name")?;
+    let revision = path_param(&req, "revision")?;
+    let repo = get_repo(&app_data.path, namespace, repo_name)?;
+
+    let result = repositories::embeddings::neighbors(&repo, &revision, &body)?;
+    Ok(HttpResponse::Ok().json(EmbeddingNeighborsResponse {
+        status: StatusMessage::resource_found(),
+        result,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use actix_web::body::to_bytes;
+    use actix_web::web;
+
+    use liboxen::error::OxenError;
+    use liboxen::opts::EmbeddingNeighborsOpts;
+    use liboxen::repositories;
+    use liboxen::util;
+    use liboxen::view::EmbeddingNeighborsResponse;
+
+    use crate::controllers;
+    use crate::test;
+
+    #[actix_web::test]
+    async fn test_controllers_embeddings_query() -> Result<(), OxenError> {
+        let sync_dir = test::get_sync_dir()?;
+        let namespace = "Testing-Namespace";
+        let repo_name = "Testing-Repo";
+        let repo = test::create_local_repo(&sync_dir, namespace, repo_name)?;
+
+        util::fs::write_to_path(
+            repo.path.join("embeddings.jsonl"),
+            r#"{"file": "a.jpg", "embedding": [1.0, 0.0]}
+{"file": "b.jpg", "embedding": [0.0, 1.0]}
+{"file": "c.jpg", "embedding": [0.9, 0.1]}
+"#,
+        )?;
+        repositories::add(&repo, &repo.path).await?;
+        repositories::commit(&repo, "Adding embeddings")?;
+
+        let uri = format!("/oxen/{namespace}/{repo_name}/embeddings/main/query");
+        let req = test::repo_request_with_param(
+            &sync_dir, &uri, namespace, repo_name, "revision", "main",
+        );
+        let body = web::Json(EmbeddingNeighborsOpts {
+            path: PathBuf::from("embeddings.jsonl"),
+            column: "embedding".to_string(),
+            file_column: None,
+            file: Some("a.jpg".to_string()),
+            embedding: None,
+            k: Some(1),
+            nprobe: None,
+        });
+        let resp = controllers::embeddings::query(req, body).await.unwrap();
+        assert_eq!(resp.status(), actix_web::http::StatusCode::OK);
+        let body = to_bytes(resp.into_body()).await.unwrap();
+        let response: EmbeddingNeighborsResponse = serde_json::from_slice(&body)?;
+
+        assert_eq!(response.result.neighbors.len(), 1);
+        assert_eq!(response.result.neighbors[0].file, Some("c.jpg".to_string()));
+
+        test::cleanup_sync_dir(&sync_dir)?;
+        Ok(())
+    }
+}
@@ -1,7 +1,9 @@
 use crate::errors::OxenHttpError;
-use crate::helpers::get_repo;
+use crate::helpers::{get_repo, get_user};
+use crate::merge_queue;
 use crate::params::{app_data, parse_resource, path_param};
 
+use liboxen::core::merge::tabular_merge;
 use liboxen::error::OxenError;
 use liboxen::model::commit::NewCommitBody;
 use liboxen::model::file::{FileContents, FileNew, TempFileNew};
@@ -9,15 +11,22 @@ use liboxen::model::metadata::metadata_image::ImgResize;
 use liboxen::model::{Commit, User};
 use liboxen::repositories::{self, branches};
 use liboxen::util;
+use liboxen::view::merge::FileConflictResponse;
 use liboxen::view::{CommitResponse, StatusMessage};
 
 use actix_files::NamedFile;
 use actix_multipart::Multipart;
-use actix_web::{http::header, web, HttpRequest, HttpResponse};
+use actix_web::http::{header, StatusCode};
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse};
 use futures_util::TryStreamExt as _;
 use liboxen::repositories::commits;
 use serde_json::Value;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// Revision a file edit was based on, the PUT fails with a 409 if the file changed since
+const BASED_ON_HEADER: &str = "oxen-based-on";
+/// `merge` to merge tabular edits into a file that changed since `oxen-based-on`
+const ON_CONFLICT_HEADER: &str = "oxen-on-conflict";
 
 const ALLOWED_IMPORT_DOMAINS: [&str; 3] = ["huggingface.co", "kaggle.com", "oxen.ai"];
 
@@ -55,7 +64,8 @@ pub async fn get(
         let entry = repositories::entries::get_file(&repo, &commit, &path)?;
         let entry = entry.ok_or(OxenError::path_does_not_exist(path.clone()))?;
 
-        let version_path = util::fs::version_path_from_hash(&repo, entry.hash().to_string());
+        let version_store = repo.version_store()?;
+        let version_path = version_store.get_version_path(&entry.hash().to_string())?;
 
         // TODO: refactor out of here and check for type,
         // but seeing if it works to resize the image and cache it to disk if we have a resize query
@@ -83,12 +93,31 @@ pub async fn get(
             version_path
         );
 
-        let file = NamedFile::open(version_path)?;
+        // The content hash is a strong validator, it changes exactly when the bytes do
+        let etag = header::EntityTag::new_strong(entry.hash().to_string());
+        let last_commit_id = entry.last_commit_id().to_string();
+        let is_cached = match req.get_header::<header::IfNoneMatch>() {
+            Some(header::IfNoneMatch::Any) => true,
+            Some(header::IfNoneMatch::Items(items)) => items.iter().any(|i| i.weak_eq(&etag)),
+            None => false,
+        };
+        if is_cached {
+            return Ok(HttpResponse::NotModified()
+                .insert_header(header::ETag(etag))
+                .insert_header(("oxen-revision-id", last_commit_id))
+                .finish());
+        }
+
+        // NamedFile answers Range requests with 206 Partial Content
+        let file = NamedFile::open(version_path)?.use_etag(false);
         let mut response = file.into_response(&req);
 
-        let last_commit_id = entry.last_commit_id().to_string();
         let meta_entry = repositories::entries::get_meta_entry(&repo, &commit, &path)?;
         let content_length = meta_entry.size.to_string();
+        response.headers_mut().insert(
+            header::ETAG,
+            header::HeaderValue::from_str(&etag.to_string()).unwrap(),
+        );
         response.headers_mut().insert(
             header::HeaderName::from_static("oxen-revision-id"),
             header::HeaderValue::from_str(&last_commit_id).unwrap(),
@@ -99,10 +128,13 @@ pub async fn get(
             header::HeaderValue::from_str(&meta_entry.mime_type).unwrap(),
         );
 
-        response.headers_mut().insert(
-            header::CONTENT_LENGTH,
-            header::HeaderValue::from_str(&content_length).unwrap(),
-        );
+        // Partial responses already carry the length of the range
+        if response.status() == StatusCode::OK {
+            response.headers_mut().insert(
+                header::CONTENT_LENGTH,
+                header::HeaderValue::from_str(&content_length).unwrap(),
+            );
+        }
 
         response
     };
@@ -154,6 +186,33 @@ pub async fn put(
         ));
     }
 
+    // The revision the client started editing from, to detect changes made on the branch since
+    let based_on = match req.headers().get(BASED_ON_HEADER) {
+        Some(value) => {
+            let revision = value.to_str().map_err(|_| {
+                OxenHttpError::BadRequest(format!("Invalid {BASED_ON_HEADER} header").into())
+            })?;
+            let based_on =
+                repositories::revisions::get(&repo, revision)?.ok_or(OxenHttpError::BadRequest(
+                    format!("{BASED_ON_HEADER} revision not found: {revision}").into(),
+                ))?;
+            Some(based_on)
+        }
+        None => None,
+    };
+    let merge_on_conflict = match req.headers().get(ON_CONFLICT_HEADER) {
+        Some(value) => match value.to_str() {
+            Ok("merge") => true,
+            Ok("fail") => false,
+            _ => {
+                return Err(OxenHttpError::BadRequest(
+                    format!("{ON_CONFLICT_HEADER} must be \"merge\" or \"fail\"").into(),
+                ))
+            }
+        },
+        None => false,
+    };
+
     let (name, email, message, temp_files) = parse_multipart_fields(payload).await?;
 
     let user = create_user_from_options(name.clone(), email.clone())?;
@@ -168,6 +227,57 @@ pub async fn put(
     }
     let workspace = repositories::workspaces::create_temporary(&repo, &commit)?;
 
+    if let Some(based_on) = &based_on {
+        for file in files.iter_mut() {
+            let path = resource.path.join(&file.path);
+            let original = repositories::entries::get_file(&repo, based_on, &path)?;
+            let current = repositories::entries::get_file(&repo, &commit, &path)?;
+            let changed = match (&original, &current) {
+                (Some(original), Some(current)) => original.hash() != current.hash(),
+                (None, None) => false,
+                _ => true,
+            };
+            if !changed {
+                continue;
+            }
+
+            // Tabular edits can be merged row by row into the current version of the file
+            let mut row_conflicts = vec![];
+            let mergeable = merge_on_conflict && util::fs::is_tabular(&path);
+            if let Some(current) = current.as_ref().filter(|_| mergeable) {
+                let edited_path = workspace.dir().join(&path);
+                write_file_contents(&edited_path, &file.contents)?;
+                let result = tabular_merge::merge_edited_file(
+                    &repo,
+                    &path,
+                    original.as_ref(),
+                    current,
+                    &edited_path,
+                )?;
+                if result.conflicts.is_empty() {
+                    log::debug!("file::put merged {:?} onto {}", path, commit.id);
+                    let merged = util::fs::read_bytes_from_path(&edited_path)?;
+                    file.contents = FileContents::Binary(merged);
+                    continue;
+                }
+                row_conflicts = result.conflicts;
+            }
+
+            return Ok(HttpResponse::Conflict().json(FileConflictResponse {
+                status: StatusMessage::error(format!(
+                    "{} was changed since {}",
+                    path.display(),
+                    based_on.id
+                )),
+                path: path.to_string_lossy().to_string(),
+                based_on: based_on.id.clone(),
+                current_revision: current.map(|node| node.last_commit_id().to_string()),
+                head_commit_id: commit.id.clone(),
+                row_conflicts,
+            }));
+        }
+    }
+
     process_and_add_files(
         &repo,
         Some(&workspace),
@@ -186,7 +296,14 @@ pub async fn put(
         )),
     };
 
-    let commit = repositories::workspaces::commit(&workspace, &commit_body, branch.name)?;
+    let pusher = get_user(app_data, &req)?;
+    let _guard = merge_queue::enqueue(&repo.path, &branch.name).await;
+    let commit = repositories::workspaces::commit_as(
+        &workspace,
+        &commit_body,
+        branch.name,
+        pusher.as_ref(),
+    )?;
 
     log::debug!("file::put workspace commit ✅ success! commit {:?}", commit);
 
@@ -349,7 +466,14 @@ pub async fn import(
         ),
     };
 
-    let commit = repositories::workspaces::commit(&workspace, &commit_body, branch.name)?;
+    let pusher = get_user(app_data, &req)?;
+    let _guard = merge_queue::enqueue(&repo.path, &branch.name).await;
+    let commit = repositories::workspaces::commit_as(
+        &workspace,
+        &commit_body,
+        branch.name,
+        pusher.as_ref(),
+    )?;
     log::debug!("workspace::commit ✅ success! commit {:?}", commit);
 
     Ok(HttpResponse::Ok().json(CommitResponse {
@@ -479,15 +603,7 @@ async fn process_and_add_files(
             }
 
             let filepath = full_dir.join(path);
-
-            match contents {
-                FileContents::Text(text) => {
-                    util::fs::write(&filepath, text.as_bytes())?;
-                }
-                FileContents::Binary(bytes) => {
-                    util::fs::write(&filepath, bytes)?;
-                }
-            }
+            write_file_contents(&filepath, contents)?;
 
             if let Some(ws) = workspace {
                 repositories::workspaces::files::add(ws, &filepath).await?;
@@ -499,15 +615,29 @@ async fn process_and_add_files(
     Ok(())
 }
 
+fn write_file_contents(path: &Path, contents: &FileContents) -> Result<(), OxenError> {
+    if let Some(parent) = path.parent() {
+        util::fs::create_dir_all(parent)?;
+    }
+    match contents {
+        FileContents::Text(text) => util::fs::write(path, text.as_bytes()),
+        FileContents::Binary(bytes) => util::fs::write(path, bytes),
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use std::path::PathBuf;
+    use std::path::{Path, PathBuf};
 
     use actix_multipart_test::MultiPartFormDataBuilder;
+    use actix_web::http::{header, StatusCode};
     use actix_web::{web, App};
+    use liboxen::view::merge::FileConflictResponse;
     use liboxen::view::CommitResponse;
 
+    use liboxen::core::df::tabular;
     use liboxen::error::OxenError;
+    use liboxen::opts::DFOpts;
     use liboxen::repositories;
     use liboxen::util;
 
@@ -580,6 +710,247 @@ mod tests {
         Ok(())
     }
 
+    async fn put_file(
+        sync_dir: &Path,
+        namespace: &str,
+        repo_name: &str,
+        upload: PathBuf,
+        filename: &str,
+        headers: &[(&str, &str)],
+    ) -> (StatusCode, String) {
+        let mut multipart_form_data_builder = MultiPartFormDataBuilder::new();
+        multipart_form_data_builder.with_file(upload, "file", "text/plain", filename);
+        multipart_form_data_builder.with_text("name", "some_name");
+        multipart_form_data_builder.with_text("email", "some_email");
+        let (header, body) = multipart_form_data_builder.build();
+        let uri = format!("/oxen/{namespace}/{repo_name}/file/main/data");
+        let mut req = actix_web::test::TestRequest::put()
+            .uri(&uri)
+            .insert_header(header);
+        for header in headers {
+            req = req.insert_header(*header);
+        }
+        let req = req.set_payload(body).to_request();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(OxenAppData::new(sync_dir.to_path_buf()))
+                .route(
+                    "/oxen/{namespace}/{repo_name}/file/{resource:.*}",
+                    web::put().to(controllers::file::put),
+                ),
+        )
+        .await;
+
+        let resp = actix_web::test::call_service(&app, req).await;
+        let status = resp.status();
+        let bytes = actix_http::body::to_bytes(resp.into_body()).await.unwrap();
+        (status, String::from_utf8(bytes.to_vec()).unwrap())
+    }
+
+    #[actix_web::test]
+    async fn test_controllers_file_put_conflicts_with_newer_revision() -> Result<(), OxenError> {
+        test::init_test_env();
+        let sync_dir = test::get_sync_dir()?;
+        let namespace = "Testing-Namespace";
+        let repo_name = "Testing-Name";
+        let repo = test::create_local_repo(&sync_dir, namespace, repo_name)?;
+        util::fs::create_dir_all(repo.path.join("data"))?;
+        let hello_file = repo.path.join("data/hello.txt");
+        util::fs::write_to_path(&hello_file, "Hello")?;
+        repositories::add(&repo, &hello_file).await?;
+        let first_commit = repositories::commit(&repo, "First commit")?;
+        util::fs::write_to_path(&hello_file, "Hello from the branch")?;
+        repositories::add(&repo, &hello_file).await?;
+        let second_commit = repositories::commit(&repo, "Second commit")?;
+
+        // Edited starting from the first commit, but the file changed since
+        let upload = sync_dir.join("hello.txt");
+        util::fs::write_to_path(&upload, "Hello from the client")?;
+        let (status, body) = put_file(
+            &sync_dir,
+            namespace,
+            repo_name,
+            upload.clone(),
+            "hello.txt",
+            &[("oxen-based-on", first_commit.id.as_str())],
+        )
+        .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        let resp: FileConflictResponse = serde_json::from_str(&body)?;
+        assert_eq!(resp.path, "data/hello.txt");
+        assert_eq!(resp.based_on, first_commit.id);
+        assert_eq!(resp.current_revision, Some(second_commit.id.clone()));
+        assert_eq!(resp.head_commit_id, second_commit.id);
+
+        // Text files cannot be merged
+        let (status, _) = put_file(
+            &sync_dir,
+            namespace,
+            repo_name,
+            upload.clone(),
+            "hello.txt",
+            &[
+                ("oxen-based-on", first_commit.id.as_str()),
+                ("oxen-on-conflict", "merge"),
+            ],
+        )
+        .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        let (status, _) = put_file(
+            &sync_dir,
+            namespace,
+            repo_name,
+            upload.clone(),
+            "hello.txt",
+            &[("oxen-based-on", "not-a-revision")],
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        // Based on the latest revision succeeds
+        let (status, body) = put_file(
+            &sync_dir,
+            namespace,
+            repo_name,
+            upload,
+            "hello.txt",
+            &[("oxen-based-on", second_commit.id.as_str())],
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let resp: CommitResponse = serde_json::from_str(&body)?;
+        let entry =
+            repositories::entries::get_file(&repo, &resp.commit, PathBuf::from("data/hello.txt"))?
+                .unwrap();
+        let version_path = util::fs::version_path_from_hash(&repo, entry.hash().to_string());
+        assert_eq!(
+            util::fs::read_from_path(&version_path)?,
+            "Hello from the client"
+        );
+
+        // cleanup
+        test::cleanup_sync_dir(&sync_dir)?;
+
+        Ok(())
+    }
+
+    #[actix_web::test]
+    async fn test_controllers_file_put_merges_tabular_edits() -> Result<(), OxenError> {
+        test::init_test_env();
+        let sync_dir = test::get_sync_dir()?;
+        let namespace = "Testing-Namespace";
+        let repo_name = "Testing-Name";
+        let repo = test::create_local_repo(&sync_dir, namespace, repo_name)?;
+        util::fs::create_dir_all(repo.path.join("data"))?;
+        let labels_file = repo.path.join("data/labels.csv");
+        util::fs::write_to_path(&labels_file, "file,label\na.jpg,cat\nb.jpg,dog\n")?;
+        repositories::add(&repo, &labels_file).await?;
+        let first_commit = repositories::commit(&repo, "First commit")?;
+        util::fs::write_to_path(
+            &labels_file,
+            "file,label\na.jpg,cat\nb.jpg,dog\nc.jpg,cat\n",
+        )?;
+        repositories::add(&repo, &labels_file).await?;
+        repositories::commit(&repo, "Add c.jpg")?;
+
+        // The client appended a different row starting from the first commit
+        let upload = sync_dir.join("labels.csv");
+        util::fs::write_to_path(&upload, "file,label\na.jpg,cat\nb.jpg,dog\nd.jpg,dog\n")?;
+        let (status, body) = put_file(
+            &sync_dir,
+            namespace,
+            repo_name,
+            upload,
+            "labels.csv",
+            &[
+                ("oxen-based-on", first_commit.id.as_str()),
+                ("oxen-on-conflict", "merge"),
+            ],
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let resp: CommitResponse = serde_json::from_str(&body)?;
+
+        let entry =
+            repositories::entries::get_file(&repo, &resp.commit, PathBuf::from("data/labels.csv"))?
+                .unwrap();
+        let version_path = util::fs::version_path_from_hash(&repo, entry.hash().to_string());
+        let df = tabular::read_df_with_extension(version_path, "csv", &DFOpts::empty())?;
+        assert_eq!(df.height(), 4);
+
+        // cleanup
+        test::cleanup_sync_dir(&sync_dir)?;
+
+        Ok(())
+    }
+
+    #[actix_web::test]
+    async fn test_controllers_file_get_range_and_etag() -> Result<(), OxenError> {
+        test::init_test_env();
+        let sync_dir = test::get_sync_dir()?;
+        let namespace = "Testing-Namespace";
+        let repo_name = "Testing-Name";
+        let repo = test::create_local_repo(&sync_dir, namespace, repo_name)?;
+        util::fs::create_dir_all(repo.path.join("data"))?;
+        let hello_file = repo.path.join("data/hello.txt");
+        util::fs::write_to_path(&hello_file, "Hello World")?;
+        repositories::add(&repo, &hello_file).await?;
+        let commit = repositories::commit(&repo, "First commit")?;
+        let entry = repositories::entries::get_file(&repo, &commit, "data/hello.txt")?.unwrap();
+        let etag = format!("\"{}\"", entry.hash());
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(OxenAppData::new(sync_dir.clone()))
+                .route(
+                    "/oxen/{namespace}/{repo_name}/file/{resource:.*}",
+                    web::get().to(controllers::file::get),
+                ),
+        )
+        .await;
+        let uri = format!("/oxen/{namespace}/{repo_name}/file/main/data/hello.txt");
+
+        // Seek into the file
+        let req = actix_web::test::TestRequest::get()
+            .uri(&uri)
+            .insert_header((header::RANGE, "bytes=6-10"))
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(
+            resp.headers().get(header::CONTENT_RANGE).unwrap(),
+            "bytes 6-10/11"
+        );
+        assert_eq!(resp.headers().get(header::ETAG).unwrap(), etag.as_str());
+        let bytes = actix_http::body::to_bytes(resp.into_body()).await.unwrap();
+        assert_eq!(bytes, "World");
+
+        // Revalidate a cached copy
+        let req = actix_web::test::TestRequest::get()
+            .uri(&uri)
+            .insert_header((header::IF_NONE_MATCH, etag.as_str()))
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_MODIFIED);
+
+        // A stale copy gets the full contents
+        let req = actix_web::test::TestRequest::get()
+            .uri(&uri)
+            .insert_header((header::IF_NONE_MATCH, "\"stale\""))
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let bytes = actix_http::body::to_bytes(resp.into_body()).await.unwrap();
+        assert_eq!(bytes, "Hello World");
+
+        // cleanup
+        test::cleanup_sync_dir(&sync_dir)?;
+
+        Ok(())
+    }
+
     #[actix_web::test]
     async fn test_controllers_file_import() -> Result<(), OxenError> {
         test::init_test_env();
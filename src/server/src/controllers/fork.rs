@@ -2,7 +2,9 @@ use crate::errors::OxenHttpError;
 use crate::helpers::get_repo;
 use crate::params::{app_data, path_param};
 use actix_web::{web, HttpRequest, HttpResponse, Result};
+use liboxen::api::endpoint::API_NAMESPACE;
 use liboxen::error::OxenError;
+use liboxen::opts::ForkOpts;
 use liboxen::repositories;
 use liboxen::view::fork::ForkRequest;
 use liboxen::view::StatusMessage;
@@ -22,11 +24,19 @@ pub async fn fork(
 
     let new_repo_name = body.new_repo_name.clone().unwrap_or(repo_name.clone());
 
-    let new_repo_path = app_data.path.join(&new_repo_namespace).join(&new_repo_name);
+    let new_repo_path =
+        repositories::fork::destination_path(&app_data.path, &new_repo_namespace, &new_repo_name)?;
 
-    match repositories::fork::start_fork(original_repo.path, new_repo_path.clone()) {
-        Ok(fork_start_response) => {
+    let opts = ForkOpts {
+        mode: body.mode,
+        resume: body.resume,
+    };
+    match repositories::fork::start_fork(original_repo.path.clone(), new_repo_path.clone(), &opts) {
+        Ok(mut fork_start_response) => {
             log::info!("Successfully forked repository to {:?}", &new_repo_path);
+            fork_start_response.status_url = Some(format!(
+                "{API_NAMESPACE}/{new_repo_namespace}/{new_repo_name}/fork/status"
+            ));
             Ok(HttpResponse::Accepted().json(fork_start_response))
         }
         Err(OxenError::RepoAlreadyExistsAtDestination(path)) => {
@@ -48,7 +58,7 @@ pub async fn get_status(req: HttpRequest) -> Result<HttpResponse, OxenHttpError>
 
     log::debug!("Getting fork status for repo: {}/{}", namespace, repo_name);
 
-    let repo_path = app_data.path.join(&namespace).join(&repo_name);
+    let repo_path = repositories::fork::destination_path(&app_data.path, &namespace, &repo_name)?;
 
     match repositories::fork::get_fork_status(&repo_path) {
         Ok(status) => Ok(HttpResponse::Ok().json(status)),
@@ -61,3 +71,25 @@ pub async fn get_status(req: HttpRequest) -> Result<HttpResponse, OxenHttpError>
         }
     }
 }
+
+/// Stop the fork into this repository, it can be resumed by forking again with `resume`
+pub async fn cancel(req: HttpRequest) -> Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let repo_name = path_param(&req, "repo_name")?;
+
+    log::debug!("Cancelling fork into repo: {}/{}", namespace, repo_name);
+
+    let repo_path = repositories::fork::destination_path(&app_data.path, &namespace, &repo_name)?;
+
+    match repositories::fork::cancel_fork(&repo_path) {
+        Ok(status) => Ok(HttpResponse::Ok().json(status)),
+        Err(OxenError::ForkStatusNotFound(_)) => {
+            Ok(HttpResponse::NotFound().json(StatusMessage::error("Fork status not found")))
+        }
+        Err(e) => {
+            log::error!("Failed to cancel fork: {}", e);
+            Err(OxenHttpError::from(e))
+        }
+    }
+}
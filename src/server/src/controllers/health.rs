@@ -1,8 +1,9 @@
 use crate::errors::OxenHttpError;
 use crate::params::app_data;
 use actix_web::{HttpRequest, HttpResponse};
+use liboxen::model::merkle_tree::merkle_tree_node_cache;
 use liboxen::util;
-use liboxen::view::{HealthResponse, StatusMessage};
+use liboxen::view::{HealthResponse, MerkleCacheMetrics, MetricsResponse, StatusMessage};
 
 pub async fn index(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
     let app_data = app_data(&req)?;
@@ -20,3 +21,16 @@ pub async fn index(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttp
         }
     }
 }
+
+pub async fn metrics(_req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let (repositories, stats) = merkle_tree_node_cache::total_stats();
+    let response = MetricsResponse {
+        status: StatusMessage::resource_found(),
+        merkle_cache: MerkleCacheMetrics {
+            enabled: merkle_tree_node_cache::is_enabled(),
+            repositories,
+            stats,
+        },
+    };
+    Ok(HttpResponse::Ok().json(response))
+}
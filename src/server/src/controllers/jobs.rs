@@ -0,0 +1,219 @@
+use crate::errors::OxenHttpError;
+use crate::helpers::{get_admin, get_repo};
+use crate::params::{app_data, path_param};
+
+use actix_web::{HttpRequest, HttpResponse};
+
+use liboxen::error::OxenError;
+use liboxen::model::{Job, LocalRepository};
+use liboxen::repositories;
+use liboxen::view::{
+    JobNew, JobResponse, JobRunResponse, ListJobRunsResponse, ListJobsResponse, StatusMessage,
+};
+
+fn get_job(repo: &LocalRepository, job_id: &str) -> Result<Job, OxenHttpError> {
+    repositories::jobs::get(repo, job_id)?
+        .ok_or(OxenError::resource_not_found(format!("job {job_id}")).into())
+}
+
+/// The jobs of the repository with when they run next and how their latest run went
+pub async fn index(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let repo = get_repo(&app_data.path, namespace, name)?;
+
+    let jobs = repositories::jobs::list(&repo)?
+        .into_iter()
+        .map(|job| repositories::jobs::status(&repo, job))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(HttpResponse::Ok().json(ListJobsResponse {
+        status: StatusMessage::resource_found(),
+        jobs,
+    }))
+}
+
+/// Jobs run as the server, so only admins can create them
+pub async fn create(req: HttpRequest, body: String) -> Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let admin = get_admin(app_data, &req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let repo = get_repo(&app_data.path, namespace, name)?;
+
+    let data: JobNew = serde_json::from_str(&body)
+        .map_err(|err| OxenHttpError::BadRequest(format!("{:?}", err).into()))?;
+    log::debug!(
+        "Create job {} on {:?}: {}",
+        data.name,
+        data.schedule,
+        data.action
+    );
+
+    let job = repositories::jobs::add(&repo, &data, &admin)
+        .map_err(|err| OxenHttpError::BadRequest(format!("{err}").into()))?;
+
+    Ok(HttpResponse::Ok().json(JobResponse {
+        status: StatusMessage::resource_created(),
+        job: repositories::jobs::status(&repo, job)?,
+    }))
+}
+
+pub async fn show(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let job_id = path_param(&req, "job_id")?;
+    let repo = get_repo(&app_data.path, namespace, name)?;
+
+    let job = get_job(&repo, &job_id)?;
+
+    Ok(HttpResponse::Ok().json(JobResponse {
+        status: StatusMessage::resource_found(),
+        job: repositories::jobs::status(&repo, job)?,
+    }))
+}
+
+pub async fn delete(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let job_id = path_param(&req, "job_id")?;
+    let repo = get_repo(&app_data.path, namespace, name)?;
+
+    repositories::jobs::remove(&repo, &job_id)?;
+
+    Ok(HttpResponse::Ok().json(StatusMessage::resource_deleted()))
+}
+
+/// The run history of a job, newest first
+pub async fn runs(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let job_id = path_param(&req, "job_id")?;
+    let repo = get_repo(&app_data.path, namespace, name)?;
+
+    let job = get_job(&repo, &job_id)?;
+    let runs = repositories::jobs::runs(&repo, &job.id)?;
+
+    Ok(HttpResponse::Ok().json(ListJobRunsResponse {
+        status: StatusMessage::resource_found(),
+        runs,
+    }))
+}
+
+/// Start a run of the job now. The run continues in the background, poll the run history
+/// for how it went.
+pub async fn run(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let job_id = path_param(&req, "job_id")?;
+    let repo = get_repo(&app_data.path, namespace, name)?;
+
+    let job = get_job(&repo, &job_id)?;
+    let run = repositories::jobs::start(&repo, &job)
+        .map_err(|err| OxenHttpError::BadRequest(format!("{err}").into()))?;
+
+    let started = run.clone();
+    actix_web::rt::spawn(async move {
+        if let Err(err) = repositories::jobs::finish(&repo, &job, started).await {
+            log::error!("Could not record the run of job {}: {}", job.name, err);
+        }
+    });
+
+    Ok(HttpResponse::Ok().json(JobRunResponse {
+        status: StatusMessage::resource_created(),
+        run,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::http::{header, StatusCode};
+    use actix_web::{web, App};
+
+    use liboxen::constants::DEFAULT_BRANCH_NAME;
+    use liboxen::error::OxenError;
+    use liboxen::model::{JobAction, User};
+    use liboxen::repositories;
+    use liboxen::util;
+    use liboxen::view::JobNew;
+
+    use crate::app_data::OxenAppData;
+    use crate::auth::access_keys::AccessKeyManager;
+    use crate::controllers;
+    use crate::helpers::ADMIN_EMAILS_ENV;
+    use crate::test;
+
+    #[actix_web::test]
+    async fn test_controllers_jobs_create_requires_admin() -> Result<(), OxenError> {
+        test::init_test_env();
+        let sync_dir = test::get_sync_dir()?;
+        let namespace = "Testing-Namespace";
+        let repo_name = "Testing-Name";
+        let repo = test::create_local_repo(&sync_dir, namespace, repo_name)?;
+        let path = repo.path.join("README.md");
+        util::fs::write_to_path(&path, "# Dataset\n")?;
+        repositories::add(&repo, &path).await?;
+        repositories::commit(&repo, "Adding readme")?;
+
+        std::env::set_var(ADMIN_EMAILS_ENV, "ox@oxen.ai");
+        let keygen = AccessKeyManager::new(&sync_dir)?;
+        let (_, ox_token) = keygen.create(&User {
+            name: "Ox".to_string(),
+            email: "ox@oxen.ai".to_string(),
+        })?;
+        let (_, bo_token) = keygen.create(&User {
+            name: "Bo".to_string(),
+            email: "bo@oxen.ai".to_string(),
+        })?;
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(OxenAppData::new(sync_dir.clone()))
+                .route(
+                    "/oxen/{namespace}/{repo_name}/jobs",
+                    web::post().to(controllers::jobs::create),
+                ),
+        )
+        .await;
+        let uri = format!("/oxen/{namespace}/{repo_name}/jobs");
+        let body = serde_json::to_string(&JobNew {
+            name: "nightly validation".to_string(),
+            schedule: "0 3 * * *".to_string(),
+            action: JobAction::Validate {
+                branch: DEFAULT_BRANCH_NAME.to_string(),
+            },
+            enabled: None,
+        })?;
+
+        for token in [None, Some(bo_token)] {
+            let mut req = actix_web::test::TestRequest::post().uri(&uri);
+            if let Some(token) = token {
+                req = req.insert_header((header::AUTHORIZATION, format!("Bearer {token}")));
+            }
+            let resp =
+                actix_web::test::call_service(&app, req.set_payload(body.clone()).to_request())
+                    .await;
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        }
+        assert!(repositories::jobs::list(&repo)?.is_empty());
+
+        let req = actix_web::test::TestRequest::post()
+            .uri(&uri)
+            .insert_header((header::AUTHORIZATION, format!("Bearer {ox_token}")))
+            .set_payload(body)
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let jobs = repositories::jobs::list(&repo)?;
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].created_by.as_ref().unwrap().email, "ox@oxen.ai");
+
+        test::cleanup_sync_dir(&sync_dir)?;
+        Ok(())
+    }
+}
@@ -1,13 +1,16 @@
 use crate::errors::OxenHttpError;
-use crate::helpers::get_repo;
+use crate::helpers::{get_repo, get_user};
+use crate::merge_queue;
 use crate::params::{app_data, parse_base_head, path_param, resolve_base_head_branches};
 
 use actix_web::{HttpRequest, HttpResponse};
 
 use liboxen::error::OxenError;
+use liboxen::model::{LocalRepository, User};
 use liboxen::repositories;
 use liboxen::view::merge::{
-    MergeConflictFile, MergeResult, MergeSuccessResponse, Mergeable, MergeableResponse,
+    MergeBranches, MergeConflictFile, MergeResult, MergeSuccessResponse, Mergeable,
+    MergeableResponse,
 };
 use liboxen::view::StatusMessage;
 
@@ -64,18 +67,56 @@ pub async fn merge(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttp
 
     // Parse the base and head from the base..head string
     let (base, head) = parse_base_head(&base_head)?;
-    let (maybe_base_branch, maybe_head_branch) = resolve_base_head_branches(&repo, &base, &head)?;
+    let pusher = get_user(app_data, &req)?;
+    merge_branches(&repo, &base, &head, false, pusher.as_ref()).await
+}
+
+/// Merge the branches named in the body, optionally waiting in the merge queue for `base`
+pub async fn merge_from_body(
+    req: HttpRequest,
+    body: String,
+) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let repo = get_repo(&app_data.path, namespace, name)?;
+
+    let data: MergeBranches = serde_json::from_str(&body)
+        .map_err(|err| OxenHttpError::BadRequest(format!("Invalid merge request: {err}").into()))?;
+    let pusher = get_user(app_data, &req)?;
+    merge_branches(&repo, &data.base, &data.head, data.queue, pusher.as_ref()).await
+}
+
+async fn merge_branches(
+    repo: &LocalRepository,
+    base: &str,
+    head: &str,
+    queue: bool,
+    pusher: Option<&User>,
+) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    // Protected branches always take merges one at a time
+    let queue = queue || repositories::branches::protection::get(repo, base)?.is_some();
+    let _turn = if queue {
+        Some(merge_queue::enqueue(&repo.path, base).await)
+    } else {
+        None
+    };
+
+    // Resolve the branches after waiting, earlier merges may have moved base
+    let (maybe_base_branch, maybe_head_branch) = resolve_base_head_branches(repo, base, head)?;
     let base_branch =
-        maybe_base_branch.ok_or(OxenError::revision_not_found(base.clone().into()))?;
+        maybe_base_branch.ok_or(OxenError::revision_not_found(base.to_string().into()))?;
     let head_branch =
-        maybe_head_branch.ok_or(OxenError::revision_not_found(head.clone().into()))?;
+        maybe_head_branch.ok_or(OxenError::revision_not_found(head.to_string().into()))?;
 
     // .unwrap() safe because branches must have commits
-    let base_commit = repositories::commits::get_by_id(&repo, &base_branch.commit_id)?.unwrap();
-    let head_commit = repositories::commits::get_by_id(&repo, &head_branch.commit_id)?.unwrap();
+    let base_commit = repositories::commits::get_by_id(repo, &base_branch.commit_id)?.unwrap();
+    let head_commit = repositories::commits::get_by_id(repo, &head_branch.commit_id)?.unwrap();
+    // Owned paths reach protected branches through approved proposals, or from their owners
+    repositories::owners::check_push(repo, &base_branch, &head_commit, pusher)?;
 
     // Check if mergeable
-    match repositories::merge::merge_into_base(&repo, &head_branch, &base_branch).await {
+    match repositories::merge::merge_into_base(repo, &head_branch, &base_branch).await {
         Ok(Some(merge_commit)) => {
             let response = MergeSuccessResponse {
                 status: StatusMessage::resource_found(),
@@ -0,0 +1,81 @@
+use crate::errors::OxenHttpError;
+use crate::params::{app_data, path_param};
+
+use actix_web::{HttpRequest, HttpResponse};
+
+use liboxen::model::MirrorState;
+use liboxen::repositories;
+use liboxen::repositories::webhooks::SIGNATURE_HEADER;
+use liboxen::view::{ListMirrorsResponse, MirrorResponse, StatusMessage};
+
+pub async fn index(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+
+    let mirrors = match &app_data.mirror_config {
+        Some(config) => repositories::mirror::list(&app_data.path, config)?,
+        None => vec![],
+    };
+
+    Ok(HttpResponse::Ok().json(ListMirrorsResponse {
+        status: StatusMessage::resource_found(),
+        mirrors,
+    }))
+}
+
+/// Start a sync of the mirror in the background, meant to be the url of a webhook on the
+/// upstream repository. The body must be signed with the mirror's secret, this route has no
+/// bearer auth so mirrors without a secret can't be synced through it.
+pub async fn sync(
+    req: HttpRequest,
+    body: String,
+) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+
+    let Some(mirror) = app_data
+        .mirror_config
+        .as_ref()
+        .and_then(|config| config.get(&namespace, &name))
+    else {
+        return Err(OxenHttpError::NotFound);
+    };
+
+    let Some(secret) = &mirror.secret else {
+        log::warn!("Rejected mirror sync of {namespace}/{name}, the mirror has no secret");
+        return Ok(HttpResponse::Unauthorized().json(StatusMessage::error(
+            "Mirror has no secret to sign webhooks with",
+        )));
+    };
+    let signature = req
+        .headers()
+        .get(SIGNATURE_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    if !repositories::webhooks::verify(secret, body.as_bytes(), signature) {
+        log::warn!("Rejected mirror sync of {namespace}/{name}, bad signature");
+        return Ok(HttpResponse::Unauthorized().json(StatusMessage::error("Invalid signature")));
+    }
+
+    let status = repositories::mirror::get_status(&app_data.path, mirror)?;
+    if status.state == MirrorState::Syncing {
+        log::debug!("Mirror {namespace}/{name} is already syncing");
+        return Ok(HttpResponse::Accepted().json(MirrorResponse {
+            status: StatusMessage::resource_found(),
+            mirror: status,
+        }));
+    }
+
+    let sync_dir = app_data.path.clone();
+    let mirror = mirror.clone();
+    actix_web::rt::spawn(async move {
+        if let Err(err) = repositories::mirror::sync(&sync_dir, &mirror).await {
+            log::error!("Mirror sync of {} failed: {}", mirror.url, err);
+        }
+    });
+
+    Ok(HttpResponse::Accepted().json(MirrorResponse {
+        status: StatusMessage::resource_found(),
+        mirror: status,
+    }))
+}
@@ -0,0 +1,421 @@
+use crate::errors::OxenHttpError;
+use crate::helpers::{get_repo, get_user};
+use crate::merge_queue;
+use crate::params::{app_data, path_param, PageNumQuery};
+
+use actix_web::{web, HttpRequest, HttpResponse};
+use serde::Deserialize;
+
+use liboxen::constants;
+use liboxen::error::OxenError;
+use liboxen::model::{LocalRepository, MergeProposal, MergeProposalStatus};
+use liboxen::repositories;
+use liboxen::view::{
+    ListMergeProposalsResponse, MergeProposalApprovalNew, MergeProposalChecksResponse,
+    MergeProposalCommentNew, MergeProposalCommentResponse, MergeProposalFilesResponse,
+    MergeProposalNew, MergeProposalResponse, MergeProposalThreadsResponse, StatusMessage,
+};
+
+#[derive(Deserialize, Debug)]
+pub struct ProposalStatusQuery {
+    /// Only list the proposals that are open, merged or closed
+    pub status: Option<String>,
+}
+
+fn proposal_id(req: &HttpRequest) -> Result<u64, OxenHttpError> {
+    let id = path_param(req, "proposal_id")?;
+    id.parse::<u64>()
+        .map_err(|_| OxenHttpError::BadRequest(format!("Invalid proposal id {id:?}").into()))
+}
+
+fn get_proposal(repo: &LocalRepository, id: u64) -> Result<MergeProposal, OxenHttpError> {
+    Ok(repositories::proposals::get(repo, id)?
+        .ok_or(OxenError::resource_not_found(format!("proposal {id}")))?)
+}
+
+pub async fn index(
+    req: HttpRequest,
+    query: web::Query<ProposalStatusQuery>,
+) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let repo = get_repo(&app_data.path, namespace, name)?;
+
+    let status = match &query.status {
+        Some(status) => Some(status.parse::<MergeProposalStatus>()?),
+        None => None,
+    };
+    let proposals = repositories::proposals::list(&repo, status)?;
+
+    Ok(HttpResponse::Ok().json(ListMergeProposalsResponse {
+        status: StatusMessage::resource_found(),
+        proposals,
+    }))
+}
+
+pub async fn create(req: HttpRequest, body: String) -> Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let repo = get_repo(&app_data.path, namespace, name)?;
+
+    let data: MergeProposalNew = serde_json::from_str(&body)
+        .map_err(|err| OxenHttpError::BadRequest(format!("{:?}", err).into()))?;
+    log::debug!("Create proposal to merge {} into {}", data.head, data.base);
+
+    let proposal = repositories::proposals::create(&repo, &data)?;
+
+    Ok(HttpResponse::Ok().json(MergeProposalResponse {
+        status: StatusMessage::resource_created(),
+        proposal,
+    }))
+}
+
+pub async fn show(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let repo = get_repo(&app_data.path, namespace, name)?;
+    let proposal = get_proposal(&repo, proposal_id(&req)?)?;
+
+    Ok(HttpResponse::Ok().json(MergeProposalResponse {
+        status: StatusMessage::resource_found(),
+        proposal,
+    }))
+}
+
+/// The files the proposal changes, compared to where head split from base
+pub async fn files(
+    req: HttpRequest,
+    query: web::Query<PageNumQuery>,
+) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let repo = get_repo(&app_data.path, namespace, name)?;
+    let proposal = get_proposal(&repo, proposal_id(&req)?)?;
+
+    let page = query.page.unwrap_or(constants::DEFAULT_PAGE_NUM);
+    let page_size = query.page_size.unwrap_or(constants::DEFAULT_PAGE_SIZE);
+    let changes = repositories::proposals::changes(&repo, &proposal, page, page_size)?;
+
+    Ok(HttpResponse::Ok().json(MergeProposalFilesResponse {
+        status: StatusMessage::resource_found(),
+        counts: changes.counts,
+        entries: changes.entries,
+        pagination: changes.pagination,
+    }))
+}
+
+pub async fn mergeable(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let repo = get_repo(&app_data.path, namespace, name)?;
+    let proposal = get_proposal(&repo, proposal_id(&req)?)?;
+
+    let checks = repositories::proposals::check(&repo, &proposal).await?;
+
+    Ok(HttpResponse::Ok().json(MergeProposalChecksResponse {
+        status: StatusMessage::resource_found(),
+        checks,
+    }))
+}
+
+/// The comments on the proposal, grouped into a thread per file and row
+pub async fn comments(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let repo = get_repo(&app_data.path, namespace, name)?;
+    let proposal = get_proposal(&repo, proposal_id(&req)?)?;
+
+    Ok(HttpResponse::Ok().json(MergeProposalThreadsResponse {
+        status: StatusMessage::resource_found(),
+        threads: proposal.threads(),
+    }))
+}
+
+pub async fn comment(req: HttpRequest, body: String) -> Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let repo = get_repo(&app_data.path, namespace, name)?;
+    let id = proposal_id(&req)?;
+
+    let data: MergeProposalCommentNew = serde_json::from_str(&body)
+        .map_err(|err| OxenHttpError::BadRequest(format!("{:?}", err).into()))?;
+    let comment = repositories::proposals::comment(&repo, id, &data)?;
+
+    Ok(HttpResponse::Ok().json(MergeProposalCommentResponse {
+        status: StatusMessage::resource_created(),
+        comment,
+    }))
+}
+
+/// Approve the head branch as it is now, owners approving changes to their paths. The
+/// approver is the user the access token was issued to, the author in the body has to match.
+pub async fn approve(req: HttpRequest, body: String) -> Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let repo = get_repo(&app_data.path, namespace, name)?;
+    let id = proposal_id(&req)?;
+
+    let data: MergeProposalApprovalNew = serde_json::from_str(&body)
+        .map_err(|err| OxenHttpError::BadRequest(format!("{:?}", err).into()))?;
+    let Some(approver) = get_user(app_data, &req)? else {
+        return Err(OxenHttpError::BadRequest(
+            "Approvals need an access token issued by this server".into(),
+        ));
+    };
+    if !data.author.email.eq_ignore_ascii_case(&approver.email) {
+        return Err(OxenHttpError::BadRequest(
+            format!(
+                "Can not approve as {}, the access token belongs to {}",
+                data.author.email, approver.email
+            )
+            .into(),
+        ));
+    }
+    let proposal = repositories::proposals::approve(&repo, id, &approver)?;
+
+    Ok(HttpResponse::Ok().json(MergeProposalResponse {
+        status: StatusMessage::resource_updated(),
+        proposal,
+    }))
+}
+
+/// Merge head into base on the server, waiting behind other merges into base
+pub async fn merge(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let repo = get_repo(&app_data.path, namespace, name)?;
+    let proposal = get_proposal(&repo, proposal_id(&req)?)?;
+
+    let _turn = merge_queue::enqueue(&repo.path, &proposal.base).await;
+    let proposal = repositories::proposals::merge(&repo, proposal.id).await?;
+
+    Ok(HttpResponse::Ok().json(MergeProposalResponse {
+        status: StatusMessage::resource_updated(),
+        proposal,
+    }))
+}
+
+pub async fn close(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let repo = get_repo(&app_data.path, namespace, name)?;
+    let id = proposal_id(&req)?;
+
+    let proposal = repositories::proposals::close(&repo, id)?;
+
+    Ok(HttpResponse::Ok().json(MergeProposalResponse {
+        status: StatusMessage::resource_updated(),
+        proposal,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::http::{header, StatusCode};
+    use actix_web::{web, App};
+
+    use liboxen::error::OxenError;
+    use liboxen::model::{MergeProposalStatus, User};
+    use liboxen::repositories;
+    use liboxen::util;
+    use liboxen::view::{
+        MergeProposalApprovalNew, MergeProposalChecksResponse, MergeProposalNew,
+        MergeProposalResponse,
+    };
+
+    use crate::app_data::OxenAppData;
+    use crate::auth::access_keys::AccessKeyManager;
+    use crate::controllers;
+    use crate::test;
+
+    #[actix_web::test]
+    async fn test_controllers_proposals_create_check_and_merge() -> Result<(), OxenError> {
+        test::init_test_env();
+        let sync_dir = test::get_sync_dir()?;
+        let namespace = "Testing-Namespace";
+        let repo_name = "Testing-Name";
+        let repo = test::create_local_repo(&sync_dir, namespace, repo_name)?;
+        let path = repo.path.join("README.md");
+        util::fs::write_to_path(&path, "# Dataset\n")?;
+        repositories::add(&repo, &path).await?;
+        repositories::commit(&repo, "Adding readme")?;
+        repositories::branches::create_checkout(&repo, "docs")?;
+        util::fs::write_to_path(&path, "# Dataset\n\nImages of cats.\n")?;
+        repositories::add(&repo, &path).await?;
+        repositories::commit(&repo, "Describe the dataset")?;
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(OxenAppData::new(sync_dir.clone()))
+                .route(
+                    "/oxen/{namespace}/{repo_name}/proposals",
+                    web::post().to(controllers::proposals::create),
+                )
+                .route(
+                    "/oxen/{namespace}/{repo_name}/proposals/{proposal_id}/mergeable",
+                    web::get().to(controllers::proposals::mergeable),
+                )
+                .route(
+                    "/oxen/{namespace}/{repo_name}/proposals/{proposal_id}/merge",
+                    web::post().to(controllers::proposals::merge),
+                ),
+        )
+        .await;
+        let uri = format!("/oxen/{namespace}/{repo_name}/proposals");
+
+        let new_proposal = MergeProposalNew {
+            title: "Describe the dataset".to_string(),
+            description: String::new(),
+            base: "main".to_string(),
+            head: "docs".to_string(),
+            author: User {
+                name: "Ox".to_string(),
+                email: "ox@oxen.ai".to_string(),
+            },
+        };
+        let req = actix_web::test::TestRequest::post()
+            .uri(&uri)
+            .set_payload(serde_json::to_string(&new_proposal)?)
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let bytes = actix_http::body::to_bytes(resp.into_body()).await.unwrap();
+        let response: MergeProposalResponse = serde_json::from_slice(&bytes)?;
+        let id = response.proposal.id;
+
+        let req = actix_web::test::TestRequest::get()
+            .uri(&format!("{uri}/{id}/mergeable"))
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        let bytes = actix_http::body::to_bytes(resp.into_body()).await.unwrap();
+        let response: MergeProposalChecksResponse = serde_json::from_slice(&bytes)?;
+        assert!(response.checks.is_mergeable);
+        assert_eq!(response.checks.num_commits, 1);
+
+        let req = actix_web::test::TestRequest::post()
+            .uri(&format!("{uri}/{id}/merge"))
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let bytes = actix_http::body::to_bytes(resp.into_body()).await.unwrap();
+        let response: MergeProposalResponse = serde_json::from_slice(&bytes)?;
+        assert_eq!(response.proposal.status, MergeProposalStatus::Merged);
+
+        let main = repositories::branches::get_by_name(&repo, "main")?.unwrap();
+        let docs = repositories::branches::get_by_name(&repo, "docs")?.unwrap();
+        assert_eq!(main.commit_id, docs.commit_id);
+
+        // cleanup
+        test::cleanup_sync_dir(&sync_dir)?;
+
+        Ok(())
+    }
+
+    #[actix_web::test]
+    async fn test_controllers_proposals_approve_as_token_user() -> Result<(), OxenError> {
+        test::init_test_env();
+        let sync_dir = test::get_sync_dir()?;
+        let namespace = "Testing-Namespace";
+        let repo_name = "Testing-Name";
+        let repo = test::create_local_repo(&sync_dir, namespace, repo_name)?;
+        let path = repo.path.join("README.md");
+        util::fs::write_to_path(&path, "# Dataset\n")?;
+        repositories::add(&repo, &path).await?;
+        repositories::commit(&repo, "Adding readme")?;
+        repositories::branches::create_checkout(&repo, "docs")?;
+        util::fs::write_to_path(&path, "# Dataset\n\nImages of cats.\n")?;
+        repositories::add(&repo, &path).await?;
+        repositories::commit(&repo, "Describe the dataset")?;
+
+        let ox = User {
+            name: "Ox".to_string(),
+            email: "ox@oxen.ai".to_string(),
+        };
+        let ada = User {
+            name: "Ada".to_string(),
+            email: "ada@oxen.ai".to_string(),
+        };
+        let proposal = repositories::proposals::create(
+            &repo,
+            &MergeProposalNew {
+                title: "Describe the dataset".to_string(),
+                description: String::new(),
+                base: "main".to_string(),
+                head: "docs".to_string(),
+                author: ox.clone(),
+            },
+        )?;
+        let (_, bo_token) = AccessKeyManager::new(&sync_dir)?.create(&User {
+            name: "Bo".to_string(),
+            email: "bo@oxen.ai".to_string(),
+        })?;
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(OxenAppData::new(sync_dir.clone()))
+                .route(
+                    "/oxen/{namespace}/{repo_name}/proposals/{proposal_id}/approve",
+                    web::post().to(controllers::proposals::approve),
+                ),
+        )
+        .await;
+        let uri = format!(
+            "/oxen/{namespace}/{repo_name}/proposals/{}/approve",
+            proposal.id
+        );
+        let approval = |author: &User| {
+            serde_json::to_string(&MergeProposalApprovalNew {
+                author: author.clone(),
+            })
+        };
+
+        // Without a token nobody can be held to the approval
+        let req = actix_web::test::TestRequest::post()
+            .uri(&uri)
+            .set_payload(approval(&ada)?)
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        // Bo's token can't approve as Ada
+        let req = actix_web::test::TestRequest::post()
+            .uri(&uri)
+            .insert_header((header::AUTHORIZATION, format!("Bearer {bo_token}")))
+            .set_payload(approval(&ada)?)
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        let proposal = repositories::proposals::get(&repo, proposal.id)?.unwrap();
+        assert!(proposal.approvals.is_empty());
+
+        let req = actix_web::test::TestRequest::post()
+            .uri(&uri)
+            .insert_header((header::AUTHORIZATION, format!("Bearer {bo_token}")))
+            .set_payload(approval(&User {
+                name: "Bo".to_string(),
+                email: "bo@oxen.ai".to_string(),
+            })?)
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let bytes = actix_http::body::to_bytes(resp.into_body()).await.unwrap();
+        let response: MergeProposalResponse = serde_json::from_slice(&bytes)?;
+        assert_eq!(response.proposal.approvals.len(), 1);
+        assert_eq!(response.proposal.approvals[0].author.email, "bo@oxen.ai");
+
+        // cleanup
+        test::cleanup_sync_dir(&sync_dir)?;
+
+        Ok(())
+    }
+}
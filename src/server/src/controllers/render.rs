@@ -0,0 +1,86 @@
+use crate::errors::OxenHttpError;
+use crate::helpers::get_repo;
+use crate::params::{app_data, parse_resource, path_param};
+
+use liboxen::repositories;
+
+use actix_web::{HttpRequest, HttpResponse};
+
+/// Sanitized HTML of a markdown file or jupyter notebook at a revision
+pub async fn get(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let repo_name = path_param(&req, "repo_name")?;
+    let repo = get_repo(&app_data.path, namespace, repo_name)?;
+    let resource = parse_resource(&req, &repo)?;
+    let Some(commit) = resource.commit.as_ref() else {
+        return Err(OxenHttpError::BadRequest(
+            "Only committed files can be rendered".into(),
+        ));
+    };
+
+    let html = repositories::render::to_html(&repo, commit, &resource.path).await?;
+    Ok(HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(html))
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::http::StatusCode;
+    use actix_web::{web, App};
+
+    use liboxen::error::OxenError;
+    use liboxen::repositories;
+    use liboxen::util;
+
+    use crate::app_data::OxenAppData;
+    use crate::controllers;
+    use crate::test;
+
+    #[actix_web::test]
+    async fn test_controllers_render_notebook() -> Result<(), OxenError> {
+        test::init_test_env();
+        let sync_dir = test::get_sync_dir()?;
+        let namespace = "Testing-Namespace";
+        let repo_name = "Testing-Name";
+        let repo = test::create_local_repo(&sync_dir, namespace, repo_name)?;
+        let path = repo.path.join("notebooks").join("explore.ipynb");
+        util::fs::write_to_path(
+            &path,
+            r##"{"cells": [
+                {"cell_type": "markdown", "source": "# Explore"},
+                {"cell_type": "code", "source": "df.head()", "outputs": []}
+            ]}"##,
+        )?;
+        repositories::add(&repo, &path).await?;
+        repositories::commit(&repo, "Adding a notebook")?;
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(OxenAppData::new(sync_dir.clone()))
+                .route(
+                    "/oxen/{namespace}/{repo_name}/render/{resource:.*}",
+                    web::get().to(controllers::render::get),
+                ),
+        )
+        .await;
+        let uri = format!("/oxen/{namespace}/{repo_name}/render/main/notebooks/explore.ipynb");
+        let req = actix_web::test::TestRequest::get().uri(&uri).to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers().get("content-type").unwrap(),
+            "text/html; charset=utf-8"
+        );
+        let bytes = actix_http::body::to_bytes(resp.into_body()).await.unwrap();
+        let html = std::str::from_utf8(&bytes).unwrap();
+        assert!(html.contains("<h1>Explore</h1>"));
+        assert!(html.contains("df.head()"));
+
+        // cleanup
+        test::cleanup_sync_dir(&sync_dir)?;
+
+        Ok(())
+    }
+}
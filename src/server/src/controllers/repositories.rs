@@ -13,7 +13,8 @@ use liboxen::util;
 use liboxen::view::http::{MSG_RESOURCE_FOUND, MSG_RESOURCE_UPDATED, STATUS_SUCCESS};
 use liboxen::view::repository::{
     DataTypeView, RepositoryCreationResponse, RepositoryCreationView, RepositoryDataTypesResponse,
-    RepositoryDataTypesView, RepositoryListView, RepositoryStatsResponse, RepositoryStatsView,
+    RepositoryDataTypesView, RepositoryListView, RepositoryReadOnly, RepositoryReadOnlyResponse,
+    RepositoryStatsResponse, RepositoryStatsView,
 };
 use liboxen::view::{
     DataTypeCount, ListRepositoryResponse, NamespaceView, RepositoryResponse, RepositoryView,
@@ -87,6 +88,8 @@ pub async fn show(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpE
             data_types,
             min_version: Some(repository.min_version().to_string()),
             is_empty: repositories::is_empty(&repository)?,
+            storage_backend: Some(repository.version_store()?.storage_type().to_string()),
+            head: repositories::branches::current_branch(&repository)?.map(|branch| branch.name),
         },
     }))
 }
@@ -432,6 +435,40 @@ pub async fn transfer_namespace(
     }))
 }
 
+pub async fn get_read_only(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let repository = get_repo(&app_data.path, namespace, name)?;
+
+    Ok(HttpResponse::Ok().json(RepositoryReadOnlyResponse {
+        status: StatusMessage::resource_found(),
+        read_only: repository.is_read_only(),
+    }))
+}
+
+pub async fn update_read_only(
+    req: HttpRequest,
+    body: String,
+) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let mut repository = get_repo(&app_data.path, namespace, name)?;
+
+    let data: Result<RepositoryReadOnly, serde_json::Error> = serde_json::from_str(&body);
+    let data = data.map_err(|err| OxenHttpError::BadRequest(format!("{:?}", err).into()))?;
+
+    log::debug!("set read_only {} for {:?}", data.read_only, repository.path);
+    repository.set_read_only(data.read_only);
+    repository.save()?;
+
+    Ok(HttpResponse::Ok().json(RepositoryReadOnlyResponse {
+        status: StatusMessage::resource_updated(),
+        read_only: repository.is_read_only(),
+    }))
+}
+
 pub async fn get_file_for_branch(req: HttpRequest) -> Result<NamedFile, OxenHttpError> {
     let app_data = app_data(&req)?;
     let namespace = path_param(&req, "namespace")?;
@@ -481,9 +518,13 @@ mod tests {
     use actix_web::body::to_bytes;
 
     use liboxen::error::OxenError;
+    use liboxen::repositories;
+    use liboxen::util;
 
     use liboxen::view::http::STATUS_SUCCESS;
-    use liboxen::view::{ListRepositoryResponse, NamespaceView, RepositoryResponse};
+    use liboxen::view::{
+        ListRepositoryResponse, NamespaceView, RepositoryDataTypesResponse, RepositoryResponse,
+    };
 
     use crate::controllers;
     use crate::test;
@@ -558,6 +599,38 @@ mod tests {
         Ok(())
     }
 
+    #[actix_web::test]
+    async fn test_controllers_repositories_show_head() -> Result<(), OxenError> {
+        let sync_dir = test::get_sync_dir()?;
+        let namespace = "Test-Namespace";
+        let name = "Testing-Name";
+        let repo = test::create_local_repo(&sync_dir, namespace, name)?;
+        let path = repo.path.join("README.md");
+        util::fs::write_to_path(&path, "# Dataset\n")?;
+        repositories::add(&repo, &path).await?;
+        repositories::commit(&repo, "Adding readme")?;
+        // HEAD is not always main
+        repositories::branches::create_checkout(&repo, "develop")?;
+
+        let uri = format!("/api/repos/{namespace}/{name}");
+        let req = test::repo_request(&sync_dir, &uri, namespace, name);
+        let resp = controllers::repositories::show(req).await.unwrap();
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        let body = to_bytes(resp.into_body()).await.unwrap();
+        let text = std::str::from_utf8(&body).unwrap();
+        let repo_response: RepositoryDataTypesResponse = serde_json::from_str(text)?;
+        assert_eq!(repo_response.repository.head, Some("develop".to_string()));
+        assert_eq!(
+            repo_response.repository.storage_backend,
+            Some("local".to_string())
+        );
+
+        // cleanup
+        test::cleanup_sync_dir(&sync_dir)?;
+
+        Ok(())
+    }
+
     #[actix_web::test]
     async fn test_controllers_repositories_transfer_namespace() -> Result<(), OxenError> {
         let sync_dir = test::get_sync_dir()?;
@@ -0,0 +1,76 @@
+use crate::errors::OxenHttpError;
+use crate::helpers::{fire_webhooks, get_repo};
+use crate::params::{app_data, path_param};
+
+use actix_web::{HttpRequest, HttpResponse};
+
+use liboxen::error::OxenError;
+use liboxen::model::{Tag, WebhookPayload};
+use liboxen::repositories;
+use liboxen::view::{ListTagsResponse, StatusMessage, TagResponse};
+
+pub async fn index(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let repo = get_repo(&app_data.path, namespace, name)?;
+
+    let tags = repositories::tags::list(&repo)?;
+
+    let view = ListTagsResponse {
+        status: StatusMessage::resource_found(),
+        tags,
+    };
+    Ok(HttpResponse::Ok().json(view))
+}
+
+pub async fn show(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let tag_name = path_param(&req, "tag_name")?;
+    let repo = get_repo(&app_data.path, namespace, name)?;
+
+    let tag = repositories::tags::get_by_name(&repo, &tag_name)?
+        .ok_or(OxenError::tag_not_found(&tag_name))?;
+
+    Ok(HttpResponse::Ok().json(TagResponse {
+        status: StatusMessage::resource_found(),
+        tag,
+    }))
+}
+
+pub async fn create(req: HttpRequest, body: String) -> Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let full_name = format!("{namespace}/{name}");
+    let repo = get_repo(&app_data.path, namespace, name)?;
+
+    let tag: Tag = serde_json::from_str(&body)
+        .map_err(|err| OxenHttpError::BadRequest(format!("{:?}", err).into()))?;
+    log::debug!("Create tag: {}", tag);
+
+    repositories::tags::save(&repo, &tag)?;
+    fire_webhooks(&repo, WebhookPayload::tag_created(&full_name, &tag));
+
+    Ok(HttpResponse::Ok().json(TagResponse {
+        status: StatusMessage::resource_created(),
+        tag,
+    }))
+}
+
+pub async fn delete(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let tag_name = path_param(&req, "tag_name")?;
+    let repo = get_repo(&app_data.path, namespace, name)?;
+
+    let tag = repositories::tags::delete(&repo, &tag_name)?;
+
+    Ok(HttpResponse::Ok().json(TagResponse {
+        status: StatusMessage::resource_deleted(),
+        tag,
+    }))
+}
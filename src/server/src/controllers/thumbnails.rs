@@ -0,0 +1,111 @@
+use crate::errors::OxenHttpError;
+use crate::helpers::get_repo;
+use crate::params::{app_data, parse_resource, path_param};
+
+use liboxen::error::OxenError;
+use liboxen::repositories;
+
+use actix_files::NamedFile;
+use actix_web::{web, HttpRequest, HttpResponse};
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct ThumbnailQuery {
+    pub w: Option<u32>,
+    pub h: Option<u32>,
+}
+
+/// Thumbnail of an image, or poster frame of a video, at a revision. Only served when
+/// the server is started with `--media`.
+pub async fn get(
+    req: HttpRequest,
+    query: web::Query<ThumbnailQuery>,
+) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    if !app_data.media {
+        return Err(OxenHttpError::NotFound);
+    }
+    let namespace = path_param(&req, "namespace")?;
+    let repo_name = path_param(&req, "repo_name")?;
+    let repo = get_repo(&app_data.path, namespace, repo_name)?;
+    let resource = parse_resource(&req, &repo)?;
+    let Some(commit) = resource.commit.as_ref() else {
+        return Err(OxenHttpError::BadRequest(
+            "Thumbnails are only served for committed files".into(),
+        ));
+    };
+
+    // Decoding and resizing, or running ffmpeg, must not hold up the server's workers
+    let commit = commit.clone();
+    let path = resource.path.clone();
+    let (width, height) = (query.w, query.h);
+    let thumbnail_path =
+        web::block(move || repositories::thumbnails::get(&repo, &commit, &path, width, height))
+            .await
+            .map_err(|err| OxenError::basic_str(err.to_string()))??;
+    Ok(NamedFile::open(thumbnail_path)?.into_response(&req))
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::http::StatusCode;
+    use actix_web::{web, App};
+
+    use liboxen::error::OxenError;
+    use liboxen::error::OxenError;
+    use liboxen::repositories;
+    use liboxen::util;
+
+    use crate::app_data::OxenAppData;
+    use crate::controllers;
+    use crate::test;
+
+    #[actix_web::test]
+    async fn test_controllers_thumbnails_get() -> Result<(), OxenError> {
+        test::init_test_env();
+        let sync_dir = test::get_sync_dir()?;
+        let namespace = "Testing-Namespace";
+        let repo_name = "Testing-Name";
+        let repo = test::create_local_repo(&sync_dir, namespace, repo_name)?;
+        let path = repo.path.join("images").join("photo.jpg");
+        util::fs::create_dir_all(path.parent().unwrap())?;
+        image::RgbImage::new(300, 600).save(&path)?;
+        repositories::add(&repo, &path).await?;
+        repositories::commit(&repo, "Adding a photo")?;
+
+        let uri = format!("/oxen/{namespace}/{repo_name}/thumbnail/main/images/photo.jpg?h=100");
+        let route = "/oxen/{namespace}/{repo_name}/thumbnail/{resource:.*}";
+
+        // Off by default
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(OxenAppData::new(sync_dir.clone()))
+                .route(route, web::get().to(controllers::thumbnails::get)),
+        )
+        .await;
+        let req = actix_web::test::TestRequest::get().uri(&uri).to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        let mut app_data = OxenAppData::new(sync_dir.clone());
+        app_data.media = true;
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(app_data)
+                .route(route, web::get().to(controllers::thumbnails::get)),
+        )
+        .await;
+        let req = actix_web::test::TestRequest::get().uri(&uri).to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers().get("content-type").unwrap(), "image/jpeg");
+        let bytes = actix_http::body::to_bytes(resp.into_body()).await.unwrap();
+        let thumbnail = image::load_from_memory(&bytes)?;
+        assert_eq!((thumbnail.width(), thumbnail.height()), (50, 100));
+
+        // cleanup
+        test::cleanup_sync_dir(&sync_dir)?;
+
+        Ok(())
+    }
+}
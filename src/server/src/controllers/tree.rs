@@ -253,7 +253,13 @@ pub async fn download_tree_nodes(
     let subtrees = get_subtree_paths(&query.subtrees)?;
 
     // Could be a single commit or a range of commits
-    let commits = get_commit_list(&repository, &base_commit, &maybe_head_commit_id, &subtrees)?;
+    let commits = get_commit_list(
+        &repository,
+        &base_commit,
+        &maybe_head_commit_id,
+        &subtrees,
+        query.history_depth,
+    )?;
     log::debug!("download_tree_nodes got {} commits", commits.len());
 
     let node_hashes = if maybe_head_commit_id.is_some() {
@@ -292,6 +298,7 @@ fn get_commit_list(
     base_commit: &Commit,
     maybe_head_commit_id: &Option<String>,
     maybe_subtrees: &Option<Vec<PathBuf>>,
+    history_depth: Option<usize>,
 ) -> Result<Vec<Commit>, OxenError> {
     // If we have a head commit, then we are downloading a range of commits
     // Otherwise, we are downloading all commits from the base commit back to the first commit
@@ -305,6 +312,13 @@ fn get_commit_list(
         // If the subtree is specified, we only want to get the latest commit
         if maybe_subtrees.is_some() {
             vec![base_commit.clone()]
+        } else if let Some(history_depth) = history_depth {
+            // Shallow clones only want the last commits
+            repositories::commits::list_from_with_history_depth(
+                repository,
+                &base_commit.id,
+                history_depth,
+            )?
         } else {
             repositories::commits::list_from(repository, &base_commit.id)?
         }
@@ -0,0 +1,265 @@
+use crate::errors::OxenHttpError;
+use crate::helpers::get_repo;
+use crate::params::{app_data, path_param};
+
+use liboxen::model::UploadSession;
+use liboxen::repositories;
+use liboxen::view::{NewUploadSession, StatusMessage, UploadSessionResponse};
+
+use actix_web::web::BytesMut;
+use actix_web::{web, HttpRequest, HttpResponse, HttpResponseBuilder};
+use futures_util::stream::StreamExt as _;
+
+/// Bytes of the upload received so far, sent with every session like tus does
+const UPLOAD_OFFSET_HEADER: &str = "upload-offset";
+/// Most bytes appended by one request, larger files are sent in several
+const MAX_CHUNK_SIZE: u64 = 256 * 1024 * 1024;
+
+/// Start the upload of a version file, or return the session in progress for the same hash
+pub async fn create(
+    req: HttpRequest,
+    body: web::Json<NewUploadSession>,
+) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let repo_name = path_param(&req, "repo_name")?;
+    let repo = get_repo(&app_data.path, namespace, repo_name)?;
+
+    let session = repositories::uploads::create(&repo, &body.hash, body.size)?;
+    log::debug!(
+        "uploads::create {} at {}/{}",
+        session.id,
+        session.offset,
+        session.size
+    );
+    Ok(session_response(
+        HttpResponse::Ok(),
+        StatusMessage::resource_created(),
+        session,
+    ))
+}
+
+pub async fn show(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let repo_name = path_param(&req, "repo_name")?;
+    let upload_id = path_param(&req, "upload_id")?;
+    let repo = get_repo(&app_data.path, namespace, repo_name)?;
+
+    let session = repositories::uploads::get(&repo, &upload_id)?.ok_or(OxenHttpError::NotFound)?;
+    Ok(session_response(
+        HttpResponse::Ok(),
+        StatusMessage::resource_found(),
+        session,
+    ))
+}
+
+/// Append the body to the upload, the `upload-offset` header must be the number of bytes
+/// the server has already received. Bodies past the end of the upload, or larger than
+/// [`MAX_CHUNK_SIZE`], are rejected with 413.
+pub async fn append(
+    req: HttpRequest,
+    mut body: web::Payload,
+) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let repo_name = path_param(&req, "repo_name")?;
+    let upload_id = path_param(&req, "upload_id")?;
+    let repo = get_repo(&app_data.path, namespace, repo_name)?;
+
+    let offset = req
+        .headers()
+        .get(UPLOAD_OFFSET_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok())
+        .ok_or(OxenHttpError::BadRequest(
+            format!("Must supply a numeric {UPLOAD_OFFSET_HEADER} header").into(),
+        ))?;
+
+    let session = repositories::uploads::get(&repo, &upload_id)?.ok_or(OxenHttpError::NotFound)?;
+    if offset != session.offset {
+        // Tell the client where to resume from
+        return Ok(session_response(
+            HttpResponse::Conflict(),
+            StatusMessage::error(format!(
+                "Upload is at offset {}, not {offset}",
+                session.offset
+            )),
+            session,
+        ));
+    }
+
+    let limit = session
+        .size
+        .saturating_sub(session.offset)
+        .min(MAX_CHUNK_SIZE) as usize;
+    let mut buffered = BytesMut::new();
+    while let Some(chunk) = body.next().await {
+        let chunk = chunk.map_err(|e| OxenHttpError::BadRequest(e.to_string().into()))?;
+        if buffered.len() + chunk.len() > limit {
+            return Ok(session_response(
+                HttpResponse::PayloadTooLarge(),
+                StatusMessage::error(format!(
+                    "Upload chunks can be at most {limit} bytes at offset {}",
+                    session.offset
+                )),
+                session,
+            ));
+        }
+        buffered.extend_from_slice(&chunk);
+    }
+    let session = repositories::uploads::append(&repo, &upload_id, offset, &buffered)?;
+
+    Ok(session_response(
+        HttpResponse::Ok(),
+        StatusMessage::resource_updated(),
+        session,
+    ))
+}
+
+/// Verify the upload and move it into the version store
+pub async fn complete(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let repo_name = path_param(&req, "repo_name")?;
+    let upload_id = path_param(&req, "upload_id")?;
+    let repo = get_repo(&app_data.path, namespace, repo_name)?;
+
+    if repositories::uploads::get(&repo, &upload_id)?.is_none() {
+        return Err(OxenHttpError::NotFound);
+    }
+    repositories::uploads::complete(&repo, &upload_id).await?;
+
+    Ok(HttpResponse::Ok().json(StatusMessage::resource_created()))
+}
+
+pub async fn delete(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let repo_name = path_param(&req, "repo_name")?;
+    let upload_id = path_param(&req, "upload_id")?;
+    let repo = get_repo(&app_data.path, namespace, repo_name)?;
+
+    repositories::uploads::delete(&repo, &upload_id)?;
+    Ok(HttpResponse::Ok().json(StatusMessage::resource_deleted()))
+}
+
+fn session_response(
+    mut builder: HttpResponseBuilder,
+    status: StatusMessage,
+    session: UploadSession,
+) -> HttpResponse {
+    builder
+        .insert_header((UPLOAD_OFFSET_HEADER, session.offset.to_string()))
+        .json(UploadSessionResponse {
+            status,
+            upload: session,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::http::StatusCode;
+    use actix_web::{web, App};
+
+    use liboxen::error::OxenError;
+    use liboxen::util;
+    use liboxen::view::{NewUploadSession, UploadSessionResponse};
+
+    use crate::app_data::OxenAppData;
+    use crate::controllers;
+    use crate::test;
+
+    #[actix_web::test]
+    async fn test_controllers_uploads_resume_from_offset() -> Result<(), OxenError> {
+        test::init_test_env();
+        let sync_dir = test::get_sync_dir()?;
+        let namespace = "Testing-Namespace";
+        let repo_name = "Testing-Name";
+        let repo = test::create_local_repo(&sync_dir, namespace, repo_name)?;
+
+        let contents = b"a large file in two pieces".to_vec();
+        let path = sync_dir.join("large.bin");
+        util::fs::write(&path, &contents)?;
+        let hash = format!("{:x}", repo.hash_algorithm().hash_file_contents(&path)?);
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(OxenAppData::new(sync_dir.clone()))
+                .route(
+                    "/oxen/{namespace}/{repo_name}/uploads",
+                    web::post().to(controllers::uploads::create),
+                )
+                .route(
+                    "/oxen/{namespace}/{repo_name}/uploads/{upload_id}",
+                    web::patch().to(controllers::uploads::append),
+                )
+                .route(
+                    "/oxen/{namespace}/{repo_name}/uploads/{upload_id}/complete",
+                    web::post().to(controllers::uploads::complete),
+                ),
+        )
+        .await;
+
+        let uri = format!("/oxen/{namespace}/{repo_name}/uploads");
+        let req = actix_web::test::TestRequest::post()
+            .uri(&uri)
+            .set_json(NewUploadSession {
+                hash: hash.clone(),
+                size: contents.len() as u64,
+            })
+            .to_request();
+        let resp: UploadSessionResponse = actix_web::test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp.upload.offset, 0);
+
+        let uri = format!("/oxen/{namespace}/{repo_name}/uploads/{hash}");
+        let req = actix_web::test::TestRequest::patch()
+            .uri(&uri)
+            .insert_header(("upload-offset", "0"))
+            .set_payload(contents[..8].to_vec())
+            .to_request();
+        let resp: UploadSessionResponse = actix_web::test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp.upload.offset, 8);
+
+        // Resending the first chunk conflicts and reports where to resume
+        let req = actix_web::test::TestRequest::patch()
+            .uri(&uri)
+            .insert_header(("upload-offset", "0"))
+            .set_payload(contents[..8].to_vec())
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+        assert_eq!(resp.headers().get("upload-offset").unwrap(), "8");
+
+        // Nothing past the declared size is buffered
+        let mut too_long = contents[8..].to_vec();
+        too_long.extend_from_slice(b" and then some");
+        let req = actix_web::test::TestRequest::patch()
+            .uri(&uri)
+            .insert_header(("upload-offset", "8"))
+            .set_payload(too_long)
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(resp.headers().get("upload-offset").unwrap(), "8");
+
+        let req = actix_web::test::TestRequest::patch()
+            .uri(&uri)
+            .insert_header(("upload-offset", "8"))
+            .set_payload(contents[8..].to_vec())
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let uri = format!("/oxen/{namespace}/{repo_name}/uploads/{hash}/complete");
+        let req = actix_web::test::TestRequest::post().uri(&uri).to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert!(repo.version_store()?.version_exists(&hash)?);
+
+        // cleanup
+        test::cleanup_sync_dir(&sync_dir)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,70 @@
+use crate::errors::OxenHttpError;
+use crate::helpers::get_user;
+use crate::params::app_data;
+
+use actix_web::{HttpRequest, HttpResponse};
+use liboxen::view::user::WhoamiResponse;
+use liboxen::view::StatusMessage;
+
+/// Who the bearer token on the request belongs to. When auth is enabled, invalid tokens
+/// are rejected with a 401 before reaching this handler.
+pub async fn whoami(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+
+    let user = get_user(app_data, &req)?;
+
+    Ok(HttpResponse::Ok().json(WhoamiResponse {
+        status: StatusMessage::resource_found(),
+        user,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::body::to_bytes;
+    use actix_web::http::{self, header};
+
+    use liboxen::error::OxenError;
+    use liboxen::model::User;
+    use liboxen::view::user::WhoamiResponse;
+
+    use crate::app_data::OxenAppData;
+    use crate::auth::access_keys::AccessKeyManager;
+    use crate::controllers;
+    use crate::test;
+
+    #[actix_web::test]
+    async fn test_controllers_users_whoami() -> Result<(), OxenError> {
+        let sync_dir = test::get_sync_dir()?;
+        let user = User {
+            name: String::from("Ox"),
+            email: String::from("ox@oxen.ai"),
+        };
+        let (_user, token) = {
+            let keygen = AccessKeyManager::new(&sync_dir)?;
+            keygen.create(&user)?
+        };
+
+        let req = actix_web::test::TestRequest::get()
+            .uri("/api/whoami")
+            .app_data(OxenAppData::new(sync_dir.clone()))
+            .insert_header((header::AUTHORIZATION, format!("Bearer {token}")))
+            .to_http_request();
+        let resp = controllers::users::whoami(req).await.unwrap();
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        let body = to_bytes(resp.into_body()).await.unwrap();
+        let response: WhoamiResponse = serde_json::from_slice(&body)?;
+        assert_eq!(response.user, Some(user));
+
+        // No token, no user
+        let req = test::request(&sync_dir, "/api/whoami");
+        let resp = controllers::users::whoami(req).await.unwrap();
+        let body = to_bytes(resp.into_body()).await.unwrap();
+        let response: WhoamiResponse = serde_json::from_slice(&body)?;
+        assert_eq!(response.user, None);
+
+        test::cleanup_sync_dir(&sync_dir)?;
+
+        Ok(())
+    }
+}
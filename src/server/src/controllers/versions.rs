@@ -5,14 +5,17 @@ use crate::helpers::get_repo;
 use crate::params::{app_data, path_param};
 
 use actix_multipart::Multipart;
-use actix_web::{Error, HttpRequest, HttpResponse};
+use actix_web::{web, Error, HttpRequest, HttpResponse};
 use flate2::read::GzDecoder;
+use futures_util::stream::StreamExt as _;
 use futures_util::TryStreamExt as _;
 use liboxen::error::OxenError;
 use liboxen::model::LocalRepository;
+use liboxen::view::tree::merkle_hashes::MerkleHashes;
 use liboxen::view::versions::{VersionFile, VersionFileResponse};
-use liboxen::view::{ErrorFileInfo, ErrorFilesResponse, StatusMessage};
+use liboxen::view::{ErrorFileInfo, ErrorFilesResponse, MerkleHashesResponse, StatusMessage};
 use mime;
+use std::collections::HashSet;
 use std::io::Read as StdRead;
 use std::path::PathBuf;
 
@@ -39,6 +42,39 @@ pub async fn metadata(req: HttpRequest) -> Result<HttpResponse, OxenHttpError> {
     }))
 }
 
+/// Takes a list of content hashes and returns the ones the repo has no version file for,
+/// so a client can plan a push in one round trip
+pub async fn exists(
+    req: HttpRequest,
+    mut body: web::Payload,
+) -> Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let repo_name = path_param(&req, "repo_name")?;
+    let repo = get_repo(&app_data.path, namespace, repo_name)?;
+
+    let mut bytes = web::BytesMut::new();
+    while let Some(item) = body.next().await {
+        bytes.extend_from_slice(&item.map_err(|_| OxenHttpError::FailedToReadRequestPayload)?);
+    }
+    let request: MerkleHashes = serde_json::from_slice(&bytes)?;
+    log::debug!("versions::exists checking {} hashes", request.hashes.len());
+
+    let version_store = repo.version_store()?;
+    let mut hashes = HashSet::new();
+    for hash in request.hashes {
+        if !version_store.version_exists(&hash.to_string())? {
+            hashes.insert(hash);
+        }
+    }
+    log::debug!("versions::exists found {} missing hashes", hashes.len());
+
+    Ok(HttpResponse::Ok().json(MerkleHashesResponse {
+        status: StatusMessage::resource_found(),
+        hashes,
+    }))
+}
+
 pub async fn download(req: HttpRequest) -> Result<HttpResponse, OxenHttpError> {
     let app_data = app_data(&req)?;
     let namespace = path_param(&req, "namespace")?;
@@ -0,0 +1,64 @@
+use crate::errors::OxenHttpError;
+use crate::helpers::get_repo;
+use crate::params::{app_data, path_param};
+
+use actix_web::{HttpRequest, HttpResponse};
+
+use liboxen::model::Webhook;
+use liboxen::repositories;
+use liboxen::view::{ListWebhooksResponse, StatusMessage, WebhookNew, WebhookResponse};
+
+// Secrets are write only, anyone who can read the repository can list its webhooks
+fn without_secret(webhook: Webhook) -> Webhook {
+    Webhook {
+        secret: None,
+        ..webhook
+    }
+}
+
+pub async fn index(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let repo = get_repo(&app_data.path, namespace, name)?;
+
+    let webhooks = repositories::webhooks::list(&repo)?
+        .into_iter()
+        .map(without_secret)
+        .collect();
+
+    Ok(HttpResponse::Ok().json(ListWebhooksResponse {
+        status: StatusMessage::resource_found(),
+        webhooks,
+    }))
+}
+
+pub async fn create(req: HttpRequest, body: String) -> Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let repo = get_repo(&app_data.path, namespace, name)?;
+
+    let data: WebhookNew = serde_json::from_str(&body)
+        .map_err(|err| OxenHttpError::BadRequest(format!("{:?}", err).into()))?;
+    log::debug!("Create webhook for {} on {:?}", data.url, data.events);
+
+    let webhook = repositories::webhooks::add(&repo, &data.url, &data.events, data.secret)?;
+
+    Ok(HttpResponse::Ok().json(WebhookResponse {
+        status: StatusMessage::resource_created(),
+        webhook: without_secret(webhook),
+    }))
+}
+
+pub async fn delete(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let webhook_id = path_param(&req, "webhook_id")?;
+    let repo = get_repo(&app_data.path, namespace, name)?;
+
+    repositories::webhooks::remove(&repo, &webhook_id)?;
+
+    Ok(HttpResponse::Ok().json(StatusMessage::resource_deleted()))
+}
@@ -1,9 +1,10 @@
 use crate::errors::{OxenHttpError, WorkspaceBranch};
-use crate::helpers::get_repo;
+use crate::helpers::{fire_webhooks, get_repo, get_user, record_activity};
+use crate::merge_queue;
 use crate::params::{app_data, path_param, NameParam};
 
 use liboxen::error::OxenError;
-use liboxen::model::NewCommitBody;
+use liboxen::model::{ActivityEvent, NewCommitBody, WebhookPayload};
 use liboxen::repositories;
 use liboxen::view::merge::MergeableResponse;
 use liboxen::view::workspaces::{ListWorkspaceResponseView, NewWorkspace, WorkspaceResponse};
@@ -277,13 +278,29 @@ pub async fn commit(req: HttpRequest, body: String) -> Result<HttpResponse, Oxen
             .json(StatusMessageDescription::workspace_not_found(workspace_id)));
     };
 
+    // Commits to the branch land one at a time, in order with pushes and merges
+    let _guard = merge_queue::enqueue(&repo.path, &branch_name).await;
     let Some(branch) = repositories::branches::get_by_name(&repo, &branch_name)? else {
         return Ok(HttpResponse::NotFound().json(StatusMessageDescription::not_found(branch_name)));
     };
 
-    match repositories::workspaces::commit(&workspace, &data, &branch_name) {
+    let pusher = get_user(app_data, &req)?;
+    match repositories::workspaces::commit_as(&workspace, &data, &branch_name, pusher.as_ref()) {
         Ok(commit) => {
             log::debug!("workspace::commit ✅ success! commit {:?}", commit);
+            record_activity(
+                &repo,
+                &ActivityEvent::workspace_merge(&workspace, &branch_name, &commit),
+            );
+            fire_webhooks(
+                &repo,
+                WebhookPayload::workspace_commit(
+                    format!("{namespace}/{repo_name}"),
+                    &workspace.id,
+                    &branch_name,
+                    &commit,
+                ),
+            );
             Ok(HttpResponse::Ok().json(CommitResponse {
                 status: StatusMessage::resource_created(),
                 commit,
@@ -295,6 +312,11 @@ pub async fn commit(req: HttpRequest, body: String) -> Result<HttpResponse, Oxen
                 branch,
             })))
         }
+        Err(
+            err @ (OxenError::InvalidCommitMessage(_)
+            | OxenError::ProtectedBranch(_)
+            | OxenError::ApprovalRequired(_)),
+        ) => Err(err.into()),
         Err(err) => {
             log::error!("unable to commit branch {:?}. Err: {}", branch_name, err);
             Ok(HttpResponse::UnprocessableEntity().json(StatusMessage::error(format!("{err:?}"))))
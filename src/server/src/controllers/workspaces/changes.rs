@@ -36,6 +36,7 @@ pub async fn list_root(
     let staged = repositories::workspaces::status::status_from_dir(&workspace, &path)?;
 
     staged.print();
+    let changes = repositories::workspaces::status::changes_from_staged(&workspace, &staged)?;
 
     let response = RemoteStagedStatusResponse {
         status: StatusMessage::resource_found(),
@@ -45,6 +46,7 @@ pub async fn list_root(
             page_num,
             page_size,
         ),
+        changes: Some(changes),
     };
     Ok(HttpResponse::Ok().json(response))
 }
@@ -73,6 +75,7 @@ pub async fn list(
     let staged = repositories::workspaces::status::status_from_dir(&workspace, &path)?;
 
     staged.print();
+    let changes = repositories::workspaces::status::changes_from_staged(&workspace, &staged)?;
 
     let response = RemoteStagedStatusResponse {
         status: StatusMessage::resource_found(),
@@ -82,6 +85,7 @@ pub async fn list(
             page_num,
             page_size,
         ),
+        changes: Some(changes),
     };
     Ok(HttpResponse::Ok().json(response))
 }
@@ -5,8 +5,8 @@ use liboxen::constants;
 use liboxen::error::{OxenError, PathBufError, StringError};
 use liboxen::model::{Branch, Workspace};
 use liboxen::view::http::{
-    MSG_BAD_REQUEST, MSG_CONFLICT, MSG_INTERNAL_SERVER_ERROR, MSG_RESOURCE_ALREADY_EXISTS,
-    MSG_RESOURCE_NOT_FOUND, MSG_UPDATE_REQUIRED, STATUS_ERROR,
+    MSG_BAD_REQUEST, MSG_CONFLICT, MSG_INTERNAL_SERVER_ERROR, MSG_NON_FAST_FORWARD,
+    MSG_RESOURCE_ALREADY_EXISTS, MSG_RESOURCE_NOT_FOUND, MSG_UPDATE_REQUIRED, STATUS_ERROR,
 };
 use liboxen::view::{SQLParseError, StatusMessage, StatusMessageDescription};
 
@@ -35,6 +35,7 @@ impl std::error::Error for WorkspaceBranch {}
 pub enum OxenHttpError {
     InternalServerError,
     BadRequest(StringError),
+    Forbidden(StringError),
     MultipartError(MultipartError),
     NotFound,
     AppDataDoesNotExist,
@@ -114,6 +115,18 @@ impl error::ResponseError for OxenHttpError {
                 });
                 HttpResponse::BadRequest().json(error_json)
             }
+            OxenHttpError::Forbidden(desc) => {
+                let error_json = json!({
+                    "error": {
+                        "type": "forbidden",
+                        "title": "Forbidden",
+                        "detail": desc.to_string()
+                    },
+                    "status": STATUS_ERROR,
+                    "status_message": MSG_BAD_REQUEST,
+                });
+                HttpResponse::Forbidden().json(error_json)
+            }
             OxenHttpError::SQLParseError(query) => {
                 HttpResponse::BadRequest().json(SQLParseError::new(query.to_string()))
             }
@@ -370,6 +383,70 @@ impl error::ResponseError for OxenHttpError {
                         HttpResponse::BadRequest()
                             .json(StatusMessageDescription::bad_request(format!("{}", desc)))
                     }
+                    OxenError::InvalidCommitMessage(desc) => {
+                        log::debug!("Invalid commit message: {}", desc);
+
+                        HttpResponse::BadRequest()
+                            .json(StatusMessageDescription::bad_request(format!("{}", desc)))
+                    }
+                    OxenError::ChecksFailed(report) => {
+                        log::debug!("Checks failed: {}", report);
+
+                        let error_json = json!({
+                            "error": {
+                                "type": "checks_failed",
+                                "title": "Data validation checks failed",
+                                "detail": format!("{}", report),
+                                "report": report,
+                            },
+                            "status": STATUS_ERROR,
+                            "status_message": MSG_BAD_REQUEST,
+                        });
+
+                        HttpResponse::BadRequest().json(error_json)
+                    }
+                    OxenError::RepoIsReadOnly(desc) => {
+                        log::debug!("Repo is read-only: {}", desc);
+
+                        let error_json = json!({
+                            "error": {
+                                "type": "read_only",
+                                "title": "Repository is read-only",
+                                "detail": format!("{}", desc)
+                            },
+                            "status": STATUS_ERROR,
+                            "status_message": MSG_BAD_REQUEST,
+                        });
+
+                        HttpResponse::Forbidden().json(error_json)
+                    }
+                    OxenError::NonFastForward(desc) => {
+                        log::debug!("Non-fast-forward update: {}", desc);
+
+                        let error_json = json!({
+                            "error": {
+                                "type": MSG_NON_FAST_FORWARD,
+                                "title": "Non-fast-forward",
+                                "detail": format!("{}", desc)
+                            },
+                            "status": STATUS_ERROR,
+                            "status_message": MSG_CONFLICT,
+                        });
+
+                        HttpResponse::Conflict().json(error_json)
+                    }
+                    OxenError::ProtectedBranch(desc) => {
+                        log::debug!("Protected branch: {}", desc);
+
+                        HttpResponse::BadRequest()
+                            .json(StatusMessageDescription::bad_request(format!("{}", desc)))
+                    }
+                    OxenError::ApprovalRequired(desc) => {
+                        log::debug!("Approval required: {}", desc);
+
+                        HttpResponse::BadRequest()
+                            .json(StatusMessageDescription::bad_request(format!("{}", desc)))
+                    }
                     OxenError::IncompleteLocalHistory(desc) => {
                         log::error!("Cannot push repo with incomplete local history: {}", desc);
 
@@ -528,6 +605,7 @@ impl error::ResponseError for OxenHttpError {
             OxenHttpError::AppDataDoesNotExist => StatusCode::BAD_REQUEST,
             OxenHttpError::PathParamDoesNotExist(_) => StatusCode::BAD_REQUEST,
             OxenHttpError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            OxenHttpError::Forbidden(_) => StatusCode::FORBIDDEN,
             OxenHttpError::SQLParseError(_) => StatusCode::BAD_REQUEST,
             OxenHttpError::MultipartError(_) => StatusCode::BAD_REQUEST,
             OxenHttpError::NotFound => StatusCode::NOT_FOUND,
@@ -545,6 +623,12 @@ impl error::ResponseError for OxenHttpError {
                 OxenError::RepoNotFound(_) => StatusCode::NOT_FOUND,
                 OxenError::RevisionNotFound(_) => StatusCode::NOT_FOUND,
                 OxenError::InvalidSchema(_) => StatusCode::BAD_REQUEST,
+                OxenError::RepoIsReadOnly(_) => StatusCode::FORBIDDEN,
+                OxenError::ProtectedBranch(_) => StatusCode::BAD_REQUEST,
+                OxenError::ApprovalRequired(_) => StatusCode::BAD_REQUEST,
+                OxenError::NonFastForward(_) => StatusCode::CONFLICT,
+                OxenError::InvalidCommitMessage(_) => StatusCode::BAD_REQUEST,
+                OxenError::ChecksFailed(_) => StatusCode::BAD_REQUEST,
                 _ => StatusCode::INTERNAL_SERVER_ERROR,
             },
         }
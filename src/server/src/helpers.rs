@@ -1,10 +1,18 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
 
+use actix_web::http::header;
+use actix_web::HttpRequest;
+use liboxen::config::{BackupConfig, MirrorConfig};
 // use liboxen::constants::DEFAULT_REDIS_URL;
 use liboxen::error::OxenError;
-use liboxen::model::{LocalRepository, RepoNew};
+use liboxen::model::{ActivityEvent, Commit, LocalRepository, RepoNew, User, WebhookPayload};
 use liboxen::repositories;
+use time::OffsetDateTime;
 
+use crate::app_data::OxenAppData;
+use crate::auth::access_keys::AccessKeyManager;
 use crate::errors::OxenHttpError;
 
 pub fn get_repo(
@@ -22,6 +30,223 @@ pub fn get_repo(
     Ok(repo)
 }
 
+/// The user the bearer token on the request was issued to, None without a token that is
+/// valid on this server
+pub fn get_user(app_data: &OxenAppData, req: &HttpRequest) -> Result<Option<User>, OxenHttpError> {
+    let token = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    let Some(token) = token else {
+        return Ok(None);
+    };
+
+    match AccessKeyManager::new_read_only(&app_data.path) {
+        Ok(keygen) => Ok(keygen.user_for_token(token)?),
+        Err(err) => {
+            log::debug!("Could not open access keys: {err}");
+            Ok(None)
+        }
+    }
+}
+
+/// Env var with the emails of the users that may administer the server, comma separated
+pub const ADMIN_EMAILS_ENV: &str = "OXEN_ADMIN_EMAILS";
+
+/// The user of the request's token if their email is in `OXEN_ADMIN_EMAILS`. Admins can
+/// do what reaches outside of a repository, such as scheduling imports from S3.
+pub fn get_admin(app_data: &OxenAppData, req: &HttpRequest) -> Result<User, OxenHttpError> {
+    let admins = std::env::var(ADMIN_EMAILS_ENV).unwrap_or_default();
+    match get_user(app_data, req)? {
+        Some(user)
+            if admins
+                .split(',')
+                .any(|email| email.trim().eq_ignore_ascii_case(&user.email)) =>
+        {
+            Ok(user)
+        }
+        _ => Err(OxenHttpError::Forbidden(
+            "Only server admins can do this, their access token is required".into(),
+        )),
+    }
+}
+
+/// Queue the post-commit cachers for a commit that landed on the server. Failing to
+/// queue them is logged rather than failing the request that landed the commit.
+pub fn schedule_cachers(app_data: &OxenAppData, repo: &LocalRepository, commit: &Commit) {
+    if let Err(err) = app_data.cache_scheduler.submit_all(repo, commit) {
+        log::error!(
+            "Could not schedule cachers for commit {}: {}",
+            commit.id,
+            err
+        );
+    }
+}
+
+/// Add an event to the repository's activity feed, logging rather than failing the
+/// request if it can't be written.
+pub fn record_activity(repo: &LocalRepository, event: &ActivityEvent) {
+    if let Err(err) = repositories::activity::record(repo, event) {
+        log::error!("Could not record {:?} activity: {}", event.kind, err);
+    }
+}
+
+/// Send the payload to the repository's webhooks for its event in the background, so a
+/// slow or failing endpoint never holds up the request that fired it.
+pub fn fire_webhooks(repo: &LocalRepository, payload: WebhookPayload) {
+    let webhooks = match repositories::webhooks::for_event(repo, payload.event) {
+        Ok(webhooks) => webhooks,
+        Err(err) => {
+            log::error!("Could not read webhooks of {:?}: {}", repo.path, err);
+            return;
+        }
+    };
+    for webhook in webhooks {
+        let payload = payload.clone();
+        tokio::spawn(async move {
+            if let Err(err) = repositories::webhooks::deliver(&webhook, &payload).await {
+                log::error!("{}", err);
+            }
+        });
+    }
+}
+
+/// Take a snapshot of every repository on the interval in the backup config, for as
+/// long as the server runs. Failed snapshots are logged and retried on the next tick.
+pub async fn run_scheduled_backups(sync_dir: PathBuf, config: BackupConfig) {
+    let mut interval = tokio::time::interval(config.interval());
+    // The first tick completes immediately, wait a full interval after startup
+    interval.tick().await;
+    loop {
+        interval.tick().await;
+        let sync_dir = sync_dir.clone();
+        let config = config.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            let kind = repositories::backup::scheduled_kind(&config.dir, config.full_every)?;
+            repositories::backup::create(&sync_dir, &config.dir, kind)
+        })
+        .await;
+        match result {
+            Ok(Ok(manifest)) => log::info!(
+                "Backup {} ({:?}) of {} repositories complete",
+                manifest.id,
+                manifest.kind,
+                manifest.repos.len()
+            ),
+            Ok(Err(err)) => log::error!("Scheduled backup failed: {}", err),
+            Err(err) => log::error!("Scheduled backup panicked: {}", err),
+        }
+    }
+}
+
+/// Sync every mirror on the interval in the mirror config, for as long as the server
+/// runs. The first sync starts right away so new mirrors are created on startup.
+pub async fn run_scheduled_mirrors(sync_dir: PathBuf, config: Arc<MirrorConfig>) {
+    let mut interval = tokio::time::interval(config.interval());
+    loop {
+        interval.tick().await;
+        for mirror in &config.repos {
+            match repositories::mirror::sync(&sync_dir, mirror).await {
+                Ok(status) => log::info!(
+                    "Mirror {}/{} of {} is {:?}",
+                    status.namespace,
+                    status.name,
+                    mirror.url,
+                    status.state
+                ),
+                Err(err) => log::error!("Mirror sync of {} failed: {}", mirror.url, err),
+            }
+        }
+    }
+}
+
+/// How often the scheduled jobs are checked for ones that are due
+const JOBS_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Start the jobs of every repository when their cron schedule says so, for as long as the
+/// server runs. Each run continues in the background, so a slow import never holds up the
+/// other jobs.
+pub async fn run_scheduled_jobs(sync_dir: PathBuf) {
+    let mut interval = tokio::time::interval(JOBS_INTERVAL);
+    loop {
+        interval.tick().await;
+        let namespaces = match repositories::list_namespaces(&sync_dir) {
+            Ok(namespaces) => namespaces,
+            Err(err) => {
+                log::error!("Could not list namespaces for the scheduled jobs: {}", err);
+                continue;
+            }
+        };
+        let now = OffsetDateTime::now_utc();
+        for namespace in namespaces {
+            for repo in repositories::list_repos_in_namespace(&sync_dir.join(&namespace)) {
+                if let Err(err) = start_due_jobs(repo, now) {
+                    log::error!("Could not start the scheduled jobs: {}", err);
+                }
+            }
+        }
+    }
+}
+
+fn start_due_jobs(repo: LocalRepository, now: OffsetDateTime) -> Result<(), OxenError> {
+    for job in repositories::jobs::list(&repo)? {
+        if !repositories::jobs::is_due(&repo, &job, now)? {
+            continue;
+        }
+        // Still running since it was last due
+        let Ok(run) = repositories::jobs::start(&repo, &job) else {
+            continue;
+        };
+        log::info!("Starting job {} on {:?}", job.name, repo.path);
+        let repo = repo.clone();
+        actix_web::rt::spawn(async move {
+            if let Err(err) = repositories::jobs::finish(&repo, &job, run).await {
+                log::error!("Could not record the run of job {}: {}", job.name, err);
+            }
+        });
+    }
+    Ok(())
+}
+
+/// How often buffered appends are checked for rows that have waited long enough
+const APPEND_FLUSH_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Commit the rows appended to every repository once they have waited long enough, for
+/// as long as the server runs, so a quiet stream still gets its rows committed.
+pub async fn run_append_flushes(sync_dir: PathBuf) {
+    let mut interval = tokio::time::interval(APPEND_FLUSH_INTERVAL);
+    loop {
+        interval.tick().await;
+        let sync_dir = sync_dir.clone();
+        let result = tokio::task::spawn_blocking(move || flush_appends(&sync_dir)).await;
+        match result {
+            Ok(Ok(())) => {}
+            Ok(Err(err)) => log::error!("Flushing appended rows failed: {}", err),
+            Err(err) => log::error!("Flushing appended rows panicked: {}", err),
+        }
+    }
+}
+
+fn flush_appends(sync_dir: &Path) -> Result<(), OxenError> {
+    for namespace in repositories::list_namespaces(sync_dir)? {
+        for repo in repositories::list_repos_in_namespace(&sync_dir.join(&namespace)) {
+            if repositories::append::list(&repo)?.is_empty() {
+                continue;
+            }
+            for commit in repositories::append::flush_due(&repo)? {
+                log::info!(
+                    "Committed appended rows to {:?} in {}",
+                    repo.path,
+                    commit.id
+                );
+                record_activity(&repo, &ActivityEvent::commit(&commit));
+            }
+        }
+    }
+    Ok(())
+}
+
 // #[allow(dependency_on_unit_never_type_fallback)]
 // pub fn get_redis_connection() -> Result<r2d2::Pool<redis::Client>, OxenError> {
 //     let redis_url = std::env::var("REDIS_URL").unwrap_or_else(|_| DEFAULT_REDIS_URL.to_string());
@@ -1,16 +1,21 @@
 use dotenv::dotenv;
 use dotenv::from_filename;
-use liboxen::config::UserConfig;
+use liboxen::config::{BackupConfig, CacheConfig, MirrorConfig, UserConfig};
 use liboxen::constants::OXEN_VERSION;
+use liboxen::core::cache::{CacheScheduler, CacheSchedulerOpts};
 use liboxen::model::merkle_tree::merkle_tree_node_cache;
 use liboxen::model::User;
+use liboxen::repositories;
+use liboxen::repositories::backup::BackupKind;
 use liboxen::util;
+use liboxen::util::signing::SigningKey;
 
 pub mod app_data;
 pub mod auth;
 pub mod controllers;
 pub mod errors;
 pub mod helpers;
+pub mod merge_queue;
 pub mod middleware;
 pub mod params;
 pub mod routes;
@@ -20,20 +25,24 @@ pub mod test;
 extern crate log;
 extern crate lru;
 
-use actix_web::middleware::{Condition, DefaultHeaders, Logger};
-use actix_web::{web, App, HttpServer};
-use actix_web_httpauth::middleware::HttpAuthentication;
+use actix_web::middleware::{DefaultHeaders, Logger};
+use actix_web::{App, HttpServer};
 
 use clap::{Arg, Command};
 
 use std::env;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 const VERSION: &str = liboxen::constants::OXEN_VERSION;
 
 const ADD_USER_USAGE: &str =
     "Usage: `oxen-server add-user -e <email> -n <name> -o user_config.toml`";
 
+const RETENTION_USAGE: &str = "Usage: `oxen-server retention --dry-run`";
+
+const GC_USAGE: &str = "Usage: `oxen-server gc --dry-run`";
+
 const START_SERVER_USAGE: &str = "Usage: `oxen-server start -i 0.0.0.0 -p 3000`";
 
 const INVALID_PORT_MSG: &str = "Port must a valid number between 0-65535";
@@ -98,6 +107,30 @@ async fn main() -> std::io::Result<()> {
                         .short('a')
                         .help("Start the server with token-based authentication enforced")
                         .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("cache-config")
+                        .long("cache-config")
+                        .help("TOML file to enable, disable or set size limits on post-commit cachers")
+                        .action(clap::ArgAction::Set),
+                )
+                .arg(
+                    Arg::new("backup-config")
+                        .long("backup-config")
+                        .help("TOML file to take scheduled backups of every repository")
+                        .action(clap::ArgAction::Set),
+                )
+                .arg(
+                    Arg::new("mirror-config")
+                        .long("mirror-config")
+                        .help("TOML file of upstream repositories to keep mirrored on this server")
+                        .action(clap::ArgAction::Set),
+                )
+                .arg(
+                    Arg::new("media")
+                        .long("media")
+                        .help("Serve resized thumbnails of images and poster frames of videos, video frames require ffmpeg")
+                        .action(clap::ArgAction::SetTrue),
                 ),
         )
         .subcommand(
@@ -128,6 +161,71 @@ async fn main() -> std::io::Result<()> {
                         .help("Where to write the output config file to give to the user")
                         .action(clap::ArgAction::Set),
                 ),
+        )
+        .subcommand(
+            Command::new("retention")
+                .about("Prune the version files of old commits in every repository with a [retention] policy")
+                .arg(
+                    Arg::new("dry-run")
+                        .long("dry-run")
+                        .help("Report what would be pruned without deleting anything")
+                        .action(clap::ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            Command::new("gc")
+                .about("Delete the version files nothing can reach anymore in every repository")
+                .arg(
+                    Arg::new("dry-run")
+                        .long("dry-run")
+                        .help("Report how much space would be reclaimed without deleting anything")
+                        .action(clap::ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            Command::new("admin")
+                .about("Server administration")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("backup")
+                        .about("Snapshot every repository in the sync dir")
+                        .arg(
+                            Arg::new("output")
+                                .long("output")
+                                .short('o')
+                                .help("Directory the snapshots are written to")
+                                .required(true)
+                                .action(clap::ArgAction::Set),
+                        )
+                        .arg(
+                            Arg::new("incremental")
+                                .long("incremental")
+                                .help("Only copy the files that changed since the latest snapshot")
+                                .action(clap::ArgAction::SetTrue),
+                        ),
+                )
+                .subcommand(
+                    Command::new("restore")
+                        .about("Restore the repositories in a snapshot into the sync dir")
+                        .arg(
+                            Arg::new("input")
+                                .long("input")
+                                .short('i')
+                                .help("Directory the snapshots were written to")
+                                .required(true)
+                                .action(clap::ArgAction::Set),
+                        )
+                        .arg(
+                            Arg::new("snapshot")
+                                .long("snapshot")
+                                .help("Id of the snapshot to restore, defaults to the latest")
+                                .action(clap::ArgAction::Set),
+                        ),
+                )
+                .subcommand(
+                    Command::new("signing-key")
+                        .about("Generate the key branch heads are signed with if there is none, and print its public key"),
+                ),
         );
     let matches = command.get_matches();
 
@@ -150,45 +248,90 @@ async fn main() -> std::io::Result<()> {
                     } else {
                         log::info!("Merkle tree node caching enabled");
                         merkle_tree_node_cache::enable();
+                    }
+
+                    let enable_auth = sub_matches.get_flag("auth");
+                    let mut data = app_data::OxenAppData::new(PathBuf::from(sync_dir));
+                    if let Some(path) = sub_matches.get_one::<String>("cache-config") {
+                        let config = match CacheConfig::from_file(path) {
+                            Ok(config) => config,
+                            Err(err) => {
+                                eprintln!("Could not read cache config {path}: {err}");
+                                return Ok(());
+                            }
+                        };
+                        log::info!("Cache config: {:?}", config);
+                        if let Some(max_bytes) = config.merkle_cache_max_bytes {
+                            merkle_tree_node_cache::set_max_bytes(max_bytes as usize);
+                        }
+                        data.cache_scheduler = CacheScheduler::new(CacheSchedulerOpts {
+                            config,
+                            ..Default::default()
+                        });
+                    }
+
+                    if merkle_tree_node_cache::is_enabled() {
                         log::info!(
-                            "Merkle tree node cache size: {}",
-                            merkle_tree_node_cache::CACHE_SIZE.get()
+                            "Merkle tree node cache max bytes per repository: {}",
+                            merkle_tree_node_cache::max_bytes()
                         );
                     }
 
-                    let enable_auth = sub_matches.get_flag("auth");
-                    let data = app_data::OxenAppData::new(PathBuf::from(sync_dir));
+                    match SigningKey::load(Path::new(&sync_dir)) {
+                        Ok(Some(key)) => {
+                            log::info!("Signing branch heads with key {}", key.public_key());
+                            data.signing_key = Some(Arc::new(key));
+                        }
+                        Ok(None) => {}
+                        Err(err) => {
+                            eprintln!("Could not load signing key: {err}");
+                            return Ok(());
+                        }
+                    }
+
+                    if let Some(path) = sub_matches.get_one::<String>("backup-config") {
+                        let config = match BackupConfig::from_file(path) {
+                            Ok(config) => config,
+                            Err(err) => {
+                                eprintln!("Could not read backup config {path}: {err}");
+                                return Ok(());
+                            }
+                        };
+                        log::info!("Backup config: {:?}", config);
+                        actix_web::rt::spawn(helpers::run_scheduled_backups(
+                            PathBuf::from(&sync_dir),
+                            config,
+                        ));
+                    }
+
+                    if let Some(path) = sub_matches.get_one::<String>("mirror-config") {
+                        let config = match MirrorConfig::from_file(path) {
+                            Ok(config) => Arc::new(config),
+                            Err(err) => {
+                                eprintln!("Could not read mirror config {path}: {err}");
+                                return Ok(());
+                            }
+                        };
+                        log::info!("Mirror config: {:?}", config);
+                        data.mirror_config = Some(config.clone());
+                        actix_web::rt::spawn(helpers::run_scheduled_mirrors(
+                            PathBuf::from(&sync_dir),
+                            config,
+                        ));
+                    }
+
+                    actix_web::rt::spawn(helpers::run_append_flushes(PathBuf::from(&sync_dir)));
+                    actix_web::rt::spawn(helpers::run_scheduled_jobs(PathBuf::from(&sync_dir)));
+
+                    if sub_matches.get_flag("media") {
+                        log::info!("Media thumbnails enabled");
+                        data.media = true;
+                    }
 
                     HttpServer::new(move || {
                         App::new()
                             .app_data(data.clone())
-                            .route(
-                                "/api/version",
-                                web::get().to(controllers::oxen_version::index),
-                            )
-                            .route(
-                                "/api/min_version",
-                                web::get().to(controllers::oxen_version::min_version),
-                            )
-                            .route("/api/health", web::get().to(controllers::health::index))
-                            .route(
-                                "/api/namespaces",
-                                web::get().to(controllers::namespaces::index),
-                            )
-                            .route(
-                                "/api/namespaces/{namespace}",
-                                web::get().to(controllers::namespaces::show),
-                            )
-                            .route(
-                                "/api/migrations/{migration_tstamp}",
-                                web::get().to(controllers::migrations::list_unmigrated),
-                            )
-                            .wrap(Condition::new(
-                                enable_auth,
-                                HttpAuthentication::bearer(auth::validator::validate),
-                            ))
-                            .service(web::scope("/api/repos").configure(routes::config))
-                            .default_service(web::route().to(controllers::not_found::index))
+                            .configure(|cfg| routes::app(cfg, enable_auth))
                             .wrap(DefaultHeaders::new().add(("oxen-version", OXEN_VERSION)))
                             .wrap(Logger::default())
                             .wrap(Logger::new("user agent is %a %{User-Agent}i"))
@@ -242,6 +385,120 @@ async fn main() -> std::io::Result<()> {
 
             Ok(())
         }
+        Some(("retention", sub_matches)) => {
+            let dry_run = sub_matches.get_flag("dry-run");
+            let sync_dir = Path::new(&sync_dir);
+            let namespaces = match repositories::list_namespaces(sync_dir) {
+                Ok(namespaces) => namespaces,
+                Err(err) => {
+                    eprintln!("Err: {err}\n{RETENTION_USAGE}");
+                    return Ok(());
+                }
+            };
+            for namespace in namespaces {
+                for repo in repositories::list_repos_in_namespace(&sync_dir.join(&namespace)) {
+                    // Don't prune versions out from under a push
+                    if repositories::is_locked(&repo) {
+                        println!("Skipping {:?}, it is locked", repo.path);
+                        continue;
+                    }
+                    match repositories::retention::apply(&repo, dry_run).await {
+                        Ok(Some(report)) => println!(
+                            "{:?}: {} expired commits, pruned {} versions ({})",
+                            repo.path,
+                            report.expired_commits.len(),
+                            report.pruned_versions,
+                            bytesize::ByteSize::b(report.pruned_bytes)
+                        ),
+                        Ok(None) => {}
+                        Err(err) => {
+                            eprintln!("Err: could not apply retention to {:?}: {err}", repo.path)
+                        }
+                    }
+                }
+            }
+            Ok(())
+        }
+        Some(("gc", sub_matches)) => {
+            let dry_run = sub_matches.get_flag("dry-run");
+            let sync_dir = Path::new(&sync_dir);
+            let namespaces = match repositories::list_namespaces(sync_dir) {
+                Ok(namespaces) => namespaces,
+                Err(err) => {
+                    eprintln!("Err: {err}\n{GC_USAGE}");
+                    return Ok(());
+                }
+            };
+            for namespace in namespaces {
+                for repo in repositories::list_repos_in_namespace(&sync_dir.join(&namespace)) {
+                    // Versions of a push in progress are not reachable until its commit lands
+                    if repositories::is_locked(&repo) {
+                        println!("Skipping {:?}, it is locked", repo.path);
+                        continue;
+                    }
+                    match repositories::gc::run(&repo, dry_run).await {
+                        Ok(report) => println!(
+                            "{:?}: removed {} unreachable versions ({})",
+                            repo.path,
+                            report.removed_versions,
+                            bytesize::ByteSize::b(report.removed_bytes)
+                        ),
+                        Err(err) => eprintln!("Err: could not gc {:?}: {err}", repo.path),
+                    }
+                }
+            }
+            Ok(())
+        }
+        Some(("admin", sub_matches)) => {
+            let sync_dir = Path::new(&sync_dir);
+            match sub_matches.subcommand() {
+                Some(("backup", sub_matches)) => {
+                    let output = sub_matches.get_one::<String>("output").expect("required");
+                    let kind = if sub_matches.get_flag("incremental") {
+                        BackupKind::Incremental
+                    } else {
+                        BackupKind::Full
+                    };
+                    match repositories::backup::create(sync_dir, Path::new(output), kind) {
+                        Ok(manifest) => println!(
+                            "Backup {} ({:?}): {} repositories, copied {} of {} files ({})",
+                            manifest.id,
+                            manifest.kind,
+                            manifest.repos.len(),
+                            manifest.copied_files().count(),
+                            manifest.num_files(),
+                            bytesize::ByteSize::b(manifest.copied_bytes())
+                        ),
+                        Err(err) => eprintln!("Err: backup failed: {err}"),
+                    }
+                }
+                Some(("restore", sub_matches)) => {
+                    let input = sub_matches.get_one::<String>("input").expect("required");
+                    let snapshot = sub_matches.get_one::<String>("snapshot");
+                    match repositories::backup::restore(
+                        Path::new(input),
+                        snapshot.map(|s| s.as_str()),
+                        sync_dir,
+                    ) {
+                        Ok(manifest) => println!(
+                            "Restored {} repositories from backup {}",
+                            manifest.repos.len(),
+                            manifest.id
+                        ),
+                        Err(err) => eprintln!("Err: restore failed: {err}"),
+                    }
+                }
+                Some(("signing-key", _)) => match SigningKey::load_or_create(sync_dir) {
+                    Ok(key) => println!(
+                        "Public key:\n\n{}\n\nTo verify branches fetched from this server have users run `oxen config --signing-key <HOST> <PUBLIC_KEY>`",
+                        key.public_key()
+                    ),
+                    Err(err) => eprintln!("Err: {err}"),
+                },
+                _ => unreachable!(),
+            }
+            Ok(())
+        }
         _ => unreachable!(), // If all subcommands are defined above, anything else is unreachabe!()
     }
 }
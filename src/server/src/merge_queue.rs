@@ -0,0 +1,73 @@
+//! Queue for server-side merges and branch updates
+//!
+//! Merges and pushes into the same branch wait on a per-branch lock so they run one at a
+//! time, in the order they arrived, and each one builds on the commit the previous one left.
+//!
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock, Mutex};
+
+use tokio::sync::OwnedMutexGuard;
+
+type BranchKey = (PathBuf, String);
+
+static QUEUES: LazyLock<Mutex<HashMap<BranchKey, Arc<tokio::sync::Mutex<()>>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn queue_for(repo_path: &Path, branch: &str) -> Arc<tokio::sync::Mutex<()>> {
+    let mut queues = QUEUES.lock().unwrap();
+    queues
+        .entry((repo_path.to_path_buf(), branch.to_string()))
+        .or_default()
+        .clone()
+}
+
+/// Number of merges into the branch that are running or waiting
+pub fn len(repo_path: &Path, branch: &str) -> usize {
+    let queues = QUEUES.lock().unwrap();
+    queues
+        .get(&(repo_path.to_path_buf(), branch.to_string()))
+        .map(|queue| Arc::strong_count(queue) - 1)
+        .unwrap_or(0)
+}
+
+/// Wait for the merges into the branch ahead of this one. The branch is held until the
+/// guard is dropped.
+pub async fn enqueue(repo_path: &Path, branch: &str) -> OwnedMutexGuard<()> {
+    let queue = queue_for(repo_path, branch);
+    log::debug!(
+        "merge into {branch} queued behind {}",
+        len(repo_path, branch) - 1
+    );
+    queue.lock_owned().await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::merge_queue;
+
+    #[tokio::test]
+    async fn test_merges_into_a_branch_wait_their_turn() {
+        let repo_path = Path::new("/tmp/merge_queue_test");
+        let first = merge_queue::enqueue(repo_path, "main").await;
+        assert_eq!(merge_queue::len(repo_path, "main"), 1);
+
+        // Other branches are not blocked
+        let other = merge_queue::enqueue(repo_path, "dev").await;
+        drop(other);
+
+        let waiting = tokio::spawn(async move {
+            let _second = merge_queue::enqueue(repo_path, "main").await;
+        });
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        assert!(!waiting.is_finished());
+        assert_eq!(merge_queue::len(repo_path, "main"), 2);
+
+        drop(first);
+        waiting.await.unwrap();
+        assert_eq!(merge_queue::len(repo_path, "main"), 0);
+    }
+}
@@ -1 +1,164 @@
+//! Middleware for the repository routes
+//!
 
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::Method;
+use actix_web::middleware::Next;
+use actix_web::Error;
+
+use liboxen::error::OxenError;
+use liboxen::repositories;
+
+use crate::app_data::OxenAppData;
+use crate::errors::OxenHttpError;
+
+/// Routes that are sent with a body but do not modify the repository. Clone and pull
+/// ask for missing commits and nodes this way, forking only reads from the source, and
+/// cancelling a fork only touches its bookkeeping files.
+const READ_ONLY_SAFE_ROUTES: [&str; 7] = [
+    "/read_only",
+    "/fork",
+    "/fork/cancel",
+    "/commits/missing",
+    "/tree/nodes/missing_node_hashes",
+    "/tree/nodes/missing_file_hashes_from_commits",
+    "/tree/nodes/missing_file_hashes_from_nodes",
+];
+
+/// Compare results are cached outside of the commit history, so they are safe too
+const READ_ONLY_SAFE_PREFIX: &str = "/compare/";
+
+/// Reject any request that would modify a repository that has been marked read-only
+pub async fn reject_writes_to_read_only_repos(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    if is_mutating(&req) {
+        check_read_only(&req)?;
+    }
+    next.call(req).await
+}
+
+fn is_mutating(req: &ServiceRequest) -> bool {
+    let mutating_method = matches!(
+        *req.method(),
+        Method::POST | Method::PUT | Method::PATCH | Method::DELETE
+    );
+    let route = req.match_info().unprocessed();
+    mutating_method
+        && !READ_ONLY_SAFE_ROUTES.contains(&route)
+        && !route.starts_with(READ_ONLY_SAFE_PREFIX)
+}
+
+fn check_read_only(req: &ServiceRequest) -> Result<(), OxenHttpError> {
+    let app_data = req
+        .app_data::<OxenAppData>()
+        .ok_or(OxenHttpError::AppDataDoesNotExist)?;
+    let (Some(namespace), Some(name)) = (
+        req.match_info().get("namespace"),
+        req.match_info().get("repo_name"),
+    ) else {
+        return Ok(());
+    };
+
+    // Let the controller respond to repositories that do not exist
+    let Some(repo) = repositories::get_by_namespace_and_name(&app_data.path, namespace, name)?
+    else {
+        return Ok(());
+    };
+
+    if repo.is_read_only() {
+        log::debug!(
+            "rejecting {} {} on read-only repo",
+            req.method(),
+            req.path()
+        );
+        return Err(OxenError::repo_is_read_only(format!("{namespace}/{name}")).into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::http::StatusCode;
+    use actix_web::App;
+
+    use liboxen::error::OxenError;
+    use liboxen::repositories;
+
+    use crate::app_data::OxenAppData;
+    use crate::routes;
+    use crate::test;
+
+    #[actix_web::test]
+    async fn test_read_only_repo_rejects_writes() -> Result<(), OxenError> {
+        test::init_test_env();
+        let sync_dir = test::get_sync_dir()?;
+        let namespace = "Testing-Namespace";
+        let repo_name = "Testing-Name";
+        let repo = test::create_local_repo(&sync_dir, namespace, repo_name)?;
+        let hello_file = repo.path.join("hello.txt");
+        liboxen::util::fs::write_to_path(&hello_file, "Hello")?;
+        repositories::add(&repo, &hello_file).await?;
+        repositories::commit(&repo, "First commit")?;
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(OxenAppData::new(sync_dir.clone()))
+                .configure(routes::config),
+        )
+        .await;
+
+        let read_only_uri = format!("/{namespace}/{repo_name}/read_only");
+        let req = actix_web::test::TestRequest::put()
+            .uri(&read_only_uri)
+            .set_payload(r#"{"read_only": true}"#)
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        // Creating a branch is rejected
+        let branches_uri = format!("/{namespace}/{repo_name}/branches");
+        let body = r#"{"new_name": "feature", "from_name": "main"}"#;
+        let req = actix_web::test::TestRequest::post()
+            .uri(&branches_uri)
+            .set_payload(body)
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+        // So is deleting the repository
+        let req = actix_web::test::TestRequest::delete()
+            .uri(&format!("/{namespace}/{repo_name}"))
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+        // Reads still work
+        let req = actix_web::test::TestRequest::get()
+            .uri(&branches_uri)
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        // Turning the flag off allows writes again
+        let req = actix_web::test::TestRequest::put()
+            .uri(&read_only_uri)
+            .set_payload(r#"{"read_only": false}"#)
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let req = actix_web::test::TestRequest::post()
+            .uri(&branches_uri)
+            .set_payload(body)
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        test::cleanup_sync_dir(&sync_dir)?;
+
+        Ok(())
+    }
+}
@@ -18,6 +18,12 @@ use percent_encoding::percent_decode;
 pub mod aggregate_query;
 pub use aggregate_query::AggregateQuery;
 
+pub mod append_query;
+pub use append_query::AppendQuery;
+
+pub mod archive_query;
+pub use archive_query::ArchiveQuery;
+
 pub mod name_param;
 pub use name_param::NameParam;
 
@@ -29,7 +35,7 @@ pub mod df_opts_query;
 pub use df_opts_query::DFOptsQuery;
 
 pub mod tree_depth;
-pub use tree_depth::TreeDepthQuery;
+pub use tree_depth::{HistoryDepthQuery, TreeDepthQuery};
 
 static REGEX_USER_AGENT_VERSION_NUMBER: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"^\d+\.\d+\.\d+").unwrap());
@@ -0,0 +1,14 @@
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct AppendQuery {
+    /// Commit once this many rows are buffered
+    pub commit_rows: Option<usize>,
+    /// Commit once the oldest buffered row is this many seconds old
+    pub commit_after: Option<u64>,
+    /// Commit the buffered rows with this request
+    pub commit: Option<bool>,
+    pub message: Option<String>,
+    pub author: Option<String>,
+    pub email: Option<String>,
+}
@@ -0,0 +1,9 @@
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct ArchiveQuery {
+    /// tar.gz (the default), tar or zip
+    pub format: Option<String>,
+    /// Comma separated paths to limit the archive to
+    pub paths: Option<String>,
+}
@@ -17,6 +17,7 @@ pub struct DFOptsQuery {
     pub row: Option<usize>,
     pub randomize: Option<bool>,
     pub reverse: Option<bool>,
+    pub sheet: Option<String>,
     pub slice: Option<String>,
     pub sort_by: Option<String>,
     pub sort_by_similarity_to: Option<String>,
@@ -57,6 +58,7 @@ pub fn parse_opts(query: &web::Query<DFOptsQuery>, filter_ops: &mut DFOpts) -> D
     filter_ops.row = query.row;
     filter_ops.should_randomize = query.randomize.unwrap_or(false);
     filter_ops.should_reverse = query.reverse.unwrap_or(false);
+    filter_ops.sheet.clone_from(&query.sheet);
     filter_ops.sort_by.clone_from(&query.sort_by);
     filter_ops
         .sort_by_similarity_to
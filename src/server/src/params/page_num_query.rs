@@ -11,4 +11,6 @@ pub struct PageNumVersionQuery {
     pub page: Option<usize>,
     pub page_size: Option<usize>,
     pub api_version: Option<String>,
+    /// Only list entries whose metadata matches, ie: `data_type=image,image.width>1024`
+    pub filter: Option<String>,
 }
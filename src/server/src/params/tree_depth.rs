@@ -5,4 +5,11 @@ pub struct TreeDepthQuery {
     pub depth: Option<i32>,
     pub subtrees: Option<String>,
     pub is_download: Option<bool>,
+    /// Only include the last N commits of history, for shallow clones
+    pub history_depth: Option<usize>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct HistoryDepthQuery {
+    pub history_depth: Option<usize>,
 }
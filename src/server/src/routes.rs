@@ -1,9 +1,56 @@
 use super::controllers;
 
+use actix_web::middleware::{from_fn, Condition};
 use actix_web::web;
+use actix_web_httpauth::middleware::HttpAuthentication;
 
+use crate::auth;
+use crate::middleware::reject_writes_to_read_only_repos;
 use crate::services;
 
+/// Every route of the server. With `enable_auth` requests need a bearer token, except
+/// the ones that are authenticated another way.
+pub fn app(cfg: &mut web::ServiceConfig, enable_auth: bool) {
+    // Upstream servers sign these with the mirror's secret, they have no token for this server
+    cfg.route(
+        "/api/mirrors/{namespace}/{repo_name}/sync",
+        web::post().to(controllers::mirrors::sync),
+    )
+    .service(
+        web::scope("")
+            .wrap(Condition::new(
+                enable_auth,
+                HttpAuthentication::bearer(auth::validator::validate),
+            ))
+            .route(
+                "/api/version",
+                web::get().to(controllers::oxen_version::index),
+            )
+            .route(
+                "/api/min_version",
+                web::get().to(controllers::oxen_version::min_version),
+            )
+            .route("/api/health", web::get().to(controllers::health::index))
+            .route("/api/metrics", web::get().to(controllers::health::metrics))
+            .route("/api/whoami", web::get().to(controllers::users::whoami))
+            .route(
+                "/api/namespaces",
+                web::get().to(controllers::namespaces::index),
+            )
+            .route(
+                "/api/namespaces/{namespace}",
+                web::get().to(controllers::namespaces::show),
+            )
+            .route("/api/mirrors", web::get().to(controllers::mirrors::index))
+            .route(
+                "/api/migrations/{migration_tstamp}",
+                web::get().to(controllers::migrations::list_unmigrated),
+            )
+            .service(web::scope("/api/repos").configure(config))
+            .default_service(web::route().to(controllers::not_found::index)),
+    );
+}
+
 pub fn config(cfg: &mut web::ServiceConfig) {
     // Create Repository
     cfg.route("", web::post().to(controllers::repositories::create))
@@ -17,32 +64,142 @@ pub fn config(cfg: &mut web::ServiceConfig) {
             web::resource("/{namespace}/{repo_name}")
                 // we give the resource a name here so it can be used with HttpRequest.url_for
                 .name("repo_root")
+                .wrap(from_fn(reject_writes_to_read_only_repos))
                 .route(web::get().to(controllers::repositories::show))
                 .route(web::delete().to(controllers::repositories::delete)),
         )
         // Repository Services
         .service(
             web::scope("/{namespace}/{repo_name}")
+                .wrap(from_fn(reject_writes_to_read_only_repos))
                 .service(services::action())
+                .service(services::activity())
+                .service(services::append())
+                .service(services::archive())
                 .service(services::branches())
                 .service(services::chunk())
                 .service(services::commits())
                 .service(services::commits_db())
                 .service(services::compare())
                 .service(services::data_frames())
+                .service(services::dedup())
+                .service(services::embeddings())
                 .service(services::dir())
                 .service(services::file())
                 .service(services::fork())
+                .service(services::jobs())
                 .service(services::merge())
                 .service(services::meta())
+                .service(services::proposals())
+                .service(services::protected_branches())
+                .service(services::read_only())
+                .service(services::render())
                 .service(services::revisions())
                 .service(services::size())
                 .service(services::schemas())
                 .service(services::stats())
                 .service(services::tabular())
+                .service(services::tags())
+                .service(services::thumbnails())
                 .service(services::transfer())
                 .service(services::tree())
+                .service(services::uploads())
                 .service(services::versions())
+                .service(services::webhooks())
                 .service(services::workspace()),
         );
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use actix_web::dev::Service;
+    use actix_web::http::StatusCode;
+    use actix_web::App;
+
+    use liboxen::config::{MirrorConfig, MirrorRepoConfig};
+    use liboxen::error::OxenError;
+    use liboxen::model::MirrorState;
+    use liboxen::repositories::webhooks::{self, SIGNATURE_HEADER};
+
+    use crate::app_data::OxenAppData;
+    use crate::test;
+
+    #[actix_web::test]
+    async fn test_mirror_sync_is_signed_instead_of_authenticated() -> Result<(), OxenError> {
+        let sync_dir = test::get_sync_dir()?;
+        let open = MirrorRepoConfig {
+            url: "http://localhost:1/ox/open".to_string(),
+            namespace: Some("ox".to_string()),
+            name: Some("open".to_string()),
+            secret: None,
+        };
+        let mut data = OxenAppData::new(sync_dir.clone());
+        data.mirror_config = Some(Arc::new(MirrorConfig {
+            interval_minutes: 60,
+            repos: vec![
+                MirrorRepoConfig {
+                    url: "http://localhost:1/ox/upstream".to_string(),
+                    namespace: Some("ox".to_string()),
+                    name: Some("mirror".to_string()),
+                    secret: Some("mirror-secret".to_string()),
+                },
+                open.clone(),
+            ],
+        }));
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(data)
+                .configure(|cfg| super::app(cfg, true)),
+        )
+        .await;
+        let status =
+            |result: Result<actix_web::dev::ServiceResponse, actix_web::Error>| match result {
+                Ok(resp) => resp.status(),
+                Err(err) => err.as_response_error().status_code(),
+            };
+
+        // Everything else needs a bearer token
+        let req = actix_web::test::TestRequest::get()
+            .uri("/api/namespaces")
+            .to_request();
+        assert_eq!(status(app.call(req).await), StatusCode::UNAUTHORIZED);
+
+        let body = "{\"event\": \"push\"}";
+        let req = actix_web::test::TestRequest::post()
+            .uri("/api/mirrors/ox/mirror/sync")
+            .insert_header((
+                SIGNATURE_HEADER,
+                webhooks::sign("mirror-secret", body.as_bytes()),
+            ))
+            .set_payload(body)
+            .to_request();
+        assert_eq!(status(app.call(req).await), StatusCode::ACCEPTED);
+
+        let req = actix_web::test::TestRequest::post()
+            .uri("/api/mirrors/ox/mirror/sync")
+            .insert_header((
+                SIGNATURE_HEADER,
+                webhooks::sign("wrong-secret", body.as_bytes()),
+            ))
+            .set_payload(body)
+            .to_request();
+        assert_eq!(status(app.call(req).await), StatusCode::UNAUTHORIZED);
+
+        // Without a secret there is nothing to check the caller with
+        let req = actix_web::test::TestRequest::post()
+            .uri("/api/mirrors/ox/open/sync")
+            .set_payload(body)
+            .to_request();
+        assert_eq!(status(app.call(req).await), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            liboxen::repositories::mirror::get_status(&sync_dir, &open)?.state,
+            MirrorState::Pending
+        );
+
+        test::cleanup_sync_dir(&sync_dir)?;
+
+        Ok(())
+    }
+}
@@ -1,43 +1,69 @@
 pub mod action;
+pub mod activity;
+pub mod append;
+pub mod archive;
 pub mod branches;
 pub mod chunk;
 pub mod commits;
 pub mod commits_db;
 pub mod compare;
 pub mod data_frames;
+pub mod dedup;
 pub mod dir;
+pub mod embeddings;
 pub mod file;
 pub mod fork;
+pub mod jobs;
 pub mod merge;
 pub mod meta;
+pub mod proposals;
+pub mod read_only;
+pub mod render;
 pub mod revisions;
 pub mod schemas;
 pub mod size;
 pub mod stats;
 pub mod tabular;
+pub mod tags;
+pub mod thumbnails;
 pub mod transfer;
 pub mod tree;
+pub mod uploads;
 pub mod versions;
+pub mod webhooks;
 pub mod workspaces;
 
 pub use action::action;
-pub use branches::branches;
+pub use activity::activity;
+pub use append::append;
+pub use archive::archive;
+pub use branches::{branches, protected_branches};
 pub use chunk::chunk;
 pub use commits::commits;
 pub use commits_db::commits_db;
 pub use compare::compare;
 pub use data_frames::data_frames;
+pub use dedup::dedup;
 pub use dir::dir;
+pub use embeddings::embeddings;
 pub use file::file;
 pub use fork::fork;
+pub use jobs::jobs;
 pub use merge::merge;
 pub use meta::meta;
+pub use proposals::proposals;
+pub use read_only::read_only;
+pub use render::render;
 pub use revisions::revisions;
 pub use schemas::schemas;
 pub use size::size;
 pub use stats::stats;
 pub use tabular::tabular;
+pub use tags::tags;
+pub use thumbnails::thumbnails;
 pub use transfer::transfer;
 pub use tree::tree;
+pub use uploads::uploads;
 pub use versions::versions;
+pub use webhooks::webhooks;
 pub use workspaces::workspace;
@@ -0,0 +1,8 @@
+use actix_web::web;
+use actix_web::Scope;
+
+use crate::controllers;
+
+pub fn activity() -> Scope {
+    web::scope("/activity").route("", web::get().to(controllers::activity::index))
+}
@@ -0,0 +1,11 @@
+use actix_web::web;
+use actix_web::Scope;
+
+use crate::controllers;
+
+pub fn append() -> Scope {
+    web::scope("/df").route(
+        "/{resource:.*}/append",
+        web::post().to(controllers::append::append),
+    )
+}
@@ -0,0 +1,11 @@
+use actix_web::web;
+use actix_web::Scope;
+
+use crate::controllers;
+
+pub fn archive() -> Scope {
+    web::scope("/archive").route(
+        "/{revision:.*}",
+        web::get().to(controllers::archive::download),
+    )
+}
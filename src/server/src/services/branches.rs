@@ -27,6 +27,10 @@ pub fn branches() -> Scope {
             "/{branch_name:.*}/unlock",
             web::post().to(controllers::branches::unlock),
         )
+        .route(
+            "/{branch_name:.*}/compare/{head_branch:.*}",
+            web::get().to(controllers::branches::compare),
+        )
         .route(
             "/{branch_name:.*}/merge",
             web::put().to(controllers::branches::maybe_create_merge),
@@ -44,3 +48,16 @@ pub fn branches() -> Scope {
             web::put().to(controllers::branches::update),
         )
 }
+
+pub fn protected_branches() -> Scope {
+    web::scope("/protected_branches")
+        .route(
+            "",
+            web::get().to(controllers::branches::list_protection_rules),
+        )
+        .route("", web::put().to(controllers::branches::protect))
+        .route(
+            "/{branch:.*}",
+            web::delete().to(controllers::branches::unprotect),
+        )
+}
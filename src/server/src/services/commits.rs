@@ -10,6 +10,7 @@ pub fn commits() -> Scope {
         .route("", web::post().to(controllers::commits::create))
         .route("/root", web::get().to(controllers::commits::root_commit))
         .route("/all", web::get().to(controllers::commits::list_all))
+        .route("/search", web::get().to(controllers::commits::search))
         .route("/upload", web::post().to(controllers::commits::upload))
         .route(
             "/upload_chunk",
@@ -32,6 +33,14 @@ pub fn commits() -> Scope {
             "/history/{resource:.*}",
             web::get().to(controllers::commits::history),
         )
+        .route(
+            "/{commit_id}/cache",
+            web::get().to(controllers::commits::list_cache_jobs),
+        )
+        .route(
+            "/{commit_id}/cache/{cacher}",
+            web::post().to(controllers::commits::recompute_cache_job),
+        )
         .route(
             "/{commit_or_branch:.*}/parents",
             web::get().to(controllers::commits::parents),
@@ -9,6 +9,10 @@ pub fn data_frames() -> Scope {
             "/index/{resource:.*}",
             web::post().to(controllers::data_frames::index),
         )
+        .route(
+            "/sql/{resource:.*}",
+            web::post().to(controllers::data_frames::sql),
+        )
         .route(
             "/{resource:.*}",
             web::get().to(controllers::data_frames::get),
@@ -0,0 +1,8 @@
+use actix_web::web;
+use actix_web::Scope;
+
+use crate::controllers;
+
+pub fn dedup() -> Scope {
+    web::scope("/dedup").route("/{resource:.*}", web::get().to(controllers::dedup::report))
+}
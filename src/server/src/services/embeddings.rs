@@ -0,0 +1,11 @@
+use actix_web::web;
+use actix_web::Scope;
+
+use crate::controllers;
+
+pub fn embeddings() -> Scope {
+    web::scope("/embeddings").route(
+        "/{revision:.*}/query",
+        web::post().to(controllers::embeddings::query),
+    )
+}
@@ -0,0 +1,14 @@
+use actix_web::web;
+use actix_web::Scope;
+
+use crate::controllers;
+
+pub fn jobs() -> Scope {
+    web::scope("/jobs")
+        .route("", web::get().to(controllers::jobs::index))
+        .route("", web::post().to(controllers::jobs::create))
+        .route("/{job_id}", web::get().to(controllers::jobs::show))
+        .route("/{job_id}", web::delete().to(controllers::jobs::delete))
+        .route("/{job_id}/runs", web::get().to(controllers::jobs::runs))
+        .route("/{job_id}/runs", web::post().to(controllers::jobs::run))
+}
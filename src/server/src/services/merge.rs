@@ -5,6 +5,7 @@ use crate::controllers;
 
 pub fn merge() -> Scope {
     web::scope("/merge")
+        .route("", web::post().to(controllers::merger::merge_from_body))
         .route("/{base_head:.*}", web::get().to(controllers::merger::show))
         .route(
             "/{base_head:.*}",
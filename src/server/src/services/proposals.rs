@@ -0,0 +1,42 @@
+use actix_web::web;
+use actix_web::Scope;
+
+use crate::controllers;
+
+pub fn proposals() -> Scope {
+    web::scope("/proposals")
+        .route("", web::get().to(controllers::proposals::index))
+        .route("", web::post().to(controllers::proposals::create))
+        .route(
+            "/{proposal_id}",
+            web::get().to(controllers::proposals::show),
+        )
+        .route(
+            "/{proposal_id}/files",
+            web::get().to(controllers::proposals::files),
+        )
+        .route(
+            "/{proposal_id}/mergeable",
+            web::get().to(controllers::proposals::mergeable),
+        )
+        .route(
+            "/{proposal_id}/comments",
+            web::get().to(controllers::proposals::comments),
+        )
+        .route(
+            "/{proposal_id}/comments",
+            web::post().to(controllers::proposals::comment),
+        )
+        .route(
+            "/{proposal_id}/approve",
+            web::post().to(controllers::proposals::approve),
+        )
+        .route(
+            "/{proposal_id}/merge",
+            web::post().to(controllers::proposals::merge),
+        )
+        .route(
+            "/{proposal_id}/close",
+            web::post().to(controllers::proposals::close),
+        )
+}
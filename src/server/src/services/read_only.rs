@@ -0,0 +1,13 @@
+use actix_web::web;
+use actix_web::Scope;
+
+use crate::controllers;
+
+pub fn read_only() -> Scope {
+    web::scope("/read_only")
+        .route("", web::get().to(controllers::repositories::get_read_only))
+        .route(
+            "",
+            web::put().to(controllers::repositories::update_read_only),
+        )
+}
@@ -0,0 +1,8 @@
+use actix_web::web;
+use actix_web::Scope;
+
+use crate::controllers;
+
+pub fn render() -> Scope {
+    web::scope("/render").route("/{resource:.*}", web::get().to(controllers::render::get))
+}
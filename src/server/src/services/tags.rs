@@ -0,0 +1,12 @@
+use actix_web::web;
+use actix_web::Scope;
+
+use crate::controllers;
+
+pub fn tags() -> Scope {
+    web::scope("/tags")
+        .route("", web::get().to(controllers::tags::index))
+        .route("", web::post().to(controllers::tags::create))
+        .route("/{tag_name}", web::get().to(controllers::tags::show))
+        .route("/{tag_name}", web::delete().to(controllers::tags::delete))
+}
@@ -0,0 +1,11 @@
+use actix_web::web;
+use actix_web::Scope;
+
+use crate::controllers;
+
+pub fn thumbnails() -> Scope {
+    web::scope("/thumbnail").route(
+        "/{resource:.*}",
+        web::get().to(controllers::thumbnails::get),
+    )
+}
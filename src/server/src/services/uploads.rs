@@ -0,0 +1,22 @@
+use actix_web::web;
+use actix_web::Scope;
+
+use crate::controllers;
+
+pub fn uploads() -> Scope {
+    web::scope("/uploads")
+        .route("", web::post().to(controllers::uploads::create))
+        .route("/{upload_id}", web::get().to(controllers::uploads::show))
+        .route(
+            "/{upload_id}",
+            web::patch().to(controllers::uploads::append),
+        )
+        .route(
+            "/{upload_id}",
+            web::delete().to(controllers::uploads::delete),
+        )
+        .route(
+            "/{upload_id}/complete",
+            web::post().to(controllers::uploads::complete),
+        )
+}
@@ -12,6 +12,7 @@ pub fn versions() -> Scope {
             web::get().to(controllers::entries::download_data_from_version_paths),
         )
         .route("", web::post().to(controllers::versions::batch_upload))
+        .route("/exists", web::post().to(controllers::versions::exists))
         .route(
             "/{version_id}/metadata",
             web::get().to(controllers::versions::metadata),